@@ -34,6 +34,19 @@ pub struct CompileResultV0 {
     pub report_json: String,
     pub log_bytes: Vec<u8>,
     pub main_xdv_bytes: Vec<u8>,
+    /// PDF rendering of `main_xdv_bytes`; set by the engine on `OK` results only.
+    pub main_pdf_bytes: Vec<u8>,
+    /// `.aux` file of the last cross-reference pass; set on `OK` results only.
+    pub main_aux_bytes: Vec<u8>,
+    /// `.toc` file of the last cross-reference pass; set on `OK` results only.
+    pub main_toc_bytes: Vec<u8>,
+    /// `.lof` file of the last cross-reference pass; set on `OK` results only.
+    pub main_lof_bytes: Vec<u8>,
+    /// `.lot` file of the last cross-reference pass; set on `OK` results only.
+    pub main_lot_bytes: Vec<u8>,
+    /// `.bbl` file the built-in BibTeX wrote for `\bibliography`; set on `OK`
+    /// results only.
+    pub main_bbl_bytes: Vec<u8>,
     pub tex_stats_json: String,
 }
 
@@ -50,10 +63,35 @@ pub fn build_compile_result_v0(
         report_json,
         log_bytes,
         main_xdv_bytes,
+        main_pdf_bytes: Vec::new(),
+        main_aux_bytes: Vec::new(),
+        main_toc_bytes: Vec::new(),
+        main_lof_bytes: Vec::new(),
+        main_lot_bytes: Vec::new(),
+        main_bbl_bytes: Vec::new(),
         tex_stats_json,
     }
 }
 
+/// Prefix of every line an `OK` log may carry.
+pub const LOG_WARNING_PREFIX_V0: &[u8] = b"LaTeX Warning: ";
+
+/// `OK` logs hold warnings only: every line starts with `LOG_WARNING_PREFIX_V0`,
+/// except that the last one may have been truncated inside it.
+pub fn ok_log_has_warnings_only_v0(log_bytes: &[u8]) -> bool {
+    let mut lines = log_bytes.split(|byte| *byte == b'\n').peekable();
+    while let Some(line) = lines.next() {
+        let last = lines.peek().is_none();
+        if last && LOG_WARNING_PREFIX_V0.starts_with(line) {
+            return true;
+        }
+        if !line.starts_with(LOG_WARNING_PREFIX_V0) {
+            return false;
+        }
+    }
+    true
+}
+
 pub fn truncate_log_bytes_v0(log_bytes: &[u8], max_log_bytes: u32) -> Vec<u8> {
     let max = max_log_bytes as usize;
     if log_bytes.len() <= max {
@@ -313,6 +351,16 @@ fn consume_json_string(bytes: &[u8], index: &mut usize) -> Result<(), Error> {
 }
 
 fn build_compile_report_json(status: CompileStatus, missing_components: &[&str]) -> String {
+    build_compile_report_json_v0(status, missing_components, &[])
+}
+
+/// Report JSON that also lists the packages `\usepackage` loaded, in load order,
+/// as `loaded_packages`; the key is left out when there are none.
+pub fn build_compile_report_json_v0(
+    status: CompileStatus,
+    missing_components: &[&str],
+    loaded_packages: &[&str],
+) -> String {
     let status_str = match status {
         CompileStatus::Ok => "OK",
         CompileStatus::InvalidInput => "INVALID_INPUT",
@@ -322,17 +370,27 @@ fn build_compile_report_json(status: CompileStatus, missing_components: &[&str])
     let mut out = String::new();
     out.push_str("{\"status\":\"");
     out.push_str(status_str);
-    out.push_str("\",\"missing_components\":[");
-    for (index, component) in missing_components.iter().enumerate() {
+    out.push_str("\",\"missing_components\":");
+    push_json_string_array(&mut out, missing_components);
+    if !loaded_packages.is_empty() {
+        out.push_str(",\"loaded_packages\":");
+        push_json_string_array(&mut out, loaded_packages);
+    }
+    out.push('}');
+    out
+}
+
+fn push_json_string_array(out: &mut String, values: &[&str]) {
+    out.push('[');
+    for (index, value) in values.iter().enumerate() {
         if index != 0 {
             out.push(',');
         }
         out.push('"');
-        out.push_str(&escape_json_string(component));
+        out.push_str(&escape_json_string(value));
         out.push('"');
     }
-    out.push_str("]}");
-    out
+    out.push(']');
 }
 
 fn escape_json_string(value: &str) -> String {
@@ -381,13 +439,14 @@ pub fn validate_compile_report_json(report_json: &str) -> Result<(), Error> {
 #[cfg(test)]
 mod tests {
     use super::{
-        append_event_v0, artifact_bytes_within_cap_v0, build_compile_result_v0,
-        build_tex_stats_json_v0, report_json_has_status_token_v0,
-        report_json_missing_components_is_empty_v0, truncate_log_bytes_v0,
-        validate_compile_report_json, validate_input_trace_json_v0, validate_tex_stats_json_v0,
-        CompileRequestV0, CompileStatus, DEFAULT_COMPILE_MAIN_MAX_LOG_BYTES_V0,
-        EVENT_KIND_LOG_BYTES_V0, EVENT_KIND_TEX_STATS_JSON_V0, MAX_ARTIFACT_BYTES_V0,
-        MAX_EVENTS_BYTES_V0, MAX_LOG_BYTES_V0, MAX_TEX_STATS_JSON_BYTES_V0,
+        append_event_v0, artifact_bytes_within_cap_v0, build_compile_report_json_v0,
+        build_compile_result_v0, build_tex_stats_json_v0, ok_log_has_warnings_only_v0,
+        report_json_has_status_token_v0, report_json_missing_components_is_empty_v0,
+        truncate_log_bytes_v0, validate_compile_report_json, validate_input_trace_json_v0,
+        validate_tex_stats_json_v0, CompileRequestV0, CompileStatus,
+        DEFAULT_COMPILE_MAIN_MAX_LOG_BYTES_V0, EVENT_KIND_LOG_BYTES_V0,
+        EVENT_KIND_TEX_STATS_JSON_V0, MAX_ARTIFACT_BYTES_V0, MAX_EVENTS_BYTES_V0, MAX_LOG_BYTES_V0,
+        MAX_TEX_STATS_JSON_BYTES_V0,
     };
 
     #[test]
@@ -425,6 +484,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn report_json_lists_loaded_packages_only_when_any() {
+        assert_eq!(
+            build_compile_report_json_v0(CompileStatus::Ok, &[], &["amsmath", "my\"pkg"]),
+            "{\"status\":\"OK\",\"missing_components\":[],\"loaded_packages\":[\"amsmath\",\"my\\\"pkg\"]}"
+        );
+        let report = build_compile_report_json_v0(CompileStatus::Ok, &[], &[]);
+        assert_eq!(report, "{\"status\":\"OK\",\"missing_components\":[]}");
+        assert!(validate_compile_report_json(&report).is_ok());
+    }
+
     #[test]
     fn compile_result_builder_escapes_json_string_content() {
         let result = build_compile_result_v0(
@@ -440,9 +510,29 @@ mod tests {
         );
         assert_eq!(result.log_bytes, vec![0xff, b'\n', b'X']);
         assert_eq!(result.main_xdv_bytes, vec![1, 2, 3]);
+        assert!(result.main_pdf_bytes.is_empty());
+        assert!(result.main_aux_bytes.is_empty());
+        assert!(result.main_toc_bytes.is_empty());
+        assert!(result.main_lof_bytes.is_empty());
+        assert!(result.main_lot_bytes.is_empty());
+        assert!(result.main_bbl_bytes.is_empty());
         assert_eq!(result.tex_stats_json, "{\"token_count\":2}");
     }
 
+    #[test]
+    fn ok_logs_may_only_carry_warning_lines() {
+        assert!(ok_log_has_warnings_only_v0(b""));
+        assert!(ok_log_has_warnings_only_v0(
+            b"LaTeX Warning: Reference `a' on page 1 undefined.\nLaTeX Warning: La"
+        ));
+        assert!(ok_log_has_warnings_only_v0(b"LaTeX Warning: x\n"));
+        assert!(!ok_log_has_warnings_only_v0(b"LaTeX Warning: x\n\n"));
+        assert!(!ok_log_has_warnings_only_v0(
+            b"LaTeX Warning: x\nINVALID_INPUT: y"
+        ));
+        assert!(!ok_log_has_warnings_only_v0(b"Warning"));
+    }
+
     #[test]
     fn validate_compile_report_json_rejects_missing_keys_or_unknown_status() {
         assert!(validate_compile_report_json("{\"status\":\"OK\"}").is_err());
@@ -476,6 +566,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::assertions_on_constants)]
     fn max_log_bytes_constant_is_non_zero() {
         assert!(MAX_LOG_BYTES_V0 > 0);
     }
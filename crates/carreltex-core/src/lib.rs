@@ -2,13 +2,14 @@ pub mod compile;
 pub mod mount;
 
 pub use compile::{
-    append_event_v0, artifact_bytes_within_cap_v0, build_compile_result_v0,
-    build_tex_stats_json_v0, report_json_has_status_token_v0,
-    report_json_missing_components_is_empty_v0, truncate_log_bytes_v0,
-    validate_compile_report_json, validate_input_trace_json_v0, validate_tex_stats_json_v0,
-    CompileRequestV0, CompileResultV0, CompileStatus, DEFAULT_COMPILE_MAIN_MAX_LOG_BYTES_V0,
-    EVENT_KIND_LOG_BYTES_V0, EVENT_KIND_TEX_STATS_JSON_V0, MAX_ARTIFACT_BYTES_V0,
-    MAX_EVENTS_BYTES_V0, MAX_LOG_BYTES_V0, MAX_TEX_STATS_JSON_BYTES_V0, MAX_WASM_ALLOC_BYTES_V0,
+    append_event_v0, artifact_bytes_within_cap_v0, build_compile_report_json_v0,
+    build_compile_result_v0, build_tex_stats_json_v0, ok_log_has_warnings_only_v0,
+    report_json_has_status_token_v0, report_json_missing_components_is_empty_v0,
+    truncate_log_bytes_v0, validate_compile_report_json, validate_input_trace_json_v0,
+    validate_tex_stats_json_v0, CompileRequestV0, CompileResultV0, CompileStatus,
+    DEFAULT_COMPILE_MAIN_MAX_LOG_BYTES_V0, EVENT_KIND_LOG_BYTES_V0, EVENT_KIND_TEX_STATS_JSON_V0,
+    LOG_WARNING_PREFIX_V0, MAX_ARTIFACT_BYTES_V0, MAX_EVENTS_BYTES_V0, MAX_LOG_BYTES_V0,
+    MAX_TEX_STATS_JSON_BYTES_V0, MAX_WASM_ALLOC_BYTES_V0,
 };
 pub use mount::{
     normalize_path_v0, validate_main_tex, Error, Mount, MAIN_TEX_MAX_BYTES, MAX_FILES,
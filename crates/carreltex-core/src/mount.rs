@@ -1,10 +1,10 @@
 use std::collections::BTreeMap;
 
-pub const MAIN_TEX_MAX_BYTES: usize = 1 * 1024 * 1024;
+pub const MAIN_TEX_MAX_BYTES: usize = 1024 * 1024;
 pub const MAX_FILES: usize = 64;
 pub const MAX_TOTAL_BYTES: usize = 4 * 1024 * 1024;
 pub const MAX_PATH_LEN: usize = 256;
-pub const MAX_FILE_BYTES: usize = 1 * 1024 * 1024;
+pub const MAX_FILE_BYTES: usize = 1024 * 1024;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Error {
@@ -103,7 +103,7 @@ pub fn validate_main_tex(bytes: &[u8]) -> Result<(), Error> {
     if bytes.is_empty() || bytes.len() > MAIN_TEX_MAX_BYTES {
         return Err(Error::InvalidInput);
     }
-    if bytes.iter().any(|byte| *byte == 0) {
+    if bytes.contains(&0) {
         return Err(Error::InvalidInput);
     }
     if !bytes.iter().copied().any(is_non_whitespace_tex_byte) {
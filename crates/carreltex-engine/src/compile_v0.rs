@@ -1,7 +1,26 @@
+mod bib_v0;
+#[cfg(test)]
+mod bib_v0_tests;
+#[cfg(test)]
+mod class_v0_tests;
+#[cfg(test)]
+mod color_v0_tests;
 #[cfg(test)]
 mod count_v0_tests;
 #[cfg(test)]
 mod edef_v0_tests;
+#[cfg(test)]
+mod equation_v0_tests;
+#[cfg(test)]
+mod float_v0_tests;
+#[cfg(test)]
+mod font_v0_tests;
+#[cfg(test)]
+mod footnote_v0_tests;
+#[cfg(test)]
+mod geometry_v0_tests;
+#[cfg(test)]
+mod graphics_v0_tests;
 mod ifnum_v0;
 #[cfg(test)]
 mod ifnum_v0_tests;
@@ -11,19 +30,64 @@ mod ifx_v0_tests;
 mod input_expand_v0;
 #[cfg(test)]
 mod input_macro_v0_tests;
+#[cfg(test)]
+mod label_v0_tests;
+#[cfg(test)]
+mod list_v0_tests;
 mod macro_expand_v0;
 #[cfg(test)]
+mod math_v0_tests;
+#[cfg(test)]
 mod meaning_v0_tests_base;
 #[cfg(test)]
 mod meaning_v0_tests_input_guards;
 #[cfg(test)]
 mod newcommand_v0_tests;
+mod ok_args_v0;
+mod ok_body_v0;
+mod ok_cite_v0;
+mod ok_class_v0;
+mod ok_color_v0;
+mod ok_dimen_v0;
+mod ok_equation_v0;
+mod ok_float_v0;
+mod ok_font_v0;
+mod ok_footnote_v0;
+mod ok_geometry_v0;
+mod ok_graphics_v0;
+mod ok_label_v0;
+mod ok_list_v0;
+mod ok_math_layout_v0;
+mod ok_math_v0;
+mod ok_page_v0;
+mod ok_rule_v0;
+mod ok_section_v0;
+mod ok_tabular_v0;
+mod ok_toc_v0;
 mod ok_v0;
 #[cfg(test)]
+#[allow(clippy::len_zero)]
 mod ok_v0_tests;
+mod package_v0;
+#[cfg(test)]
+mod package_v0_tests;
+#[cfg(test)]
+mod page_v0_tests;
 #[cfg(test)]
 mod providecommand_v0_tests;
+#[cfg(test)]
+mod rule_v0_tests;
+#[cfg(test)]
+mod section_v0_tests;
+#[cfg(test)]
+mod special_v0_tests;
 mod stats_v0;
+#[cfg(test)]
+mod tabular_v0_tests;
+#[cfg(test)]
+mod test_text_v0;
+#[cfg(test)]
+mod toc_v0_tests;
 mod tokenize_reason_v0;
 #[cfg(test)]
 mod tokenizer_textword_139_tests;
@@ -46,26 +110,42 @@ mod tokenizer_textword_148_tests;
 mod trace_v0;
 #[cfg(test)]
 mod xdef_noexpand_v0_tests;
-use crate::reasons_v0::{invalid_log_bytes_v0, InvalidInputReasonV0};
+use crate::reasons_v0::{invalid_log_bytes_v0, invalid_named_log_bytes_v0, InvalidInputReasonV0};
 use crate::tex::tokenize_v0::{tokenize_v0, TokenV0, MAX_TOKENS_V0};
 use carreltex_core::{
-    build_compile_result_v0, truncate_log_bytes_v0, CompileRequestV0, CompileResultV0,
-    CompileStatus, Mount, DEFAULT_COMPILE_MAIN_MAX_LOG_BYTES_V0, MAX_LOG_BYTES_V0,
+    build_compile_report_json_v0, build_compile_result_v0, truncate_log_bytes_v0, CompileRequestV0,
+    CompileResultV0, CompileStatus, Mount, DEFAULT_COMPILE_MAIN_MAX_LOG_BYTES_V0, MAX_LOG_BYTES_V0,
 };
+use carreltex_pdf::{write_pdf_with_images_v0, PdfFontFileV0, PdfImageFileV0, PdfWriteOptionsV0};
 use carreltex_xdv::{
-    validate_dvi_v2_text_page_v0, write_dvi_v2_text_page_with_layout_wrap_and_paging_v0,
-    DEFAULT_MAX_LINES_PER_PAGE_V0, DEFAULT_MAX_LINE_GLYPHS_V0,
+    read_dvi_v0, text_float_pages_v0, text_page_starts_v0, validate_dvi_v2_text_page_v0,
+    write_dvi_v2_text_page_with_layout_paging_and_marks_v0, DviTextMarksV0,
+    DEFAULT_MAX_LINES_PER_PAGE_V0, DEFAULT_MAX_LINE_GLYPHS_V0, MAX_DVI_RULE_DIMENSION_SP_V0,
 };
 use input_expand_v0::expand_inputs_v0;
 use macro_expand_v0::expand_macros_v0;
+use ok_body_v0::OkBodyV0;
+use ok_cite_v0::{mounted_bibliography_v0, OkBibliographyV0};
+use ok_class_v0::class_failure_v0;
+use ok_dimen_v0::OkLayoutV0;
+use ok_float_v0::{float_entries_v0, write_float_list_v0, FloatKindV0};
+use ok_font_v0::{parse_font_map_v0, FONT_MAP_PATH_V0};
+use ok_geometry_v0::OkGeometryV0;
+use ok_graphics_v0::{mounted_images_v0, OkImageV0};
+use ok_label_v0::{aux_labels_v0, label_warnings_v0, write_aux_v0, OkAuxFilesV0};
+use ok_page_v0::{dvi_pages_v0, text_height_sp_v0, OkPagesV0};
+use ok_toc_v0::{toc_entries_v0, write_toc_v0};
 use ok_v0::{
     extract_strict_ok_text_body_v0, MAX_OK_TEXT_BYTES_V0, OK_GLYPH_ADVANCE_SP_V0,
     OK_LINE_ADVANCE_SP_V0,
 };
+use package_v0::{expand_packages_v0, PackageErrorV0};
 use stats_v0::build_tex_stats_from_tokens_v0;
 use trace_v0::build_not_implemented_log_v0;
 const MISSING_COMPONENTS_V0: &[&str] = &["tex-engine"];
 const EMPTY_TEX_STATS_JSON: &str = "";
+/// Runs over the body before cross-references are left as they stand.
+const MAX_LABEL_PASSES_V0: usize = 3;
 fn invalid_result_v0(max_log_bytes: u32, reason: InvalidInputReasonV0) -> CompileResultV0 {
     build_compile_result_v0(
         CompileStatus::InvalidInput,
@@ -75,6 +155,139 @@ fn invalid_result_v0(max_log_bytes: u32, reason: InvalidInputReasonV0) -> Compil
         EMPTY_TEX_STATS_JSON.to_owned(),
     )
 }
+/// `invalid_result_v0` with the log naming what `reason` applies to.
+fn invalid_named_result_v0(
+    max_log_bytes: u32,
+    reason: InvalidInputReasonV0,
+    name: &[u8],
+) -> CompileResultV0 {
+    build_compile_result_v0(
+        CompileStatus::InvalidInput,
+        &[],
+        truncate_log_bytes_v0(&invalid_named_log_bytes_v0(reason, name), max_log_bytes),
+        vec![],
+        EMPTY_TEX_STATS_JSON.to_owned(),
+    )
+}
+/// Font files named by the mount's font map, if it has one; the PDF writer embeds
+/// those used by the document's fonts.
+fn mounted_font_files_v0(mount: &Mount) -> Result<Vec<PdfFontFileV0<'_>>, InvalidInputReasonV0> {
+    let Some(map) = mount.read_file(FONT_MAP_PATH_V0) else {
+        return Ok(Vec::new());
+    };
+    let mut files = Vec::<PdfFontFileV0<'_>>::new();
+    for entry in parse_font_map_v0(map)? {
+        let bytes = mount
+            .read_file_by_bytes_v0(entry.path)
+            .map_err(|_| InvalidInputReasonV0::FontMapInvalid)?
+            .ok_or(InvalidInputReasonV0::FontFileMissing)?;
+        files.push(PdfFontFileV0 {
+            name: entry.name,
+            bytes,
+        });
+    }
+    Ok(files)
+}
+/// An OK body after the cross-reference passes: the pages, `.aux` labels and
+/// `.toc`, `.lof` and `.lot` entries of its last pass and the warnings to log.
+/// `\bibliography` input comes from the `.bbl` file written before the passes.
+struct ResolvedOkBodyV0 {
+    body: OkBodyV0,
+    pages: OkPagesV0,
+    aux_bytes: Vec<u8>,
+    toc_bytes: Vec<u8>,
+    lof_bytes: Vec<u8>,
+    lot_bytes: Vec<u8>,
+    warnings: Vec<u8>,
+}
+
+/// Where the writer starts each page of `text` and the page each float lands on;
+/// `page_layout_failed` / `float_placement_failed` when it cannot lay them out.
+fn ok_page_layout_v0(
+    text: &[u8],
+    marks: &DviTextMarksV0<'_>,
+    em_sp: i32,
+    max_line_glyphs: usize,
+    max_lines_per_page: usize,
+) -> Result<(Vec<usize>, Vec<usize>), InvalidInputReasonV0> {
+    let page_starts = text_page_starts_v0(text, marks, em_sp, max_line_glyphs, max_lines_per_page)
+        .ok_or(InvalidInputReasonV0::PageLayoutFailed)?;
+    let float_pages = text_float_pages_v0(text, marks, em_sp, max_line_glyphs, max_lines_per_page)
+        .ok_or(InvalidInputReasonV0::FloatPlacementFailed)?;
+    Ok((page_starts, float_pages))
+}
+
+/// Extracts the OK body from the tokens before and after macro expansion, rerunning
+/// it against the labels, contents entries and page numbers of the previous pass,
+/// starting from none, until they stop changing or `MAX_LABEL_PASSES_V0` is reached.
+/// `Ok(None)` when the document falls outside the OK subset.
+fn resolve_ok_body_v0(
+    tokens: &[TokenV0],
+    macro_expanded_tokens: &[TokenV0],
+    layout: &OkLayoutV0,
+    images: &[OkImageV0],
+    bibliography: Option<&Result<OkBibliographyV0, InvalidInputReasonV0>>,
+) -> Result<Option<ResolvedOkBodyV0>, InvalidInputReasonV0> {
+    let mut aux = OkAuxFilesV0::default();
+    for pass in 1..=MAX_LABEL_PASSES_V0 {
+        let body = match (
+            extract_strict_ok_text_body_v0(tokens, layout, &aux, images, bibliography),
+            extract_strict_ok_text_body_v0(
+                macro_expanded_tokens,
+                layout,
+                &aux,
+                images,
+                bibliography,
+            ),
+        ) {
+            (_, Err(reason)) => return Err(reason),
+            (Ok(Some(pre_macro)), Ok(Some(post_macro))) if pre_macro == post_macro => post_macro,
+            _ => return Ok(None),
+        };
+        if body.text.len() > MAX_OK_TEXT_BYTES_V0 {
+            return Ok(None);
+        }
+        let (page_starts, float_pages) = ok_page_layout_v0(
+            &body.text,
+            &body.marks_v0(),
+            body.layout.em_sp,
+            body.layout.max_line_glyphs,
+            body.layout.max_lines_per_page,
+        )?;
+        let pages = OkPagesV0::new_v0(page_starts, &body.page_commands)?
+            .with_floats_v0(&body.floats, &float_pages);
+        let next = OkAuxFilesV0 {
+            labels: aux_labels_v0(&body.labels, &pages),
+            bibcites: body.citations.bibcites.clone(),
+            toc: toc_entries_v0(&body.toc_entries, &pages),
+            floats: float_entries_v0(&body.float_entries, &pages),
+            pages,
+        };
+        if next == aux || pass == MAX_LABEL_PASSES_V0 {
+            let rerun = next.labels != aux.labels || next.bibcites != aux.bibcites;
+            let warnings = label_warnings_v0(
+                &body.unused_class_options,
+                &next.labels,
+                &body.undefined_refs,
+                &next.pages,
+                rerun,
+            );
+            let aux_bytes = write_aux_v0(&next.labels, &body.citations);
+            return Ok(Some(ResolvedOkBodyV0 {
+                body,
+                pages: next.pages,
+                aux_bytes,
+                toc_bytes: write_toc_v0(&next.toc),
+                lof_bytes: write_float_list_v0(&next.floats, FloatKindV0::Figure),
+                lot_bytes: write_float_list_v0(&next.floats, FloatKindV0::Table),
+                warnings,
+            }));
+        }
+        aux = next;
+    }
+    Ok(None)
+}
+
 pub fn compile_main_v0(mount: &mut Mount) -> CompileResultV0 {
     let request = CompileRequestV0 {
         entrypoint: "main.tex".to_owned(),
@@ -89,7 +302,7 @@ pub fn compile_main_v0(mount: &mut Mount) -> CompileResultV0 {
 }
 
 pub fn compile_request_v0(mount: &mut Mount, req: &CompileRequestV0) -> CompileResultV0 {
-    // INVALID_INPUT reason precedence SSOT: request -> finalize -> read -> tokenize -> input -> macro -> stats.
+    // INVALID_INPUT reason precedence SSOT: request -> finalize -> read -> tokenize -> input -> package -> macro -> stats.
     if req.entrypoint != "main.tex" || req.source_date_epoch == 0 || req.max_log_bytes == 0 {
         return invalid_result_v0(req.max_log_bytes, InvalidInputReasonV0::RequestInvalid);
     }
@@ -137,6 +350,19 @@ pub fn compile_request_v0(mount: &mut Mount, req: &CompileRequestV0) -> CompileR
         Ok(result) => result,
         Err(reason) => return invalid_result_v0(req.max_log_bytes, reason),
     };
+    let (expanded_tokens, loaded_packages) = match expand_packages_v0(&expanded_tokens, mount) {
+        Ok(result) => result,
+        Err(PackageErrorV0::NotAvailable(name)) => {
+            return invalid_named_result_v0(
+                req.max_log_bytes,
+                InvalidInputReasonV0::PackageNotAvailable,
+                &name,
+            )
+        }
+        Err(PackageErrorV0::Invalid(reason)) => {
+            return invalid_result_v0(req.max_log_bytes, reason)
+        }
+    };
     if expanded_tokens.len() > MAX_TOKENS_V0 {
         return invalid_result_v0(
             req.max_log_bytes,
@@ -167,53 +393,128 @@ pub fn compile_request_v0(mount: &mut Mount, req: &CompileRequestV0) -> CompileR
     if tex_stats_json.is_empty() {
         return invalid_result_v0(req.max_log_bytes, InvalidInputReasonV0::StatsBuildFailed);
     }
-    let ok_text_bytes = match (
-        extract_strict_ok_text_body_v0(&expanded_tokens),
-        extract_strict_ok_text_body_v0(&macro_expanded_tokens),
+    if let Some((reason, name)) = class_failure_v0(&macro_expanded_tokens) {
+        return invalid_named_result_v0(req.max_log_bytes, reason, &name);
+    }
+    let max_line_glyphs = req
+        .ok_max_line_glyphs_v0
+        .unwrap_or(DEFAULT_MAX_LINE_GLYPHS_V0 as u32) as usize;
+    let max_lines_per_page = req
+        .ok_max_lines_per_page_v0
+        .unwrap_or(DEFAULT_MAX_LINES_PER_PAGE_V0 as u32) as usize;
+    let line_advance_sp = req.ok_line_advance_sp_v0.unwrap_or(OK_LINE_ADVANCE_SP_V0);
+    let glyph_advance_sp = req.ok_glyph_advance_sp_v0.unwrap_or(OK_GLYPH_ADVANCE_SP_V0);
+    let layout = OkLayoutV0 {
+        em_sp: glyph_advance_sp,
+        line_advance_sp,
+        text_width_sp: (glyph_advance_sp as i64 * max_line_glyphs as i64)
+            .min(MAX_DVI_RULE_DIMENSION_SP_V0 as i64) as i32,
+        text_height_sp: text_height_sp_v0(line_advance_sp, max_lines_per_page),
+        max_line_glyphs,
+        max_lines_per_page,
+        geometry: OkGeometryV0::default(),
+    };
+    let images = mounted_images_v0(&[&expanded_tokens, &macro_expanded_tokens], mount);
+    let bibliography = mounted_bibliography_v0(&macro_expanded_tokens, mount);
+    let resolved = match resolve_ok_body_v0(
+        &expanded_tokens,
+        &macro_expanded_tokens,
+        &layout,
+        &images,
+        bibliography.as_ref(),
     ) {
-        (Some(pre_macro), Some(post_macro)) if pre_macro == post_macro => Some(post_macro),
-        _ => None,
+        Ok(resolved) => resolved,
+        Err(reason) => return invalid_result_v0(req.max_log_bytes, reason),
     };
 
-    if let Some(ok_text_bytes) = ok_text_bytes {
-        if ok_text_bytes.len() <= MAX_OK_TEXT_BYTES_V0 {
-            let max_line_glyphs =
-                req.ok_max_line_glyphs_v0
-                    .unwrap_or(DEFAULT_MAX_LINE_GLYPHS_V0 as u32) as usize;
-            let max_lines_per_page =
-                req.ok_max_lines_per_page_v0
-                    .unwrap_or(DEFAULT_MAX_LINES_PER_PAGE_V0 as u32) as usize;
-            let line_advance_sp = req.ok_line_advance_sp_v0.unwrap_or(OK_LINE_ADVANCE_SP_V0);
-            let glyph_advance_sp = req.ok_glyph_advance_sp_v0.unwrap_or(OK_GLYPH_ADVANCE_SP_V0);
-            let xdv_bytes = match write_dvi_v2_text_page_with_layout_wrap_and_paging_v0(
-                &ok_text_bytes,
-                glyph_advance_sp,
-                line_advance_sp,
-                max_line_glyphs,
-                max_lines_per_page,
-            ) {
-                Some(bytes) => bytes,
-                None => {
-                    return invalid_result_v0(
-                        req.max_log_bytes,
-                        InvalidInputReasonV0::StatsBuildFailed,
-                    )
-                }
-            };
-            if !validate_dvi_v2_text_page_v0(&xdv_bytes) {
-                return invalid_result_v0(
-                    req.max_log_bytes,
-                    InvalidInputReasonV0::StatsBuildFailed,
-                );
+    if let Some(resolved) = resolved {
+        let ok_body = &resolved.body;
+        let layout = ok_body.layout;
+        let furniture = resolved.pages.furniture_v0(
+            &ok_body.toc_entries,
+            ok_body.mark_level,
+            ok_body.mark_font,
+            &layout,
+        );
+        let pages = match dvi_pages_v0(&furniture, &layout) {
+            Ok(pages) => pages,
+            Err(reason) => return invalid_result_v0(req.max_log_bytes, reason),
+        };
+        let marks = DviTextMarksV0 {
+            pages,
+            ..ok_body.marks_v0()
+        };
+        let xdv_bytes = match write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+            &ok_body.text,
+            &marks,
+            layout.em_sp,
+            layout.line_advance_sp,
+            layout.max_line_glyphs,
+            layout.max_lines_per_page,
+        ) {
+            Some(bytes) => bytes,
+            None => {
+                return invalid_result_v0(req.max_log_bytes, InvalidInputReasonV0::StatsBuildFailed)
             }
-            return build_compile_result_v0(
-                CompileStatus::Ok,
-                &[],
-                vec![],
-                xdv_bytes,
-                tex_stats_json,
-            );
+        };
+        if !validate_dvi_v2_text_page_v0(&xdv_bytes) {
+            return invalid_result_v0(req.max_log_bytes, InvalidInputReasonV0::StatsBuildFailed);
+        }
+        let font_files = match mounted_font_files_v0(mount) {
+            Ok(files) => files,
+            Err(reason) => return invalid_result_v0(req.max_log_bytes, reason),
+        };
+        let image_files = images
+            .iter()
+            .filter_map(|image| {
+                Some(PdfImageFileV0 {
+                    name: &image.path,
+                    bytes: mount.read_file_by_bytes_v0(&image.path).ok()??,
+                })
+            })
+            .collect::<Vec<_>>();
+        let pdf_options = PdfWriteOptionsV0 {
+            paper_width_sp: layout.geometry.paper_width_sp,
+            paper_height_sp: layout.geometry.paper_height_sp,
+            source_date_epoch: req.source_date_epoch,
+            ..PdfWriteOptionsV0::default()
+        };
+        let main_pdf_bytes = match read_dvi_v0(&xdv_bytes).ok().and_then(|document| {
+            write_pdf_with_images_v0(&document, &font_files, &image_files, &pdf_options).ok()
+        }) {
+            Some(bytes) => bytes,
+            None => {
+                return invalid_result_v0(req.max_log_bytes, InvalidInputReasonV0::PdfWriteFailed)
+            }
+        };
+        let mut result = build_compile_result_v0(
+            CompileStatus::Ok,
+            &[],
+            truncate_log_bytes_v0(&resolved.warnings, req.max_log_bytes),
+            xdv_bytes,
+            tex_stats_json,
+        );
+        let loaded_packages = loaded_packages
+            .iter()
+            .map(|name| String::from_utf8_lossy(name))
+            .collect::<Vec<_>>();
+        result.report_json = build_compile_report_json_v0(
+            CompileStatus::Ok,
+            &[],
+            &loaded_packages
+                .iter()
+                .map(AsRef::as_ref)
+                .collect::<Vec<_>>(),
+        );
+        result.main_pdf_bytes = main_pdf_bytes;
+        result.main_aux_bytes = resolved.aux_bytes;
+        result.main_toc_bytes = resolved.toc_bytes;
+        result.main_lof_bytes = resolved.lof_bytes;
+        result.main_lot_bytes = resolved.lot_bytes;
+        if let Some(Ok(bibliography)) = bibliography {
+            result.main_bbl_bytes = bibliography.bbl;
         }
+        return result;
     }
 
     let not_implemented_log =
@@ -0,0 +1,301 @@
+mod database_v0;
+mod entry_v0;
+mod names_v0;
+mod text_v0;
+
+use super::ok_toc_v0::text_width_sp_v0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use database_v0::{BibDatabaseV0, BibEntryV0};
+use entry_v0::{chop_word_v0, BibFormatterV0};
+use names_v0::{parse_name_v0, split_names_v0};
+use text_v0::{purify_v0, sortify_v0, text_prefix_v0};
+
+/// Longest `.bbl` line before BibTeX breaks it at a space.
+const MAX_BBL_LINE_V0: usize = 79;
+/// The month macros of `plain`, `unsrt` and `alpha`.
+const MONTHS_V0: [&[u8]; 12] = [
+    b"January",
+    b"February",
+    b"March",
+    b"April",
+    b"May",
+    b"June",
+    b"July",
+    b"August",
+    b"September",
+    b"October",
+    b"November",
+    b"December",
+];
+/// The month macros of `abbrv`.
+const MONTHS_ABBRV_V0: [&[u8]; 12] = [
+    b"Jan.", b"Feb.", b"Mar.", b"Apr.", b"May", b"June", b"July", b"Aug.", b"Sept.", b"Oct.",
+    b"Nov.", b"Dec.",
+];
+const MONTH_MACROS_V0: [&[u8]; 12] = [
+    b"jan", b"feb", b"mar", b"apr", b"may", b"jun", b"jul", b"aug", b"sep", b"oct", b"nov", b"dec",
+];
+
+/// The standard BibTeX styles `\bibliographystyle` may name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BibStyleV0 {
+    Plain,
+    Unsrt,
+    Alpha,
+    Abbrv,
+}
+
+impl BibStyleV0 {
+    pub(crate) fn from_name_v0(name: &[u8]) -> Option<Self> {
+        match name {
+            b"plain" => Some(Self::Plain),
+            b"unsrt" => Some(Self::Unsrt),
+            b"alpha" => Some(Self::Alpha),
+            b"abbrv" => Some(Self::Abbrv),
+            _ => None,
+        }
+    }
+}
+
+/// `sort.format.names`: the names' sort forms, `others` last as "et al".
+fn sort_names_v0(value: &[u8]) -> Vec<u8> {
+    let names = split_names_v0(value);
+    let mut out = Vec::<u8>::new();
+    for (index, name) in names.iter().enumerate() {
+        if index > 0 {
+            out.extend_from_slice(b"   ");
+        }
+        let name = parse_name_v0(name);
+        if index + 1 == names.len() && name.is_others_v0() {
+            out.extend_from_slice(b"et al");
+        } else {
+            out.extend_from_slice(&sortify_v0(&name.sort_form_v0()));
+        }
+    }
+    out
+}
+
+/// `format.lab.names`: the first three letters of a single author's last name,
+/// or the initials of up to four authors, with `+` for more.
+fn label_names_v0(value: &[u8]) -> Vec<u8> {
+    let names = split_names_v0(value);
+    if names.len() == 1 {
+        let name = parse_name_v0(names[0]);
+        let initials = name.label_initials_v0();
+        if initials.len() >= 2 {
+            return initials;
+        }
+        return text_prefix_v0(&name.last_v0(), 3);
+    }
+    let mut out = Vec::<u8>::new();
+    let shown = if names.len() > 4 { 3 } else { names.len() };
+    for (index, name) in names.iter().take(shown).enumerate() {
+        let name = parse_name_v0(name);
+        if index + 1 == names.len() && name.is_others_v0() {
+            out.push(b'+');
+        } else {
+            out.extend_from_slice(&name.label_initials_v0());
+        }
+    }
+    if names.len() > 4 {
+        out.push(b'+');
+    }
+    out
+}
+
+/// The names an entry is sorted and labelled by, as the styles' `*.sort` and
+/// `*.label` functions choose them, formatted by `format`.
+fn by_names_v0(entry: &BibEntryV0, format: fn(&[u8]) -> Vec<u8>) -> Option<Vec<u8>> {
+    let field = |name: &[u8]| entry.field_v0(name);
+    match entry.entry_type.as_slice() {
+        b"book" | b"inbook" => field(b"author").or(field(b"editor")).map(format),
+        b"proceedings" => field(b"editor").map(format),
+        _ => field(b"author").map(format),
+    }
+}
+
+/// The organization `manual` and `proceedings` entries without names fall back
+/// to, `The ` chopped.
+fn by_organization_v0(entry: &BibEntryV0) -> Option<&[u8]> {
+    match entry.entry_type.as_slice() {
+        b"manual" | b"proceedings" => entry
+            .field_v0(b"organization")
+            .map(|value| chop_word_v0(b"The ", value)),
+        _ => None,
+    }
+}
+
+/// `sort.format.title`: the title without a leading article.
+fn sort_title_v0(title: &[u8]) -> Vec<u8> {
+    let title = [b"A ".as_slice(), b"An ", b"The "]
+        .iter()
+        .fold(title, |title, word| chop_word_v0(word, title));
+    sortify_v0(title)
+}
+
+/// The alpha style's label, before any `a`, `b`, ... suffix, and the sort label
+/// entries with equal labels share.
+fn alpha_label_v0(entry: &BibEntryV0) -> (Vec<u8>, Vec<u8>) {
+    let prefix = by_names_v0(entry, label_names_v0)
+        .or_else(|| entry.field_v0(b"key").map(|key| text_prefix_v0(key, 3)))
+        .or_else(|| by_organization_v0(entry).map(|name| text_prefix_v0(name, 3)))
+        .unwrap_or_else(|| entry.key.iter().take(3).copied().collect());
+    let year = purify_v0(entry.field_v0(b"year").unwrap_or_default());
+    let label = [prefix.as_slice(), &year[year.len().saturating_sub(2)..]].concat();
+    let sort_label =
+        sortify_v0(&[prefix.as_slice(), &year[year.len().saturating_sub(4)..]].concat());
+    (label, sort_label)
+}
+
+/// `presort`: the key entries are sorted by.
+fn sort_key_v0(entry: &BibEntryV0, alpha_sort_label: Option<&[u8]>) -> Vec<u8> {
+    let mut key = Vec::<u8>::new();
+    if let Some(label) = alpha_sort_label {
+        key.extend_from_slice(label);
+        key.extend_from_slice(b"    ");
+    }
+    let names = by_names_v0(entry, sort_names_v0)
+        .or_else(|| by_organization_v0(entry).map(sortify_v0))
+        .or_else(|| entry.field_v0(b"key").map(sortify_v0))
+        .unwrap_or_default();
+    key.extend_from_slice(&names);
+    key.extend_from_slice(b"    ");
+    if alpha_sort_label.is_none() {
+        key.extend_from_slice(&sortify_v0(entry.field_v0(b"year").unwrap_or_default()));
+        key.extend_from_slice(b"    ");
+    }
+    key.extend_from_slice(&sort_title_v0(entry.field_v0(b"title").unwrap_or_default()));
+    key
+}
+
+/// The cited entries in citation order: `*` stands for every entry not cited
+/// before it, and keys the databases lack are left out.
+fn cited_entries_v0<'a>(citations: &[Vec<u8>], database: &'a BibDatabaseV0) -> Vec<&'a BibEntryV0> {
+    let mut cited = Vec::<&BibEntryV0>::new();
+    for citation in citations {
+        let found = database
+            .entries
+            .iter()
+            .filter(|entry| citation == b"*" || entry.key == *citation);
+        for entry in found {
+            if cited.iter().all(|known| known.key != entry.key) {
+                cited.push(entry);
+            }
+        }
+    }
+    cited
+}
+
+/// Appends `line` and a newline, broken at spaces as BibTeX's `write$` does when
+/// longer than `MAX_BBL_LINE_V0`; continuation lines start with two spaces.
+fn push_bbl_line_v0(out: &mut Vec<u8>, line: &[u8]) {
+    let mut rest = line.to_vec();
+    while rest.len() > MAX_BBL_LINE_V0 {
+        let before = rest[3..=MAX_BBL_LINE_V0]
+            .iter()
+            .rposition(|byte| byte.is_ascii_whitespace())
+            .map(|index| index + 3);
+        let after = || {
+            rest[MAX_BBL_LINE_V0 + 1..]
+                .iter()
+                .position(|byte| byte.is_ascii_whitespace())
+                .map(|index| index + MAX_BBL_LINE_V0 + 1)
+        };
+        let Some(at) = before.or_else(after) else {
+            break;
+        };
+        out.extend_from_slice(&rest[..at]);
+        out.push(b'\n');
+        let next = rest[at..].trim_ascii_start();
+        rest = [b"  ", next].concat();
+    }
+    out.extend_from_slice(&rest);
+    out.push(b'\n');
+}
+
+/// The `.bbl` file BibTeX writes for `citations` with `style` from the `.bib`
+/// files `databases`: the `@preamble` text, then a `thebibliography` of the cited
+/// entries, sorted unless the style is `unsrt`, each a `\bibitem` with its
+/// blocks on `\newblock` lines.
+pub(crate) fn write_bbl_v0(
+    style: BibStyleV0,
+    citations: &[Vec<u8>],
+    databases: &[&[u8]],
+) -> Result<Vec<u8>, InvalidInputReasonV0> {
+    let months = if style == BibStyleV0::Abbrv {
+        MONTHS_ABBRV_V0
+    } else {
+        MONTHS_V0
+    };
+    let mut database = BibDatabaseV0 {
+        strings: MONTH_MACROS_V0
+            .iter()
+            .zip(months)
+            .map(|(name, month)| (name.to_vec(), month.to_vec()))
+            .collect(),
+        ..BibDatabaseV0::default()
+    };
+    for bytes in databases {
+        database.read_file_v0(bytes)?;
+    }
+    let entries = cited_entries_v0(citations, &database);
+    let mut items = entries
+        .into_iter()
+        .map(|entry| {
+            let alpha = (style == BibStyleV0::Alpha).then(|| alpha_label_v0(entry));
+            let sort_key = sort_key_v0(entry, alpha.as_ref().map(|(_, sort)| sort.as_slice()));
+            (entry, alpha, sort_key)
+        })
+        .collect::<Vec<_>>();
+    if style != BibStyleV0::Unsrt {
+        items.sort_by(|left, right| left.2.cmp(&right.2));
+    }
+    let sort_labels = items
+        .iter()
+        .map(|(_, alpha, _)| alpha.as_ref().map(|(_, sort_label)| sort_label))
+        .collect::<Vec<_>>();
+    let mut labels = Vec::<Option<Vec<u8>>>::new();
+    let mut longest = Vec::<u8>::new();
+    for (index, (_, alpha, _)) in items.iter().enumerate() {
+        let label = match alpha {
+            // Entries whose sort labels match get `a`, `b`, ... in sorted order.
+            Some((label, _)) => {
+                let same = |at: usize| sort_labels.get(at) == Some(&sort_labels[index]);
+                let previous = (0..index).rev().take_while(|at| same(*at)).count();
+                let mut label = label.clone();
+                if previous > 0 || same(index + 1) {
+                    label.push(b'a' + previous.min(25) as u8);
+                }
+                label
+            }
+            None => (index + 1).to_string().into_bytes(),
+        };
+        if text_width_sp_v0(&label, 65_536) > text_width_sp_v0(&longest, 65_536) {
+            longest = label.clone();
+        }
+        labels.push(alpha.is_some().then_some(label));
+    }
+    let mut out = Vec::<u8>::new();
+    if !database.preamble.is_empty() {
+        push_bbl_line_v0(&mut out, &database.preamble);
+    }
+    push_bbl_line_v0(
+        &mut out,
+        &[b"\\begin{thebibliography}{", longest.as_slice(), b"}"].concat(),
+    );
+    for ((entry, _, _), label) in items.iter().zip(labels) {
+        out.push(b'\n');
+        let bibitem = match label {
+            Some(label) => [b"\\bibitem[", label.as_slice(), b"]{", &entry.key, b"}"].concat(),
+            None => [b"\\bibitem{", entry.key.as_slice(), b"}"].concat(),
+        };
+        push_bbl_line_v0(&mut out, &bibitem);
+        let text = BibFormatterV0::new_v0(entry, style == BibStyleV0::Abbrv).format_v0();
+        for line in text.split(|byte| *byte == b'\n') {
+            push_bbl_line_v0(&mut out, line);
+        }
+    }
+    out.push(b'\n');
+    push_bbl_line_v0(&mut out, b"\\end{thebibliography}");
+    Ok(out)
+}
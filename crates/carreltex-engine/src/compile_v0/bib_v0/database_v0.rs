@@ -0,0 +1,250 @@
+use crate::reasons_v0::InvalidInputReasonV0;
+
+/// Entries the `.bib` files of a document may hold together.
+pub(crate) const MAX_BIB_ENTRIES_V0: usize = 4096;
+
+/// One `@type{key, field = value, ...}` entry; the type and field names are in
+/// lower case, values have their white space runs made single spaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BibEntryV0 {
+    pub(crate) entry_type: Vec<u8>,
+    pub(crate) key: Vec<u8>,
+    fields: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl BibEntryV0 {
+    /// The value of field `name`, `None` when it is missing or blank, as `empty$`
+    /// tells.
+    pub(crate) fn field_v0(&self, name: &[u8]) -> Option<&[u8]> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_slice())
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// The entries and `@preamble` text read from a document's `.bib` files, with
+/// the `@string` macros defined so far.
+#[derive(Debug, Default)]
+pub(crate) struct BibDatabaseV0 {
+    pub(crate) entries: Vec<BibEntryV0>,
+    pub(crate) preamble: Vec<u8>,
+    pub(crate) strings: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+struct BibParserV0<'a> {
+    bytes: &'a [u8],
+    index: usize,
+}
+
+/// Characters BibTeX allows in entry types, keys, field and macro names.
+fn is_name_byte_v0(byte: u8) -> bool {
+    byte.is_ascii_graphic() && !b"\"#%'(),={}".contains(&byte)
+}
+
+impl BibParserV0<'_> {
+    fn skip_spaces_v0(&mut self) {
+        while self
+            .bytes
+            .get(self.index)
+            .is_some_and(|byte| byte.is_ascii_whitespace())
+        {
+            self.index += 1;
+        }
+    }
+
+    fn peek_v0(&mut self) -> Option<u8> {
+        self.skip_spaces_v0();
+        self.bytes.get(self.index).copied()
+    }
+
+    fn expect_v0(&mut self, byte: u8) -> Result<(), InvalidInputReasonV0> {
+        if self.peek_v0() != Some(byte) {
+            return Err(InvalidInputReasonV0::BibSyntaxInvalid);
+        }
+        self.index += 1;
+        Ok(())
+    }
+
+    fn name_v0(&mut self) -> Result<&[u8], InvalidInputReasonV0> {
+        self.skip_spaces_v0();
+        let start = self.index;
+        while self
+            .bytes
+            .get(self.index)
+            .is_some_and(|byte| is_name_byte_v0(*byte))
+        {
+            self.index += 1;
+        }
+        if start == self.index {
+            return Err(InvalidInputReasonV0::BibSyntaxInvalid);
+        }
+        Ok(&self.bytes[start..self.index])
+    }
+
+    /// Text up to the `close` delimiter of a `{...}` or `"..."` part, braces
+    /// balanced; the delimiter itself is consumed.
+    fn delimited_v0(&mut self, close: u8) -> Result<&[u8], InvalidInputReasonV0> {
+        let start = self.index;
+        let mut depth = 0usize;
+        while let Some(byte) = self.bytes.get(self.index).copied() {
+            self.index += 1;
+            match byte {
+                b'{' => depth += 1,
+                b'}' if depth == 0 && close == b'}' => {
+                    return Ok(&self.bytes[start..self.index - 1])
+                }
+                b'}' => {
+                    depth = depth
+                        .checked_sub(1)
+                        .ok_or(InvalidInputReasonV0::BibSyntaxInvalid)?
+                }
+                b'"' if depth == 0 && close == b'"' => {
+                    return Ok(&self.bytes[start..self.index - 1])
+                }
+                _ => {}
+            }
+        }
+        Err(InvalidInputReasonV0::BibSyntaxInvalid)
+    }
+
+    /// A value: `{...}`, `"..."`, number and macro parts joined by `#`.
+    fn value_v0(
+        &mut self,
+        strings: &[(Vec<u8>, Vec<u8>)],
+    ) -> Result<Vec<u8>, InvalidInputReasonV0> {
+        let mut value = Vec::<u8>::new();
+        loop {
+            match self.peek_v0() {
+                Some(open @ (b'{' | b'"')) => {
+                    self.index += 1;
+                    let close = if open == b'{' { b'}' } else { b'"' };
+                    value.extend_from_slice(self.delimited_v0(close)?);
+                }
+                Some(byte) if byte.is_ascii_digit() => {
+                    while self
+                        .bytes
+                        .get(self.index)
+                        .is_some_and(|byte| byte.is_ascii_digit())
+                    {
+                        value.push(self.bytes[self.index]);
+                        self.index += 1;
+                    }
+                }
+                Some(_) => {
+                    let name = self.name_v0()?.to_ascii_lowercase();
+                    let (_, text) = strings
+                        .iter()
+                        .rev()
+                        .find(|(macro_name, _)| *macro_name == name)
+                        .ok_or(InvalidInputReasonV0::BibStringUndefined)?;
+                    value.extend_from_slice(text);
+                }
+                None => return Err(InvalidInputReasonV0::BibSyntaxInvalid),
+            }
+            if self.peek_v0() != Some(b'#') {
+                return Ok(value);
+            }
+            self.index += 1;
+        }
+    }
+}
+
+/// `text` with each white space run made one space and none at either end.
+fn compress_spaces_v0(text: &[u8]) -> Vec<u8> {
+    text.split(|byte| byte.is_ascii_whitespace())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(&b' ')
+}
+
+impl BibDatabaseV0 {
+    /// Reads the entries, `@string` macros and `@preamble` text of one `.bib`
+    /// file. Text outside entries is a comment, as is `@comment`; an entry whose
+    /// key was read before is left out, as is a field repeated within an entry.
+    pub(crate) fn read_file_v0(&mut self, bytes: &[u8]) -> Result<(), InvalidInputReasonV0> {
+        let mut parser = BibParserV0 { bytes, index: 0 };
+        while let Some(at) = bytes[parser.index..].iter().position(|byte| *byte == b'@') {
+            parser.index += at + 1;
+            let entry_type = parser.name_v0()?.to_ascii_lowercase();
+            if entry_type == b"comment" {
+                continue;
+            }
+            let close = match parser.peek_v0() {
+                Some(b'{') => b'}',
+                Some(b'(') => b')',
+                _ => return Err(InvalidInputReasonV0::BibSyntaxInvalid),
+            };
+            parser.index += 1;
+            match entry_type.as_slice() {
+                b"preamble" => {
+                    let value = parser.value_v0(&self.strings)?;
+                    self.preamble.extend_from_slice(&compress_spaces_v0(&value));
+                }
+                b"string" => {
+                    let name = parser.name_v0()?.to_ascii_lowercase();
+                    parser.expect_v0(b'=')?;
+                    let value = compress_spaces_v0(&parser.value_v0(&self.strings)?);
+                    self.strings.push((name, value));
+                }
+                _ => {
+                    let entry = self.read_entry_v0(&mut parser, entry_type, close)?;
+                    if self.entries.iter().all(|known| known.key != entry.key) {
+                        if self.entries.len() >= MAX_BIB_ENTRIES_V0 {
+                            return Err(InvalidInputReasonV0::BibEntryCountExceeded);
+                        }
+                        self.entries.push(entry);
+                    }
+                    continue;
+                }
+            }
+            parser.expect_v0(close)?;
+        }
+        Ok(())
+    }
+
+    fn read_entry_v0(
+        &self,
+        parser: &mut BibParserV0,
+        entry_type: Vec<u8>,
+        close: u8,
+    ) -> Result<BibEntryV0, InvalidInputReasonV0> {
+        parser.skip_spaces_v0();
+        let start = parser.index;
+        while parser
+            .bytes
+            .get(parser.index)
+            .is_some_and(|byte| !byte.is_ascii_whitespace() && *byte != b',' && *byte != close)
+        {
+            parser.index += 1;
+        }
+        if start == parser.index {
+            return Err(InvalidInputReasonV0::BibSyntaxInvalid);
+        }
+        let mut entry = BibEntryV0 {
+            entry_type,
+            key: parser.bytes[start..parser.index].to_vec(),
+            fields: Vec::new(),
+        };
+        loop {
+            match parser.peek_v0() {
+                Some(byte) if byte == close => {
+                    parser.index += 1;
+                    return Ok(entry);
+                }
+                Some(b',') => parser.index += 1,
+                _ => return Err(InvalidInputReasonV0::BibSyntaxInvalid),
+            }
+            if parser.peek_v0() == Some(close) {
+                continue;
+            }
+            let name = parser.name_v0()?.to_ascii_lowercase();
+            parser.expect_v0(b'=')?;
+            let value = compress_spaces_v0(&parser.value_v0(&self.strings)?);
+            if entry.fields.iter().all(|(field, _)| *field != name) {
+                entry.fields.push((name, value));
+            }
+        }
+    }
+}
@@ -0,0 +1,559 @@
+use super::database_v0::BibEntryV0;
+use super::names_v0::{parse_name_v0, split_names_v0};
+use super::text_v0::{add_period_v0, lower_case_v0};
+
+/// Where `output` stands in the entry, as the standard styles' `output.state`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputStateV0 {
+    BeforeAll,
+    MidSentence,
+    AfterSentence,
+    AfterBlock,
+}
+
+/// The text of one `\bibitem`, written a piece at a time: each piece waits until
+/// the next one shows whether a comma, a period or a `\newblock` follows it.
+struct BibOutputV0 {
+    out: Vec<u8>,
+    pending: Vec<u8>,
+    state: OutputStateV0,
+}
+
+impl BibOutputV0 {
+    fn new_v0() -> Self {
+        Self {
+            out: Vec::new(),
+            pending: Vec::new(),
+            state: OutputStateV0::BeforeAll,
+        }
+    }
+
+    /// `output.nonnull`.
+    fn nonnull_v0(&mut self, text: Vec<u8>) {
+        let pending = std::mem::replace(&mut self.pending, text);
+        match self.state {
+            OutputStateV0::BeforeAll => self.out.extend_from_slice(&pending),
+            OutputStateV0::MidSentence => {
+                self.out.extend_from_slice(&pending);
+                self.out.extend_from_slice(b", ");
+            }
+            OutputStateV0::AfterSentence => {
+                self.out.extend_from_slice(&add_period_v0(pending));
+                self.out.push(b' ');
+            }
+            OutputStateV0::AfterBlock => {
+                self.out.extend_from_slice(&add_period_v0(pending));
+                self.out.extend_from_slice(b"\n\\newblock ");
+            }
+        }
+        self.state = OutputStateV0::MidSentence;
+    }
+
+    /// `output` and `output.check`: empty pieces are left out.
+    fn output_v0(&mut self, text: Vec<u8>) {
+        if !text.is_empty() {
+            self.nonnull_v0(text);
+        }
+    }
+
+    fn new_block_v0(&mut self) {
+        if self.state != OutputStateV0::BeforeAll {
+            self.state = OutputStateV0::AfterBlock;
+        }
+    }
+
+    fn new_sentence_v0(&mut self) {
+        if self.state == OutputStateV0::MidSentence {
+            self.state = OutputStateV0::AfterSentence;
+        }
+    }
+
+    /// `new.block.checka` and `new.block.checkb`: a new block when any of
+    /// `fields` is there.
+    fn new_block_check_v0(&mut self, fields: &[Option<&[u8]>]) {
+        if fields.iter().any(Option::is_some) {
+            self.new_block_v0();
+        }
+    }
+
+    fn new_sentence_check_v0(&mut self, fields: &[Option<&[u8]>]) {
+        if fields.iter().any(Option::is_some) {
+            self.new_sentence_v0();
+        }
+    }
+
+    /// `fin.entry`.
+    fn finish_v0(mut self) -> Vec<u8> {
+        self.out
+            .extend_from_slice(&add_period_v0(std::mem::take(&mut self.pending)));
+        self.out
+    }
+}
+
+/// Formats one entry as its style's `\bibitem` text, without the `\bibitem` line.
+pub(crate) struct BibFormatterV0<'a> {
+    entry: &'a BibEntryV0,
+    initials: bool,
+    output: BibOutputV0,
+}
+
+fn emphasize_v0(text: Option<&[u8]>) -> Vec<u8> {
+    text.map_or_else(Vec::new, |text| [b"{\\em ", text, b"}"].concat())
+}
+
+/// `n.dashify`: single hyphens made en dashes.
+fn dashify_v0(text: &[u8]) -> Vec<u8> {
+    let mut out = Vec::<u8>::with_capacity(text.len());
+    let mut index = 0usize;
+    while index < text.len() {
+        if text[index] != b'-' {
+            out.push(text[index]);
+            index += 1;
+        } else if text.get(index + 1) == Some(&b'-') {
+            while text.get(index) == Some(&b'-') {
+                out.push(b'-');
+                index += 1;
+            }
+        } else {
+            out.extend_from_slice(b"--");
+            index += 1;
+        }
+    }
+    out
+}
+
+/// `"The "` and the like taken off the front of `text`, as `chop.word` does.
+pub(crate) fn chop_word_v0<'a>(word: &[u8], text: &'a [u8]) -> &'a [u8] {
+    text.strip_prefix(word).unwrap_or(text)
+}
+
+impl<'a> BibFormatterV0<'a> {
+    pub(crate) fn new_v0(entry: &'a BibEntryV0, initials: bool) -> Self {
+        Self {
+            entry,
+            initials,
+            output: BibOutputV0::new_v0(),
+        }
+    }
+
+    fn field_v0(&self, name: &[u8]) -> Option<&'a [u8]> {
+        self.entry.field_v0(name)
+    }
+
+    /// `format.names`: `A`, `A and B`, `A, B, and C`, with `others` as "et al.".
+    fn format_names_v0(&self, value: &[u8]) -> Vec<u8> {
+        let names = split_names_v0(value);
+        let mut out = Vec::<u8>::new();
+        for (index, name) in names.iter().enumerate() {
+            let name = parse_name_v0(name);
+            let text = name.format_v0(self.initials);
+            if index == 0 {
+                out = text;
+            } else if index + 1 < names.len() {
+                out.extend_from_slice(b", ");
+                out.extend_from_slice(&text);
+            } else {
+                if names.len() > 2 {
+                    out.push(b',');
+                }
+                if name.is_others_v0() {
+                    out.extend_from_slice(b" et al.");
+                } else {
+                    out.extend_from_slice(b" and ");
+                    out.extend_from_slice(&text);
+                }
+            }
+        }
+        out
+    }
+
+    fn authors_v0(&self) -> Vec<u8> {
+        self.field_v0(b"author")
+            .map_or_else(Vec::new, |author| self.format_names_v0(author))
+    }
+
+    fn editors_v0(&self) -> Vec<u8> {
+        let Some(editor) = self.field_v0(b"editor") else {
+            return Vec::new();
+        };
+        let mut out = self.format_names_v0(editor);
+        if split_names_v0(editor).len() > 1 {
+            out.extend_from_slice(b", editors");
+        } else {
+            out.extend_from_slice(b", editor");
+        }
+        out
+    }
+
+    /// The authors, or the editors when there are none.
+    fn authors_or_editors_v0(&self) -> Vec<u8> {
+        if self.field_v0(b"author").is_some() {
+            self.authors_v0()
+        } else {
+            self.editors_v0()
+        }
+    }
+
+    fn title_v0(&self) -> Vec<u8> {
+        self.field_v0(b"title")
+            .map_or_else(Vec::new, |title| lower_case_v0(title, true))
+    }
+
+    fn btitle_v0(&self) -> Vec<u8> {
+        emphasize_v0(self.field_v0(b"title"))
+    }
+
+    fn raw_v0(&self, name: &[u8]) -> Vec<u8> {
+        self.field_v0(name).unwrap_or_default().to_vec()
+    }
+
+    fn date_v0(&self) -> Vec<u8> {
+        match (self.field_v0(b"month"), self.field_v0(b"year")) {
+            (Some(month), Some(year)) => [month, b" ", year].concat(),
+            (month, year) => year.or(month).unwrap_or_default().to_vec(),
+        }
+    }
+
+    fn bvolume_v0(&self) -> Vec<u8> {
+        let Some(volume) = self.field_v0(b"volume") else {
+            return Vec::new();
+        };
+        let mut out = [b"volume ", volume].concat();
+        if let Some(series) = self.field_v0(b"series") {
+            out.extend_from_slice(b" of ");
+            out.extend_from_slice(&emphasize_v0(Some(series)));
+        }
+        out
+    }
+
+    fn number_series_v0(&self) -> Vec<u8> {
+        if self.field_v0(b"volume").is_some() {
+            return Vec::new();
+        }
+        let Some(number) = self.field_v0(b"number") else {
+            return self.raw_v0(b"series");
+        };
+        let word: &[u8] = if self.output.state == OutputStateV0::MidSentence {
+            b"number "
+        } else {
+            b"Number "
+        };
+        let mut out = [word, number].concat();
+        if let Some(series) = self.field_v0(b"series") {
+            out.extend_from_slice(b" in ");
+            out.extend_from_slice(series);
+        }
+        out
+    }
+
+    fn edition_v0(&self) -> Vec<u8> {
+        let Some(edition) = self.field_v0(b"edition") else {
+            return Vec::new();
+        };
+        let title = self.output.state != OutputStateV0::MidSentence;
+        [lower_case_v0(edition, title).as_slice(), b" edition"].concat()
+    }
+
+    fn pages_v0(&self) -> Vec<u8> {
+        let Some(pages) = self.field_v0(b"pages") else {
+            return Vec::new();
+        };
+        if pages.iter().any(|byte| matches!(byte, b'-' | b',' | b'+')) {
+            [b"pages ", dashify_v0(pages).as_slice()].concat()
+        } else {
+            [b"page ", pages].concat()
+        }
+    }
+
+    fn vol_num_pages_v0(&self) -> Vec<u8> {
+        let mut out = self.raw_v0(b"volume");
+        if let Some(number) = self.field_v0(b"number") {
+            out.extend_from_slice(&[b"(", number, b")"].concat());
+        }
+        if let Some(pages) = self.field_v0(b"pages") {
+            if out.is_empty() {
+                return self.pages_v0();
+            }
+            out.push(b':');
+            out.extend_from_slice(&dashify_v0(pages));
+        }
+        out
+    }
+
+    fn chapter_pages_v0(&self) -> Vec<u8> {
+        let Some(chapter) = self.field_v0(b"chapter") else {
+            return self.pages_v0();
+        };
+        let mut out = self
+            .field_v0(b"type")
+            .map_or_else(|| b"chapter".to_vec(), |kind| lower_case_v0(kind, false));
+        out.push(b' ');
+        out.extend_from_slice(chapter);
+        if self.field_v0(b"pages").is_some() {
+            out.extend_from_slice(b", ");
+            out.extend_from_slice(&self.pages_v0());
+        }
+        out
+    }
+
+    fn in_ed_booktitle_v0(&self) -> Vec<u8> {
+        let Some(booktitle) = self.field_v0(b"booktitle") else {
+            return Vec::new();
+        };
+        let mut out = b"In ".to_vec();
+        if self.field_v0(b"editor").is_some() {
+            out.extend_from_slice(&self.editors_v0());
+            out.extend_from_slice(b", ");
+        }
+        out.extend_from_slice(&emphasize_v0(Some(booktitle)));
+        out
+    }
+
+    fn thesis_type_v0(&self, default: &[u8]) -> Vec<u8> {
+        self.field_v0(b"type")
+            .map_or_else(|| default.to_vec(), |kind| lower_case_v0(kind, true))
+    }
+
+    fn tr_number_v0(&self) -> Vec<u8> {
+        let kind = self.field_v0(b"type").unwrap_or(b"Technical Report");
+        match self.field_v0(b"number") {
+            Some(number) => [kind, b" ", number].concat(),
+            None => lower_case_v0(kind, true),
+        }
+    }
+
+    /// The output of the style function for the entry's type; unknown types are
+    /// formatted as `misc`.
+    pub(crate) fn format_v0(mut self) -> Vec<u8> {
+        let authors = self.authors_v0();
+        match self.entry.entry_type.as_slice() {
+            b"article" => {
+                self.output.output_v0(authors);
+                self.output.new_block_v0();
+                let title = self.title_v0();
+                self.output.output_v0(title);
+                self.output.new_block_v0();
+                let journal = emphasize_v0(self.field_v0(b"journal"));
+                self.output.output_v0(journal);
+                let vol_num_pages = self.vol_num_pages_v0();
+                self.output.output_v0(vol_num_pages);
+                let date = self.date_v0();
+                self.output.output_v0(date);
+                self.note_v0();
+            }
+            kind @ (b"book" | b"inbook") => {
+                let authors = self.authors_or_editors_v0();
+                self.output.output_v0(authors);
+                self.output.new_block_v0();
+                let title = self.btitle_v0();
+                self.output.output_v0(title);
+                let volume = self.bvolume_v0();
+                self.output.output_v0(volume);
+                if kind == b"inbook" {
+                    let chapter_pages = self.chapter_pages_v0();
+                    self.output.output_v0(chapter_pages);
+                }
+                self.output.new_block_v0();
+                let number_series = self.number_series_v0();
+                self.output.output_v0(number_series);
+                self.publisher_edition_date_v0();
+            }
+            b"booklet" => {
+                self.output.output_v0(authors);
+                self.output.new_block_v0();
+                let title = self.title_v0();
+                self.output.output_v0(title);
+                let howpublished = self.field_v0(b"howpublished");
+                let address = self.field_v0(b"address");
+                self.output.new_block_check_v0(&[howpublished, address]);
+                self.output.output_v0(self.raw_v0(b"howpublished"));
+                self.output.output_v0(self.raw_v0(b"address"));
+                let date = self.date_v0();
+                self.output.output_v0(date);
+                self.note_v0();
+            }
+            b"incollection" => {
+                self.author_title_v0(authors);
+                let booktitle = self.in_ed_booktitle_v0();
+                self.output.output_v0(booktitle);
+                let volume = self.bvolume_v0();
+                self.output.output_v0(volume);
+                let number_series = self.number_series_v0();
+                self.output.output_v0(number_series);
+                let chapter_pages = self.chapter_pages_v0();
+                self.output.output_v0(chapter_pages);
+                self.publisher_edition_date_v0();
+            }
+            b"inproceedings" | b"conference" => {
+                self.author_title_v0(authors);
+                let booktitle = self.in_ed_booktitle_v0();
+                self.output.output_v0(booktitle);
+                let volume = self.bvolume_v0();
+                self.output.output_v0(volume);
+                let number_series = self.number_series_v0();
+                self.output.output_v0(number_series);
+                let pages = self.pages_v0();
+                self.output.output_v0(pages);
+                self.proceedings_tail_v0(true);
+            }
+            b"manual" => self.manual_v0(authors),
+            kind @ (b"mastersthesis" | b"phdthesis") => {
+                self.output.output_v0(authors);
+                self.output.new_block_v0();
+                let title = if kind == b"phdthesis" {
+                    self.btitle_v0()
+                } else {
+                    self.title_v0()
+                };
+                self.output.output_v0(title);
+                self.output.new_block_v0();
+                let default: &[u8] = if kind == b"phdthesis" {
+                    b"PhD thesis"
+                } else {
+                    b"Master's thesis"
+                };
+                let thesis_type = self.thesis_type_v0(default);
+                self.output.nonnull_v0(thesis_type);
+                self.output.output_v0(self.raw_v0(b"school"));
+                self.output.output_v0(self.raw_v0(b"address"));
+                let date = self.date_v0();
+                self.output.output_v0(date);
+                self.note_v0();
+            }
+            b"proceedings" => {
+                let editors = self.editors_v0();
+                if editors.is_empty() {
+                    self.output.output_v0(self.raw_v0(b"organization"));
+                } else {
+                    self.output.nonnull_v0(editors);
+                }
+                self.output.new_block_v0();
+                let title = self.btitle_v0();
+                self.output.output_v0(title);
+                let volume = self.bvolume_v0();
+                self.output.output_v0(volume);
+                let number_series = self.number_series_v0();
+                self.output.output_v0(number_series);
+                self.proceedings_tail_v0(self.field_v0(b"editor").is_some());
+            }
+            b"techreport" => {
+                self.author_title_v0(authors);
+                let number = self.tr_number_v0();
+                self.output.nonnull_v0(number);
+                self.output.output_v0(self.raw_v0(b"institution"));
+                self.output.output_v0(self.raw_v0(b"address"));
+                let date = self.date_v0();
+                self.output.output_v0(date);
+                self.note_v0();
+            }
+            b"unpublished" => {
+                self.author_title_v0(authors);
+                self.output.output_v0(self.raw_v0(b"note"));
+                let date = self.date_v0();
+                self.output.output_v0(date);
+            }
+            _ => {
+                self.output.output_v0(authors);
+                let title = self.field_v0(b"title");
+                let howpublished = self.field_v0(b"howpublished");
+                self.output.new_block_check_v0(&[title, howpublished]);
+                let title = self.title_v0();
+                self.output.output_v0(title);
+                self.output.new_block_check_v0(&[howpublished]);
+                self.output.output_v0(self.raw_v0(b"howpublished"));
+                let date = self.date_v0();
+                self.output.output_v0(date);
+                self.note_v0();
+            }
+        }
+        self.output.finish_v0()
+    }
+
+    /// Authors, then the title, each closing a block.
+    fn author_title_v0(&mut self, authors: Vec<u8>) {
+        self.output.output_v0(authors);
+        self.output.new_block_v0();
+        let title = self.title_v0();
+        self.output.output_v0(title);
+        self.output.new_block_v0();
+    }
+
+    fn note_v0(&mut self) {
+        self.output.new_block_v0();
+        self.output.output_v0(self.raw_v0(b"note"));
+    }
+
+    /// Publisher and address in a sentence of their own, then the edition, the
+    /// date and the note, as books and collections end.
+    fn publisher_edition_date_v0(&mut self) {
+        self.output.new_sentence_v0();
+        self.output.output_v0(self.raw_v0(b"publisher"));
+        self.output.output_v0(self.raw_v0(b"address"));
+        let edition = self.edition_v0();
+        self.output.output_v0(edition);
+        let date = self.date_v0();
+        self.output.output_v0(date);
+        self.note_v0();
+    }
+
+    /// The end of `inproceedings` and `proceedings`: without an address the
+    /// organization and publisher lead to the date; with one the address and date
+    /// come first. `organization` says whether the organization is printed here.
+    fn proceedings_tail_v0(&mut self, organization: bool) {
+        let organization = if organization {
+            self.field_v0(b"organization")
+        } else {
+            None
+        };
+        let publisher = self.field_v0(b"publisher");
+        if self.field_v0(b"address").is_none() {
+            self.output
+                .new_sentence_check_v0(&[organization, publisher]);
+            self.output
+                .output_v0(organization.unwrap_or_default().to_vec());
+            self.output.output_v0(self.raw_v0(b"publisher"));
+            let date = self.date_v0();
+            self.output.output_v0(date);
+        } else {
+            self.output.nonnull_v0(self.raw_v0(b"address"));
+            let date = self.date_v0();
+            self.output.output_v0(date);
+            self.output.new_sentence_v0();
+            self.output
+                .output_v0(organization.unwrap_or_default().to_vec());
+            self.output.output_v0(self.raw_v0(b"publisher"));
+        }
+        self.note_v0();
+    }
+
+    fn manual_v0(&mut self, authors: Vec<u8>) {
+        let organization = self.field_v0(b"organization");
+        let address = self.field_v0(b"address");
+        if authors.is_empty() {
+            if let Some(organization) = organization {
+                self.output.nonnull_v0(organization.to_vec());
+                self.output.output_v0(self.raw_v0(b"address"));
+            }
+        } else {
+            self.output.nonnull_v0(authors.clone());
+        }
+        self.output.new_block_v0();
+        let title = self.btitle_v0();
+        self.output.output_v0(title);
+        if authors.is_empty() {
+            if organization.is_none() {
+                self.output.new_block_check_v0(&[address]);
+                self.output.output_v0(self.raw_v0(b"address"));
+            }
+        } else {
+            self.output.new_block_check_v0(&[organization, address]);
+            self.output.output_v0(self.raw_v0(b"organization"));
+            self.output.output_v0(self.raw_v0(b"address"));
+        }
+        let edition = self.edition_v0();
+        self.output.output_v0(edition);
+        let date = self.date_v0();
+        self.output.output_v0(date);
+        self.note_v0();
+    }
+}
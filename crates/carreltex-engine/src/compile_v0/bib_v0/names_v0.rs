@@ -0,0 +1,268 @@
+use super::text_v0::text_prefix_v0;
+
+/// A word of a name and whether a hyphen, rather than a space or tie, follows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct NameWordV0 {
+    text: Vec<u8>,
+    hyphen_after: bool,
+}
+
+/// A name split into BibTeX's First, von, Last and Jr parts.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct BibNameV0 {
+    first: Vec<NameWordV0>,
+    von: Vec<NameWordV0>,
+    last: Vec<NameWordV0>,
+    jr: Vec<NameWordV0>,
+}
+
+/// `value` split at the ` and `s outside braces, as `num.names$` counts them.
+pub(crate) fn split_names_v0(value: &[u8]) -> Vec<&[u8]> {
+    let mut names = Vec::<&[u8]>::new();
+    let mut depth = 0usize;
+    let mut start = 0usize;
+    let mut index = 0usize;
+    while index < value.len() {
+        match value[index] {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            byte if depth == 0
+                && byte.is_ascii_whitespace()
+                && value
+                    .get(index + 1..index + 4)
+                    .is_some_and(|word| word.eq_ignore_ascii_case(b"and"))
+                && value
+                    .get(index + 4)
+                    .is_some_and(|byte| byte.is_ascii_whitespace()) =>
+            {
+                names.push(value[start..index].trim_ascii());
+                index += 5;
+                start = index;
+                continue;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    names.push(value[start..].trim_ascii());
+    names.retain(|name| !name.is_empty());
+    names
+}
+
+/// Words of one comma-separated part, split at white space, ties and hyphens
+/// outside braces.
+fn words_v0(part: &[u8]) -> Vec<NameWordV0> {
+    let mut words = Vec::<NameWordV0>::new();
+    let mut depth = 0usize;
+    let mut word = Vec::<u8>::new();
+    for byte in part {
+        match byte {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        let separator = depth == 0 && (byte.is_ascii_whitespace() || matches!(byte, b'~' | b'-'));
+        if !separator {
+            word.push(*byte);
+            continue;
+        }
+        if !word.is_empty() {
+            words.push(NameWordV0 {
+                text: std::mem::take(&mut word),
+                hyphen_after: *byte == b'-',
+            });
+        } else if *byte == b'-' {
+            if let Some(previous) = words.last_mut() {
+                previous.hyphen_after = true;
+            }
+        }
+    }
+    if !word.is_empty() {
+        words.push(NameWordV0 {
+            text: word,
+            hyphen_after: false,
+        });
+    }
+    words
+}
+
+/// Whether a word starts with a lower-case letter, so belongs to the von part:
+/// its first letter outside braces or inside a leading special character; words
+/// starting with any other brace group have no case.
+fn is_von_word_v0(word: &[u8]) -> bool {
+    if word.starts_with(b"{\\") {
+        let mut index = 2;
+        while word
+            .get(index)
+            .is_some_and(|byte| byte.is_ascii_alphabetic())
+        {
+            index += 1;
+        }
+        return word[index..]
+            .iter()
+            .find(|byte| byte.is_ascii_alphabetic())
+            .is_some_and(|byte| byte.is_ascii_lowercase());
+    }
+    let mut depth = 0usize;
+    for byte in word {
+        match byte {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            _ if depth == 0 && byte.is_ascii_alphabetic() => return byte.is_ascii_lowercase(),
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Splits one name written as `First von Last`, `von Last, First` or
+/// `von Last, Jr, First`.
+pub(crate) fn parse_name_v0(name: &[u8]) -> BibNameV0 {
+    let mut parts = Vec::<&[u8]>::new();
+    let mut depth = 0usize;
+    let mut start = 0usize;
+    for (index, byte) in name.iter().enumerate() {
+        match byte {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            b',' if depth == 0 => {
+                parts.push(&name[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&name[start..]);
+    let mut words = words_v0(parts[0]);
+    let mut result = BibNameV0::default();
+    if parts.len() == 1 {
+        let Some(last_word) = words.pop() else {
+            return result;
+        };
+        let von_start = words.iter().position(|word| is_von_word_v0(&word.text));
+        let von_last = words.iter().rposition(|word| is_von_word_v0(&word.text));
+        match (von_start, von_last) {
+            (Some(von_start), Some(von_last)) => {
+                result.last = words.split_off(von_last + 1);
+                result.von = words.split_off(von_start);
+                result.first = words;
+            }
+            _ => result.first = words,
+        }
+        result.last.push(last_word);
+        return result;
+    }
+    let von_end = words[..words.len().saturating_sub(1)]
+        .iter()
+        .rposition(|word| is_von_word_v0(&word.text))
+        .map_or(0, |index| index + 1);
+    result.last = words.split_off(von_end);
+    result.von = words;
+    result.first = words_v0(parts[parts.len() - 1]);
+    if parts.len() > 2 {
+        result.jr = words_v0(parts[1]);
+    }
+    result
+}
+
+/// Words joined as written, hyphens kept and other separators made spaces.
+fn join_words_v0(words: &[NameWordV0]) -> Vec<u8> {
+    let mut out = Vec::<u8>::new();
+    for (index, word) in words.iter().enumerate() {
+        out.extend_from_slice(&word.text);
+        if index + 1 < words.len() {
+            out.push(if word.hyphen_after { b'-' } else { b' ' });
+        }
+    }
+    out
+}
+
+/// First character of a word: a leading brace group whole, else one character.
+fn initial_v0(word: &[u8]) -> Vec<u8> {
+    let letter = word
+        .iter()
+        .position(|byte| *byte == b'{' || byte.is_ascii_alphanumeric());
+    match letter {
+        Some(index) if word[index] == b'{' => text_prefix_v0(&word[index..], 1),
+        Some(index) => vec![word[index]],
+        None => Vec::new(),
+    }
+}
+
+impl BibNameV0 {
+    /// `{ff~}{vv~}{ll}{, jj}`, or `{f.~}{vv~}{ll}{, jj}` with `initials`; ties are
+    /// written as spaces.
+    pub(crate) fn format_v0(&self, initials: bool) -> Vec<u8> {
+        let mut parts = Vec::<Vec<u8>>::new();
+        if initials && !self.first.is_empty() {
+            let mut first = Vec::<u8>::new();
+            for (index, word) in self.first.iter().enumerate() {
+                first.extend_from_slice(&initial_v0(&word.text));
+                first.push(b'.');
+                if index + 1 < self.first.len() {
+                    first.push(if word.hyphen_after { b'-' } else { b' ' });
+                }
+            }
+            parts.push(first);
+        } else if !self.first.is_empty() {
+            parts.push(join_words_v0(&self.first));
+        }
+        for part in [&self.von, &self.last] {
+            if !part.is_empty() {
+                parts.push(join_words_v0(part));
+            }
+        }
+        let mut out = parts.join(&b' ');
+        if !self.jr.is_empty() {
+            out.extend_from_slice(b", ");
+            out.extend_from_slice(&join_words_v0(&self.jr));
+        }
+        out
+    }
+
+    /// `{vv{ } }{ll{ }}{  ff{ }}{  jj{ }}`, the form the styles sort names by.
+    pub(crate) fn sort_form_v0(&self) -> Vec<u8> {
+        let join = |words: &[NameWordV0]| {
+            words
+                .iter()
+                .map(|word| word.text.as_slice())
+                .collect::<Vec<_>>()
+                .join(&b' ')
+        };
+        let mut out = Vec::<u8>::new();
+        if !self.von.is_empty() {
+            out.extend_from_slice(&join(&self.von));
+            out.push(b' ');
+        }
+        out.extend_from_slice(&join(&self.last));
+        for part in [&self.first, &self.jr] {
+            if !part.is_empty() {
+                out.extend_from_slice(b"  ");
+                out.extend_from_slice(&join(part));
+            }
+        }
+        out
+    }
+
+    /// `{v{}}{l{}}`: the initials of the von and Last words run together.
+    pub(crate) fn label_initials_v0(&self) -> Vec<u8> {
+        self.von
+            .iter()
+            .chain(&self.last)
+            .flat_map(|word| initial_v0(&word.text))
+            .collect()
+    }
+
+    /// `{ll}`: the Last part.
+    pub(crate) fn last_v0(&self) -> Vec<u8> {
+        join_words_v0(&self.last)
+    }
+
+    /// Whether the name is `others`, which the styles print as "et al.".
+    pub(crate) fn is_others_v0(&self) -> bool {
+        self.first.is_empty()
+            && self.von.is_empty()
+            && self.jr.is_empty()
+            && matches!(self.last.as_slice(), [word] if word.text == b"others")
+    }
+}
@@ -0,0 +1,159 @@
+/// Control words `purify$` keeps the letters of, as it does for foreign characters.
+const FOREIGN_LETTERS_V0: &[&[u8]] = &[
+    b"oe", b"OE", b"ae", b"AE", b"aa", b"AA", b"o", b"O", b"l", b"L", b"ss", b"i", b"j",
+];
+
+/// Whether the group opening at `index` is a special character such as `{\"o}`:
+/// a brace at depth 0 followed by a backslash.
+fn is_special_char_v0(text: &[u8], index: usize, depth: usize) -> bool {
+    depth == 0 && text.get(index + 1) == Some(&b'\\')
+}
+
+/// Index just past the brace group opening at `index`, or the end of `text`.
+fn group_end_v0(text: &[u8], index: usize) -> usize {
+    let mut depth = 0usize;
+    for (at, byte) in text.iter().enumerate().skip(index) {
+        match byte {
+            b'{' => depth += 1,
+            b'}' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return at + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// `change.case$` with `"t"` (`title` true) or `"l"`: letters at brace depth 0 go
+/// to lower case, except, for titles, the first character and the first one after
+/// a colon and white space. Braced text and special characters stay as they are.
+pub(crate) fn lower_case_v0(text: &[u8], title: bool) -> Vec<u8> {
+    let mut out = Vec::<u8>::with_capacity(text.len());
+    let mut depth = 0usize;
+    let mut keep_next = title;
+    let mut after_colon = false;
+    let mut index = 0usize;
+    while index < text.len() {
+        let byte = text[index];
+        if byte == b'{' && is_special_char_v0(text, index, depth) {
+            let end = group_end_v0(text, index);
+            out.extend_from_slice(&text[index..end]);
+            index = end;
+            keep_next = false;
+            after_colon = false;
+            continue;
+        }
+        match byte {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if byte.is_ascii_whitespace() {
+            keep_next |= title && after_colon;
+            out.push(byte);
+        } else {
+            let keep = keep_next || depth > 0 || byte == b'}';
+            out.push(if keep {
+                byte
+            } else {
+                byte.to_ascii_lowercase()
+            });
+            keep_next = false;
+            after_colon = depth == 0 && byte == b':';
+        }
+        index += 1;
+    }
+    out
+}
+
+/// `purify$`: letters and digits, with white space, hyphens and ties as spaces;
+/// special characters keep the letters of their argument, and of the control word
+/// for foreign characters such as `{\ss}`.
+pub(crate) fn purify_v0(text: &[u8]) -> Vec<u8> {
+    let mut out = Vec::<u8>::with_capacity(text.len());
+    let mut depth = 0usize;
+    let mut index = 0usize;
+    while index < text.len() {
+        let byte = text[index];
+        if byte == b'{' && is_special_char_v0(text, index, depth) {
+            let end = group_end_v0(text, index);
+            let mut at = index + 1;
+            while at < end {
+                if text[at] == b'\\' {
+                    let start = at + 1;
+                    at = start;
+                    while at < end && text[at].is_ascii_alphabetic() {
+                        at += 1;
+                    }
+                    if at == start {
+                        at += 1;
+                    } else if FOREIGN_LETTERS_V0.contains(&&text[start..at]) {
+                        out.extend_from_slice(&text[start..at]);
+                    }
+                    continue;
+                }
+                if text[at].is_ascii_alphanumeric() {
+                    out.push(text[at]);
+                }
+                at += 1;
+            }
+            index = end;
+            continue;
+        }
+        match byte {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            b'-' | b'~' => out.push(b' '),
+            _ if byte.is_ascii_whitespace() => out.push(b' '),
+            _ if byte.is_ascii_alphanumeric() => out.push(byte),
+            _ => {}
+        }
+        index += 1;
+    }
+    out
+}
+
+/// `purify$` then `change.case$` with `"l"`, as the styles' `sortify` does.
+pub(crate) fn sortify_v0(text: &[u8]) -> Vec<u8> {
+    purify_v0(text).to_ascii_lowercase()
+}
+
+/// `text.prefix$`: the first `count` characters, a special character counting as
+/// one and braces as none, with the braces left open closed.
+pub(crate) fn text_prefix_v0(text: &[u8], count: usize) -> Vec<u8> {
+    let mut out = Vec::<u8>::new();
+    let mut depth = 0usize;
+    let mut taken = 0usize;
+    let mut index = 0usize;
+    while index < text.len() && taken < count {
+        let byte = text[index];
+        if byte == b'{' && is_special_char_v0(text, index, depth) {
+            let end = group_end_v0(text, index);
+            out.extend_from_slice(&text[index..end]);
+            index = end;
+            taken += 1;
+            continue;
+        }
+        match byte {
+            b'{' => depth += 1,
+            b'}' => depth = depth.saturating_sub(1),
+            _ => taken += 1,
+        }
+        out.push(byte);
+        index += 1;
+    }
+    out.extend(std::iter::repeat_n(b'}', depth));
+    out
+}
+
+/// `add.period$`: a period after text not ending, braces aside, in `.`, `?` or `!`.
+pub(crate) fn add_period_v0(mut text: Vec<u8>) -> Vec<u8> {
+    let last = text.iter().rev().find(|byte| **byte != b'}');
+    if last.is_some_and(|byte| !matches!(byte, b'.' | b'?' | b'!')) {
+        text.push(b'.');
+    }
+    text
+}
@@ -0,0 +1,192 @@
+use super::compile_request_v0;
+use super::test_text_v0::{read_writer_dvi_v0, spaced_text_v0};
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+
+const REFS_BIB_V0: &[u8] = br#"Comments outside entries are ignored.
+@string{tug = "TeX Users Group"}
+@comment{Entries typed by hand}
+@article{knuth84,
+  author = {Donald E. Knuth},
+  title = {Literate Programming},
+  journal = {The Computer Journal},
+  volume = 27, number = 2, pages = {97-111},
+  year = 1984, month = may,
+}
+@book(lamport94,
+  AUTHOR = "Leslie Lamport",
+  Title = "{LaTeX}: A Document Preparation System",
+  publisher = {Addison-Wesley}, edition = "Second",
+  year = {1994}
+)
+@inproceedings{beebe,
+  author = {Nelson H. F. Beebe and Jean-Pierre Dupont and others},
+  title = {Bibliography Prettyprinting},
+  booktitle = tug # " Annual Meeting",
+  year = 1993, pages = 7,
+}
+@misc{unused, title = {Never cited}, year = 2000}
+"#;
+
+fn compile_v0(preamble: &[u8], body: &[u8], bib: &[u8]) -> CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n".to_vec();
+    main.extend_from_slice(preamble);
+    main.extend_from_slice(b"\\begin{document}\n");
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    assert!(mount.add_file(b"refs.bib", bib).is_ok());
+    let request = CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    };
+    compile_request_v0(&mut mount, &request)
+}
+
+/// Glyphs of the first page, with a space for each space or gap.
+fn page_text_v0(result: &CompileResultV0) -> String {
+    assert_eq!(
+        result.status,
+        CompileStatus::Ok,
+        "{}",
+        String::from_utf8_lossy(&result.log_bytes)
+    );
+    let document = read_writer_dvi_v0(&result.main_xdv_bytes);
+    spaced_text_v0(&document.pages[0].glyphs)
+}
+
+fn cite_body_v0(style: &str) -> Vec<u8> {
+    format!(
+        "See \\cite{{lamport94}} and \\cite[p. 5]{{knuth84, beebe}}.\n\
+\\bibliographystyle{{{style}}}\n\\bibliography{{refs}}"
+    )
+    .into_bytes()
+}
+
+#[test]
+fn plain_style_writes_a_sorted_bbl_and_resolves_citations() {
+    let result = compile_v0(b"", &cite_body_v0("plain"), REFS_BIB_V0);
+    assert_eq!(
+        page_text_v0(&result),
+        " See [3] and [2, 1, p. 5].References\
+[1] Nelson H. F. Beebe, Jean-Pierre Dupont, et al. Bibliography prettyprinting.\
+In TeX Users Group Annual Meeting, page 7, 1993.\
+[2] Donald E. Knuth. Literate programming. The Computer Journal, 27(2):97--111,\
+May 1984.\
+[3] Leslie Lamport. LaTeX: A Document Preparation System. Addison-Wesley, second\
+edition, 1994."
+    );
+    assert!(result.log_bytes.is_empty());
+    assert_eq!(
+        String::from_utf8_lossy(&result.main_bbl_bytes),
+        "\\begin{thebibliography}{1}\n\
+\n\\bibitem{beebe}\n\
+Nelson H. F. Beebe, Jean-Pierre Dupont, et al.\n\
+\\newblock Bibliography prettyprinting.\n\
+\\newblock In {\\em TeX Users Group Annual Meeting}, page 7, 1993.\n\
+\n\\bibitem{knuth84}\n\
+Donald E. Knuth.\n\
+\\newblock Literate programming.\n\
+\\newblock {\\em The Computer Journal}, 27(2):97--111, May 1984.\n\
+\n\\bibitem{lamport94}\n\
+Leslie Lamport.\n\
+\\newblock {\\em {LaTeX}: A Document Preparation System}.\n\
+\\newblock Addison-Wesley, second edition, 1994.\n\
+\n\\end{thebibliography}\n"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&result.main_aux_bytes),
+        "\\relax\n\\citation{lamport94}\n\\citation{knuth84}\n\\citation{beebe}\n\
+\\bibstyle{plain}\n\\bibdata{refs}\n\
+\\bibcite{beebe}{1}\n\\bibcite{knuth84}{2}\n\\bibcite{lamport94}{3}\n"
+    );
+}
+
+#[test]
+fn unsrt_alpha_and_abbrv_order_label_and_abbreviate_entries() {
+    let unsrt = compile_v0(b"", &cite_body_v0("unsrt"), REFS_BIB_V0);
+    assert!(page_text_v0(&unsrt).starts_with(" See [1] and [2, 3, p. 5].References[1] Leslie"));
+    let alpha = compile_v0(b"", &cite_body_v0("alpha"), REFS_BIB_V0);
+    assert!(page_text_v0(&alpha).starts_with(" See [Lam94] and [Knu84, BD+93, p. 5]."));
+    let bbl = String::from_utf8_lossy(&alpha.main_bbl_bytes);
+    assert!(bbl.starts_with("\\begin{thebibliography}{Lam94}\n\n\\bibitem[BD+93]{beebe}\n"));
+    assert!(bbl.contains("\n\\bibitem[Knu84]{knuth84}\n"));
+    let abbrv = compile_v0(b"", &cite_body_v0("abbrv"), REFS_BIB_V0);
+    let bbl = String::from_utf8_lossy(&abbrv.main_bbl_bytes);
+    assert!(bbl.contains("\\bibitem{beebe}\nN. H. F. Beebe, J.-P. Dupont, et al.\n"));
+    assert!(bbl.contains("\\bibitem{lamport94}\nL. Lamport.\n"));
+}
+
+#[test]
+fn undefined_citations_warn_and_nocite_star_lists_every_entry() {
+    let result = compile_v0(
+        b"\\bibliographystyle{plain}\n",
+        b"\\cite{nokey}\\nocite{*}\\bibliography{refs.bib}",
+        b"@string{acme = {Acme}}\n@techreport{berg, author = {van der Berg, Jr., Piet and \
+Anna Smith}, title = {A Very Long Title That Goes On And On Until The Line Has To Be \
+Broken Somewhere}, institution = acme # {, Inc.}, year = 2001}",
+    );
+    assert_eq!(
+        page_text_v0(&result),
+        " [?]References[1] Piet van der Berg, Jr. and Anna Smith. A very long title that \
+goes on and onuntil the line has to be broken somewhere. Technical report, Acme, Inc.,\
+2001."
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&result.log_bytes),
+        "LaTeX Warning: Citation `nokey' on page 1 undefined.\n\
+LaTeX Warning: There were undefined references."
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&result.main_bbl_bytes),
+        "\\begin{thebibliography}{1}\n\
+\n\\bibitem{berg}\n\
+Piet van der Berg, Jr. and Anna Smith.\n\
+\\newblock A very long title that goes on and on until the line has to be broken\n  somewhere.\n\
+\\newblock Technical report, Acme, Inc., 2001.\n\
+\n\\end{thebibliography}\n"
+    );
+    assert!(String::from_utf8_lossy(&result.main_aux_bytes)
+        .starts_with("\\relax\n\\citation{nokey}\n\\citation{*}\n"));
+}
+
+#[test]
+fn bibliographies_outside_the_subset_fail_closed() {
+    for (preamble, bib, reason) in [
+        (
+            b"\\bibliographystyle{plain}\n".as_slice(),
+            b"@article{a, title = {Open".as_slice(),
+            b"INVALID_INPUT: bib_syntax_invalid".as_slice(),
+        ),
+        (
+            b"\\bibliographystyle{plain}\n",
+            b"@article{a, journal = cacm}",
+            b"INVALID_INPUT: bib_string_undefined",
+        ),
+        (b"", REFS_BIB_V0, b"INVALID_INPUT: bib_style_missing"),
+    ] {
+        let result = compile_v0(preamble, b"\\cite{a}\\bibliography{refs}", bib);
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(result.log_bytes, reason);
+        assert!(result.main_bbl_bytes.is_empty());
+    }
+    let result = compile_v0(
+        b"\\bibliographystyle{plain}\n",
+        b"\\bibliography{refs,more}",
+        REFS_BIB_V0,
+    );
+    assert_eq!(result.log_bytes, b"INVALID_INPUT: bib_file_missing");
+    for body in [
+        b"\\bibliographystyle{ieeetr}\\bibliography{refs}".as_slice(),
+        b"\\bibliographystyle{ieeetr}",
+        b"\\bibliographystyle{plain}\\bibliography{refs}\\bibliography{refs}",
+    ] {
+        let result = compile_v0(b"", body, REFS_BIB_V0);
+        assert_eq!(result.status, CompileStatus::NotImplemented);
+    }
+}
@@ -0,0 +1,333 @@
+use super::compile_request_v0;
+use super::test_text_v0::{first_baseline_v_v0, read_writer_dvi_v0, spaced_text_v0};
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::{validate_dvi_v2_text_page_v0, DviDocumentV0, DviFontKindV0};
+
+const LINE_SP: i32 = 786_432;
+const TEXT_WIDTH_SP: i32 = 80 * 65_536;
+
+fn request_v0() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_document_v0(class: &[u8], preamble: &[u8], body: &[u8]) -> CompileResultV0 {
+    let main = [
+        b"\\documentclass".as_slice(),
+        class,
+        b"\n",
+        preamble,
+        b"\n\\begin{document}\n",
+        body,
+        b"\n\\end{document}\n",
+    ]
+    .concat();
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, &request_v0())
+}
+
+fn document_v0(result: &CompileResultV0) -> DviDocumentV0 {
+    assert_eq!(
+        result.status,
+        CompileStatus::Ok,
+        "{}",
+        String::from_utf8_lossy(&result.log_bytes)
+    );
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    read_writer_dvi_v0(&result.main_xdv_bytes)
+}
+
+/// Text of page `page` regrouped into lines by their baseline, down to `lines`.
+fn page_lines_v0(document: &DviDocumentV0, page: usize, lines: usize) -> Vec<String> {
+    (0..lines)
+        .map(|line| {
+            spaced_text_v0(
+                document.pages[page]
+                    .glyphs
+                    .iter()
+                    .filter(|glyph| glyph.v == first_baseline_v_v0() + line as i32 * LINE_SP),
+            )
+        })
+        .collect()
+}
+
+/// Text of the lowest line of page `page`, where `plain` sets the page number.
+fn foot_v0(document: &DviDocumentV0, page: usize) -> String {
+    let glyphs = &document.pages[page].glyphs;
+    let foot_v = glyphs.iter().map(|glyph| glyph.v).max().expect("glyphs");
+    spaced_text_v0(glyphs.iter().filter(|glyph| glyph.v == foot_v))
+}
+
+#[test]
+fn report_chapters_start_pages_and_number_what_they_hold() {
+    let result = compile_document_v0(
+        b"{report}",
+        b"\\pagestyle{empty}",
+        b"\\chapter{Intro}Text\\section{One}\\label{s}\\begin{equation}x\\label{a}\\end{equation}\
+\\chapter*{Aside}\\chapter{Next}\\section{Two}\\begin{equation}y\\label{b}\\end{equation}\
+See \\ref{s}, \\eqref{a} and \\eqref{b}.",
+    );
+    let document = document_v0(&result);
+    assert_eq!(document.pages.len(), 3);
+    assert_eq!(
+        page_lines_v0(&document, 0, 6),
+        ["Chapter 1", "", "Intro", "", "Text", ""]
+    );
+    assert_eq!(page_lines_v0(&document, 0, 8)[6], "1.1 One");
+    assert_eq!(page_lines_v0(&document, 1, 3), ["Aside", "", ""]);
+    assert_eq!(
+        page_lines_v0(&document, 2, 7),
+        ["Chapter 2", "", "Next", "", "2.1 Two", "", "y (2.1)"]
+    );
+    let text = page_lines_v0(&document, 2, 12).concat();
+    assert!(text.contains("See 1.1, (1.1) and (2.1)."), "{text}");
+
+    // Chapter pages are `plain` whatever the page style.
+    assert_eq!(foot_v0(&document, 2), "3");
+}
+
+#[test]
+fn report_contents_list_chapters_and_take_a_chapter_head() {
+    let result = compile_document_v0(
+        b"{report}",
+        b"",
+        b"\\tableofcontents\\chapter{Intro}\\section{One}\\subsection{Deep}\\subsubsection{Deeper}",
+    );
+    let document = document_v0(&result);
+    assert_eq!(document.pages.len(), 2);
+    let contents = page_lines_v0(&document, 0, 6);
+    assert_eq!(contents[..3], ["Contents", "", "1 Intro 2"]);
+    assert!(contents[3].starts_with("1.1One . . ."), "{contents:?}");
+    assert!(contents[4].starts_with("1.1.1Deep . . ."), "{contents:?}");
+    assert_eq!(contents[5], "", "tocdepth 2");
+    // `secnumdepth` 2 leaves `\subsubsection` unnumbered.
+    assert!(page_lines_v0(&document, 1, 12).contains(&"Deeper".to_owned()));
+}
+
+#[test]
+fn book_front_matter_is_roman_and_its_chapters_unnumbered() {
+    let result = compile_document_v0(
+        b"{book}",
+        b"\\pagestyle{headings}",
+        b"\\frontmatter\\chapter{Preface}P\\mainmatter\\chapter{Start}S\\pagebreak T\
+\\backmatter\\chapter{Index}I",
+    );
+    let document = document_v0(&result);
+    let counts = document
+        .pages
+        .iter()
+        .map(|page| page.counts[0])
+        .collect::<Vec<_>>();
+    assert_eq!(counts, [1, 1, 2, 3]);
+    assert_eq!(page_lines_v0(&document, 0, 3), ["Preface", "", "P"]);
+    assert_eq!(page_lines_v0(&document, 1, 3), ["Chapter 1", "", "Start"]);
+    assert_eq!(page_lines_v0(&document, 3, 3), ["Index", "", "I "]);
+    // The `headings` mark of a page within a chapter names it.
+    let head = spaced_text_v0(
+        document.pages[2]
+            .glyphs
+            .iter()
+            .filter(|glyph| glyph.v < first_baseline_v_v0()),
+    );
+    assert_eq!(head, "CHAPTER 1. START 2");
+    assert_eq!(foot_v0(&document, 0), "i");
+}
+
+#[test]
+fn class_options_set_the_base_size_and_the_paper() {
+    let result = compile_document_v0(
+        b"[11pt,a4paper,landscape]{article}",
+        b"\\pagestyle{empty}",
+        b"\\section{A}a",
+    );
+    let document = document_v0(&result);
+    assert_eq!(
+        String::from_utf8_lossy(&document.pages[0].specials[0].bytes),
+        "papersize=845.04684pt,597.50787pt"
+    );
+    let fonts = document.pages[0]
+        .glyphs
+        .iter()
+        .filter(|glyph| glyph.code != u32::from(b' '))
+        .map(|glyph| {
+            let font = document.font_v0(glyph.font_num).expect("defined font");
+            let DviFontKindV0::Tfm(definition) = &font.kind else {
+                panic!("unexpected font {font:?}");
+            };
+            (
+                glyph.code as u8,
+                String::from_utf8_lossy(&definition.name).into_owned(),
+                definition.scale,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fonts,
+        [
+            (b'1', "carreltex-v0-rm-bx-n".to_owned(), 943_718),
+            (b'A', "carreltex-v0-rm-bx-n".to_owned(), 943_718),
+            (b'a', "carreltex-v0-rm-m-n".to_owned(), 717_619),
+        ]
+    );
+}
+
+#[test]
+fn letters_set_the_address_recipient_and_signature() {
+    let result = compile_document_v0(
+        b"{letter}",
+        b"\\address{1 Main St \\\\ Springfield}\\signature{A. Writer}\\date{1 May}",
+        b"\\begin{letter}{B. Reader \\\\ 2 Side St}\\opening{Dear B,}Thanks.\\closing{Yours,}\
+\\end{letter}",
+    );
+    let document = document_v0(&result);
+    assert_eq!(document.pages.len(), 1);
+    assert_eq!(
+        page_lines_v0(&document, 0, 16),
+        [
+            "1 Main St",
+            "Springfield",
+            "",
+            "1 May",
+            "",
+            "B. Reader",
+            "2 Side St",
+            "",
+            "Dear B,",
+            "",
+            "Thanks.",
+            "",
+            "Yours,",
+            "",
+            "",
+            "",
+        ]
+    );
+    assert_eq!(page_lines_v0(&document, 0, 17)[16], "A. Writer");
+    let h_of = |line: usize| {
+        document.pages[0]
+            .glyphs
+            .iter()
+            .find(|glyph| glyph.v == first_baseline_v_v0() + line as i32 * LINE_SP)
+            .map(|glyph| glyph.h)
+    };
+    // The address block keeps an em per glyph of its widest line from the right;
+    // the closing and signature start from the middle.
+    assert_eq!(h_of(0), Some(TEXT_WIDTH_SP - 11 * 65_536));
+    assert_eq!(h_of(5), Some(0));
+    assert_eq!(h_of(12), Some(TEXT_WIDTH_SP / 2));
+    assert_eq!(h_of(16), Some(TEXT_WIDTH_SP / 2));
+    // The first page of a letter has no page number.
+    assert!(document.pages[0]
+        .glyphs
+        .iter()
+        .all(|glyph| glyph.v < first_baseline_v_v0() + 17 * LINE_SP));
+}
+
+#[test]
+fn beamer_frames_are_unnumbered_slides() {
+    let result = compile_document_v0(
+        b"{beamer}",
+        b"",
+        b"\\begin{frame}{First}One\\end{frame}\\begin{frame}\\frametitle{Second}Two\\end{frame}",
+    );
+    let document = document_v0(&result);
+    assert_eq!(document.pages.len(), 2);
+    assert_eq!(
+        String::from_utf8_lossy(&document.pages[0].specials[0].bytes),
+        "papersize=364.19536pt,273.14662pt"
+    );
+    let texts = document
+        .pages
+        .iter()
+        .map(|page| spaced_text_v0(&page.glyphs))
+        .collect::<Vec<_>>();
+    assert_eq!(texts, ["FirstOne", "SecondTwo"]);
+    let title = &document.pages[0].glyphs[0];
+    let body = &document.pages[0].glyphs[5];
+    assert_eq!(body.v - title.v, 2 * LINE_SP);
+}
+
+#[test]
+fn unknown_classes_fail_closed_with_their_name() {
+    let result = compile_document_v0(b"[11pt]{foo}", b"", b"x");
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+    assert_eq!(result.log_bytes, b"INVALID_INPUT: class_not_available:foo");
+}
+
+#[test]
+fn class_options_outside_the_subset_fail_closed_with_their_name() {
+    for (class, option) in [
+        (b"[twocolumn]{article}".as_slice(), b"twocolumn".as_slice()),
+        (b"[11pt,twoside]{report}", b"twoside"),
+        (b"[draft,foo]{article}", b"draft"),
+        (b"[openright]{book}", b"openright"),
+        (b"[a5paper]{letter}", b"a5paper"),
+        (b"[letterpaper]{beamer}", b"letterpaper"),
+        (b"[12pt,handout]{beamer}", b"handout"),
+    ] {
+        let result = compile_document_v0(class, b"", b"x");
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(
+            result.log_bytes,
+            [b"INVALID_INPUT: class_option_not_supported:", option].concat()
+        );
+    }
+}
+
+#[test]
+fn options_the_class_does_not_declare_are_reported_unused() {
+    let result = compile_document_v0(b"[foo,11pt,openany,14pt]{article}", b"", b"x\\ref{a}");
+    document_v0(&result);
+    assert_eq!(
+        result.log_bytes,
+        b"LaTeX Warning: Unused global option(s): [foo,openany,14pt].\n\
+LaTeX Warning: Reference `a' on page 1 undefined.\n\
+LaTeX Warning: There were undefined references."
+    );
+
+    // Report declares `openany`, letter does not declare `onecolumn`.
+    let result = compile_document_v0(b"[openany]{report}", b"", b"x");
+    document_v0(&result);
+    assert_eq!(result.log_bytes, b"");
+    let result = compile_document_v0(b"[onecolumn]{letter}", b"", b"x");
+    document_v0(&result);
+    assert_eq!(
+        result.log_bytes,
+        b"LaTeX Warning: Unused global option(s): [onecolumn]."
+    );
+}
+
+#[test]
+fn class_features_outside_the_subset_fail_closed() {
+    for (class, body) in [
+        (b"{article}".as_slice(), b"\\chapter{A}".as_slice()),
+        (b"{report}", b"\\frontmatter"),
+        (b"{letter}", b"\\section{A}"),
+        (b"{letter}", b"\\opening{Dear B,}"),
+        (
+            b"{letter}",
+            b"\\begin{letter}{B}\\begin{letter}{C}\\end{letter}\\end{letter}",
+        ),
+        (b"{letter}", b"\\begin{letter}{B}{x\\end{letter}}"),
+        (b"{beamer}", b"\\begin{frame}[fragile]x\\end{frame}"),
+        (b"{beamer}", b"\\begin{frame}x"),
+        (b"{report}", b"\\footnote{\\chapter{A}}"),
+    ] {
+        let result = compile_document_v0(class, b"", body);
+        assert_eq!(
+            result.status,
+            CompileStatus::NotImplemented,
+            "{} {}",
+            String::from_utf8_lossy(class),
+            String::from_utf8_lossy(body)
+        );
+    }
+}
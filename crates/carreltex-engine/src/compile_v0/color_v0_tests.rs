@@ -0,0 +1,157 @@
+use super::compile_request_v0;
+use carreltex_core::{CompileRequestV0, CompileStatus, Mount};
+use carreltex_xdv::{read_dvi_v0, validate_dvi_color_stack_v0, validate_dvi_v2_text_page_v0};
+
+fn valid_request() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_v0(preamble: &[u8], body: &[u8]) -> carreltex_core::CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n".to_vec();
+    main.extend_from_slice(preamble);
+    main.extend_from_slice(b"\\begin{document}\n");
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, &valid_request())
+}
+
+/// `(h, payload)` of every special on the first page after the `papersize` special.
+fn colored_specials_v0(result: &carreltex_core::CompileResultV0) -> Vec<(i32, String)> {
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    let document = read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read");
+    assert!(validate_dvi_color_stack_v0(&document));
+    document.pages[0]
+        .specials
+        .iter()
+        .skip(1)
+        .map(|special| {
+            (
+                special.h,
+                String::from_utf8(special.bytes.clone()).expect("ascii"),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn textcolor_wraps_its_argument_in_push_and_pop() {
+    let result = compile_v0(b"\\usepackage{xcolor}\n", b"A\\textcolor{red}{BC}D");
+    assert_eq!(
+        colored_specials_v0(&result),
+        vec![
+            (98_304, "color push rgb 1 0 0".to_owned()),
+            (229_376, "color pop".to_owned()),
+        ]
+    );
+    assert!(result.main_pdf_bytes.starts_with(b"%PDF-1.7"));
+}
+
+#[test]
+fn color_models_and_definecolor_resolve() {
+    let result = compile_v0(
+        b"\\usepackage{color}\n\\definecolor{accent}{RGB}{255,128,0}\n\\definecolor{ink}{cmyk}{0,.5,1,0.25}\n",
+        b"\\textcolor{accent}{a}\\textcolor[gray]{0.75}{b}\\textcolor[HTML]{00FF80}{c}\\textcolor{ink}{d}\\textcolor{blue!40}{e}\\textcolor{red!25!ink}{f}",
+    );
+    let pushes = colored_specials_v0(&result)
+        .into_iter()
+        .map(|(_, payload)| payload)
+        .filter(|payload| payload != "color pop")
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pushes,
+        vec![
+            "color push rgb 1 0.502 0",
+            "color push gray 0.75",
+            "color push rgb 0 1 0.502",
+            "color push cmyk 0 0.5 1 0.25",
+            "color push rgb 0.6 0.6 1",
+            "color push rgb 0.8125 0.2813 0",
+        ]
+    );
+}
+
+#[test]
+fn color_switch_lasts_until_its_group_ends() {
+    let result = compile_v0(
+        b"\\usepackage{xcolor}\n",
+        b"{\\color{blue}A\\color[rgb]{0,0.5,0}B}C\\color{red}D",
+    );
+    assert_eq!(
+        colored_specials_v0(&result),
+        vec![
+            (32_768, "color push rgb 0 0 1".to_owned()),
+            (98_304, "color push rgb 0 0.5 0".to_owned()),
+            (163_840, "color pop".to_owned()),
+            (163_840, "color pop".to_owned()),
+            (229_376, "color push rgb 1 0 0".to_owned()),
+            (327_680, "color pop".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn color_fails_closed_on_bad_specs_and_unbalanced_stacks() {
+    let cases: [(&[u8], &[u8], &[u8]); 6] = [
+        (
+            b"",
+            b"\\textcolor{nocolor}{x}",
+            b"INVALID_INPUT: color_undefined",
+        ),
+        (
+            b"",
+            b"\\textcolor[rgb]{1,0}{x}",
+            b"INVALID_INPUT: color_spec_invalid",
+        ),
+        (
+            b"",
+            b"\\color[cmyk]{0,0,0,2}x",
+            b"INVALID_INPUT: color_spec_invalid",
+        ),
+        (
+            b"\\definecolor{bad}{hsb}{0,1,1}\n",
+            b"x",
+            b"INVALID_INPUT: color_spec_invalid",
+        ),
+        (
+            b"",
+            b"\\special{color pop}x",
+            b"INVALID_INPUT: color_stack_unbalanced",
+        ),
+        (
+            b"",
+            b"\\special{color push gray 0}x",
+            b"INVALID_INPUT: color_stack_unbalanced",
+        ),
+    ];
+    for (preamble, body, log) in cases {
+        let mut full_preamble = b"\\usepackage{xcolor}\n".to_vec();
+        full_preamble.extend_from_slice(preamble);
+        let result = compile_v0(&full_preamble, body);
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(result.log_bytes, log.to_vec());
+    }
+
+    // Color commands need the package, and packages that are neither built in nor
+    // mounted fail closed.
+    let result = compile_v0(b"", b"\\textcolor{red}{x}");
+    assert_eq!(result.status, CompileStatus::NotImplemented);
+    let result = compile_v0(b"\\usepackage{tikz}\n", b"x");
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+    assert_eq!(
+        result.log_bytes,
+        b"INVALID_INPUT: package_not_available:tikz"
+    );
+    let result = compile_v0(b"\\usepackage{xcolor}\n", b"\\textcolor{red}x");
+    assert_eq!(result.status, CompileStatus::NotImplemented);
+}
@@ -0,0 +1,179 @@
+use super::compile_request_v0;
+use super::test_text_v0::{first_baseline_v_v0, read_writer_dvi_v0, spaced_text_v0};
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::{validate_dvi_v2_text_page_v0, DviDocumentV0};
+
+const TEXT_WIDTH_SP: i32 = 5_242_880;
+const EM_SP: i32 = 65_536;
+
+fn request_v0() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_body_v0(preamble: &[u8], body: &[u8]) -> CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n".to_vec();
+    main.extend_from_slice(preamble);
+    main.extend_from_slice(b"\\begin{document}\n");
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, &request_v0())
+}
+
+fn document_v0(preamble: &[u8], body: &[u8]) -> DviDocumentV0 {
+    let result = compile_body_v0(preamble, body);
+    assert_eq!(
+        result.status,
+        CompileStatus::Ok,
+        "{}",
+        String::from_utf8_lossy(&result.log_bytes)
+    );
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    read_writer_dvi_v0(&result.main_xdv_bytes)
+}
+
+/// `(glyph, h, line)` of the first page's glyphs, lines counted from the first.
+fn glyphs_v0(document: &DviDocumentV0) -> Vec<(char, i32, i32)> {
+    document.pages[0]
+        .glyphs
+        .iter()
+        .map(|glyph| {
+            (
+                glyph.code as u8 as char,
+                glyph.h,
+                (glyph.v - first_baseline_v_v0()) / 786_432,
+            )
+        })
+        .collect()
+}
+
+fn text_v0(document: &DviDocumentV0) -> String {
+    spaced_text_v0(&document.pages[0].glyphs)
+}
+
+const AMSMATH: &[u8] = b"\\usepackage{amsmath}\n";
+
+#[test]
+fn equations_are_numbered_flush_right_and_referenced() {
+    let body = b"\\begin{equation}x\\label{a}\\end{equation}\
+\\begin{equation*}y\\end{equation*}\\begin{equation}z\\nonumber\\end{equation}\
+\\begin{equation}w\\label{b}\\end{equation}See \\eqref{a} and \\ref{b}.";
+    let document = document_v0(AMSMATH, body);
+    assert_eq!(text_v0(&document), "x (1)yzw (2)See (1) and 2. ");
+    let number_h = TEXT_WIDTH_SP - 3 * EM_SP;
+    assert_eq!(
+        glyphs_v0(&document)[..7],
+        [
+            ('x', (TEXT_WIDTH_SP - EM_SP) / 2, 0),
+            ('(', number_h, 0),
+            ('1', number_h + EM_SP, 0),
+            (')', number_h + 2 * EM_SP, 0),
+            ('y', (TEXT_WIDTH_SP - EM_SP) / 2, 1),
+            ('z', (TEXT_WIDTH_SP - EM_SP) / 2, 2),
+            ('w', (TEXT_WIDTH_SP - EM_SP) / 2, 3),
+        ]
+    );
+
+    let document = document_v0(b"", b"\\begin{equation}x\\end{equation}");
+    assert_eq!(text_v0(&document), "x (1)");
+}
+
+#[test]
+fn align_sets_columns_around_the_alignment_points() {
+    let body = b"\\begin{align}a&=b\\label{first}\\\\c+d&=e\\nonumber\\\\&\\le f\\tag{T}\\label{t}\
+\\\\\\end{align}\\eqref{first}\\eqref{t}";
+    let document = document_v0(AMSMATH, body);
+    assert_eq!(text_v0(&document), "a = b (1)c + d = e\u{a3} f (T)(1)(T) ");
+    let (bin_sp, rel_sp) = (14_560, 18_200);
+    let left_width = 3 * EM_SP + 2 * bin_sp;
+    let right_width = 2 * EM_SP + 2 * rel_sp;
+    let gap = (TEXT_WIDTH_SP - left_width - right_width) / 2;
+    let (column0, column1) = (gap, gap + left_width);
+    let number_h = TEXT_WIDTH_SP - 3 * EM_SP;
+    assert_eq!(
+        glyphs_v0(&document)[..15],
+        [
+            ('a', column1 - EM_SP, 0),
+            ('=', column1 + rel_sp, 0),
+            ('b', column1 + 2 * rel_sp + EM_SP, 0),
+            ('(', number_h, 0),
+            ('1', number_h + EM_SP, 0),
+            (')', number_h + 2 * EM_SP, 0),
+            ('c', column0, 1),
+            ('+', column0 + EM_SP + bin_sp, 1),
+            ('d', column0 + 2 * EM_SP + 2 * bin_sp, 1),
+            ('=', column1 + rel_sp, 1),
+            ('e', column1 + 2 * rel_sp + EM_SP, 1),
+            ('\u{a3}', column1 + rel_sp, 2),
+            ('f', column1 + 2 * rel_sp + EM_SP, 2),
+            ('(', number_h, 2),
+            ('T', number_h + EM_SP, 2),
+        ]
+    );
+}
+
+#[test]
+fn gather_centres_rows_and_multline_numbers_its_last() {
+    let body = b"\\begin{gather*}a\\\\bb\\end{gather*}\\begin{gather}a\\end{gather}\
+\\begin{multline}a\\\\b\\label{m}\\\\c\\end{multline}\\eqref{m}";
+    let document = document_v0(AMSMATH, body);
+    assert_eq!(text_v0(&document), "abba (1)abc (2)(2) ");
+    let centred_h = (TEXT_WIDTH_SP - EM_SP) / 2;
+    assert_eq!(
+        glyphs_v0(&document)[..12],
+        [
+            ('a', centred_h, 0),
+            ('b', centred_h - EM_SP / 2, 1),
+            ('b', centred_h + EM_SP / 2, 1),
+            ('a', centred_h, 2),
+            ('(', TEXT_WIDTH_SP - 3 * EM_SP, 2),
+            ('1', TEXT_WIDTH_SP - 2 * EM_SP, 2),
+            (')', TEXT_WIDTH_SP - EM_SP, 2),
+            ('a', EM_SP, 3),
+            ('b', centred_h, 4),
+            ('c', TEXT_WIDTH_SP - 3 * EM_SP - EM_SP / 2 - EM_SP, 5),
+            ('(', TEXT_WIDTH_SP - 3 * EM_SP, 5),
+            ('2', TEXT_WIDTH_SP - 2 * EM_SP, 5),
+        ]
+    );
+}
+
+#[test]
+fn equations_outside_the_subset_fail_closed() {
+    for (preamble, body) in [
+        (b"".as_slice(), b"\\begin{align}a&=b\\end{align}".as_slice()),
+        (b"", b"\\begin{equation*}a\\end{equation*}"),
+        (b"", b"\\begin{equation}a\\tag{A}\\end{equation}"),
+        (AMSMATH, b"\\begin{equation}a&=b\\end{equation}"),
+        (AMSMATH, b"\\begin{equation}a\\\\b\\end{equation}"),
+        (AMSMATH, b"\\begin{gather}a&b\\end{gather}"),
+        (AMSMATH, b"\\begin{align}a\\\\[2pt]b\\end{align}"),
+        (AMSMATH, b"\\begin{align}a\\end{gather}"),
+        (AMSMATH, b"a & b"),
+    ] {
+        let result = compile_body_v0(preamble, body);
+        assert_eq!(
+            result.status,
+            CompileStatus::NotImplemented,
+            "{}",
+            String::from_utf8_lossy(body)
+        );
+    }
+    for body in [
+        b"\\begin{equation}a\\tag{A}\\tag*{B}\\end{equation}".as_slice(),
+        b"\\begin{multline}a\\tag{A}\\\\b\\tag{B}\\end{multline}",
+    ] {
+        let result = compile_body_v0(AMSMATH, body);
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(result.log_bytes, b"INVALID_INPUT: equation_multiple_tag");
+    }
+}
@@ -0,0 +1,167 @@
+use super::compile_request_v0;
+use super::test_text_v0::{read_writer_dvi_v0, spaced_text_v0};
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::{validate_dvi_v2_text_page_v0, DviGlyphV0};
+
+fn request_v0(max_lines_per_page: Option<u32>) -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: max_lines_per_page,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_v0(preamble: &[u8], body: &[u8], request: &CompileRequestV0) -> CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n".to_vec();
+    main.extend_from_slice(preamble);
+    main.extend_from_slice(b"\\begin{document}\n");
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, request)
+}
+
+/// Glyphs of every page regrouped into lines by their baseline, each line led by the
+/// `h` of its first glyph.
+fn page_lines_v0(result: &CompileResultV0) -> Vec<Vec<(i32, String)>> {
+    assert_eq!(
+        result.status,
+        CompileStatus::Ok,
+        "{}",
+        String::from_utf8_lossy(&result.log_bytes)
+    );
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    let document = read_writer_dvi_v0(&result.main_xdv_bytes);
+    document
+        .pages
+        .iter()
+        .map(|page| {
+            let mut lines = Vec::<(i32, i32, Vec<&DviGlyphV0>)>::new();
+            for glyph in &page.glyphs {
+                match lines.last_mut() {
+                    Some((v, _, glyphs)) if *v == glyph.v => glyphs.push(glyph),
+                    _ => lines.push((glyph.v, glyph.h, vec![glyph])),
+                }
+            }
+            lines
+                .into_iter()
+                .map(|(_, h, glyphs)| (h, spaced_text_v0(glyphs)))
+                .collect()
+        })
+        .collect()
+}
+
+fn texts_v0(pages: &[Vec<(i32, String)>]) -> Vec<Vec<&str>> {
+    pages
+        .iter()
+        .map(|page| page.iter().map(|(_, text)| text.as_str()).collect())
+        .collect()
+}
+
+#[test]
+fn floats_go_to_the_top_of_their_page_or_here_with_centred_captions() {
+    let result = compile_v0(
+        b"",
+        b"Text.\n\\begin{figure}\nPlot\n\\caption{A plot}\\label{f}\n\\end{figure}\n\
+More, see \\ref{f}.\n\\begin{table}[h]\\caption{Data}\nRows\\end{table}\nEnd.",
+        &request_v0(None),
+    );
+    let pages = page_lines_v0(&result);
+    assert_eq!(
+        texts_v0(&pages),
+        [vec![
+            "Plot",
+            "Figure 1: A plot",
+            " Text. More, see 1. End. ",
+            "Table 1: Data",
+            "Rows"
+        ]]
+    );
+    // `Figure 1: A plot` is 14 ems wide on a text width of 80.
+    assert_eq!(pages[0][1].0, 33 * 65_536);
+    assert_eq!(pages[0][0].0, 0);
+    assert!(result.log_bytes.is_empty());
+}
+
+#[test]
+fn floats_that_do_not_fit_make_a_float_page_and_lists() {
+    let request = request_v0(Some(8));
+    let body = b"\\listoffigures\\listoftables\n\\section{One}\nText.\n\
+\\begin{figure}[p]\na\\newline b\\newline c\\newline d\\newline e\\caption[Short]{Long title}\\label{big}\n\\end{figure}\n\
+See page \\pageref{big}.";
+    let result = compile_v0(b"", body, &request);
+    let pages = page_lines_v0(&result);
+    assert_eq!(
+        String::from_utf8_lossy(&result.main_lof_bytes),
+        "\\contentsline {figure}{\\numberline {1}Short}{3}%\n"
+    );
+    assert!(result.main_lot_bytes.is_empty());
+    let dots = vec!["."; 69].join(" ");
+    assert_eq!(
+        texts_v0(&pages)[0][..3],
+        [
+            "List of Figures",
+            &format!("1 Short {dots} 3"),
+            "List of Tables"
+        ]
+    );
+    assert_eq!(
+        texts_v0(&pages)[2],
+        ["a", "b", "c", "d", "e", "Figure 1: Long title"]
+    );
+    // The float waits for a page of its own after the one holding its anchor.
+    assert_eq!(texts_v0(&pages)[1], ["Text. See page 3. "]);
+    assert!(result.log_bytes.is_empty());
+}
+
+#[test]
+fn float_package_h_sets_the_float_in_place() {
+    let result = compile_v0(
+        b"\\usepackage{float}\n",
+        b"Before.\n\\begin{table}[H]\n\\caption{Kept}\n\\end{table}\nAfter.",
+        &request_v0(None),
+    );
+    assert_eq!(
+        texts_v0(&page_lines_v0(&result)),
+        [vec![" Before.", "Table 1: Kept", "After. "]]
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&result.main_lot_bytes),
+        "\\contentsline {table}{\\numberline {1}Kept}{1}%\n"
+    );
+}
+
+#[test]
+fn floats_outside_the_subset_fail_closed() {
+    for (preamble, body, reason) in [
+        (
+            b"".as_slice(),
+            b"\\begin{figure}[H]x\\end{figure}".as_slice(),
+            b"INVALID_INPUT: float_placement_invalid".as_slice(),
+        ),
+        (
+            b"",
+            b"x\\caption{y}",
+            b"INVALID_INPUT: caption_outside_float",
+        ),
+    ] {
+        let result = compile_v0(preamble, body, &request_v0(None));
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(result.log_bytes, reason);
+        assert!(result.main_lof_bytes.is_empty());
+    }
+    for body in [
+        b"\\begin{figure}\\begin{table}x\\end{table}\\end{figure}".as_slice(),
+        b"\\begin{figure}x\\footnote{y}\\end{figure}",
+        b"x\\footnote{\\begin{figure}y\\end{figure}}",
+        b"\\begin{figure}x",
+    ] {
+        let result = compile_v0(b"", body, &request_v0(None));
+        assert_eq!(result.status, CompileStatus::NotImplemented);
+    }
+}
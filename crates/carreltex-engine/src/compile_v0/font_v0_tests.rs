@@ -0,0 +1,175 @@
+use super::compile_request_v0;
+use carreltex_core::{CompileRequestV0, CompileStatus, Mount};
+use carreltex_xdv::{read_dvi_v0, validate_dvi_v2_text_page_v0, DviFontKindV0};
+
+fn valid_request() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_with_files_v0(body: &[u8], files: &[(&[u8], &[u8])]) -> carreltex_core::CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n\\begin{document}\n".to_vec();
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    for (path, bytes) in files {
+        assert!(mount.add_file(path, bytes).is_ok());
+    }
+    compile_request_v0(&mut mount, &valid_request())
+}
+
+/// `(byte, font name, scale, h)` of every non-space glyph of the first page.
+fn glyph_fonts_v0(body: &[u8]) -> Vec<(u8, String, i32, i32)> {
+    let result = compile_with_files_v0(body, &[]);
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    let document = read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read");
+    document.pages[0]
+        .glyphs
+        .iter()
+        .filter(|glyph| glyph.code != u32::from(b' '))
+        .map(|glyph| {
+            let font = document.font_v0(glyph.font_num).expect("defined font");
+            let DviFontKindV0::Tfm(definition) = &font.kind else {
+                panic!("unexpected font {font:?}");
+            };
+            (
+                glyph.code as u8,
+                String::from_utf8_lossy(&definition.name).into_owned(),
+                definition.scale,
+                glyph.h,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn text_commands_and_declarations_switch_fonts_within_groups() {
+    let glyphs = glyph_fonts_v0(b"a\\textbf{b\\emph{c}}\\itshape d\\emph{e}{\\Large\\ttfamily f}g");
+    let fonts = glyphs
+        .iter()
+        .map(|(byte, name, scale, _)| (*byte, name.as_str(), *scale))
+        .collect::<Vec<_>>();
+    // `\emph` inside italics switches back to upright, here the default font.
+    assert_eq!(
+        fonts,
+        vec![
+            (b'a', "carreltex-v0", 0),
+            (b'b', "carreltex-v0-rm-bx-n", 655_360),
+            (b'c', "carreltex-v0-rm-bx-it", 655_360),
+            (b'd', "carreltex-v0-rm-m-it", 655_360),
+            (b'e', "carreltex-v0", 0),
+            (b'f', "carreltex-v0-tt-m-it", 943_718),
+            (b'g', "carreltex-v0-rm-m-it", 655_360),
+        ]
+    );
+    // `f` is set at 14.4pt, 1.44em wide.
+    assert_eq!(glyphs[6].3 - glyphs[5].3, 94_371);
+}
+
+#[test]
+fn headings_and_description_labels_are_bold() {
+    let glyphs = glyph_fonts_v0(
+        b"\\section{A}\\subsection{B}x\\begin{description}\\item[T] d\\end{description}",
+    );
+    let fonts = glyphs
+        .iter()
+        .map(|(byte, name, scale, _)| (*byte, name.as_str(), *scale))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fonts,
+        vec![
+            (b'1', "carreltex-v0-rm-bx-n", 943_718),
+            (b'A', "carreltex-v0-rm-bx-n", 943_718),
+            (b'1', "carreltex-v0-rm-bx-n", 786_432),
+            (b'.', "carreltex-v0-rm-bx-n", 786_432),
+            (b'1', "carreltex-v0-rm-bx-n", 786_432),
+            (b'B', "carreltex-v0-rm-bx-n", 786_432),
+            (b'x', "carreltex-v0", 0),
+            (b'T', "carreltex-v0-rm-bx-n", 655_360),
+            (b'd', "carreltex-v0", 0),
+        ]
+    );
+}
+
+#[test]
+fn font_commands_outside_the_subset_fail_closed() {
+    let sizes = [
+        &b"\\tiny"[..],
+        b"\\small",
+        b"\\large",
+        b"\\Large",
+        b"\\LARGE",
+        b"\\huge",
+        b"\\Huge",
+    ]
+    .map(|size| [size, b" x"].concat())
+    .concat();
+    let mut too_many = Vec::<u8>::new();
+    for shape in [&b"\\upshape"[..], b"\\itshape", b"\\slshape", b"\\scshape"] {
+        for series in [&b"\\mdseries"[..], b"\\bfseries"] {
+            for family in [&b"\\rmfamily"[..], b"\\ttfamily"] {
+                too_many.extend_from_slice(&[shape, series, family, sizes.as_slice()].concat());
+            }
+        }
+    }
+    let result = compile_with_files_v0(&too_many, &[]);
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+    assert_eq!(result.log_bytes, b"INVALID_INPUT: font_count_exceeded");
+
+    for body in [b"\\textbf x".as_slice(), b"\\textsf{x}", b"\\textbf{\\foo}"] {
+        let result = compile_with_files_v0(body, &[]);
+        assert_eq!(
+            result.status,
+            CompileStatus::NotImplemented,
+            "{}",
+            String::from_utf8_lossy(body)
+        );
+    }
+}
+
+#[test]
+fn mounted_font_map_supplies_pdf_font_files() {
+    let unused: [(&[u8], &[u8]); 2] = [
+        (
+            b"carreltex-fonts.map",
+            b"% bold only\n\ncarreltex-v0-rm-bx-n  fonts/bold.ttf\n",
+        ),
+        (b"fonts/bold.ttf", b"not a font"),
+    ];
+    let result = compile_with_files_v0(b"plain", &unused);
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(!result.main_pdf_bytes.is_empty());
+    // Once the bold font is used, its mapped file must parse.
+    let result = compile_with_files_v0(b"\\textbf{bold}", &unused);
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+    assert_eq!(result.log_bytes, b"INVALID_INPUT: pdf_write_failed");
+
+    let cases: [(&[u8], &[u8]); 3] = [
+        (
+            b"carreltex-v0-rm-bx-n\n",
+            b"INVALID_INPUT: font_map_invalid",
+        ),
+        (b"a x.ttf\na y.ttf\n", b"INVALID_INPUT: font_map_invalid"),
+        (
+            b"carreltex-v0-rm-bx-n missing.ttf\n",
+            b"INVALID_INPUT: font_file_missing",
+        ),
+    ];
+    for (map, log) in cases {
+        let result = compile_with_files_v0(b"plain", &[(b"carreltex-fonts.map", map)]);
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(
+            String::from_utf8_lossy(&result.log_bytes),
+            String::from_utf8_lossy(log)
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use super::compile_request_v0;
+use super::test_text_v0::{first_baseline_v_v0, read_writer_dvi_v0, spaced_text_v0};
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::{validate_dvi_v2_text_page_v0, DviDocumentV0, DviRuleV0};
+
+const LINE_SP: i32 = 786_432;
+
+fn request_v0(max_lines_per_page: Option<u32>) -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: max_lines_per_page,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_body_v0(body: &[u8], request: &CompileRequestV0) -> CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n\\begin{document}\n".to_vec();
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, request)
+}
+
+/// `(glyph, h, v, font)` of every page, `v` from the first baseline.
+fn page_glyphs_v0(result: &CompileResultV0) -> Vec<Vec<(char, i32, i32, i32)>> {
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    let document = read_writer_dvi_v0(&result.main_xdv_bytes);
+    document
+        .pages
+        .iter()
+        .map(|page| {
+            page.glyphs
+                .iter()
+                .map(|glyph| {
+                    let v = glyph.v - first_baseline_v_v0();
+                    (glyph.code as u8 as char, glyph.h, v, glyph.font_num)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Text of the glyphs of page `page` on baseline `v`, from the first baseline.
+fn line_text_v0(document: &DviDocumentV0, page: usize, v: i32) -> String {
+    spaced_text_v0(
+        document.pages[page]
+            .glyphs
+            .iter()
+            .filter(|glyph| glyph.v - first_baseline_v_v0() == v),
+    )
+}
+
+#[test]
+fn footnotes_sit_below_a_rule_at_the_bottom_of_their_page() {
+    let result = compile_body_v0(
+        b"Text\\footnote{First note.} more\\footnotemark\n\\footnotetext{Second.}",
+        &request_v0(Some(6)),
+    );
+    let pages = page_glyphs_v0(&result);
+    assert_eq!(pages.len(), 1);
+    let glyphs = &pages[0];
+    let document = read_writer_dvi_v0(&result.main_xdv_bytes);
+    assert_eq!(line_text_v0(&document, 0, 0), " Text  more  ");
+
+    // Marks are set in the 7pt script size of the 10pt text, 0.412892em up.
+    let raise = -27_059;
+    assert_eq!(line_text_v0(&document, 0, raise), "1 2");
+    assert_eq!(glyphs[5], ('1', 294_912, raise, 1));
+
+    // Bodies in 8pt end the page under a 0.4pt rule two fifths of the text width
+    // wide, each mark (6pt) flush right in a 1.8em box.
+    assert_eq!(line_text_v0(&document, 0, 4 * LINE_SP), "First note.");
+    assert_eq!(line_text_v0(&document, 0, 5 * LINE_SP), "Second.");
+    let body_raise = -21_647;
+    assert_eq!(
+        glyphs[13],
+        ('1', 94_370 - 39_321, 4 * LINE_SP + body_raise, 2)
+    );
+    assert_eq!(glyphs[14], ('F', 94_370, 4 * LINE_SP, 3));
+    assert_eq!(
+        line_text_v0(&document, 0, 5 * LINE_SP + body_raise),
+        "2",
+        "footnotetext reuses the last mark"
+    );
+    assert_eq!(
+        document.pages[0].rules,
+        vec![DviRuleV0 {
+            h: 0,
+            v: first_baseline_v_v0() + 3 * LINE_SP,
+            width: 2_097_152,
+            height: 26_214,
+        }]
+    );
+}
+
+#[test]
+fn a_line_moves_on_when_its_footnote_does_not_fit_below_it() {
+    // `c` fits on the first page, but with the rule and its footnote it does not.
+    let result = compile_body_v0(
+        b"a\\newline\\newline b\\newline\\newline c\\footnote[7]{x\\label{n}} \\footnote{y} \
+see \\ref{n} on \\pageref{n}",
+        &request_v0(Some(5)),
+    );
+    let pages = page_glyphs_v0(&result);
+    assert_eq!(pages.len(), 2);
+    let document = read_writer_dvi_v0(&result.main_xdv_bytes);
+    assert_eq!(line_text_v0(&document, 0, 2 * LINE_SP), "b");
+    assert_eq!(line_text_v0(&document, 1, 0), "c    see 7 on 2 ");
+    assert_eq!(line_text_v0(&document, 1, -27_059), "7 1");
+    assert_eq!(line_text_v0(&document, 1, 3 * LINE_SP), "x");
+    assert_eq!(line_text_v0(&document, 1, 4 * LINE_SP), "y");
+    assert!(result.log_bytes.is_empty());
+    assert_eq!(
+        String::from_utf8_lossy(&result.main_aux_bytes),
+        "\\relax\n\\newlabel{n}{{7}{2}}\n"
+    );
+}
+
+#[test]
+fn footnotes_outside_the_subset_fail_closed() {
+    for body in [
+        b"a\\footnote{b\\footnote{c}}".as_slice(),
+        b"a\\footnote{b\\footnotetext{c}}",
+        b"a\\footnote[x]{b}",
+        b"a\\footnote b",
+        b"a\\footnote{b\\end{document}}",
+    ] {
+        let result = compile_body_v0(body, &request_v0(None));
+        assert_eq!(
+            result.status,
+            CompileStatus::NotImplemented,
+            "{}",
+            String::from_utf8_lossy(body)
+        );
+    }
+    let result = compile_body_v0(b"a\\footnote{\\begin{itemize}}", &request_v0(None));
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+    assert_eq!(
+        result.log_bytes,
+        b"INVALID_INPUT: list_environment_unbalanced"
+    );
+    let result = compile_body_v0(&b"\\footnote{}".repeat(1025), &request_v0(None));
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+    assert_eq!(result.log_bytes, b"INVALID_INPUT: footnote_count_exceeded");
+    let result = compile_body_v0(&b"\\footnotemark".repeat(4097), &request_v0(None));
+    assert_eq!(result.log_bytes, b"INVALID_INPUT: footnote_count_exceeded");
+}
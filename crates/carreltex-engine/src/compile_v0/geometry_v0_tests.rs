@@ -0,0 +1,174 @@
+use super::compile_request_v0;
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::{read_dvi_v0, validate_dvi_v2_text_page_v0, DviDocumentV0};
+
+/// `\headheight`, `\headsep` and `\topskip` between `\topmargin` and the first baseline.
+const HEAD_TO_BASELINE_SP: i32 = 786_432 + 1_638_400 + 655_360;
+
+fn request_v0() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_document_v0(class_options: &[u8], preamble: &[u8], body: &[u8]) -> CompileResultV0 {
+    let main = [
+        b"\\documentclass".as_slice(),
+        class_options,
+        b"{article}\n",
+        preamble,
+        b"\n\\pagestyle{empty}\n\\begin{document}\n",
+        body,
+        b"\n\\end{document}\n",
+    ]
+    .concat();
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, &request_v0())
+}
+
+fn document_v0(result: &CompileResultV0) -> DviDocumentV0 {
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read")
+}
+
+fn first_glyph_position_v0(document: &DviDocumentV0) -> (i32, i32) {
+    let glyph = &document.pages[0].glyphs[0];
+    (glyph.h, glyph.v)
+}
+
+fn papersize_v0(document: &DviDocumentV0) -> String {
+    String::from_utf8_lossy(&document.pages[0].specials[0].bytes).into_owned()
+}
+
+fn contains_v0(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[test]
+fn default_geometry_is_letter_paper_with_the_kernel_margins() {
+    let result = compile_document_v0(b"", b"", b"A");
+    let document = document_v0(&result);
+    assert_eq!(first_glyph_position_v0(&document), (0, HEAD_TO_BASELINE_SP));
+    assert_eq!(papersize_v0(&document), "papersize=614.295pt,794.96999pt");
+    assert!(contains_v0(
+        &result.main_pdf_bytes,
+        b"/MediaBox [0 0 612 792]"
+    ));
+}
+
+#[test]
+fn a4paper_class_option_sets_the_paper_size() {
+    let result = compile_document_v0(b"[a4paper]", b"", b"\\rule{0.5\\paperheight}{1pt}");
+    let document = document_v0(&result);
+    assert_eq!(papersize_v0(&document), "papersize=597.50787pt,845.04684pt");
+    assert_eq!(document.pages[0].rules[0].width, 55_380_990 / 2);
+    assert!(contains_v0(
+        &result.main_pdf_bytes,
+        b"/MediaBox [0 0 595.2756 841.8898]"
+    ));
+}
+
+#[test]
+fn geometry_margins_place_the_text_block_from_the_paper_edge() {
+    let result = compile_document_v0(b"", b"\\usepackage[letterpaper,margin=1in]{geometry}", b"A");
+    let document = document_v0(&result);
+    assert_eq!(first_glyph_position_v0(&document), (0, 655_360));
+
+    let result = compile_document_v0(
+        b"[letterpaper]",
+        b"\\usepackage{geometry}\n\\geometry{paper=a4paper, left=1in, top=2in}",
+        b"A",
+    );
+    let document = document_v0(&result);
+    assert_eq!(
+        first_glyph_position_v0(&document),
+        (0, 9_472_573 - 4_736_286 + 655_360)
+    );
+    assert_eq!(papersize_v0(&document), "papersize=597.50787pt,845.04684pt");
+}
+
+#[test]
+fn setlength_moves_the_margins_and_sets_the_paper() {
+    let result = compile_document_v0(
+        b"",
+        b"\\setlength{\\oddsidemargin}{10pt}\\setlength\\topmargin{-20pt}\
+\\setlength{\\paperwidth}{200pt}",
+        b"A",
+    );
+    let document = document_v0(&result);
+    assert_eq!(
+        first_glyph_position_v0(&document),
+        (655_360, HEAD_TO_BASELINE_SP - 1_310_720)
+    );
+    assert_eq!(papersize_v0(&document), "papersize=200.0pt,794.96999pt");
+}
+
+#[test]
+fn the_text_block_shrinks_to_fit_between_the_margins() {
+    let result = compile_document_v0(
+        b"",
+        b"\\usepackage[paperwidth=200pt,hmargin=1in,vmargin=2in]{geometry}",
+        b"\\rule{\\textwidth}{1pt}\\newline\\rule{\\textheight}{1pt}",
+    );
+    let document = document_v0(&result);
+    let widths = document.pages[0]
+        .rules
+        .iter()
+        .map(|rule| rule.width)
+        .collect::<Vec<_>>();
+    // 55 glyphs of 200pt less two inches; \topskip and 41 more lines of the paper's
+    // height less four inches.
+    assert_eq!(widths, [55 * 65_536, 655_360 + 41 * 786_432]);
+
+    let result = compile_document_v0(b"", b"\\usepackage[right=2in,bottom=2in]{geometry}", b"A");
+    assert_eq!(result.status, CompileStatus::Ok);
+}
+
+#[test]
+fn feet_that_fall_off_the_paper_fail_closed() {
+    let preamble = b"\\usepackage[bottom=0pt]{geometry}";
+    let result = compile_document_v0(b"", preamble, b"A");
+    assert_eq!(result.status, CompileStatus::Ok);
+    let result = compile_document_v0(b"", preamble, b"A\\thispagestyle{plain}");
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+    assert_eq!(result.log_bytes, b"INVALID_INPUT: page_layout_failed");
+}
+
+#[test]
+fn geometry_outside_the_subset_fails_closed() {
+    for (class_options, preamble) in [
+        (b"".as_slice(), b"\\geometry{margin=1in}".as_slice()),
+        (b"", b"\\usepackage[includehead]{geometry}"),
+        (b"", b"\\usepackage[margin=4.5in]{geometry}"),
+        (b"", b"\\usepackage[top=5in,bottom=6in]{geometry}"),
+        (b"", b"\\setlength{\\paperwidth}{100pt}"),
+        (b"", b"\\usepackage[dvips]{xcolor}"),
+        (b"", b"\\setlength{\\parindent}{0pt}"),
+    ] {
+        let result = compile_document_v0(class_options, preamble, b"A");
+        assert_eq!(
+            result.status,
+            CompileStatus::NotImplemented,
+            "{}",
+            String::from_utf8_lossy(preamble)
+        );
+    }
+    for preamble in [
+        b"\\setlength{\\paperwidth}{-1pt}".as_slice(),
+        b"\\usepackage[margin=wide]{geometry}",
+    ] {
+        let result = compile_document_v0(b"", preamble, b"A");
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(result.log_bytes, b"INVALID_INPUT: dimen_invalid");
+    }
+}
@@ -0,0 +1,179 @@
+use super::compile_request_v0;
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::{parse_image_special_v0, read_dvi_v0, validate_dvi_v2_text_page_v0};
+
+/// 144x72 RGB PNG of zero samples, in stored zlib blocks; CRCs and the Adler-32
+/// sum are left zero.
+fn png_v0() -> Vec<u8> {
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    let mut chunk = |chunk_type: &[u8], data: &[u8]| {
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        out.extend_from_slice(chunk_type);
+        out.extend_from_slice(data);
+        out.extend_from_slice(&[0; 4]);
+    };
+    let mut header = 144u32.to_be_bytes().to_vec();
+    header.extend_from_slice(&72u32.to_be_bytes());
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+    chunk(b"IHDR", &header);
+    let row = [0u8; 1 + 3 * 144];
+    let mut data = vec![0x78, 0x01];
+    for index in 0..72 {
+        let length = row.len() as u16;
+        data.push(u8::from(index == 71));
+        data.extend_from_slice(&length.to_le_bytes());
+        data.extend_from_slice(&(!length).to_le_bytes());
+        data.extend_from_slice(&row);
+    }
+    data.extend_from_slice(&[0; 4]);
+    chunk(b"IDAT", &data);
+    chunk(b"IEND", b"");
+    out
+}
+
+/// 8-bit gray JPEG headers of a 300x150 image at 300 dots per inch.
+fn jpeg_v0() -> Vec<u8> {
+    let mut out = vec![0xff, 0xd8, 0xff, 0xe0, 0, 16];
+    out.extend_from_slice(b"JFIF\0\x01\x02\x01\x01\x2c\x01\x2c\0\0");
+    out.extend_from_slice(&[0xff, 0xc0, 0, 11, 8, 0, 150, 1, 0x2c, 1, 1, 0x11, 0]);
+    out.extend_from_slice(&[0xff, 0xda, 0xff, 0xd9]);
+    out
+}
+
+fn compile_v0(preamble: &[u8], body: &[u8]) -> CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n".to_vec();
+    main.extend_from_slice(preamble);
+    main.extend_from_slice(b"\\begin{document}\n");
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    assert!(mount.add_file(b"fig/plot.png", &png_v0()).is_ok());
+    assert!(mount.add_file(b"photo.jpg", &jpeg_v0()).is_ok());
+    assert!(mount.add_file(b"anim.gif", b"GIF89a").is_ok());
+    let request = CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    };
+    compile_request_v0(&mut mount, &request)
+}
+
+#[test]
+fn includegraphics_reserves_the_image_box_and_embeds_the_file() {
+    let result = compile_v0(
+        b"\\usepackage{graphicx}\n",
+        b"Before.\n\\includegraphics{fig/plot}After.\n\
+\\includegraphics[scale=.5]{fig/plot.png}\\includegraphics[width=.5\\textwidth]{photo}",
+    );
+    assert_eq!(
+        result.status,
+        CompileStatus::Ok,
+        "{}",
+        String::from_utf8_lossy(&result.log_bytes)
+    );
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    let document = read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read");
+    let page = &document.pages[0];
+    let images = page
+        .specials
+        .iter()
+        .filter_map(|special| {
+            let image = parse_image_special_v0(&special.bytes)?;
+            Some((
+                special.h,
+                special.v,
+                image.width_sp,
+                image.height_sp,
+                image.name,
+            ))
+        })
+        .collect::<Vec<_>>();
+    // 144x72 dots at 72 per inch, half of that, and a 2:1 image half as wide as
+    // the 80 em text, each on the line its height reaches below the top margin.
+    let line_v = |line: i32| 47 * 65_536 + line * 786_432;
+    assert_eq!(
+        images,
+        [
+            (
+                0,
+                line_v(7),
+                9_472_573,
+                4_736_287,
+                b"fig/plot.png".as_slice()
+            ),
+            (
+                0,
+                line_v(11),
+                4_736_287,
+                2_368_144,
+                b"fig/plot.png".as_slice()
+            ),
+            (
+                0,
+                line_v(13),
+                40 * 65_536,
+                20 * 65_536,
+                b"photo.jpg".as_slice()
+            ),
+        ]
+    );
+    // `After.` follows the image on its baseline.
+    let after = page
+        .glyphs
+        .iter()
+        .find(|glyph| glyph.code == u32::from(b'A'))
+        .expect("text after the image");
+    assert_eq!((after.h, after.v), (9_472_573, line_v(7)));
+    let pdf = String::from_utf8_lossy(&result.main_pdf_bytes);
+    assert!(pdf.contains("/XObject << /Im1 "));
+    assert!(pdf.contains("/Filter /DCTDecode"));
+    assert_eq!(pdf.matches(" cm /Im1 Do Q").count(), 2);
+    assert_eq!(pdf.matches(" cm /Im2 Do Q").count(), 1);
+}
+
+#[test]
+fn includegraphics_outside_the_subset_fails_closed() {
+    for (body, reason) in [
+        (
+            b"\\includegraphics{missing}".as_slice(),
+            b"INVALID_INPUT: image_missing".as_slice(),
+        ),
+        (
+            b"\\includegraphics{anim.gif}",
+            b"INVALID_INPUT: image_format_unsupported",
+        ),
+        (
+            b"\\includegraphics[width=-1cm]{photo}",
+            b"INVALID_INPUT: dimen_invalid",
+        ),
+        (
+            b"\\includegraphics[height=1cm 2]{photo}",
+            b"INVALID_INPUT: dimen_invalid",
+        ),
+    ] {
+        let result = compile_v0(b"\\usepackage{graphicx}\n", body);
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(result.log_bytes, reason);
+        assert!(result.main_pdf_bytes.is_empty());
+    }
+    for (preamble, body) in [
+        (b"".as_slice(), b"\\includegraphics{photo}".as_slice()),
+        (
+            b"\\usepackage{graphicx}\n",
+            b"\\includegraphics[angle=90]{photo}",
+        ),
+        (
+            b"\\usepackage{graphicx}\n",
+            b"\\includegraphics[scale=2,width=1cm]{photo}",
+        ),
+        (b"\\usepackage[draft]{graphicx}\n", b"x"),
+    ] {
+        let result = compile_v0(preamble, body);
+        assert_eq!(result.status, CompileStatus::NotImplemented);
+    }
+}
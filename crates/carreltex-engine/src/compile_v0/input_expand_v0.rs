@@ -51,6 +51,8 @@ fn parse_input_path_group_v0(
                     break;
                 }
                 Some(TokenV0::Space)
+                | Some(TokenV0::MathShift)
+                | Some(TokenV0::AlignTab)
                 | Some(TokenV0::ControlSeq(_))
                 | Some(TokenV0::BeginGroup)
                 | None => {
@@ -0,0 +1,116 @@
+use super::compile_request_v0;
+use super::test_text_v0::{read_writer_dvi_v0, spaced_text_v0};
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::validate_dvi_v2_text_page_v0;
+
+fn request_v0(max_line_glyphs: Option<u32>, max_lines_per_page: Option<u32>) -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: max_line_glyphs,
+        ok_max_lines_per_page_v0: max_lines_per_page,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_body_v0(body: &[u8], request: &CompileRequestV0) -> CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n\\begin{document}\n".to_vec();
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, request)
+}
+
+/// Text of every page, with the gaps between its glyphs.
+fn page_texts_v0(result: &CompileResultV0) -> Vec<String> {
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    let document = read_writer_dvi_v0(&result.main_xdv_bytes);
+    document
+        .pages
+        .iter()
+        .map(|page| spaced_text_v0(&page.glyphs))
+        .collect()
+}
+
+#[test]
+fn references_resolve_to_sections_items_and_pages() {
+    let body = b"See \\ref{intro} and \\pageref{items}.\\section{Intro}\\label{intro}\n\x0c\
+\\begin{enumerate}\\item\\label{items}a\\begin{enumerate}\\item b\\item\\label{two}c\
+\\end{enumerate}\\end{enumerate}\\label{after}Then \\ref{items}, \\ref{two}, \\eqref{after}.";
+    let result = compile_body_v0(body, &request_v0(None, None));
+    assert_eq!(
+        page_texts_v0(&result),
+        vec![
+            " See 1 and 2.1 Intro".to_owned(),
+            "1. a(a) b(b) cThen 1, 1b, (1). ".to_owned()
+        ]
+    );
+    assert!(result.log_bytes.is_empty());
+    assert_eq!(
+        String::from_utf8_lossy(&result.main_aux_bytes),
+        "\\relax\n\\newlabel{intro}{{1}{1}}\n\\newlabel{items}{{1}{2}}\n\
+\\newlabel{two}{{1b}{2}}\n\\newlabel{after}{{1}{2}}\n"
+    );
+}
+
+#[test]
+fn undefined_and_multiply_defined_labels_warn() {
+    let result = compile_body_v0(
+        b"\\label{a}x\\label{a}\\ref{b} \\pageref{b}",
+        &request_v0(None, None),
+    );
+    assert_eq!(page_texts_v0(&result), vec![" x?? ?? ".to_owned()]);
+    assert_eq!(
+        String::from_utf8_lossy(&result.log_bytes),
+        "LaTeX Warning: Label `a' multiply defined.\n\
+LaTeX Warning: Reference `b' on page 1 undefined.\n\
+LaTeX Warning: Reference `b' on page 1 undefined.\n\
+LaTeX Warning: There were undefined references.\n\
+LaTeX Warning: There were multiply-defined labels."
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&result.main_aux_bytes),
+        "\\relax\n\\newlabel{a}{{}{1}}\n\\newlabel{a}{{}{1}}\n"
+    );
+}
+
+#[test]
+fn references_still_changing_after_the_last_pass_ask_for_a_rerun() {
+    // Every pass moves a label to another page, so they settle only after the last one.
+    let result = compile_body_v0(
+        b"\\pageref{y} \\label{x} ab \\pageref{x} ab c\\label{y}",
+        &request_v0(Some(2), Some(1)),
+    );
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert_eq!(
+        String::from_utf8_lossy(&result.log_bytes),
+        "LaTeX Warning: Label(s) may have changed. Rerun to get cross-references right."
+    );
+    assert!(result.main_aux_bytes.starts_with(b"\\relax\n\\newlabel{x}"));
+}
+
+#[test]
+fn label_commands_outside_the_subset_fail_closed() {
+    for body in [
+        b"\\label x".as_slice(),
+        b"\\ref{\\foo}",
+        b"\\begin{itemize}\\ref{a}\\item x\\end{itemize}",
+    ] {
+        let result = compile_body_v0(body, &request_v0(None, None));
+        assert_ne!(
+            result.status,
+            CompileStatus::Ok,
+            "{}",
+            String::from_utf8_lossy(body)
+        );
+        assert!(result.main_aux_bytes.is_empty());
+    }
+    let labels = b"\\label{k}".repeat(4097);
+    let result = compile_body_v0(&labels, &request_v0(None, None));
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+    assert_eq!(result.log_bytes, b"INVALID_INPUT: label_count_exceeded");
+}
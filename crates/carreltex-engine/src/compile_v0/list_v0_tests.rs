@@ -0,0 +1,173 @@
+use super::compile_request_v0;
+use super::test_text_v0::first_baseline_v_v0;
+use carreltex_core::{CompileRequestV0, CompileStatus, Mount};
+use carreltex_xdv::{read_dvi_v0, validate_dvi_v2_text_page_v0, DviPageV0};
+
+fn valid_request() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_main_v0(preamble: &[u8], body: &[u8]) -> carreltex_core::CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n".to_vec();
+    main.extend_from_slice(preamble);
+    main.extend_from_slice(b"\\begin{document}\n");
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, &valid_request())
+}
+
+fn first_page_v0(body: &[u8]) -> DviPageV0 {
+    let result = compile_main_v0(b"", body);
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    let mut document = read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read");
+    document.pages.remove(0)
+}
+
+/// `(byte, h)` of every glyph, one entry per line.
+fn glyph_lines_v0(page: &DviPageV0) -> Vec<Vec<(u8, i32)>> {
+    let mut lines = Vec::<Vec<(u8, i32)>>::new();
+    for glyph in &page.glyphs {
+        let line = ((glyph.v - first_baseline_v_v0()) / 786_432) as usize;
+        if lines.len() <= line {
+            lines.resize(line + 1, Vec::new());
+        }
+        lines[line].push((glyph.code as u8, glyph.h));
+    }
+    lines
+}
+
+#[test]
+fn itemize_hangs_bullets_in_the_left_margin() {
+    let page =
+        first_page_v0(b"\\begin{itemize}\n\\item One\n\\item[--] Two\n\\end{itemize}\nAfter");
+    let lines = glyph_lines_v0(&page);
+    assert_eq!(lines.len(), 3);
+    // 2.5em margin; labels end 0.5em before it.
+    assert_eq!(&lines[0][..2], &[(b'*', 65_536), (b'O', 163_840)]);
+    assert_eq!(
+        &lines[1][..3],
+        &[(b'-', 0), (b'-', 65_536), (b'T', 163_840)]
+    );
+    assert_eq!(lines[2][0], (b'A', 0));
+}
+
+#[test]
+fn enumerate_labels_and_margins_follow_the_nesting_depth() {
+    let page = first_page_v0(
+        b"\\begin{enumerate}\\item a\\begin{enumerate}\\item b\\item c\
+          \\begin{enumerate}\\item d\\begin{enumerate}\\item e\\end{enumerate}\
+          \\end{enumerate}\\end{enumerate}\\item f\\end{enumerate}",
+    );
+    let lines = glyph_lines_v0(&page);
+    let labelled = lines
+        .iter()
+        .map(|line| {
+            let label = line[..line.len() - 1]
+                .iter()
+                .map(|(byte, _)| *byte as char)
+                .collect::<String>();
+            (label, line[0].1, line[line.len() - 1])
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        labelled,
+        vec![
+            ("1.".to_owned(), 32_768, (b'a', 163_840)),
+            ("(a)".to_owned(), 78_643, (b'b', 308_019)),
+            ("(b)".to_owned(), 78_643, (b'c', 308_019)),
+            ("i.".to_owned(), 332_267, (b'd', 430_571)),
+            ("A.".to_owned(), 410_910, (b'e', 541_982)),
+            ("2.".to_owned(), 32_768, (b'f', 163_840)),
+        ]
+    );
+}
+
+#[test]
+fn description_labels_start_at_the_outer_margin() {
+    let page = first_page_v0(
+        b"\\begin{description}\\item[Term  one] def\\item[X]y\\item z\\end{description}",
+    );
+    let lines = glyph_lines_v0(&page);
+    assert_eq!(lines[0][0], (b'T', 0));
+    assert_eq!(lines[0][8], (b'd', 557_056));
+    assert_eq!(lines[1], vec![(b'X', 0), (b'y', 98_304)]);
+    assert_eq!(lines[2], vec![(b'z', 163_840)]);
+}
+
+#[test]
+fn list_environments_close_color_groups() {
+    let result = compile_main_v0(
+        b"\\usepackage{xcolor}\n",
+        b"\\begin{itemize}\\item \\color{red}a\\end{itemize}b",
+    );
+    assert_eq!(result.status, CompileStatus::Ok);
+    let document = read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read");
+    let specials = document.pages[0]
+        .specials
+        .iter()
+        .skip(1)
+        .map(|special| (special.bytes.clone(), special.glyph_index))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        specials,
+        vec![
+            (b"color push rgb 1 0 0".to_vec(), 1),
+            (b"color pop".to_vec(), 2),
+        ]
+    );
+}
+
+#[test]
+fn lists_fail_closed_when_malformed() {
+    let too_deep = [
+        b"\\begin{itemize}\\item x".repeat(5),
+        b"\\end{itemize}".repeat(5),
+    ]
+    .concat();
+    let mut alph = b"\\begin{enumerate}\\item a\\begin{enumerate}".to_vec();
+    alph.extend_from_slice(&b"\\item x".repeat(27));
+    alph.extend_from_slice(b"\\end{enumerate}\\end{enumerate}");
+    let cases: [(&[u8], &[u8]); 7] = [
+        (&too_deep, b"INVALID_INPUT: list_nesting_exceeded"),
+        (&alph, b"INVALID_INPUT: counter_too_large"),
+        (
+            b"\\begin{itemize}text\\item x\\end{itemize}",
+            b"INVALID_INPUT: list_item_missing",
+        ),
+        (b"\\item x", b"INVALID_INPUT: list_item_outside_list"),
+        (
+            b"\\begin{itemize}\\item a\\end{enumerate}",
+            b"INVALID_INPUT: list_environment_unbalanced",
+        ),
+        (
+            b"\\begin{itemize}\\item a",
+            b"INVALID_INPUT: list_environment_unbalanced",
+        ),
+        (
+            b"{\\begin{itemize}\\item a}\\end{itemize}",
+            b"INVALID_INPUT: list_environment_unbalanced",
+        ),
+    ];
+    for (body, log) in cases {
+        let result = compile_main_v0(b"", body);
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(
+            String::from_utf8_lossy(&result.log_bytes),
+            String::from_utf8_lossy(log)
+        );
+    }
+
+    let result = compile_main_v0(b"", b"\\begin{itemize}\\item[\\foo] x\\end{itemize}");
+    assert_eq!(result.status, CompileStatus::NotImplemented);
+}
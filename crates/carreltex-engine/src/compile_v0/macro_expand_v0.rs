@@ -25,6 +25,8 @@ mod noexpand;
 mod newcommand_renewcommand;
 #[path = "macro_v0/providecommand.rs"]
 mod providecommand;
+#[path = "macro_v0/special.rs"]
+mod special;
 #[path = "macro_v0/string_meaning.rs"]
 mod string_meaning;
 #[path = "macro_v0/utils.rs"]
@@ -42,6 +44,7 @@ use let_futurelet::{parse_futurelet_v0, parse_let_v0};
 use noexpand::parse_noexpand_v0;
 use newcommand_renewcommand::{parse_newcommand_v0, parse_renewcommand_v0};
 use providecommand::parse_providecommand_v0;
+use special::{parse_special_payload_v0, push_special_v0, validate_special_count_v0};
 use string_meaning::{parse_meaning_v0, parse_string_v0};
 use utils::{push_checked_v0, substitute_single_param_placeholders_v0};
 
@@ -71,6 +74,7 @@ pub(crate) fn expand_macros_v0(tokens: &[TokenV0]) -> Result<Vec<TokenV0>, Inval
         &mut expansion_count,
         0,
     )?;
+    validate_special_count_v0(&output)?;
     Ok(output)
 }
 
@@ -202,6 +206,21 @@ fn expand_stream_v0(
                 push_checked_v0(out, group_token)?;
                 index += 1;
             }
+            TokenV0::ControlSeq(name) if name.as_slice() == b"special" => {
+                let (payload, next_index) = parse_special_payload_v0(tokens, index)?;
+                let mut expanded_payload = Vec::<TokenV0>::new();
+                expand_stream_v0(
+                    &payload,
+                    macro_frames,
+                    counters,
+                    &mut expanded_payload,
+                    active_macros,
+                    expansion_count,
+                    depth + 1,
+                )?;
+                push_special_v0(out, expanded_payload)?;
+                index = next_index;
+            }
             TokenV0::ControlSeq(name) if name.as_slice() == b"relax" => {
                 index += 1;
             }
@@ -132,6 +132,7 @@ pub(super) fn snapshot_let_binding_v0(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn expand_binding_v0(
     name: &[u8],
     binding: MacroBindingV0,
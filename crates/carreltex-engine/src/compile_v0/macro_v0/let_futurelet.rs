@@ -5,7 +5,7 @@ use super::utils::skip_space_tokens_v0;
 pub(super) fn parse_let_v0(
     tokens: &[TokenV0],
     let_index: usize,
-    macro_frames: &mut Vec<BTreeMap<Vec<u8>, MacroBindingV0>>,
+    macro_frames: &mut [BTreeMap<Vec<u8>, MacroBindingV0>],
     is_global: bool,
 ) -> Result<usize, InvalidInputReasonV0> {
     let alias_name = match tokens.get(let_index + 1) {
@@ -51,7 +51,7 @@ pub(super) fn parse_let_v0(
 pub(super) fn parse_futurelet_v0(
     tokens: &[TokenV0],
     futurelet_index: usize,
-    macro_frames: &mut Vec<BTreeMap<Vec<u8>, MacroBindingV0>>,
+    macro_frames: &mut [BTreeMap<Vec<u8>, MacroBindingV0>],
     is_global: bool,
 ) -> Result<usize, InvalidInputReasonV0> {
     let alias_name_index = skip_space_tokens_v0(tokens, futurelet_index + 1);
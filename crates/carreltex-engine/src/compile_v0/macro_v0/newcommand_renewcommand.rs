@@ -5,7 +5,7 @@ use super::utils::{parse_balanced_group_payload_v0, validate_macro_body_tokens_v
 pub(super) fn parse_newcommand_v0(
     tokens: &[TokenV0],
     command_index: usize,
-    macro_frames: &mut Vec<BTreeMap<Vec<u8>, MacroBindingV0>>,
+    macro_frames: &mut [BTreeMap<Vec<u8>, MacroBindingV0>],
 ) -> Result<usize, InvalidInputReasonV0> {
     parse_new_or_renew_command_v0(tokens, command_index, macro_frames, false)
 }
@@ -13,7 +13,7 @@ pub(super) fn parse_newcommand_v0(
 pub(super) fn parse_renewcommand_v0(
     tokens: &[TokenV0],
     command_index: usize,
-    macro_frames: &mut Vec<BTreeMap<Vec<u8>, MacroBindingV0>>,
+    macro_frames: &mut [BTreeMap<Vec<u8>, MacroBindingV0>],
 ) -> Result<usize, InvalidInputReasonV0> {
     parse_new_or_renew_command_v0(tokens, command_index, macro_frames, true)
 }
@@ -21,7 +21,7 @@ pub(super) fn parse_renewcommand_v0(
 fn parse_new_or_renew_command_v0(
     tokens: &[TokenV0],
     command_index: usize,
-    macro_frames: &mut Vec<BTreeMap<Vec<u8>, MacroBindingV0>>,
+    macro_frames: &mut [BTreeMap<Vec<u8>, MacroBindingV0>],
     is_renew: bool,
 ) -> Result<usize, InvalidInputReasonV0> {
     let unsupported_reason = if is_renew {
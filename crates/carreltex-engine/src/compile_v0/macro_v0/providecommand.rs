@@ -5,7 +5,7 @@ use super::utils::{parse_balanced_group_payload_v0, validate_macro_body_tokens_v
 pub(super) fn parse_providecommand_v0(
     tokens: &[TokenV0],
     command_index: usize,
-    macro_frames: &mut Vec<BTreeMap<Vec<u8>, MacroBindingV0>>,
+    macro_frames: &mut [BTreeMap<Vec<u8>, MacroBindingV0>],
 ) -> Result<usize, InvalidInputReasonV0> {
     let unsupported_reason = InvalidInputReasonV0::MacroProvidecommandUnsupported;
     let name_group_index = command_index + 1;
@@ -0,0 +1,50 @@
+use super::utils::{parse_balanced_group_payload_v0, push_checked_v0, skip_space_tokens_v0};
+use super::*;
+use carreltex_xdv::{MAX_DVI_SPECIALS_V0, MAX_DVI_SPECIAL_BYTES_V0};
+
+pub(super) fn parse_special_payload_v0(
+    tokens: &[TokenV0],
+    special_index: usize,
+) -> Result<(Vec<TokenV0>, usize), InvalidInputReasonV0> {
+    let group_index = skip_space_tokens_v0(tokens, special_index + 1);
+    if !matches!(tokens.get(group_index), Some(TokenV0::BeginGroup)) {
+        return Err(InvalidInputReasonV0::MacroSpecialUnsupported);
+    }
+    parse_balanced_group_payload_v0(tokens, group_index)
+}
+
+/// Re-emits `\special{...}` around its expanded payload, which must reduce to
+/// characters, spaces and balanced braces within `MAX_DVI_SPECIAL_BYTES_V0`.
+pub(super) fn push_special_v0(
+    out: &mut Vec<TokenV0>,
+    expanded_payload: Vec<TokenV0>,
+) -> Result<(), InvalidInputReasonV0> {
+    if expanded_payload.iter().any(|token| {
+        !matches!(
+            token,
+            TokenV0::Char(_) | TokenV0::Space | TokenV0::BeginGroup | TokenV0::EndGroup
+        )
+    }) {
+        return Err(InvalidInputReasonV0::MacroSpecialUnsupported);
+    }
+    if expanded_payload.len() > MAX_DVI_SPECIAL_BYTES_V0 {
+        return Err(InvalidInputReasonV0::MacroSpecialTooLarge);
+    }
+    push_checked_v0(out, TokenV0::ControlSeq(b"special".to_vec()))?;
+    push_checked_v0(out, TokenV0::BeginGroup)?;
+    for token in expanded_payload {
+        push_checked_v0(out, token)?;
+    }
+    push_checked_v0(out, TokenV0::EndGroup)
+}
+
+pub(super) fn validate_special_count_v0(tokens: &[TokenV0]) -> Result<(), InvalidInputReasonV0> {
+    let special_count = tokens
+        .iter()
+        .filter(|token| matches!(token, TokenV0::ControlSeq(name) if name.as_slice() == b"special"))
+        .count();
+    if special_count > MAX_DVI_SPECIALS_V0 {
+        return Err(InvalidInputReasonV0::MacroSpecialCountExceeded);
+    }
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use super::compile_request_v0;
+use super::test_text_v0::first_baseline_v_v0;
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::{read_dvi_v0, validate_dvi_v2_text_page_v0, DviDocumentV0, DviFontKindV0};
+
+fn request_v0() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_body_v0(body: &[u8]) -> CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n\\begin{document}\n".to_vec();
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, &request_v0())
+}
+
+fn document_v0(body: &[u8]) -> DviDocumentV0 {
+    let result = compile_body_v0(body);
+    assert_eq!(
+        result.status,
+        CompileStatus::Ok,
+        "{}",
+        String::from_utf8_lossy(&result.log_bytes)
+    );
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read")
+}
+
+/// `(code, h, raise, font name)` of the first page's glyphs, raised from the first
+/// baseline.
+fn glyphs_v0(document: &DviDocumentV0) -> Vec<(u32, i32, i32, String)> {
+    document.pages[0]
+        .glyphs
+        .iter()
+        .map(|glyph| {
+            let font = match document.font_v0(glyph.font_num).map(|font| &font.kind) {
+                Some(DviFontKindV0::Tfm(definition)) => {
+                    format!(
+                        "{}@{}",
+                        String::from_utf8_lossy(&definition.name),
+                        definition.scale
+                    )
+                }
+                _ => String::new(),
+            };
+            (glyph.code, glyph.h, first_baseline_v_v0() - glyph.v, font)
+        })
+        .collect()
+}
+
+fn font_v0(name: &str, size_sp: i32) -> String {
+    format!("{name}@{size_sp}")
+}
+
+#[test]
+fn inline_math_spaces_atoms_by_their_types() {
+    let rm = font_v0("carreltex-v0", 0);
+    let mi = font_v0("carreltex-v0-mi", 655_360);
+    let sy = font_v0("carreltex-v0-sy", 655_360);
+    let document = document_v0(b"a $x+1$ b");
+    assert_eq!(
+        glyphs_v0(&document)[3..6],
+        [
+            (u32::from(b'x'), 131_072, 0, mi.clone()),
+            (u32::from(b'+'), 131_072 + 65_536 + 14_560, 0, rm.clone()),
+            (
+                u32::from(b'1'),
+                131_072 + 2 * (65_536 + 14_560),
+                0,
+                rm.clone()
+            ),
+        ]
+    );
+    assert_eq!(glyphs_v0(&document)[7].1, 389_568);
+
+    let document = document_v0(b"\\(\\alpha\\le-\\beta\\)");
+    assert_eq!(
+        glyphs_v0(&document)[1..],
+        [
+            (u32::from(b'a'), 32_768, 0, sy.clone()),
+            (0xa3, 32_768 + 65_536 + 18_200, 0, sy.clone()),
+            (
+                u32::from(b'-'),
+                32_768 + 2 * 65_536 + 2 * 18_200,
+                0,
+                sy.clone()
+            ),
+            (u32::from(b'b'), 32_768 + 3 * 65_536 + 2 * 18_200, 0, sy),
+            (u32::from(b' '), 32_768 + 4 * 65_536 + 2 * 18_200, 0, rm),
+        ]
+    );
+}
+
+#[test]
+fn scripts_are_set_in_script_size_one_over_the_other() {
+    let document = document_v0(b"$x^2_i$ $\\mathrm{e}^{-x}$");
+    let glyphs = glyphs_v0(&document);
+    assert_eq!(
+        glyphs[1..4],
+        [
+            (
+                u32::from(b'x'),
+                32_768,
+                0,
+                font_v0("carreltex-v0-mi", 655_360)
+            ),
+            (
+                u32::from(b'2'),
+                98_304,
+                23_782,
+                font_v0("carreltex-v0-rm-m-n", 458_752)
+            ),
+            (
+                u32::from(b'i'),
+                98_304,
+                -16_201,
+                font_v0("carreltex-v0-mi", 458_752)
+            ),
+        ]
+    );
+    assert_eq!(glyphs[4].1, 98_304 + 45_875 + 3_276);
+    assert_eq!(glyphs[5].0, u32::from(b'e'));
+    assert_eq!(glyphs[5].3, font_v0("carreltex-v0", 0));
+    assert!(glyphs[6..8].iter().all(|glyph| glyph.2 == 23_782));
+}
+
+#[test]
+fn fractions_and_radicals_draw_their_rules() {
+    let document = document_v0(b"$\\frac{a}{b}$");
+    let glyphs = glyphs_v0(&document);
+    assert_eq!((glyphs[1].1, glyphs[1].2), (32_768 + 7_864, 25_803));
+    assert_eq!((glyphs[2].1, glyphs[2].2), (32_768 + 7_864, -22_599));
+    let rule = document.pages[0].rules[0];
+    assert_eq!(
+        (
+            rule.h,
+            first_baseline_v_v0() - rule.v,
+            rule.width,
+            rule.height
+        ),
+        (40_632, 16_384 - 1_310, 45_875, 2_621)
+    );
+    assert_eq!(glyphs[3].1, 40_632 + 45_875 + 7_864);
+
+    let document = document_v0(b"$\\sqrt{x}$");
+    let glyphs = glyphs_v0(&document);
+    assert_eq!(glyphs[1].0, 0xd6);
+    assert_eq!(glyphs[2].1, 98_304);
+    let rule = document.pages[0].rules[0];
+    assert_eq!(
+        (rule.h, first_baseline_v_v0() - rule.v, rule.width),
+        (98_304, glyphs[1].2 + 60_096, 65_536)
+    );
+}
+
+#[test]
+fn display_math_is_centred_on_its_own_line_with_limits() {
+    let expected = [
+        (0xe5, 2_553_720, -6_553, font_v0("carreltex-v0-ex", 917_504)),
+        (
+            u32::from(b'n'),
+            2_576_658,
+            75_366,
+            font_v0("carreltex-v0-mi", 458_752),
+        ),
+        (
+            u32::from(b'i'),
+            2_542_252,
+            -69_975,
+            font_v0("carreltex-v0-mi", 458_752),
+        ),
+    ];
+    for body in [
+        b"\\[\\sum_{i=1}^n i\\]".as_slice(),
+        b"$$\\sum_{i=1}^n i$$",
+        b"\\begin{displaymath}\\sum_{i=1}^n i\\end{displaymath}",
+    ] {
+        assert_eq!(glyphs_v0(&document_v0(body))[..3], expected);
+    }
+    let glyphs = glyphs_v0(&document_v0(b"$\\sum_{i=1}^n i$"));
+    assert_eq!(glyphs[1].3, font_v0("carreltex-v0-ex", 655_360));
+    assert!(glyphs[2].1 > glyphs[1].1 && glyphs[2].2 > 0);
+
+    let glyphs = glyphs_v0(&document_v0(b"a\\[x\\]b"));
+    let line_sp = 786_432;
+    assert_eq!(
+        glyphs[1..4]
+            .iter()
+            .map(|glyph| (glyph.0, glyph.1, glyph.2))
+            .collect::<Vec<_>>(),
+        [
+            (u32::from(b'a'), 32_768, 0),
+            (u32::from(b'x'), (5_242_880 - 65_536) / 2, -line_sp),
+            (u32::from(b'b'), 0, -2 * line_sp),
+        ]
+    );
+}
+
+#[test]
+fn math_outside_the_subset_fails_closed() {
+    for body in [
+        b"$x".as_slice(),
+        b"$x$$",
+        b"\\(x$",
+        b"$\\left( x \\right)$",
+        b"$\\sqrt[3]{x}$",
+        b"$\\frac{a}$",
+        b"$\\limits$",
+        b"$x^$",
+        b"$\\mbox{x}$",
+    ] {
+        let result = compile_body_v0(body);
+        assert_eq!(
+            result.status,
+            CompileStatus::NotImplemented,
+            "{}",
+            String::from_utf8_lossy(body)
+        );
+    }
+    for body in [b"$x^2^3$".as_slice(), b"$x_1_2$", b"$x_{1}^2_3$"] {
+        let result = compile_body_v0(body);
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(result.log_bytes, b"INVALID_INPUT: math_double_script");
+    }
+}
@@ -0,0 +1,107 @@
+use crate::tex::tokenize_v0::TokenV0;
+
+pub(crate) fn skip_spaces(tokens: &[TokenV0], mut index: usize) -> usize {
+    while matches!(tokens.get(index), Some(TokenV0::Space)) {
+        index += 1;
+    }
+    index
+}
+
+pub(crate) fn consume_group_literal(
+    tokens: &[TokenV0],
+    mut index: usize,
+    literal: &[u8],
+) -> Option<usize> {
+    if !matches!(tokens.get(index), Some(TokenV0::BeginGroup)) {
+        return None;
+    }
+    index += 1;
+    for expected in literal {
+        if !matches!(tokens.get(index), Some(TokenV0::Char(value)) if value == expected) {
+            return None;
+        }
+        index += 1;
+    }
+    if !matches!(tokens.get(index), Some(TokenV0::EndGroup)) {
+        return None;
+    }
+    Some(index + 1)
+}
+
+/// Payload bytes of `\special{...}` as left by the macro engine: characters,
+/// spaces and balanced braces.
+pub(crate) fn consume_special_payload_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+) -> Option<(Vec<u8>, usize)> {
+    if !matches!(tokens.get(index), Some(TokenV0::BeginGroup)) {
+        return None;
+    }
+    index += 1;
+    let mut depth = 1usize;
+    let mut payload = Vec::<u8>::new();
+    loop {
+        match tokens.get(index)? {
+            TokenV0::Char(byte) => payload.push(*byte),
+            TokenV0::Space => payload.push(b' '),
+            TokenV0::BeginGroup => {
+                depth += 1;
+                payload.push(b'{');
+            }
+            TokenV0::EndGroup => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((payload, index + 1));
+                }
+                payload.push(b'}');
+            }
+            _ => return None,
+        }
+        index += 1;
+    }
+}
+
+/// Bytes of a `{...}` argument made of characters and spaces only.
+pub(crate) fn consume_group_bytes_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+) -> Option<(Vec<u8>, usize)> {
+    if !matches!(tokens.get(index), Some(TokenV0::BeginGroup)) {
+        return None;
+    }
+    index += 1;
+    let mut bytes = Vec::<u8>::new();
+    loop {
+        match tokens.get(index)? {
+            TokenV0::Char(byte) => bytes.push(*byte),
+            TokenV0::Space => bytes.push(b' '),
+            TokenV0::EndGroup => return Some((bytes, index + 1)),
+            _ => return None,
+        }
+        index += 1;
+    }
+}
+
+/// Bytes of an optional `[...]` argument made of characters only.
+pub(crate) fn consume_optional_bytes_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+) -> Option<(Option<Vec<u8>>, usize)> {
+    if !matches!(tokens.get(index), Some(TokenV0::Char(b'['))) {
+        return Some((None, index));
+    }
+    index += 1;
+    let mut bytes = Vec::<u8>::new();
+    loop {
+        match tokens.get(index)? {
+            TokenV0::Char(b']') => return Some((Some(bytes), index + 1)),
+            TokenV0::Char(byte) => bytes.push(*byte),
+            _ => return None,
+        }
+        index += 1;
+    }
+}
+
+pub(crate) fn is_control_seq_v0(token: Option<&TokenV0>, expected: &[u8]) -> bool {
+    matches!(token, Some(TokenV0::ControlSeq(name)) if name.as_slice() == expected)
+}
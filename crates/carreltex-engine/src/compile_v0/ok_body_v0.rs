@@ -0,0 +1,107 @@
+use super::ok_cite_v0::OkCitationsV0;
+use super::ok_dimen_v0::OkLayoutV0;
+use super::ok_float_v0::OkFloatEntryV0;
+use super::ok_label_v0::{OkLabelV0, OkUndefinedRefV0};
+use super::ok_page_v0::OkPageCommandV0;
+use super::ok_section_v0::SectionLevelV0;
+use super::ok_toc_v0::OkTocEntryV0;
+use carreltex_xdv::{
+    DviTextFillV0, DviTextFloatV0, DviTextFontSwitchV0, DviTextFontV0, DviTextFootnoteV0,
+    DviTextFootnotesV0, DviTextIndentV0, DviTextKernV0, DviTextMarksV0, DviTextPagesV0,
+    DviTextRaiseV0, DviTextRuleV0, DviTextSpecialV0,
+};
+
+/// Text of the strict OK subset plus the specials, rules, indents, font switches,
+/// fills, raises, kerns, floats, footnotes, labels, unresolved references, headings,
+/// captions and page commands anchored in it, the fonts switched to, the footnote rule, the level
+/// and font of `headings` marks, the page layout fitted to the paper, what the `.aux` file records for BibTeX and
+/// the class options no one used.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct OkBodyV0 {
+    pub(crate) text: Vec<u8>,
+    pub(crate) specials: Vec<DviTextSpecialV0>,
+    pub(crate) rules: Vec<DviTextRuleV0>,
+    pub(crate) indents: Vec<DviTextIndentV0>,
+    pub(crate) font_switches: Vec<DviTextFontSwitchV0>,
+    pub(crate) fonts: Vec<DviTextFontV0>,
+    pub(crate) fills: Vec<DviTextFillV0>,
+    pub(crate) raises: Vec<DviTextRaiseV0>,
+    pub(crate) kerns: Vec<DviTextKernV0>,
+    pub(crate) floats: Vec<DviTextFloatV0>,
+    pub(crate) footnotes: Vec<DviTextFootnoteV0>,
+    pub(crate) footnote_rule: (i32, i32),
+    pub(crate) labels: Vec<OkLabelV0>,
+    pub(crate) undefined_refs: Vec<OkUndefinedRefV0>,
+    pub(crate) toc_entries: Vec<OkTocEntryV0>,
+    pub(crate) float_entries: Vec<OkFloatEntryV0>,
+    pub(crate) page_commands: Vec<OkPageCommandV0>,
+    pub(crate) mark_level: SectionLevelV0,
+    pub(crate) mark_font: usize,
+    pub(crate) layout: OkLayoutV0,
+    pub(crate) citations: OkCitationsV0,
+    pub(crate) unused_class_options: Vec<Vec<u8>>,
+}
+
+impl OkBodyV0 {
+    pub(crate) fn new_v0(footnote_rule: (i32, i32), layout: OkLayoutV0) -> Self {
+        OkBodyV0 {
+            text: Vec::new(),
+            specials: Vec::new(),
+            rules: Vec::new(),
+            indents: Vec::new(),
+            font_switches: Vec::new(),
+            fonts: Vec::new(),
+            fills: Vec::new(),
+            raises: Vec::new(),
+            kerns: Vec::new(),
+            floats: Vec::new(),
+            footnotes: Vec::new(),
+            footnote_rule,
+            labels: Vec::new(),
+            undefined_refs: Vec::new(),
+            toc_entries: Vec::new(),
+            float_entries: Vec::new(),
+            page_commands: Vec::new(),
+            mark_level: SectionLevelV0::Section,
+            mark_font: 0,
+            layout,
+            citations: OkCitationsV0::default(),
+            unused_class_options: Vec::new(),
+        }
+    }
+
+    pub(crate) fn marks_v0(&self) -> DviTextMarksV0<'_> {
+        DviTextMarksV0 {
+            specials: &self.specials,
+            rules: &self.rules,
+            indents: &self.indents,
+            fonts: &self.fonts,
+            font_switches: &self.font_switches,
+            fills: &self.fills,
+            raises: &self.raises,
+            kerns: &self.kerns,
+            floats: &self.floats,
+            footnotes: DviTextFootnotesV0 {
+                notes: &self.footnotes,
+                rule_width: self.footnote_rule.0,
+                rule_height: self.footnote_rule.1,
+            },
+            pages: DviTextPagesV0::default(),
+        }
+    }
+
+    /// Glyphs pushed from here on use font number `font`.
+    pub(crate) fn switch_font_v0(&mut self, font: usize) {
+        let offset = self.text.len();
+        let switches = &mut self.font_switches;
+        if switches
+            .last()
+            .is_some_and(|switch| switch.offset == offset)
+        {
+            switches.pop();
+        }
+        if switches.last().map_or(0, |switch| switch.font) != font {
+            switches.push(DviTextFontSwitchV0 { offset, font });
+        }
+    }
+}
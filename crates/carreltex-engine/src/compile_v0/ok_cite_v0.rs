@@ -0,0 +1,138 @@
+use super::bib_v0::{write_bbl_v0, BibStyleV0};
+use super::ok_args_v0::{consume_group_bytes_v0, is_control_seq_v0};
+use super::ok_list_v0::consume_item_label_v0;
+use super::tokenize_reason_v0::map_tokenize_error_to_reason_v0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::{tokenize_v0, TokenV0};
+use carreltex_core::Mount;
+
+/// One `\bibcite{key}{label}` line of the `.aux` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AuxBibciteV0 {
+    pub(crate) key: Vec<u8>,
+    pub(crate) label: Vec<u8>,
+}
+
+/// What a pass writes to the `.aux` file for BibTeX: the keys of `\cite` and
+/// `\nocite` in order, the `\bibliographystyle` and `\bibliography` arguments,
+/// and the labels of the `\bibitem`s typeset.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct OkCitationsV0 {
+    pub(crate) keys: Vec<Vec<u8>>,
+    pub(crate) style: Option<Vec<u8>>,
+    pub(crate) data: Option<Vec<u8>>,
+    pub(crate) bibcites: Vec<AuxBibciteV0>,
+}
+
+/// The `.bbl` file written for the document and its tokens, which
+/// `\bibliography` reads in.
+#[derive(Clone)]
+pub(crate) struct OkBibliographyV0 {
+    pub(crate) bbl: Vec<u8>,
+    pub(crate) tokens: Vec<TokenV0>,
+}
+
+/// The label a `\cite` resolves to; a key cited more than once keeps its last
+/// `\bibcite`, as LaTeX's does.
+pub(crate) fn find_bibcite_v0<'a>(
+    bibcites: &'a [AuxBibciteV0],
+    key: &[u8],
+) -> Option<&'a AuxBibciteV0> {
+    bibcites.iter().rev().find(|bibcite| bibcite.key == key)
+}
+
+/// The comma-separated keys of a `\cite` or `\nocite` argument, spaces trimmed.
+pub(crate) fn split_cite_keys_v0(argument: &[u8]) -> Vec<Vec<u8>> {
+    argument
+        .split(|byte| *byte == b',')
+        .map(|key| key.trim_ascii().to_vec())
+        .filter(|key| !key.is_empty())
+        .collect()
+}
+
+/// `\citation`, `\bibstyle` and `\bibdata` lines, then one `\bibcite` per
+/// `\bibitem`.
+pub(crate) fn write_citations_aux_v0(citations: &OkCitationsV0) -> Vec<u8> {
+    let mut aux = Vec::<u8>::new();
+    let mut line = |command: &[u8], argument: &[u8]| {
+        aux.extend_from_slice(&[b"\\", command, b"{", argument, b"}\n"].concat());
+    };
+    for key in &citations.keys {
+        line(b"citation", key);
+    }
+    if let Some(style) = &citations.style {
+        line(b"bibstyle", style);
+    }
+    if let Some(data) = &citations.data {
+        line(b"bibdata", data);
+    }
+    for bibcite in &citations.bibcites {
+        line(
+            b"bibcite",
+            &[bibcite.key.as_slice(), b"}{", &bibcite.label].concat(),
+        );
+    }
+    aux
+}
+
+/// Runs the built-in BibTeX over the citations of `tokens` when they hold one
+/// `\bibliography{names}`: the `.bib` files come from the mount, `.bib` added to
+/// names without it. `None` without a `\bibliography`, with more than one, or
+/// with a style outside the standard four; the body then finds the document
+/// outside the subset.
+pub(crate) fn mounted_bibliography_v0(
+    tokens: &[TokenV0],
+    mount: &Mount,
+) -> Option<Result<OkBibliographyV0, InvalidInputReasonV0>> {
+    let mut citations = Vec::<Vec<u8>>::new();
+    let mut style = None;
+    let mut data = None;
+    for index in 0..tokens.len() {
+        let argument = |index: usize| consume_group_bytes_v0(tokens, index).map(|(bytes, _)| bytes);
+        if is_control_seq_v0(tokens.get(index), b"cite") {
+            let next_index =
+                consume_item_label_v0(tokens, index + 1).map_or(index + 1, |(_, at)| at);
+            citations.extend(
+                argument(next_index)
+                    .iter()
+                    .flat_map(|keys| split_cite_keys_v0(keys)),
+            );
+        } else if is_control_seq_v0(tokens.get(index), b"nocite") {
+            citations.extend(
+                argument(index + 1)
+                    .iter()
+                    .flat_map(|keys| split_cite_keys_v0(keys)),
+            );
+        } else if is_control_seq_v0(tokens.get(index), b"bibliographystyle") {
+            style = argument(index + 1);
+        } else if is_control_seq_v0(tokens.get(index), b"bibliography") {
+            if data.is_some() {
+                return None;
+            }
+            data = Some(argument(index + 1)?);
+        }
+    }
+    let data = data?;
+    let Some(style) = style else {
+        return Some(Err(InvalidInputReasonV0::BibStyleMissing));
+    };
+    let style = BibStyleV0::from_name_v0(&style)?;
+    let mut databases = Vec::<&[u8]>::new();
+    for name in data.split(|byte| *byte == b',') {
+        let name = name.trim_ascii();
+        let path = if name.ends_with(b".bib") {
+            name.to_vec()
+        } else {
+            [name, b".bib"].concat()
+        };
+        match mount.read_file_by_bytes_v0(&path) {
+            Ok(Some(bytes)) => databases.push(bytes),
+            _ => return Some(Err(InvalidInputReasonV0::BibFileMissing)),
+        }
+    }
+    let bibliography = write_bbl_v0(style, &citations, &databases).and_then(|bbl| {
+        let tokens = tokenize_v0(&bbl).map_err(map_tokenize_error_to_reason_v0)?;
+        Ok(OkBibliographyV0 { bbl, tokens })
+    });
+    Some(bibliography)
+}
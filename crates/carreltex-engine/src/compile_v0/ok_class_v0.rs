@@ -0,0 +1,274 @@
+use super::ok_args_v0::{consume_group_bytes_v0, consume_optional_bytes_v0, is_control_seq_v0};
+use super::ok_dimen_v0::OkLayoutV0;
+use super::ok_font_v0::{BaseSizeV0, OkFontV0};
+use super::ok_geometry_v0::apply_geometry_options_v0;
+use super::ok_section_v0::{heading_text_v0, SectionLevelV0};
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+/// Beamer's 128mm x 96mm slides with 1cm side margins, as it hands them to
+/// `geometry`.
+const BEAMER_GEOMETRY_V0: &[u8] = b"paperwidth=12.8cm,paperheight=9.6cm,hmargin=1cm,vmargin=0cm";
+
+/// The classes `\documentclass` may name: the standard four and a `beamer` subset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DocumentClassV0 {
+    Article,
+    Report,
+    Book,
+    Letter,
+    Beamer,
+}
+
+impl DocumentClassV0 {
+    pub(crate) fn from_name_v0(name: &[u8]) -> Option<Self> {
+        match name {
+            b"article" => Some(Self::Article),
+            b"report" => Some(Self::Report),
+            b"book" => Some(Self::Book),
+            b"letter" => Some(Self::Letter),
+            b"beamer" => Some(Self::Beamer),
+            _ => None,
+        }
+    }
+
+    /// Report and book have `\chapter`, and number sections, equations and floats
+    /// within chapters.
+    pub(crate) fn has_chapters_v0(self) -> bool {
+        matches!(self, Self::Report | Self::Book)
+    }
+
+    /// Whether the class defines the sectioning command of `level`; letter and the
+    /// beamer subset define none.
+    pub(crate) fn has_level_v0(self, level: SectionLevelV0) -> bool {
+        match self {
+            Self::Article => level != SectionLevelV0::Chapter,
+            Self::Report | Self::Book => true,
+            Self::Letter | Self::Beamer => false,
+        }
+    }
+
+    /// Heading of `thebibliography`: `\refname` in article, `\bibname` with chapters.
+    pub(crate) fn bibliography_name_v0(self) -> &'static [u8] {
+        if self.has_chapters_v0() {
+            b"Bibliography"
+        } else {
+            b"References"
+        }
+    }
+}
+
+/// The document class and the base font size its options chose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OkClassV0 {
+    pub(crate) class: DocumentClassV0,
+    pub(crate) base_size: BaseSizeV0,
+}
+
+impl OkClassV0 {
+    pub(crate) fn normal_font_v0(&self) -> OkFontV0 {
+        OkFontV0::normal_v0(self.base_size)
+    }
+
+    /// One option the class applies: a base size, a paper size, or an option the
+    /// layout already follows. `landscape` is noted in `landscape`, since it
+    /// applies after every other option.
+    fn apply_option_v0(
+        &mut self,
+        option: &[u8],
+        landscape: &mut bool,
+        layout: &mut OkLayoutV0,
+    ) -> Option<()> {
+        if let Some(base_size) = BaseSizeV0::from_option_v0(option) {
+            self.base_size = base_size;
+            return Some(());
+        }
+        match option {
+            b"landscape" => *landscape = true,
+            b"oneside" | b"onecolumn" | b"final" | b"openany" => {}
+            _ => layout.geometry.paper_option_v0(option)?,
+        }
+        Some(())
+    }
+}
+
+/// How a class takes one of its options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClassOptionV0 {
+    /// A base size, a paper size, `landscape`, or an option the one-sided,
+    /// one-column layout already follows.
+    Applied,
+    /// An option the class declares that the layout does not follow, such as
+    /// `twocolumn`, `twoside`, `draft` or `openright`; beamer takes only sizes.
+    NotSupported,
+    /// An option the class does not declare, which LaTeX passes on to packages
+    /// and reports when none uses it.
+    Unused,
+}
+
+impl DocumentClassV0 {
+    /// How the class takes `option`, as its `.cls` declares them: letter has no
+    /// column, title page or chapter options, and only report and book `openany`
+    /// and `openright`.
+    fn option_v0(self, option: &[u8]) -> ClassOptionV0 {
+        if BaseSizeV0::from_option_v0(option).is_some() {
+            return ClassOptionV0::Applied;
+        }
+        match (self, option) {
+            (Self::Beamer, _) => ClassOptionV0::NotSupported,
+            (_, b"a4paper" | b"letterpaper" | b"landscape" | b"oneside" | b"final") => {
+                ClassOptionV0::Applied
+            }
+            (
+                _,
+                b"a5paper" | b"b5paper" | b"legalpaper" | b"executivepaper" | b"twoside" | b"draft"
+                | b"leqno" | b"fleqn",
+            ) => ClassOptionV0::NotSupported,
+            (Self::Letter, _) => ClassOptionV0::Unused,
+            (_, b"onecolumn") => ClassOptionV0::Applied,
+            (_, b"twocolumn" | b"titlepage" | b"notitlepage" | b"openbib") => {
+                ClassOptionV0::NotSupported
+            }
+            (Self::Report | Self::Book, b"openany") => ClassOptionV0::Applied,
+            (Self::Report | Self::Book, b"openright") => ClassOptionV0::NotSupported,
+            _ => ClassOptionV0::Unused,
+        }
+    }
+}
+
+/// The `\documentclass[options]{class}` that starts `tokens`, with `layout` set up
+/// for it: the class's paper and margins, then its options. An unknown class is
+/// `ClassNotAvailable`, a declared option outside the subset
+/// `ClassOptionNotSupported`; options the class does not declare are left out.
+pub(crate) fn consume_document_class_v0(
+    tokens: &[TokenV0],
+    layout: &mut OkLayoutV0,
+) -> Result<Option<(OkClassV0, usize)>, InvalidInputReasonV0> {
+    let Some((options, name, index)) = consume_document_class_parts_v0(tokens) else {
+        return Ok(None);
+    };
+    let class =
+        DocumentClassV0::from_name_v0(&name).ok_or(InvalidInputReasonV0::ClassNotAvailable)?;
+    let mut class = OkClassV0 {
+        class,
+        base_size: BaseSizeV0::Ten,
+    };
+    if class.class == DocumentClassV0::Beamer {
+        class.base_size = BaseSizeV0::Eleven;
+        if apply_geometry_options_v0(BEAMER_GEOMETRY_V0, layout)?.is_none() {
+            return Ok(None);
+        }
+    }
+    let mut landscape = false;
+    for option in class_options_v0(&options) {
+        match class.class.option_v0(option) {
+            ClassOptionV0::Applied => {
+                if class
+                    .apply_option_v0(option, &mut landscape, layout)
+                    .is_none()
+                {
+                    return Ok(None);
+                }
+            }
+            ClassOptionV0::NotSupported => {
+                return Err(InvalidInputReasonV0::ClassOptionNotSupported)
+            }
+            ClassOptionV0::Unused => {}
+        }
+    }
+    if landscape {
+        let geometry = &mut layout.geometry;
+        std::mem::swap(&mut geometry.paper_width_sp, &mut geometry.paper_height_sp);
+    }
+    Ok(Some((class, index)))
+}
+
+/// The options of the `\documentclass` that starts `tokens` that its class does
+/// not declare, in order.
+pub(crate) fn unused_class_options_v0(tokens: &[TokenV0]) -> Vec<Vec<u8>> {
+    let Some((options, name, _)) = consume_document_class_parts_v0(tokens) else {
+        return Vec::new();
+    };
+    let Some(class) = DocumentClassV0::from_name_v0(&name) else {
+        return Vec::new();
+    };
+    class_options_v0(&options)
+        .filter(|option| class.option_v0(option) == ClassOptionV0::Unused)
+        .map(<[u8]>::to_vec)
+        .collect()
+}
+
+/// The reason `\documentclass` fails with and what it names: a class that is not
+/// available, or the first option of an available class outside the subset.
+pub(crate) fn class_failure_v0(tokens: &[TokenV0]) -> Option<(InvalidInputReasonV0, Vec<u8>)> {
+    let (options, name, _) = consume_document_class_parts_v0(tokens)?;
+    let Some(class) = DocumentClassV0::from_name_v0(&name) else {
+        return Some((InvalidInputReasonV0::ClassNotAvailable, name));
+    };
+    let option = class_options_v0(&options)
+        .find(|option| class.option_v0(option) == ClassOptionV0::NotSupported)?;
+    Some((
+        InvalidInputReasonV0::ClassOptionNotSupported,
+        option.to_vec(),
+    ))
+}
+
+/// The non-empty options of a comma-separated list, spaces trimmed.
+fn class_options_v0(options: &Option<Vec<u8>>) -> impl Iterator<Item = &[u8]> {
+    options
+        .iter()
+        .flat_map(|options| options.split(|byte| *byte == b','))
+        .map(|option| option.trim_ascii())
+        .filter(|option| !option.is_empty())
+}
+
+fn consume_document_class_parts_v0(
+    tokens: &[TokenV0],
+) -> Option<(Option<Vec<u8>>, Vec<u8>, usize)> {
+    if !is_control_seq_v0(tokens.first(), b"documentclass") {
+        return None;
+    }
+    let (options, index) = consume_optional_bytes_v0(tokens, 1)?;
+    let (name, index) = consume_group_bytes_v0(tokens, index)?;
+    Some((options, name.trim_ascii().to_vec(), index))
+}
+
+/// Letter's return address, signature and date, declared in the preamble, and the
+/// recipient of the open `letter` environment, each as lines.
+#[derive(Debug, Default)]
+pub(crate) struct OkLetterV0 {
+    pub(crate) address: Vec<Vec<u8>>,
+    pub(crate) signature: Vec<Vec<u8>>,
+    pub(crate) date: Vec<Vec<u8>>,
+    pub(crate) recipient: Vec<Vec<u8>>,
+}
+
+/// A `{...}` argument of lines separated by `\\`, spaces collapsed as in headings.
+/// `None` when it holds anything else.
+pub(crate) fn consume_letter_lines_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+) -> Option<(Vec<Vec<u8>>, usize)> {
+    if !matches!(tokens.get(index), Some(TokenV0::BeginGroup)) {
+        return None;
+    }
+    index += 1;
+    let mut lines = Vec::<Vec<u8>>::new();
+    let mut line = Vec::<u8>::new();
+    loop {
+        match tokens.get(index)? {
+            TokenV0::Char(byte) => line.push(*byte),
+            TokenV0::Space => line.push(b' '),
+            TokenV0::ControlSeq(name) if name.as_slice() == b"\\" => {
+                lines.push(heading_text_v0(None, &line)?);
+                line.clear();
+            }
+            TokenV0::EndGroup => break,
+            _ => return None,
+        }
+        index += 1;
+    }
+    lines.push(heading_text_v0(None, &line)?);
+    lines.retain(|line| !line.is_empty());
+    Some((lines, index + 1))
+}
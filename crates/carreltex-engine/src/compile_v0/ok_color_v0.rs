@@ -0,0 +1,238 @@
+use std::collections::BTreeMap;
+
+use crate::reasons_v0::InvalidInputReasonV0;
+
+pub(crate) const MAX_DEFINED_COLORS_V0: usize = 1024;
+/// Color components are fixed-point in units of 1/10000.
+const COLOR_UNIT_V0: u32 = 10_000;
+
+/// An xcolor value in one of the models dvips color specials understand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColorV0 {
+    Gray(u32),
+    Rgb([u32; 3]),
+    Cmyk([u32; 4]),
+}
+
+impl ColorV0 {
+    fn to_rgb_v0(self) -> [u32; 3] {
+        match self {
+            ColorV0::Gray(value) => [value; 3],
+            ColorV0::Rgb(rgb) => rgb,
+            ColorV0::Cmyk([c, m, y, k]) => [c, m, y]
+                .map(|component| (COLOR_UNIT_V0 - component) * (COLOR_UNIT_V0 - k) / COLOR_UNIT_V0),
+        }
+    }
+
+    /// `color push <model> <components>` special payload.
+    pub(crate) fn push_special_v0(self) -> Vec<u8> {
+        let (model, components) = match self {
+            ColorV0::Gray(value) => ("gray", vec![value]),
+            ColorV0::Rgb(rgb) => ("rgb", rgb.to_vec()),
+            ColorV0::Cmyk(cmyk) => ("cmyk", cmyk.to_vec()),
+        };
+        let mut out = format!("color push {model}");
+        for component in components {
+            out.push(' ');
+            out.push_str(&format_component_v0(component));
+        }
+        out.into_bytes()
+    }
+}
+
+pub(crate) const COLOR_POP_SPECIAL_V0: &[u8] = b"color pop";
+
+fn format_component_v0(value: u32) -> String {
+    let whole = value / COLOR_UNIT_V0;
+    let fraction = value % COLOR_UNIT_V0;
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let digits = format!("{fraction:04}");
+    format!("{whole}.{}", digits.trim_end_matches('0'))
+}
+
+/// xcolor's base color names.
+fn named_color_v0(name: &[u8]) -> Option<ColorV0> {
+    let rgb = |r: u32, g: u32, b: u32| Some(ColorV0::Rgb([r, g, b]));
+    match name {
+        b"black" => Some(ColorV0::Gray(0)),
+        b"white" => Some(ColorV0::Gray(COLOR_UNIT_V0)),
+        b"gray" => Some(ColorV0::Gray(5_000)),
+        b"darkgray" => Some(ColorV0::Gray(2_500)),
+        b"lightgray" => Some(ColorV0::Gray(7_500)),
+        b"red" => rgb(10_000, 0, 0),
+        b"green" => rgb(0, 10_000, 0),
+        b"blue" => rgb(0, 0, 10_000),
+        b"cyan" => Some(ColorV0::Cmyk([10_000, 0, 0, 0])),
+        b"magenta" => Some(ColorV0::Cmyk([0, 10_000, 0, 0])),
+        b"yellow" => Some(ColorV0::Cmyk([0, 0, 10_000, 0])),
+        b"brown" => rgb(7_500, 5_000, 2_500),
+        b"lime" => rgb(7_500, 10_000, 0),
+        b"olive" => rgb(5_000, 5_000, 0),
+        b"orange" => rgb(10_000, 5_000, 0),
+        b"pink" => rgb(10_000, 7_500, 7_500),
+        b"purple" => rgb(7_500, 0, 2_500),
+        b"teal" => rgb(0, 5_000, 5_000),
+        b"violet" => rgb(5_000, 0, 5_000),
+        _ => None,
+    }
+}
+
+/// Decimal in `0..=1` (`1`, `0.25`, `.5`), rounded to `COLOR_UNIT_V0`.
+fn parse_unit_decimal_v0(bytes: &[u8]) -> Option<u32> {
+    let (whole, fraction) = match bytes.iter().position(|byte| *byte == b'.') {
+        Some(dot) => (&bytes[..dot], &bytes[dot + 1..]),
+        None => (bytes, &[][..]),
+    };
+    if (whole.is_empty() && fraction.is_empty())
+        || whole.len() > 1
+        || fraction.len() > 8
+        || !whole.iter().chain(fraction).all(u8::is_ascii_digit)
+    {
+        return None;
+    }
+    let mut scaled = whole
+        .first()
+        .map(|digit| (digit - b'0') as u64)
+        .unwrap_or(0);
+    let mut denominator = 1u64;
+    for digit in fraction {
+        scaled = scaled * 10 + (digit - b'0') as u64;
+        denominator *= 10;
+    }
+    let value = (scaled * COLOR_UNIT_V0 as u64 + denominator / 2) / denominator;
+    u32::try_from(value)
+        .ok()
+        .filter(|value| *value <= COLOR_UNIT_V0)
+}
+
+fn parse_byte_component_v0(bytes: &[u8]) -> Option<u32> {
+    if bytes.is_empty() || bytes.len() > 3 || !bytes.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let value = bytes
+        .iter()
+        .fold(0u32, |value, digit| value * 10 + (digit - b'0') as u32);
+    (value <= 255).then(|| (value * COLOR_UNIT_V0 + 127) / 255)
+}
+
+/// Color from an explicit model and specification, as in `\definecolor{name}{rgb}{1,0,0}`
+/// or `\color[gray]{0.5}`; models are `gray`, `rgb`, `RGB`, `cmyk` and `HTML`.
+pub(crate) fn parse_color_spec_v0(
+    model: &[u8],
+    spec: &[u8],
+) -> Result<ColorV0, InvalidInputReasonV0> {
+    let spec = spec
+        .iter()
+        .copied()
+        .filter(|byte| *byte != b' ')
+        .collect::<Vec<_>>();
+    let components = spec.split(|byte| *byte == b',').collect::<Vec<_>>();
+    let parse_all = |parse: fn(&[u8]) -> Option<u32>| {
+        components
+            .iter()
+            .map(|component| parse(component))
+            .collect::<Option<Vec<_>>>()
+    };
+    let color = match model {
+        b"gray" => parse_all(parse_unit_decimal_v0)
+            .filter(|values| values.len() == 1)
+            .map(|values| ColorV0::Gray(values[0])),
+        b"rgb" => parse_all(parse_unit_decimal_v0)
+            .filter(|values| values.len() == 3)
+            .map(|values| ColorV0::Rgb([values[0], values[1], values[2]])),
+        b"RGB" => parse_all(parse_byte_component_v0)
+            .filter(|values| values.len() == 3)
+            .map(|values| ColorV0::Rgb([values[0], values[1], values[2]])),
+        b"cmyk" => parse_all(parse_unit_decimal_v0)
+            .filter(|values| values.len() == 4)
+            .map(|values| ColorV0::Cmyk([values[0], values[1], values[2], values[3]])),
+        b"HTML" if spec.len() == 6 => {
+            let hex = spec
+                .chunks(2)
+                .map(|pair| {
+                    core::str::from_utf8(pair)
+                        .ok()
+                        .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                        .map(|value| (value as u32 * COLOR_UNIT_V0 + 127) / 255)
+                })
+                .collect::<Option<Vec<_>>>();
+            hex.map(|values| ColorV0::Rgb([values[0], values[1], values[2]]))
+        }
+        _ => None,
+    };
+    color.ok_or(InvalidInputReasonV0::ColorSpecInvalid)
+}
+
+fn mix_colors_v0(first: ColorV0, percent: u32, second: ColorV0) -> ColorV0 {
+    let mix = |a: u32, b: u32| (a * percent + b * (100 - percent) + 50) / 100;
+    match (first, second) {
+        (ColorV0::Gray(a), ColorV0::Gray(b)) => ColorV0::Gray(mix(a, b)),
+        (ColorV0::Cmyk(a), ColorV0::Cmyk(b)) => {
+            ColorV0::Cmyk([0, 1, 2, 3].map(|index| mix(a[index], b[index])))
+        }
+        _ => {
+            let (a, b) = (first.to_rgb_v0(), second.to_rgb_v0());
+            ColorV0::Rgb([0, 1, 2].map(|index| mix(a[index], b[index])))
+        }
+    }
+}
+
+/// Colors defined with `\definecolor`, looked up before xcolor's base names.
+#[derive(Default)]
+pub(crate) struct ColorTableV0 {
+    defined: BTreeMap<Vec<u8>, ColorV0>,
+}
+
+impl ColorTableV0 {
+    pub(crate) fn define_v0(
+        &mut self,
+        name: &[u8],
+        color: ColorV0,
+    ) -> Result<(), InvalidInputReasonV0> {
+        if name.is_empty() || !name.iter().all(u8::is_ascii_alphanumeric) {
+            return Err(InvalidInputReasonV0::ColorSpecInvalid);
+        }
+        if !self.defined.contains_key(name) && self.defined.len() >= MAX_DEFINED_COLORS_V0 {
+            return Err(InvalidInputReasonV0::ColorDefinitionsExceeded);
+        }
+        self.defined.insert(name.to_vec(), color);
+        Ok(())
+    }
+
+    fn lookup_v0(&self, name: &[u8]) -> Result<ColorV0, InvalidInputReasonV0> {
+        self.defined
+            .get(name)
+            .copied()
+            .or_else(|| named_color_v0(name))
+            .ok_or(InvalidInputReasonV0::ColorUndefined)
+    }
+
+    /// xcolor expression `name`, `name!pct` (mixed with white) or `name!pct!other`.
+    pub(crate) fn resolve_v0(&self, expression: &[u8]) -> Result<ColorV0, InvalidInputReasonV0> {
+        let parts = expression.split(|byte| *byte == b'!').collect::<Vec<_>>();
+        let first = self.lookup_v0(parts[0])?;
+        if parts.len() == 1 {
+            return Ok(first);
+        }
+        if parts.len() > 3 {
+            return Err(InvalidInputReasonV0::ColorSpecInvalid);
+        }
+        let percent = parts[1];
+        if percent.is_empty() || percent.len() > 3 || !percent.iter().all(u8::is_ascii_digit) {
+            return Err(InvalidInputReasonV0::ColorSpecInvalid);
+        }
+        let percent = percent
+            .iter()
+            .fold(0u32, |value, digit| value * 10 + (digit - b'0') as u32);
+        if percent > 100 {
+            return Err(InvalidInputReasonV0::ColorSpecInvalid);
+        }
+        let second = match parts.get(2) {
+            Some(name) => self.lookup_v0(name)?,
+            None => ColorV0::Gray(COLOR_UNIT_V0),
+        };
+        Ok(mix_colors_v0(first, percent, second))
+    }
+}
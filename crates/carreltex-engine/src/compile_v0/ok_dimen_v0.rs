@@ -0,0 +1,192 @@
+use super::ok_geometry_v0::OkGeometryV0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+const UNITY_SP_V0: i64 = 65_536;
+/// TeX keeps at most this many fraction digits of a decimal constant.
+const MAX_FRACTION_DIGITS_V0: usize = 17;
+/// TeX's `\maxdimen` in scaled points; larger magnitudes are "Dimension too large".
+pub(crate) const MAX_DIMEN_SP_V0: i64 = 0x3fff_ffff;
+/// `ex` as a fraction of `em`, following cmr10 (x-height 4.30554pt, quad 10pt).
+const EX_PER_EM_MILLIONTHS_V0: i64 = 430_554;
+
+/// Lengths behind the font- and page-relative units of the strict OK layout, and
+/// the glyphs per line and lines per page the writer breaks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OkLayoutV0 {
+    pub(crate) em_sp: i32,
+    pub(crate) line_advance_sp: i32,
+    pub(crate) text_width_sp: i32,
+    pub(crate) text_height_sp: i32,
+    pub(crate) max_line_glyphs: usize,
+    pub(crate) max_lines_per_page: usize,
+    pub(crate) geometry: OkGeometryV0,
+}
+
+impl OkLayoutV0 {
+    /// Internal dimensions usable as units, as in `0.5\linewidth`.
+    fn internal_dimen_v0(&self, name: &[u8]) -> Option<i64> {
+        match name {
+            b"linewidth" | b"textwidth" | b"columnwidth" | b"hsize" => {
+                Some(self.text_width_sp as i64)
+            }
+            b"textheight" | b"vsize" => Some(self.text_height_sp as i64),
+            b"baselineskip" => Some(self.line_advance_sp as i64),
+            _ => self.geometry.length_v0(name).map(i64::from),
+        }
+    }
+}
+
+fn char_at_v0(tokens: &[TokenV0], index: usize) -> Option<u8> {
+    match tokens.get(index) {
+        Some(TokenV0::Char(byte)) => Some(*byte),
+        _ => None,
+    }
+}
+
+fn skip_spaces_v0(tokens: &[TokenV0], mut index: usize) -> usize {
+    while matches!(tokens.get(index), Some(TokenV0::Space)) {
+        index += 1;
+    }
+    index
+}
+
+/// Case-insensitive keyword made of character tokens, as TeX's `scan_keyword`.
+pub(crate) fn scan_keyword_v0(tokens: &[TokenV0], index: usize, keyword: &[u8]) -> Option<usize> {
+    let index = skip_spaces_v0(tokens, index);
+    for (offset, expected) in keyword.iter().enumerate() {
+        if !char_at_v0(tokens, index + offset)?.eq_ignore_ascii_case(expected) {
+            return None;
+        }
+    }
+    Some(index + keyword.len())
+}
+
+/// TeX's `round_decimals`: the fraction `0.d1d2...` in units of 2^-16.
+fn round_decimals_v0(digits: &[u8]) -> i64 {
+    let mut value = 0i64;
+    for digit in digits.iter().rev() {
+        value = (value + (*digit - b'0') as i64 * 0x2_0000) / 10;
+    }
+    (value + 1) / 2
+}
+
+/// Physical units with `(numerator, denominator)` in points, from TeX's `scan_dimen`.
+const UNIT_RATIOS_V0: [(&[u8], (i64, i64)); 8] = [
+    (b"pt", (1, 1)),
+    (b"in", (7227, 100)),
+    (b"pc", (12, 1)),
+    (b"cm", (7227, 254)),
+    (b"mm", (7227, 2540)),
+    (b"bp", (7227, 7200)),
+    (b"dd", (1238, 1157)),
+    (b"cc", (14856, 1157)),
+];
+
+/// Dimension such as `-1.5pt`, `2 cm`, `.5\linewidth` or `\baselineskip` starting at
+/// `index`, converted with TeX's rounding. `Ok(None)` when an unknown control
+/// sequence stands where a number or unit belongs (outside the OK subset).
+pub(crate) fn scan_dimen_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+    layout: &OkLayoutV0,
+) -> Result<Option<(i32, usize)>, InvalidInputReasonV0> {
+    let mut negative = false;
+    loop {
+        index = skip_spaces_v0(tokens, index);
+        match char_at_v0(tokens, index) {
+            Some(b'-') => negative = !negative,
+            Some(b'+') => {}
+            _ => break,
+        }
+        index += 1;
+    }
+
+    let mut integer = 0i64;
+    let mut integer_digits = 0usize;
+    while let Some(digit) = char_at_v0(tokens, index).filter(u8::is_ascii_digit) {
+        integer = integer * 10 + (digit - b'0') as i64;
+        if integer > MAX_DIMEN_SP_V0 {
+            return Err(InvalidInputReasonV0::DimenInvalid);
+        }
+        integer_digits += 1;
+        index += 1;
+    }
+    let mut fraction_digits = Vec::<u8>::new();
+    let has_point = matches!(char_at_v0(tokens, index), Some(b'.' | b','));
+    if has_point {
+        index += 1;
+        while let Some(digit) = char_at_v0(tokens, index).filter(u8::is_ascii_digit) {
+            if fraction_digits.len() < MAX_FRACTION_DIGITS_V0 {
+                fraction_digits.push(digit);
+            }
+            index += 1;
+        }
+    }
+    let fraction = round_decimals_v0(&fraction_digits);
+
+    let sp = if integer_digits == 0 && !has_point {
+        let Some(TokenV0::ControlSeq(name)) = tokens.get(index) else {
+            return Err(InvalidInputReasonV0::DimenInvalid);
+        };
+        let Some(internal) = layout.internal_dimen_v0(name) else {
+            return Ok(None);
+        };
+        index += 1;
+        internal
+    } else {
+        index = skip_spaces_v0(tokens, index);
+        let internal = if let Some(TokenV0::ControlSeq(name)) = tokens.get(index) {
+            let Some(internal) = layout.internal_dimen_v0(name) else {
+                return Ok(None);
+            };
+            index += 1;
+            Some(internal)
+        } else if let Some(next_index) = scan_keyword_v0(tokens, index, b"em") {
+            index = next_index;
+            Some(layout.em_sp as i64)
+        } else if let Some(next_index) = scan_keyword_v0(tokens, index, b"ex") {
+            index = next_index;
+            Some(layout.em_sp as i64 * EX_PER_EM_MILLIONTHS_V0 / 1_000_000)
+        } else {
+            None
+        };
+        let sp = match internal {
+            Some(unit) => integer * unit + unit * fraction / UNITY_SP_V0,
+            None => {
+                if let Some(next_index) = scan_keyword_v0(tokens, index, b"true") {
+                    index = next_index;
+                }
+                if let Some(next_index) = scan_keyword_v0(tokens, index, b"sp") {
+                    index = next_index;
+                    integer
+                } else {
+                    let unit = UNIT_RATIOS_V0.into_iter().find_map(|(unit, ratio)| {
+                        scan_keyword_v0(tokens, index, unit).map(|next_index| (ratio, next_index))
+                    });
+                    let Some(((numerator, denominator), next_index)) = unit else {
+                        return Err(InvalidInputReasonV0::DimenInvalid);
+                    };
+                    index = next_index;
+                    let scaled = integer * numerator;
+                    let remainder = scaled % denominator;
+                    let fraction = (numerator * fraction + UNITY_SP_V0 * remainder) / denominator;
+                    let points = scaled / denominator + fraction / UNITY_SP_V0;
+                    if points >= 0x4000 {
+                        return Err(InvalidInputReasonV0::DimenInvalid);
+                    }
+                    points * UNITY_SP_V0 + fraction % UNITY_SP_V0
+                }
+            }
+        };
+        if matches!(tokens.get(index), Some(TokenV0::Space)) {
+            index += 1;
+        }
+        sp
+    };
+    if sp > MAX_DIMEN_SP_V0 {
+        return Err(InvalidInputReasonV0::DimenInvalid);
+    }
+    let sp = if negative { -sp } else { sp };
+    Ok(Some((sp as i32, index)))
+}
@@ -0,0 +1,319 @@
+use super::ok_args_v0::{consume_group_bytes_v0, consume_group_literal, skip_spaces};
+use super::ok_math_layout_v0::{overlay_v0, MathBoxV0};
+use super::ok_math_v0::{consume_math_list_v0, AtomKindV0, MathItemV0, NoadV0, NucleusV0};
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+/// Numbered displays: LaTeX's `equation`, and amsmath's `align`, `gather` and
+/// `multline`, each with a starred form that numbers no row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EquationKindV0 {
+    Equation,
+    Align,
+    Gather,
+    Multline,
+}
+
+const EQUATION_ENVIRONMENTS_V0: [(&[u8], EquationKindV0, bool); 8] = [
+    (b"equation", EquationKindV0::Equation, false),
+    (b"equation*", EquationKindV0::Equation, true),
+    (b"align", EquationKindV0::Align, false),
+    (b"align*", EquationKindV0::Align, true),
+    (b"gather", EquationKindV0::Gather, false),
+    (b"gather*", EquationKindV0::Gather, true),
+    (b"multline", EquationKindV0::Multline, false),
+    (b"multline*", EquationKindV0::Multline, true),
+];
+
+/// What a row shows at the right margin: the next equation number, amsmath's
+/// `\tag{text}` in parentheses, `\tag*{text}` as is, or nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum EquationTagV0 {
+    None,
+    Number,
+    Tag(Vec<u8>),
+    TagStar(Vec<u8>),
+}
+
+/// One row: its `&`-separated cells, the `\label` keys naming it, and its tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EquationRowV0 {
+    pub(crate) cells: Vec<Vec<MathItemV0>>,
+    pub(crate) labels: Vec<Vec<u8>>,
+    pub(crate) tag: EquationTagV0,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EquationV0 {
+    pub(crate) kind: EquationKindV0,
+    pub(crate) rows: Vec<EquationRowV0>,
+}
+
+/// The environment begun at `index`, if it is one of them, with its name and the
+/// index after `\begin{name}`. Only `equation` is there without amsmath.
+fn equation_opener_v0(
+    tokens: &[TokenV0],
+    index: usize,
+    amsmath: bool,
+) -> Option<(&'static [u8], EquationKindV0, bool, usize)> {
+    if !matches!(tokens.get(index), Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"begin")
+    {
+        return None;
+    }
+    EQUATION_ENVIRONMENTS_V0
+        .into_iter()
+        .filter(|(name, _, _)| amsmath || *name == b"equation")
+        .find_map(|(name, kind, starred)| {
+            consume_group_literal(tokens, index + 1, name)
+                .map(|next_index| (name, kind, starred, next_index))
+        })
+}
+
+pub(crate) fn is_equation_opener_v0(tokens: &[TokenV0], index: usize, amsmath: bool) -> bool {
+    equation_opener_v0(tokens, index, amsmath).is_some()
+}
+
+/// A row under construction; `nonumber` is `\nonumber` or `\notag`.
+#[derive(Default)]
+struct RowBuilderV0 {
+    cells: Vec<Vec<MathItemV0>>,
+    labels: Vec<Vec<u8>>,
+    tag: Option<EquationTagV0>,
+    nonumber: bool,
+}
+
+impl RowBuilderV0 {
+    fn is_empty_v0(&self) -> bool {
+        self.cells.iter().all(Vec::is_empty)
+            && self.labels.is_empty()
+            && self.tag.is_none()
+            && !self.nonumber
+    }
+
+    fn finish_v0(self, starred: bool) -> EquationRowV0 {
+        let tag = match self.tag {
+            Some(tag) => tag,
+            None if starred || self.nonumber => EquationTagV0::None,
+            None => EquationTagV0::Number,
+        };
+        EquationRowV0 {
+            cells: self.cells,
+            labels: self.labels,
+            tag,
+        }
+    }
+}
+
+/// The environment begun at `index` through its `\end`: rows split at `\\`, and
+/// cells at `&` in `align`. A `\\` ending the last row is dropped, as amsmath
+/// does, and left-aligned `align` cells start with the `{}` amsmath puts there, so
+/// that a relation is spaced as after an ordinary atom. `multline` numbers its last
+/// row only, with the tag and labels of all of them.
+/// `Ok(None)` outside the subset, such as `\\[1ex]` or `&` elsewhere.
+pub(crate) fn consume_equation_v0(
+    tokens: &[TokenV0],
+    index: usize,
+    amsmath: bool,
+) -> Result<Option<(EquationV0, usize)>, InvalidInputReasonV0> {
+    let Some((name, kind, starred, mut index)) = equation_opener_v0(tokens, index, amsmath) else {
+        return Ok(None);
+    };
+    let mut rows = Vec::<RowBuilderV0>::new();
+    let mut row = RowBuilderV0 {
+        cells: vec![Vec::new()],
+        ..RowBuilderV0::default()
+    };
+    loop {
+        let Some((list, next_index)) = consume_math_list_v0(tokens, index)? else {
+            return Ok(None);
+        };
+        match row.cells.last_mut() {
+            Some(cell) => cell.extend(list),
+            None => row.cells.push(list),
+        }
+        index = next_index;
+        match tokens.get(index) {
+            Some(TokenV0::AlignTab) if kind == EquationKindV0::Align => {
+                let mut cell = Vec::new();
+                if row.cells.len() % 2 == 1 {
+                    cell.push(MathItemV0::Noad(NoadV0 {
+                        kind: AtomKindV0::Ord,
+                        nucleus: NucleusV0::List(Vec::new()),
+                        sup: None,
+                        sub: None,
+                        limits: None,
+                    }));
+                }
+                row.cells.push(cell);
+                index += 1;
+            }
+            Some(TokenV0::ControlSeq(command)) => match command.as_slice() {
+                b"\\" if kind != EquationKindV0::Equation => {
+                    if matches!(tokens.get(index + 1), Some(TokenV0::Char(b'[' | b'*'))) {
+                        return Ok(None);
+                    }
+                    rows.push(std::mem::replace(
+                        &mut row,
+                        RowBuilderV0 {
+                            cells: vec![Vec::new()],
+                            ..RowBuilderV0::default()
+                        },
+                    ));
+                    index += 1;
+                }
+                b"nonumber" => {
+                    row.nonumber = true;
+                    index += 1;
+                }
+                b"notag" if amsmath => {
+                    row.nonumber = true;
+                    index += 1;
+                }
+                b"label" => {
+                    let Some((key, next_index)) = consume_group_bytes_v0(tokens, index + 1) else {
+                        return Ok(None);
+                    };
+                    row.labels.push(key);
+                    index = next_index;
+                }
+                b"tag" if amsmath => {
+                    let star = matches!(tokens.get(index + 1), Some(TokenV0::Char(b'*')));
+                    let Some((text, next_index)) =
+                        consume_group_bytes_v0(tokens, index + 1 + usize::from(star))
+                    else {
+                        return Ok(None);
+                    };
+                    if !text.iter().all(|byte| (0x20..=0x7e).contains(byte)) {
+                        return Ok(None);
+                    }
+                    if row.tag.is_some() {
+                        return Err(InvalidInputReasonV0::EquationMultipleTag);
+                    }
+                    row.tag = Some(if star {
+                        EquationTagV0::TagStar(text)
+                    } else {
+                        EquationTagV0::Tag(text)
+                    });
+                    index = next_index;
+                }
+                b"end" => {
+                    let Some(next_index) = consume_group_literal(tokens, index + 1, name) else {
+                        return Ok(None);
+                    };
+                    if rows.is_empty() || !row.is_empty_v0() {
+                        rows.push(row);
+                    }
+                    index = next_index;
+                    break;
+                }
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        }
+        index = skip_spaces(tokens, index);
+    }
+    if kind == EquationKindV0::Multline {
+        let mut last = RowBuilderV0::default();
+        for row in &mut rows {
+            last.labels.append(&mut row.labels);
+            last.nonumber |= row.nonumber;
+            if let Some(tag) = row.tag.take() {
+                if last.tag.replace(tag).is_some() {
+                    return Err(InvalidInputReasonV0::EquationMultipleTag);
+                }
+            }
+        }
+        let Some(final_row) = rows.last_mut() else {
+            return Ok(None);
+        };
+        final_row.labels = last.labels;
+        final_row.tag = last.tag;
+        final_row.nonumber = last.nonumber;
+    }
+    let count = rows.len();
+    let rows = rows
+        .into_iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let mut row = row.finish_v0(starred);
+            if kind == EquationKindV0::Multline && index + 1 < count {
+                row.tag = EquationTagV0::None;
+            }
+            row
+        })
+        .collect();
+    Ok(Some((EquationV0 { kind, rows }, index)))
+}
+
+/// Each row's box, its cells set side by side, and the left edge it starts at,
+/// from the text block's; `numbers` holds the left edge of each row's number, if it
+/// shows one. Rows are centred between `left_sp` and the text width, keeping half
+/// an em clear of their number; `multline` sets its first row a `\multlinegap` of
+/// one em from the left and its last one as far from the right. `align` columns
+/// take the width of their widest cell, right-aligned and left-aligned in turn,
+/// with the space left over shared evenly between and around the pairs.
+pub(crate) fn place_equation_rows_v0(
+    kind: EquationKindV0,
+    rows: Vec<Vec<MathBoxV0>>,
+    numbers: &[Option<i64>],
+    left_sp: i64,
+    text_width_sp: i64,
+    em_sp: i64,
+) -> Vec<(MathBoxV0, i64)> {
+    let mut widths = Vec::<i64>::new();
+    for cells in &rows {
+        for (column, cell) in cells.iter().enumerate() {
+            if widths.len() == column {
+                widths.push(0);
+            }
+            widths[column] = widths[column].max(cell.width);
+        }
+    }
+    let pairs = widths.len().div_ceil(2) as i64;
+    let free_sp = text_width_sp - left_sp - widths.iter().sum::<i64>();
+    let gap_sp = (free_sp / (pairs + 1)).max(0);
+    let mut columns = Vec::<i64>::new();
+    let mut column_h = 0;
+    for (column, width) in widths.iter().enumerate() {
+        if column % 2 == 0 {
+            column_h += gap_sp;
+        }
+        columns.push(column_h);
+        column_h += width;
+    }
+    let count = rows.len();
+    rows.into_iter()
+        .zip(numbers)
+        .enumerate()
+        .map(|(index, (cells, number))| {
+            if kind == EquationKindV0::Align {
+                let parts = cells
+                    .into_iter()
+                    .enumerate()
+                    .map(|(column, cell)| {
+                        let mut x = columns[column];
+                        if column % 2 == 0 {
+                            x += widths[column] - cell.width;
+                        }
+                        (cell, x)
+                    })
+                    .collect::<Vec<_>>();
+                let width = parts.last().map_or(0, |(cell, x)| x + cell.width);
+                return (overlay_v0(parts, width), left_sp);
+            }
+            let math = cells.into_iter().next().unwrap_or_default();
+            let multline = kind == EquationKindV0::Multline && count > 1;
+            let mut h = if multline && index == 0 {
+                left_sp + em_sp
+            } else if multline && index + 1 == count {
+                text_width_sp - em_sp - math.width
+            } else {
+                left_sp + (text_width_sp - left_sp - math.width) / 2
+            };
+            if let Some(number_h) = number {
+                h = h.min(number_h - em_sp / 2 - math.width);
+            }
+            (math, h.max(left_sp))
+        })
+        .collect()
+}
@@ -0,0 +1,259 @@
+use super::ok_args_v0::{consume_group_bytes_v0, consume_optional_bytes_v0, skip_spaces};
+use super::ok_dimen_v0::OkLayoutV0;
+use super::ok_font_v0::OkFontV0;
+use super::ok_page_v0::OkPagesV0;
+use super::ok_section_v0::SectionLevelV0;
+use super::ok_toc_v0::{toc_lines_v0, TocEntryV0, TocLineV0};
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+use carreltex_xdv::{DviTextFloatPlacementV0, MAX_DVI_TEXT_FLOATS_V0};
+use std::ops::Range;
+
+/// The `figure` and `table` floats, each with its own counter and list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FloatKindV0 {
+    Figure,
+    Table,
+}
+
+impl FloatKindV0 {
+    /// Float environments; the starred forms span both columns, which a single
+    /// column sets alike.
+    pub(crate) fn from_env_name_v0(name: &[u8]) -> Option<Self> {
+        match name {
+            b"figure" | b"figure*" => Some(FloatKindV0::Figure),
+            b"table" | b"table*" => Some(FloatKindV0::Table),
+            _ => None,
+        }
+    }
+
+    /// `\fnum@figure` text before the number, and the `\contentsline` entry type.
+    fn names_v0(self) -> (&'static [u8], &'static [u8]) {
+        match self {
+            FloatKindV0::Figure => (b"Figure", b"figure"),
+            FloatKindV0::Table => (b"Table", b"table"),
+        }
+    }
+
+    /// `\listfigurename` and `\listtablename`.
+    pub(crate) fn list_heading_v0(self) -> &'static [u8] {
+        match self {
+            FloatKindV0::Figure => b"List of Figures",
+            FloatKindV0::Table => b"List of Tables",
+        }
+    }
+
+    /// `\caption` text before the title: `Figure 3:`.
+    pub(crate) fn caption_label_v0(self, number: &[u8]) -> Vec<u8> {
+        [self.names_v0().0, b" ", number, b":"].concat()
+    }
+}
+
+/// Where a float goes: somewhere `DviTextFloatPlacementV0` allows, or, with the
+/// `float` package's `H`, exactly where it is in the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FloatPlacementV0 {
+    Float(DviTextFloatPlacementV0),
+    Exact,
+}
+
+/// `[spec]` after `\begin{figure}` or `\begin{table}`; without one, or with only
+/// `!`, floats take article's default `tbp`, and a lone `h` becomes `ht`, as LaTeX
+/// does. `H` needs the `float` package and stands alone.
+pub(crate) fn consume_float_placement_v0(
+    tokens: &[TokenV0],
+    index: usize,
+    float_loaded: bool,
+) -> Result<Option<(FloatPlacementV0, usize)>, InvalidInputReasonV0> {
+    let index = skip_spaces(tokens, index);
+    let Some((spec, index)) = consume_optional_bytes_v0(tokens, index) else {
+        return Ok(None);
+    };
+    let spec = spec.unwrap_or_default();
+    if spec == b"H" && float_loaded {
+        return Ok(Some((FloatPlacementV0::Exact, index)));
+    }
+    let mut placement = DviTextFloatPlacementV0::default();
+    for byte in &spec {
+        match byte {
+            b'h' => placement.here = true,
+            b't' => placement.top = true,
+            b'b' => placement.bottom = true,
+            b'p' => placement.page = true,
+            b'!' => placement.force = true,
+            _ => return Err(InvalidInputReasonV0::FloatPlacementInvalid),
+        }
+    }
+    if !(placement.here || placement.top || placement.bottom || placement.page) {
+        placement.top = true;
+        placement.bottom = true;
+        placement.page = true;
+    } else if placement.here && !(placement.top || placement.bottom || placement.page) {
+        placement.top = true;
+    }
+    Ok(Some((FloatPlacementV0::Float(placement), index)))
+}
+
+/// Tokens of a float body up to its `\end{env}`, and the index past it. Floats
+/// inside floats are outside the subset.
+pub(crate) fn consume_float_body_v0(
+    tokens: &[TokenV0],
+    index: usize,
+    env: &[u8],
+) -> Option<(Range<usize>, usize)> {
+    for (end, token) in tokens.iter().enumerate().skip(index) {
+        let TokenV0::ControlSeq(name) = token else {
+            continue;
+        };
+        let Some((name_env, next_index)) = consume_group_bytes_v0(tokens, end + 1) else {
+            continue;
+        };
+        match name.as_slice() {
+            b"begin" if FloatKindV0::from_env_name_v0(&name_env).is_some() => return None,
+            b"end" if name_env == env => return Some((index..end, next_index)),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// A float body waiting for the end of the main text: the tokens of its
+/// environment, placed from the page holding `anchor` on, with its captions
+/// numbered from `first_number` after `number_prefix`.
+pub(crate) struct PendingFloatV0 {
+    pub(crate) anchor: usize,
+    pub(crate) kind: FloatKindV0,
+    pub(crate) placement: DviTextFloatPlacementV0,
+    pub(crate) body: Range<usize>,
+    pub(crate) number_prefix: Vec<u8>,
+    pub(crate) first_number: u32,
+}
+
+/// The float being typeset: its kind, the number of its next `\caption` and what
+/// goes before it, and the offset its body starts at.
+pub(crate) struct CurrentFloatV0 {
+    pub(crate) kind: FloatKindV0,
+    pub(crate) number_prefix: Vec<u8>,
+    pub(crate) next_number: u32,
+    pub(crate) start: usize,
+}
+
+/// The `figure` and `table` counters, the bodies met so far and the float being
+/// typeset, if any.
+#[derive(Default)]
+pub(crate) struct FloatStateV0 {
+    figures: u32,
+    tables: u32,
+    pub(crate) pending: Vec<PendingFloatV0>,
+    pub(crate) current: Option<CurrentFloatV0>,
+}
+
+impl FloatStateV0 {
+    /// Steps the counter of `kind` past the `\caption`s in `body` and returns the
+    /// first of their numbers, so captions are numbered in source order although
+    /// bodies are typeset after the main text.
+    pub(crate) fn reserve_numbers_v0(&mut self, kind: FloatKindV0, body: &[TokenV0]) -> u32 {
+        let captions = body
+            .iter()
+            .filter(
+                |token| matches!(token, TokenV0::ControlSeq(name) if name.as_slice() == b"caption"),
+            )
+            .count();
+        let counter = match kind {
+            FloatKindV0::Figure => &mut self.figures,
+            FloatKindV0::Table => &mut self.tables,
+        };
+        let first = counter.saturating_add(1);
+        *counter = counter.saturating_add(captions as u32);
+        first
+    }
+
+    /// Restarts both counters, as a new chapter does.
+    pub(crate) fn reset_counters_v0(&mut self) {
+        self.figures = 0;
+        self.tables = 0;
+    }
+
+    pub(crate) fn defer_v0(&mut self, float: PendingFloatV0) -> Result<(), InvalidInputReasonV0> {
+        if self.pending.len() >= MAX_DVI_TEXT_FLOATS_V0 {
+            return Err(InvalidInputReasonV0::FloatCountExceeded);
+        }
+        self.pending.push(float);
+        Ok(())
+    }
+}
+
+/// A `\caption` as the body met it; `offset` is where its text starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OkFloatEntryV0 {
+    pub(crate) kind: FloatKindV0,
+    pub(crate) number: Vec<u8>,
+    pub(crate) title: Vec<u8>,
+    pub(crate) offset: usize,
+}
+
+/// One `\contentsline` of the `.lof` or `.lot` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FloatEntryV0 {
+    pub(crate) kind: FloatKindV0,
+    pub(crate) number: Vec<u8>,
+    pub(crate) title: Vec<u8>,
+    pub(crate) page: Vec<u8>,
+}
+
+/// Captions of a pass as its `.lof` and `.lot` record them, with the page each
+/// float landed on.
+pub(crate) fn float_entries_v0(entries: &[OkFloatEntryV0], pages: &OkPagesV0) -> Vec<FloatEntryV0> {
+    entries
+        .iter()
+        .map(|entry| FloatEntryV0 {
+            kind: entry.kind,
+            number: entry.number.clone(),
+            title: entry.title.clone(),
+            page: pages.label_v0(entry.offset),
+        })
+        .collect()
+}
+
+/// `\contentsline {figure}{\numberline {1}Title}{1}%` per caption of `kind`.
+pub(crate) fn write_float_list_v0(entries: &[FloatEntryV0], kind: FloatKindV0) -> Vec<u8> {
+    let mut list = Vec::<u8>::new();
+    for entry in entries.iter().filter(|entry| entry.kind == kind) {
+        list.extend_from_slice(b"\\contentsline {");
+        list.extend_from_slice(kind.names_v0().1);
+        list.extend_from_slice(b"}{\\numberline {");
+        list.extend_from_slice(&entry.number);
+        list.push(b'}');
+        list.extend_from_slice(&entry.title);
+        list.extend_from_slice(b"}{");
+        list.extend_from_slice(&entry.page);
+        list.extend_from_slice(b"}%\n");
+    }
+    list
+}
+
+/// `\l@figure` and `\l@table` are `\@dottedtocline{1}{1.5em}{2.3em}`, the indents
+/// and leaders of the contents lines one below the top level.
+pub(crate) fn float_list_lines_v0(
+    entries: &[FloatEntryV0],
+    kind: FloatKindV0,
+    chapters: bool,
+    font: OkFontV0,
+    layout: &OkLayoutV0,
+) -> Vec<TocLineV0> {
+    let entries = entries
+        .iter()
+        .filter(|entry| entry.kind == kind)
+        .map(|entry| TocEntryV0 {
+            level: if chapters {
+                SectionLevelV0::Section
+            } else {
+                SectionLevelV0::Subsection
+            },
+            number: Some(entry.number.clone()),
+            title: entry.title.clone(),
+            page: entry.page.clone(),
+        })
+        .collect::<Vec<_>>();
+    toc_lines_v0(&entries, chapters, font, layout)
+}
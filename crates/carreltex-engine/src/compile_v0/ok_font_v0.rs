@@ -0,0 +1,359 @@
+use crate::reasons_v0::InvalidInputReasonV0;
+use carreltex_xdv::{DviTextFontV0, MAX_DVI_TEXT_FONTS_V0};
+
+/// DVI name of the default font; every other font is named after it.
+const BASE_FONT_NAME_V0: &[u8] = b"carreltex-v0";
+const NORMAL_SIZE_SP_V0: i32 = 655_360;
+/// Article's 10pt `\tiny`..`\Huge` (`size10.clo`).
+const SIZES_V0: [i32; 10] = [
+    327_680, 458_752, 524_288, 589_824, 655_360, 786_432, 943_718, 1_132_462, 1_359_217, 1_630_536,
+];
+/// The same sizes under the `11pt` class option (`size11.clo`).
+const SIZES_11PT_V0: [i32; 10] = [
+    393_216, 524_288, 589_824, 655_360, 717_619, 786_432, 943_718, 1_132_462, 1_359_217, 1_630_536,
+];
+/// The same sizes under the `12pt` class option (`size12.clo`).
+const SIZES_12PT_V0: [i32; 10] = [
+    393_216, 524_288, 655_360, 717_619, 786_432, 943_718, 1_132_462, 1_359_217, 1_630_536,
+    1_630_536,
+];
+
+/// LaTeX's `\sf@size` and `\ssf@size` for each of those sizes (`\DeclareMathSizes`):
+/// the sizes of scripts and of scripts of scripts, as superscripts set in text, such
+/// as footnote marks, use the first.
+const SCRIPT_SIZES_V0: [(i32, i32, i32); 12] = [
+    (327_680, 327_680, 327_680),
+    (393_216, 327_680, 327_680),
+    (458_752, 327_680, 327_680),
+    (524_288, 393_216, 327_680),
+    (589_824, 393_216, 327_680),
+    (NORMAL_SIZE_SP_V0, 458_752, 327_680),
+    (717_619, 524_288, 393_216),
+    (786_432, 524_288, 393_216),
+    (943_718, 655_360, 458_752),
+    (1_132_462, 786_432, 655_360),
+    (1_359_217, 943_718, 786_432),
+    (1_630_536, 1_359_217, 1_132_462),
+];
+
+/// The class option that sets `\normalsize` and the sizes around it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum BaseSizeV0 {
+    #[default]
+    Ten,
+    Eleven,
+    Twelve,
+}
+
+impl BaseSizeV0 {
+    pub(crate) fn from_option_v0(option: &[u8]) -> Option<Self> {
+        match option {
+            b"10pt" => Some(Self::Ten),
+            b"11pt" => Some(Self::Eleven),
+            b"12pt" => Some(Self::Twelve),
+            _ => None,
+        }
+    }
+
+    fn sizes_v0(self) -> &'static [i32; 10] {
+        match self {
+            Self::Ten => &SIZES_V0,
+            Self::Eleven => &SIZES_11PT_V0,
+            Self::Twelve => &SIZES_12PT_V0,
+        }
+    }
+}
+
+/// `\tiny`..`\Huge`, in the order of the size tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FontSizeV0 {
+    Tiny,
+    ScriptSize,
+    FootnoteSize,
+    Small,
+    NormalSize,
+    /// `\large`.
+    Large,
+    /// `\Large`.
+    Larger,
+    /// `\LARGE`.
+    Largest,
+    /// `\huge`.
+    Huge,
+    /// `\Huge`.
+    Huger,
+}
+
+impl FontSizeV0 {
+    fn from_name_v0(name: &[u8]) -> Option<Self> {
+        match name {
+            b"tiny" => Some(Self::Tiny),
+            b"scriptsize" => Some(Self::ScriptSize),
+            b"footnotesize" => Some(Self::FootnoteSize),
+            b"small" => Some(Self::Small),
+            b"normalsize" => Some(Self::NormalSize),
+            b"large" => Some(Self::Large),
+            b"Large" => Some(Self::Larger),
+            b"LARGE" => Some(Self::Largest),
+            b"huge" => Some(Self::Huge),
+            b"Huge" => Some(Self::Huger),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FontFamilyV0 {
+    Roman,
+    Typewriter,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FontShapeV0 {
+    Upright,
+    Italic,
+    Slanted,
+    SmallCaps,
+}
+
+/// The NFSS subset: family `rm`/`tt`, series `m`/`bx`, shape `n`/`it`/`sl`/`sc` and
+/// a size, named after the class's `base` sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OkFontV0 {
+    family: FontFamilyV0,
+    bold: bool,
+    shape: FontShapeV0,
+    size_sp: i32,
+    base: BaseSizeV0,
+}
+
+impl Default for OkFontV0 {
+    fn default() -> Self {
+        OkFontV0 {
+            family: FontFamilyV0::Roman,
+            bold: false,
+            shape: FontShapeV0::Upright,
+            size_sp: NORMAL_SIZE_SP_V0,
+            base: BaseSizeV0::Ten,
+        }
+    }
+}
+
+impl OkFontV0 {
+    /// `\normalfont\normalsize` of a class with `base` sizes.
+    pub(crate) fn normal_v0(base: BaseSizeV0) -> Self {
+        OkFontV0 {
+            base,
+            ..OkFontV0::default()
+        }
+        .sized_v0(FontSizeV0::NormalSize)
+    }
+
+    /// `\textbf{...}` and friends set their text in the font of the matching
+    /// declaration; `\emph` toggles between italic and upright.
+    pub(crate) fn text_command_v0(self, name: &[u8]) -> Option<Self> {
+        let declaration: &[u8] = match name {
+            b"textrm" => b"rmfamily",
+            b"texttt" => b"ttfamily",
+            b"textmd" => b"mdseries",
+            b"textbf" => b"bfseries",
+            b"textup" => b"upshape",
+            b"textit" => b"itshape",
+            b"textsl" => b"slshape",
+            b"textsc" => b"scshape",
+            b"textnormal" => b"normalfont",
+            b"emph" if matches!(self.shape, FontShapeV0::Italic | FontShapeV0::Slanted) => {
+                b"upshape"
+            }
+            b"emph" => b"itshape",
+            _ => return None,
+        };
+        self.declaration_v0(declaration)
+    }
+
+    /// Font declarations such as `\bfseries` or `\large`, in effect to the end of
+    /// the current group; `\em` switches between italic and upright as `\emph` does.
+    pub(crate) fn declaration_v0(self, name: &[u8]) -> Option<Self> {
+        let mut font = self;
+        match name {
+            b"rmfamily" => font.family = FontFamilyV0::Roman,
+            b"ttfamily" => font.family = FontFamilyV0::Typewriter,
+            b"mdseries" => font.bold = false,
+            b"bfseries" => font.bold = true,
+            b"upshape" => font.shape = FontShapeV0::Upright,
+            b"itshape" => font.shape = FontShapeV0::Italic,
+            b"slshape" => font.shape = FontShapeV0::Slanted,
+            b"scshape" => font.shape = FontShapeV0::SmallCaps,
+            b"em" if matches!(self.shape, FontShapeV0::Italic | FontShapeV0::Slanted) => {
+                font.shape = FontShapeV0::Upright
+            }
+            b"em" => font.shape = FontShapeV0::Italic,
+            b"normalfont" => font = self.normalfont_v0(),
+            _ => font = self.sized_v0(FontSizeV0::from_name_v0(name)?),
+        }
+        Some(font)
+    }
+
+    /// `\normalfont`: the class's upright roman at this font's size.
+    pub(crate) fn normalfont_v0(self) -> Self {
+        OkFontV0 {
+            size_sp: self.size_sp,
+            ..OkFontV0::normal_v0(self.base)
+        }
+    }
+
+    /// `\bfseries`.
+    pub(crate) fn bold_v0(self) -> Self {
+        OkFontV0 { bold: true, ..self }
+    }
+
+    /// `\ttfamily`.
+    pub(crate) fn typewriter_v0(self) -> Self {
+        OkFontV0 {
+            family: FontFamilyV0::Typewriter,
+            ..self
+        }
+    }
+
+    /// `\slshape`.
+    pub(crate) fn slanted_v0(self) -> Self {
+        OkFontV0 {
+            shape: FontShapeV0::Slanted,
+            ..self
+        }
+    }
+
+    /// The font at `size` of its class.
+    pub(crate) fn sized_v0(self, size: FontSizeV0) -> Self {
+        OkFontV0 {
+            size_sp: self.base.sizes_v0()[size as usize],
+            ..self
+        }
+    }
+
+    /// The font at its script size, as `\textsuperscript` selects it.
+    pub(crate) fn script_size_v0(self) -> Self {
+        let size_sp = SCRIPT_SIZES_V0
+            .iter()
+            .find(|(text, _, _)| *text == self.size_sp)
+            .map_or(self.size_sp, |(_, script, _)| *script);
+        OkFontV0 { size_sp, ..self }
+    }
+
+    /// The font at the size of scripts of scripts.
+    pub(crate) fn script_script_size_v0(self) -> Self {
+        let size_sp = SCRIPT_SIZES_V0
+            .iter()
+            .find(|(text, _, _)| *text == self.size_sp)
+            .map_or(self.size_sp, |(_, _, script_script)| *script_script);
+        OkFontV0 { size_sp, ..self }
+    }
+
+    /// Em of the font when `\normalsize` has an em of `normal_em_sp`, as the DVI
+    /// writer scales glyph advances.
+    pub(crate) fn em_sp_v0(&self, normal_em_sp: i32) -> i32 {
+        (i64::from(normal_em_sp) * i64::from(self.size_sp) / i64::from(NORMAL_SIZE_SP_V0)) as i32
+    }
+
+    /// `carreltex-v0-<family>` for a math font family (`mi`, `sy` or `ex`) at this
+    /// font's size scaled by `tenths` / 10.
+    pub(crate) fn math_dvi_font_v0(&self, family: &[u8], tenths: i32) -> DviTextFontV0 {
+        DviTextFontV0 {
+            name: [BASE_FONT_NAME_V0, b"-", family].concat(),
+            size_sp: (i64::from(self.size_sp) * i64::from(tenths) / 10) as i32,
+        }
+    }
+
+    /// `carreltex-v0` for the default font, the 10pt `\normalfont`, otherwise
+    /// `carreltex-v0-<family>-<series>-<shape>` at its own size.
+    pub(crate) fn dvi_font_v0(&self) -> Option<DviTextFontV0> {
+        let ten_point = OkFontV0 {
+            base: BaseSizeV0::Ten,
+            ..*self
+        };
+        if ten_point == OkFontV0::default() {
+            return None;
+        }
+        let family: &[u8] = match self.family {
+            FontFamilyV0::Roman => b"rm",
+            FontFamilyV0::Typewriter => b"tt",
+        };
+        let series: &[u8] = if self.bold { b"bx" } else { b"m" };
+        let shape: &[u8] = match self.shape {
+            FontShapeV0::Upright => b"n",
+            FontShapeV0::Italic => b"it",
+            FontShapeV0::Slanted => b"sl",
+            FontShapeV0::SmallCaps => b"sc",
+        };
+        let name = [BASE_FONT_NAME_V0, b"-", family, b"-", series, b"-", shape].concat();
+        Some(DviTextFontV0 {
+            name,
+            size_sp: self.size_sp,
+        })
+    }
+}
+
+/// Fonts used by the body in order of first use, numbered from 1 after the
+/// default font.
+#[derive(Debug, Default)]
+pub(crate) struct FontTableV0 {
+    pub(crate) fonts: Vec<DviTextFontV0>,
+}
+
+impl FontTableV0 {
+    pub(crate) fn number_v0(&mut self, font: &OkFontV0) -> Result<usize, InvalidInputReasonV0> {
+        self.number_dvi_v0(font.dvi_font_v0())
+    }
+
+    /// Number of a DVI font; `None` is the default font, 0.
+    pub(crate) fn number_dvi_v0(
+        &mut self,
+        font: Option<DviTextFontV0>,
+    ) -> Result<usize, InvalidInputReasonV0> {
+        let Some(font) = font else {
+            return Ok(0);
+        };
+        if let Some(index) = self.fonts.iter().position(|known| *known == font) {
+            return Ok(index + 1);
+        }
+        if self.fonts.len() >= MAX_DVI_TEXT_FONTS_V0 {
+            return Err(InvalidInputReasonV0::FontCountExceeded);
+        }
+        self.fonts.push(font);
+        Ok(self.fonts.len())
+    }
+}
+
+/// Mount file mapping DVI font names to the font files embedded in the PDF.
+pub(crate) const FONT_MAP_PATH_V0: &str = "carreltex-fonts.map";
+
+/// One `<dvi font name> <mount path>` line of the font map.
+pub(crate) struct FontMapEntryV0<'a> {
+    pub(crate) name: &'a [u8],
+    pub(crate) path: &'a [u8],
+}
+
+/// `carreltex-fonts.map` lines, with `%` comments and blank lines ignored; a name
+/// may be mapped once.
+pub(crate) fn parse_font_map_v0(
+    bytes: &[u8],
+) -> Result<Vec<FontMapEntryV0<'_>>, InvalidInputReasonV0> {
+    let mut entries = Vec::<FontMapEntryV0<'_>>::new();
+    for line in bytes.split(|byte| *byte == b'\n') {
+        let line = line.split(|byte| *byte == b'%').next().unwrap_or_default();
+        let mut fields = line
+            .split(|byte| matches!(byte, b' ' | b'\t' | b'\r'))
+            .filter(|field| !field.is_empty());
+        let Some(name) = fields.next() else {
+            continue;
+        };
+        let (Some(path), None) = (fields.next(), fields.next()) else {
+            return Err(InvalidInputReasonV0::FontMapInvalid);
+        };
+        if entries.iter().any(|entry| entry.name == name) {
+            return Err(InvalidInputReasonV0::FontMapInvalid);
+        }
+        entries.push(FontMapEntryV0 { name, path });
+    }
+    Ok(entries)
+}
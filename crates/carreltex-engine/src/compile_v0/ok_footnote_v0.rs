@@ -0,0 +1,151 @@
+use super::ok_dimen_v0::OkLayoutV0;
+use super::ok_font_v0::OkFontV0;
+use super::ok_rule_v0::DEFAULT_RULE_THICKNESS_SP_V0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+use carreltex_xdv::{glyph_width_sp_v0, MAX_DVI_TEXT_FOOTNOTES_V0};
+use std::ops::Range;
+
+/// `\fontdimen13` (sup1) of cmsy10 in millionths of an em: how far text-style
+/// superscripts such as footnote marks sit above the baseline.
+const SUPERSCRIPT_RAISE_EM_MILLIONTHS_V0: i64 = 412_892;
+/// `\@makefntext` sets the mark flush right in a box this many tenths of an em wide.
+const FOOTNOTE_MARK_BOX_EM_TENTHS_V0: i64 = 18;
+
+/// `\footnote`, `\footnotemark` and `\footnotetext`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FootnoteCommandV0 {
+    Footnote,
+    Mark,
+    Text,
+}
+
+impl FootnoteCommandV0 {
+    pub(crate) fn from_name_v0(name: &[u8]) -> Option<Self> {
+        match name {
+            b"footnote" => Some(FootnoteCommandV0::Footnote),
+            b"footnotemark" => Some(FootnoteCommandV0::Mark),
+            b"footnotetext" => Some(FootnoteCommandV0::Text),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn has_mark_v0(self) -> bool {
+        self != FootnoteCommandV0::Text
+    }
+
+    pub(crate) fn has_body_v0(self) -> bool {
+        self != FootnoteCommandV0::Mark
+    }
+}
+
+/// A footnote body waiting for the end of the main text: the tokens inside its
+/// braces, set after the mark `number`, at the bottom of the page holding `anchor`.
+pub(crate) struct PendingFootnoteV0 {
+    pub(crate) anchor: usize,
+    pub(crate) number: Vec<u8>,
+    pub(crate) body: Range<usize>,
+}
+
+/// The `footnote` counter, the bodies met so far and, while a body is typeset, the
+/// main-text offset it is anchored at.
+#[derive(Default)]
+pub(crate) struct FootnoteStateV0 {
+    counter: u32,
+    pub(crate) pending: Vec<PendingFootnoteV0>,
+    pub(crate) anchor: Option<usize>,
+}
+
+impl FootnoteStateV0 {
+    /// Restarts the counter, as a new chapter does.
+    pub(crate) fn reset_counter_v0(&mut self) {
+        self.counter = 0;
+    }
+
+    /// The mark of a footnote command: `[n]` when given, else the counter, stepped
+    /// by `\footnote` and `\footnotemark` but not by `\footnotetext`.
+    pub(crate) fn number_v0(
+        &mut self,
+        command: FootnoteCommandV0,
+        optional: Option<Vec<u8>>,
+    ) -> Option<Vec<u8>> {
+        if let Some(number) = optional {
+            let digits = number.iter().all(u8::is_ascii_digit);
+            return (digits && !number.is_empty()).then_some(number);
+        }
+        if command.has_mark_v0() {
+            self.counter = self.counter.saturating_add(1);
+        }
+        Some(self.counter.to_string().into_bytes())
+    }
+
+    pub(crate) fn defer_v0(
+        &mut self,
+        footnote: PendingFootnoteV0,
+    ) -> Result<(), InvalidInputReasonV0> {
+        if self.pending.len() >= MAX_DVI_TEXT_FOOTNOTES_V0 {
+            return Err(InvalidInputReasonV0::FootnoteCountExceeded);
+        }
+        self.pending.push(footnote);
+        Ok(())
+    }
+}
+
+/// Tokens of a `{...}` argument, up to its matching brace, and the index past it.
+pub(crate) fn consume_group_range_v0(
+    tokens: &[TokenV0],
+    index: usize,
+) -> Option<(Range<usize>, usize)> {
+    if !matches!(tokens.get(index), Some(TokenV0::BeginGroup)) {
+        return None;
+    }
+    let mut depth = 0usize;
+    for (end, token) in tokens.iter().enumerate().skip(index) {
+        match token {
+            TokenV0::BeginGroup => depth += 1,
+            TokenV0::EndGroup => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((index + 1..end, end + 1));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn em_fraction_sp_v0(em_sp: i32, numerator: i64, denominator: i64) -> i32 {
+    (i64::from(em_sp) * numerator / denominator) as i32
+}
+
+/// Raise of a superscript set after text in `font`.
+pub(crate) fn superscript_raise_sp_v0(font: OkFontV0, layout: &OkLayoutV0) -> i32 {
+    let em_sp = font.em_sp_v0(layout.em_sp);
+    em_fraction_sp_v0(em_sp, SUPERSCRIPT_RAISE_EM_MILLIONTHS_V0, 1_000_000)
+}
+
+/// Horizontal position of a body's mark, right-aligned in `\@makefntext`'s box.
+pub(crate) fn footnote_mark_h_sp_v0(
+    body_font: OkFontV0,
+    number: &[u8],
+    layout: &OkLayoutV0,
+) -> i32 {
+    let box_sp = em_fraction_sp_v0(
+        body_font.em_sp_v0(layout.em_sp),
+        FOOTNOTE_MARK_BOX_EM_TENTHS_V0,
+        10,
+    );
+    let mark_em_sp = body_font.script_size_v0().em_sp_v0(layout.em_sp);
+    let mark_sp = number
+        .iter()
+        .filter_map(|byte| glyph_width_sp_v0(*byte, mark_em_sp))
+        .sum::<i32>();
+    box_sp.saturating_sub(mark_sp).max(0)
+}
+
+/// Article's `\footnoterule`: 0.4pt thick, two fifths of the column wide.
+pub(crate) fn footnote_rule_v0(layout: &OkLayoutV0) -> (i32, i32) {
+    let width = em_fraction_sp_v0(layout.text_width_sp, 2, 5);
+    (width, DEFAULT_RULE_THICKNESS_SP_V0)
+}
@@ -0,0 +1,311 @@
+use super::ok_dimen_v0::{scan_dimen_v0, OkLayoutV0, MAX_DIMEN_SP_V0};
+use super::ok_page_v0::{text_height_sp_v0, HEADHEIGHT_SP_V0, HEADSEP_SP_V0, TOPSKIP_SP_V0};
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+use carreltex_xdv::{MAX_DVI_RULE_DIMENSION_SP_V0, ONE_INCH_SP_V0};
+
+/// ISO A4, 210mm x 297mm (597.50787pt x 845.04684pt).
+const A4_PAPER_SP_V0: (i32, i32) = (39_158_276, 55_380_990);
+/// US letter, LaTeX's default: 8.5in x 11in (614.295pt x 794.96999pt).
+const LETTER_PAPER_SP_V0: (i32, i32) = (40_258_437, 52_099_153);
+
+/// The paper and where the text block sits on it: `\oddsidemargin` and `\topmargin`
+/// are measured from TeX's 1in origin, as in LaTeX. Both start at the kernel's 0pt,
+/// since the OK layout's text block is not article's. The right and bottom margins,
+/// from the paper's edge as `geometry` takes them, bound the text block; both start
+/// at 1in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OkGeometryV0 {
+    pub(crate) paper_width_sp: i32,
+    pub(crate) paper_height_sp: i32,
+    odd_side_margin_sp: i32,
+    top_margin_sp: i32,
+    right_margin_sp: i32,
+    bottom_margin_sp: i32,
+}
+
+impl Default for OkGeometryV0 {
+    fn default() -> Self {
+        OkGeometryV0 {
+            paper_width_sp: LETTER_PAPER_SP_V0.0,
+            paper_height_sp: LETTER_PAPER_SP_V0.1,
+            odd_side_margin_sp: 0,
+            top_margin_sp: 0,
+            right_margin_sp: ONE_INCH_SP_V0,
+            bottom_margin_sp: ONE_INCH_SP_V0,
+        }
+    }
+}
+
+impl OkGeometryV0 {
+    /// `\paperwidth`, `\paperheight`, `\oddsidemargin` and `\topmargin`.
+    pub(crate) fn length_v0(&self, name: &[u8]) -> Option<i32> {
+        match name {
+            b"paperwidth" => Some(self.paper_width_sp),
+            b"paperheight" => Some(self.paper_height_sp),
+            b"oddsidemargin" => Some(self.odd_side_margin_sp),
+            b"topmargin" => Some(self.top_margin_sp),
+            _ => None,
+        }
+    }
+
+    /// Sets one of those lengths; the paper must keep a positive size.
+    fn set_length_v0(&mut self, name: &[u8], sp: i32) -> Result<(), InvalidInputReasonV0> {
+        if matches!(name, b"paperwidth" | b"paperheight") && sp <= 0 {
+            return Err(InvalidInputReasonV0::DimenInvalid);
+        }
+        match name {
+            b"paperwidth" => self.paper_width_sp = sp,
+            b"paperheight" => self.paper_height_sp = sp,
+            b"oddsidemargin" => self.odd_side_margin_sp = sp,
+            _ => self.top_margin_sp = sp,
+        }
+        Ok(())
+    }
+
+    /// `a4paper` or `letterpaper`, as a class or `geometry` option.
+    pub(crate) fn paper_option_v0(&mut self, option: &[u8]) -> Option<()> {
+        let (width, height) = match option {
+            b"a4paper" => A4_PAPER_SP_V0,
+            b"letterpaper" => LETTER_PAPER_SP_V0,
+            _ => return None,
+        };
+        self.paper_width_sp = width;
+        self.paper_height_sp = height;
+        Some(())
+    }
+
+    /// Comma-separated `geometry` options: the paper names, `paper`, `paperwidth`,
+    /// `paperheight`, and the margins measured from the paper's edge: `left`
+    /// (`lmargin`), `right` (`rmargin`), `top` (`tmargin`) and `bottom` (`bmargin`),
+    /// `hmargin` and `vmargin` for both of a pair and `margin` for all four.
+    /// `Ok(None)` for other keys or values.
+    fn apply_options_v0(
+        &mut self,
+        options: &[u8],
+        layout: &OkLayoutV0,
+    ) -> Result<Option<()>, InvalidInputReasonV0> {
+        for option in options.split(|byte| *byte == b',') {
+            let option = option.trim_ascii();
+            let Some(equals) = option.iter().position(|byte| *byte == b'=') else {
+                if !option.is_empty() && self.paper_option_v0(option).is_none() {
+                    return Ok(None);
+                }
+                continue;
+            };
+            let key = option[..equals].trim_ascii();
+            let value = option[equals + 1..].trim_ascii();
+            if key == b"paper" {
+                if self.paper_option_v0(value).is_none() {
+                    return Ok(None);
+                }
+                continue;
+            }
+            let tokens = value
+                .iter()
+                .map(|byte| match byte {
+                    b' ' => TokenV0::Space,
+                    byte => TokenV0::Char(*byte),
+                })
+                .collect::<Vec<_>>();
+            let Some((sp, end)) = scan_dimen_v0(&tokens, 0, layout)? else {
+                return Ok(None);
+            };
+            if end != tokens.len() {
+                return Ok(None);
+            }
+            let left = i64::from(sp) - i64::from(ONE_INCH_SP_V0);
+            let top = left - i64::from(HEADHEIGHT_SP_V0) - i64::from(HEADSEP_SP_V0);
+            let (left, top) = (dimen_v0(left)?, dimen_v0(top)?);
+            let (horizontal, vertical) = match key {
+                b"paperwidth" | b"paperheight" => {
+                    self.set_length_v0(key, sp)?;
+                    continue;
+                }
+                b"margin" => (true, true),
+                b"hmargin" => (true, false),
+                b"vmargin" => (false, true),
+                b"left" | b"lmargin" => {
+                    self.odd_side_margin_sp = left;
+                    continue;
+                }
+                b"right" | b"rmargin" => {
+                    self.right_margin_sp = sp;
+                    continue;
+                }
+                b"top" | b"tmargin" => {
+                    self.top_margin_sp = top;
+                    continue;
+                }
+                b"bottom" | b"bmargin" => {
+                    self.bottom_margin_sp = sp;
+                    continue;
+                }
+                _ => return Ok(None),
+            };
+            if horizontal {
+                self.odd_side_margin_sp = left;
+                self.right_margin_sp = sp;
+            }
+            if vertical {
+                self.top_margin_sp = top;
+                self.bottom_margin_sp = sp;
+            }
+        }
+        Ok(Some(()))
+    }
+
+    /// Where the first baseline of a page sits from the DVI origin: `\oddsidemargin`
+    /// across, and `\topmargin`, `\headheight`, `\headsep` and `\topskip` down.
+    pub(crate) fn origin_v0(&self) -> Result<(i32, i32), InvalidInputReasonV0> {
+        let v = [HEADHEIGHT_SP_V0, HEADSEP_SP_V0, TOPSKIP_SP_V0]
+            .iter()
+            .fold(i64::from(self.top_margin_sp), |v, skip| {
+                v + i64::from(*skip)
+            });
+        Ok((self.odd_side_margin_sp, dimen_v0(v)?))
+    }
+
+    /// Width and height of the text block between the margins, from `\oddsidemargin`
+    /// to the right margin and from below `\headsep` to the bottom margin; negative
+    /// when the margins overlap.
+    fn text_block_v0(&self) -> (i64, i64) {
+        let left = i64::from(ONE_INCH_SP_V0) + i64::from(self.odd_side_margin_sp);
+        let top = [self.top_margin_sp, HEADHEIGHT_SP_V0, HEADSEP_SP_V0]
+            .iter()
+            .fold(i64::from(ONE_INCH_SP_V0), |top, skip| {
+                top + i64::from(*skip)
+            });
+        (
+            i64::from(self.paper_width_sp) - left - i64::from(self.right_margin_sp),
+            i64::from(self.paper_height_sp) - top - i64::from(self.bottom_margin_sp),
+        )
+    }
+
+    /// `papersize=<\paperwidth>,<\paperheight>`, as the `geometry` package writes it
+    /// for DVI viewers and drivers.
+    pub(crate) fn papersize_special_v0(&self) -> Vec<u8> {
+        [
+            b"papersize=".as_slice(),
+            &the_dimen_v0(self.paper_width_sp),
+            b",",
+            &the_dimen_v0(self.paper_height_sp),
+        ]
+        .concat()
+    }
+}
+
+/// Applies `geometry` options to `layout`'s geometry; `Ok(None)` leaves it as it was.
+pub(crate) fn apply_geometry_options_v0(
+    options: &[u8],
+    layout: &mut OkLayoutV0,
+) -> Result<Option<()>, InvalidInputReasonV0> {
+    let mut geometry = layout.geometry;
+    let applied = geometry.apply_options_v0(options, layout)?;
+    if applied.is_some() {
+        layout.geometry = geometry;
+    }
+    Ok(applied)
+}
+
+/// `layout` with as many of its glyphs per line and lines per page as fit the text
+/// block of its geometry, and `\textwidth` and `\textheight` to match. `None` when
+/// not even one glyph or line fits.
+pub(crate) fn fit_text_block_v0(layout: &OkLayoutV0) -> Option<OkLayoutV0> {
+    let (width_sp, height_sp) = layout.geometry.text_block_v0();
+    let line_glyphs = width_sp / i64::from(layout.em_sp.max(1));
+    let below_topskip_sp = height_sp - i64::from(TOPSKIP_SP_V0);
+    if line_glyphs < 1 || below_topskip_sp < 0 {
+        return None;
+    }
+    let page_lines = below_topskip_sp / i64::from(layout.line_advance_sp.max(1)) + 1;
+    let max_line_glyphs = layout
+        .max_line_glyphs
+        .min(usize::try_from(line_glyphs).ok()?);
+    let max_lines_per_page = layout
+        .max_lines_per_page
+        .min(usize::try_from(page_lines).ok()?);
+    Some(OkLayoutV0 {
+        text_width_sp: (i64::from(layout.em_sp) * max_line_glyphs as i64)
+            .min(i64::from(MAX_DVI_RULE_DIMENSION_SP_V0)) as i32,
+        text_height_sp: text_height_sp_v0(layout.line_advance_sp, max_lines_per_page),
+        max_line_glyphs,
+        max_lines_per_page,
+        ..*layout
+    })
+}
+
+fn dimen_v0(sp: i64) -> Result<i32, InvalidInputReasonV0> {
+    if sp.abs() > MAX_DIMEN_SP_V0 {
+        return Err(InvalidInputReasonV0::DimenInvalid);
+    }
+    Ok(sp as i32)
+}
+
+/// `\the` of a dimension: TeX's `print_scaled` followed by `pt`.
+fn the_dimen_v0(sp: i32) -> Vec<u8> {
+    const UNITY: i64 = 65_536;
+    let mut text = Vec::<u8>::new();
+    let mut s = i64::from(sp);
+    if s < 0 {
+        text.push(b'-');
+        s = -s;
+    }
+    text.extend_from_slice((s / UNITY).to_string().as_bytes());
+    text.push(b'.');
+    s = 10 * (s % UNITY) + 5;
+    let mut delta = 10i64;
+    loop {
+        if delta > UNITY {
+            s += 0x8000 - 50_000;
+        }
+        text.push(b'0' + (s / UNITY) as u8);
+        s = 10 * (s % UNITY);
+        delta *= 10;
+        if s <= delta {
+            break;
+        }
+    }
+    text.extend_from_slice(b"pt");
+    text
+}
+
+/// `\setlength{\name}{dimen}` (or `\setlength\name{dimen}`) at `index`, just past
+/// `\setlength`, for a geometry length. `Ok(None)` outside the subset.
+pub(crate) fn consume_setlength_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+    layout: &mut OkLayoutV0,
+) -> Result<Option<usize>, InvalidInputReasonV0> {
+    let braced = matches!(tokens.get(index), Some(TokenV0::BeginGroup));
+    if braced {
+        index += 1;
+    }
+    let Some(TokenV0::ControlSeq(name)) = tokens.get(index) else {
+        return Ok(None);
+    };
+    if layout.geometry.length_v0(name).is_none() {
+        return Ok(None);
+    }
+    index += 1;
+    if braced {
+        if !matches!(tokens.get(index), Some(TokenV0::EndGroup)) {
+            return Ok(None);
+        }
+        index += 1;
+    }
+    if !matches!(tokens.get(index), Some(TokenV0::BeginGroup)) {
+        return Ok(None);
+    }
+    let Some((sp, mut index)) = scan_dimen_v0(tokens, index + 1, layout)? else {
+        return Ok(None);
+    };
+    while matches!(tokens.get(index), Some(TokenV0::Space)) {
+        index += 1;
+    }
+    if !matches!(tokens.get(index), Some(TokenV0::EndGroup)) {
+        return Ok(None);
+    }
+    layout.geometry.set_length_v0(name, sp)?;
+    Ok(Some(index + 1))
+}
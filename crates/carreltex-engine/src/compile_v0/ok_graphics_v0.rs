@@ -0,0 +1,205 @@
+use super::ok_args_v0::{consume_group_bytes_v0, is_control_seq_v0, skip_spaces};
+use super::ok_dimen_v0::{scan_dimen_v0, OkLayoutV0};
+use super::ok_math_layout_v0::dimen_v0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+use carreltex_core::Mount;
+use carreltex_xdv::parse_image_v0;
+use std::ops::Range;
+
+/// Extensions tried, in order, for a name without one: the bitmap formats of
+/// graphicx's `dvipdfmx` driver.
+const IMAGE_EXTENSIONS_V0: &[&[u8]] = &[b".png", b".jpg", b".jpeg"];
+const UNITY_SP_V0: i64 = 65_536;
+
+/// A file named by `\includegraphics` and found in the mount.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OkImageV0 {
+    /// The name as the argument gives it.
+    name: Vec<u8>,
+    /// Mount path of the file, with the extension found for it.
+    pub(crate) path: Vec<u8>,
+    /// Size at the file's resolution; `None` when it is no PNG or JPEG.
+    natural_size: Option<(i32, i32)>,
+}
+
+/// An image as `\includegraphics` places it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OkGraphicV0 {
+    pub(crate) path: Vec<u8>,
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+}
+
+/// Tokens inside an optional `[...]` argument at `index`, braces balanced, and
+/// where the argument ends; an empty range without one.
+fn consume_options_v0(tokens: &[TokenV0], index: usize) -> Option<(Range<usize>, usize)> {
+    if !matches!(tokens.get(index), Some(TokenV0::Char(b'['))) {
+        return Some((index..index, index));
+    }
+    let mut depth = 0usize;
+    for (end, token) in tokens.iter().enumerate().skip(index + 1) {
+        match token {
+            TokenV0::BeginGroup => depth += 1,
+            TokenV0::EndGroup => depth = depth.checked_sub(1)?,
+            TokenV0::Char(b']') if depth == 0 => return Some((index + 1..end, end + 1)),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn find_image_v0(name: &[u8], mount: &Mount) -> Option<OkImageV0> {
+    let file_name = name.rsplit(|byte| *byte == b'/').next()?;
+    let paths = if file_name.contains(&b'.') {
+        vec![name.to_vec()]
+    } else {
+        IMAGE_EXTENSIONS_V0
+            .iter()
+            .map(|extension| [name, extension].concat())
+            .collect()
+    };
+    paths.into_iter().find_map(|path| {
+        let bytes = mount.read_file_by_bytes_v0(&path).ok()??;
+        Some(OkImageV0 {
+            name: name.to_vec(),
+            natural_size: parse_image_v0(bytes).and_then(|image| image.natural_size_sp_v0()),
+            path,
+        })
+    })
+}
+
+/// The mounted files of the `\includegraphics` commands in `token_lists`, once
+/// each; names found nowhere are left out.
+pub(crate) fn mounted_images_v0(token_lists: &[&[TokenV0]], mount: &Mount) -> Vec<OkImageV0> {
+    let mut images = Vec::<OkImageV0>::new();
+    for tokens in token_lists {
+        for index in 0..tokens.len() {
+            if !is_control_seq_v0(tokens.get(index), b"includegraphics") {
+                continue;
+            }
+            let Some((_, next_index)) = consume_options_v0(tokens, index + 1) else {
+                continue;
+            };
+            let Some((name, _)) = consume_group_bytes_v0(tokens, next_index) else {
+                continue;
+            };
+            if images.iter().all(|image| image.name != name) {
+                images.extend(find_image_v0(&name, mount));
+            }
+        }
+    }
+    images
+}
+
+/// A dimension filling all of `tokens`.
+fn scan_whole_dimen_v0(
+    tokens: &[TokenV0],
+    layout: &OkLayoutV0,
+) -> Result<Option<i32>, InvalidInputReasonV0> {
+    let Some((sp, index)) = scan_dimen_v0(tokens, 0, layout)? else {
+        return Ok(None);
+    };
+    if skip_spaces(tokens, index) != tokens.len() {
+        return Err(InvalidInputReasonV0::DimenInvalid);
+    }
+    Ok(Some(sp))
+}
+
+/// `value * numerator / denominator`, rounded.
+fn scale_sp_v0(value: i32, numerator: i64, denominator: i64) -> i64 {
+    (2 * i64::from(value) * numerator + denominator) / (2 * denominator)
+}
+
+/// `\includegraphics[options]{name}` after the command at `index`. The options are
+/// `width`, `height` and `scale`: the image gets its natural size times `scale`,
+/// or the sides given, the other one following the aspect ratio when only one is.
+/// `scale` with a side, other keys and names `pdf:image` cannot carry are outside
+/// the subset.
+pub(crate) fn consume_includegraphics_v0(
+    tokens: &[TokenV0],
+    index: usize,
+    images: &[OkImageV0],
+    layout: &OkLayoutV0,
+) -> Result<Option<(OkGraphicV0, usize)>, InvalidInputReasonV0> {
+    let Some((options, index)) = consume_options_v0(tokens, index) else {
+        return Ok(None);
+    };
+    let Some((name, index)) = consume_group_bytes_v0(tokens, index) else {
+        return Ok(None);
+    };
+    if name.iter().any(|byte| matches!(byte, b'(' | b')')) {
+        return Ok(None);
+    }
+    let image = images
+        .iter()
+        .find(|image| image.name == name)
+        .ok_or(InvalidInputReasonV0::ImageMissing)?;
+    let (natural_width, natural_height) = image
+        .natural_size
+        .ok_or(InvalidInputReasonV0::ImageFormatUnsupported)?;
+    let (mut width, mut height, mut scale) = (None, None, None);
+    for option in tokens[options].split(|token| matches!(token, TokenV0::Char(b','))) {
+        let start = skip_spaces(option, 0);
+        if start == option.len() {
+            continue;
+        }
+        let Some(equals) = option
+            .iter()
+            .position(|token| matches!(token, TokenV0::Char(b'=')))
+        else {
+            return Ok(None);
+        };
+        let mut key = Vec::<u8>::new();
+        for token in &option[start..equals] {
+            match token {
+                TokenV0::Char(byte) => key.push(*byte),
+                TokenV0::Space => {}
+                _ => return Ok(None),
+            }
+        }
+        let value = &option[equals + 1..];
+        let Some(sp) = (match key.as_slice() {
+            b"width" | b"height" => scan_whole_dimen_v0(value, layout)?,
+            b"scale" => {
+                let mut value = value.to_vec();
+                value.extend([TokenV0::Char(b'p'), TokenV0::Char(b't')]);
+                scan_whole_dimen_v0(&value, layout)?
+            }
+            _ => return Ok(None),
+        }) else {
+            return Ok(None);
+        };
+        if sp <= 0 {
+            return Err(InvalidInputReasonV0::DimenInvalid);
+        }
+        match key.as_slice() {
+            b"width" => width = Some(sp),
+            b"height" => height = Some(sp),
+            _ => scale = Some(sp),
+        }
+    }
+    let (width, height) = match (width, height, scale) {
+        (None, None, Some(scale)) => (
+            scale_sp_v0(natural_width, i64::from(scale), UNITY_SP_V0),
+            scale_sp_v0(natural_height, i64::from(scale), UNITY_SP_V0),
+        ),
+        (_, _, Some(_)) => return Ok(None),
+        (None, None, None) => (i64::from(natural_width), i64::from(natural_height)),
+        (Some(width), None, None) => (
+            i64::from(width),
+            scale_sp_v0(natural_height, i64::from(width), i64::from(natural_width)),
+        ),
+        (None, Some(height), None) => (
+            scale_sp_v0(natural_width, i64::from(height), i64::from(natural_height)),
+            i64::from(height),
+        ),
+        (Some(width), Some(height), None) => (i64::from(width), i64::from(height)),
+    };
+    let graphic = OkGraphicV0 {
+        path: image.path.clone(),
+        width: dimen_v0(width)?,
+        height: dimen_v0(height)?,
+    };
+    Ok(Some((graphic, index)))
+}
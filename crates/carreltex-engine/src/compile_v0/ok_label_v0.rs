@@ -0,0 +1,162 @@
+use super::ok_cite_v0::{write_citations_aux_v0, AuxBibciteV0, OkCitationsV0};
+use super::ok_float_v0::FloatEntryV0;
+use super::ok_page_v0::OkPagesV0;
+use super::ok_toc_v0::TocEntryV0;
+use carreltex_core::LOG_WARNING_PREFIX_V0;
+
+/// Labels a document may define, each one a `\newlabel` line of its `.aux` file.
+pub(crate) const MAX_OK_LABELS_V0: usize = 4096;
+/// What `\ref` and `\pageref` set for a label the previous pass did not define.
+const UNDEFINED_REFERENCE_V0: &[u8] = b"??";
+
+/// `\label{key}` at text `offset`, with the `\ref` text in effect there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OkLabelV0 {
+    pub(crate) key: Vec<u8>,
+    pub(crate) reference: Vec<u8>,
+    pub(crate) offset: usize,
+}
+
+/// A `\ref`, `\pageref` or `\eqref` at text `offset` to a label the previous pass
+/// did not define, or with `citation` a `\cite` of a key it did not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OkUndefinedRefV0 {
+    pub(crate) key: Vec<u8>,
+    pub(crate) offset: usize,
+    pub(crate) citation: bool,
+}
+
+/// One `\newlabel{key}{{reference}{page}}` line of the `.aux` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AuxLabelV0 {
+    pub(crate) key: Vec<u8>,
+    pub(crate) reference: Vec<u8>,
+    pub(crate) page: Vec<u8>,
+}
+
+/// What a pass leaves for the next one: its `.aux` labels and citation labels,
+/// `.toc` entries, `.lof` and `.lot` entries and page numbers.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct OkAuxFilesV0 {
+    pub(crate) labels: Vec<AuxLabelV0>,
+    pub(crate) bibcites: Vec<AuxBibciteV0>,
+    pub(crate) toc: Vec<TocEntryV0>,
+    pub(crate) floats: Vec<FloatEntryV0>,
+    pub(crate) pages: OkPagesV0,
+}
+
+/// The label a `\ref` resolves to; a key defined more than once keeps its last
+/// definition, as LaTeX's `\newlabel` does.
+pub(crate) fn find_aux_label_v0<'a>(
+    labels: &'a [AuxLabelV0],
+    key: &[u8],
+) -> Option<&'a AuxLabelV0> {
+    labels.iter().rev().find(|label| label.key == key)
+}
+
+/// Text set by `\ref`, `\pageref` and `\eqref`; `None` for other commands.
+pub(crate) fn ref_text_v0(command: &[u8], label: Option<&AuxLabelV0>) -> Option<Vec<u8>> {
+    let text =
+        |field: fn(&AuxLabelV0) -> &[u8]| label.map_or(UNDEFINED_REFERENCE_V0, field).to_vec();
+    match command {
+        b"ref" => Some(text(|label| &label.reference)),
+        b"pageref" => Some(text(|label| &label.page)),
+        b"eqref" => Some([b"(", text(|label| &label.reference).as_slice(), b")"].concat()),
+        _ => None,
+    }
+}
+
+/// Labels of a pass as its `.aux` records them, with the page each one fell on.
+pub(crate) fn aux_labels_v0(labels: &[OkLabelV0], pages: &OkPagesV0) -> Vec<AuxLabelV0> {
+    labels
+        .iter()
+        .map(|label| AuxLabelV0 {
+            key: label.key.clone(),
+            reference: label.reference.clone(),
+            page: pages.label_v0(label.offset),
+        })
+        .collect()
+}
+
+/// `\relax`, the lines of `citations`, then one `\newlabel` per label, in
+/// definition order.
+pub(crate) fn write_aux_v0(labels: &[AuxLabelV0], citations: &OkCitationsV0) -> Vec<u8> {
+    let mut aux = b"\\relax\n".to_vec();
+    aux.extend_from_slice(&write_citations_aux_v0(citations));
+    for label in labels {
+        aux.extend_from_slice(b"\\newlabel{");
+        aux.extend_from_slice(&label.key);
+        aux.extend_from_slice(b"}{{");
+        aux.extend_from_slice(&label.reference);
+        aux.extend_from_slice(b"}{");
+        aux.extend_from_slice(&label.page);
+        aux.extend_from_slice(b"}}\n");
+    }
+    aux
+}
+
+/// Warnings of the last pass in LaTeX's order and wording (without input line
+/// numbers): class options no one used, multiply defined labels, undefined
+/// references and citations, the end-of-run summaries and, when `rerun`, the
+/// request to run again.
+pub(crate) fn label_warnings_v0(
+    unused_class_options: &[Vec<u8>],
+    labels: &[AuxLabelV0],
+    undefined_refs: &[OkUndefinedRefV0],
+    pages: &OkPagesV0,
+    rerun: bool,
+) -> Vec<u8> {
+    let mut warnings = Vec::<Vec<u8>>::new();
+    if !unused_class_options.is_empty() {
+        warnings.push(
+            [
+                b"Unused global option(s): [".as_slice(),
+                &unused_class_options.join(&b','),
+                b"].",
+            ]
+            .concat(),
+        );
+    }
+    let mut multiply_defined = false;
+    for (index, label) in labels.iter().enumerate() {
+        if labels[..index]
+            .iter()
+            .any(|earlier| earlier.key == label.key)
+        {
+            warnings.push([b"Label `", label.key.as_slice(), b"' multiply defined."].concat());
+            multiply_defined = true;
+        }
+    }
+    for undefined in undefined_refs {
+        let page = pages.label_v0(undefined.offset);
+        let kind: &[u8] = if undefined.citation {
+            b"Citation `"
+        } else {
+            b"Reference `"
+        };
+        warnings.push(
+            [
+                kind,
+                undefined.key.as_slice(),
+                b"' on page ",
+                page.as_slice(),
+                b" undefined.",
+            ]
+            .concat(),
+        );
+    }
+    if !undefined_refs.is_empty() {
+        warnings.push(b"There were undefined references.".to_vec());
+    }
+    if multiply_defined {
+        warnings.push(b"There were multiply-defined labels.".to_vec());
+    }
+    if rerun {
+        warnings.push(b"Label(s) may have changed. Rerun to get cross-references right.".to_vec());
+    }
+    warnings
+        .iter()
+        .map(|warning| [LOG_WARNING_PREFIX_V0, warning.as_slice()].concat())
+        .collect::<Vec<_>>()
+        .join(&b'\n')
+}
@@ -0,0 +1,422 @@
+use super::ok_page_v0::PageNumberingV0;
+use super::ok_section_v0::heading_text_v0;
+use super::ok_v0::is_supported_ok_char_v0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+/// LaTeX's limit on nested lists of any kind.
+const MAX_LIST_DEPTH_V0: usize = 6;
+/// Levels with their own labels and counters for `itemize` and `enumerate`.
+const MAX_LIST_KIND_DEPTH_V0: usize = 4;
+/// Article's `\leftmargini`..`\leftmarginvi` in hundredths of an em.
+const LEFT_MARGIN_EM_HUNDREDTHS_V0: [i32; MAX_LIST_DEPTH_V0] = [250, 220, 187, 170, 100, 100];
+/// `\labelitemi`..`\labelitemiv` in the tokenizer's ASCII stand-ins for
+/// `\textbullet`, `\textendash`, `\textasteriskcentered` and `\textperiodcentered`.
+const ITEM_LABELS_V0: [&[u8]; MAX_LIST_KIND_DEPTH_V0] = [b"*", b"-", b"*", b"."];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListKindV0 {
+    Itemize,
+    Enumerate,
+    Description,
+    /// `thebibliography`, which `from_name_v0` leaves to the bibliography code.
+    Bibliography,
+}
+
+impl ListKindV0 {
+    pub(crate) fn from_name_v0(name: &[u8]) -> Option<Self> {
+        match name {
+            b"itemize" => Some(Self::Itemize),
+            b"enumerate" => Some(Self::Enumerate),
+            b"description" => Some(Self::Description),
+            _ => None,
+        }
+    }
+}
+
+/// One open list: `left_sp` is its text margin, `outer_left_sp` the enclosing one,
+/// and `group_depth` the group count inside its environment group.
+#[derive(Debug)]
+struct ListFrameV0 {
+    kind: ListKindV0,
+    kind_depth: usize,
+    left_sp: i32,
+    outer_left_sp: i32,
+    counter: u32,
+    has_item: bool,
+    group_depth: usize,
+    label: Option<ListLabelV0>,
+}
+
+/// Placement of an item label, as `carreltex_xdv::DviTextIndentV0` takes it;
+/// `description` labels are set in bold. Numbered items also carry their `\ref` text.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ListItemV0 {
+    pub(crate) label: Vec<u8>,
+    pub(crate) reference: Option<Vec<u8>>,
+    pub(crate) bold_label: bool,
+    pub(crate) left_sp: i32,
+    pub(crate) label_sep_sp: i32,
+    pub(crate) label_h_sp: Option<i32>,
+}
+
+pub(crate) fn roman_v0(mut value: u32) -> Vec<u8> {
+    const NUMERALS_V0: [(u32, &[u8]); 13] = [
+        (1000, b"m"),
+        (900, b"cm"),
+        (500, b"d"),
+        (400, b"cd"),
+        (100, b"c"),
+        (90, b"xc"),
+        (50, b"l"),
+        (40, b"xl"),
+        (10, b"x"),
+        (9, b"ix"),
+        (5, b"v"),
+        (4, b"iv"),
+        (1, b"i"),
+    ];
+    let mut out = Vec::<u8>::new();
+    for (amount, numeral) in NUMERALS_V0 {
+        while value >= amount {
+            out.extend_from_slice(numeral);
+            value -= amount;
+        }
+    }
+    out
+}
+
+/// `\theenumi`..`\theenumiv`: `1`, `a`, `i` and `A`.
+fn enumerate_counter_v0(kind_depth: usize, counter: u32) -> Result<Vec<u8>, InvalidInputReasonV0> {
+    let letter = |base: u8| {
+        u8::try_from(counter)
+            .ok()
+            .filter(|value| (1..=26).contains(value))
+            .map(|value| base + value - 1)
+            .ok_or(InvalidInputReasonV0::CounterTooLarge)
+    };
+    Ok(match kind_depth {
+        0 => counter.to_string().into_bytes(),
+        1 => vec![letter(b'a')?],
+        2 => roman_v0(counter),
+        _ => vec![letter(b'A')?],
+    })
+}
+
+/// `\labelenumi`..`\labelenumiv`: `1.`, `(a)`, `i.` and `A.`.
+fn enumerate_label_v0(kind_depth: usize, value: &[u8]) -> Vec<u8> {
+    match kind_depth {
+        1 => [b"(", value, b")"].concat(),
+        _ => [value, b"."].concat(),
+    }
+}
+
+/// `\p@enumN\theenumN` over the counters of the open `enumerate` levels: `1`,
+/// `1a`, `1(a)i` and `1(a)iA`.
+fn enumerate_reference_v0(values: &[Vec<u8>]) -> Vec<u8> {
+    let mut reference = Vec::<u8>::new();
+    for (kind_depth, value) in values.iter().enumerate() {
+        if kind_depth == 1 && values.len() > 2 {
+            reference.extend_from_slice(&enumerate_label_v0(kind_depth, value));
+        } else {
+            reference.extend_from_slice(value);
+        }
+    }
+    reference
+}
+
+/// Open `itemize`, `enumerate`, `description` and `thebibliography` environments,
+/// innermost last.
+#[derive(Debug, Default)]
+pub(crate) struct ListStackV0 {
+    frames: Vec<ListFrameV0>,
+}
+
+impl ListStackV0 {
+    pub(crate) fn is_empty_v0(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub(crate) fn left_sp_v0(&self) -> i32 {
+        self.frames.last().map_or(0, |frame| frame.left_sp)
+    }
+
+    /// Text before the first `\item` of the innermost list.
+    pub(crate) fn awaits_item_v0(&self) -> bool {
+        self.frames.last().is_some_and(|frame| !frame.has_item)
+    }
+
+    /// Whether closing a group now would close the innermost list's environment.
+    pub(crate) fn owns_group_v0(&self, group_depth: usize) -> bool {
+        self.frames
+            .last()
+            .is_some_and(|frame| frame.group_depth == group_depth)
+    }
+
+    pub(crate) fn begin_v0(
+        &mut self,
+        kind: ListKindV0,
+        em_sp: i32,
+        group_depth: usize,
+    ) -> Result<(), InvalidInputReasonV0> {
+        let depth = self.frames.len().min(MAX_LIST_DEPTH_V0 - 1);
+        let margin = (em_sp as i64 * LEFT_MARGIN_EM_HUNDREDTHS_V0[depth] as i64 / 100) as i32;
+        self.push_frame_v0(kind, margin, group_depth)
+    }
+
+    /// `thebibliography`'s list: its margin is the width of the widest label, set
+    /// as `label_width_sp`, plus the label separation.
+    pub(crate) fn begin_bibliography_v0(
+        &mut self,
+        label_width_sp: i32,
+        em_sp: i32,
+        group_depth: usize,
+    ) -> Result<(), InvalidInputReasonV0> {
+        let margin = label_width_sp.saturating_add(em_sp / 2);
+        self.push_frame_v0(ListKindV0::Bibliography, margin, group_depth)
+    }
+
+    fn push_frame_v0(
+        &mut self,
+        kind: ListKindV0,
+        margin: i32,
+        group_depth: usize,
+    ) -> Result<(), InvalidInputReasonV0> {
+        let kind_depth = self
+            .frames
+            .iter()
+            .filter(|frame| frame.kind == kind)
+            .count();
+        if self.frames.len() >= MAX_LIST_DEPTH_V0
+            || (matches!(kind, ListKindV0::Itemize | ListKindV0::Enumerate)
+                && kind_depth >= MAX_LIST_KIND_DEPTH_V0)
+        {
+            return Err(InvalidInputReasonV0::ListNestingExceeded);
+        }
+        let outer_left_sp = self.left_sp_v0();
+        self.frames.push(ListFrameV0 {
+            kind,
+            kind_depth,
+            left_sp: outer_left_sp.saturating_add(margin),
+            outer_left_sp,
+            counter: 0,
+            has_item: false,
+            group_depth,
+            label: None,
+        });
+        Ok(())
+    }
+
+    /// enumitem's `label=` for the items of the innermost list.
+    pub(crate) fn set_label_v0(&mut self, label: ListLabelV0) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.label = Some(label);
+        }
+    }
+
+    /// `\end{name}` of the innermost list, which must have been opened by the
+    /// matching `\begin` in the same group.
+    pub(crate) fn end_v0(
+        &mut self,
+        kind: ListKindV0,
+        group_depth: usize,
+    ) -> Result<(), InvalidInputReasonV0> {
+        match self.frames.last() {
+            Some(frame) if frame.kind == kind && frame.group_depth == group_depth => {
+                self.frames.pop();
+                Ok(())
+            }
+            _ => Err(InvalidInputReasonV0::ListEnvironmentUnbalanced),
+        }
+    }
+
+    /// `\item` or `\item[label]`; only unlabelled `enumerate` and `thebibliography`
+    /// items step the counter.
+    pub(crate) fn item_v0(
+        &mut self,
+        label: Option<Vec<u8>>,
+        em_sp: i32,
+    ) -> Result<ListItemV0, InvalidInputReasonV0> {
+        let frame = self
+            .frames
+            .last_mut()
+            .ok_or(InvalidInputReasonV0::ListItemOutsideList)?;
+        frame.has_item = true;
+        let (left_sp, outer_left_sp) = (frame.left_sp, frame.outer_left_sp);
+        let description = frame.kind == ListKindV0::Description;
+        let mut reference = None;
+        let label = match (label, frame.kind, &frame.label) {
+            (Some(label), _, _) => label,
+            (None, ListKindV0::Itemize | ListKindV0::Enumerate, Some(custom)) => {
+                if frame.kind == ListKindV0::Enumerate {
+                    frame.counter = frame.counter.saturating_add(1);
+                }
+                let label = custom.format_v0(frame.counter)?;
+                if frame.kind == ListKindV0::Enumerate {
+                    reference = Some(label.clone());
+                }
+                label
+            }
+            (None, ListKindV0::Itemize, None) => ITEM_LABELS_V0[frame.kind_depth].to_vec(),
+            (None, ListKindV0::Enumerate, None) => {
+                frame.counter = frame.counter.saturating_add(1);
+                let kind_depth = frame.kind_depth;
+                let values = self
+                    .frames
+                    .iter()
+                    .filter(|frame| frame.kind == ListKindV0::Enumerate)
+                    .map(|frame| enumerate_counter_v0(frame.kind_depth, frame.counter))
+                    .collect::<Result<Vec<_>, _>>()?;
+                reference = Some(enumerate_reference_v0(&values));
+                enumerate_label_v0(kind_depth, &values[kind_depth])
+            }
+            (None, ListKindV0::Description, _) => Vec::new(),
+            (None, ListKindV0::Bibliography, _) => {
+                frame.counter = frame.counter.saturating_add(1);
+                let number = frame.counter.to_string().into_bytes();
+                reference = Some(number.clone());
+                [b"[", number.as_slice(), b"]"].concat()
+            }
+        };
+        Ok(ListItemV0 {
+            label,
+            reference,
+            bold_label: description,
+            left_sp,
+            label_sep_sp: em_sp / 2,
+            label_h_sp: description.then_some(outer_left_sp),
+        })
+    }
+}
+
+/// Optional `[label]` of `\item`: characters and spaces, collapsed as in headings.
+pub(crate) fn consume_item_label_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+) -> Option<(Option<Vec<u8>>, usize)> {
+    if !matches!(tokens.get(index), Some(TokenV0::Char(b'['))) {
+        return Some((None, index));
+    }
+    index += 1;
+    let mut bytes = Vec::<u8>::new();
+    loop {
+        match tokens.get(index)? {
+            TokenV0::Char(b']') => break,
+            TokenV0::Char(byte) => bytes.push(*byte),
+            TokenV0::Space => bytes.push(b' '),
+            _ => return None,
+        }
+        index += 1;
+    }
+    Some((Some(heading_text_v0(None, &bytes)?), index + 1))
+}
+
+/// enumitem's `label=` of a list: text around at most one counter of the list,
+/// `\arabic*`, `\alph*`, `\Alph*`, `\roman*` or `\Roman*`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ListLabelV0 {
+    before: Vec<u8>,
+    counter: Option<PageNumberingV0>,
+    after: Vec<u8>,
+}
+
+impl ListLabelV0 {
+    /// The label of the item numbered `value`, which also serves as its `\ref` text.
+    fn format_v0(&self, value: u32) -> Result<Vec<u8>, InvalidInputReasonV0> {
+        let value = match self.counter {
+            Some(counter) => counter.format_v0(value)?,
+            None => Vec::new(),
+        };
+        Ok([self.before.as_slice(), &value, &self.after].concat())
+    }
+}
+
+/// enumitem's optional `[keys]` after `\begin` of a list of `kind`: `label=`, with
+/// a counter in `enumerate` only, and `nosep` / `noitemsep`, which the layout,
+/// with no space between items, already follows. Braces in the label hide commas
+/// and brackets.
+pub(crate) fn consume_list_options_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+    kind: ListKindV0,
+) -> Option<(Option<ListLabelV0>, usize)> {
+    if !matches!(tokens.get(index), Some(TokenV0::Char(b'['))) {
+        return Some((None, index));
+    }
+    index += 1;
+    let mut label = None;
+    loop {
+        let mut key = Vec::<u8>::new();
+        loop {
+            match tokens.get(index)? {
+                TokenV0::Char(b'=' | b',' | b']') => break,
+                TokenV0::Char(byte) => key.push(*byte),
+                TokenV0::Space => {}
+                _ => return None,
+            }
+            index += 1;
+        }
+        match key.as_slice() {
+            b"nosep" | b"noitemsep" => {}
+            b"label" if kind != ListKindV0::Description => {
+                let (value, next_index) = consume_list_label_v0(tokens, index + 1, kind)?;
+                label = Some(value);
+                index = next_index;
+            }
+            _ => return None,
+        }
+        match tokens.get(index)? {
+            TokenV0::Char(b',') => index += 1,
+            TokenV0::Char(b']') => return Some((label, index + 1)),
+            _ => return None,
+        }
+    }
+}
+
+/// The value of `label=` up to the `,` or `]` that ends it.
+fn consume_list_label_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+    kind: ListKindV0,
+) -> Option<(ListLabelV0, usize)> {
+    let mut label = ListLabelV0 {
+        before: Vec::new(),
+        counter: None,
+        after: Vec::new(),
+    };
+    let mut depth = 0usize;
+    loop {
+        let text = if label.counter.is_some() {
+            &mut label.after
+        } else {
+            &mut label.before
+        };
+        match tokens.get(index)? {
+            TokenV0::Char(b',' | b']') if depth == 0 => break,
+            TokenV0::Char(byte) if is_supported_ok_char_v0(*byte) => text.push(*byte),
+            TokenV0::Space => text.push(b' '),
+            TokenV0::BeginGroup => depth += 1,
+            TokenV0::EndGroup => depth = depth.checked_sub(1)?,
+            TokenV0::ControlSeq(name)
+                if kind == ListKindV0::Enumerate
+                    && label.counter.is_none()
+                    && matches!(tokens.get(index + 1), Some(TokenV0::Char(b'*'))) =>
+            {
+                label.counter = Some(PageNumberingV0::from_name_v0(name)?);
+                index += 1;
+            }
+            _ => return None,
+        }
+        index += 1;
+    }
+    if depth != 0 {
+        return None;
+    }
+    label.before = label.before.trim_ascii_start().to_vec();
+    if label.counter.is_some() {
+        label.after = label.after.trim_ascii_end().to_vec();
+    } else {
+        label.before = label.before.trim_ascii_end().to_vec();
+    }
+    Some((label, index))
+}
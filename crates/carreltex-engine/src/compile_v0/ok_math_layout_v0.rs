@@ -0,0 +1,708 @@
+use super::ok_body_v0::OkBodyV0;
+use super::ok_font_v0::{FontTableV0, OkFontV0};
+use super::ok_math_v0::{AtomKindV0, MathCharV0, MathFamilyV0, MathItemV0, NoadV0, NucleusV0};
+use crate::reasons_v0::InvalidInputReasonV0;
+use carreltex_xdv::{
+    glyph_width_sp_v0, DviTextFontV0, DviTextKernV0, DviTextRaiseV0, DviTextRuleV0,
+    DVI_TEXT_FONT_DESIGN_SIZE_SP_V0, MAX_DVI_RULE_DIMENSION_SP_V0, MAX_DVI_TEXT_KERNS_V0,
+    MAX_DVI_TEXT_RAISES_V0, MAX_DVI_TEXT_RULES_V0,
+};
+
+/// Font dimensions of `cmsy10` (family 2) and `cmex10` (family 3), in millionths of
+/// the em of the size they are set at.
+const X_HEIGHT_V0: i64 = 430_554;
+const NUM1_V0: i64 = 676_508;
+const NUM2_V0: i64 = 393_732;
+const DENOM1_V0: i64 = 685_951;
+const DENOM2_V0: i64 = 344_841;
+const SUP1_V0: i64 = 412_892;
+const SUP2_V0: i64 = 362_892;
+const SUP3_V0: i64 = 288_889;
+const SUB1_V0: i64 = 150_000;
+const SUB2_V0: i64 = 247_217;
+const SUP_DROP_V0: i64 = 386_108;
+const SUB_DROP_V0: i64 = 50_000;
+const AXIS_HEIGHT_V0: i64 = 250_000;
+const DEFAULT_RULE_THICKNESS_V0: i64 = 39_999;
+const BIG_OP_SPACING_V0: [i64; 5] = [111_112, 166_667, 200_000, 600_000, 100_000];
+/// `\nulldelimiterspace` (1.2pt) and `\scriptspace` (0.5pt), of the normal em.
+const NULL_DELIMITER_SPACE_V0: i64 = 120_000;
+const SCRIPT_SPACE_V0: i64 = 50_000;
+/// The radical sign, in the symbol font.
+const RADICAL_CODE_V0: u8 = 0xd6;
+
+/// TeX's `math_spacing` table, rows and columns in `AtomKindV0` order: `1` is a thin
+/// space outside scripts, `2` a thin space, `3` a medium and `4` a thick space
+/// outside scripts; `*` pairs cannot occur.
+const MATH_SPACING_V0: [&[u8; 8]; 8] = [
+    b"02340001",
+    b"22*40001",
+    b"33**3**3",
+    b"44*04004",
+    b"00*00000",
+    b"02340001",
+    b"11*11111",
+    b"12341011",
+];
+
+/// Display, text, script and scriptscript style, cramped or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MathStyleV0 {
+    level: u8,
+    cramped: bool,
+}
+
+impl MathStyleV0 {
+    fn is_display_v0(self) -> bool {
+        self.level == 0
+    }
+
+    fn is_script_v0(self) -> bool {
+        self.level >= 2
+    }
+
+    fn size_index_v0(self) -> usize {
+        usize::from(self.level.saturating_sub(1))
+    }
+
+    fn sup_v0(self) -> Self {
+        MathStyleV0 {
+            level: if self.level < 2 { 2 } else { 3 },
+            cramped: self.cramped,
+        }
+    }
+
+    fn sub_v0(self) -> Self {
+        MathStyleV0 {
+            cramped: true,
+            ..self.sup_v0()
+        }
+    }
+
+    fn numerator_v0(self) -> Self {
+        MathStyleV0 {
+            level: (self.level + 1).clamp(1, 3),
+            cramped: self.cramped,
+        }
+    }
+
+    fn denominator_v0(self) -> Self {
+        MathStyleV0 {
+            cramped: true,
+            ..self.numerator_v0()
+        }
+    }
+
+    fn cramped_v0(self) -> Self {
+        MathStyleV0 {
+            cramped: true,
+            ..self
+        }
+    }
+}
+
+/// Typeset material, left to right: glyphs raised from the baseline, kerns, and
+/// rules that advance by their width with their bottom edge raised by `raise`.
+/// A math list item with the kind its noad is set as.
+enum MathAtomV0<'a> {
+    Noad(&'a NoadV0, AtomKindV0),
+    Space(i32),
+}
+
+/// Kind of the last noad among `atoms`.
+fn last_atom_kind_v0<'a>(atoms: &'a mut [MathAtomV0<'_>]) -> Option<&'a mut AtomKindV0> {
+    atoms.iter_mut().rev().find_map(|atom| match atom {
+        MathAtomV0::Noad(_, kind) => Some(kind),
+        MathAtomV0::Space(_) => None,
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MathNodeV0 {
+    Glyph {
+        font: Option<DviTextFontV0>,
+        code: u8,
+        raise: i64,
+    },
+    Kern(i64),
+    Rule {
+        width: i64,
+        thickness: i64,
+        raise: i64,
+    },
+}
+
+/// A formula set as a box, as `mlist_to_hlist` leaves it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct MathBoxV0 {
+    nodes: Vec<MathNodeV0>,
+    pub(crate) width: i64,
+    height: i64,
+    depth: i64,
+}
+
+impl MathBoxV0 {
+    fn kern_v0(&mut self, width: i64) {
+        if width == 0 {
+            return;
+        }
+        if let Some(MathNodeV0::Kern(last)) = self.nodes.last_mut() {
+            *last += width;
+            if *last == 0 {
+                self.nodes.pop();
+            }
+        } else {
+            self.nodes.push(MathNodeV0::Kern(width));
+        }
+        self.width += width;
+    }
+
+    fn append_v0(&mut self, other: MathBoxV0) {
+        let width = self.width + other.width;
+        for node in other.nodes {
+            match node {
+                MathNodeV0::Kern(width) => self.kern_v0(width),
+                node => self.nodes.push(node),
+            }
+        }
+        self.width = width;
+        self.height = self.height.max(other.height);
+        self.depth = self.depth.max(other.depth);
+    }
+
+    fn raise_v0(mut self, raise: i64) -> Self {
+        for node in &mut self.nodes {
+            match node {
+                MathNodeV0::Glyph { raise: shift, .. } | MathNodeV0::Rule { raise: shift, .. } => {
+                    *shift += raise
+                }
+                MathNodeV0::Kern(_) => {}
+            }
+        }
+        self.height += raise;
+        self.depth -= raise;
+        self
+    }
+
+    fn with_space_v0(mut self, space: i64) -> Self {
+        self.kern_v0(space);
+        self
+    }
+}
+
+/// Boxes set side by side from one left edge, each `x` from it, in a box of `width`.
+pub(crate) fn overlay_v0(parts: Vec<(MathBoxV0, i64)>, width: i64) -> MathBoxV0 {
+    let mut overlay = MathBoxV0::default();
+    for (part, x) in parts {
+        let part_width = part.width;
+        overlay.kern_v0(x);
+        overlay.append_v0(part);
+        overlay.kern_v0(-x - part_width);
+    }
+    overlay.kern_v0(width);
+    overlay
+}
+
+/// Height and depth of a glyph, in millionths of its font's em.
+fn glyph_extent_v0(character: MathCharV0) -> (i64, i64) {
+    match (character.family, character.code) {
+        (MathFamilyV0::Extension, _) => (750_000, 250_000),
+        (MathFamilyV0::Symbols, RADICAL_CODE_V0) => (917_000, 18_000),
+        (_, b'(' | b')' | b'[' | b']' | b'{' | b'}' | b'|' | 0xe1 | 0xf1) => (750_000, 250_000),
+        (_, b'0'..=b'9') => (644_444, 0),
+        (_, b'A'..=b'Z') => (683_333, 0),
+        (_, b'g' | b'j' | b'p' | b'q' | b'y') => (430_555, 194_445),
+        (_, b'b' | b'd' | b'f' | b'h' | b'k' | b'l' | b't') => (694_445, 0),
+        (_, b'a'..=b'z') => (430_555, 0),
+        _ => (583_333, 83_333),
+    }
+}
+
+fn fraction_v0(sp: i64, millionths: i64) -> i64 {
+    sp * millionths / 1_000_000
+}
+
+/// The text font at the three math sizes, with the normal em they scale.
+struct MathFontsV0 {
+    sizes: [OkFontV0; 3],
+    normal_em_sp: i32,
+}
+
+impl MathFontsV0 {
+    fn em_sp_v0(&self, font: &Option<DviTextFontV0>) -> i32 {
+        font.as_ref().map_or(self.normal_em_sp, |font| {
+            (i64::from(self.normal_em_sp) * i64::from(font.size_sp)
+                / i64::from(DVI_TEXT_FONT_DESIGN_SIZE_SP_V0)) as i32
+        })
+    }
+
+    /// A parameter of the symbol font at the size of `style`.
+    fn param_v0(&self, style: MathStyleV0, millionths: i64) -> i64 {
+        let em = self.sizes[style.size_index_v0()].em_sp_v0(self.normal_em_sp);
+        fraction_v0(i64::from(em), millionths)
+    }
+
+    /// DVI font of `family` at the size of `style`, the extension font scaled by
+    /// `tenths` / 10.
+    fn font_v0(
+        &self,
+        family: MathFamilyV0,
+        style: MathStyleV0,
+        tenths: i32,
+    ) -> Option<DviTextFontV0> {
+        let sized = self.sizes[style.size_index_v0()];
+        match family {
+            MathFamilyV0::Roman => sized.dvi_font_v0(),
+            MathFamilyV0::Bold => sized.declaration_v0(b"bfseries")?.dvi_font_v0(),
+            MathFamilyV0::Italic => sized.declaration_v0(b"itshape")?.dvi_font_v0(),
+            MathFamilyV0::MathItalic => Some(sized.math_dvi_font_v0(b"mi", 10)),
+            MathFamilyV0::Symbols => Some(sized.math_dvi_font_v0(b"sy", 10)),
+            MathFamilyV0::Extension => Some(sized.math_dvi_font_v0(b"ex", tenths)),
+        }
+    }
+
+    fn char_box_v0(&self, character: MathCharV0, style: MathStyleV0, tenths: i32) -> MathBoxV0 {
+        let font = self.font_v0(character.family, style, tenths);
+        let em = self.em_sp_v0(&font);
+        let (height, depth) = glyph_extent_v0(character);
+        MathBoxV0 {
+            width: i64::from(glyph_width_sp_v0(character.code, em).unwrap_or(em)),
+            height: fraction_v0(i64::from(em), height),
+            depth: fraction_v0(i64::from(em), depth),
+            nodes: vec![MathNodeV0::Glyph {
+                font,
+                code: character.code,
+                raise: 0,
+            }],
+        }
+    }
+
+    /// TeX's `mlist_to_hlist`: atoms set with their scripts, binary operators that
+    /// cannot be ones made ordinary, and the spacing table between neighbours.
+    fn list_v0(&self, list: &[MathItemV0], style: MathStyleV0) -> MathBoxV0 {
+        let mut atoms = Vec::<MathAtomV0<'_>>::with_capacity(list.len());
+        for item in list {
+            let noad = match item {
+                MathItemV0::Space(amount) => {
+                    atoms.push(MathAtomV0::Space(*amount));
+                    continue;
+                }
+                MathItemV0::Noad(noad) => noad,
+            };
+            let mut kind = noad.kind;
+            let previous = last_atom_kind_v0(&mut atoms);
+            if kind == AtomKindV0::Bin
+                && matches!(
+                    previous.as_deref(),
+                    None | Some(
+                        AtomKindV0::Bin
+                            | AtomKindV0::Op
+                            | AtomKindV0::Rel
+                            | AtomKindV0::Open
+                            | AtomKindV0::Punct
+                    )
+                )
+            {
+                kind = AtomKindV0::Ord;
+            }
+            if matches!(
+                kind,
+                AtomKindV0::Rel | AtomKindV0::Close | AtomKindV0::Punct
+            ) {
+                if let Some(previous @ AtomKindV0::Bin) = previous {
+                    *previous = AtomKindV0::Ord;
+                }
+            }
+            atoms.push(MathAtomV0::Noad(noad, kind));
+        }
+        if let Some(last @ AtomKindV0::Bin) = last_atom_kind_v0(&mut atoms) {
+            *last = AtomKindV0::Ord;
+        }
+        let mu = self.param_v0(style, 1_000_000) / 18;
+        let mut hlist = MathBoxV0::default();
+        let mut previous = None::<AtomKindV0>;
+        for atom in atoms {
+            let (noad, kind) = match atom {
+                MathAtomV0::Space(amount) => {
+                    hlist.kern_v0(mu * i64::from(amount));
+                    continue;
+                }
+                MathAtomV0::Noad(noad, kind) => (noad, kind),
+            };
+            if let Some(previous) = previous {
+                let space = match MATH_SPACING_V0[previous as usize][kind as usize] {
+                    b'1' if !style.is_script_v0() => 3,
+                    b'2' => 3,
+                    b'3' if !style.is_script_v0() => 4,
+                    b'4' if !style.is_script_v0() => 5,
+                    _ => 0,
+                };
+                hlist.kern_v0(mu * space);
+            }
+            hlist.append_v0(self.noad_v0(noad, kind, style));
+            previous = Some(kind);
+        }
+        hlist
+    }
+
+    fn noad_v0(&self, noad: &NoadV0, kind: AtomKindV0, style: MathStyleV0) -> MathBoxV0 {
+        let op = kind == AtomKindV0::Op;
+        let tenths = if op && style.is_display_v0() { 14 } else { 10 };
+        let nucleus = match &noad.nucleus {
+            NucleusV0::Empty => MathBoxV0::default(),
+            NucleusV0::Char(character) if op => {
+                let glyph = self.char_box_v0(*character, style, tenths);
+                let axis = self.param_v0(style, AXIS_HEIGHT_V0);
+                let raise = axis - (glyph.height - glyph.depth) / 2;
+                glyph.raise_v0(raise)
+            }
+            NucleusV0::Char(character) => self.char_box_v0(*character, style, tenths),
+            NucleusV0::List(list) => self.list_v0(list, style),
+            NucleusV0::Fraction(numerator, denominator) => {
+                self.fraction_v0(numerator, denominator, style)
+            }
+            NucleusV0::Radical(radicand) => self.radical_v0(radicand, style),
+        };
+        if noad.sup.is_none() && noad.sub.is_none() {
+            return nucleus;
+        }
+        if op && noad.limits.unwrap_or(style.is_display_v0()) {
+            return self.limits_v0(nucleus, noad, style);
+        }
+        let is_char = matches!(noad.nucleus, NucleusV0::Char(_)) && !op;
+        self.scripts_v0(nucleus, is_char, noad, style)
+    }
+
+    fn script_v0(&self, list: &[MathItemV0], style: MathStyleV0) -> MathBoxV0 {
+        let space = fraction_v0(i64::from(self.normal_em_sp), SCRIPT_SPACE_V0);
+        self.list_v0(list, style).with_space_v0(space)
+    }
+
+    /// TeX's `make_scripts`: the superscript and subscript after the nucleus, one over
+    /// the other when both are present.
+    fn scripts_v0(
+        &self,
+        mut nucleus: MathBoxV0,
+        is_char: bool,
+        noad: &NoadV0,
+        style: MathStyleV0,
+    ) -> MathBoxV0 {
+        let (mut shift_up, mut shift_down) = if is_char {
+            (0, 0)
+        } else {
+            (
+                nucleus.height - self.param_v0(style.sup_v0(), SUP_DROP_V0),
+                nucleus.depth + self.param_v0(style.sub_v0(), SUB_DROP_V0),
+            )
+        };
+        let x_height = self.param_v0(style, X_HEIGHT_V0);
+        let sub = noad
+            .sub
+            .as_ref()
+            .map(|sub| self.script_v0(sub, style.sub_v0()));
+        let Some(sup) = &noad.sup else {
+            let Some(sub) = sub else {
+                return nucleus;
+            };
+            shift_down = shift_down
+                .max(self.param_v0(style, SUB1_V0))
+                .max(sub.height - x_height * 4 / 5);
+            nucleus.append_v0(sub.raise_v0(-shift_down));
+            return nucleus;
+        };
+        let sup = self.script_v0(sup, style.sup_v0());
+        let clearance = match (style.is_display_v0(), style.cramped) {
+            (_, true) => SUP3_V0,
+            (true, false) => SUP1_V0,
+            (false, false) => SUP2_V0,
+        };
+        shift_up = shift_up
+            .max(self.param_v0(style, clearance))
+            .max(sup.depth + x_height / 4);
+        let Some(sub) = sub else {
+            nucleus.append_v0(sup.raise_v0(shift_up));
+            return nucleus;
+        };
+        shift_down = shift_down.max(self.param_v0(style, SUB2_V0));
+        let thickness = self.param_v0(style, DEFAULT_RULE_THICKNESS_V0);
+        let gap = 4 * thickness - ((shift_up - sup.depth) - (sub.height - shift_down));
+        if gap > 0 {
+            shift_down += gap;
+            let lift = x_height * 4 / 5 - (shift_up - sup.depth);
+            if lift > 0 {
+                shift_up += lift;
+                shift_down -= lift;
+            }
+        }
+        let width = sup.width.max(sub.width);
+        nucleus.append_v0(overlay_v0(
+            vec![(sup.raise_v0(shift_up), 0), (sub.raise_v0(-shift_down), 0)],
+            width,
+        ));
+        nucleus
+    }
+
+    /// TeX's `make_op` with limits: the scripts centred over and under the operator.
+    fn limits_v0(&self, nucleus: MathBoxV0, noad: &NoadV0, style: MathStyleV0) -> MathBoxV0 {
+        let spacing = BIG_OP_SPACING_V0.map(|millionths| self.param_v0(style, millionths));
+        let sup = noad
+            .sup
+            .as_ref()
+            .map(|sup| self.list_v0(sup, style.sup_v0()));
+        let sub = noad
+            .sub
+            .as_ref()
+            .map(|sub| self.list_v0(sub, style.sub_v0()));
+        let width = [Some(&nucleus), sup.as_ref(), sub.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(|part| part.width)
+            .max()
+            .unwrap_or(0);
+        let (nucleus_height, nucleus_depth) = (nucleus.height, nucleus.depth);
+        let mut parts = vec![nucleus];
+        let mut extra = (0, 0);
+        if let Some(sup) = sup {
+            let gap = spacing[0].max(spacing[2] - sup.depth);
+            let shift = nucleus_height + gap + sup.depth;
+            let sup = sup.raise_v0(shift);
+            extra.0 = sup.height + spacing[4];
+            parts.push(sup);
+        }
+        if let Some(sub) = sub {
+            let gap = spacing[1].max(spacing[3] - sub.height);
+            let shift = nucleus_depth + gap + sub.height;
+            let sub = sub.raise_v0(-shift);
+            extra.1 = sub.depth + spacing[4];
+            parts.push(sub);
+        }
+        let parts = parts
+            .into_iter()
+            .map(|part| {
+                let x = (width - part.width) / 2;
+                (part, x)
+            })
+            .collect();
+        let mut limits = overlay_v0(parts, width);
+        limits.height = limits.height.max(extra.0);
+        limits.depth = limits.depth.max(extra.1);
+        limits
+    }
+
+    /// TeX's `make_fraction`: numerator and denominator centred over and under a rule
+    /// on the axis, between null delimiters.
+    fn fraction_v0(
+        &self,
+        numerator: &[MathItemV0],
+        denominator: &[MathItemV0],
+        style: MathStyleV0,
+    ) -> MathBoxV0 {
+        let numerator = self.list_v0(numerator, style.numerator_v0());
+        let denominator = self.list_v0(denominator, style.denominator_v0());
+        let thickness = self.param_v0(style, DEFAULT_RULE_THICKNESS_V0);
+        let axis = self.param_v0(style, AXIS_HEIGHT_V0);
+        let (mut shift_up, mut shift_down, clearance) = if style.is_display_v0() {
+            (
+                self.param_v0(style, NUM1_V0),
+                self.param_v0(style, DENOM1_V0),
+                3 * thickness,
+            )
+        } else {
+            (
+                self.param_v0(style, NUM2_V0),
+                self.param_v0(style, DENOM2_V0),
+                thickness,
+            )
+        };
+        let half = thickness / 2;
+        shift_up += (clearance - ((shift_up - numerator.depth) - (axis + half))).max(0);
+        shift_down += (clearance - ((axis - half) - (denominator.height - shift_down))).max(0);
+        let width = numerator.width.max(denominator.width);
+        let rule = MathBoxV0 {
+            nodes: vec![MathNodeV0::Rule {
+                width,
+                thickness,
+                raise: axis - half,
+            }],
+            width,
+            height: axis - half + thickness,
+            depth: half - axis,
+        };
+        let numerator_x = (width - numerator.width) / 2;
+        let denominator_x = (width - denominator.width) / 2;
+        let null_delimiter = fraction_v0(i64::from(self.normal_em_sp), NULL_DELIMITER_SPACE_V0);
+        let mut fraction = MathBoxV0::default();
+        fraction.kern_v0(null_delimiter);
+        fraction.append_v0(overlay_v0(
+            vec![
+                (rule, 0),
+                (numerator.raise_v0(shift_up), numerator_x),
+                (denominator.raise_v0(-shift_down), denominator_x),
+            ],
+            width,
+        ));
+        fraction.kern_v0(null_delimiter);
+        fraction
+    }
+
+    /// TeX's `make_radical`: the radical sign, its top meeting a rule over the
+    /// cramped radicand.
+    fn radical_v0(&self, radicand: &[MathItemV0], style: MathStyleV0) -> MathBoxV0 {
+        let radicand = self.list_v0(radicand, style.cramped_v0());
+        let thickness = self.param_v0(style, DEFAULT_RULE_THICKNESS_V0);
+        let mut clearance = if style.is_display_v0() {
+            thickness + self.param_v0(style, X_HEIGHT_V0) / 4
+        } else {
+            thickness + thickness / 4
+        };
+        let sign = self.char_box_v0(
+            MathCharV0 {
+                family: MathFamilyV0::Symbols,
+                code: RADICAL_CODE_V0,
+            },
+            style,
+            10,
+        );
+        let excess = sign.depth - (radicand.height + radicand.depth + clearance);
+        if excess > 0 {
+            clearance += excess / 2;
+        }
+        let rule_raise = radicand.height + clearance;
+        let sign_height = sign.height;
+        let mut radical = sign.raise_v0(rule_raise - sign_height);
+        let width = radicand.width;
+        let rule = MathBoxV0 {
+            nodes: vec![MathNodeV0::Rule {
+                width,
+                thickness,
+                raise: rule_raise,
+            }],
+            width,
+            height: rule_raise + thickness,
+            depth: 0,
+        };
+        radical.append_v0(overlay_v0(vec![(rule, 0), (radicand, 0)], width));
+        radical
+    }
+}
+
+/// Sets a formula in `font`'s size, in display or text style.
+pub(crate) fn typeset_math_v0(
+    list: &[MathItemV0],
+    display: bool,
+    font: OkFontV0,
+    normal_em_sp: i32,
+) -> MathBoxV0 {
+    let text = font.normalfont_v0();
+    let fonts = MathFontsV0 {
+        sizes: [text, text.script_size_v0(), text.script_script_size_v0()],
+        normal_em_sp,
+    };
+    let style = MathStyleV0 {
+        level: if display { 0 } else { 1 },
+        cramped: false,
+    };
+    fonts.list_v0(list, style)
+}
+
+pub(crate) fn dimen_v0(sp: i64) -> Result<i32, InvalidInputReasonV0> {
+    if sp.unsigned_abs() > MAX_DVI_RULE_DIMENSION_SP_V0 as u64 {
+        return Err(InvalidInputReasonV0::DimenInvalid);
+    }
+    Ok(sp as i32)
+}
+
+/// Appends a typeset formula to the body: glyphs in their fonts, raised runs of
+/// them, kerns, and rules put at their left edge.
+pub(crate) fn emit_math_v0(
+    math: &MathBoxV0,
+    body: &mut OkBodyV0,
+    font_table: &mut FontTableV0,
+) -> Result<(), InvalidInputReasonV0> {
+    for node in &math.nodes {
+        match node {
+            MathNodeV0::Glyph { font, code, raise } => {
+                let font = font_table.number_dvi_v0(font.clone())?;
+                body.switch_font_v0(font);
+                let offset = body.text.len();
+                let raise = dimen_v0(*raise)?;
+                body.text.push(*code);
+                if raise == 0 {
+                    continue;
+                }
+                let rule_anchored = body.rules.last().is_some_and(|rule| rule.offset == offset);
+                if let Some(last) = body
+                    .raises
+                    .last_mut()
+                    .filter(|last| last.end == offset && last.raise == raise && !rule_anchored)
+                {
+                    last.end += 1;
+                    continue;
+                }
+                if body.raises.len() >= MAX_DVI_TEXT_RAISES_V0 {
+                    return Err(InvalidInputReasonV0::MathCountExceeded);
+                }
+                body.raises.push(DviTextRaiseV0 {
+                    start: offset,
+                    end: offset + 1,
+                    raise,
+                });
+            }
+            MathNodeV0::Kern(width) => push_kern_v0(body, dimen_v0(*width)?)?,
+            MathNodeV0::Rule {
+                width,
+                thickness,
+                raise,
+            } => {
+                if body.rules.len() >= MAX_DVI_TEXT_RULES_V0 {
+                    return Err(InvalidInputReasonV0::RuleCountExceeded);
+                }
+                let (width, height) = (dimen_v0(*width)?, dimen_v0(*thickness)?);
+                if width < 0 || height < 0 {
+                    return Err(InvalidInputReasonV0::DimenInvalid);
+                }
+                body.rules.push(DviTextRuleV0 {
+                    offset: body.text.len(),
+                    special_index: body.specials.len(),
+                    width,
+                    height,
+                    raise: dimen_v0(*raise)?,
+                    advance: false,
+                });
+                push_kern_v0(body, width)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A kern at the end of the text, merged into one made there after the same rules.
+pub(crate) fn push_kern_v0(body: &mut OkBodyV0, width: i32) -> Result<(), InvalidInputReasonV0> {
+    let offset = body.text.len();
+    let special_index = body.specials.len();
+    let rule_index = body.rules.len();
+    if let Some(last) = body.kerns.last_mut().filter(|kern| {
+        kern.offset == offset
+            && kern.special_index == special_index
+            && kern.rule_index == rule_index
+    }) {
+        last.width = dimen_v0(i64::from(last.width) + i64::from(width))?;
+        if last.width == 0 {
+            body.kerns.pop();
+        }
+        return Ok(());
+    }
+    if width == 0 {
+        return Ok(());
+    }
+    if body.kerns.len() >= MAX_DVI_TEXT_KERNS_V0 {
+        return Err(InvalidInputReasonV0::MathCountExceeded);
+    }
+    body.kerns.push(DviTextKernV0 {
+        offset,
+        special_index,
+        rule_index,
+        width,
+    });
+    Ok(())
+}
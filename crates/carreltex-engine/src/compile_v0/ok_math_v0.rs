@@ -0,0 +1,616 @@
+use super::ok_args_v0::{consume_group_literal, is_control_seq_v0};
+use super::ok_v0::MAX_OK_TEXT_BYTES_V0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+/// Groups, scripts, fractions and radicals one formula may nest.
+const MAX_MATH_DEPTH_V0: usize = 32;
+
+/// TeX's atom types, which choose the space between neighbouring atoms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AtomKindV0 {
+    Ord,
+    Op,
+    Bin,
+    Rel,
+    Open,
+    Close,
+    Punct,
+    Inner,
+}
+
+/// Where a math character comes from: the text fonts (family 0, and the `\mathbf`
+/// and `\mathit` alphabets), math italic (family 1), the symbol font (family 2) and
+/// the extension font (family 3). Families 2 and 3 use the Symbol encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MathFamilyV0 {
+    Roman,
+    Bold,
+    Italic,
+    MathItalic,
+    Symbols,
+    Extension,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MathCharV0 {
+    pub(crate) family: MathFamilyV0,
+    pub(crate) code: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum NucleusV0 {
+    Empty,
+    Char(MathCharV0),
+    List(Vec<MathItemV0>),
+    Fraction(Vec<MathItemV0>, Vec<MathItemV0>),
+    Radical(Vec<MathItemV0>),
+}
+
+/// An atom with its scripts; `limits` is `\limits` (`Some(true)`), `\nolimits`
+/// (`Some(false)`) or TeX's normal choice of limits in display style only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NoadV0 {
+    pub(crate) kind: AtomKindV0,
+    pub(crate) nucleus: NucleusV0,
+    pub(crate) sup: Option<Vec<MathItemV0>>,
+    pub(crate) sub: Option<Vec<MathItemV0>>,
+    pub(crate) limits: Option<bool>,
+}
+
+/// A math list entry: an atom, or explicit space in math units (18mu to the quad).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum MathItemV0 {
+    Noad(NoadV0),
+    Space(i32),
+}
+
+/// A formula between its math shifts; display math sits on a line of its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct MathFormulaV0 {
+    pub(crate) list: Vec<MathItemV0>,
+    pub(crate) display: bool,
+}
+
+/// Symbols by control sequence: Greek letters, ordinary symbols, binary operators,
+/// relations and delimiters, with their code in the Symbol encoding.
+const MATH_SYMBOLS_V0: [(&[u8], AtomKindV0, u8); 96] = [
+    (b"alpha", AtomKindV0::Ord, b'a'),
+    (b"beta", AtomKindV0::Ord, b'b'),
+    (b"gamma", AtomKindV0::Ord, b'g'),
+    (b"delta", AtomKindV0::Ord, b'd'),
+    (b"epsilon", AtomKindV0::Ord, b'e'),
+    (b"varepsilon", AtomKindV0::Ord, b'e'),
+    (b"zeta", AtomKindV0::Ord, b'z'),
+    (b"eta", AtomKindV0::Ord, b'h'),
+    (b"theta", AtomKindV0::Ord, b'q'),
+    (b"vartheta", AtomKindV0::Ord, b'J'),
+    (b"iota", AtomKindV0::Ord, b'i'),
+    (b"kappa", AtomKindV0::Ord, b'k'),
+    (b"lambda", AtomKindV0::Ord, b'l'),
+    (b"mu", AtomKindV0::Ord, b'm'),
+    (b"nu", AtomKindV0::Ord, b'n'),
+    (b"xi", AtomKindV0::Ord, b'x'),
+    (b"pi", AtomKindV0::Ord, b'p'),
+    (b"varpi", AtomKindV0::Ord, b'v'),
+    (b"rho", AtomKindV0::Ord, b'r'),
+    (b"sigma", AtomKindV0::Ord, b's'),
+    (b"varsigma", AtomKindV0::Ord, b'V'),
+    (b"tau", AtomKindV0::Ord, b't'),
+    (b"upsilon", AtomKindV0::Ord, b'u'),
+    (b"phi", AtomKindV0::Ord, b'j'),
+    (b"varphi", AtomKindV0::Ord, b'f'),
+    (b"chi", AtomKindV0::Ord, b'c'),
+    (b"psi", AtomKindV0::Ord, b'y'),
+    (b"omega", AtomKindV0::Ord, b'w'),
+    (b"Gamma", AtomKindV0::Ord, b'G'),
+    (b"Delta", AtomKindV0::Ord, b'D'),
+    (b"Theta", AtomKindV0::Ord, b'Q'),
+    (b"Lambda", AtomKindV0::Ord, b'L'),
+    (b"Xi", AtomKindV0::Ord, b'X'),
+    (b"Pi", AtomKindV0::Ord, b'P'),
+    (b"Sigma", AtomKindV0::Ord, b'S'),
+    (b"Upsilon", AtomKindV0::Ord, 0xa1),
+    (b"Phi", AtomKindV0::Ord, b'F'),
+    (b"Psi", AtomKindV0::Ord, b'Y'),
+    (b"Omega", AtomKindV0::Ord, b'W'),
+    (b"infty", AtomKindV0::Ord, 0xa5),
+    (b"partial", AtomKindV0::Ord, 0xb6),
+    (b"nabla", AtomKindV0::Ord, 0xd1),
+    (b"forall", AtomKindV0::Ord, b'"'),
+    (b"exists", AtomKindV0::Ord, b'$'),
+    (b"emptyset", AtomKindV0::Ord, 0xc6),
+    (b"neg", AtomKindV0::Ord, 0xd8),
+    (b"lnot", AtomKindV0::Ord, 0xd8),
+    (b"prime", AtomKindV0::Ord, 0xa2),
+    (b"aleph", AtomKindV0::Ord, 0xc0),
+    (b"Re", AtomKindV0::Ord, 0xc2),
+    (b"Im", AtomKindV0::Ord, 0xc1),
+    (b"wp", AtomKindV0::Ord, 0xc3),
+    (b"angle", AtomKindV0::Ord, 0xd0),
+    (b"bot", AtomKindV0::Ord, b'^'),
+    (b"vert", AtomKindV0::Ord, b'|'),
+    (b"clubsuit", AtomKindV0::Ord, 0xa7),
+    (b"diamondsuit", AtomKindV0::Ord, 0xa8),
+    (b"heartsuit", AtomKindV0::Ord, 0xa9),
+    (b"spadesuit", AtomKindV0::Ord, 0xaa),
+    (b"pm", AtomKindV0::Bin, 0xb1),
+    (b"times", AtomKindV0::Bin, 0xb4),
+    (b"div", AtomKindV0::Bin, 0xb8),
+    (b"cdot", AtomKindV0::Bin, 0xd7),
+    (b"ast", AtomKindV0::Bin, b'*'),
+    (b"bullet", AtomKindV0::Bin, 0xb7),
+    (b"circ", AtomKindV0::Bin, 0xb0),
+    (b"cap", AtomKindV0::Bin, 0xc7),
+    (b"cup", AtomKindV0::Bin, 0xc8),
+    (b"wedge", AtomKindV0::Bin, 0xd9),
+    (b"land", AtomKindV0::Bin, 0xd9),
+    (b"vee", AtomKindV0::Bin, 0xda),
+    (b"lor", AtomKindV0::Bin, 0xda),
+    (b"oplus", AtomKindV0::Bin, 0xc5),
+    (b"otimes", AtomKindV0::Bin, 0xc4),
+    (b"leq", AtomKindV0::Rel, 0xa3),
+    (b"le", AtomKindV0::Rel, 0xa3),
+    (b"geq", AtomKindV0::Rel, 0xb3),
+    (b"ge", AtomKindV0::Rel, 0xb3),
+    (b"neq", AtomKindV0::Rel, 0xb9),
+    (b"ne", AtomKindV0::Rel, 0xb9),
+    (b"equiv", AtomKindV0::Rel, 0xba),
+    (b"approx", AtomKindV0::Rel, 0xbb),
+    (b"sim", AtomKindV0::Rel, b'~'),
+    (b"cong", AtomKindV0::Rel, b'@'),
+    (b"propto", AtomKindV0::Rel, 0xb5),
+    (b"in", AtomKindV0::Rel, 0xce),
+    (b"notin", AtomKindV0::Rel, 0xcf),
+    (b"ni", AtomKindV0::Rel, b'\''),
+    (b"subset", AtomKindV0::Rel, 0xcc),
+    (b"subseteq", AtomKindV0::Rel, 0xcd),
+    (b"supset", AtomKindV0::Rel, 0xc9),
+    (b"supseteq", AtomKindV0::Rel, 0xca),
+    (b"perp", AtomKindV0::Rel, b'^'),
+    (b"mid", AtomKindV0::Rel, b'|'),
+    (b"langle", AtomKindV0::Open, 0xe1),
+    (b"rangle", AtomKindV0::Close, 0xf1),
+];
+
+/// Arrows, all relations in the Symbol encoding.
+const MATH_ARROWS_V0: [(&[u8], u8); 10] = [
+    (b"to", 0xae),
+    (b"rightarrow", 0xae),
+    (b"leftarrow", 0xac),
+    (b"gets", 0xac),
+    (b"leftrightarrow", 0xab),
+    (b"uparrow", 0xad),
+    (b"downarrow", 0xaf),
+    (b"Rightarrow", 0xde),
+    (b"Leftarrow", 0xdc),
+    (b"Leftrightarrow", 0xdb),
+];
+
+/// Large operators from the extension font; `\int` takes its limits beside it.
+const MATH_LARGE_OPS_V0: [(&[u8], u8, Option<bool>); 5] = [
+    (b"sum", 0xe5, None),
+    (b"prod", 0xd5, None),
+    (b"int", 0xf2, Some(false)),
+    (b"bigcap", 0xc7, None),
+    (b"bigcup", 0xc8, None),
+];
+
+/// Log-like operators set in roman; those marked take limits in display style.
+const MATH_FUNCTIONS_V0: [(&[u8], bool); 26] = [
+    (b"arccos", false),
+    (b"arcsin", false),
+    (b"arctan", false),
+    (b"arg", false),
+    (b"cos", false),
+    (b"cosh", false),
+    (b"cot", false),
+    (b"coth", false),
+    (b"csc", false),
+    (b"deg", false),
+    (b"det", true),
+    (b"dim", false),
+    (b"exp", false),
+    (b"gcd", true),
+    (b"inf", true),
+    (b"ker", false),
+    (b"lim", true),
+    (b"ln", false),
+    (b"log", false),
+    (b"max", true),
+    (b"min", true),
+    (b"sec", false),
+    (b"sin", false),
+    (b"sinh", false),
+    (b"sup", true),
+    (b"tan", false),
+];
+
+fn noad_v0(kind: AtomKindV0, nucleus: NucleusV0) -> MathItemV0 {
+    MathItemV0::Noad(bare_noad_v0(kind, nucleus))
+}
+
+fn bare_noad_v0(kind: AtomKindV0, nucleus: NucleusV0) -> NoadV0 {
+    NoadV0 {
+        kind,
+        nucleus,
+        sup: None,
+        sub: None,
+        limits: None,
+    }
+}
+
+fn op_noad_v0(nucleus: NucleusV0, limits: Option<bool>) -> MathItemV0 {
+    MathItemV0::Noad(NoadV0 {
+        kind: AtomKindV0::Op,
+        nucleus,
+        sup: None,
+        sub: None,
+        limits,
+    })
+}
+
+fn char_noad_v0(kind: AtomKindV0, family: MathFamilyV0, code: u8) -> MathItemV0 {
+    noad_v0(kind, NucleusV0::Char(MathCharV0 { family, code }))
+}
+
+/// Atom type and family of a character typed in math, as plain TeX's `\mathcode`s
+/// give them; letters and digits take the `alphabet` of `\mathrm` and friends.
+fn math_char_v0(byte: u8, alphabet: Option<MathFamilyV0>) -> Option<MathItemV0> {
+    let (kind, family) = match byte {
+        b'a'..=b'z' | b'A'..=b'Z' => (
+            AtomKindV0::Ord,
+            alphabet.unwrap_or(MathFamilyV0::MathItalic),
+        ),
+        b'0'..=b'9' => (AtomKindV0::Ord, alphabet.unwrap_or(MathFamilyV0::Roman)),
+        b'+' => (AtomKindV0::Bin, MathFamilyV0::Roman),
+        b'-' | b'*' => (AtomKindV0::Bin, MathFamilyV0::Symbols),
+        b'=' | b':' => (AtomKindV0::Rel, MathFamilyV0::Roman),
+        b'<' | b'>' => (AtomKindV0::Rel, MathFamilyV0::Symbols),
+        b'(' | b'[' => (AtomKindV0::Open, MathFamilyV0::Roman),
+        b'{' => (AtomKindV0::Open, MathFamilyV0::Symbols),
+        b')' | b']' | b'!' | b'?' => (AtomKindV0::Close, MathFamilyV0::Roman),
+        b'}' => (AtomKindV0::Close, MathFamilyV0::Symbols),
+        b',' | b';' => (AtomKindV0::Punct, MathFamilyV0::Roman),
+        b'.' | b'/' => (AtomKindV0::Ord, MathFamilyV0::Roman),
+        b'|' => (AtomKindV0::Ord, MathFamilyV0::Symbols),
+        b'\'' => return Some(char_noad_v0(AtomKindV0::Ord, MathFamilyV0::Symbols, 0xa2)),
+        _ => return None,
+    };
+    Some(char_noad_v0(kind, family, byte))
+}
+
+/// What `item_v0` found at the current token.
+enum ParsedItemV0 {
+    Item(MathItemV0),
+    /// A token that does not start an item, such as the closing math shift.
+    Stop,
+    /// Outside the subset.
+    Invalid,
+}
+
+struct MathParserV0<'a> {
+    tokens: &'a [TokenV0],
+    index: usize,
+    glyphs: usize,
+}
+
+impl MathParserV0<'_> {
+    /// Counts `count` more glyphs against the text cap.
+    fn glyphs_fit_v0(&mut self, count: usize) -> bool {
+        self.glyphs += count;
+        self.glyphs <= MAX_OK_TEXT_BYTES_V0
+    }
+
+    /// Items up to the first token that does not start one; `Ok(None)` outside the
+    /// subset. `^` and `_` attach scripts to the last atom (an empty one at the
+    /// start), and `\limits` / `\nolimits` set the limits of a last large operator.
+    fn list_v0(
+        &mut self,
+        depth: usize,
+        alphabet: Option<MathFamilyV0>,
+    ) -> Result<Option<Vec<MathItemV0>>, InvalidInputReasonV0> {
+        if depth > MAX_MATH_DEPTH_V0 {
+            return Ok(None);
+        }
+        let mut list = Vec::<MathItemV0>::new();
+        loop {
+            match self.tokens.get(self.index) {
+                Some(TokenV0::Space) => self.index += 1,
+                Some(TokenV0::Char(byte @ (b'^' | b'_'))) => {
+                    let superscript = *byte == b'^';
+                    self.index += 1;
+                    let Some(script) = self.argument_v0(depth + 1, alphabet)? else {
+                        return Ok(None);
+                    };
+                    let mut noad = match list.pop() {
+                        Some(MathItemV0::Noad(noad)) => noad,
+                        item => {
+                            list.extend(item);
+                            bare_noad_v0(AtomKindV0::Ord, NucleusV0::Empty)
+                        }
+                    };
+                    let slot = if superscript {
+                        &mut noad.sup
+                    } else {
+                        &mut noad.sub
+                    };
+                    if slot.is_some() {
+                        return Err(InvalidInputReasonV0::MathDoubleScript);
+                    }
+                    *slot = Some(script);
+                    list.push(MathItemV0::Noad(noad));
+                }
+                Some(TokenV0::ControlSeq(name))
+                    if matches!(name.as_slice(), b"limits" | b"nolimits") =>
+                {
+                    let Some(MathItemV0::Noad(noad)) = list.last_mut() else {
+                        return Ok(None);
+                    };
+                    if noad.kind != AtomKindV0::Op {
+                        return Ok(None);
+                    }
+                    noad.limits = Some(name.as_slice() == b"limits");
+                    self.index += 1;
+                }
+                _ => match self.item_v0(depth, alphabet)? {
+                    ParsedItemV0::Item(item) => list.push(item),
+                    ParsedItemV0::Stop => return Ok(Some(list)),
+                    ParsedItemV0::Invalid => return Ok(None),
+                },
+            }
+        }
+    }
+
+    /// A braced list, or the single item a script or `\frac` argument may be.
+    fn argument_v0(
+        &mut self,
+        depth: usize,
+        alphabet: Option<MathFamilyV0>,
+    ) -> Result<Option<Vec<MathItemV0>>, InvalidInputReasonV0> {
+        while matches!(self.tokens.get(self.index), Some(TokenV0::Space)) {
+            self.index += 1;
+        }
+        if !matches!(self.tokens.get(self.index), Some(TokenV0::BeginGroup)) {
+            return Ok(match self.item_v0(depth, alphabet)? {
+                ParsedItemV0::Item(item) => Some(vec![item]),
+                ParsedItemV0::Stop | ParsedItemV0::Invalid => None,
+            });
+        }
+        self.index += 1;
+        let Some(list) = self.list_v0(depth, alphabet)? else {
+            return Ok(None);
+        };
+        if !matches!(self.tokens.get(self.index), Some(TokenV0::EndGroup)) {
+            return Ok(None);
+        }
+        self.index += 1;
+        Ok(Some(list))
+    }
+
+    fn item_v0(
+        &mut self,
+        depth: usize,
+        alphabet: Option<MathFamilyV0>,
+    ) -> Result<ParsedItemV0, InvalidInputReasonV0> {
+        let item = match self.tokens.get(self.index) {
+            // `\,` and `\;`, which the tokenizer turns into a space character.
+            Some(TokenV0::Char(b' ')) => MathItemV0::Space(3),
+            Some(TokenV0::Char(byte)) => match math_char_v0(*byte, alphabet) {
+                Some(item) => item,
+                None => return Ok(ParsedItemV0::Stop),
+            },
+            Some(TokenV0::BeginGroup) => {
+                return Ok(match self.argument_v0(depth + 1, alphabet)? {
+                    Some(list) => {
+                        ParsedItemV0::Item(noad_v0(AtomKindV0::Ord, NucleusV0::List(list)))
+                    }
+                    None => ParsedItemV0::Invalid,
+                });
+            }
+            Some(TokenV0::ControlSeq(name)) => return self.command_v0(name, depth, alphabet),
+            _ => return Ok(ParsedItemV0::Stop),
+        };
+        self.index += 1;
+        Ok(if self.glyphs_fit_v0(1) {
+            ParsedItemV0::Item(item)
+        } else {
+            ParsedItemV0::Invalid
+        })
+    }
+
+    /// Symbols, operators, `\frac{num}{den}`, `\sqrt{radicand}`, `\mathrm`,
+    /// `\mathbf` and `\mathit`, the dots, and `\:`, `\>`, `\quad` and `\qquad` space.
+    fn command_v0(
+        &mut self,
+        name: &[u8],
+        depth: usize,
+        alphabet: Option<MathFamilyV0>,
+    ) -> Result<ParsedItemV0, InvalidInputReasonV0> {
+        self.index += 1;
+        let symbol = MATH_SYMBOLS_V0
+            .iter()
+            .find(|(symbol, _, _)| *symbol == name)
+            .map(|(_, kind, code)| char_noad_v0(*kind, MathFamilyV0::Symbols, *code))
+            .or_else(|| {
+                MATH_ARROWS_V0
+                    .iter()
+                    .find(|(arrow, _)| *arrow == name)
+                    .map(|(_, code)| char_noad_v0(AtomKindV0::Rel, MathFamilyV0::Symbols, *code))
+            });
+        let item = if let Some(item) = symbol {
+            self.glyphs += 1;
+            item
+        } else if let Some((_, code, limits)) = MATH_LARGE_OPS_V0.iter().find(|op| op.0 == name) {
+            self.glyphs += 1;
+            let nucleus = NucleusV0::Char(MathCharV0 {
+                family: MathFamilyV0::Extension,
+                code: *code,
+            });
+            op_noad_v0(nucleus, *limits)
+        } else if let Some((_, limits)) = MATH_FUNCTIONS_V0
+            .iter()
+            .find(|(function, _)| *function == name)
+        {
+            self.glyphs += name.len();
+            let letters = name
+                .iter()
+                .map(|byte| char_noad_v0(AtomKindV0::Ord, MathFamilyV0::Roman, *byte))
+                .collect();
+            op_noad_v0(NucleusV0::List(letters), (!limits).then_some(false))
+        } else {
+            match name {
+                b":" | b">" => MathItemV0::Space(4),
+                b"quad" => MathItemV0::Space(18),
+                b"qquad" => MathItemV0::Space(36),
+                b"ldots" | b"dots" | b"cdots" => {
+                    self.glyphs += 3;
+                    let (family, code) = if name == b"cdots" {
+                        (MathFamilyV0::Symbols, 0xd7)
+                    } else {
+                        (MathFamilyV0::Roman, b'.')
+                    };
+                    let dots = vec![char_noad_v0(AtomKindV0::Punct, family, code); 3];
+                    noad_v0(AtomKindV0::Inner, NucleusV0::List(dots))
+                }
+                b"frac" => {
+                    let Some(numerator) = self.argument_v0(depth + 1, None)? else {
+                        return Ok(ParsedItemV0::Invalid);
+                    };
+                    let Some(denominator) = self.argument_v0(depth + 1, None)? else {
+                        return Ok(ParsedItemV0::Invalid);
+                    };
+                    noad_v0(
+                        AtomKindV0::Inner,
+                        NucleusV0::Fraction(numerator, denominator),
+                    )
+                }
+                b"sqrt" => {
+                    self.glyphs += 1;
+                    if matches!(self.tokens.get(self.index), Some(TokenV0::Char(b'['))) {
+                        return Ok(ParsedItemV0::Invalid);
+                    }
+                    let Some(radicand) = self.argument_v0(depth + 1, alphabet)? else {
+                        return Ok(ParsedItemV0::Invalid);
+                    };
+                    noad_v0(AtomKindV0::Ord, NucleusV0::Radical(radicand))
+                }
+                b"mathrm" | b"mathbf" | b"mathit" => {
+                    let family = match name {
+                        b"mathrm" => MathFamilyV0::Roman,
+                        b"mathbf" => MathFamilyV0::Bold,
+                        _ => MathFamilyV0::Italic,
+                    };
+                    let Some(list) = self.argument_v0(depth + 1, Some(family))? else {
+                        return Ok(ParsedItemV0::Invalid);
+                    };
+                    noad_v0(AtomKindV0::Ord, NucleusV0::List(list))
+                }
+                _ => {
+                    self.index -= 1;
+                    return Ok(ParsedItemV0::Stop);
+                }
+            }
+        };
+        Ok(if self.glyphs <= MAX_OK_TEXT_BYTES_V0 {
+            ParsedItemV0::Item(item)
+        } else {
+            ParsedItemV0::Invalid
+        })
+    }
+}
+
+/// How the math opened at an index closes.
+enum MathCloserV0 {
+    Shift,
+    DoubleShift,
+    ControlSeq(&'static [u8]),
+    Environment(&'static [u8]),
+}
+
+/// The math opened at `index`, if any: `$`, `$$`, `\(`, `\[`, or a `math` or
+/// `displaymath` environment, with its closer and the index after the opener.
+fn math_opener_v0(tokens: &[TokenV0], index: usize) -> Option<(MathCloserV0, usize)> {
+    match tokens.get(index)? {
+        TokenV0::MathShift if tokens.get(index + 1) == Some(&TokenV0::MathShift) => {
+            Some((MathCloserV0::DoubleShift, index + 2))
+        }
+        TokenV0::MathShift => Some((MathCloserV0::Shift, index + 1)),
+        TokenV0::ControlSeq(name) if name.as_slice() == b"(" => {
+            Some((MathCloserV0::ControlSeq(b")"), index + 1))
+        }
+        TokenV0::ControlSeq(name) if name.as_slice() == b"[" => {
+            Some((MathCloserV0::ControlSeq(b"]"), index + 1))
+        }
+        TokenV0::ControlSeq(name) if name.as_slice() == b"begin" => {
+            [b"math".as_slice(), b"displaymath"]
+                .into_iter()
+                .find_map(|env| {
+                    consume_group_literal(tokens, index + 1, env)
+                        .map(|next_index| (MathCloserV0::Environment(env), next_index))
+                })
+        }
+        _ => None,
+    }
+}
+
+pub(crate) fn is_math_opener_v0(tokens: &[TokenV0], index: usize) -> bool {
+    math_opener_v0(tokens, index).is_some()
+}
+
+/// The formula opened at `index` through its closer; `Ok(None)` when it falls
+/// outside the subset or does not close.
+pub(crate) fn consume_math_v0(
+    tokens: &[TokenV0],
+    index: usize,
+) -> Result<Option<(MathFormulaV0, usize)>, InvalidInputReasonV0> {
+    let Some((closer, index)) = math_opener_v0(tokens, index) else {
+        return Ok(None);
+    };
+    let mut parser = MathParserV0 {
+        tokens,
+        index,
+        glyphs: 0,
+    };
+    let Some(list) = parser.list_v0(0, None)? else {
+        return Ok(None);
+    };
+    let index = parser.index;
+    let (display, next_index) = match closer {
+        MathCloserV0::Shift => (
+            false,
+            matches!(tokens.get(index), Some(TokenV0::MathShift)).then_some(index + 1),
+        ),
+        MathCloserV0::DoubleShift => (
+            true,
+            (tokens.get(index..index + 2) == Some(&[TokenV0::MathShift, TokenV0::MathShift]))
+                .then_some(index + 2),
+        ),
+        MathCloserV0::ControlSeq(name) => (
+            name == b"]",
+            is_control_seq_v0(tokens.get(index), name).then_some(index + 1),
+        ),
+        MathCloserV0::Environment(env) => (
+            env == b"displaymath",
+            is_control_seq_v0(tokens.get(index), b"end")
+                .then(|| consume_group_literal(tokens, index + 1, env))
+                .flatten(),
+        ),
+    };
+    Ok(next_index.map(|next_index| (MathFormulaV0 { list, display }, next_index)))
+}
+
+/// Math items from `index` up to the first token that does not start one, such as
+/// an alignment's `&` or `\\`; `Ok(None)` outside the subset.
+pub(crate) fn consume_math_list_v0(
+    tokens: &[TokenV0],
+    index: usize,
+) -> Result<Option<(Vec<MathItemV0>, usize)>, InvalidInputReasonV0> {
+    let mut parser = MathParserV0 {
+        tokens,
+        index,
+        glyphs: 0,
+    };
+    let list = parser.list_v0(0, None)?;
+    Ok(list.map(|list| (list, parser.index)))
+}
@@ -0,0 +1,325 @@
+use super::ok_dimen_v0::{OkLayoutV0, MAX_DIMEN_SP_V0};
+use super::ok_list_v0::roman_v0;
+use super::ok_section_v0::SectionLevelV0;
+use super::ok_toc_v0::OkTocEntryV0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use carreltex_xdv::{
+    glyph_width_sp_v0, DviTextFloatV0, DviTextFurnitureV0, DviTextPagesV0, DviTextRunV0,
+    MAX_DVI_TEXT_RUN_BYTES_V0, ONE_INCH_SP_V0,
+};
+use std::ops::RangeInclusive;
+
+/// Article's 10pt `\topskip`, `\headheight`, `\headsep` and `\footskip`.
+pub(crate) const TOPSKIP_SP_V0: i32 = 655_360;
+pub(crate) const HEADHEIGHT_SP_V0: i32 = 786_432;
+pub(crate) const HEADSEP_SP_V0: i32 = 1_638_400;
+const FOOTSKIP_SP_V0: i32 = 1_966_080;
+
+/// `\ps@empty`, `\ps@plain` (the default) and the one-sided `\ps@headings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PageStyleV0 {
+    Empty,
+    Plain,
+    Headings,
+}
+
+impl PageStyleV0 {
+    fn from_name_v0(name: &[u8]) -> Option<Self> {
+        match name {
+            b"empty" => Some(PageStyleV0::Empty),
+            b"plain" => Some(PageStyleV0::Plain),
+            b"headings" => Some(PageStyleV0::Headings),
+            _ => None,
+        }
+    }
+}
+
+/// `\pagenumbering` styles of `\thepage`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PageNumberingV0 {
+    Arabic,
+    Roman,
+    UpperRoman,
+    Alph,
+    UpperAlph,
+}
+
+impl PageNumberingV0 {
+    pub(crate) fn from_name_v0(name: &[u8]) -> Option<Self> {
+        match name {
+            b"arabic" => Some(PageNumberingV0::Arabic),
+            b"roman" => Some(PageNumberingV0::Roman),
+            b"Roman" => Some(PageNumberingV0::UpperRoman),
+            b"alph" => Some(PageNumberingV0::Alph),
+            b"Alph" => Some(PageNumberingV0::UpperAlph),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn format_v0(self, value: u32) -> Result<Vec<u8>, InvalidInputReasonV0> {
+        let letter = |base: u8| {
+            u8::try_from(value)
+                .ok()
+                .filter(|value| (1..=26).contains(value))
+                .map(|value| vec![base + value - 1])
+                .ok_or(InvalidInputReasonV0::CounterTooLarge)
+        };
+        match self {
+            PageNumberingV0::Arabic => Ok(value.to_string().into_bytes()),
+            PageNumberingV0::Roman => Ok(roman_v0(value)),
+            PageNumberingV0::UpperRoman => Ok(roman_v0(value).to_ascii_uppercase()),
+            PageNumberingV0::Alph => letter(b'a'),
+            PageNumberingV0::UpperAlph => letter(b'A'),
+        }
+    }
+}
+
+/// `\pagestyle{...}`, `\thispagestyle{...}` and `\pagenumbering{...}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PageCommandV0 {
+    Style(PageStyleV0),
+    ThisPageStyle(PageStyleV0),
+    Numbering(PageNumberingV0),
+}
+
+impl PageCommandV0 {
+    pub(crate) fn is_page_command_v0(name: &[u8]) -> bool {
+        matches!(name, b"pagestyle" | b"thispagestyle" | b"pagenumbering")
+    }
+
+    /// The command `name` with argument `argument`; `None` outside the subset.
+    pub(crate) fn parse_v0(name: &[u8], argument: &[u8]) -> Option<Self> {
+        match name {
+            b"pagestyle" => PageStyleV0::from_name_v0(argument).map(PageCommandV0::Style),
+            b"thispagestyle" => {
+                PageStyleV0::from_name_v0(argument).map(PageCommandV0::ThisPageStyle)
+            }
+            b"pagenumbering" => {
+                PageNumberingV0::from_name_v0(argument).map(PageCommandV0::Numbering)
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn style_v0(self) -> Option<PageStyleV0> {
+        match self {
+            PageCommandV0::Style(style) | PageCommandV0::ThisPageStyle(style) => Some(style),
+            PageCommandV0::Numbering(_) => None,
+        }
+    }
+}
+
+/// A page command met at text `offset`; it applies to the page holding `offset`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OkPageCommandV0 {
+    pub(crate) command: PageCommandV0,
+    pub(crate) offset: usize,
+}
+
+/// 1-based page holding text `offset`, given the first offset of every page.
+fn page_of_offset_v0(page_starts: &[usize], offset: usize) -> usize {
+    page_starts.partition_point(|start| *start <= offset).max(1)
+}
+
+/// The output pages of a pass: where each starts, its `page` counter, `\thepage`
+/// and page style, and the 0-based page each float body's text landed on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct OkPagesV0 {
+    starts: Vec<usize>,
+    numbers: Vec<u32>,
+    labels: Vec<Vec<u8>>,
+    styles: Vec<PageStyleV0>,
+    floats: Vec<(RangeInclusive<usize>, usize)>,
+}
+
+impl OkPagesV0 {
+    /// Pages starting at `starts`. The counter steps from 1 and restarts at 1 on the
+    /// page of a `\pagenumbering`; a page takes the last `\pagestyle` met up to its
+    /// end (`plain` before any), unless a `\thispagestyle` on it overrides it.
+    pub(crate) fn new_v0(
+        starts: Vec<usize>,
+        commands: &[OkPageCommandV0],
+    ) -> Result<Self, InvalidInputReasonV0> {
+        let mut pages = OkPagesV0 {
+            starts,
+            ..OkPagesV0::default()
+        };
+        let mut value = 0u32;
+        let mut numbering = PageNumberingV0::Arabic;
+        let mut style = PageStyleV0::Plain;
+        let mut next = 0usize;
+        for page in 0..pages.starts.len() {
+            let end = pages.starts.get(page + 1).copied().unwrap_or(usize::MAX);
+            value = value.saturating_add(1);
+            let mut this_page_style = None;
+            while let Some(command) = commands.get(next).filter(|command| command.offset < end) {
+                match command.command {
+                    PageCommandV0::Style(next_style) => style = next_style,
+                    PageCommandV0::ThisPageStyle(next_style) => this_page_style = Some(next_style),
+                    PageCommandV0::Numbering(next_numbering) => {
+                        numbering = next_numbering;
+                        value = 1;
+                    }
+                }
+                next += 1;
+            }
+            pages.numbers.push(value);
+            pages.labels.push(numbering.format_v0(value)?);
+            pages.styles.push(this_page_style.unwrap_or(style));
+        }
+        Ok(pages)
+    }
+
+    /// Float bodies `floats` were set on `float_pages`, away from where the text
+    /// around them goes.
+    pub(crate) fn with_floats_v0(
+        mut self,
+        floats: &[DviTextFloatV0],
+        float_pages: &[usize],
+    ) -> Self {
+        self.floats = floats
+            .iter()
+            .zip(float_pages)
+            .map(|(float, page)| (float.start..=float.end, *page))
+            .collect();
+        self
+    }
+
+    /// `\thepage` on the page holding `offset`; `1` before the first pass.
+    pub(crate) fn label_v0(&self, offset: usize) -> Vec<u8> {
+        let page = self
+            .floats
+            .iter()
+            .find(|(body, _)| body.contains(&offset))
+            .map_or_else(
+                || page_of_offset_v0(&self.starts, offset),
+                |(_, page)| page + 1,
+            );
+        self.labels
+            .get(page - 1)
+            .cloned()
+            .unwrap_or_else(|| b"1".to_vec())
+    }
+
+    /// Number, head and foot of every page. `plain` centres `\thepage` in the foot;
+    /// `headings` sets it flush right in the head after the slanted, uppercased
+    /// `\rightmark` in `mark_font`: the first numbered heading of `mark_level` on the
+    /// page, else the last before it, chapters as `CHAPTER 1.`.
+    pub(crate) fn furniture_v0(
+        &self,
+        toc_entries: &[OkTocEntryV0],
+        mark_level: SectionLevelV0,
+        mark_font: usize,
+        layout: &OkLayoutV0,
+    ) -> Vec<DviTextFurnitureV0> {
+        let sections = toc_entries
+            .iter()
+            .filter(|entry| entry.level == mark_level && entry.number.is_some())
+            .collect::<Vec<_>>();
+        let mut furniture = Vec::<DviTextFurnitureV0>::new();
+        for (page, start) in self.starts.iter().enumerate() {
+            let end = self.starts.get(page + 1).copied().unwrap_or(usize::MAX);
+            let label = &self.labels[page];
+            let label_sp = width_sp_v0(label, layout.em_sp);
+            let mut page_furniture = DviTextFurnitureV0 {
+                number: i32::try_from(self.numbers[page]).unwrap_or(i32::MAX),
+                ..DviTextFurnitureV0::default()
+            };
+            match self.styles[page] {
+                PageStyleV0::Empty => {}
+                PageStyleV0::Plain => page_furniture.foot.push(DviTextRunV0 {
+                    h: (layout.text_width_sp.saturating_sub(label_sp) / 2).max(0),
+                    font: 0,
+                    bytes: label.clone(),
+                }),
+                PageStyleV0::Headings => {
+                    let number_h = layout.text_width_sp.saturating_sub(label_sp).max(0);
+                    let mark = sections
+                        .iter()
+                        .find(|entry| (*start..end).contains(&entry.offset))
+                        .or_else(|| sections.iter().rev().find(|entry| entry.offset < *start));
+                    if let Some(mark) = mark {
+                        let mut number = mark.number.clone().unwrap_or_default();
+                        if mark.level == SectionLevelV0::Chapter {
+                            number = [b"CHAPTER ", number.as_slice(), b"."].concat();
+                        }
+                        let title = mark.title.to_ascii_uppercase();
+                        page_furniture.head =
+                            mark_runs_v0([number, title], mark_font, number_h, layout.em_sp);
+                    }
+                    page_furniture.head.push(DviTextRunV0 {
+                        h: number_h,
+                        font: 0,
+                        bytes: label.clone(),
+                    });
+                }
+            }
+            furniture.push(page_furniture);
+        }
+        furniture
+    }
+}
+
+fn width_sp_v0(bytes: &[u8], em_sp: i32) -> i32 {
+    bytes
+        .iter()
+        .filter_map(|byte| glyph_width_sp_v0(*byte, em_sp))
+        .fold(0i32, i32::saturating_add)
+}
+
+/// Runs of a mark's parts a `\quad` apart, cut short where they would reach `limit_h`.
+fn mark_runs_v0(parts: [Vec<u8>; 2], font: usize, limit_h: i32, em_sp: i32) -> Vec<DviTextRunV0> {
+    let mut runs = Vec::<DviTextRunV0>::new();
+    let mut h = 0i32;
+    for mut bytes in parts {
+        bytes.truncate(MAX_DVI_TEXT_RUN_BYTES_V0);
+        while !bytes.is_empty() && h.saturating_add(width_sp_v0(&bytes, em_sp)) > limit_h {
+            bytes.pop();
+        }
+        if bytes.is_empty() {
+            break;
+        }
+        let width = width_sp_v0(&bytes, em_sp);
+        runs.push(DviTextRunV0 { h, font, bytes });
+        h = h.saturating_add(width).saturating_add(em_sp);
+    }
+    runs
+}
+
+/// `\textheight` of `max_lines_per_page` lines: `\topskip` down to the first
+/// baseline, then one `\baselineskip` per further line.
+pub(crate) fn text_height_sp_v0(line_advance_sp: i32, max_lines_per_page: usize) -> i32 {
+    let lines = max_lines_per_page.saturating_sub(1) as i64;
+    (i64::from(TOPSKIP_SP_V0) + i64::from(line_advance_sp) * lines).min(MAX_DIMEN_SP_V0) as i32
+}
+
+/// Where heads and feet sit relative to the first baseline: `\headsep` above the
+/// body's top, and `\footskip` below its bottom. The first baseline itself sits at
+/// the geometry's origin. `page_layout_failed` when a head or foot would fall off
+/// the paper.
+pub(crate) fn dvi_pages_v0<'a>(
+    furniture: &'a [DviTextFurnitureV0],
+    layout: &OkLayoutV0,
+) -> Result<DviTextPagesV0<'a>, InvalidInputReasonV0> {
+    let head_v = -(TOPSKIP_SP_V0 + HEADSEP_SP_V0);
+    let foot_v =
+        i64::from(layout.text_height_sp) - i64::from(TOPSKIP_SP_V0) + i64::from(FOOTSKIP_SP_V0);
+    let (origin_h, origin_v) = layout.geometry.origin_v0()?;
+    // The DVI origin sits 1in in from the paper's top left corner.
+    let on_paper = |v: i64| {
+        (0..=i64::from(layout.geometry.paper_height_sp))
+            .contains(&(i64::from(ONE_INCH_SP_V0) + i64::from(origin_v) + v))
+    };
+    let heads = furniture.iter().any(|page| !page.head.is_empty());
+    let feet = furniture.iter().any(|page| !page.foot.is_empty());
+    if heads && !on_paper(i64::from(head_v)) || feet && !on_paper(foot_v) {
+        return Err(InvalidInputReasonV0::PageLayoutFailed);
+    }
+    Ok(DviTextPagesV0 {
+        furniture,
+        head_v,
+        foot_v: foot_v.min(MAX_DIMEN_SP_V0) as i32,
+        origin_h,
+        origin_v,
+    })
+}
@@ -0,0 +1,147 @@
+use super::ok_dimen_v0::{scan_dimen_v0, scan_keyword_v0, OkLayoutV0, MAX_DIMEN_SP_V0};
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+/// Default rule thickness (`0.4pt`) of `\hrule`, `\vrule` and LaTeX's `\rule` use.
+pub(crate) const DEFAULT_RULE_THICKNESS_SP_V0: i32 = 26_214;
+
+/// A rule read from the body, its bottom edge `raise` above the baseline. Rules
+/// with `own_line` (`\hrule`) take a line of their own; others sit on the current
+/// line and advance past their width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OkRuleV0 {
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+    pub(crate) raise: i32,
+    pub(crate) own_line: bool,
+}
+
+/// Height of LaTeX's `\strutbox` (8.5pt of a 12pt baseline skip); its depth is
+/// the rest of the line advance.
+pub(crate) fn strut_height_sp_v0(layout: &OkLayoutV0) -> i32 {
+    layout.line_advance_sp / 24 * 17
+}
+
+/// `{<dimen>}` argument; anything besides the dimension and spaces is invalid.
+pub(crate) fn consume_dimen_group_v0(
+    tokens: &[TokenV0],
+    index: usize,
+    layout: &OkLayoutV0,
+) -> Result<Option<(i32, usize)>, InvalidInputReasonV0> {
+    if !matches!(tokens.get(index), Some(TokenV0::BeginGroup)) {
+        return Ok(None);
+    }
+    let Some((value, mut index)) = scan_dimen_v0(tokens, index + 1, layout)? else {
+        return Ok(None);
+    };
+    while matches!(tokens.get(index), Some(TokenV0::Space)) {
+        index += 1;
+    }
+    if !matches!(tokens.get(index), Some(TokenV0::EndGroup)) {
+        return Err(InvalidInputReasonV0::DimenInvalid);
+    }
+    Ok(Some((value, index + 1)))
+}
+
+/// LaTeX's `\rule[raise]{width}{height}`.
+fn consume_latex_rule_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+    layout: &OkLayoutV0,
+) -> Result<Option<(OkRuleV0, usize)>, InvalidInputReasonV0> {
+    let mut raise = 0;
+    if matches!(tokens.get(index), Some(TokenV0::Char(b'['))) {
+        let Some((value, next_index)) = scan_dimen_v0(tokens, index + 1, layout)? else {
+            return Ok(None);
+        };
+        if !matches!(tokens.get(next_index), Some(TokenV0::Char(b']'))) {
+            return Err(InvalidInputReasonV0::DimenInvalid);
+        }
+        raise = value;
+        index = next_index + 1;
+    }
+    let Some((width, index)) = consume_dimen_group_v0(tokens, index, layout)? else {
+        return Ok(None);
+    };
+    let Some((height, index)) = consume_dimen_group_v0(tokens, index, layout)? else {
+        return Ok(None);
+    };
+    Ok(Some((
+        OkRuleV0 {
+            width,
+            height,
+            raise,
+            own_line: false,
+        },
+        index,
+    )))
+}
+
+/// `\hrule`/`\vrule` rule specification: any of `width`, `height` and `depth`
+/// keywords, each followed by a dimension; later keywords override earlier ones.
+fn consume_primitive_rule_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+    layout: &OkLayoutV0,
+    vertical: bool,
+) -> Result<Option<(OkRuleV0, usize)>, InvalidInputReasonV0> {
+    let strut_height = strut_height_sp_v0(layout);
+    let (mut width, mut height, mut depth) = if vertical {
+        (
+            DEFAULT_RULE_THICKNESS_SP_V0,
+            strut_height,
+            layout.line_advance_sp - strut_height,
+        )
+    } else {
+        (layout.text_width_sp, DEFAULT_RULE_THICKNESS_SP_V0, 0)
+    };
+    loop {
+        let (target, next_index) =
+            if let Some(next_index) = scan_keyword_v0(tokens, index, b"width") {
+                (&mut width, next_index)
+            } else if let Some(next_index) = scan_keyword_v0(tokens, index, b"height") {
+                (&mut height, next_index)
+            } else if let Some(next_index) = scan_keyword_v0(tokens, index, b"depth") {
+                (&mut depth, next_index)
+            } else {
+                break;
+            };
+        let Some((value, next_index)) = scan_dimen_v0(tokens, next_index, layout)? else {
+            return Ok(None);
+        };
+        *target = value;
+        index = next_index;
+    }
+    let total = height as i64 + depth as i64;
+    if total > MAX_DIMEN_SP_V0 {
+        return Err(InvalidInputReasonV0::RuleDimensionInvalid);
+    }
+    let rule = OkRuleV0 {
+        width,
+        height: total as i32,
+        raise: -depth,
+        own_line: !vertical,
+    };
+    Ok(Some((rule, index)))
+}
+
+/// Rule command `name` (`rule`, `hrule` or `vrule`) with its arguments starting at
+/// `index`. Negative widths or heights are outside what the DVI writer draws and
+/// fail closed.
+pub(crate) fn consume_rule_v0(
+    name: &[u8],
+    tokens: &[TokenV0],
+    index: usize,
+    layout: &OkLayoutV0,
+) -> Result<Option<(OkRuleV0, usize)>, InvalidInputReasonV0> {
+    let consumed = match name {
+        b"rule" => consume_latex_rule_v0(tokens, index, layout)?,
+        b"vrule" => consume_primitive_rule_v0(tokens, index, layout, true)?,
+        b"hrule" => consume_primitive_rule_v0(tokens, index, layout, false)?,
+        _ => return Ok(None),
+    };
+    if consumed.is_some_and(|(rule, _)| rule.width < 0 || rule.height < 0) {
+        return Err(InvalidInputReasonV0::RuleDimensionInvalid);
+    }
+    Ok(consumed)
+}
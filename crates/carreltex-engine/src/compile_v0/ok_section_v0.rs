@@ -0,0 +1,143 @@
+use super::ok_font_v0::{FontSizeV0, OkFontV0};
+
+/// Sectioning levels, outermost first; `\chapter` belongs to report and book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SectionLevelV0 {
+    Chapter,
+    Section,
+    Subsection,
+    Subsubsection,
+    Paragraph,
+}
+
+impl SectionLevelV0 {
+    pub(crate) fn from_name_v0(name: &[u8]) -> Option<Self> {
+        match name {
+            b"chapter" => Some(Self::Chapter),
+            b"section" => Some(Self::Section),
+            b"subsection" => Some(Self::Subsection),
+            b"subsubsection" => Some(Self::Subsubsection),
+            b"paragraph" => Some(Self::Paragraph),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name_v0(self) -> &'static [u8] {
+        match self {
+            Self::Chapter => b"chapter",
+            Self::Section => b"section",
+            Self::Subsection => b"subsection",
+            Self::Subsubsection => b"subsubsection",
+            Self::Paragraph => b"paragraph",
+        }
+    }
+
+    /// LaTeX's level number, compared against `secnumdepth` and `tocdepth`: 0 for
+    /// chapters, 1 for sections and so on.
+    pub(crate) fn depth_v0(self) -> usize {
+        match self {
+            Self::Chapter => 0,
+            Self::Section => 1,
+            Self::Subsection => 2,
+            Self::Subsubsection => 3,
+            Self::Paragraph => 4,
+        }
+    }
+
+    /// `\paragraph` is a run-in heading: the text continues on its line.
+    pub(crate) fn is_run_in_v0(self) -> bool {
+        self == Self::Paragraph
+    }
+
+    /// `\Huge\bfseries` for chapter titles, `\Large\bfseries` for sections,
+    /// `\large` for subsections and `\normalsize` below, from the class's `normal`
+    /// font.
+    pub(crate) fn font_v0(self, normal: OkFontV0) -> OkFontV0 {
+        let size = match self {
+            Self::Chapter => FontSizeV0::Huger,
+            Self::Section => FontSizeV0::Larger,
+            Self::Subsection => FontSizeV0::Large,
+            Self::Subsubsection | Self::Paragraph => FontSizeV0::NormalSize,
+        };
+        normal.sized_v0(size).bold_v0()
+    }
+}
+
+/// `chapter`, `section`, `subsection`, `subsubsection` and `paragraph` counters;
+/// stepping one resets those below it. Classes with chapters number sections
+/// within them.
+#[derive(Debug, Default)]
+pub(crate) struct SectionCountersV0 {
+    values: [u32; 5],
+    chapters: bool,
+}
+
+impl SectionCountersV0 {
+    pub(crate) fn new_v0(chapters: bool) -> Self {
+        SectionCountersV0 {
+            chapters,
+            ..SectionCountersV0::default()
+        }
+    }
+
+    /// `secnumdepth`: 3 in article, 2 in report and book.
+    fn secnumdepth_v0(&self) -> usize {
+        if self.chapters {
+            2
+        } else {
+            3
+        }
+    }
+
+    /// Steps the counter of `level` and returns its `\thesection`-style number,
+    /// such as `2.1`, when the level is numbered.
+    pub(crate) fn step_v0(&mut self, level: SectionLevelV0) -> Option<Vec<u8>> {
+        let depth = level.depth_v0();
+        self.values[depth] = self.values[depth].saturating_add(1);
+        for value in &mut self.values[depth + 1..] {
+            *value = 0;
+        }
+        if depth > self.secnumdepth_v0() {
+            return None;
+        }
+        let first = usize::from(!self.chapters);
+        let number = self.values[first..=depth]
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(".");
+        Some(number.into_bytes())
+    }
+
+    /// What `\theequation`, `\thefigure` and `\thetable` put before their counter:
+    /// the chapter and a dot, as in `2.1`, in classes with chapters.
+    pub(crate) fn number_prefix_v0(&self) -> Vec<u8> {
+        if self.chapters {
+            format!("{}.", self.values[0]).into_bytes()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Heading line text: the number, when present, then the title with space runs
+/// collapsed and trimmed. `None` when the title holds bytes outside the OK text.
+pub(crate) fn heading_text_v0(number: Option<&[u8]>, title: &[u8]) -> Option<Vec<u8>> {
+    let mut text = Vec::<u8>::new();
+    if let Some(number) = number {
+        text.extend_from_slice(number);
+    }
+    for word in title
+        .split(|byte| *byte == b' ')
+        .filter(|word| !word.is_empty())
+    {
+        if word.contains(&b'\\') || !word.iter().all(|byte| (0x21..=0x7e).contains(byte)) {
+            return None;
+        }
+        if !text.is_empty() {
+            text.push(b' ');
+        }
+        text.extend_from_slice(word);
+    }
+    Some(text)
+}
@@ -0,0 +1,750 @@
+use super::ok_args_v0::{
+    consume_group_bytes_v0, consume_group_literal, is_control_seq_v0, skip_spaces,
+};
+use super::ok_dimen_v0::OkLayoutV0;
+use super::ok_font_v0::OkFontV0;
+use super::ok_footnote_v0::consume_group_range_v0;
+use super::ok_math_layout_v0::{typeset_math_v0, MathBoxV0};
+use super::ok_math_v0::{consume_math_v0, is_math_opener_v0};
+use super::ok_rule_v0::{consume_dimen_group_v0, strut_height_sp_v0, DEFAULT_RULE_THICKNESS_SP_V0};
+use super::ok_toc_v0::text_width_sp_v0;
+use super::ok_v0::is_supported_ok_char_v0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+/// `\tabcolsep` (6pt) and `\doublerulesep` (2pt) at 10pt, in tenths of an em.
+const TABCOLSEP_EM_TENTHS_V0: i64 = 6;
+const DOUBLERULESEP_EM_TENTHS_V0: i64 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnAlignV0 {
+    Left,
+    Center,
+    Right,
+    /// `p{width}`: a paragraph of that width, broken at spaces and set ragged right.
+    Paragraph(i32),
+}
+
+/// What stands between columns: a `|` rule, or `@{text}` set in place of the
+/// column space on that side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SeparatorV0 {
+    Rule,
+    Text(Vec<u8>),
+}
+
+/// A column template as LaTeX builds it from the preamble: the first column owns
+/// the separators before it, and every column those after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ColumnV0 {
+    align: ColumnAlignV0,
+    before: Vec<SeparatorV0>,
+    after: Vec<SeparatorV0>,
+}
+
+/// Column templates of a `{spec}` argument of `l`, `c`, `r`, `p{width}`, `|` and
+/// `@{text}`; any other column type is `tabular_column_unsupported`.
+fn consume_column_spec_v0(
+    tokens: &[TokenV0],
+    index: usize,
+    layout: &OkLayoutV0,
+) -> Result<Option<(Vec<ColumnV0>, usize)>, InvalidInputReasonV0> {
+    let Some((range, next_index)) = consume_group_range_v0(tokens, index) else {
+        return Ok(None);
+    };
+    let spec = &tokens[..range.end];
+    let mut columns = Vec::<ColumnV0>::new();
+    let mut separators = Vec::<SeparatorV0>::new();
+    let mut index = range.start;
+    while index < range.end {
+        let align = match &spec[index] {
+            TokenV0::Space => None,
+            TokenV0::Char(b'l') => Some(ColumnAlignV0::Left),
+            TokenV0::Char(b'c') => Some(ColumnAlignV0::Center),
+            TokenV0::Char(b'r') => Some(ColumnAlignV0::Right),
+            TokenV0::Char(b'p') => {
+                let Some((width, next_index)) = consume_dimen_group_v0(spec, index + 1, layout)?
+                else {
+                    return Err(InvalidInputReasonV0::TabularColumnUnsupported);
+                };
+                index = next_index - 1;
+                Some(ColumnAlignV0::Paragraph(width.max(0)))
+            }
+            TokenV0::Char(b'|') => {
+                separators.push(SeparatorV0::Rule);
+                None
+            }
+            TokenV0::Char(b'@') => {
+                let Some((text, next_index)) = consume_group_bytes_v0(spec, index + 1) else {
+                    return Err(InvalidInputReasonV0::TabularColumnUnsupported);
+                };
+                if !text.iter().all(|byte| is_supported_ok_char_v0(*byte)) {
+                    return Err(InvalidInputReasonV0::TabularColumnUnsupported);
+                }
+                separators.push(SeparatorV0::Text(text));
+                index = next_index - 1;
+                None
+            }
+            _ => return Err(InvalidInputReasonV0::TabularColumnUnsupported),
+        };
+        index += 1;
+        let Some(align) = align else {
+            continue;
+        };
+        if let Some(last) = columns.last_mut() {
+            last.after = std::mem::take(&mut separators);
+        }
+        columns.push(ColumnV0 {
+            align,
+            before: std::mem::take(&mut separators),
+            after: Vec::new(),
+        });
+    }
+    let Some(last) = columns.last_mut() else {
+        return Err(InvalidInputReasonV0::TabularColumnUnsupported);
+    };
+    last.after = separators;
+    Ok(Some((columns, next_index)))
+}
+
+/// Material of a cell: text in one font, an interword space, or inline math.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CellPieceV0 {
+    Text(OkFontV0, Vec<u8>),
+    Space(OkFontV0),
+    Math(MathBoxV0),
+}
+
+impl CellPieceV0 {
+    pub(crate) fn width_v0(&self, layout: &OkLayoutV0) -> i64 {
+        match self {
+            CellPieceV0::Text(font, bytes) => {
+                i64::from(text_width_sp_v0(bytes, font.em_sp_v0(layout.em_sp)))
+            }
+            CellPieceV0::Space(font) => {
+                i64::from(text_width_sp_v0(b" ", font.em_sp_v0(layout.em_sp)))
+            }
+            CellPieceV0::Math(math) => math.width,
+        }
+    }
+}
+
+fn pieces_width_v0(pieces: &[CellPieceV0], layout: &OkLayoutV0) -> i64 {
+    pieces.iter().map(|piece| piece.width_v0(layout)).sum()
+}
+
+/// Cell material from `index` up to the `&`, `\\`, `\end` or unmatched `}` ending
+/// it: characters, spaces (dropped at both ends, as TeX's alignments drop them),
+/// groups, font commands and inline math.
+fn consume_cell_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+    font: OkFontV0,
+    layout: &OkLayoutV0,
+) -> Result<Option<(Vec<CellPieceV0>, usize)>, InvalidInputReasonV0> {
+    let mut font = font;
+    let mut outer_fonts = Vec::<OkFontV0>::new();
+    let mut pieces = Vec::<CellPieceV0>::new();
+    index = skip_spaces(tokens, index);
+    loop {
+        match tokens.get(index) {
+            Some(TokenV0::Char(byte)) if is_supported_ok_char_v0(*byte) => {
+                match pieces.last_mut() {
+                    Some(CellPieceV0::Text(last, bytes)) if *last == font => bytes.push(*byte),
+                    _ => pieces.push(CellPieceV0::Text(font, vec![*byte])),
+                }
+                index += 1;
+            }
+            Some(TokenV0::Space) => {
+                if !matches!(pieces.last(), None | Some(CellPieceV0::Space(_))) {
+                    pieces.push(CellPieceV0::Space(font));
+                }
+                index += 1;
+            }
+            Some(TokenV0::BeginGroup) => {
+                outer_fonts.push(font);
+                index += 1;
+            }
+            Some(TokenV0::EndGroup) => {
+                let Some(outer) = outer_fonts.pop() else {
+                    break;
+                };
+                font = outer;
+                index += 1;
+            }
+            Some(_) if is_math_opener_v0(tokens, index) => {
+                let Some((formula, next_index)) = consume_math_v0(tokens, index)? else {
+                    return Ok(None);
+                };
+                if formula.display {
+                    return Ok(None);
+                }
+                let math = typeset_math_v0(&formula.list, false, font, layout.em_sp);
+                pieces.push(CellPieceV0::Math(math));
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name)) if font.text_command_v0(name).is_some() => {
+                let (Some(inner), Some(TokenV0::BeginGroup)) =
+                    (font.text_command_v0(name), tokens.get(index + 1))
+                else {
+                    return Ok(None);
+                };
+                outer_fonts.push(font);
+                font = inner;
+                index += 2;
+            }
+            Some(TokenV0::ControlSeq(name)) if font.declaration_v0(name).is_some() => {
+                let Some(declared) = font.declaration_v0(name) else {
+                    return Ok(None);
+                };
+                font = declared;
+                index += 1;
+            }
+            _ => break,
+        }
+    }
+    if !outer_fonts.is_empty() {
+        return Ok(None);
+    }
+    if matches!(pieces.last(), Some(CellPieceV0::Space(_))) {
+        pieces.pop();
+    }
+    Ok(Some((pieces, index)))
+}
+
+/// A `\multicolumn{n}{spec}{text}` cell spans `n` columns under a template of its
+/// own; other cells span one under their column's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CellV0 {
+    span: usize,
+    template: Option<ColumnV0>,
+    pieces: Vec<CellPieceV0>,
+}
+
+/// Columns `first..=last` an `\hline` (all of them) or `\cline{first-last}` spans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HorizontalRuleV0 {
+    first: usize,
+    last: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RowV0 {
+    cells: Vec<CellV0>,
+    rules_above: Vec<HorizontalRuleV0>,
+}
+
+fn consume_cell_or_multicolumn_v0(
+    tokens: &[TokenV0],
+    index: usize,
+    font: OkFontV0,
+    layout: &OkLayoutV0,
+) -> Result<Option<(CellV0, usize)>, InvalidInputReasonV0> {
+    let index = skip_spaces(tokens, index);
+    if !is_control_seq_v0(tokens.get(index), b"multicolumn") {
+        return Ok(
+            consume_cell_v0(tokens, index, font, layout)?.map(|(pieces, index)| {
+                let cell = CellV0 {
+                    span: 1,
+                    template: None,
+                    pieces,
+                };
+                (cell, index)
+            }),
+        );
+    }
+    let Some((span, index)) = consume_group_bytes_v0(tokens, index + 1) else {
+        return Ok(None);
+    };
+    let Some(span) = std::str::from_utf8(&span)
+        .ok()
+        .and_then(|span| span.trim().parse::<usize>().ok())
+        .filter(|span| *span > 0)
+    else {
+        return Ok(None);
+    };
+    let Some((mut template, index)) = consume_column_spec_v0(tokens, index, layout)? else {
+        return Ok(None);
+    };
+    if template.len() != 1 {
+        return Err(InvalidInputReasonV0::TabularColumnUnsupported);
+    }
+    if !matches!(tokens.get(index), Some(TokenV0::BeginGroup)) {
+        return Ok(None);
+    }
+    let Some((pieces, index)) = consume_cell_v0(tokens, index + 1, font, layout)? else {
+        return Ok(None);
+    };
+    if !matches!(tokens.get(index), Some(TokenV0::EndGroup)) {
+        return Ok(None);
+    }
+    let cell = CellV0 {
+        span,
+        template: template.pop(),
+        pieces,
+    };
+    Ok(Some((cell, skip_spaces(tokens, index + 1))))
+}
+
+/// `\hline`s and `\cline{first-last}`s at the start of a row.
+fn consume_horizontal_rules_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+    columns: usize,
+    rules: &mut Vec<HorizontalRuleV0>,
+) -> Option<usize> {
+    loop {
+        index = skip_spaces(tokens, index);
+        match tokens.get(index) {
+            Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"hline" => {
+                rules.push(HorizontalRuleV0 {
+                    first: 0,
+                    last: columns - 1,
+                });
+                index += 1;
+            }
+            Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"cline" => {
+                let (range, next_index) = consume_group_bytes_v0(tokens, index + 1)?;
+                let range = std::str::from_utf8(&range).ok()?;
+                let (first, last) = range.split_once('-')?;
+                let (first, last) = (first.parse::<usize>().ok()?, last.parse::<usize>().ok()?);
+                if first == 0 || first > last || last > columns {
+                    return None;
+                }
+                rules.push(HorizontalRuleV0 {
+                    first: first - 1,
+                    last: last - 1,
+                });
+                index = next_index;
+            }
+            _ => return Some(index),
+        }
+    }
+}
+
+/// A `tabular` environment: its column templates, its rows and the horizontal
+/// rules below the last one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TabularV0 {
+    columns: Vec<ColumnV0>,
+    rows: Vec<RowV0>,
+    rules_below: Vec<HorizontalRuleV0>,
+    font: OkFontV0,
+}
+
+pub(crate) fn is_tabular_opener_v0(tokens: &[TokenV0], index: usize) -> bool {
+    matches!(tokens.get(index), Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"begin")
+        && consume_group_literal(tokens, index + 1, b"tabular").is_some()
+}
+
+/// `\begin{tabular}[pos]{spec}` at `index` through `\end{tabular}`, its cells in
+/// `font`. Rows end at `\\` (or `\tabularnewline`); one ending the last row adds
+/// none. More cells than columns are `tabular_extra_cell`.
+pub(crate) fn consume_tabular_v0(
+    tokens: &[TokenV0],
+    index: usize,
+    font: OkFontV0,
+    layout: &OkLayoutV0,
+) -> Result<Option<(TabularV0, usize)>, InvalidInputReasonV0> {
+    if !matches!(tokens.get(index), Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"begin")
+    {
+        return Ok(None);
+    }
+    let Some(mut index) = consume_group_literal(tokens, index + 1, b"tabular") else {
+        return Ok(None);
+    };
+    if matches!(tokens.get(index), Some(TokenV0::Char(b'['))) {
+        if !matches!(
+            tokens.get(index + 1),
+            Some(TokenV0::Char(b't' | b'b' | b'c'))
+        ) || !matches!(tokens.get(index + 2), Some(TokenV0::Char(b']')))
+        {
+            return Ok(None);
+        }
+        index += 3;
+    }
+    let Some((columns, next_index)) = consume_column_spec_v0(tokens, index, layout)? else {
+        return Ok(None);
+    };
+    index = next_index;
+    let mut rows = Vec::<RowV0>::new();
+    loop {
+        let mut rules_above = Vec::new();
+        let Some(next_index) =
+            consume_horizontal_rules_v0(tokens, index, columns.len(), &mut rules_above)
+        else {
+            return Ok(None);
+        };
+        index = next_index;
+        if matches!(tokens.get(index), Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"end")
+        {
+            let Some(next_index) = consume_group_literal(tokens, index + 1, b"tabular") else {
+                return Ok(None);
+            };
+            let tabular = TabularV0 {
+                columns,
+                rows,
+                rules_below: rules_above,
+                font,
+            };
+            return Ok(Some((tabular, next_index)));
+        }
+        let mut cells = Vec::<CellV0>::new();
+        let mut spanned = 0usize;
+        loop {
+            let Some((cell, next_index)) =
+                consume_cell_or_multicolumn_v0(tokens, index, font, layout)?
+            else {
+                return Ok(None);
+            };
+            spanned += cell.span;
+            if spanned > columns.len() {
+                return Err(InvalidInputReasonV0::TabularExtraCell);
+            }
+            cells.push(cell);
+            index = next_index;
+            match tokens.get(index) {
+                Some(TokenV0::AlignTab) => index += 1,
+                Some(TokenV0::ControlSeq(name))
+                    if matches!(name.as_slice(), b"\\" | b"tabularnewline") =>
+                {
+                    if matches!(tokens.get(index + 1), Some(TokenV0::Char(b'[' | b'*'))) {
+                        return Ok(None);
+                    }
+                    index += 1;
+                    break;
+                }
+                Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"end" => break,
+                _ => return Ok(None),
+            }
+        }
+        rows.push(RowV0 { cells, rules_above });
+    }
+}
+
+/// Something set on a line of the table, `h` from its left edge: a rule with its
+/// bottom edge `raise` above the baseline, or cell material.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TabularMarkV0 {
+    Rule { width: i64, height: i64, raise: i64 },
+    Piece(CellPieceV0),
+}
+
+/// The marks of one output line, in the order they are set.
+pub(crate) type TabularLineV0 = Vec<(i64, TabularMarkV0)>;
+
+/// Horizontal extent of a column template around its body.
+#[derive(Debug, Clone, Copy)]
+struct TemplateExtentV0 {
+    before_sp: i64,
+    after_sp: i64,
+}
+
+/// A cell of a row placed across columns `start..end` of the table, broken into
+/// lines, with the separators its template sets around it.
+struct PlacedCellV0<'a> {
+    start: i64,
+    end: i64,
+    before: &'a [SeparatorV0],
+    after: &'a [SeparatorV0],
+    align: ColumnAlignV0,
+    extent: TemplateExtentV0,
+    lines: Vec<Vec<CellPieceV0>>,
+}
+
+impl TabularV0 {
+    fn tabcolsep_sp_v0(layout: &OkLayoutV0) -> i64 {
+        i64::from(layout.em_sp) * TABCOLSEP_EM_TENTHS_V0 / 10
+    }
+
+    fn separators_width_v0(&self, separators: &[SeparatorV0], layout: &OkLayoutV0) -> i64 {
+        let mut width = 0;
+        for (index, separator) in separators.iter().enumerate() {
+            width += match separator {
+                SeparatorV0::Rule => i64::from(DEFAULT_RULE_THICKNESS_SP_V0),
+                SeparatorV0::Text(text) => pieces_width_v0(&self.text_pieces_v0(text), layout),
+            };
+            if index > 0 && *separator == SeparatorV0::Rule && separators[index - 1] == *separator {
+                width += i64::from(layout.em_sp) * DOUBLERULESEP_EM_TENTHS_V0 / 10;
+            }
+        }
+        width
+    }
+
+    fn text_pieces_v0(&self, text: &[u8]) -> Vec<CellPieceV0> {
+        vec![CellPieceV0::Text(self.font, text.to_vec())]
+    }
+
+    /// Separators a template sets before and after its body, and its padding: a
+    /// `\tabcolsep` on each side not taken by an `@{text}`.
+    fn extent_v0(
+        &self,
+        before: &[SeparatorV0],
+        after: &[SeparatorV0],
+        layout: &OkLayoutV0,
+    ) -> TemplateExtentV0 {
+        let pad = |separator: Option<&SeparatorV0>| {
+            if matches!(separator, Some(SeparatorV0::Text(_))) {
+                0
+            } else {
+                Self::tabcolsep_sp_v0(layout)
+            }
+        };
+        TemplateExtentV0 {
+            before_sp: self.separators_width_v0(before, layout) + pad(before.last()),
+            after_sp: self.separators_width_v0(after, layout) + pad(after.first()),
+        }
+    }
+
+    /// Separators before column `column` in the table's own templates; the padding
+    /// before a later column follows the separator ending the one before it.
+    fn column_extent_v0(&self, column: usize, layout: &OkLayoutV0) -> TemplateExtentV0 {
+        let template = &self.columns[column];
+        let mut extent = self.extent_v0(&template.before, &template.after, layout);
+        if column > 0 {
+            let previous = self.columns[column - 1].after.last();
+            extent.before_sp = if matches!(previous, Some(SeparatorV0::Text(_))) {
+                0
+            } else {
+                Self::tabcolsep_sp_v0(layout)
+            };
+        }
+        extent
+    }
+
+    /// Lines of a cell: paragraph cells broken at spaces to their width.
+    fn cell_lines_v0(
+        pieces: &[CellPieceV0],
+        align: ColumnAlignV0,
+        layout: &OkLayoutV0,
+    ) -> Vec<Vec<CellPieceV0>> {
+        let ColumnAlignV0::Paragraph(width) = align else {
+            return vec![pieces.to_vec()];
+        };
+        let is_space = |piece: &CellPieceV0| matches!(piece, CellPieceV0::Space(_));
+        let mut lines = Vec::<Vec<CellPieceV0>>::new();
+        let mut words = pieces.split(is_space);
+        let mut spaces = pieces.iter().filter(|piece| is_space(piece));
+        let mut line = words.next().unwrap_or_default().to_vec();
+        for (word, space) in words.zip(&mut spaces) {
+            let line_width = pieces_width_v0(&line, layout) + space.width_v0(layout);
+            if !line.is_empty() && line_width + pieces_width_v0(word, layout) > i64::from(width) {
+                lines.push(std::mem::replace(&mut line, word.to_vec()));
+            } else {
+                if !line.is_empty() {
+                    line.push(space.clone());
+                }
+                line.extend_from_slice(word);
+            }
+        }
+        lines.push(line);
+        lines
+    }
+
+    /// The table's lines, a row's cells at the natural width of their columns and
+    /// its height that of its tallest paragraph cell. Rules run the height of a
+    /// strut on each line, `\hline`s at the top of the row below them (the bottom
+    /// of the last row), a `\doublerulesep` between repeated ones; spanning cells
+    /// widen the last column they span when they need to.
+    pub(crate) fn lines_v0(&self, layout: &OkLayoutV0) -> Vec<TabularLineV0> {
+        let count = self.columns.len();
+        let extents = (0..count)
+            .map(|column| self.column_extent_v0(column, layout))
+            .collect::<Vec<_>>();
+        let mut bodies = self
+            .columns
+            .iter()
+            .map(|column| match column.align {
+                ColumnAlignV0::Paragraph(width) => i64::from(width),
+                _ => 0,
+            })
+            .collect::<Vec<_>>();
+        for row in &self.rows {
+            for (cell, column) in self.cell_columns_v0(row) {
+                if cell.span == 1
+                    && cell.template.is_none()
+                    && !matches!(self.columns[column].align, ColumnAlignV0::Paragraph(_))
+                {
+                    bodies[column] = bodies[column].max(pieces_width_v0(&cell.pieces, layout));
+                }
+            }
+        }
+        for row in &self.rows {
+            for (cell, column) in self.cell_columns_v0(row) {
+                if cell.span == 1 && cell.template.is_none() {
+                    continue;
+                }
+                let last = column + cell.span - 1;
+                let region: i64 = (column..=last)
+                    .map(|index| extents[index].before_sp + bodies[index] + extents[index].after_sp)
+                    .sum();
+                let needed = self.cell_need_v0(cell, column, &extents, layout);
+                if needed > region {
+                    bodies[last] += needed - region;
+                }
+            }
+        }
+        let mut starts = Vec::<i64>::new();
+        let mut h = 0;
+        for column in 0..count {
+            starts.push(h);
+            h += extents[column].before_sp + bodies[column] + extents[column].after_sp;
+        }
+        starts.push(h);
+
+        let strut_sp = i64::from(strut_height_sp_v0(layout));
+        let depth_sp = i64::from(layout.line_advance_sp) - strut_sp;
+        let thickness_sp = i64::from(DEFAULT_RULE_THICKNESS_SP_V0);
+        let step_sp = thickness_sp + i64::from(layout.em_sp) * DOUBLERULESEP_EM_TENTHS_V0 / 10;
+        let horizontal = |rules: &[HorizontalRuleV0], top_sp: i64| {
+            rules
+                .iter()
+                .enumerate()
+                .map(|(index, rule)| {
+                    let mark = TabularMarkV0::Rule {
+                        width: starts[rule.last + 1] - starts[rule.first],
+                        height: thickness_sp,
+                        raise: top_sp - thickness_sp - step_sp * index as i64,
+                    };
+                    (starts[rule.first], mark)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut lines = Vec::<TabularLineV0>::new();
+        for (row_index, row) in self.rows.iter().enumerate() {
+            let mut cells = Vec::new();
+            for (cell, column) in self.cell_columns_v0(row) {
+                let (template, extent) = match &cell.template {
+                    Some(template) => (
+                        template,
+                        self.extent_v0(&template.before, &template.after, layout),
+                    ),
+                    None => (&self.columns[column], extents[column]),
+                };
+                cells.push(PlacedCellV0 {
+                    start: starts[column],
+                    end: starts[column + cell.span],
+                    before: if cell.template.is_some() || column == 0 {
+                        &template.before
+                    } else {
+                        &[]
+                    },
+                    after: &template.after,
+                    align: template.align,
+                    extent,
+                    lines: Self::cell_lines_v0(&cell.pieces, template.align, layout),
+                });
+            }
+            let height = cells.iter().map(|cell| cell.lines.len()).max().unwrap_or(1);
+            for line_index in 0..height {
+                let mut line = TabularLineV0::new();
+                if line_index == 0 {
+                    line.extend(horizontal(&row.rules_above, strut_sp));
+                }
+                if row_index + 1 == self.rows.len() && line_index + 1 == height {
+                    line.extend(horizontal(&self.rules_below, -depth_sp));
+                }
+                for cell in &cells {
+                    let text = line_index == 0;
+                    self.push_separators_v0(&mut line, cell.start, cell.before, text, layout);
+                    let after_h = cell.end - self.separators_width_v0(cell.after, layout);
+                    self.push_separators_v0(&mut line, after_h, cell.after, text, layout);
+                    let Some(pieces) = cell.lines.get(line_index) else {
+                        continue;
+                    };
+                    let body_start = cell.start + cell.extent.before_sp;
+                    let body_end = cell.end - cell.extent.after_sp;
+                    let width = pieces_width_v0(pieces, layout);
+                    let mut h = match cell.align {
+                        ColumnAlignV0::Left | ColumnAlignV0::Paragraph(_) => body_start,
+                        ColumnAlignV0::Center => body_start + (body_end - body_start - width) / 2,
+                        ColumnAlignV0::Right => body_end - width,
+                    };
+                    for piece in pieces {
+                        let width = piece.width_v0(layout);
+                        line.push((h, TabularMarkV0::Piece(piece.clone())));
+                        h += width;
+                    }
+                }
+                line.sort_by_key(|(h, mark)| (*h, matches!(mark, TabularMarkV0::Piece(_))));
+                lines.push(line);
+            }
+        }
+        lines
+    }
+
+    /// Width a spanning cell needs: its content with its own template around it.
+    fn cell_need_v0(
+        &self,
+        cell: &CellV0,
+        column: usize,
+        extents: &[TemplateExtentV0],
+        layout: &OkLayoutV0,
+    ) -> i64 {
+        let (align, extent) = match &cell.template {
+            Some(template) => (
+                template.align,
+                self.extent_v0(&template.before, &template.after, layout),
+            ),
+            None => (self.columns[column].align, extents[column]),
+        };
+        let content = match align {
+            ColumnAlignV0::Paragraph(width) => i64::from(width),
+            _ => pieces_width_v0(&cell.pieces, layout),
+        };
+        extent.before_sp + content + extent.after_sp
+    }
+
+    /// Each cell of `row` with the first column it spans.
+    fn cell_columns_v0<'a>(&self, row: &'a RowV0) -> Vec<(&'a CellV0, usize)> {
+        let mut column = 0;
+        row.cells
+            .iter()
+            .map(|cell| {
+                let first = column;
+                column += cell.span;
+                (cell, first)
+            })
+            .collect()
+    }
+
+    /// Separators from `h` on: strut-high rules on every line, `@` text on the
+    /// first line of the row only.
+    fn push_separators_v0(
+        &self,
+        line: &mut TabularLineV0,
+        mut h: i64,
+        separators: &[SeparatorV0],
+        text: bool,
+        layout: &OkLayoutV0,
+    ) {
+        let strut_sp = i64::from(strut_height_sp_v0(layout));
+        let depth_sp = i64::from(layout.line_advance_sp) - strut_sp;
+        for (index, separator) in separators.iter().enumerate() {
+            if index > 0 && *separator == SeparatorV0::Rule && separators[index - 1] == *separator {
+                h += i64::from(layout.em_sp) * DOUBLERULESEP_EM_TENTHS_V0 / 10;
+            }
+            match separator {
+                SeparatorV0::Rule => {
+                    let mark = TabularMarkV0::Rule {
+                        width: i64::from(DEFAULT_RULE_THICKNESS_SP_V0),
+                        height: strut_sp + depth_sp,
+                        raise: -depth_sp,
+                    };
+                    line.push((h, mark));
+                    h += i64::from(DEFAULT_RULE_THICKNESS_SP_V0);
+                }
+                SeparatorV0::Text(bytes) => {
+                    for piece in self.text_pieces_v0(bytes) {
+                        let width = piece.width_v0(layout);
+                        if text {
+                            line.push((h, TabularMarkV0::Piece(piece)));
+                        }
+                        h += width;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use super::ok_dimen_v0::OkLayoutV0;
+use super::ok_font_v0::OkFontV0;
+use super::ok_page_v0::OkPagesV0;
+use super::ok_section_v0::SectionLevelV0;
+use carreltex_xdv::glyph_width_sp_v0;
+
+/// `\@pnumwidth`, the box page numbers are set flush right in, in hundredths of an em.
+const PNUMWIDTH_EM_HUNDREDTHS_V0: i64 = 155;
+
+/// A numbered heading as the body met it; `offset` is where its text starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OkTocEntryV0 {
+    pub(crate) level: SectionLevelV0,
+    pub(crate) number: Option<Vec<u8>>,
+    pub(crate) title: Vec<u8>,
+    pub(crate) offset: usize,
+}
+
+/// One `\contentsline` of the `.toc` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TocEntryV0 {
+    pub(crate) level: SectionLevelV0,
+    pub(crate) number: Option<Vec<u8>>,
+    pub(crate) title: Vec<u8>,
+    pub(crate) page: Vec<u8>,
+}
+
+/// Headings of a pass as its `.toc` records them, with the page each one fell on.
+pub(crate) fn toc_entries_v0(entries: &[OkTocEntryV0], pages: &OkPagesV0) -> Vec<TocEntryV0> {
+    entries
+        .iter()
+        .map(|entry| TocEntryV0 {
+            level: entry.level,
+            number: entry.number.clone(),
+            title: entry.title.clone(),
+            page: pages.label_v0(entry.offset),
+        })
+        .collect()
+}
+
+/// `\contentsline {section}{\numberline {1}Title}{1}%` per heading, numbers only for
+/// numbered ones.
+pub(crate) fn write_toc_v0(entries: &[TocEntryV0]) -> Vec<u8> {
+    let mut toc = Vec::<u8>::new();
+    for entry in entries {
+        toc.extend_from_slice(b"\\contentsline {");
+        toc.extend_from_slice(entry.level.name_v0());
+        toc.extend_from_slice(b"}{");
+        if let Some(number) = &entry.number {
+            toc.extend_from_slice(b"\\numberline {");
+            toc.extend_from_slice(number);
+            toc.push(b'}');
+        }
+        toc.extend_from_slice(&entry.title);
+        toc.extend_from_slice(b"}{");
+        toc.extend_from_slice(&entry.page);
+        toc.extend_from_slice(b"}%\n");
+    }
+    toc
+}
+
+/// A typeset entry: the number starts at `indent_sp` and the title at `left_sp`,
+/// `label_sep_sp` after the number; `fills` lead to the page number, set flush
+/// right at the text width. Top-level entries follow an empty line.
+#[derive(Debug)]
+pub(crate) struct TocLineV0 {
+    pub(crate) top_level: bool,
+    pub(crate) font: OkFontV0,
+    pub(crate) indent_sp: i32,
+    pub(crate) left_sp: i32,
+    pub(crate) label_sep_sp: i32,
+    pub(crate) number: Vec<u8>,
+    pub(crate) title: Vec<u8>,
+    /// `(to_h, leader_pitch_sp)` of each `carreltex_xdv::DviTextFillV0`.
+    pub(crate) fills: Vec<(i32, Option<i32>)>,
+    pub(crate) page: Vec<u8>,
+}
+
+pub(crate) fn text_width_sp_v0(bytes: &[u8], em_sp: i32) -> i32 {
+    bytes
+        .iter()
+        .filter_map(|byte| glyph_width_sp_v0(*byte, em_sp))
+        .sum()
+}
+
+fn em_hundredths_v0(em_sp: i32, hundredths: i64) -> i32 {
+    (i64::from(em_sp) * hundredths / 100) as i32
+}
+
+/// Article's `\l@section` (bold, no leaders), `\l@subsection` and
+/// `\l@subsubsection` (dot leaders of `\@dottedtocline`, a half-em dot between two
+/// `\@dotsep` kerns for one dot per em); `\paragraph` entries stay out, below
+/// `tocdepth` 3. With `chapters`, report's and book's `\l@chapter`, `\l@section`
+/// and `\l@subsection` take those indents, and `tocdepth` 2 leaves out
+/// `\subsubsection`.
+pub(crate) fn toc_lines_v0(
+    entries: &[TocEntryV0],
+    chapters: bool,
+    font: OkFontV0,
+    layout: &OkLayoutV0,
+) -> Vec<TocLineV0> {
+    let top = usize::from(!chapters);
+    let tocdepth = top + 2;
+    let mut lines = Vec::<TocLineV0>::new();
+    for entry in entries {
+        let depth = entry.level.depth_v0();
+        if depth > tocdepth {
+            continue;
+        }
+        let (indent, numwidth) = match depth.saturating_sub(top) {
+            0 => (0, 150),
+            1 => (150, 230),
+            _ => (380, 320),
+        };
+        let section = depth <= top;
+        let font = if section { font.bold_v0() } else { font };
+        let em_sp = font.em_sp_v0(layout.em_sp);
+        let indent_sp = em_hundredths_v0(em_sp, indent);
+        let number = entry.number.clone().unwrap_or_default();
+        let left_sp = if number.is_empty() {
+            indent_sp
+        } else {
+            indent_sp.saturating_add(em_hundredths_v0(em_sp, numwidth))
+        };
+        let number_end_sp = indent_sp.saturating_add(text_width_sp_v0(&number, em_sp));
+        let page_h = layout
+            .text_width_sp
+            .saturating_sub(text_width_sp_v0(&entry.page, em_sp))
+            .max(0);
+        let mut fills = Vec::<(i32, Option<i32>)>::new();
+        if !section {
+            let leaders_end = layout
+                .text_width_sp
+                .saturating_sub(em_hundredths_v0(em_sp, PNUMWIDTH_EM_HUNDREDTHS_V0))
+                .max(0);
+            fills.push((leaders_end, Some(em_sp)));
+        }
+        fills.push((page_h, None));
+        lines.push(TocLineV0 {
+            top_level: section,
+            font,
+            indent_sp,
+            left_sp,
+            label_sep_sp: left_sp.saturating_sub(number_end_sp).max(0),
+            number,
+            title: entry.title.clone(),
+            fills,
+            page: entry.page.clone(),
+        });
+    }
+    lines
+}
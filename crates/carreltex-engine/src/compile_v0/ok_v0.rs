@@ -1,98 +1,938 @@
+use super::ok_args_v0::{
+    consume_group_bytes_v0, consume_group_literal, consume_optional_bytes_v0,
+    consume_special_payload_v0, is_control_seq_v0, skip_spaces,
+};
+use super::ok_body_v0::OkBodyV0;
+use super::ok_cite_v0::OkBibliographyV0;
+use super::ok_class_v0::{
+    consume_document_class_v0, unused_class_options_v0, DocumentClassV0, OkClassV0, OkLetterV0,
+};
+use super::ok_color_v0::{parse_color_spec_v0, ColorTableV0, ColorV0, COLOR_POP_SPECIAL_V0};
+use super::ok_dimen_v0::OkLayoutV0;
+use super::ok_equation_v0::{consume_equation_v0, is_equation_opener_v0};
+use super::ok_float_v0::{FloatKindV0, FloatStateV0};
+use super::ok_font_v0::{FontTableV0, OkFontV0};
+use super::ok_footnote_v0::{
+    consume_group_range_v0, footnote_rule_v0, superscript_raise_sp_v0, FootnoteCommandV0,
+    FootnoteStateV0, PendingFootnoteV0,
+};
+use super::ok_geometry_v0::fit_text_block_v0;
+use super::ok_graphics_v0::{consume_includegraphics_v0, OkImageV0};
+use super::ok_label_v0::{
+    find_aux_label_v0, ref_text_v0, AuxLabelV0, OkAuxFilesV0, OkLabelV0, OkUndefinedRefV0,
+    MAX_OK_LABELS_V0,
+};
+use super::ok_list_v0::{consume_item_label_v0, consume_list_options_v0, ListKindV0, ListStackV0};
+use super::ok_math_v0::{consume_math_v0, is_math_opener_v0};
+use super::ok_page_v0::{OkPageCommandV0, PageCommandV0, PageStyleV0};
+use super::ok_rule_v0::{consume_rule_v0, OkRuleV0};
+use super::ok_section_v0::{SectionCountersV0, SectionLevelV0};
+use super::ok_tabular_v0::{consume_tabular_v0, is_tabular_opener_v0};
+use super::ok_toc_v0::{toc_lines_v0, TocLineV0};
+use super::package_v0::BuiltinPackageV0;
+use crate::reasons_v0::InvalidInputReasonV0;
 use crate::tex::tokenize_v0::TokenV0;
+use carreltex_xdv::{
+    DviColorStackV0, DviTextFillV0, DviTextIndentV0, DviTextRaiseV0, DviTextRuleV0,
+    DviTextSpecialV0, MAX_DVI_TEXT_FILLS_V0, MAX_DVI_TEXT_INDENTS_V0, MAX_DVI_TEXT_RAISES_V0,
+    MAX_DVI_TEXT_RULES_V0,
+};
+
+mod bib_v0;
+mod class_v0;
+mod display_v0;
+mod float_v0;
+mod footnote_v0;
+mod graphics_v0;
+mod hyperref_v0;
+mod preamble_v0;
+mod section_v0;
+mod tabular_v0;
+
 pub(crate) const MAX_OK_TEXT_BYTES_V0: usize = 64 * 1024;
 pub(crate) const OK_GLYPH_ADVANCE_SP_V0: i32 = 65_536;
 pub(crate) const OK_LINE_ADVANCE_SP_V0: i32 = 786_432;
 
-fn skip_spaces(tokens: &[TokenV0], mut index: usize) -> usize {
-    while matches!(tokens.get(index), Some(TokenV0::Space)) {
-        index += 1;
-    }
-    index
+pub(crate) fn is_supported_ok_char_v0(byte: u8) -> bool {
+    (0x20..=0x7e).contains(&byte) && byte != b'\\'
 }
 
-fn consume_group_literal(tokens: &[TokenV0], mut index: usize, literal: &[u8]) -> Option<usize> {
-    if !matches!(tokens.get(index), Some(TokenV0::BeginGroup)) {
-        return None;
+/// An open group: the color pushes to pop, and the font and `\ref` text to restore
+/// when it closes.
+struct GroupFrameV0 {
+    color_pushes: usize,
+    font: OkFontV0,
+    current_label: Vec<u8>,
+}
+
+/// Body under construction; `groups` holds every enclosing group, the first entry
+/// being the document body itself, and `class_environment` the depth of the
+/// class's `letter` or `frame` group when one is open.
+struct OkBodyStateV0 {
+    body: OkBodyV0,
+    class: OkClassV0,
+    letter: OkLetterV0,
+    class_environment: Option<usize>,
+    main_matter: bool,
+    previous_was_space: bool,
+    packages: Vec<BuiltinPackageV0>,
+    images: Vec<OkImageV0>,
+    bibliography: Option<Result<OkBibliographyV0, InvalidInputReasonV0>>,
+    colors: ColorTableV0,
+    groups: Vec<GroupFrameV0>,
+    color_stack: DviColorStackV0,
+    font: OkFontV0,
+    font_table: FontTableV0,
+    current_label: Vec<u8>,
+    sections: SectionCountersV0,
+    lists: ListStackV0,
+    footnotes: FootnoteStateV0,
+    floats: FloatStateV0,
+    equations: usize,
+}
+
+impl OkBodyStateV0 {
+    /// Whether `\usepackage` loaded `package`.
+    fn loaded_v0(&self, package: BuiltinPackageV0) -> bool {
+        self.packages.contains(&package)
     }
-    index += 1;
-    for expected in literal {
-        if !matches!(tokens.get(index), Some(TokenV0::Char(value)) if value == expected) {
-            return None;
+
+    /// Raw `\special{color ...}` payloads share the stack with `\color`, so
+    /// they must balance as well.
+    fn push_special_v0(&mut self, bytes: Vec<u8>) -> Result<(), InvalidInputReasonV0> {
+        self.color_stack
+            .apply_special_v0(&bytes)
+            .ok_or(InvalidInputReasonV0::ColorStackUnbalanced)?;
+        self.body.specials.push(DviTextSpecialV0 {
+            offset: self.body.text.len(),
+            bytes,
+        });
+        Ok(())
+    }
+
+    /// Glyphs pushed from here on use the current font.
+    fn sync_font_v0(&mut self) -> Result<(), InvalidInputReasonV0> {
+        let font = self.font_table.number_v0(&self.font)?;
+        self.body.switch_font_v0(font);
+        Ok(())
+    }
+
+    fn push_text_v0(&mut self, bytes: &[u8]) -> Result<(), InvalidInputReasonV0> {
+        if !bytes.is_empty() {
+            self.sync_font_v0()?;
+            self.body.text.extend_from_slice(bytes);
         }
-        index += 1;
+        Ok(())
     }
-    if !matches!(tokens.get(index), Some(TokenV0::EndGroup)) {
-        return None;
+
+    fn open_group_v0(&mut self, color_pushes: usize) {
+        self.groups.push(GroupFrameV0 {
+            color_pushes,
+            font: self.font,
+            current_label: self.current_label.clone(),
+        });
     }
-    Some(index + 1)
-}
 
-fn is_supported_ok_char_v0(byte: u8) -> bool {
-    (0x20..=0x7e).contains(&byte) && byte != b'\\'
-}
+    /// Offset labels and references are anchored at: the mark of the footnote being
+    /// typeset, if any, for the page it lands on.
+    fn anchor_offset_v0(&self) -> usize {
+        self.footnotes.anchor.unwrap_or(self.body.text.len())
+    }
 
-pub(crate) fn extract_strict_ok_text_body_v0(tokens: &[TokenV0]) -> Option<Vec<u8>> {
-    let mut index = 0usize;
-    if !matches!(
-        tokens.get(index),
-        Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"documentclass"
-    ) {
-        return None;
+    /// `\label{key}` names the last number stepped with `\refstepcounter` (a
+    /// section or an `enumerate` item), or nothing.
+    fn push_label_v0(&mut self, key: Vec<u8>) -> Result<(), InvalidInputReasonV0> {
+        if self.body.labels.len() >= MAX_OK_LABELS_V0 {
+            return Err(InvalidInputReasonV0::LabelCountExceeded);
+        }
+        self.body.labels.push(OkLabelV0 {
+            key,
+            reference: self.current_label.clone(),
+            offset: self.anchor_offset_v0(),
+        });
+        Ok(())
     }
-    index += 1;
-    index = consume_group_literal(tokens, index, b"article")?;
-    index = skip_spaces(tokens, index);
 
-    if !matches!(
-        tokens.get(index),
-        Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"begin"
-    ) {
-        return None;
+    /// `\ref`, `\pageref` and `\eqref` with the `{key}` at `index`, against the
+    /// labels of the previous pass.
+    fn push_ref_v0(
+        &mut self,
+        command: &[u8],
+        tokens: &[TokenV0],
+        index: usize,
+        aux_labels: &[AuxLabelV0],
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        let Some((key, index)) = consume_group_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        if self.lists.awaits_item_v0() {
+            return Err(InvalidInputReasonV0::ListItemMissing);
+        }
+        let label = find_aux_label_v0(aux_labels, &key);
+        let Some(text) = ref_text_v0(command, label) else {
+            return Ok(None);
+        };
+        if label.is_none() {
+            self.body.undefined_refs.push(OkUndefinedRefV0 {
+                key,
+                offset: self.anchor_offset_v0(),
+                citation: false,
+            });
+        }
+        self.push_text_v0(&text)?;
+        self.previous_was_space = false;
+        Ok(Some(index))
+    }
+
+    /// `\color` and `\textcolor` arguments: `[model]{spec}` or `{expression}`.
+    fn consume_color_v0(
+        &self,
+        tokens: &[TokenV0],
+        index: usize,
+    ) -> Result<Option<(ColorV0, usize)>, InvalidInputReasonV0> {
+        let Some((model, index)) = consume_optional_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        let Some((argument, index)) = consume_group_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        let color = match model {
+            Some(model) => parse_color_spec_v0(&model, &argument)?,
+            None => self.colors.resolve_v0(&argument)?,
+        };
+        Ok(Some((color, index)))
+    }
+
+    /// `\hrule` ends the current line, sits on an empty line of its own and lets
+    /// the text continue on the next one, skipping spaces as TeX's vertical mode does.
+    fn push_rule_v0(&mut self, rule: OkRuleV0) -> Result<(), InvalidInputReasonV0> {
+        if self.body.rules.len() >= MAX_DVI_TEXT_RULES_V0 {
+            return Err(InvalidInputReasonV0::RuleCountExceeded);
+        }
+        let at_line_start = matches!(self.body.text.last(), None | Some(0x0a | 0x0c));
+        if rule.own_line && !at_line_start {
+            self.body.text.push(0x0a);
+        }
+        self.body.rules.push(DviTextRuleV0 {
+            offset: self.body.text.len(),
+            special_index: self.body.specials.len(),
+            width: rule.width,
+            height: rule.height,
+            raise: rule.raise,
+            advance: !rule.own_line,
+        });
+        if rule.own_line {
+            self.body.text.push(0x0a);
+            self.previous_was_space = true;
+        } else {
+            self.previous_was_space = false;
+        }
+        Ok(())
+    }
+
+    /// Drops a space ending the current line, as TeX's `\unskip` before a heading,
+    /// unless a special or rule is anchored after it.
+    fn trim_trailing_space_v0(&mut self) {
+        let len = self.body.text.len();
+        let anchored = self
+            .body
+            .specials
+            .last()
+            .is_some_and(|special| special.offset == len)
+            || self
+                .body
+                .rules
+                .last()
+                .is_some_and(|rule| rule.offset == len);
+        if self.body.text.last() == Some(&b' ') && !anchored {
+            self.body.text.pop();
+        }
+    }
+
+    fn end_line_v0(&mut self) {
+        self.trim_trailing_space_v0();
+        if !matches!(self.body.text.last(), None | Some(0x0a | 0x0c)) {
+            self.body.text.push(0x0a);
+        }
+        self.previous_was_space = true;
+    }
+
+    /// Margin (and item label) of the lines from the current line on; an indent
+    /// already anchored there, such as one ending an inner list, is replaced.
+    fn push_indent_v0(
+        &mut self,
+        left_sp: i32,
+        label: &[u8],
+        label_sep_sp: i32,
+        label_h_sp: Option<i32>,
+    ) -> Result<(), InvalidInputReasonV0> {
+        self.end_line_v0();
+        let offset = self.body.text.len();
+        if self
+            .body
+            .indents
+            .last()
+            .is_some_and(|indent| indent.offset == offset)
+        {
+            self.body.indents.pop();
+        }
+        if self.body.indents.len() >= MAX_DVI_TEXT_INDENTS_V0 {
+            return Err(InvalidInputReasonV0::ListItemsExceeded);
+        }
+        self.body.indents.push(DviTextIndentV0 {
+            offset,
+            left_sp,
+            label_len: label.len(),
+            label_sep_sp,
+            label_h_sp,
+        });
+        self.push_text_v0(label)
+    }
+
+    /// `\begin{name}` / `\end{name}` of a list environment, which is also a group.
+    fn list_environment_v0(
+        &mut self,
+        begin: bool,
+        kind: ListKindV0,
+        layout: &OkLayoutV0,
+    ) -> Result<(), InvalidInputReasonV0> {
+        if begin {
+            self.open_group_v0(0);
+            self.lists.begin_v0(kind, layout.em_sp, self.groups.len())?;
+            self.end_line_v0();
+        } else {
+            self.lists.end_v0(kind, self.groups.len())?;
+            self.close_group_v0()?;
+            let left_sp = self.lists.left_sp_v0();
+            self.push_indent_v0(left_sp, b"", layout.em_sp / 2, None)?;
+        }
+        Ok(())
+    }
+
+    /// Ends the current line and leaves an empty one, unless the text is at the top
+    /// of a page or after an empty line already.
+    fn end_paragraph_v0(&mut self) {
+        self.end_line_v0();
+        let at_blank_line = matches!(self.body.text.last(), None | Some(0x0c))
+            || self.body.text.ends_with(&[0x0a, 0x0a]);
+        if !at_blank_line {
+            self.body.text.push(0x0a);
+        }
+    }
+
+    /// Headings start a new line after an empty one (the space above them, dropped
+    /// at the top of the body and after another heading). Display headings leave an
+    /// empty line below them; run-in ones continue the text after a space. Returns
+    /// the offset of the heading text.
+    fn push_heading_v0(
+        &mut self,
+        level: SectionLevelV0,
+        text: &[u8],
+    ) -> Result<usize, InvalidInputReasonV0> {
+        self.end_paragraph_v0();
+        let offset = self.body.text.len();
+        let font = self.font;
+        self.font = level.font_v0(self.class.normal_font_v0());
+        self.push_text_v0(text)?;
+        self.font = font;
+        if level.is_run_in_v0() {
+            self.push_text_v0(b" ")?;
+        } else {
+            self.body.text.extend_from_slice(&[0x0a, 0x0a]);
+        }
+        self.previous_was_space = true;
+        Ok(offset)
+    }
+
+    /// `\tableofcontents` and the lists of floats: a `heading` over the lines of
+    /// the previous pass, then the text continues at the enclosing margin.
+    fn push_contents_v0(
+        &mut self,
+        heading: &[u8],
+        lines: &[TocLineV0],
+        layout: &OkLayoutV0,
+    ) -> Result<(), InvalidInputReasonV0> {
+        self.push_top_heading_v0(heading)?;
+        for line in lines {
+            self.push_toc_line_v0(line)?;
+        }
+        if !lines.is_empty() {
+            let left_sp = self.lists.left_sp_v0();
+            self.push_indent_v0(left_sp, b"", layout.em_sp / 2, None)?;
+        }
+        Ok(())
+    }
+
+    fn push_toc_line_v0(&mut self, line: &TocLineV0) -> Result<(), InvalidInputReasonV0> {
+        if self.body.fills.len() + line.fills.len() > MAX_DVI_TEXT_FILLS_V0 {
+            return Err(InvalidInputReasonV0::TocEntriesExceeded);
+        }
+        if line.top_level {
+            self.end_paragraph_v0();
+        }
+        let font = self.font;
+        self.font = line.font;
+        self.push_indent_v0(
+            line.left_sp,
+            &line.number,
+            line.label_sep_sp,
+            Some(line.indent_sp),
+        )?;
+        self.push_text_v0(&line.title)?;
+        let offset = self.body.text.len();
+        for (to_h, leader_pitch_sp) in &line.fills {
+            self.body.fills.push(DviTextFillV0 {
+                offset,
+                to_h: *to_h,
+                leader_pitch_sp: *leader_pitch_sp,
+            });
+        }
+        self.push_text_v0(&line.page)?;
+        self.font = font;
+        self.end_line_v0();
+        Ok(())
+    }
+
+    /// `\footnote`, `\footnotemark` and `\footnotetext`: the mark goes into the text
+    /// now while the body waits for the end of the main text. Footnotes with a body
+    /// inside another body or a float are outside the subset.
+    fn push_footnote_v0(
+        &mut self,
+        command: FootnoteCommandV0,
+        tokens: &[TokenV0],
+        index: usize,
+        layout: &OkLayoutV0,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        if command.has_body_v0()
+            && (self.footnotes.anchor.is_some() || self.floats.current.is_some())
+        {
+            return Ok(None);
+        }
+        let Some((optional, mut index)) = consume_optional_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        let Some(number) = self.footnotes.number_v0(command, optional) else {
+            return Ok(None);
+        };
+        let mut body = None;
+        if command.has_body_v0() {
+            let Some((range, next_index)) = consume_group_range_v0(tokens, index) else {
+                return Ok(None);
+            };
+            body = Some(range);
+            index = next_index;
+        }
+        let anchor = self.body.text.len();
+        if command.has_mark_v0() {
+            if self.lists.awaits_item_v0() {
+                return Err(InvalidInputReasonV0::ListItemMissing);
+            }
+            self.push_footnote_mark_v0(&number, layout, None)?;
+        }
+        if let Some(body) = body {
+            self.footnotes.defer_v0(PendingFootnoteV0 {
+                anchor,
+                number,
+                body,
+            })?;
+        }
+        Ok(Some(index))
     }
-    index += 1;
-    index = consume_group_literal(tokens, index, b"document")?;
 
-    let mut body = Vec::<u8>::new();
-    let mut previous_was_space = false;
+    /// `number` raised as a superscript in the script size of the upright text
+    /// font; with `mark_h_sp` it is the label of a new line, as a body starts.
+    fn push_footnote_mark_v0(
+        &mut self,
+        number: &[u8],
+        layout: &OkLayoutV0,
+        mark_h_sp: Option<i32>,
+    ) -> Result<(), InvalidInputReasonV0> {
+        if self.body.raises.len() >= MAX_DVI_TEXT_RAISES_V0 {
+            return Err(InvalidInputReasonV0::FootnoteCountExceeded);
+        }
+        let font = self.font;
+        self.font = font.normalfont_v0().script_size_v0();
+        if mark_h_sp.is_some() {
+            self.push_indent_v0(0, number, 0, mark_h_sp)?;
+        } else {
+            self.push_text_v0(number)?;
+        }
+        self.font = font;
+        let end = self.body.text.len();
+        self.body.raises.push(DviTextRaiseV0 {
+            start: end - number.len(),
+            end,
+            raise: superscript_raise_sp_v0(font, layout),
+        });
+        self.previous_was_space = false;
+        Ok(())
+    }
+
+    /// `\pagestyle`, `\thispagestyle` or `\pagenumbering` at `index`, for the page
+    /// holding the current offset; footnote and float bodies may not hold one.
+    fn push_page_command_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        let Some(TokenV0::ControlSeq(name)) = tokens.get(index) else {
+            return Ok(None);
+        };
+        let Some((argument, index)) = consume_group_bytes_v0(tokens, index + 1) else {
+            return Ok(None);
+        };
+        let Some(command) = PageCommandV0::parse_v0(name, &argument) else {
+            return Ok(None);
+        };
+        if self.footnotes.anchor.is_some() || self.floats.current.is_some() {
+            return Ok(None);
+        }
+        if command.style_v0() == Some(PageStyleV0::Headings) {
+            let slanted = self.class.normal_font_v0().slanted_v0();
+            self.body.mark_font = self.font_table.number_v0(&slanted)?;
+        }
+        self.body.page_commands.push(OkPageCommandV0 {
+            command,
+            offset: self.body.text.len(),
+        });
+        Ok(Some(index))
+    }
+
+    fn close_group_v0(&mut self) -> Result<(), InvalidInputReasonV0> {
+        let Some(frame) = self.groups.pop() else {
+            return Ok(());
+        };
+        for _ in 0..frame.color_pushes {
+            self.push_special_v0(COLOR_POP_SPECIAL_V0.to_vec())?;
+        }
+        self.font = frame.font;
+        self.current_label = frame.current_label;
+        Ok(())
+    }
+}
+
+/// Body material from `index` up to the first token outside the subset, such as
+/// `\end{document}`, or the end of `tokens`; returns where it stopped.
+fn consume_body_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+    state: &mut OkBodyStateV0,
+    layout: &OkLayoutV0,
+    aux: &OkAuxFilesV0,
+) -> Result<Option<usize>, InvalidInputReasonV0> {
     loop {
         match tokens.get(index) {
+            Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"special" => {
+                let Some((payload, next_index)) = consume_special_payload_v0(tokens, index + 1)
+                else {
+                    return Ok(None);
+                };
+                state.push_special_v0(payload)?;
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name))
+                if state.loaded_v0(BuiltinPackageV0::Color)
+                    && (name.as_slice() == b"color" || name.as_slice() == b"textcolor") =>
+            {
+                let Some((color, next_index)) = state.consume_color_v0(tokens, index + 1)? else {
+                    return Ok(None);
+                };
+                state.push_special_v0(color.push_special_v0())?;
+                if name.as_slice() == b"color" {
+                    let Some(frame) = state.groups.last_mut() else {
+                        return Ok(None);
+                    };
+                    frame.color_pushes += 1;
+                    index = next_index;
+                } else {
+                    if !matches!(tokens.get(next_index), Some(TokenV0::BeginGroup)) {
+                        return Ok(None);
+                    }
+                    state.open_group_v0(1);
+                    index = next_index + 1;
+                }
+            }
+            Some(TokenV0::ControlSeq(name)) if state.font.text_command_v0(name).is_some() => {
+                let (Some(font), Some(TokenV0::BeginGroup)) =
+                    (state.font.text_command_v0(name), tokens.get(index + 1))
+                else {
+                    return Ok(None);
+                };
+                state.open_group_v0(0);
+                state.font = font;
+                index += 2;
+            }
+            Some(TokenV0::ControlSeq(name)) if state.font.declaration_v0(name).is_some() => {
+                let Some(font) = state.font.declaration_v0(name) else {
+                    return Ok(None);
+                };
+                state.font = font;
+                index += 1;
+            }
+            Some(TokenV0::ControlSeq(name))
+                if matches!(name.as_slice(), b"rule" | b"hrule" | b"vrule") =>
+            {
+                let Some((rule, next_index)) = consume_rule_v0(name, tokens, index + 1, layout)?
+                else {
+                    return Ok(None);
+                };
+                state.push_rule_v0(rule)?;
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name))
+                if SectionLevelV0::from_name_v0(name)
+                    .is_some_and(|level| state.class.class.has_level_v0(level)) =>
+            {
+                let Some(level) = SectionLevelV0::from_name_v0(name) else {
+                    return Ok(None);
+                };
+                let Some(next_index) = state.push_section_v0(level, tokens, index + 1)? else {
+                    return Ok(None);
+                };
+                index = next_index;
+            }
+            Some(_) if class_v0::is_class_command_v0(tokens, index, state.class.class) => {
+                let Some(next_index) = state.push_class_command_v0(tokens, index, layout)? else {
+                    return Ok(None);
+                };
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name))
+                if name.as_slice() == b"begin"
+                    && consume_group_bytes_v0(tokens, index + 1)
+                        .is_some_and(|(env, _)| FloatKindV0::from_env_name_v0(&env).is_some()) =>
+            {
+                let Some(next_index) = state.push_float_v0(tokens, index, layout, aux)? else {
+                    return Ok(None);
+                };
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name))
+                if state.loaded_v0(BuiltinPackageV0::Graphicx)
+                    && name.as_slice() == b"includegraphics" =>
+            {
+                let Some((graphic, next_index)) =
+                    consume_includegraphics_v0(tokens, index + 1, &state.images, layout)?
+                else {
+                    return Ok(None);
+                };
+                state.push_graphic_v0(&graphic, layout)?;
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name))
+                if state.loaded_v0(BuiltinPackageV0::Hyperref)
+                    && matches!(name.as_slice(), b"url" | b"href") =>
+            {
+                let Some(next_index) = state.push_hyperref_v0(name, tokens, index + 1)? else {
+                    return Ok(None);
+                };
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"caption" => {
+                let Some(next_index) = state.push_caption_v0(tokens, index, layout)? else {
+                    return Ok(None);
+                };
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name))
+                if matches!(name.as_slice(), b"begin" | b"end")
+                    && consume_group_bytes_v0(tokens, index + 1)
+                        .is_some_and(|(env, _)| ListKindV0::from_name_v0(&env).is_some()) =>
+            {
+                let Some((env, mut next_index)) = consume_group_bytes_v0(tokens, index + 1) else {
+                    return Ok(None);
+                };
+                let Some(kind) = ListKindV0::from_name_v0(&env) else {
+                    return Ok(None);
+                };
+                let begin = name.as_slice() == b"begin";
+                let mut label = None;
+                if begin && state.loaded_v0(BuiltinPackageV0::Enumitem) {
+                    let Some((options, options_end)) =
+                        consume_list_options_v0(tokens, next_index, kind)
+                    else {
+                        return Ok(None);
+                    };
+                    (label, next_index) = (options, options_end);
+                }
+                state.list_environment_v0(begin, kind, layout)?;
+                if let Some(label) = label {
+                    state.lists.set_label_v0(label);
+                }
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"item" => {
+                let Some((label, next_index)) = consume_item_label_v0(tokens, index + 1) else {
+                    return Ok(None);
+                };
+                let item = state.lists.item_v0(label, layout.em_sp)?;
+                if let Some(reference) = &item.reference {
+                    state.current_label = reference.clone();
+                }
+                let font = state.font;
+                if item.bold_label {
+                    state.font = font.bold_v0();
+                }
+                state.push_indent_v0(
+                    item.left_sp,
+                    &item.label,
+                    item.label_sep_sp,
+                    item.label_h_sp,
+                )?;
+                state.font = font;
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"label" => {
+                let Some((key, next_index)) = consume_group_bytes_v0(tokens, index + 1) else {
+                    return Ok(None);
+                };
+                state.push_label_v0(key)?;
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name)) if ref_text_v0(name, None).is_some() => {
+                let Some(next_index) = state.push_ref_v0(name, tokens, index + 1, &aux.labels)?
+                else {
+                    return Ok(None);
+                };
+                index = next_index;
+            }
+            Some(_) if bib_v0::is_bib_command_v0(tokens, index) => {
+                let Some(next_index) = state.push_bib_command_v0(tokens, index, layout, aux)?
+                else {
+                    return Ok(None);
+                };
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name)) if FootnoteCommandV0::from_name_v0(name).is_some() => {
+                let Some(command) = FootnoteCommandV0::from_name_v0(name) else {
+                    return Ok(None);
+                };
+                let Some(next_index) =
+                    state.push_footnote_v0(command, tokens, index + 1, layout)?
+                else {
+                    return Ok(None);
+                };
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name)) if PageCommandV0::is_page_command_v0(name) => {
+                let Some(next_index) = state.push_page_command_v0(tokens, index)? else {
+                    return Ok(None);
+                };
+                index = next_index;
+            }
+            Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"thepage" => {
+                if state.lists.awaits_item_v0() {
+                    return Err(InvalidInputReasonV0::ListItemMissing);
+                }
+                let label = aux.pages.label_v0(state.anchor_offset_v0());
+                state.push_text_v0(&label)?;
+                state.previous_was_space = false;
+                index += 1;
+            }
+            Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"tableofcontents" => {
+                let chapters = state.class.class.has_chapters_v0();
+                let lines = toc_lines_v0(&aux.toc, chapters, state.font, layout);
+                state.push_contents_v0(b"Contents", &lines, layout)?;
+                index += 1;
+            }
+            Some(TokenV0::ControlSeq(name))
+                if matches!(name.as_slice(), b"listoffigures" | b"listoftables") =>
+            {
+                let kind = if name.as_slice() == b"listoffigures" {
+                    FloatKindV0::Figure
+                } else {
+                    FloatKindV0::Table
+                };
+                state.push_float_list_v0(kind, &aux.floats, layout)?;
+                index += 1;
+            }
+            Some(_) if is_math_opener_v0(tokens, index) => {
+                if state.lists.awaits_item_v0() {
+                    return Err(InvalidInputReasonV0::ListItemMissing);
+                }
+                let Some((formula, next_index)) = consume_math_v0(tokens, index)? else {
+                    return Ok(None);
+                };
+                state.push_math_v0(&formula, layout)?;
+                index = next_index;
+            }
+            Some(_)
+                if is_equation_opener_v0(
+                    tokens,
+                    index,
+                    state.loaded_v0(BuiltinPackageV0::Amsmath),
+                ) =>
+            {
+                if state.lists.awaits_item_v0() {
+                    return Err(InvalidInputReasonV0::ListItemMissing);
+                }
+                let Some((equation, next_index)) =
+                    consume_equation_v0(tokens, index, state.loaded_v0(BuiltinPackageV0::Amsmath))?
+                else {
+                    return Ok(None);
+                };
+                state.push_equation_v0(&equation, layout)?;
+                index = next_index;
+            }
+            Some(_) if is_tabular_opener_v0(tokens, index) => {
+                if state.lists.awaits_item_v0() {
+                    return Err(InvalidInputReasonV0::ListItemMissing);
+                }
+                let Some((tabular, next_index)) =
+                    consume_tabular_v0(tokens, index, state.font, layout)?
+                else {
+                    return Ok(None);
+                };
+                state.push_tabular_v0(&tabular, layout)?;
+                index = next_index;
+            }
+            Some(TokenV0::BeginGroup) => {
+                state.open_group_v0(0);
+                index += 1;
+            }
+            Some(TokenV0::EndGroup)
+                if state.groups.len() > 1
+                    && state.class_environment != Some(state.groups.len()) =>
+            {
+                if state.lists.owns_group_v0(state.groups.len()) {
+                    return Err(InvalidInputReasonV0::ListEnvironmentUnbalanced);
+                }
+                state.close_group_v0()?;
+                index += 1;
+            }
             Some(TokenV0::Space) => {
-                if !previous_was_space {
-                    body.push(b' ');
-                    previous_was_space = true;
+                if !state.previous_was_space {
+                    state.push_text_v0(b" ")?;
+                    state.previous_was_space = true;
                 }
                 index += 1;
             }
             Some(TokenV0::Char(0x0c)) => {
-                body.push(0x0c);
-                previous_was_space = false;
+                state.body.text.push(0x0c);
+                state.previous_was_space = false;
                 index += 1;
             }
             Some(TokenV0::Char(0x0a)) => {
-                body.push(0x0a);
-                previous_was_space = false;
+                state.body.text.push(0x0a);
+                state.previous_was_space = false;
                 index += 1;
             }
             Some(TokenV0::Char(byte)) if is_supported_ok_char_v0(*byte) => {
-                body.push(*byte);
-                previous_was_space = false;
+                if state.lists.awaits_item_v0() {
+                    return Err(InvalidInputReasonV0::ListItemMissing);
+                }
+                state.push_text_v0(&[*byte])?;
+                state.previous_was_space = false;
                 index += 1;
             }
             _ => break,
         }
     }
+    Ok(Some(index))
+}
+
+/// Strict OK subset: `Ok(None)` when the document falls outside it, `Err` when it
+/// is inside the subset but invalid (such as an undefined color). References and
+/// the table of contents come from `aux`, what the previous pass wrote; the files
+/// of `\includegraphics` from `images` and what `\bibliography` reads from
+/// `bibliography`.
+pub(crate) fn extract_strict_ok_text_body_v0(
+    tokens: &[TokenV0],
+    layout: &OkLayoutV0,
+    aux: &OkAuxFilesV0,
+    images: &[OkImageV0],
+    bibliography: Option<&Result<OkBibliographyV0, InvalidInputReasonV0>>,
+) -> Result<Option<OkBodyV0>, InvalidInputReasonV0> {
+    let mut layout = *layout;
+    let Some((class, next_index)) = consume_document_class_v0(tokens, &mut layout)? else {
+        return Ok(None);
+    };
+    let chapters = class.class.has_chapters_v0();
+    let mut state = OkBodyStateV0 {
+        body: OkBodyV0::new_v0(footnote_rule_v0(&layout), layout),
+        class,
+        letter: OkLetterV0::default(),
+        class_environment: None,
+        main_matter: true,
+        previous_was_space: false,
+        packages: Vec::new(),
+        images: images.to_vec(),
+        bibliography: bibliography.cloned(),
+        colors: ColorTableV0::default(),
+        groups: vec![GroupFrameV0 {
+            color_pushes: 0,
+            font: class.normal_font_v0(),
+            current_label: Vec::new(),
+        }],
+        color_stack: DviColorStackV0::default(),
+        font: class.normal_font_v0(),
+        font_table: FontTableV0::default(),
+        current_label: Vec::new(),
+        sections: SectionCountersV0::new_v0(chapters),
+        lists: ListStackV0::default(),
+        footnotes: FootnoteStateV0::default(),
+        floats: FloatStateV0::default(),
+        equations: 0,
+    };
+    state.body.unused_class_options = unused_class_options_v0(tokens);
+    if chapters {
+        state.body.mark_level = SectionLevelV0::Chapter;
+    }
+    if class.class == DocumentClassV0::Beamer {
+        state.push_page_style_v0(PageCommandV0::Style(PageStyleV0::Empty));
+    }
+    let Some(mut index) =
+        preamble_v0::consume_preamble_v0(tokens, next_index, &mut state, &mut layout)?
+    else {
+        return Ok(None);
+    };
+    let Some(layout) = fit_text_block_v0(&layout) else {
+        return Ok(None);
+    };
+    let layout = &layout;
+    state.body.layout = *layout;
+    state.body.footnote_rule = footnote_rule_v0(layout);
+
+    if !is_control_seq_v0(tokens.get(index), b"begin") {
+        return Ok(None);
+    }
+    index += 1;
+    let Some(next_index) = consume_group_literal(tokens, index, b"document") else {
+        return Ok(None);
+    };
+    index = next_index;
+    state.push_special_v0(layout.geometry.papersize_special_v0())?;
 
-    if !matches!(
-        tokens.get(index),
-        Some(TokenV0::ControlSeq(name)) if name.as_slice() == b"end"
-    ) {
-        return None;
+    let Some(next_index) = consume_body_v0(tokens, index, &mut state, layout, aux)? else {
+        return Ok(None);
+    };
+    index = next_index;
+
+    let ends_document = is_control_seq_v0(tokens.get(index), b"end")
+        && consume_group_literal(tokens, index + 1, b"document").is_some();
+    if ends_document && !state.lists.is_empty_v0() {
+        return Err(InvalidInputReasonV0::ListEnvironmentUnbalanced);
+    }
+    if state.groups.len() != 1 || !is_control_seq_v0(tokens.get(index), b"end") {
+        return Ok(None);
     }
+    state.close_group_v0()?;
     index += 1;
-    index = consume_group_literal(tokens, index, b"document")?;
-    index = skip_spaces(tokens, index);
+    let Some(next_index) = consume_group_literal(tokens, index, b"document") else {
+        return Ok(None);
+    };
+    index = skip_spaces(tokens, next_index);
     if index != tokens.len() {
-        return None;
+        return Ok(None);
+    }
+    if float_v0::push_float_bodies_v0(tokens, &mut state, layout, aux)?.is_none()
+        || footnote_v0::push_footnote_bodies_v0(tokens, &mut state, layout, aux)?.is_none()
+    {
+        return Ok(None);
+    }
+    if state.color_stack.depth_v0() != 0 {
+        return Err(InvalidInputReasonV0::ColorStackUnbalanced);
     }
-    Some(body)
+    state.body.fonts = state.font_table.fonts;
+    Ok(Some(state.body))
 }
@@ -0,0 +1,208 @@
+use super::super::bib_v0::BibStyleV0;
+use super::super::ok_args_v0::{consume_group_bytes_v0, consume_group_literal, skip_spaces};
+use super::super::ok_cite_v0::{find_bibcite_v0, split_cite_keys_v0, AuxBibciteV0};
+use super::super::ok_dimen_v0::OkLayoutV0;
+use super::super::ok_label_v0::{OkAuxFilesV0, OkUndefinedRefV0};
+use super::super::ok_list_v0::{consume_item_label_v0, ListKindV0};
+use super::super::ok_toc_v0::text_width_sp_v0;
+use super::{consume_body_v0, OkBodyStateV0};
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+/// Whether `index` holds a command of the citation subset: `\cite`, `\nocite`,
+/// `\bibliographystyle`, `\bibliography`, `\bibitem`, `\newblock` or an end of
+/// `thebibliography`.
+pub(super) fn is_bib_command_v0(tokens: &[TokenV0], index: usize) -> bool {
+    let Some(TokenV0::ControlSeq(name)) = tokens.get(index) else {
+        return false;
+    };
+    match name.as_slice() {
+        b"cite" | b"nocite" | b"bibliographystyle" | b"bibliography" | b"bibitem" | b"newblock" => {
+            true
+        }
+        b"begin" | b"end" => consume_group_literal(tokens, index + 1, b"thebibliography").is_some(),
+        _ => false,
+    }
+}
+
+impl OkBodyStateV0 {
+    /// `\bibliographystyle{style}` argument at `index`, one of the standard styles.
+    pub(super) fn consume_bibliography_style_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+    ) -> Option<usize> {
+        let (style, index) = consume_group_bytes_v0(tokens, index)?;
+        BibStyleV0::from_name_v0(&style)?;
+        self.body.citations.style = Some(style);
+        Some(index)
+    }
+
+    /// The command at `index`, which `is_bib_command_v0` accepts. `\newblock`
+    /// only separates the blocks of a `\bibitem`.
+    pub(super) fn push_bib_command_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+        layout: &OkLayoutV0,
+        aux: &OkAuxFilesV0,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        let Some(TokenV0::ControlSeq(name)) = tokens.get(index) else {
+            return Ok(None);
+        };
+        match name.as_slice() {
+            b"cite" => self.push_cite_v0(tokens, index + 1, aux),
+            b"nocite" => {
+                let Some((keys, index)) = consume_group_bytes_v0(tokens, index + 1) else {
+                    return Ok(None);
+                };
+                self.body.citations.keys.extend(split_cite_keys_v0(&keys));
+                Ok(Some(index))
+            }
+            b"bibliographystyle" => Ok(self.consume_bibliography_style_v0(tokens, index + 1)),
+            b"bibliography" => self.push_bibliography_v0(tokens, index + 1, layout, aux),
+            b"bibitem" => self.push_bibitem_v0(tokens, index + 1, layout),
+            b"newblock" => Ok(Some(index + 1)),
+            b"begin" => {
+                let Some(index) = consume_group_literal(tokens, index + 1, b"thebibliography")
+                else {
+                    return Ok(None);
+                };
+                let Some((widest, index)) = consume_group_bytes_v0(tokens, index) else {
+                    return Ok(None);
+                };
+                self.push_top_heading_v0(self.class.class.bibliography_name_v0())?;
+                self.open_group_v0(0);
+                let label_width_sp =
+                    text_width_sp_v0(&[b"[", widest.as_slice(), b"]"].concat(), layout.em_sp);
+                self.lists.begin_bibliography_v0(
+                    label_width_sp,
+                    layout.em_sp,
+                    self.groups.len(),
+                )?;
+                self.end_line_v0();
+                Ok(Some(index))
+            }
+            _ => {
+                let Some(index) = consume_group_literal(tokens, index + 1, b"thebibliography")
+                else {
+                    return Ok(None);
+                };
+                self.list_environment_v0(false, ListKindV0::Bibliography, layout)?;
+                Ok(Some(index))
+            }
+        }
+    }
+
+    /// `\cite[note]{keys}`: the labels of the previous pass's `\bibcite`s in
+    /// brackets, a bold `?` for keys without one, then the note.
+    fn push_cite_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+        aux: &OkAuxFilesV0,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        if self.lists.awaits_item_v0() {
+            return Err(InvalidInputReasonV0::ListItemMissing);
+        }
+        let Some((note, index)) = consume_item_label_v0(tokens, index) else {
+            return Ok(None);
+        };
+        let Some((keys, index)) = consume_group_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        let keys = split_cite_keys_v0(&keys);
+        if keys.is_empty() {
+            return Ok(None);
+        }
+        self.push_text_v0(b"[")?;
+        for (position, key) in keys.iter().enumerate() {
+            if position > 0 {
+                self.push_text_v0(b", ")?;
+            }
+            if let Some(bibcite) = find_bibcite_v0(&aux.bibcites, key) {
+                self.push_text_v0(&bibcite.label)?;
+                continue;
+            }
+            self.body.undefined_refs.push(OkUndefinedRefV0 {
+                key: key.clone(),
+                offset: self.anchor_offset_v0(),
+                citation: true,
+            });
+            let font = self.font;
+            self.font = font.bold_v0();
+            self.push_text_v0(b"?")?;
+            self.font = font;
+        }
+        if let Some(note) = note {
+            self.push_text_v0(b", ")?;
+            self.push_text_v0(&note)?;
+        }
+        self.push_text_v0(b"]")?;
+        self.body.citations.keys.extend(keys);
+        self.previous_was_space = false;
+        Ok(Some(index))
+    }
+
+    /// `\bibliography{names}` reads in the `.bbl` file written for the document,
+    /// which must hold body material only.
+    fn push_bibliography_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+        layout: &OkLayoutV0,
+        aux: &OkAuxFilesV0,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        let Some((data, index)) = consume_group_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        let Some(bibliography) = self.bibliography.take() else {
+            return Ok(None);
+        };
+        let bibliography = bibliography?;
+        self.body.citations.data = Some(data);
+        let end = consume_body_v0(&bibliography.tokens, 0, self, layout, aux)?;
+        if end.map(|end| skip_spaces(&bibliography.tokens, end)) != Some(bibliography.tokens.len())
+        {
+            return Ok(None);
+        }
+        Ok(Some(index))
+    }
+
+    /// `\bibitem[label]{key}`: an item of `thebibliography` labelled `[label]`, or
+    /// numbered, whose label the `.aux` file records for `\cite`.
+    fn push_bibitem_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+        layout: &OkLayoutV0,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        let Some((label, index)) = consume_item_label_v0(tokens, index) else {
+            return Ok(None);
+        };
+        let Some((key, index)) = consume_group_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        let item_label = label
+            .as_ref()
+            .map(|label| [b"[", label.as_slice(), b"]"].concat());
+        let item = self.lists.item_v0(item_label, layout.em_sp)?;
+        let Some(cite_label) = label.or(item.reference.clone()) else {
+            return Ok(None);
+        };
+        if let Some(reference) = &item.reference {
+            self.current_label = reference.clone();
+        }
+        self.push_indent_v0(
+            item.left_sp,
+            &item.label,
+            item.label_sep_sp,
+            item.label_h_sp,
+        )?;
+        self.body.citations.bibcites.push(AuxBibciteV0 {
+            key,
+            label: cite_label,
+        });
+        Ok(Some(index))
+    }
+}
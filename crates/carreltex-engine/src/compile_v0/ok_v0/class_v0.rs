@@ -0,0 +1,236 @@
+use super::super::ok_args_v0::{consume_group_bytes_v0, consume_group_literal};
+use super::super::ok_class_v0::{consume_letter_lines_v0, DocumentClassV0};
+use super::super::ok_dimen_v0::OkLayoutV0;
+use super::super::ok_font_v0::FontSizeV0;
+use super::super::ok_page_v0::{PageCommandV0, PageNumberingV0, PageStyleV0};
+use super::super::ok_toc_v0::text_width_sp_v0;
+use super::OkBodyStateV0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+/// Empty lines between a letter's closing and its signature, for the
+/// `6\medskipamount` of `\closing`.
+const CLOSING_SKIP_LINES_V0: usize = 3;
+
+/// Whether `index` holds a body command of `class`: book's `\frontmatter`,
+/// `\mainmatter` and `\backmatter`, letter's `letter` environment, `\opening` and
+/// `\closing`, or beamer's `frame` environment and `\frametitle`.
+pub(super) fn is_class_command_v0(
+    tokens: &[TokenV0],
+    index: usize,
+    class: DocumentClassV0,
+) -> bool {
+    let Some(TokenV0::ControlSeq(name)) = tokens.get(index) else {
+        return false;
+    };
+    let environment = match class {
+        DocumentClassV0::Letter => b"letter".as_slice(),
+        DocumentClassV0::Beamer => b"frame",
+        _ => b"",
+    };
+    match (class, name.as_slice()) {
+        (DocumentClassV0::Book, b"frontmatter" | b"mainmatter" | b"backmatter") => true,
+        (DocumentClassV0::Letter, b"opening" | b"closing") => true,
+        (DocumentClassV0::Beamer, b"frametitle") => true,
+        (DocumentClassV0::Letter | DocumentClassV0::Beamer, b"begin" | b"end") => {
+            consume_group_literal(tokens, index + 1, environment).is_some()
+        }
+        _ => false,
+    }
+}
+
+impl OkBodyStateV0 {
+    /// The command at `index`, which `is_class_command_v0` accepts; footnote and
+    /// float bodies and lists may not hold one.
+    pub(super) fn push_class_command_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+        layout: &OkLayoutV0,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        if self.footnotes.anchor.is_some()
+            || self.floats.current.is_some()
+            || !self.lists.is_empty_v0()
+        {
+            return Ok(None);
+        }
+        let Some(TokenV0::ControlSeq(name)) = tokens.get(index) else {
+            return Ok(None);
+        };
+        match name.as_slice() {
+            b"frontmatter" | b"mainmatter" | b"backmatter" => {
+                self.new_page_v0();
+                let numbering = match name.as_slice() {
+                    b"frontmatter" => Some(PageNumberingV0::Roman),
+                    b"mainmatter" => Some(PageNumberingV0::Arabic),
+                    _ => None,
+                };
+                if let Some(numbering) = numbering {
+                    self.push_page_style_v0(PageCommandV0::Numbering(numbering));
+                }
+                self.main_matter = name.as_slice() == b"mainmatter";
+                Ok(Some(index + 1))
+            }
+            b"begin" => self.begin_class_environment_v0(tokens, index + 1),
+            b"end" => {
+                let Some((_, index)) = consume_group_bytes_v0(tokens, index + 1) else {
+                    return Ok(None);
+                };
+                if self.class_environment != Some(self.groups.len()) {
+                    return Ok(None);
+                }
+                self.end_paragraph_v0();
+                self.close_group_v0()?;
+                self.class_environment = None;
+                Ok(Some(index))
+            }
+            b"opening" => self.push_opening_v0(tokens, index + 1, layout),
+            b"closing" => self.push_closing_v0(tokens, index + 1, layout),
+            _ => {
+                if self.class_environment.is_none() {
+                    return Ok(None);
+                }
+                self.push_frame_title_v0(tokens, index + 1)
+            }
+        }
+    }
+
+    /// `\begin{letter}{recipient}` or `\begin{frame}`, with an optional `{title}`:
+    /// a group on a new page; letters restart the page count.
+    fn begin_class_environment_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        let Some((environment, mut index)) = consume_group_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        if self.class_environment.is_some() {
+            return Ok(None);
+        }
+        let letter = environment == b"letter";
+        if letter {
+            let Some((recipient, next_index)) = consume_letter_lines_v0(tokens, index) else {
+                return Ok(None);
+            };
+            self.letter.recipient = recipient;
+            index = next_index;
+        } else if matches!(tokens.get(index), Some(TokenV0::Char(b'['))) {
+            return Ok(None);
+        }
+        self.new_page_v0();
+        if letter {
+            self.push_page_style_v0(PageCommandV0::Numbering(PageNumberingV0::Arabic));
+        }
+        self.open_group_v0(0);
+        self.class_environment = Some(self.groups.len());
+        if !letter && matches!(tokens.get(index), Some(TokenV0::BeginGroup)) {
+            return self.push_frame_title_v0(tokens, index);
+        }
+        Ok(Some(index))
+    }
+
+    /// Each of `lines` on a line of its own.
+    fn push_lines_v0(&mut self, lines: &[Vec<u8>]) -> Result<(), InvalidInputReasonV0> {
+        for line in lines {
+            self.push_text_v0(line)?;
+            self.body.text.push(0x0a);
+        }
+        self.previous_was_space = true;
+        Ok(())
+    }
+
+    /// `\opening{text}` on an `empty` page: the return address and the date as a
+    /// block set to the right, the recipient flush left, then the text, an empty
+    /// line between each.
+    fn push_opening_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+        layout: &OkLayoutV0,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        if self.class_environment.is_none() {
+            return Ok(None);
+        }
+        let Some((opening, index)) = consume_letter_lines_v0(tokens, index) else {
+            return Ok(None);
+        };
+        self.end_paragraph_v0();
+        self.push_page_style_v0(PageCommandV0::ThisPageStyle(PageStyleV0::Empty));
+        let mut head = self.letter.address.clone();
+        if !head.is_empty() && !self.letter.date.is_empty() {
+            head.push(Vec::new());
+        }
+        head.extend(self.letter.date.iter().cloned());
+        // Lines wrap once they hold more glyphs than the margin leaves em, so the
+        // block keeps an em per glyph.
+        let em_sp = self.font.em_sp_v0(layout.em_sp);
+        let widest = head
+            .iter()
+            .map(|line| text_width_sp_v0(line, em_sp).max(line.len() as i32 * layout.em_sp))
+            .max();
+        if let Some(widest) = widest {
+            let left_sp = (layout.text_width_sp - widest).max(0);
+            self.push_indent_v0(left_sp, b"", layout.em_sp / 2, None)?;
+            self.push_lines_v0(&head)?;
+            self.push_indent_v0(0, b"", layout.em_sp / 2, None)?;
+            self.body.text.push(0x0a);
+        }
+        let recipient = std::mem::take(&mut self.letter.recipient);
+        self.push_lines_v0(&recipient)?;
+        self.end_paragraph_v0();
+        self.push_lines_v0(&opening)?;
+        self.end_paragraph_v0();
+        Ok(Some(index))
+    }
+
+    /// `\closing{text}`: the text, then the signature below the space left for
+    /// signing, from the middle of the line when there is a return address.
+    fn push_closing_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+        layout: &OkLayoutV0,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        if self.class_environment.is_none() {
+            return Ok(None);
+        }
+        let Some((closing, index)) = consume_letter_lines_v0(tokens, index) else {
+            return Ok(None);
+        };
+        self.end_paragraph_v0();
+        let left_sp = if self.letter.address.is_empty() {
+            0
+        } else {
+            layout.text_width_sp / 2
+        };
+        self.push_indent_v0(left_sp, b"", layout.em_sp / 2, None)?;
+        self.push_lines_v0(&closing)?;
+        self.body
+            .text
+            .extend_from_slice(&[0x0a; CLOSING_SKIP_LINES_V0]);
+        let signature = self.letter.signature.clone();
+        self.push_lines_v0(&signature)?;
+        self.push_indent_v0(0, b"", layout.em_sp / 2, None)?;
+        Ok(Some(index))
+    }
+
+    /// `\frametitle{title}`, or the title argument of `frame`: the title in
+    /// `\Large` over an empty line.
+    fn push_frame_title_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        let Some((title, index)) = consume_letter_lines_v0(tokens, index) else {
+            return Ok(None);
+        };
+        self.end_paragraph_v0();
+        let font = self.font;
+        self.font = self.class.normal_font_v0().sized_v0(FontSizeV0::Larger);
+        self.push_lines_v0(&title)?;
+        self.font = font;
+        self.body.text.push(0x0a);
+        Ok(Some(index))
+    }
+}
@@ -0,0 +1,118 @@
+use super::super::ok_dimen_v0::OkLayoutV0;
+use super::super::ok_equation_v0::{place_equation_rows_v0, EquationTagV0, EquationV0};
+use super::super::ok_math_layout_v0::{emit_math_v0, typeset_math_v0};
+use super::super::ok_math_v0::MathFormulaV0;
+use super::super::ok_toc_v0::text_width_sp_v0;
+use super::OkBodyStateV0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use carreltex_xdv::{DviTextFillV0, MAX_DVI_TEXT_FILLS_V0};
+
+impl OkBodyStateV0 {
+    /// Inline math continues the text; display math is centred on a line of its own
+    /// between the list margins.
+    pub(super) fn push_math_v0(
+        &mut self,
+        formula: &MathFormulaV0,
+        layout: &OkLayoutV0,
+    ) -> Result<(), InvalidInputReasonV0> {
+        let math = typeset_math_v0(&formula.list, formula.display, self.font, layout.em_sp);
+        let left_sp = self.lists.left_sp_v0();
+        if formula.display {
+            let free_sp = i64::from(layout.text_width_sp) - i64::from(left_sp) - math.width;
+            let indent_sp = i32::try_from(i64::from(left_sp) + (free_sp / 2).max(0))
+                .map_err(|_| InvalidInputReasonV0::DimenInvalid)?;
+            self.push_indent_v0(indent_sp, b"", layout.em_sp / 2, None)?;
+        }
+        emit_math_v0(&math, &mut self.body, &mut self.font_table)?;
+        if formula.display {
+            self.push_indent_v0(left_sp, b"", layout.em_sp / 2, None)?;
+        } else {
+            self.previous_was_space = false;
+        }
+        Ok(())
+    }
+
+    /// `equation` and the amsmath displays, a line for each row. Numbers step the
+    /// `equation` counter, arabic in article, and are set in parentheses flush right
+    /// in the upright text font, as tags are. A row's `\label`s name its number or
+    /// tag, or the last one before it; the environment is a group for `\ref`.
+    pub(super) fn push_equation_v0(
+        &mut self,
+        equation: &EquationV0,
+        layout: &OkLayoutV0,
+    ) -> Result<(), InvalidInputReasonV0> {
+        let left_sp = self.lists.left_sp_v0();
+        let number_font = self.font.normalfont_v0();
+        let number_em_sp = number_font.em_sp_v0(layout.em_sp);
+        let mut rows = Vec::new();
+        let mut numbers = Vec::new();
+        let mut marks = Vec::new();
+        for row in &equation.rows {
+            let reference = match &row.tag {
+                EquationTagV0::None => None,
+                EquationTagV0::Number => {
+                    self.equations += 1;
+                    let number = self.equations.to_string();
+                    Some([self.sections.number_prefix_v0(), number.into_bytes()].concat())
+                }
+                EquationTagV0::Tag(text) | EquationTagV0::TagStar(text) => Some(text.clone()),
+            };
+            let text = match (&row.tag, &reference) {
+                (EquationTagV0::TagStar(text), _) => text.clone(),
+                (_, Some(reference)) => [b"(".as_slice(), reference, b")"].concat(),
+                (_, None) => Vec::new(),
+            };
+            numbers.push(reference.is_some().then(|| {
+                i64::from(layout.text_width_sp) - i64::from(text_width_sp_v0(&text, number_em_sp))
+            }));
+            rows.push(
+                row.cells
+                    .iter()
+                    .map(|cell| typeset_math_v0(cell, true, self.font, layout.em_sp))
+                    .collect(),
+            );
+            marks.push((reference, text));
+        }
+        let placed = place_equation_rows_v0(
+            equation.kind,
+            rows,
+            &numbers,
+            i64::from(left_sp),
+            i64::from(layout.text_width_sp),
+            i64::from(layout.em_sp),
+        );
+        let current_label = self.current_label.clone();
+        for (((math, h), (reference, text)), (row, number_h)) in placed
+            .into_iter()
+            .zip(marks)
+            .zip(equation.rows.iter().zip(numbers))
+        {
+            let indent_sp = i32::try_from(h).map_err(|_| InvalidInputReasonV0::DimenInvalid)?;
+            self.push_indent_v0(indent_sp, b"", layout.em_sp / 2, None)?;
+            if let Some(reference) = reference {
+                self.current_label = reference;
+            }
+            for key in &row.labels {
+                self.push_label_v0(key.clone())?;
+            }
+            emit_math_v0(&math, &mut self.body, &mut self.font_table)?;
+            if let Some(number_h) = number_h {
+                if self.body.fills.len() >= MAX_DVI_TEXT_FILLS_V0 {
+                    return Err(InvalidInputReasonV0::MathCountExceeded);
+                }
+                self.body.fills.push(DviTextFillV0 {
+                    offset: self.body.text.len(),
+                    to_h: i32::try_from(number_h)
+                        .map_err(|_| InvalidInputReasonV0::DimenInvalid)?,
+                    leader_pitch_sp: None,
+                });
+                let font = self.font;
+                self.font = number_font;
+                self.push_text_v0(&text)?;
+                self.font = font;
+            }
+        }
+        self.current_label = current_label;
+        self.push_indent_v0(left_sp, b"", layout.em_sp / 2, None)
+    }
+}
@@ -0,0 +1,239 @@
+use super::super::ok_args_v0::{consume_group_bytes_v0, consume_optional_bytes_v0};
+use super::super::ok_dimen_v0::OkLayoutV0;
+use super::super::ok_float_v0::{
+    consume_float_body_v0, consume_float_placement_v0, float_list_lines_v0, CurrentFloatV0,
+    FloatEntryV0, FloatKindV0, FloatPlacementV0, OkFloatEntryV0, PendingFloatV0,
+};
+use super::super::ok_label_v0::OkAuxFilesV0;
+use super::super::ok_section_v0::heading_text_v0;
+use super::super::ok_toc_v0::text_width_sp_v0;
+use super::super::package_v0::BuiltinPackageV0;
+use super::{consume_body_v0, OkBodyStateV0};
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+use carreltex_xdv::DviTextFloatV0;
+
+impl OkBodyStateV0 {
+    /// `\begin{figure}` or `\begin{table}` at `index`. The body waits for the end of
+    /// the main text, anchored where the environment stands, except with `H`, which
+    /// sets it right there between paragraphs. Floats inside footnotes or other
+    /// floats are outside the subset.
+    pub(super) fn push_float_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+        layout: &OkLayoutV0,
+        aux: &OkAuxFilesV0,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        let Some((env, index)) = consume_group_bytes_v0(tokens, index + 1) else {
+            return Ok(None);
+        };
+        let Some(kind) = FloatKindV0::from_env_name_v0(&env) else {
+            return Ok(None);
+        };
+        let Some((placement, index)) =
+            consume_float_placement_v0(tokens, index, self.loaded_v0(BuiltinPackageV0::Float))?
+        else {
+            return Ok(None);
+        };
+        let Some((body, next_index)) = consume_float_body_v0(tokens, index, &env) else {
+            return Ok(None);
+        };
+        if self.footnotes.anchor.is_some() || self.floats.current.is_some() {
+            return Ok(None);
+        }
+        if self.lists.awaits_item_v0() {
+            return Err(InvalidInputReasonV0::ListItemMissing);
+        }
+        let first_number = self.floats.reserve_numbers_v0(kind, &tokens[body.clone()]);
+        let FloatPlacementV0::Float(placement) = placement else {
+            self.end_paragraph_v0();
+            self.floats.current = Some(CurrentFloatV0 {
+                kind,
+                number_prefix: self.sections.number_prefix_v0(),
+                next_number: first_number,
+                start: self.body.text.len(),
+            });
+            self.open_group_v0(0);
+            let groups = self.groups.len();
+            let end = consume_body_v0(&tokens[..body.end], body.start, self, layout, aux)?;
+            if end != Some(body.end) || self.groups.len() != groups {
+                return Ok(None);
+            }
+            self.close_group_v0()?;
+            self.floats.current = None;
+            self.end_paragraph_v0();
+            return Ok(Some(next_index));
+        };
+        self.floats.defer_v0(PendingFloatV0 {
+            anchor: self.body.text.len(),
+            kind,
+            placement,
+            body,
+            number_prefix: self.sections.number_prefix_v0(),
+            first_number,
+        })?;
+        Ok(Some(next_index))
+    }
+
+    /// `\caption[short]{title}` as article's `\@makecaption` sets it: below what
+    /// comes before it in the float, after the `\abovecaptionskip` line, centred
+    /// when it fits on a line. The short title, or the title, goes to the list of
+    /// figures or tables, and `\label` names the number.
+    pub(super) fn push_caption_v0(
+        &mut self,
+        tokens: &[TokenV0],
+        index: usize,
+        layout: &OkLayoutV0,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        let Some(current) = &mut self.floats.current else {
+            return Err(InvalidInputReasonV0::CaptionOutsideFloat);
+        };
+        let (kind, start) = (current.kind, current.start);
+        let number = [
+            current.number_prefix.as_slice(),
+            current.next_number.to_string().as_bytes(),
+        ]
+        .concat();
+        current.next_number = current.next_number.saturating_add(1);
+        let Some((short_title, index)) = consume_optional_bytes_v0(tokens, index + 1) else {
+            return Ok(None);
+        };
+        let Some((title, index)) = consume_group_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        let Some(list_title) = heading_text_v0(None, short_title.as_ref().unwrap_or(&title)) else {
+            return Ok(None);
+        };
+        let Some(text) = heading_text_v0(Some(&kind.caption_label_v0(&number)), &title) else {
+            return Ok(None);
+        };
+        self.end_line_v0();
+        if self.body.text.len() > start && !self.body.text.ends_with(&[0x0a, 0x0a]) {
+            self.body.text.push(0x0a);
+        }
+        let left_sp = self.lists.left_sp_v0();
+        let free_sp = i64::from(layout.text_width_sp)
+            - i64::from(left_sp)
+            - i64::from(text_width_sp_v0(&text, self.font.em_sp_v0(layout.em_sp)));
+        if free_sp >= 0 {
+            let indent_sp = i32::try_from(i64::from(left_sp) + free_sp / 2)
+                .map_err(|_| InvalidInputReasonV0::DimenInvalid)?;
+            self.push_indent_v0(indent_sp, b"", layout.em_sp / 2, None)?;
+        }
+        self.body.float_entries.push(OkFloatEntryV0 {
+            kind,
+            number: number.clone(),
+            title: list_title,
+            offset: self.body.text.len(),
+        });
+        self.push_text_v0(&text)?;
+        if free_sp >= 0 {
+            self.push_indent_v0(left_sp, b"", layout.em_sp / 2, None)?;
+        } else {
+            self.end_line_v0();
+        }
+        self.current_label = number;
+        Ok(Some(index))
+    }
+
+    /// `\listoffigures` and `\listoftables`: the contents lines of the captions of
+    /// the previous pass under their heading.
+    pub(super) fn push_float_list_v0(
+        &mut self,
+        kind: FloatKindV0,
+        entries: &[FloatEntryV0],
+        layout: &OkLayoutV0,
+    ) -> Result<(), InvalidInputReasonV0> {
+        let chapters = self.class.class.has_chapters_v0();
+        let lines = float_list_lines_v0(entries, kind, chapters, self.font, layout);
+        self.push_contents_v0(kind.list_heading_v0(), &lines, layout)
+    }
+
+    /// Drops the newline ending a float body, and the margin anchored after it,
+    /// unless another mark is anchored there.
+    fn trim_float_end_v0(&mut self, start: usize) {
+        let len = self.body.text.len();
+        if len <= start || self.body.text.last() != Some(&0x0a) {
+            return;
+        }
+        let anchored = self
+            .body
+            .specials
+            .last()
+            .is_some_and(|mark| mark.offset == len)
+            || self
+                .body
+                .rules
+                .last()
+                .is_some_and(|mark| mark.offset == len)
+            || self
+                .body
+                .fills
+                .last()
+                .is_some_and(|mark| mark.offset == len)
+            || self
+                .body
+                .kerns
+                .last()
+                .is_some_and(|mark| mark.offset == len);
+        let indented = self
+            .body
+            .indents
+            .last()
+            .is_some_and(|indent| indent.offset == len && indent.label_len == 0);
+        if !anchored {
+            if indented {
+                self.body.indents.pop();
+            }
+            self.body.text.pop();
+        }
+    }
+}
+
+/// Float bodies after the main text, in the order of their anchors, each from the
+/// left margin in the normal font.
+pub(super) fn push_float_bodies_v0(
+    tokens: &[TokenV0],
+    state: &mut OkBodyStateV0,
+    layout: &OkLayoutV0,
+    aux: &OkAuxFilesV0,
+) -> Result<Option<()>, InvalidInputReasonV0> {
+    for float in std::mem::take(&mut state.floats.pending) {
+        state.body.text.push(0x0a);
+        let start = state.body.text.len();
+        state.floats.current = Some(CurrentFloatV0 {
+            kind: float.kind,
+            number_prefix: float.number_prefix,
+            next_number: float.first_number,
+            start,
+        });
+        state.current_label = Vec::new();
+        state.open_group_v0(0);
+        state.font = state.class.normal_font_v0();
+        state.previous_was_space = true;
+        state.push_indent_v0(0, b"", layout.em_sp / 2, None)?;
+        let body = float.body;
+        let Some(end) = consume_body_v0(&tokens[..body.end], body.start, state, layout, aux)?
+        else {
+            return Ok(None);
+        };
+        if !state.lists.is_empty_v0() {
+            return Err(InvalidInputReasonV0::ListEnvironmentUnbalanced);
+        }
+        if end != body.end || state.groups.len() != 1 {
+            return Ok(None);
+        }
+        state.close_group_v0()?;
+        state.trim_trailing_space_v0();
+        state.trim_float_end_v0(start);
+        state.body.floats.push(DviTextFloatV0 {
+            anchor: float.anchor,
+            start,
+            end: state.body.text.len(),
+            placement: float.placement,
+        });
+        state.floats.current = None;
+    }
+    Ok(Some(()))
+}
@@ -0,0 +1,52 @@
+use super::super::ok_dimen_v0::OkLayoutV0;
+use super::super::ok_font_v0::FontSizeV0;
+use super::super::ok_footnote_v0::footnote_mark_h_sp_v0;
+use super::super::ok_label_v0::OkAuxFilesV0;
+use super::{consume_body_v0, OkBodyStateV0};
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+use carreltex_xdv::DviTextFootnoteV0;
+
+/// Footnote bodies after the main text, in the order of their marks: each starts a
+/// line of its own in `\footnotesize`, behind its mark set as `\@makefntext` does.
+pub(super) fn push_footnote_bodies_v0(
+    tokens: &[TokenV0],
+    state: &mut OkBodyStateV0,
+    layout: &OkLayoutV0,
+    aux: &OkAuxFilesV0,
+) -> Result<Option<()>, InvalidInputReasonV0> {
+    for footnote in std::mem::take(&mut state.footnotes.pending) {
+        state.body.text.push(0x0a);
+        let start = state.body.text.len();
+        state.footnotes.anchor = Some(footnote.anchor);
+        state.current_label = footnote.number.clone();
+        state.open_group_v0(0);
+        let font = state
+            .class
+            .normal_font_v0()
+            .sized_v0(FontSizeV0::FootnoteSize);
+        state.font = font;
+        let mark_h_sp = footnote_mark_h_sp_v0(font, &footnote.number, layout);
+        state.push_footnote_mark_v0(&footnote.number, layout, Some(mark_h_sp))?;
+        let body = footnote.body;
+        let Some(end) = consume_body_v0(&tokens[..body.end], body.start, state, layout, aux)?
+        else {
+            return Ok(None);
+        };
+        if !state.lists.is_empty_v0() {
+            return Err(InvalidInputReasonV0::ListEnvironmentUnbalanced);
+        }
+        if end != body.end || state.groups.len() != 1 {
+            return Ok(None);
+        }
+        state.close_group_v0()?;
+        state.trim_trailing_space_v0();
+        state.body.footnotes.push(DviTextFootnoteV0 {
+            anchor: footnote.anchor,
+            start,
+            end: state.body.text.len(),
+        });
+        state.footnotes.anchor = None;
+    }
+    Ok(Some(()))
+}
@@ -0,0 +1,44 @@
+use super::super::ok_dimen_v0::OkLayoutV0;
+use super::super::ok_graphics_v0::OkGraphicV0;
+use super::super::ok_math_layout_v0::push_kern_v0;
+use super::OkBodyStateV0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use carreltex_xdv::image_special_v0;
+
+impl OkBodyStateV0 {
+    /// `\includegraphics`: the image stands on the baseline at the start of a line
+    /// of its own, after as many empty lines as its height takes, and the text
+    /// continues after it on that line. A line holding only an image so far counts
+    /// as taken.
+    pub(super) fn push_graphic_v0(
+        &mut self,
+        graphic: &OkGraphicV0,
+        layout: &OkLayoutV0,
+    ) -> Result<(), InvalidInputReasonV0> {
+        if self.lists.awaits_item_v0() {
+            return Err(InvalidInputReasonV0::ListItemMissing);
+        }
+        self.end_line_v0();
+        let len = self.body.text.len();
+        if self
+            .body
+            .kerns
+            .last()
+            .is_some_and(|kern| kern.offset == len)
+        {
+            self.body.text.push(0x0a);
+        }
+        let empty_lines = (graphic.height - 1) / layout.line_advance_sp.max(1);
+        for _ in 0..empty_lines {
+            self.body.text.push(0x0a);
+        }
+        self.push_special_v0(image_special_v0(
+            graphic.width,
+            graphic.height,
+            &graphic.path,
+        ))?;
+        push_kern_v0(&mut self.body, graphic.width)?;
+        self.previous_was_space = false;
+        Ok(())
+    }
+}
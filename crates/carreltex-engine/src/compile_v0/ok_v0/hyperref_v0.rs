@@ -0,0 +1,42 @@
+use super::super::ok_args_v0::consume_group_bytes_v0;
+use super::{is_supported_ok_char_v0, OkBodyStateV0};
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+impl OkBodyStateV0 {
+    /// hyperref's `\url{address}`, set in `\ttfamily`, or `\href{address}` before
+    /// its `{text}`, which opens as an ordinary group. The text carries no link.
+    pub(super) fn push_hyperref_v0(
+        &mut self,
+        name: &[u8],
+        tokens: &[TokenV0],
+        index: usize,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        let Some((address, index)) = consume_group_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        if address.is_empty()
+            || !address
+                .iter()
+                .all(|byte| *byte != b' ' && is_supported_ok_char_v0(*byte))
+        {
+            return Ok(None);
+        }
+        if self.lists.awaits_item_v0() {
+            return Err(InvalidInputReasonV0::ListItemMissing);
+        }
+        if name == b"href" {
+            if !matches!(tokens.get(index), Some(TokenV0::BeginGroup)) {
+                return Ok(None);
+            }
+            self.open_group_v0(0);
+            return Ok(Some(index + 1));
+        }
+        let font = self.font;
+        self.font = font.typewriter_v0();
+        self.push_text_v0(&address)?;
+        self.font = font;
+        self.previous_was_space = false;
+        Ok(Some(index))
+    }
+}
@@ -0,0 +1,142 @@
+use super::super::ok_args_v0::{
+    consume_group_bytes_v0, consume_optional_bytes_v0, is_control_seq_v0, skip_spaces,
+};
+use super::super::ok_class_v0::{consume_letter_lines_v0, DocumentClassV0};
+use super::super::ok_color_v0::parse_color_spec_v0;
+use super::super::ok_dimen_v0::OkLayoutV0;
+use super::super::ok_geometry_v0::{apply_geometry_options_v0, consume_setlength_v0};
+use super::super::ok_page_v0::PageCommandV0;
+use super::super::package_v0::BuiltinPackageV0;
+use super::OkBodyStateV0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+impl OkBodyStateV0 {
+    /// The lines `\address`, `\signature` or `\date` at `index` sets, in letters.
+    fn letter_field_v0(&mut self, tokens: &[TokenV0], index: usize) -> Option<&mut Vec<Vec<u8>>> {
+        let Some(TokenV0::ControlSeq(name)) = tokens.get(index) else {
+            return None;
+        };
+        if self.class.class != DocumentClassV0::Letter {
+            return None;
+        }
+        match name.as_slice() {
+            b"address" => Some(&mut self.letter.address),
+            b"signature" => Some(&mut self.letter.signature),
+            b"date" => Some(&mut self.letter.date),
+            _ => None,
+        }
+    }
+}
+
+/// Preamble subset: `\usepackage` of a built-in package, with options only for
+/// `geometry`, once `xcolor` / `color` is loaded `\definecolor{name}{model}{spec}`,
+/// the page commands, `\bibliographystyle`, the page geometry: `geometry`'s
+/// options, then `\geometry{options}`, and `\setlength` of the geometry lengths,
+/// the `\ProvidesPackage` and `\NeedsTeXFormat` of mounted package files, and in
+/// letters `\address`, `\signature` and `\date`.
+pub(super) fn consume_preamble_v0(
+    tokens: &[TokenV0],
+    mut index: usize,
+    state: &mut OkBodyStateV0,
+    layout: &mut OkLayoutV0,
+) -> Result<Option<usize>, InvalidInputReasonV0> {
+    loop {
+        index = skip_spaces(tokens, index);
+        if is_control_seq_v0(tokens.get(index), b"usepackage") {
+            let Some((options, next_index)) = consume_optional_bytes_v0(tokens, index + 1) else {
+                return Ok(None);
+            };
+            let Some((name, next_index)) = consume_group_bytes_v0(tokens, next_index) else {
+                return Ok(None);
+            };
+            let Some(package) = BuiltinPackageV0::from_name_v0(&name) else {
+                return Ok(None);
+            };
+            if package == BuiltinPackageV0::Geometry {
+                let options = options.unwrap_or_default();
+                if apply_geometry_options_v0(&options, layout)?.is_none() {
+                    return Ok(None);
+                }
+            } else if options.is_some() {
+                return Ok(None);
+            }
+            if !state.loaded_v0(package) {
+                state.packages.push(package);
+            }
+            index = next_index;
+        } else if is_control_seq_v0(tokens.get(index), b"geometry")
+            && state.loaded_v0(BuiltinPackageV0::Geometry)
+        {
+            let Some((options, next_index)) = consume_group_bytes_v0(tokens, index + 1) else {
+                return Ok(None);
+            };
+            if apply_geometry_options_v0(&options, layout)?.is_none() {
+                return Ok(None);
+            }
+            index = next_index;
+        } else if is_control_seq_v0(tokens.get(index), b"ProvidesPackage")
+            || is_control_seq_v0(tokens.get(index), b"NeedsTeXFormat")
+        {
+            let Some(next_index) = consume_package_info_v0(tokens, index + 1) else {
+                return Ok(None);
+            };
+            index = next_index;
+        } else if is_control_seq_v0(tokens.get(index), b"setlength") {
+            let Some(next_index) = consume_setlength_v0(tokens, index + 1, layout)? else {
+                return Ok(None);
+            };
+            index = next_index;
+        } else if is_control_seq_v0(tokens.get(index), b"definecolor")
+            && state.loaded_v0(BuiltinPackageV0::Color)
+        {
+            let mut arguments = Vec::<Vec<u8>>::new();
+            index += 1;
+            for _ in 0..3 {
+                let Some((argument, next_index)) = consume_group_bytes_v0(tokens, index) else {
+                    return Ok(None);
+                };
+                arguments.push(argument);
+                index = next_index;
+            }
+            let color = parse_color_spec_v0(&arguments[1], &arguments[2])?;
+            state.colors.define_v0(&arguments[0], color)?;
+        } else if matches!(tokens.get(index), Some(TokenV0::ControlSeq(name)) if PageCommandV0::is_page_command_v0(name))
+        {
+            let Some(next_index) = state.push_page_command_v0(tokens, index)? else {
+                return Ok(None);
+            };
+            index = next_index;
+        } else if let Some(lines) = state.letter_field_v0(tokens, index) {
+            let Some((value, next_index)) = consume_letter_lines_v0(tokens, index + 1) else {
+                return Ok(None);
+            };
+            *lines = value;
+            index = next_index;
+        } else if is_control_seq_v0(tokens.get(index), b"bibliographystyle") {
+            let Some(next_index) = state.consume_bibliography_style_v0(tokens, index + 1) else {
+                return Ok(None);
+            };
+            index = next_index;
+        } else {
+            return Ok(Some(index));
+        }
+    }
+}
+
+/// The `{name}[info]` of `\ProvidesPackage` or `\NeedsTeXFormat`, whose optional
+/// release information may hold spaces.
+fn consume_package_info_v0(tokens: &[TokenV0], index: usize) -> Option<usize> {
+    let (_, mut index) = consume_group_bytes_v0(tokens, index)?;
+    if !matches!(tokens.get(index), Some(TokenV0::Char(b'['))) {
+        return Some(index);
+    }
+    loop {
+        index += 1;
+        match tokens.get(index)? {
+            TokenV0::Char(b']') => return Some(index + 1),
+            TokenV0::Char(_) | TokenV0::Space => {}
+            _ => return None,
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use super::super::ok_args_v0::{consume_group_bytes_v0, consume_optional_bytes_v0};
+use super::super::ok_font_v0::FontSizeV0;
+use super::super::ok_page_v0::{OkPageCommandV0, PageCommandV0, PageStyleV0};
+use super::super::ok_section_v0::{heading_text_v0, SectionLevelV0};
+use super::super::ok_toc_v0::OkTocEntryV0;
+use super::OkBodyStateV0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::TokenV0;
+
+impl OkBodyStateV0 {
+    /// `\chapter`, `\section` and below, starred or not, with their `[short]{title}`
+    /// arguments at `index`. Numbered headings step their counter and name
+    /// `\label`s; unstarred ones go to the contents. Chapters are unnumbered outside
+    /// book's main matter and restart the equation, float and footnote counters;
+    /// footnote and float bodies may not hold one.
+    pub(super) fn push_section_v0(
+        &mut self,
+        level: SectionLevelV0,
+        tokens: &[TokenV0],
+        mut index: usize,
+    ) -> Result<Option<usize>, InvalidInputReasonV0> {
+        let chapter = level == SectionLevelV0::Chapter;
+        if chapter && (self.footnotes.anchor.is_some() || self.floats.current.is_some()) {
+            return Ok(None);
+        }
+        let starred = matches!(tokens.get(index), Some(TokenV0::Char(b'*')));
+        if starred {
+            index += 1;
+        }
+        let Some((short_title, index)) = consume_optional_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        let Some((title, index)) = consume_group_bytes_v0(tokens, index) else {
+            return Ok(None);
+        };
+        let Some(toc_title) = heading_text_v0(None, short_title.as_ref().unwrap_or(&title)) else {
+            return Ok(None);
+        };
+        let number = if starred || (chapter && !self.main_matter) {
+            None
+        } else {
+            self.sections.step_v0(level)
+        };
+        let offset = if chapter {
+            let Some(title) = heading_text_v0(None, &title) else {
+                return Ok(None);
+            };
+            if number.is_some() {
+                self.equations = 0;
+                self.floats.reset_counters_v0();
+                self.footnotes.reset_counter_v0();
+            }
+            self.push_chapter_heading_v0(number.as_deref(), &title)?
+        } else {
+            let Some(text) = heading_text_v0(number.as_deref(), &title) else {
+                return Ok(None);
+            };
+            self.push_heading_v0(level, &text)?
+        };
+        if !starred {
+            self.body.toc_entries.push(OkTocEntryV0 {
+                level,
+                number: number.clone(),
+                title: toc_title,
+                offset,
+            });
+        }
+        if let Some(number) = number {
+            self.current_label = number;
+        }
+        Ok(Some(index))
+    }
+
+    /// Ends the current line and starts a new page, unless the text is at the top
+    /// of one. The line end becomes the page break, so offsets anchored after it
+    /// stay where they are.
+    pub(super) fn new_page_v0(&mut self) {
+        self.end_line_v0();
+        if let Some(last) = self.body.text.last_mut() {
+            if *last == 0x0a {
+                *last = 0x0c;
+            }
+        }
+    }
+
+    /// A page command for the page holding the current offset.
+    pub(super) fn push_page_style_v0(&mut self, command: PageCommandV0) {
+        self.body.page_commands.push(OkPageCommandV0 {
+            command,
+            offset: self.body.text.len(),
+        });
+    }
+
+    /// Report's and book's chapter head on a new `plain` page: `Chapter n` in
+    /// `\huge\bfseries` when numbered, then the title in `\Huge\bfseries`, each
+    /// followed by an empty line. Returns the offset of the head.
+    fn push_chapter_heading_v0(
+        &mut self,
+        number: Option<&[u8]>,
+        title: &[u8],
+    ) -> Result<usize, InvalidInputReasonV0> {
+        self.new_page_v0();
+        self.push_page_style_v0(PageCommandV0::ThisPageStyle(PageStyleV0::Plain));
+        let offset = self.body.text.len();
+        let font = self.font;
+        if let Some(number) = number {
+            self.font = SectionLevelV0::Chapter
+                .font_v0(self.class.normal_font_v0())
+                .sized_v0(FontSizeV0::Huge);
+            self.push_text_v0(&[b"Chapter ", number].concat())?;
+            self.body.text.extend_from_slice(&[0x0a, 0x0a]);
+        }
+        self.font = SectionLevelV0::Chapter.font_v0(self.class.normal_font_v0());
+        self.push_text_v0(title)?;
+        self.font = font;
+        self.body.text.extend_from_slice(&[0x0a, 0x0a]);
+        self.previous_was_space = true;
+        Ok(offset)
+    }
+
+    /// The unnumbered heading of the contents, the lists of floats and the
+    /// bibliography: a `\chapter*` in classes with chapters, else a `\section*`.
+    pub(super) fn push_top_heading_v0(&mut self, text: &[u8]) -> Result<(), InvalidInputReasonV0> {
+        if self.class.class.has_chapters_v0() {
+            self.push_chapter_heading_v0(None, text)?;
+        } else {
+            self.push_heading_v0(SectionLevelV0::Section, text)?;
+        }
+        Ok(())
+    }
+}
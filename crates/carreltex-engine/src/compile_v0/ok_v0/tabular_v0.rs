@@ -0,0 +1,65 @@
+use super::super::ok_dimen_v0::OkLayoutV0;
+use super::super::ok_math_layout_v0::{dimen_v0, emit_math_v0, push_kern_v0};
+use super::super::ok_tabular_v0::{CellPieceV0, TabularMarkV0, TabularV0};
+use super::OkBodyStateV0;
+use crate::reasons_v0::InvalidInputReasonV0;
+use carreltex_xdv::{DviTextRuleV0, MAX_DVI_TEXT_RULES_V0};
+
+impl OkBodyStateV0 {
+    /// A `tabular` sets its lines from the list margin on lines of their own, each
+    /// mark reached with a kern from the one before it.
+    pub(super) fn push_tabular_v0(
+        &mut self,
+        tabular: &TabularV0,
+        layout: &OkLayoutV0,
+    ) -> Result<(), InvalidInputReasonV0> {
+        let font = self.font;
+        self.end_line_v0();
+        for line in tabular.lines_v0(layout) {
+            let mut h = 0;
+            for (mark_h, mark) in line {
+                push_kern_v0(&mut self.body, dimen_v0(mark_h - h)?)?;
+                h = mark_h;
+                match mark {
+                    TabularMarkV0::Rule {
+                        width,
+                        height,
+                        raise,
+                    } => {
+                        if self.body.rules.len() >= MAX_DVI_TEXT_RULES_V0 {
+                            return Err(InvalidInputReasonV0::RuleCountExceeded);
+                        }
+                        self.body.rules.push(DviTextRuleV0 {
+                            offset: self.body.text.len(),
+                            special_index: self.body.specials.len(),
+                            width: dimen_v0(width)?,
+                            height: dimen_v0(height)?,
+                            raise: dimen_v0(raise)?,
+                            advance: false,
+                        });
+                    }
+                    TabularMarkV0::Piece(piece) => {
+                        h += piece.width_v0(layout);
+                        match piece {
+                            CellPieceV0::Text(piece_font, bytes) => {
+                                self.font = piece_font;
+                                self.push_text_v0(&bytes)?;
+                            }
+                            CellPieceV0::Space(piece_font) => {
+                                self.font = piece_font;
+                                self.push_text_v0(b" ")?;
+                            }
+                            CellPieceV0::Math(math) => {
+                                emit_math_v0(&math, &mut self.body, &mut self.font_table)?
+                            }
+                        }
+                    }
+                }
+            }
+            self.body.text.push(0x0a);
+        }
+        self.font = font;
+        self.previous_was_space = true;
+        Ok(())
+    }
+}
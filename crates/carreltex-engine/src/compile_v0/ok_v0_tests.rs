@@ -207,7 +207,7 @@ fn ok_multichar_newline_control_word_uses_reset_sequence() {
 #[test]
 fn ok_wi_dot_uses_scaled_per_glyph_widths() {
     let mut mount = Mount::default();
-    let main = b"\\documentclass{article}\\begin{document}Wi.\\end{document}";
+    let main = b"\\documentclass{article}\\pagestyle{empty}\\begin{document}Wi.\\end{document}";
     assert!(mount.add_file(b"main.tex", main).is_ok());
     let result = compile_request_v0(&mut mount, &valid_request());
     assert_eq!(result.status, CompileStatus::Ok);
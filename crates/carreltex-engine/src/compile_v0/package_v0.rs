@@ -0,0 +1,164 @@
+use super::input_expand_v0::MAX_INPUT_DEPTH_V0;
+use super::ok_args_v0::{
+    consume_group_bytes_v0, consume_group_literal, consume_optional_bytes_v0, is_control_seq_v0,
+};
+use crate::reasons_v0::InvalidInputReasonV0;
+use crate::tex::tokenize_v0::{tokenize_at_letter_v0, TokenV0, MAX_TOKENS_V0};
+use carreltex_core::{normalize_path_v0, Mount};
+
+/// Packages the engine implements itself, each as the subset the OK path follows
+/// once `\usepackage` loads it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BuiltinPackageV0 {
+    Amsmath,
+    Color,
+    Enumitem,
+    Float,
+    Geometry,
+    Graphicx,
+    Hyperref,
+}
+
+impl BuiltinPackageV0 {
+    pub(crate) fn from_name_v0(name: &[u8]) -> Option<Self> {
+        match name {
+            b"amsmath" => Some(Self::Amsmath),
+            b"color" | b"xcolor" => Some(Self::Color),
+            b"enumitem" => Some(Self::Enumitem),
+            b"float" => Some(Self::Float),
+            b"geometry" => Some(Self::Geometry),
+            b"graphics" | b"graphicx" => Some(Self::Graphicx),
+            b"hyperref" => Some(Self::Hyperref),
+            _ => None,
+        }
+    }
+}
+
+/// Why the packages of a document could not be loaded.
+pub(crate) enum PackageErrorV0 {
+    /// Neither built in nor mounted as `<name>.sty`.
+    NotAvailable(Vec<u8>),
+    Invalid(InvalidInputReasonV0),
+}
+
+impl From<InvalidInputReasonV0> for PackageErrorV0 {
+    fn from(reason: InvalidInputReasonV0) -> Self {
+        Self::Invalid(reason)
+    }
+}
+
+/// `tokens` with each preamble `\usepackage[options]{names}` and `\RequirePackage`
+/// resolved name by name, and the names loaded, each once, in load order; loading
+/// a package again is a no-op unless it comes with options. A
+/// built-in package is left as a `\usepackage[options]{name}` of its own; a
+/// mounted `<name>.sty` is read in its place like `\input`, with `@` a letter, and
+/// its own `\RequirePackage`s resolved in turn. Options are not passed to package
+/// files, so a mounted package given options is left to fall outside the subset.
+pub(crate) fn expand_packages_v0(
+    tokens: &[TokenV0],
+    mount: &Mount,
+) -> Result<(Vec<TokenV0>, Vec<Vec<u8>>), PackageErrorV0> {
+    let mut out = Vec::<TokenV0>::new();
+    let mut loaded = Vec::<Vec<u8>>::new();
+    expand_packages_inner_v0(tokens, mount, 0, &mut loaded, &mut out)?;
+    Ok((out, loaded))
+}
+
+fn expand_packages_inner_v0(
+    tokens: &[TokenV0],
+    mount: &Mount,
+    depth: usize,
+    loaded: &mut Vec<Vec<u8>>,
+    out: &mut Vec<TokenV0>,
+) -> Result<(), PackageErrorV0> {
+    if depth > MAX_INPUT_DEPTH_V0 {
+        return Err(InvalidInputReasonV0::InputDepthExceeded.into());
+    }
+    let mut index = 0usize;
+    while index < tokens.len() {
+        if is_control_seq_v0(tokens.get(index), b"begin")
+            && consume_group_literal(tokens, index + 1, b"document").is_some()
+        {
+            return extend_tokens_checked_v0(out, &tokens[index..]);
+        }
+        let Some((options_end, names, next_index)) = consume_package_names_v0(tokens, index) else {
+            extend_tokens_checked_v0(out, &tokens[index..index + 1])?;
+            index += 1;
+            continue;
+        };
+        let options = &tokens[index + 1..options_end];
+        for name in names
+            .split(|byte| *byte == b',')
+            .map(|name| name.trim_ascii())
+        {
+            let is_loaded = loaded.iter().any(|loaded| loaded == name);
+            if name.is_empty() || (is_loaded && options.is_empty()) {
+                continue;
+            }
+            let builtin = BuiltinPackageV0::from_name_v0(name).is_some();
+            let package_bytes = if builtin || is_loaded {
+                None
+            } else {
+                Some(mounted_package_v0(mount, name)?)
+            };
+            match package_bytes {
+                Some(bytes) if options.is_empty() => {
+                    loaded.push(name.to_vec());
+                    let package_tokens = tokenize_at_letter_v0(bytes)
+                        .map_err(|_| InvalidInputReasonV0::InputValidationFailed)?;
+                    expand_packages_inner_v0(&package_tokens, mount, depth + 1, loaded, out)?;
+                }
+                _ => {
+                    if builtin && !is_loaded {
+                        loaded.push(name.to_vec());
+                    }
+                    let command = [
+                        vec![TokenV0::ControlSeq(b"usepackage".to_vec())],
+                        options.to_vec(),
+                        vec![TokenV0::BeginGroup],
+                        name.iter().map(|byte| TokenV0::Char(*byte)).collect(),
+                        vec![TokenV0::EndGroup],
+                    ]
+                    .concat();
+                    extend_tokens_checked_v0(out, &command)?;
+                }
+            }
+        }
+        index = next_index;
+    }
+    Ok(())
+}
+
+/// `\usepackage` or `\RequirePackage` at `index`: the end of its `[options]`, its
+/// comma-separated names and the index after it.
+fn consume_package_names_v0(tokens: &[TokenV0], index: usize) -> Option<(usize, Vec<u8>, usize)> {
+    if !is_control_seq_v0(tokens.get(index), b"usepackage")
+        && !is_control_seq_v0(tokens.get(index), b"RequirePackage")
+    {
+        return None;
+    }
+    let (_, options_end) = consume_optional_bytes_v0(tokens, index + 1)?;
+    let (names, next_index) = consume_group_bytes_v0(tokens, options_end)?;
+    Some((options_end, names, next_index))
+}
+
+/// Bytes of the mounted `<name>.sty`.
+fn mounted_package_v0<'a>(mount: &'a Mount, name: &[u8]) -> Result<&'a [u8], PackageErrorV0> {
+    let not_available = || PackageErrorV0::NotAvailable(name.to_vec());
+    let path = normalize_path_v0(&[name, b".sty"].concat()).map_err(|_| not_available())?;
+    match mount.read_file_by_bytes_v0(path.as_bytes()) {
+        Ok(Some(bytes)) => Ok(bytes),
+        _ => Err(not_available()),
+    }
+}
+
+fn extend_tokens_checked_v0(
+    out: &mut Vec<TokenV0>,
+    tokens: &[TokenV0],
+) -> Result<(), PackageErrorV0> {
+    if out.len().saturating_add(tokens.len()) > MAX_TOKENS_V0 {
+        return Err(InvalidInputReasonV0::InputValidationFailed.into());
+    }
+    out.extend_from_slice(tokens);
+    Ok(())
+}
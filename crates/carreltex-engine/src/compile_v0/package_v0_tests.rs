@@ -0,0 +1,195 @@
+use super::compile_request_v0;
+use super::test_text_v0::{read_writer_dvi_v0, spaced_text_v0};
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::{validate_dvi_v2_text_page_v0, DviDocumentV0, DviFontKindV0};
+
+fn request_v0() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+/// An article of `preamble` and `body` over a mount that also holds `files`.
+fn compile_with_files_v0(
+    preamble: &[u8],
+    body: &[u8],
+    files: &[(&[u8], &[u8])],
+) -> CompileResultV0 {
+    let main = [
+        b"\\documentclass{article}\n\\pagestyle{empty}\n".as_slice(),
+        preamble,
+        b"\n\\begin{document}\n",
+        body,
+        b"\n\\end{document}\n",
+    ]
+    .concat();
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    for (path, bytes) in files {
+        assert!(mount.add_file(path, bytes).is_ok());
+    }
+    compile_request_v0(&mut mount, &request_v0())
+}
+
+fn document_v0(result: &CompileResultV0) -> DviDocumentV0 {
+    assert_eq!(
+        result.status,
+        CompileStatus::Ok,
+        "{}",
+        String::from_utf8_lossy(&result.log_bytes)
+    );
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    read_writer_dvi_v0(&result.main_xdv_bytes)
+}
+
+/// Text of the first page, with the gaps between its glyphs.
+fn page_text_v0(document: &DviDocumentV0) -> String {
+    spaced_text_v0(&document.pages[0].glyphs)
+}
+
+#[test]
+fn builtin_packages_load_from_lists_and_are_reported() {
+    let result = compile_with_files_v0(
+        b"\\usepackage{amsmath, xcolor,\n hyperref}\\usepackage[margin=1in]{geometry}\
+\\usepackage{xcolor}",
+        b"\\textcolor{red}{R} \\url{a.b/c_d} \\href{https://a.b}{Link}",
+        &[],
+    );
+    let document = document_v0(&result);
+    assert_eq!(
+        result.report_json,
+        "{\"status\":\"OK\",\"missing_components\":[],\
+\"loaded_packages\":[\"amsmath\",\"xcolor\",\"hyperref\",\"geometry\"]}"
+    );
+    assert_eq!(page_text_v0(&document), " R a.b/c_d Link ");
+    let fonts = document.pages[0]
+        .glyphs
+        .iter()
+        .filter(|glyph| glyph.code == u32::from(b'a') || glyph.code == u32::from(b'L'))
+        .map(|glyph| {
+            let font = document.font_v0(glyph.font_num).expect("defined font");
+            let DviFontKindV0::Tfm(definition) = &font.kind else {
+                panic!("unexpected font {font:?}");
+            };
+            String::from_utf8_lossy(&definition.name).into_owned()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(fonts, ["carreltex-v0-tt-m-n", "carreltex-v0"]);
+
+    // Without packages the report keeps its two keys.
+    let result = compile_with_files_v0(b"", b"x", &[]);
+    assert_eq!(
+        result.report_json,
+        "{\"status\":\"OK\",\"missing_components\":[]}"
+    );
+}
+
+#[test]
+fn enumitem_labels_set_item_labels_and_references() {
+    let result = compile_with_files_v0(
+        b"\\usepackage{enumitem}",
+        b"\\begin{enumerate}[label=Step {\\Roman*:}, nosep]\\item A\\label{a}\\item B\\label{b}\
+\\end{enumerate}\\begin{itemize}[label=--]\\item C\\end{itemize}See \\ref{b}.",
+        &[],
+    );
+    let document = document_v0(&result);
+    assert_eq!(
+        page_text_v0(&document),
+        "Step I: AStep II: B-- CSee Step II:. "
+    );
+
+    for (preamble, body) in [
+        // Without the package an optional argument is text before the first item.
+        (
+            b"".as_slice(),
+            b"\\begin{enumerate}[label=\\arabic*]\\item A\\end{enumerate}".as_slice(),
+        ),
+        (
+            b"\\usepackage{enumitem}",
+            b"\\begin{itemize}[label=\\arabic*]\\item A\\end{itemize}",
+        ),
+        (
+            b"\\usepackage{enumitem}",
+            b"\\begin{enumerate}[start=2]\\item A\\end{enumerate}",
+        ),
+        (
+            b"\\usepackage{enumitem}",
+            b"\\begin{description}[label=x]\\item A\\end{description}",
+        ),
+    ] {
+        let result = compile_with_files_v0(preamble, body, &[]);
+        assert_ne!(
+            result.status,
+            CompileStatus::Ok,
+            "{}",
+            String::from_utf8_lossy(body)
+        );
+    }
+}
+
+#[test]
+fn mounted_packages_are_read_with_at_as_a_letter() {
+    let mine: &[u8] = b"\\NeedsTeXFormat{LaTeX2e}\n\
+\\ProvidesPackage{mine}[2024/01/01 v1.0 Mine]\n\
+\\RequirePackage{xcolor}\\RequirePackage{other}\n";
+    let other: &[u8] = b"\\ProvidesPackage{other}\\RequirePackage{mine}\n";
+    let result = compile_with_files_v0(
+        b"\\usepackage{mine}",
+        b"\\textcolor{blue}{x}",
+        &[(b"mine.sty", mine), (b"other.sty", other)],
+    );
+    document_v0(&result);
+    assert_eq!(
+        result.report_json,
+        "{\"status\":\"OK\",\"missing_components\":[],\
+\"loaded_packages\":[\"mine\",\"xcolor\",\"other\"]}"
+    );
+
+    // `\my@name` is one control word in a package file; its definitions leave the
+    // document outside the OK subset, but valid.
+    let defs: &[u8] = b"\\def\\my@name{Ada}\\def\\name{\\my@name}\n";
+    let result = compile_with_files_v0(b"\\usepackage{defs}", b"\\name", &[(b"defs.sty", defs)]);
+    assert_eq!(result.status, CompileStatus::NotImplemented);
+    let result = compile_with_files_v0(defs, b"\\name", &[]);
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+
+    // Options are not passed to package files.
+    let result = compile_with_files_v0(b"\\usepackage[x]{mine}", b"y", &[(b"mine.sty", mine)]);
+    assert_eq!(result.status, CompileStatus::NotImplemented);
+}
+
+#[test]
+fn unavailable_packages_fail_closed_with_their_name() {
+    let needs_missing: &[u8] = b"\\RequirePackage{missing}";
+    for (preamble, log) in [
+        (
+            b"\\usepackage{amsmath,tikz}".as_slice(),
+            b"INVALID_INPUT: package_not_available:tikz".as_slice(),
+        ),
+        (
+            b"\\usepackage[x]{foo}",
+            b"INVALID_INPUT: package_not_available:foo",
+        ),
+        (
+            b"\\usepackage{needs}",
+            b"INVALID_INPUT: package_not_available:missing",
+        ),
+    ] {
+        let result = compile_with_files_v0(preamble, b"x", &[(b"needs.sty", needs_missing)]);
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(result.log_bytes, log);
+    }
+
+    // Built-in packages other than `geometry` take no options.
+    let result = compile_with_files_v0(b"\\usepackage[colorlinks]{hyperref}", b"x", &[]);
+    assert_eq!(result.status, CompileStatus::NotImplemented);
+    // hyperref's commands need the package.
+    let result = compile_with_files_v0(b"", b"\\url{a.b}", &[]);
+    assert_eq!(result.status, CompileStatus::NotImplemented);
+}
@@ -0,0 +1,204 @@
+use super::compile_request_v0;
+use super::test_text_v0::{first_baseline_v_v0, read_writer_dvi_v0, spaced_text_v0};
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::{
+    glyph_width_sp_v0, validate_dvi_v2_text_page_v0, DviDocumentV0, DviFontKindV0, ONE_INCH_SP_V0,
+};
+
+const LINE_SP: i32 = 786_432;
+const TEXT_WIDTH_SP: i32 = 80 * 65_536;
+/// `\topskip` plus `\headsep` above the first baseline.
+const HEAD_V: i32 = -2_293_760;
+/// Two more lines, then `\footskip`, below the first baseline of a three-line page.
+const FOOT_V: i32 = 2 * LINE_SP + 1_966_080;
+
+fn request_v0(max_lines_per_page: Option<u32>) -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: max_lines_per_page,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_body_v0(body: &[u8], max_lines_per_page: Option<u32>) -> CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\begin{document}\n".to_vec();
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, &request_v0(max_lines_per_page))
+}
+
+fn document_v0(result: &CompileResultV0) -> DviDocumentV0 {
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    read_writer_dvi_v0(&result.main_xdv_bytes)
+}
+
+/// Text of the glyphs on baseline `v`, from the first, of page `page`.
+fn line_text_v0(document: &DviDocumentV0, page: usize, v: i32) -> String {
+    spaced_text_v0(
+        document.pages[page]
+            .glyphs
+            .iter()
+            .filter(|glyph| glyph.v == first_baseline_v_v0() + v),
+    )
+}
+
+fn width_sp_v0(text: &[u8]) -> i32 {
+    text.iter()
+        .map(|byte| glyph_width_sp_v0(*byte, 65_536).expect("glyph width"))
+        .sum()
+}
+
+#[test]
+fn plain_pages_centre_their_number_in_the_foot() {
+    let result = compile_body_v0(
+        b"\\pagenumbering{roman}A\\newline B\\newline C\\newline D \\thepage\\label{d}\
+\\thispagestyle{empty}\\newline E\\pagebreak \\pagenumbering{arabic}F \\thepage{} on \\pageref{d}",
+        Some(3),
+    );
+    let document = document_v0(&result);
+    let numbers = document
+        .pages
+        .iter()
+        .map(|page| page.counts)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        numbers,
+        [
+            [1, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            [2, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            [1, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        ]
+    );
+    assert_eq!(line_text_v0(&document, 1, 0), "D ii");
+    assert_eq!(line_text_v0(&document, 2, 0), "F 1 on ii ");
+    assert_eq!(line_text_v0(&document, 0, FOOT_V), "i");
+    assert_eq!(line_text_v0(&document, 1, FOOT_V), "", "thispagestyle");
+    assert_eq!(line_text_v0(&document, 2, FOOT_V), "1");
+    let foot = document.pages[0]
+        .glyphs
+        .iter()
+        .find(|glyph| glyph.v == first_baseline_v_v0() + FOOT_V)
+        .expect("foot glyph");
+    assert_eq!(foot.h, (TEXT_WIDTH_SP - width_sp_v0(b"i")) / 2);
+    assert_eq!(foot.font_num, 0);
+    assert_eq!(
+        String::from_utf8_lossy(&result.main_aux_bytes),
+        "\\relax\n\\newlabel{d}{{}{ii}}\n"
+    );
+}
+
+#[test]
+fn headings_pages_carry_the_section_mark_and_number_in_the_head() {
+    let result = compile_body_v0(
+        b"\\pagestyle{headings}\\section{Intro}Text\\pagebreak More\\pagebreak \
+\\section[Short]{Second title}y",
+        Some(3),
+    );
+    let document = document_v0(&result);
+    assert_eq!(document.pages.len(), 3);
+    assert_eq!(line_text_v0(&document, 0, HEAD_V), "1 INTRO 1");
+    assert_eq!(line_text_v0(&document, 1, HEAD_V), "1 INTRO 2");
+    assert_eq!(line_text_v0(&document, 2, HEAD_V), "2 SHORT 3");
+    assert_eq!(line_text_v0(&document, 0, FOOT_V), "");
+
+    // The mark is slanted, `\quad` apart from its number; the page number is flush
+    // right in the text font.
+    let font = document.font_v0(1).expect("mark font");
+    let DviFontKindV0::Tfm(definition) = &font.kind else {
+        panic!("unexpected font {font:?}");
+    };
+    assert_eq!(definition.name, b"carreltex-v0-rm-m-sl");
+    let head = document.pages[0]
+        .glyphs
+        .iter()
+        .filter(|glyph| glyph.v == first_baseline_v_v0() + HEAD_V)
+        .map(|glyph| (glyph.h, glyph.font_num))
+        .collect::<Vec<_>>();
+    assert_eq!(head[0], (0, 1));
+    assert_eq!(head[1], (width_sp_v0(b"1") + 65_536, 1));
+    assert_eq!(head[6], (TEXT_WIDTH_SP - width_sp_v0(b"1"), 0));
+}
+
+#[test]
+fn text_height_is_a_length() {
+    let result = compile_body_v0(b"\\rule{0.5\\textheight}{1pt}", Some(3));
+    let document = document_v0(&result);
+    assert_eq!(
+        document.pages[0].rules[0].width,
+        (655_360 + 2 * LINE_SP) / 2
+    );
+}
+
+#[test]
+fn pages_hold_the_lines_that_fit_above_the_folio() {
+    for (class_options, paper_height_sp, lines) in [
+        (b"".as_slice(), 52_099_153, 51),
+        (b"[a4paper]", 55_380_990, 55),
+    ] {
+        let main = [
+            b"\\documentclass".as_slice(),
+            class_options,
+            b"{article}\n\\begin{document}\n",
+            &b"x\\newline ".repeat(120),
+            b"\n\\end{document}\n",
+        ]
+        .concat();
+        let mut mount = Mount::default();
+        assert!(mount.add_file(b"main.tex", &main).is_ok());
+        let document = document_v0(&compile_request_v0(&mut mount, &request_v0(None)));
+        let page = &document.pages[0];
+        let body_lines = page
+            .glyphs
+            .iter()
+            .filter(|glyph| glyph.code == u32::from(b'x'))
+            .count();
+        assert_eq!(body_lines, lines);
+        let folio = page
+            .glyphs
+            .iter()
+            .max_by_key(|glyph| glyph.v)
+            .expect("folio");
+        assert_eq!(folio.code, u32::from(b'1'));
+        assert_eq!(
+            folio.v,
+            first_baseline_v_v0() + (lines as i32 - 1) * LINE_SP + 1_966_080
+        );
+        // The DVI origin sits 1in below the paper's top edge.
+        assert!(ONE_INCH_SP_V0 + folio.v < paper_height_sp);
+    }
+}
+
+#[test]
+fn page_commands_outside_the_subset_fail_closed() {
+    for body in [
+        b"\\pagestyle{myheadings}".as_slice(),
+        b"\\pagenumbering{gobble}",
+        b"\\pagestyle empty",
+        b"a\\footnote{\\thispagestyle{empty}}",
+    ] {
+        let result = compile_body_v0(body, None);
+        assert_eq!(
+            result.status,
+            CompileStatus::NotImplemented,
+            "{}",
+            String::from_utf8_lossy(body)
+        );
+    }
+    let result = compile_body_v0(
+        &[
+            b"\\pagenumbering{alph}".as_slice(),
+            &b"x\\newline ".repeat(27),
+        ]
+        .concat(),
+        Some(1),
+    );
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+    assert_eq!(result.log_bytes, b"INVALID_INPUT: counter_too_large");
+}
@@ -0,0 +1,162 @@
+use super::compile_request_v0;
+use super::test_text_v0::first_baseline_v_v0;
+use carreltex_core::{CompileRequestV0, CompileStatus, Mount};
+use carreltex_xdv::{read_dvi_v0, validate_dvi_v2_text_page_v0, DviRuleV0, MAX_DVI_TEXT_RULES_V0};
+
+fn valid_request() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_body_v0(body: &[u8]) -> carreltex_core::CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n\\begin{document}\n".to_vec();
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, &valid_request())
+}
+
+fn first_page_rules_v0(body: &[u8]) -> Vec<DviRuleV0> {
+    let result = compile_body_v0(body);
+    assert_eq!(
+        result.status,
+        CompileStatus::Ok,
+        "{:?}",
+        String::from_utf8_lossy(body)
+    );
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    let document = read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read");
+    document.pages[0].rules.clone()
+}
+
+/// A rule with `v` from the first baseline.
+fn rule_v0(h: i32, v: i32, width: i32, height: i32) -> DviRuleV0 {
+    DviRuleV0 {
+        h,
+        v: first_baseline_v_v0() + v,
+        width,
+        height,
+    }
+}
+
+#[test]
+fn latex_rule_sets_a_rule_and_advances() {
+    let result = compile_body_v0(b"A\\rule{2pt}{1cm}B\\rule[-2pt]{1em}{0.5\\baselineskip}");
+    assert_eq!(result.status, CompileStatus::Ok);
+    let document = read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read");
+    let page = &document.pages[0];
+    assert_eq!(
+        page.rules,
+        vec![
+            rule_v0(98_304, 0, 131_072, 1_864_679),
+            rule_v0(294_912, 131_072, 65_536, 393_216),
+        ]
+    );
+    assert_eq!(page.glyphs[2].h, 98_304 + 131_072);
+    assert!(result.main_pdf_bytes.starts_with(b"%PDF-1.7"));
+}
+
+#[test]
+fn dimensions_convert_units_like_tex() {
+    let cases: [(&[u8], i32); 12] = [
+        (b"1in", 4_736_286),
+        (b"1bp", 65_781),
+        (b"1pc", 786_432),
+        (b"1mm", 186_467),
+        (b"1dd", 70_124),
+        (b"1cc", 841_489),
+        (b"100sp", 100),
+        (b"1.5pt", 98_304),
+        (b",25 PT", 16_384),
+        (b"- -3pt", 196_608),
+        (b"2ex", 56_432),
+        (b".5\\linewidth", 2_621_440),
+    ];
+    for (dimen, expected) in cases {
+        let mut body = b"\\rule{".to_vec();
+        body.extend_from_slice(dimen);
+        body.extend_from_slice(b"}{1pt}");
+        assert_eq!(
+            first_page_rules_v0(&body)[0].width,
+            expected,
+            "{}",
+            String::from_utf8_lossy(dimen)
+        );
+    }
+}
+
+#[test]
+fn hrule_takes_its_own_line_and_vrule_spans_a_strut() {
+    let result = compile_body_v0(b"A\\hrule B\\hrule height 2pt depth 1pt width 10pt\nC\\vrule");
+    assert_eq!(result.status, CompileStatus::Ok);
+    let document = read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read");
+    let page = &document.pages[0];
+    assert_eq!(
+        page.rules,
+        vec![
+            rule_v0(0, 786_432, 5_242_880, 26_214),
+            rule_v0(0, 3 * 786_432 + 65_536, 655_360, 196_608),
+            rule_v0(65_536, 4 * 786_432 + 229_376, 26_214, 786_432),
+        ]
+    );
+    let lines = page
+        .glyphs
+        .iter()
+        .map(|glyph| (glyph.code as u8, glyph.v - first_baseline_v_v0()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        vec![
+            (b' ', 0),
+            (b'A', 0),
+            (b'B', 2 * 786_432),
+            (b'C', 4 * 786_432),
+        ]
+    );
+}
+
+#[test]
+fn rules_fail_closed_on_bad_dimensions() {
+    let cases: [(&[u8], &[u8]); 6] = [
+        (b"\\rule{2xx}{1pt}", b"INVALID_INPUT: dimen_invalid"),
+        (b"\\rule{1pt 2}{1pt}", b"INVALID_INPUT: dimen_invalid"),
+        (b"\\rule{20000pt}{1pt}", b"INVALID_INPUT: dimen_invalid"),
+        (b"\\rule[1pt{1pt}{1pt}", b"INVALID_INPUT: dimen_invalid"),
+        (
+            b"\\rule{-1pt}{1pt}",
+            b"INVALID_INPUT: rule_dimension_invalid",
+        ),
+        (
+            b"\\vrule height 1pt depth -2pt",
+            b"INVALID_INPUT: rule_dimension_invalid",
+        ),
+    ];
+    for (body, log) in cases {
+        let result = compile_body_v0(body);
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(result.log_bytes, log.to_vec());
+    }
+
+    let too_many = b"\\vrule".repeat(MAX_DVI_TEXT_RULES_V0 + 1);
+    let result = compile_body_v0(&too_many);
+    assert_eq!(
+        result.log_bytes,
+        b"INVALID_INPUT: rule_count_exceeded".to_vec()
+    );
+    let at_cap = b"\\vrule".repeat(MAX_DVI_TEXT_RULES_V0);
+    assert_eq!(compile_body_v0(&at_cap).status, CompileStatus::Ok);
+
+    // Unknown control sequences in a dimension leave the OK subset.
+    let result = compile_body_v0(b"\\rule{\\parindent}{1pt}");
+    assert_eq!(result.status, CompileStatus::NotImplemented);
+    let result = compile_body_v0(b"\\rule{1pt}");
+    assert_eq!(result.status, CompileStatus::NotImplemented);
+}
@@ -0,0 +1,99 @@
+use super::compile_request_v0;
+use super::test_text_v0::{first_baseline_v_v0, read_writer_dvi_v0, spaced_text_v0};
+use carreltex_core::{CompileRequestV0, CompileStatus, Mount};
+use carreltex_xdv::{validate_dvi_v2_text_page_v0, DviGlyphV0};
+
+fn valid_request() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_body_v0(body: &[u8]) -> carreltex_core::CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n\\begin{document}\n".to_vec();
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, &valid_request())
+}
+
+/// Glyphs of the first page regrouped into lines by their baseline.
+fn first_page_lines_v0(body: &[u8]) -> Vec<String> {
+    let result = compile_body_v0(body);
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    let document = read_writer_dvi_v0(&result.main_xdv_bytes);
+    let mut lines = Vec::<Vec<&DviGlyphV0>>::new();
+    for glyph in &document.pages[0].glyphs {
+        let line = ((glyph.v - first_baseline_v_v0()) / 786_432) as usize;
+        if lines.len() <= line {
+            lines.resize(line + 1, Vec::new());
+        }
+        lines[line].push(glyph);
+    }
+    lines.into_iter().map(spaced_text_v0).collect()
+}
+
+#[test]
+fn sections_are_numbered_and_set_on_their_own_lines() {
+    let lines = first_page_lines_v0(
+        b"\\section{Intro}\nSome text.\n\\subsection{Details}\nMore.\n\
+          \\subsection*{Aside}\n\\subsubsection{Deep}\nx\n\
+          \\section{Next}\n\\subsection{Again}\n\\paragraph{Note.} Run in.",
+    );
+    assert_eq!(
+        lines,
+        vec![
+            "1 Intro",
+            "",
+            "Some text.",
+            "",
+            "1.1 Details",
+            "",
+            "More.",
+            "",
+            "Aside",
+            "",
+            "1.1.1 Deep",
+            "",
+            "x",
+            "",
+            "2 Next",
+            "",
+            "2.1 Again",
+            "",
+            "Note. Run in. ",
+        ]
+    );
+}
+
+#[test]
+fn heading_titles_collapse_spaces_and_ignore_short_titles() {
+    let lines = first_page_lines_v0(b"\\section[Short]{  A   long title }text\\section*{}x");
+    assert_eq!(lines, vec!["1 A long title", "", "text", "", "", "", "x "]);
+}
+
+#[test]
+fn headings_outside_the_subset_are_not_implemented() {
+    for body in [
+        b"\\section{\\foo}".as_slice(),
+        b"\\section Intro",
+        b"\\subsection{A{B}}",
+        b"\\section[\\foo]{Intro}",
+    ] {
+        let result = compile_body_v0(body);
+        assert_eq!(
+            result.status,
+            CompileStatus::NotImplemented,
+            "{}",
+            String::from_utf8_lossy(body)
+        );
+    }
+}
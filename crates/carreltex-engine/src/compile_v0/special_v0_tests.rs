@@ -0,0 +1,99 @@
+use super::compile_request_v0;
+use carreltex_core::{CompileRequestV0, CompileStatus, Mount};
+use carreltex_xdv::{read_dvi_v0, validate_dvi_v2_text_page_v0, MAX_DVI_SPECIALS_V0};
+
+fn valid_request() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_body_v0(body: &[u8]) -> carreltex_core::CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n\\begin{document}\n".to_vec();
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, &valid_request())
+}
+
+#[test]
+fn special_passes_through_to_xdv_at_its_position() {
+    let result = compile_body_v0(b"AB \\special{color push rgb 1 0 0}CD\\special{color pop}");
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    let document = read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read");
+    let specials = document.pages[0]
+        .specials
+        .iter()
+        .skip(1)
+        .map(|special| (special.h, special.bytes.as_slice()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        specials,
+        vec![
+            (196_608, b"color push rgb 1 0 0".as_slice()),
+            (327_680, b"color pop".as_slice()),
+        ]
+    );
+    let text = document.pages[0]
+        .glyphs
+        .iter()
+        .map(|glyph| glyph.code as u8)
+        .collect::<Vec<_>>();
+    assert_eq!(text, b" AB CD ");
+    assert!(result.main_pdf_bytes.starts_with(b"%PDF-1.7"));
+}
+
+#[test]
+fn special_payload_keeps_braces_and_expands_macros() {
+    let result = compile_body_v0(b"\\special{pdf:dest {a} (x)}X");
+    assert_eq!(result.status, CompileStatus::Ok);
+    let document = read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read");
+    assert_eq!(document.pages[0].specials[1].bytes, b"pdf:dest {a} (x)");
+
+    // Macros inside the payload expand, which leaves the strict OK subset.
+    let result = compile_body_v0(b"\\def\\target{x}\\special{\\target}");
+    assert_eq!(result.status, CompileStatus::NotImplemented);
+}
+
+#[test]
+fn special_fails_closed_on_bad_payloads_and_caps() {
+    let result = compile_body_v0(b"\\special x");
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+    assert_eq!(
+        result.log_bytes,
+        b"INVALID_INPUT: macro_special_unsupported".to_vec()
+    );
+
+    let result = compile_body_v0(b"\\special{\\unknown}");
+    assert_eq!(
+        result.log_bytes,
+        b"INVALID_INPUT: macro_special_unsupported".to_vec()
+    );
+
+    let mut oversized = b"\\special{".to_vec();
+    oversized.extend(std::iter::repeat_n(b'x', 4097));
+    oversized.push(b'}');
+    let result = compile_body_v0(&oversized);
+    assert_eq!(
+        result.log_bytes,
+        b"INVALID_INPUT: macro_special_too_large".to_vec()
+    );
+
+    let too_many = b"\\special{x}".repeat(MAX_DVI_SPECIALS_V0 + 1);
+    let result = compile_body_v0(&too_many);
+    assert_eq!(
+        result.log_bytes,
+        b"INVALID_INPUT: macro_special_count_exceeded".to_vec()
+    );
+    // The `papersize` special takes one of the slots.
+    let at_cap = b"\\special{x}".repeat(MAX_DVI_SPECIALS_V0 - 1);
+    assert_eq!(compile_body_v0(&at_cap).status, CompileStatus::Ok);
+}
@@ -15,7 +15,7 @@ pub(crate) fn build_tex_stats_from_tokens_v0(tokens: &[TokenV0]) -> Result<Strin
             TokenV0::ControlSeq(_) => {
                 control_seq_count = control_seq_count.checked_add(1).ok_or(())?;
             }
-            TokenV0::Char(_) => {
+            TokenV0::Char(_) | TokenV0::MathShift | TokenV0::AlignTab => {
                 char_count = char_count.checked_add(1).ok_or(())?;
             }
             TokenV0::Space => {
@@ -0,0 +1,213 @@
+use super::compile_request_v0;
+use super::test_text_v0::first_baseline_v_v0;
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::{read_dvi_v0, validate_dvi_v2_text_page_v0, DviDocumentV0, DviRuleV0};
+
+const LINE_SP: i32 = 786_432;
+const EM_SP: i32 = 65_536;
+/// `\tabcolsep`, `\arrayrulewidth` and `\doublerulesep`.
+const PAD_SP: i32 = 39_321;
+const RULE_SP: i32 = 26_214;
+const DOUBLE_SP: i32 = 13_107;
+/// Depth of a strut below the baseline.
+const DEPTH_SP: i32 = 229_376;
+
+fn request_v0() -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: None,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_body_v0(body: &[u8]) -> CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n\\begin{document}\n".to_vec();
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, &request_v0())
+}
+
+fn document_v0(body: &[u8]) -> DviDocumentV0 {
+    let result = compile_body_v0(body);
+    assert_eq!(
+        result.status,
+        CompileStatus::Ok,
+        "{}",
+        String::from_utf8_lossy(&result.log_bytes)
+    );
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read")
+}
+
+/// `(glyph, h, line)` of the first page's glyphs, lines counted from the first.
+fn glyphs_v0(document: &DviDocumentV0) -> Vec<(char, i32, i32)> {
+    document.pages[0]
+        .glyphs
+        .iter()
+        .map(|glyph| {
+            (
+                glyph.code as u8 as char,
+                glyph.h,
+                (glyph.v - first_baseline_v_v0()) / LINE_SP,
+            )
+        })
+        .collect()
+}
+
+/// A rule with `v` from the first baseline.
+fn rule_v0(h: i32, v: i32, width: i32, height: i32) -> DviRuleV0 {
+    DviRuleV0 {
+        h,
+        v: first_baseline_v_v0() + v,
+        width,
+        height,
+    }
+}
+
+#[test]
+fn tabular_sets_columns_at_their_natural_width_between_rules() {
+    let document =
+        document_v0(b"x\\begin{tabular}{|l|c|}\\hline a & bb\\\\ \\hline \\hline\\end{tabular}y");
+    let first_body = RULE_SP + PAD_SP;
+    let second_start = first_body + EM_SP + PAD_SP + RULE_SP;
+    let width = second_start + PAD_SP + 2 * EM_SP + PAD_SP + RULE_SP;
+    assert_eq!(
+        glyphs_v0(&document),
+        [
+            (' ', 0, 0),
+            ('x', EM_SP / 2, 0),
+            ('a', first_body, 1),
+            ('b', second_start + PAD_SP, 1),
+            ('b', second_start + PAD_SP + EM_SP, 1),
+            ('y', 0, 2),
+            (' ', EM_SP, 2),
+        ]
+    );
+    let top = LINE_SP - (LINE_SP - DEPTH_SP - RULE_SP);
+    assert_eq!(
+        document.pages[0].rules,
+        [
+            rule_v0(0, top, width, RULE_SP),
+            rule_v0(0, LINE_SP + DEPTH_SP + RULE_SP, width, RULE_SP),
+            rule_v0(
+                0,
+                LINE_SP + DEPTH_SP + 2 * RULE_SP + DOUBLE_SP,
+                width,
+                RULE_SP
+            ),
+            rule_v0(0, LINE_SP + DEPTH_SP, RULE_SP, LINE_SP),
+            rule_v0(second_start - RULE_SP, LINE_SP + DEPTH_SP, RULE_SP, LINE_SP),
+            rule_v0(width - RULE_SP, LINE_SP + DEPTH_SP, RULE_SP, LINE_SP),
+        ]
+    );
+}
+
+#[test]
+fn tabular_aligns_cells_and_spans_multicolumn_and_cline() {
+    let body = b"\\begin{tabular}{@{}lr||c@{:}}\
+a & b & c\\\\\
+\\multicolumn{2}{c}{\\textbf{wide cell}} & cc\\\\\
+\\cline{1-2} x &&\\end{tabular}";
+    let document = document_v0(body);
+    // The bold `wide cell` widens the second column to span its width and padding.
+    let wide = 8 * EM_SP;
+    let double = 2 * RULE_SP + DOUBLE_SP;
+    let third_start = wide + 2 * PAD_SP;
+    let glyphs = glyphs_v0(&document);
+    assert_eq!(
+        glyphs[..4],
+        [
+            ('a', 0, 0),
+            ('b', third_start - double - PAD_SP - EM_SP, 0),
+            ('c', third_start + PAD_SP + EM_SP / 2, 0),
+            (':', third_start + PAD_SP + 2 * EM_SP, 0),
+        ]
+    );
+    assert_eq!(glyphs[4], ('w', PAD_SP, 1));
+    assert_eq!(glyphs[13], ('c', third_start + PAD_SP, 1));
+    assert_eq!(glyphs[16], ('x', 0, 2));
+    assert_eq!(glyphs[17], (':', third_start + PAD_SP + 2 * EM_SP, 2));
+    assert_eq!(
+        document.pages[0].rules[0..2],
+        [
+            rule_v0(third_start - double, DEPTH_SP, RULE_SP, LINE_SP),
+            rule_v0(third_start - RULE_SP, DEPTH_SP, RULE_SP, LINE_SP),
+        ]
+    );
+    let cline = rule_v0(0, LINE_SP + DEPTH_SP + RULE_SP, third_start, RULE_SP);
+    assert_eq!(document.pages[0].rules[2], cline);
+}
+
+#[test]
+fn paragraph_columns_break_their_cells() {
+    let document = document_v0(b"\\begin{tabular}{p{5em}l}aa bb cc dd & e\\\\f & g\\end{tabular}");
+    let second = PAD_SP + 5 * EM_SP + 2 * PAD_SP;
+    let glyphs = glyphs_v0(&document)
+        .into_iter()
+        .filter(|glyph| glyph.0 != ' ')
+        .collect::<Vec<_>>();
+    assert_eq!(
+        glyphs,
+        [
+            ('a', PAD_SP, 0),
+            ('a', PAD_SP + EM_SP, 0),
+            ('b', PAD_SP + 5 * EM_SP / 2, 0),
+            ('b', PAD_SP + 7 * EM_SP / 2, 0),
+            ('e', second, 0),
+            ('c', PAD_SP, 1),
+            ('c', PAD_SP + EM_SP, 1),
+            ('d', PAD_SP + 5 * EM_SP / 2, 1),
+            ('d', PAD_SP + 7 * EM_SP / 2, 1),
+            ('f', PAD_SP, 2),
+            ('g', second, 2),
+        ]
+    );
+}
+
+#[test]
+fn tabular_outside_the_subset_fails_closed() {
+    for body in [
+        b"\\begin{tabular}{lx}a&b\\end{tabular}".as_slice(),
+        b"\\begin{tabular}{>{\\bf}l}a\\end{tabular}",
+        b"\\begin{tabular}{*{2}{l}}a&b\\end{tabular}",
+        b"\\begin{tabular}{m{1cm}}a\\end{tabular}",
+        b"\\begin{tabular}{}a\\end{tabular}",
+        b"\\begin{tabular}{l}\\multicolumn{1}{lc}{a}\\end{tabular}",
+    ] {
+        let result = compile_body_v0(body);
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(
+            result.log_bytes,
+            b"INVALID_INPUT: tabular_column_unsupported"
+        );
+    }
+    for body in [
+        b"\\begin{tabular}{ll}a&b&c\\end{tabular}".as_slice(),
+        b"\\begin{tabular}{ll}a&\\multicolumn{2}{c}{b}\\end{tabular}",
+    ] {
+        let result = compile_body_v0(body);
+        assert_eq!(result.status, CompileStatus::InvalidInput);
+        assert_eq!(result.log_bytes, b"INVALID_INPUT: tabular_extra_cell");
+    }
+    for body in [
+        b"\\begin{tabular}{l}a\\\\[2pt]b\\end{tabular}".as_slice(),
+        b"\\begin{tabular}{l}$$a$$\\end{tabular}",
+        b"\\begin{tabular}{l}a\\end{tabular*}",
+        b"\\begin{tabular}{ll}\\cline{1-3}a&b\\end{tabular}",
+        b"\\begin{tabular}{l}a\\hline\\end{tabular}",
+    ] {
+        let result = compile_body_v0(body);
+        assert_eq!(
+            result.status,
+            CompileStatus::NotImplemented,
+            "{}",
+            String::from_utf8_lossy(body)
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use super::ok_geometry_v0::OkGeometryV0;
+use super::ok_v0::OK_GLYPH_ADVANCE_SP_V0;
+use carreltex_xdv::{
+    glyph_width_sp_v0, read_dvi_v0, DviDocumentV0, DviFontKindV0, DviGlyphV0,
+    DVI_TEXT_FONT_DESIGN_SIZE_SP_V0,
+};
+
+/// First baseline below the DVI origin under the default geometry.
+pub(super) fn first_baseline_v_v0() -> i32 {
+    let (_, v) = OkGeometryV0::default()
+        .origin_v0()
+        .ok()
+        .expect("default origin");
+    v
+}
+
+/// `main.xdv` read back with every glyph's advance at the default layout as its width.
+/// The writer's `carreltex-v0` fonts read as zero-width, so without it a glyph's own
+/// advance cannot be told from a gap after it.
+pub(super) fn read_writer_dvi_v0(bytes: &[u8]) -> DviDocumentV0 {
+    let mut document = read_dvi_v0(bytes).expect("xdv should read");
+    let ems = document
+        .fonts
+        .iter()
+        .map(|font| {
+            let DviFontKindV0::Tfm(definition) = &font.kind else {
+                panic!("unexpected font {font:?}");
+            };
+            let em = match definition.scale {
+                0 => OK_GLYPH_ADVANCE_SP_V0,
+                scale => {
+                    (i64::from(OK_GLYPH_ADVANCE_SP_V0) * i64::from(scale)
+                        / i64::from(DVI_TEXT_FONT_DESIGN_SIZE_SP_V0)) as i32
+                }
+            };
+            (font.font_num, em)
+        })
+        .collect::<Vec<_>>();
+    for glyph in document.pages.iter_mut().flat_map(|page| &mut page.glyphs) {
+        let (_, em) = ems
+            .iter()
+            .find(|(font_num, _)| *font_num == glyph.font_num)
+            .expect("defined font");
+        glyph.width = glyph_width_sp_v0(glyph.code as u8, *em).expect("writable width");
+    }
+    document
+}
+
+/// Glyph chars in order, with a space wherever a glyph starts past the advance of the
+/// one before it on the same baseline.
+pub(super) fn spaced_text_v0<'a>(glyphs: impl IntoIterator<Item = &'a DviGlyphV0>) -> String {
+    let mut text = String::new();
+    let mut previous = None::<&DviGlyphV0>;
+    for glyph in glyphs {
+        if previous.is_some_and(|previous| {
+            previous.v == glyph.v && glyph.h > previous.h.saturating_add(previous.width)
+        }) {
+            text.push(' ');
+        }
+        text.push(glyph.code as u8 as char);
+        previous = Some(glyph);
+    }
+    text
+}
@@ -1,9 +1,11 @@
 use super::input_expand_v0::{MAX_INPUT_DEPTH_V0, MAX_INPUT_EXPANSIONS_V0};
 use super::macro_expand_v0::MAX_MACRO_EXPANSIONS_V0;
-use super::{compile_main_v0, compile_request_v0};
+use super::{compile_main_v0, compile_request_v0, ok_page_layout_v0};
+use crate::reasons_v0::{invalid_log_bytes_v0, InvalidInputReasonV0};
 use carreltex_core::{
     CompileRequestV0, CompileStatus, Mount, DEFAULT_COMPILE_MAIN_MAX_LOG_BYTES_V0, MAX_LOG_BYTES_V0,
 };
+use carreltex_xdv::DviTextMarksV0;
 
 fn valid_main() -> &'static [u8] {
     b"\\documentclass{article}\n\\begin{document}\nHi\n\\end{document}\n"
@@ -71,6 +73,27 @@ fn compile_request_returns_not_implemented_when_valid() {
     assert!(!result.tex_stats_json.is_empty());
 }
 #[test]
+fn compile_request_ok_renders_main_pdf_dated_by_source_date_epoch() {
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", valid_main()).is_ok());
+
+    let result = compile_request_v0(&mut mount, &valid_request());
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(result.main_pdf_bytes.starts_with(b"%PDF-1.7\n"));
+    assert!(result.main_pdf_bytes.ends_with(b"%%EOF\n"));
+    let pdf = String::from_utf8_lossy(&result.main_pdf_bytes);
+    assert!(pdf.contains("/CreationDate (D:20231114221320Z)"));
+    assert!(pdf.contains("<48> Tj\n"));
+    let again = compile_request_v0(&mut mount, &valid_request());
+    assert_eq!(again.main_pdf_bytes, result.main_pdf_bytes);
+
+    let mut invalid = valid_request();
+    invalid.entrypoint = "other.tex".to_owned();
+    assert!(compile_request_v0(&mut mount, &invalid)
+        .main_pdf_bytes
+        .is_empty());
+}
+#[test]
 fn compile_request_rejects_invalid_entrypoint() {
     let mut mount = Mount::default();
     assert!(mount.add_file(b"main.tex", valid_main()).is_ok());
@@ -221,6 +244,25 @@ fn compile_request_rejects_unbalanced_groups() {
     assert!(result.log_bytes.ends_with(b"stats_build_failed"));
 }
 #[test]
+fn ok_page_layout_failures_have_their_own_reasons() {
+    let marks = DviTextMarksV0::default();
+    let layout = ok_page_layout_v0(b"a\x0cb", &marks, 65_536, 80, 200);
+    assert!(matches!(layout, Ok((starts, floats)) if starts == [0, 2] && floats.is_empty()));
+    let layout = ok_page_layout_v0(b"a\tb", &marks, 65_536, 80, 200);
+    assert!(matches!(
+        layout,
+        Err(InvalidInputReasonV0::PageLayoutFailed)
+    ));
+    assert_eq!(
+        invalid_log_bytes_v0(InvalidInputReasonV0::PageLayoutFailed),
+        b"INVALID_INPUT: page_layout_failed"
+    );
+    assert_eq!(
+        invalid_log_bytes_v0(InvalidInputReasonV0::FloatPlacementFailed),
+        b"INVALID_INPUT: float_placement_failed"
+    );
+}
+#[test]
 fn compile_request_missing_main_tex_reports_entrypoint_missing_reason() {
     let mut mount = Mount::default();
     let result = compile_request_v0(&mut mount, &valid_request());
@@ -0,0 +1,130 @@
+use super::compile_request_v0;
+use super::test_text_v0::{first_baseline_v_v0, read_writer_dvi_v0, spaced_text_v0};
+use carreltex_core::{CompileRequestV0, CompileResultV0, CompileStatus, Mount};
+use carreltex_xdv::{read_dvi_v0, validate_dvi_v2_text_page_v0, DviGlyphV0};
+
+fn request_v0(max_lines_per_page: Option<u32>) -> CompileRequestV0 {
+    CompileRequestV0 {
+        entrypoint: "main.tex".to_owned(),
+        source_date_epoch: 1,
+        max_log_bytes: 4096,
+        ok_max_line_glyphs_v0: None,
+        ok_max_lines_per_page_v0: max_lines_per_page,
+        ok_line_advance_sp_v0: None,
+        ok_glyph_advance_sp_v0: None,
+    }
+}
+
+fn compile_body_v0(body: &[u8], request: &CompileRequestV0) -> CompileResultV0 {
+    let mut main = b"\\documentclass{article}\n\\pagestyle{empty}\n\\begin{document}\n".to_vec();
+    main.extend_from_slice(body);
+    main.extend_from_slice(b"\n\\end{document}\n");
+    let mut mount = Mount::default();
+    assert!(mount.add_file(b"main.tex", &main).is_ok());
+    compile_request_v0(&mut mount, request)
+}
+
+/// Glyphs of every page regrouped into lines by their baseline, each line led by the
+/// `h` of its first glyph.
+fn page_lines_v0(result: &CompileResultV0) -> Vec<Vec<(i32, String)>> {
+    assert_eq!(result.status, CompileStatus::Ok);
+    assert!(validate_dvi_v2_text_page_v0(&result.main_xdv_bytes));
+    let document = read_writer_dvi_v0(&result.main_xdv_bytes);
+    document
+        .pages
+        .iter()
+        .map(|page| {
+            let mut lines = Vec::<(i32, i32, Vec<&DviGlyphV0>)>::new();
+            for glyph in &page.glyphs {
+                match lines.last_mut() {
+                    Some((v, _, glyphs)) if *v == glyph.v => glyphs.push(glyph),
+                    _ => lines.push((glyph.v, glyph.h, vec![glyph])),
+                }
+            }
+            lines
+                .into_iter()
+                .map(|(_, h, glyphs)| (h, spaced_text_v0(glyphs)))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn contents_list_numbered_headings_with_leaders_and_pages() {
+    let result = compile_body_v0(
+        b"\\tableofcontents\n\\section[Intro]{Introduction}\ntext\n\\subsection{Details}\n\x0c\
+\\subsubsection{Deep}\n\\section*{Aside}\n\\paragraph{Note.} x",
+        &request_v0(None),
+    );
+    let dots = |count: usize| vec!["."; count].join(" ");
+    assert_eq!(
+        page_lines_v0(&result)[0][..4],
+        [
+            (0, "Contents".to_owned()),
+            (0, "1 Intro 1".to_owned()),
+            (98_304, format!("1.1Details {} 1", dots(67))),
+            (249_036, format!("1.1.1Deep {} 2", dots(66))),
+        ]
+    );
+    assert!(result.log_bytes.is_empty());
+    assert_eq!(
+        String::from_utf8_lossy(&result.main_toc_bytes),
+        "\\contentsline {section}{\\numberline {1}Intro}{1}%\n\
+\\contentsline {subsection}{\\numberline {1.1}Details}{1}%\n\
+\\contentsline {subsubsection}{\\numberline {1.1.1}Deep}{2}%\n\
+\\contentsline {paragraph}{Note.}{2}%\n"
+    );
+
+    // Leader dots sit one per em box counted from the line's left edge, the page
+    // number of one em flush right at the text width of 80.
+    let document = read_dvi_v0(&result.main_xdv_bytes).expect("xdv should read");
+    let details = document.pages[0]
+        .glyphs
+        .iter()
+        .filter(|glyph| glyph.v == first_baseline_v_v0() + 2_359_296)
+        .collect::<Vec<_>>();
+    assert_eq!(details[10].h, 11 * 65_536 + 16_384);
+    assert_eq!(details[11].h - details[10].h, 65_536);
+    let page = details.last().expect("page number");
+    assert_eq!(page.h, 79 * 65_536);
+    assert_eq!(details[details.len() - 2].h, 77 * 65_536 + 16_384);
+}
+
+#[test]
+fn headings_pushed_by_the_contents_settle_without_a_rerun_warning() {
+    // The first pass sets an empty list; the second one moves every heading down a
+    // page and the third one records where they landed.
+    let result = compile_body_v0(
+        b"\\tableofcontents\\section{A}a\\section{B}b\\section{C}",
+        &request_v0(Some(7)),
+    );
+    assert_eq!(
+        page_lines_v0(&result)[0],
+        [
+            (0, "Contents".to_owned()),
+            (0, "1 A 2".to_owned()),
+            (0, "2 B 2".to_owned()),
+            (0, "3 C 3".to_owned()),
+        ]
+    );
+    assert!(result.log_bytes.is_empty());
+    assert_eq!(
+        String::from_utf8_lossy(&result.main_toc_bytes),
+        "\\contentsline {section}{\\numberline {1}A}{2}%\n\
+\\contentsline {section}{\\numberline {2}B}{2}%\n\
+\\contentsline {section}{\\numberline {3}C}{3}%\n"
+    );
+}
+
+#[test]
+fn contents_outside_the_subset_fail_closed() {
+    let result = compile_body_v0(b"\\section[\\foo]{x}", &request_v0(None));
+    assert_eq!(result.status, CompileStatus::NotImplemented);
+    assert!(result.main_toc_bytes.is_empty());
+    let mut body = b"\\tableofcontents".to_vec();
+    body.extend_from_slice(&b"\\subsection{x}".repeat(2049));
+    let result = compile_body_v0(&body, &request_v0(None));
+    assert_eq!(result.status, CompileStatus::InvalidInput);
+    assert_eq!(result.log_bytes, b"INVALID_INPUT: toc_entries_exceeded");
+    assert!(result.main_toc_bytes.is_empty());
+}
@@ -8,6 +8,9 @@ pub(crate) enum InvalidInputReasonV0 {
     TokenizerAccentNotSupported,
     TokenizerControlSeqNonAscii,
     StatsBuildFailed,
+    PdfWriteFailed,
+    PageLayoutFailed,
+    FloatPlacementFailed,
     InputValidationFailed,
     InputCycleFailed,
     InputDepthExceeded,
@@ -45,6 +48,46 @@ pub(crate) enum InvalidInputReasonV0 {
     MacroIfxElseWithoutIf,
     MacroIfxMissingFi,
     MacroIfxDepthExceeded,
+    MacroSpecialUnsupported,
+    MacroSpecialTooLarge,
+    MacroSpecialCountExceeded,
+    ColorUndefined,
+    ColorSpecInvalid,
+    ColorDefinitionsExceeded,
+    ColorStackUnbalanced,
+    DimenInvalid,
+    RuleDimensionInvalid,
+    RuleCountExceeded,
+    ListNestingExceeded,
+    ListEnvironmentUnbalanced,
+    ListItemMissing,
+    ListItemOutsideList,
+    ListItemsExceeded,
+    CounterTooLarge,
+    FontCountExceeded,
+    FontMapInvalid,
+    FontFileMissing,
+    LabelCountExceeded,
+    TocEntriesExceeded,
+    FootnoteCountExceeded,
+    MathDoubleScript,
+    MathCountExceeded,
+    EquationMultipleTag,
+    TabularColumnUnsupported,
+    TabularExtraCell,
+    FloatPlacementInvalid,
+    FloatCountExceeded,
+    CaptionOutsideFloat,
+    ImageMissing,
+    ImageFormatUnsupported,
+    BibFileMissing,
+    BibSyntaxInvalid,
+    BibStringUndefined,
+    BibStyleMissing,
+    BibEntryCountExceeded,
+    ClassNotAvailable,
+    ClassOptionNotSupported,
+    PackageNotAvailable,
 }
 
 pub(crate) fn invalid_log_bytes_v0(reason: InvalidInputReasonV0) -> &'static [u8] {
@@ -63,6 +106,9 @@ pub(crate) fn invalid_log_bytes_v0(reason: InvalidInputReasonV0) -> &'static [u8
             b"INVALID_INPUT: tokenizer_control_seq_non_ascii"
         }
         InvalidInputReasonV0::StatsBuildFailed => b"INVALID_INPUT: stats_build_failed",
+        InvalidInputReasonV0::PdfWriteFailed => b"INVALID_INPUT: pdf_write_failed",
+        InvalidInputReasonV0::PageLayoutFailed => b"INVALID_INPUT: page_layout_failed",
+        InvalidInputReasonV0::FloatPlacementFailed => b"INVALID_INPUT: float_placement_failed",
         InvalidInputReasonV0::InputValidationFailed => b"INVALID_INPUT: input_validation_failed",
         InvalidInputReasonV0::InputCycleFailed => b"INVALID_INPUT: input_cycle_failed",
         InvalidInputReasonV0::InputDepthExceeded => b"INVALID_INPUT: input_depth_exceeded",
@@ -138,5 +184,63 @@ pub(crate) fn invalid_log_bytes_v0(reason: InvalidInputReasonV0) -> &'static [u8
         InvalidInputReasonV0::MacroIfxDepthExceeded => {
             b"INVALID_INPUT: macro_ifx_depth_exceeded"
         }
+        InvalidInputReasonV0::MacroSpecialUnsupported => {
+            b"INVALID_INPUT: macro_special_unsupported"
+        }
+        InvalidInputReasonV0::MacroSpecialTooLarge => b"INVALID_INPUT: macro_special_too_large",
+        InvalidInputReasonV0::MacroSpecialCountExceeded => {
+            b"INVALID_INPUT: macro_special_count_exceeded"
+        }
+        InvalidInputReasonV0::ColorUndefined => b"INVALID_INPUT: color_undefined",
+        InvalidInputReasonV0::ColorSpecInvalid => b"INVALID_INPUT: color_spec_invalid",
+        InvalidInputReasonV0::ColorDefinitionsExceeded => {
+            b"INVALID_INPUT: color_definitions_exceeded"
+        }
+        InvalidInputReasonV0::ColorStackUnbalanced => b"INVALID_INPUT: color_stack_unbalanced",
+        InvalidInputReasonV0::DimenInvalid => b"INVALID_INPUT: dimen_invalid",
+        InvalidInputReasonV0::RuleDimensionInvalid => b"INVALID_INPUT: rule_dimension_invalid",
+        InvalidInputReasonV0::RuleCountExceeded => b"INVALID_INPUT: rule_count_exceeded",
+        InvalidInputReasonV0::ListNestingExceeded => b"INVALID_INPUT: list_nesting_exceeded",
+        InvalidInputReasonV0::ListEnvironmentUnbalanced => {
+            b"INVALID_INPUT: list_environment_unbalanced"
+        }
+        InvalidInputReasonV0::ListItemMissing => b"INVALID_INPUT: list_item_missing",
+        InvalidInputReasonV0::ListItemOutsideList => b"INVALID_INPUT: list_item_outside_list",
+        InvalidInputReasonV0::ListItemsExceeded => b"INVALID_INPUT: list_items_exceeded",
+        InvalidInputReasonV0::CounterTooLarge => b"INVALID_INPUT: counter_too_large",
+        InvalidInputReasonV0::FontCountExceeded => b"INVALID_INPUT: font_count_exceeded",
+        InvalidInputReasonV0::FontMapInvalid => b"INVALID_INPUT: font_map_invalid",
+        InvalidInputReasonV0::FontFileMissing => b"INVALID_INPUT: font_file_missing",
+        InvalidInputReasonV0::LabelCountExceeded => b"INVALID_INPUT: label_count_exceeded",
+        InvalidInputReasonV0::TocEntriesExceeded => b"INVALID_INPUT: toc_entries_exceeded",
+        InvalidInputReasonV0::FootnoteCountExceeded => b"INVALID_INPUT: footnote_count_exceeded",
+        InvalidInputReasonV0::MathDoubleScript => b"INVALID_INPUT: math_double_script",
+        InvalidInputReasonV0::MathCountExceeded => b"INVALID_INPUT: math_count_exceeded",
+        InvalidInputReasonV0::EquationMultipleTag => b"INVALID_INPUT: equation_multiple_tag",
+        InvalidInputReasonV0::TabularColumnUnsupported => {
+            b"INVALID_INPUT: tabular_column_unsupported"
+        }
+        InvalidInputReasonV0::TabularExtraCell => b"INVALID_INPUT: tabular_extra_cell",
+        InvalidInputReasonV0::FloatPlacementInvalid => b"INVALID_INPUT: float_placement_invalid",
+        InvalidInputReasonV0::FloatCountExceeded => b"INVALID_INPUT: float_count_exceeded",
+        InvalidInputReasonV0::CaptionOutsideFloat => b"INVALID_INPUT: caption_outside_float",
+        InvalidInputReasonV0::ImageMissing => b"INVALID_INPUT: image_missing",
+        InvalidInputReasonV0::ImageFormatUnsupported => b"INVALID_INPUT: image_format_unsupported",
+        InvalidInputReasonV0::BibFileMissing => b"INVALID_INPUT: bib_file_missing",
+        InvalidInputReasonV0::BibSyntaxInvalid => b"INVALID_INPUT: bib_syntax_invalid",
+        InvalidInputReasonV0::BibStringUndefined => b"INVALID_INPUT: bib_string_undefined",
+        InvalidInputReasonV0::BibStyleMissing => b"INVALID_INPUT: bib_style_missing",
+        InvalidInputReasonV0::BibEntryCountExceeded => b"INVALID_INPUT: bib_entry_count_exceeded",
+        InvalidInputReasonV0::ClassNotAvailable => b"INVALID_INPUT: class_not_available",
+        InvalidInputReasonV0::ClassOptionNotSupported => {
+            b"INVALID_INPUT: class_option_not_supported"
+        }
+        InvalidInputReasonV0::PackageNotAvailable => b"INVALID_INPUT: package_not_available",
     }
 }
+
+/// The log line of `reason` followed by `:` and the `name` it applies to, such as
+/// the class or package a document asks for.
+pub(crate) fn invalid_named_log_bytes_v0(reason: InvalidInputReasonV0, name: &[u8]) -> Vec<u8> {
+    [invalid_log_bytes_v0(reason), b":", name].concat()
+}
@@ -1,3 +1,5 @@
 pub mod tokenize_v0;
 
-pub use tokenize_v0::{tokenize_v0, TokenV0, TokenizeErrorV0, MAX_TOKENS_V0};
+pub use tokenize_v0::{
+    tokenize_at_letter_v0, tokenize_v0, TokenV0, TokenizeErrorV0, MAX_TOKENS_V0,
+};
@@ -4,6 +4,8 @@ pub enum TokenV0 {
     Char(u8),
     BeginGroup,
     EndGroup,
+    MathShift,
+    AlignTab,
     Space,
 }
 
@@ -27,6 +29,8 @@ mod whitespace;
 #[cfg(test)]
 mod tests;
 #[cfg(test)]
+mod tests_math_shift;
+#[cfg(test)]
 mod tests_newline;
 #[cfg(test)]
 mod tests_textword_144;
@@ -50,6 +54,8 @@ mod tests_textword_148;
 ///   is not applied while consuming comment bytes.
 /// - Whitespace bytes (`' '`, `\t`, `\r`, `\n`) collapse into one `Space`.
 /// - `{` and `}` become `BeginGroup` / `EndGroup`.
+/// - `$` becomes `MathShift`; the control symbol `\$` is `Char(b'$')`.
+/// - `&` becomes `AlignTab`; the control symbol `\&` is `Char(b'&')`.
 /// - `\` starts a control sequence:
 ///   - If followed by ASCII letters, consume a control word and emit
 ///     `ControlSeq(name_bytes)`. A following whitespace run is swallowed
@@ -62,3 +68,7 @@ mod tests_textword_148;
 /// - All other bytes become `Char(byte)`.
 /// - Fails with `TooManyTokens` if output would exceed `MAX_TOKENS_V0`.
 pub use core::tokenize_v0;
+
+/// `tokenize_v0` with `@` a letter in control words, as under `\makeatletter`
+/// for the package files `\usepackage` reads.
+pub use core::tokenize_at_letter_v0;
@@ -15,6 +15,7 @@ pub(super) struct ParsedControlSeqV0 {
 pub(super) fn parse_control_seq_v0(
     input: &[u8],
     start_index: usize,
+    at_letter: bool,
 ) -> Result<ParsedControlSeqV0, TokenizeErrorV0> {
     if start_index >= input.len() {
         return Err(TokenizeErrorV0::InvalidInput);
@@ -25,9 +26,14 @@ pub(super) fn parse_control_seq_v0(
         return Err(TokenizeErrorV0::InvalidInput);
     }
 
-    if next.is_ascii_alphabetic() {
-        parse_control_word_v0(input, next, after_next_index)
+    if is_letter_v0(next, at_letter) {
+        parse_control_word_v0(input, next, after_next_index, at_letter)
     } else {
         parse_control_symbol_v0(input, next, after_next_index)
     }
 }
+
+/// Letters of control words: ASCII letters, and `@` under `\makeatletter`.
+fn is_letter_v0(byte: u8, at_letter: bool) -> bool {
+    byte.is_ascii_alphabetic() || (at_letter && byte == b'@')
+}
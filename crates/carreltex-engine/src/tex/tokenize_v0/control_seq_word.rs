@@ -6,6 +6,7 @@ pub(super) fn parse_control_word_v0(
     input: &[u8],
     first_byte: u8,
     mut index: usize,
+    at_letter: bool,
 ) -> Result<ParsedControlSeqV0, TokenizeErrorV0> {
     let mut control_word = Vec::<u8>::new();
     control_word.push(first_byte);
@@ -14,7 +15,7 @@ pub(super) fn parse_control_word_v0(
         if word_byte == 0 {
             return Err(TokenizeErrorV0::InvalidInput);
         }
-        if !word_byte.is_ascii_alphabetic() {
+        if !super::is_letter_v0(word_byte, at_letter) {
             break;
         }
         control_word.push(word_byte);
@@ -59,13 +60,13 @@ pub(super) fn parse_control_word_v0(
         vec![TokenV0::Char(b'\'')]
     } else if control_word.as_slice() == b"textasciigrave" {
         vec![TokenV0::Char(b'`')]
-    } else if control_word.as_slice() == b"textquotedblleft" {
+    } else if control_word.as_slice() == b"textquotedblleft"
+        || control_word.as_slice() == b"textquotedblright"
+    {
         vec![TokenV0::Char(b'"')]
-    } else if control_word.as_slice() == b"textquotedblright" {
-        vec![TokenV0::Char(b'"')]
-    } else if control_word.as_slice() == b"textendash" {
-        vec![TokenV0::Char(b'-')]
-    } else if control_word.as_slice() == b"textemdash" {
+    } else if control_word.as_slice() == b"textendash"
+        || control_word.as_slice() == b"textemdash"
+    {
         vec![TokenV0::Char(b'-')]
     } else if control_word.as_slice() == b"textellipsis" {
         vec![TokenV0::Char(b'.'), TokenV0::Char(b'.'), TokenV0::Char(b'.')]
@@ -111,9 +112,9 @@ pub(super) fn parse_control_word_v0(
         vec![TokenV0::Char(b'<')]
     } else if control_word.as_slice() == b"textguillemotright" {
         vec![TokenV0::Char(b'>')]
-    } else if control_word.as_slice() == b"textquoteleft" {
-        vec![TokenV0::Char(b'\'')]
-    } else if control_word.as_slice() == b"textquoteright" {
+    } else if control_word.as_slice() == b"textquoteleft"
+        || control_word.as_slice() == b"textquoteright"
+    {
         vec![TokenV0::Char(b'\'')]
     } else if control_word.as_slice() == b"textquotedblbase" {
         vec![TokenV0::Char(b'"')]
@@ -332,9 +333,9 @@ pub(super) fn parse_control_word_v0(
         vec![TokenV0::Char(b'M')]
     } else if control_word.as_slice() == b"textdivorced" {
         vec![TokenV0::Char(b'D')]
-    } else if control_word.as_slice() == b"textopenstar" {
-        vec![TokenV0::Char(b'*')]
-    } else if control_word.as_slice() == b"textborn" {
+    } else if control_word.as_slice() == b"textopenstar"
+        || control_word.as_slice() == b"textborn"
+    {
         vec![TokenV0::Char(b'*')]
     } else if control_word.as_slice() == b"textdied" {
         vec![TokenV0::Char(b'+')]
@@ -13,6 +13,17 @@ fn push_token_v0(tokens: &mut Vec<TokenV0>, token: TokenV0) -> Result<(), Tokeni
 }
 
 pub fn tokenize_v0(input: &[u8]) -> Result<Vec<TokenV0>, TokenizeErrorV0> {
+    tokenize_with_catcodes_v0(input, false)
+}
+
+pub fn tokenize_at_letter_v0(input: &[u8]) -> Result<Vec<TokenV0>, TokenizeErrorV0> {
+    tokenize_with_catcodes_v0(input, true)
+}
+
+fn tokenize_with_catcodes_v0(
+    input: &[u8],
+    at_letter: bool,
+) -> Result<Vec<TokenV0>, TokenizeErrorV0> {
     let mut tokens = Vec::new();
     let mut index = 0usize;
     while index < input.len() {
@@ -41,8 +52,16 @@ pub fn tokenize_v0(input: &[u8]) -> Result<Vec<TokenV0>, TokenizeErrorV0> {
                 push_token_v0(&mut tokens, TokenV0::EndGroup)?;
                 index = next_index;
             }
+            b'$' => {
+                push_token_v0(&mut tokens, TokenV0::MathShift)?;
+                index = next_index;
+            }
+            b'&' => {
+                push_token_v0(&mut tokens, TokenV0::AlignTab)?;
+                index = next_index;
+            }
             b'\\' => {
-                let parsed = parse_control_seq_v0(input, next_index)?;
+                let parsed = parse_control_seq_v0(input, next_index, at_letter)?;
                 for token in parsed.tokens {
                     push_token_v0(&mut tokens, token)?;
                 }
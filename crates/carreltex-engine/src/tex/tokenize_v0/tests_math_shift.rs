@@ -0,0 +1,25 @@
+use super::{tokenize_v0, TokenV0};
+
+#[test]
+fn dollar_is_math_shift_and_escaped_dollar_stays_a_char() {
+    let tokens = tokenize_v0(b"$x$$\\$").expect("tokenize should succeed");
+    assert_eq!(
+        tokens,
+        vec![
+            TokenV0::MathShift,
+            TokenV0::Char(b'x'),
+            TokenV0::MathShift,
+            TokenV0::MathShift,
+            TokenV0::Char(b'$'),
+        ]
+    );
+}
+
+#[test]
+fn ampersand_is_align_tab_and_escaped_ampersand_stays_a_char() {
+    let tokens = tokenize_v0(b"a&\\&").expect("tokenize should succeed");
+    assert_eq!(
+        tokens,
+        vec![TokenV0::Char(b'a'), TokenV0::AlignTab, TokenV0::Char(b'&')]
+    );
+}
@@ -0,0 +1,578 @@
+use crate::outline_v0::{OutlineCommandV0, MAX_OUTLINE_COMMANDS_V0};
+use crate::{read_u16_at_v0, FontErrorV0, TAG_CFF_V0};
+
+const MAX_CHARSTRING_STACK_V0: usize = 48;
+const MAX_SUBR_DEPTH_V0: usize = 10;
+
+pub(crate) const DICT_CHARSET: u16 = 15;
+pub(crate) const DICT_ENCODING: u16 = 16;
+pub(crate) const DICT_CHARSTRINGS: u16 = 17;
+pub(crate) const DICT_PRIVATE: u16 = 18;
+pub(crate) const DICT_SUBRS: u16 = 19;
+pub(crate) const DICT_FD_ARRAY: u16 = 1236;
+pub(crate) const DICT_FD_SELECT: u16 = 1237;
+
+/// One DICT entry: operator, operands, and the byte range of the whole entry.
+pub(crate) type DictEntryV0 = (u16, Vec<i32>, (usize, usize));
+
+pub(crate) fn invalid_cff_v0() -> FontErrorV0 {
+    FontErrorV0::InvalidTable(TAG_CFF_V0)
+}
+
+/// Absolute byte ranges of the entries of the INDEX at `offset`, and the INDEX end.
+pub(crate) fn read_index_v0(
+    cff: &[u8],
+    offset: usize,
+) -> Result<(Vec<(usize, usize)>, usize), FontErrorV0> {
+    let count = read_u16_at_v0(cff, offset).ok_or_else(invalid_cff_v0)? as usize;
+    if count == 0 {
+        return Ok((Vec::new(), offset + 2));
+    }
+    let off_size = *cff.get(offset + 2).ok_or_else(invalid_cff_v0)? as usize;
+    if !(1..=4).contains(&off_size) {
+        return Err(invalid_cff_v0());
+    }
+    let offsets_at = offset + 3;
+    let data_base = offsets_at + (count + 1) * off_size - 1;
+    let read_offset = |index: usize| -> Result<usize, FontErrorV0> {
+        let at = offsets_at + index * off_size;
+        let bytes = cff.get(at..at + off_size).ok_or_else(invalid_cff_v0)?;
+        let value = bytes
+            .iter()
+            .fold(0usize, |acc, byte| (acc << 8) | *byte as usize);
+        if value == 0 {
+            return Err(invalid_cff_v0());
+        }
+        Ok(data_base + value)
+    };
+    let mut ranges = Vec::with_capacity(count);
+    let mut start = read_offset(0)?;
+    for index in 1..=count {
+        let end = read_offset(index)?;
+        if end < start || end > cff.len() {
+            return Err(invalid_cff_v0());
+        }
+        ranges.push((start, end));
+        start = end;
+    }
+    Ok((ranges, start))
+}
+
+/// DICT entries as `(operator, operands)`; escaped operators are `1200 + b1`.
+/// Real operands only matter for matrices and hinting, so they read as 0.
+pub(crate) fn parse_dict_v0(dict: &[u8]) -> Result<Vec<(u16, Vec<i32>)>, FontErrorV0> {
+    Ok(parse_dict_entries_v0(dict)?
+        .into_iter()
+        .map(|(operator, operands, _)| (operator, operands))
+        .collect())
+}
+
+/// Like `parse_dict_v0`, keeping each entry's byte range so it can be copied as-is.
+pub(crate) fn parse_dict_entries_v0(dict: &[u8]) -> Result<Vec<DictEntryV0>, FontErrorV0> {
+    let mut entries = Vec::new();
+    let mut operands = Vec::<i32>::new();
+    let mut entry_start = 0usize;
+    let mut index = 0usize;
+    while index < dict.len() {
+        let b0 = dict[index];
+        let byte = |at: usize| dict.get(at).copied().ok_or_else(invalid_cff_v0);
+        match b0 {
+            0..=21 => {
+                let operator = if b0 == 12 {
+                    index += 1;
+                    1200 + byte(index)? as u16
+                } else {
+                    b0 as u16
+                };
+                index += 1;
+                entries.push((
+                    operator,
+                    std::mem::take(&mut operands),
+                    (entry_start, index),
+                ));
+                entry_start = index;
+            }
+            28 => {
+                operands.push(i16::from_be_bytes([byte(index + 1)?, byte(index + 2)?]) as i32);
+                index += 3;
+            }
+            29 => {
+                operands.push(i32::from_be_bytes([
+                    byte(index + 1)?,
+                    byte(index + 2)?,
+                    byte(index + 3)?,
+                    byte(index + 4)?,
+                ]));
+                index += 5;
+            }
+            30 => {
+                index += 1;
+                loop {
+                    let nibbles = byte(index)?;
+                    index += 1;
+                    if nibbles & 0x0f == 0x0f || nibbles >> 4 == 0x0f {
+                        break;
+                    }
+                }
+                operands.push(0);
+            }
+            32..=246 => {
+                operands.push(b0 as i32 - 139);
+                index += 1;
+            }
+            247..=250 => {
+                operands.push((b0 as i32 - 247) * 256 + byte(index + 1)? as i32 + 108);
+                index += 2;
+            }
+            251..=254 => {
+                operands.push(-(b0 as i32 - 251) * 256 - byte(index + 1)? as i32 - 108);
+                index += 2;
+            }
+            _ => return Err(invalid_cff_v0()),
+        }
+    }
+    Ok(entries)
+}
+
+pub(crate) fn dict_operands_v0(entries: &[(u16, Vec<i32>)], operator: u16) -> Option<&[i32]> {
+    entries
+        .iter()
+        .find(|(candidate, _)| *candidate == operator)
+        .map(|(_, operands)| operands.as_slice())
+}
+
+pub(crate) fn dict_offset_v0(
+    entries: &[(u16, Vec<i32>)],
+    operator: u16,
+) -> Result<Option<usize>, FontErrorV0> {
+    match dict_operands_v0(entries, operator) {
+        Some([value]) if *value >= 0 => Ok(Some(*value as usize)),
+        Some(_) => Err(invalid_cff_v0()),
+        None => Ok(None),
+    }
+}
+
+/// Local subrs of the Private DICT described by `entries` (a Top or Font DICT).
+fn private_subrs_v0(
+    cff: &[u8],
+    entries: &[(u16, Vec<i32>)],
+) -> Result<Vec<(usize, usize)>, FontErrorV0> {
+    let Some(private) = dict_operands_v0(entries, DICT_PRIVATE) else {
+        return Ok(Vec::new());
+    };
+    let [size, offset] = private else {
+        return Err(invalid_cff_v0());
+    };
+    if *size < 0 || *offset < 0 {
+        return Err(invalid_cff_v0());
+    }
+    let (start, size) = (*offset as usize, *size as usize);
+    let private_dict = cff.get(start..start + size).ok_or_else(invalid_cff_v0)?;
+    match dict_offset_v0(&parse_dict_v0(private_dict)?, DICT_SUBRS)? {
+        Some(subrs) => Ok(read_index_v0(cff, start + subrs)?.0),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn fd_index_v0(
+    cff: &[u8],
+    fd_select: usize,
+    glyph_id: u16,
+    glyph_count: usize,
+) -> Result<usize, FontErrorV0> {
+    let format = *cff.get(fd_select).ok_or_else(invalid_cff_v0)?;
+    match format {
+        0 => cff
+            .get(fd_select + 1 + glyph_id as usize)
+            .map(|fd| *fd as usize)
+            .ok_or_else(invalid_cff_v0),
+        3 => {
+            let range_count =
+                read_u16_at_v0(cff, fd_select + 1).ok_or_else(invalid_cff_v0)? as usize;
+            for range_index in 0..range_count {
+                let at = fd_select + 3 + range_index * 3;
+                let first = read_u16_at_v0(cff, at).ok_or_else(invalid_cff_v0)?;
+                let next = read_u16_at_v0(cff, at + 3).ok_or_else(invalid_cff_v0)?;
+                if (first..next).contains(&glyph_id) && (glyph_id as usize) < glyph_count {
+                    return Ok(*cff.get(at + 2).ok_or_else(invalid_cff_v0)? as usize);
+                }
+            }
+            Err(invalid_cff_v0())
+        }
+        _ => Err(invalid_cff_v0()),
+    }
+}
+
+fn subr_bias_v0(count: usize) -> i32 {
+    if count < 1240 {
+        107
+    } else if count < 33_900 {
+        1131
+    } else {
+        32_768
+    }
+}
+
+struct CharstringStateV0<'a> {
+    cff: &'a [u8],
+    global_subrs: &'a [(usize, usize)],
+    local_subrs: &'a [(usize, usize)],
+    stack: Vec<f64>,
+    x: f64,
+    y: f64,
+    stem_count: usize,
+    width_parsed: bool,
+    open: bool,
+    out: Vec<OutlineCommandV0>,
+}
+
+impl CharstringStateV0<'_> {
+    fn push_v0(&mut self, command: OutlineCommandV0) -> Result<(), FontErrorV0> {
+        if self.out.len() >= MAX_OUTLINE_COMMANDS_V0 {
+            return Err(invalid_cff_v0());
+        }
+        self.out.push(command);
+        Ok(())
+    }
+
+    /// Drop the optional leading advance width from the first stack-clearing operator.
+    fn take_width_v0(&mut self, has_width: bool) {
+        if !self.width_parsed && has_width && !self.stack.is_empty() {
+            self.stack.remove(0);
+        }
+        self.width_parsed = true;
+    }
+
+    fn point_v0(&self) -> (i32, i32) {
+        (self.x.round() as i32, self.y.round() as i32)
+    }
+
+    fn move_to_v0(&mut self, dx: f64, dy: f64) -> Result<(), FontErrorV0> {
+        self.close_v0()?;
+        self.x += dx;
+        self.y += dy;
+        let (x, y) = self.point_v0();
+        self.open = true;
+        self.push_v0(OutlineCommandV0::MoveTo(x, y))
+    }
+
+    fn line_to_v0(&mut self, dx: f64, dy: f64) -> Result<(), FontErrorV0> {
+        self.x += dx;
+        self.y += dy;
+        let (x, y) = self.point_v0();
+        self.push_v0(OutlineCommandV0::LineTo(x, y))
+    }
+
+    fn curve_to_v0(&mut self, deltas: [f64; 6]) -> Result<(), FontErrorV0> {
+        let mut points = [(0i32, 0i32); 3];
+        for (point, pair) in points.iter_mut().zip(deltas.chunks(2)) {
+            self.x += pair[0];
+            self.y += pair[1];
+            *point = self.point_v0();
+        }
+        let [(x1, y1), (x2, y2), (x, y)] = points;
+        self.push_v0(OutlineCommandV0::CubicTo(x1, y1, x2, y2, x, y))
+    }
+
+    fn close_v0(&mut self) -> Result<(), FontErrorV0> {
+        if self.open {
+            self.open = false;
+            self.push_v0(OutlineCommandV0::Close)?;
+        }
+        Ok(())
+    }
+
+    /// `hvcurveto` (`horizontal_first`) and `vhcurveto`: alternating-direction curves.
+    fn alternating_curves_v0(&mut self, mut horizontal_first: bool) -> Result<(), FontErrorV0> {
+        let args = std::mem::take(&mut self.stack);
+        let mut index = 0usize;
+        while index + 4 <= args.len() {
+            let last = if args.len() - index == 5 {
+                args[index + 4]
+            } else {
+                0.0
+            };
+            let a = &args[index..index + 4];
+            let deltas = if horizontal_first {
+                [a[0], 0.0, a[1], a[2], last, a[3]]
+            } else {
+                [0.0, a[0], a[1], a[2], a[3], last]
+            };
+            self.curve_to_v0(deltas)?;
+            horizontal_first = !horizontal_first;
+            index += 4;
+        }
+        Ok(())
+    }
+
+    fn run_v0(&mut self, range: (usize, usize), depth: usize) -> Result<bool, FontErrorV0> {
+        if depth > MAX_SUBR_DEPTH_V0 {
+            return Err(invalid_cff_v0());
+        }
+        let code = self.cff.get(range.0..range.1).ok_or_else(invalid_cff_v0)?;
+        let mut index = 0usize;
+        while index < code.len() {
+            let b0 = code[index];
+            index += 1;
+            let byte = |at: usize| code.get(at).copied().ok_or_else(invalid_cff_v0);
+            let operand = match b0 {
+                28 => {
+                    index += 2;
+                    Some(i16::from_be_bytes([byte(index - 2)?, byte(index - 1)?]) as f64)
+                }
+                32..=246 => Some(b0 as f64 - 139.0),
+                247..=250 => {
+                    index += 1;
+                    Some((b0 as f64 - 247.0) * 256.0 + byte(index - 1)? as f64 + 108.0)
+                }
+                251..=254 => {
+                    index += 1;
+                    Some(-(b0 as f64 - 251.0) * 256.0 - byte(index - 1)? as f64 - 108.0)
+                }
+                255 => {
+                    index += 4;
+                    let fixed = i32::from_be_bytes([
+                        byte(index - 4)?,
+                        byte(index - 3)?,
+                        byte(index - 2)?,
+                        byte(index - 1)?,
+                    ]);
+                    Some(fixed as f64 / 65536.0)
+                }
+                _ => None,
+            };
+            if let Some(value) = operand {
+                if self.stack.len() >= MAX_CHARSTRING_STACK_V0 {
+                    return Err(invalid_cff_v0());
+                }
+                self.stack.push(value);
+                continue;
+            }
+            match b0 {
+                1 | 3 | 18 | 23 => {
+                    self.take_width_v0(self.stack.len() % 2 == 1);
+                    self.stem_count += self.stack.len() / 2;
+                    self.stack.clear();
+                }
+                19 | 20 => {
+                    self.take_width_v0(self.stack.len() % 2 == 1);
+                    self.stem_count += self.stack.len() / 2;
+                    self.stack.clear();
+                    index += self.stem_count.div_ceil(8);
+                }
+                21 => {
+                    self.take_width_v0(self.stack.len() > 2);
+                    let [dx, dy] = self.stack[..] else {
+                        return Err(invalid_cff_v0());
+                    };
+                    self.stack.clear();
+                    self.move_to_v0(dx, dy)?;
+                }
+                22 | 4 => {
+                    self.take_width_v0(self.stack.len() > 1);
+                    let [delta] = self.stack[..] else {
+                        return Err(invalid_cff_v0());
+                    };
+                    self.stack.clear();
+                    if b0 == 22 {
+                        self.move_to_v0(delta, 0.0)?;
+                    } else {
+                        self.move_to_v0(0.0, delta)?;
+                    }
+                }
+                5 => {
+                    let args = std::mem::take(&mut self.stack);
+                    for pair in args.chunks_exact(2) {
+                        self.line_to_v0(pair[0], pair[1])?;
+                    }
+                }
+                6 | 7 => {
+                    let args = std::mem::take(&mut self.stack);
+                    let mut horizontal = b0 == 6;
+                    for delta in args {
+                        if horizontal {
+                            self.line_to_v0(delta, 0.0)?;
+                        } else {
+                            self.line_to_v0(0.0, delta)?;
+                        }
+                        horizontal = !horizontal;
+                    }
+                }
+                8 => {
+                    let args = std::mem::take(&mut self.stack);
+                    for curve in args.chunks_exact(6) {
+                        self.curve_to_v0([
+                            curve[0], curve[1], curve[2], curve[3], curve[4], curve[5],
+                        ])?;
+                    }
+                }
+                24 => {
+                    let args = std::mem::take(&mut self.stack);
+                    if args.len() < 8 {
+                        return Err(invalid_cff_v0());
+                    }
+                    let (curves, line) = args.split_at(args.len() - 2);
+                    for curve in curves.chunks_exact(6) {
+                        self.curve_to_v0([
+                            curve[0], curve[1], curve[2], curve[3], curve[4], curve[5],
+                        ])?;
+                    }
+                    self.line_to_v0(line[0], line[1])?;
+                }
+                25 => {
+                    let args = std::mem::take(&mut self.stack);
+                    if args.len() < 8 {
+                        return Err(invalid_cff_v0());
+                    }
+                    let (lines, curve) = args.split_at(args.len() - 6);
+                    for pair in lines.chunks_exact(2) {
+                        self.line_to_v0(pair[0], pair[1])?;
+                    }
+                    self.curve_to_v0([curve[0], curve[1], curve[2], curve[3], curve[4], curve[5]])?;
+                }
+                26 | 27 => {
+                    let mut args = std::mem::take(&mut self.stack);
+                    let mut first = 0.0;
+                    if args.len() % 4 == 1 {
+                        first = args.remove(0);
+                    }
+                    for curve in args.chunks_exact(4) {
+                        let deltas = if b0 == 26 {
+                            [first, curve[0], curve[1], curve[2], 0.0, curve[3]]
+                        } else {
+                            [curve[0], first, curve[1], curve[2], curve[3], 0.0]
+                        };
+                        self.curve_to_v0(deltas)?;
+                        first = 0.0;
+                    }
+                }
+                30 => self.alternating_curves_v0(false)?,
+                31 => self.alternating_curves_v0(true)?,
+                10 | 29 => {
+                    let subrs = if b0 == 10 {
+                        self.local_subrs
+                    } else {
+                        self.global_subrs
+                    };
+                    let number = self.stack.pop().ok_or_else(invalid_cff_v0)?;
+                    let subr_index = number as i32 + subr_bias_v0(subrs.len());
+                    let range = usize::try_from(subr_index)
+                        .ok()
+                        .and_then(|subr_index| subrs.get(subr_index).copied())
+                        .ok_or_else(invalid_cff_v0)?;
+                    if self.run_v0(range, depth + 1)? {
+                        return Ok(true);
+                    }
+                }
+                11 => return Ok(false),
+                14 => {
+                    // Four remaining operands would be the deprecated seac accent form.
+                    self.take_width_v0(self.stack.len() % 2 == 1);
+                    if !self.stack.is_empty() {
+                        return Err(invalid_cff_v0());
+                    }
+                    self.close_v0()?;
+                    return Ok(true);
+                }
+                12 => {
+                    let escape = byte(index)?;
+                    index += 1;
+                    let args = std::mem::take(&mut self.stack);
+                    match (escape, args.len()) {
+                        (35, 13) => {
+                            self.curve_to_v0([
+                                args[0], args[1], args[2], args[3], args[4], args[5],
+                            ])?;
+                            self.curve_to_v0([
+                                args[6], args[7], args[8], args[9], args[10], args[11],
+                            ])?;
+                        }
+                        (34, 7) => {
+                            let start_y = self.y;
+                            self.curve_to_v0([args[0], 0.0, args[1], args[2], args[3], 0.0])?;
+                            self.curve_to_v0([
+                                args[4],
+                                0.0,
+                                args[5],
+                                start_y - self.y,
+                                args[6],
+                                0.0,
+                            ])?;
+                        }
+                        (36, 9) => {
+                            let start_y = self.y;
+                            self.curve_to_v0([args[0], args[1], args[2], args[3], args[4], 0.0])?;
+                            let dy = start_y - (self.y + args[7]);
+                            self.curve_to_v0([args[5], 0.0, args[6], args[7], args[8], dy])?;
+                        }
+                        (37, 11) => {
+                            let (start_x, start_y) = (self.x, self.y);
+                            let dx = args[0] + args[2] + args[4] + args[6] + args[8];
+                            let dy = args[1] + args[3] + args[5] + args[7] + args[9];
+                            self.curve_to_v0([
+                                args[0], args[1], args[2], args[3], args[4], args[5],
+                            ])?;
+                            let (last_x, last_y) = if dx.abs() > dy.abs() {
+                                (args[10], start_y - (self.y + args[7] + args[9]))
+                            } else {
+                                (start_x - (self.x + args[6] + args[8]), args[10])
+                            };
+                            self.curve_to_v0([args[6], args[7], args[8], args[9], last_x, last_y])?;
+                        }
+                        _ => return Err(invalid_cff_v0()),
+                    }
+                }
+                _ => return Err(invalid_cff_v0()),
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Outline of `glyph_id` from a `CFF ` table (name-keyed or CID-keyed), from its
+/// Type 2 charstring with local and global subroutines.
+pub(crate) fn cff_outline_v0(
+    cff: &[u8],
+    glyph_id: u16,
+) -> Result<Vec<OutlineCommandV0>, FontErrorV0> {
+    let header_size = *cff.get(2).ok_or_else(invalid_cff_v0)? as usize;
+    let (_, names_end) = read_index_v0(cff, header_size)?;
+    let (top_dicts, top_end) = read_index_v0(cff, names_end)?;
+    let (_, strings_end) = read_index_v0(cff, top_end)?;
+    let (global_subrs, _) = read_index_v0(cff, strings_end)?;
+    let &(top_start, top_stop) = top_dicts.first().ok_or_else(invalid_cff_v0)?;
+    let top = parse_dict_v0(&cff[top_start..top_stop])?;
+    let charstrings_at = dict_offset_v0(&top, DICT_CHARSTRINGS)?.ok_or_else(invalid_cff_v0)?;
+    let (charstrings, _) = read_index_v0(cff, charstrings_at)?;
+    let range = *charstrings
+        .get(glyph_id as usize)
+        .ok_or(FontErrorV0::GlyphIdInvalid)?;
+    let local_subrs = match (
+        dict_offset_v0(&top, DICT_FD_ARRAY)?,
+        dict_offset_v0(&top, DICT_FD_SELECT)?,
+    ) {
+        (Some(fd_array), Some(fd_select)) => {
+            let (font_dicts, _) = read_index_v0(cff, fd_array)?;
+            let fd = fd_index_v0(cff, fd_select, glyph_id, charstrings.len())?;
+            let &(start, stop) = font_dicts.get(fd).ok_or_else(invalid_cff_v0)?;
+            private_subrs_v0(cff, &parse_dict_v0(&cff[start..stop])?)?
+        }
+        _ => private_subrs_v0(cff, &top)?,
+    };
+    let mut state = CharstringStateV0 {
+        cff,
+        global_subrs: &global_subrs,
+        local_subrs: &local_subrs,
+        stack: Vec::new(),
+        x: 0.0,
+        y: 0.0,
+        stem_count: 0,
+        width_parsed: false,
+        open: false,
+        out: Vec::new(),
+    };
+    if !state.run_v0(range, 0)? {
+        return Err(invalid_cff_v0());
+    }
+    Ok(state.out)
+}
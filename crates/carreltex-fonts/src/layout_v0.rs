@@ -0,0 +1,496 @@
+use crate::{
+    read_i16_at_v0, read_u16_at_v0, read_u32_at_v0, FontErrorV0, TAG_GPOS_V0, TAG_GSUB_V0,
+};
+
+const FEATURE_KERN_V0: [u8; 4] = *b"kern";
+const FEATURE_LIGA_V0: [u8; 4] = *b"liga";
+const GPOS_PAIR_ADJUSTMENT_V0: u16 = 2;
+const GPOS_EXTENSION_V0: u16 = 9;
+const GSUB_LIGATURE_V0: u16 = 4;
+const GSUB_EXTENSION_V0: u16 = 7;
+const VALUE_FORMAT_X_ADVANCE_V0: u16 = 0x0004;
+pub(crate) const MAX_LAYOUT_SUBTABLES_V0: usize = 4096;
+
+#[derive(Debug, Clone)]
+enum CoverageV0 {
+    Glyphs(Vec<u16>),
+    Ranges(Vec<(u16, u16, u16)>),
+}
+
+impl CoverageV0 {
+    fn index_v0(&self, glyph_id: u16) -> Option<usize> {
+        match self {
+            CoverageV0::Glyphs(glyphs) => {
+                glyphs.iter().position(|candidate| *candidate == glyph_id)
+            }
+            CoverageV0::Ranges(ranges) => ranges
+                .iter()
+                .find(|(start, end, _)| *start <= glyph_id && glyph_id <= *end)
+                .map(|(start, _, start_index)| (*start_index + (glyph_id - start)) as usize),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ClassDefV0 {
+    Array { start: u16, classes: Vec<u16> },
+    Ranges(Vec<(u16, u16, u16)>),
+}
+
+impl ClassDefV0 {
+    fn class_v0(&self, glyph_id: u16) -> u16 {
+        match self {
+            ClassDefV0::Array { start, classes } => glyph_id
+                .checked_sub(*start)
+                .and_then(|offset| classes.get(offset as usize).copied())
+                .unwrap_or(0),
+            ClassDefV0::Ranges(ranges) => ranges
+                .iter()
+                .find(|(start, end, _)| *start <= glyph_id && glyph_id <= *end)
+                .map(|(_, _, class)| *class)
+                .unwrap_or(0),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum PairSubtableV0 {
+    Pairs {
+        coverage: CoverageV0,
+        pair_sets: Vec<Vec<(u16, i16)>>,
+    },
+    Classes {
+        coverage: CoverageV0,
+        class_def1: ClassDefV0,
+        class_def2: ClassDefV0,
+        class1_count: u16,
+        class2_count: u16,
+        x_advances: Vec<i16>,
+    },
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct KerningV0 {
+    lookups: Vec<Vec<PairSubtableV0>>,
+}
+
+impl KerningV0 {
+    pub(crate) fn lookup_v0(&self, left: u16, right: u16) -> i16 {
+        let mut total = 0i16;
+        for subtables in &self.lookups {
+            for subtable in subtables {
+                if let Some(adjustment) = pair_subtable_value_v0(subtable, left, right) {
+                    total = total.saturating_add(adjustment);
+                    break;
+                }
+            }
+        }
+        total
+    }
+}
+
+fn pair_subtable_value_v0(subtable: &PairSubtableV0, left: u16, right: u16) -> Option<i16> {
+    match subtable {
+        PairSubtableV0::Pairs {
+            coverage,
+            pair_sets,
+        } => pair_sets
+            .get(coverage.index_v0(left)?)?
+            .iter()
+            .find(|(second, _)| *second == right)
+            .map(|(_, adjustment)| *adjustment),
+        PairSubtableV0::Classes {
+            coverage,
+            class_def1,
+            class_def2,
+            class1_count,
+            class2_count,
+            x_advances,
+        } => {
+            coverage.index_v0(left)?;
+            let class1 = class_def1.class_v0(left);
+            let class2 = class_def2.class_v0(right);
+            if class1 >= *class1_count || class2 >= *class2_count {
+                return None;
+            }
+            x_advances
+                .get(class1 as usize * *class2_count as usize + class2 as usize)
+                .copied()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LigatureSubtableV0 {
+    coverage: CoverageV0,
+    ligature_sets: Vec<Vec<(Vec<u16>, u16)>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LigaturesV0 {
+    lookups: Vec<Vec<LigatureSubtableV0>>,
+}
+
+impl LigaturesV0 {
+    pub(crate) fn lookup_v0(&self, glyph_ids: &[u16]) -> Option<(u16, usize)> {
+        let first = *glyph_ids.first()?;
+        for subtables in &self.lookups {
+            for subtable in subtables {
+                let Some(coverage_index) = subtable.coverage.index_v0(first) else {
+                    continue;
+                };
+                let Some(ligatures) = subtable.ligature_sets.get(coverage_index) else {
+                    continue;
+                };
+                for (components, ligature_glyph) in ligatures {
+                    if glyph_ids.len() > components.len()
+                        && glyph_ids[1..=components.len()] == components[..]
+                    {
+                        return Some((*ligature_glyph, components.len() + 1));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn parse_coverage_v0(table: &[u8], offset: usize, tag: [u8; 4]) -> Result<CoverageV0, FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(tag);
+    let format = read_u16_at_v0(table, offset).ok_or(invalid)?;
+    let count = read_u16_at_v0(table, offset + 2).ok_or(invalid)? as usize;
+    match format {
+        1 => {
+            let mut glyphs = Vec::<u16>::with_capacity(count);
+            for glyph_index in 0..count {
+                glyphs.push(read_u16_at_v0(table, offset + 4 + glyph_index * 2).ok_or(invalid)?);
+            }
+            Ok(CoverageV0::Glyphs(glyphs))
+        }
+        2 => {
+            let mut ranges = Vec::<(u16, u16, u16)>::with_capacity(count);
+            for range_index in 0..count {
+                let record = offset + 4 + range_index * 6;
+                let start = read_u16_at_v0(table, record).ok_or(invalid)?;
+                let end = read_u16_at_v0(table, record + 2).ok_or(invalid)?;
+                let start_index = read_u16_at_v0(table, record + 4).ok_or(invalid)?;
+                if start > end || start_index.checked_add(end - start).is_none() {
+                    return Err(invalid);
+                }
+                ranges.push((start, end, start_index));
+            }
+            Ok(CoverageV0::Ranges(ranges))
+        }
+        _ => Err(invalid),
+    }
+}
+
+fn parse_class_def_v0(
+    table: &[u8],
+    offset: usize,
+    tag: [u8; 4],
+) -> Result<ClassDefV0, FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(tag);
+    match read_u16_at_v0(table, offset).ok_or(invalid)? {
+        1 => {
+            let start = read_u16_at_v0(table, offset + 2).ok_or(invalid)?;
+            let count = read_u16_at_v0(table, offset + 4).ok_or(invalid)? as usize;
+            let mut classes = Vec::<u16>::with_capacity(count);
+            for class_index in 0..count {
+                classes.push(read_u16_at_v0(table, offset + 6 + class_index * 2).ok_or(invalid)?);
+            }
+            Ok(ClassDefV0::Array { start, classes })
+        }
+        2 => {
+            let count = read_u16_at_v0(table, offset + 2).ok_or(invalid)? as usize;
+            let mut ranges = Vec::<(u16, u16, u16)>::with_capacity(count);
+            for range_index in 0..count {
+                let record = offset + 4 + range_index * 6;
+                let start = read_u16_at_v0(table, record).ok_or(invalid)?;
+                let end = read_u16_at_v0(table, record + 2).ok_or(invalid)?;
+                if start > end {
+                    return Err(invalid);
+                }
+                ranges.push((
+                    start,
+                    end,
+                    read_u16_at_v0(table, record + 4).ok_or(invalid)?,
+                ));
+            }
+            Ok(ClassDefV0::Ranges(ranges))
+        }
+        _ => Err(invalid),
+    }
+}
+
+/// Lookup indices referenced by every `feature_tag` record, in LookupList order.
+fn feature_lookup_indices_v0(
+    table: &[u8],
+    feature_tag: [u8; 4],
+    tag: [u8; 4],
+) -> Result<Vec<u16>, FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(tag);
+    if read_u16_at_v0(table, 0) != Some(1) {
+        return Err(invalid);
+    }
+    let feature_list = read_u16_at_v0(table, 6).ok_or(invalid)? as usize;
+    let feature_count = read_u16_at_v0(table, feature_list).ok_or(invalid)? as usize;
+    let mut indices = Vec::<u16>::new();
+    for feature_index in 0..feature_count {
+        let record = feature_list + 2 + feature_index * 6;
+        let record_tag = table.get(record..record + 4).ok_or(invalid)?;
+        if record_tag != feature_tag {
+            continue;
+        }
+        let feature = feature_list + read_u16_at_v0(table, record + 4).ok_or(invalid)? as usize;
+        let lookup_count = read_u16_at_v0(table, feature + 2).ok_or(invalid)? as usize;
+        for lookup_index in 0..lookup_count {
+            indices.push(read_u16_at_v0(table, feature + 4 + lookup_index * 2).ok_or(invalid)?);
+        }
+    }
+    indices.sort_unstable();
+    indices.dedup();
+    Ok(indices)
+}
+
+/// Resolve `(lookup_type, subtable_offsets)` for one lookup, unwrapping extension subtables.
+fn lookup_subtables_v0(
+    table: &[u8],
+    lookup_index: u16,
+    extension_type: u16,
+    tag: [u8; 4],
+) -> Result<(u16, Vec<usize>), FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(tag);
+    let lookup_list = read_u16_at_v0(table, 8).ok_or(invalid)? as usize;
+    let lookup_count = read_u16_at_v0(table, lookup_list).ok_or(invalid)?;
+    if lookup_index >= lookup_count {
+        return Err(invalid);
+    }
+    let lookup = lookup_list
+        + read_u16_at_v0(table, lookup_list + 2 + lookup_index as usize * 2).ok_or(invalid)?
+            as usize;
+    let mut lookup_type = read_u16_at_v0(table, lookup).ok_or(invalid)?;
+    let subtable_count = read_u16_at_v0(table, lookup + 4).ok_or(invalid)? as usize;
+    let mut offsets = Vec::<usize>::with_capacity(subtable_count);
+    let mut resolved_type = None::<u16>;
+    for subtable_index in 0..subtable_count {
+        let mut subtable = lookup
+            + read_u16_at_v0(table, lookup + 6 + subtable_index * 2).ok_or(invalid)? as usize;
+        if lookup_type == extension_type {
+            if read_u16_at_v0(table, subtable) != Some(1) {
+                return Err(invalid);
+            }
+            let wrapped_type = read_u16_at_v0(table, subtable + 2).ok_or(invalid)?;
+            if resolved_type.is_some_and(|previous| previous != wrapped_type) {
+                return Err(invalid);
+            }
+            resolved_type = Some(wrapped_type);
+            subtable = subtable
+                .checked_add(read_u32_at_v0(table, subtable + 4).ok_or(invalid)? as usize)
+                .ok_or(invalid)?;
+        }
+        offsets.push(subtable);
+    }
+    if let Some(wrapped_type) = resolved_type {
+        lookup_type = wrapped_type;
+    }
+    Ok((lookup_type, offsets))
+}
+
+fn value_record_len_v0(value_format: u16) -> usize {
+    (value_format & 0x00ff).count_ones() as usize * 2
+}
+
+fn x_advance_in_record_v0(
+    table: &[u8],
+    record: usize,
+    value_format: u16,
+    tag: [u8; 4],
+) -> Result<i16, FontErrorV0> {
+    if value_format & VALUE_FORMAT_X_ADVANCE_V0 == 0 {
+        return Ok(0);
+    }
+    let field = (value_format & 0x0003).count_ones() as usize * 2;
+    read_i16_at_v0(table, record + field).ok_or(FontErrorV0::InvalidTable(tag))
+}
+
+fn parse_pair_subtable_v0(table: &[u8], subtable: usize) -> Result<PairSubtableV0, FontErrorV0> {
+    let tag = TAG_GPOS_V0;
+    let invalid = FontErrorV0::InvalidTable(tag);
+    let format = read_u16_at_v0(table, subtable).ok_or(invalid)?;
+    let coverage = parse_coverage_v0(
+        table,
+        subtable + read_u16_at_v0(table, subtable + 2).ok_or(invalid)? as usize,
+        tag,
+    )?;
+    let value_format1 = read_u16_at_v0(table, subtable + 4).ok_or(invalid)?;
+    let value_format2 = read_u16_at_v0(table, subtable + 6).ok_or(invalid)?;
+    let record1_len = value_record_len_v0(value_format1);
+    let record2_len = value_record_len_v0(value_format2);
+    match format {
+        1 => {
+            let pair_set_count = read_u16_at_v0(table, subtable + 8).ok_or(invalid)? as usize;
+            let mut pair_sets = Vec::<Vec<(u16, i16)>>::with_capacity(pair_set_count);
+            for set_index in 0..pair_set_count {
+                let pair_set = subtable
+                    + read_u16_at_v0(table, subtable + 10 + set_index * 2).ok_or(invalid)? as usize;
+                let pair_count = read_u16_at_v0(table, pair_set).ok_or(invalid)? as usize;
+                let stride = 2 + record1_len + record2_len;
+                let mut pairs = Vec::<(u16, i16)>::with_capacity(pair_count);
+                for pair_index in 0..pair_count {
+                    let record = pair_set + 2 + pair_index * stride;
+                    let second = read_u16_at_v0(table, record).ok_or(invalid)?;
+                    let adjustment = x_advance_in_record_v0(table, record + 2, value_format1, tag)?;
+                    pairs.push((second, adjustment));
+                }
+                pair_sets.push(pairs);
+            }
+            Ok(PairSubtableV0::Pairs {
+                coverage,
+                pair_sets,
+            })
+        }
+        2 => {
+            let class_def1 = parse_class_def_v0(
+                table,
+                subtable + read_u16_at_v0(table, subtable + 8).ok_or(invalid)? as usize,
+                tag,
+            )?;
+            let class_def2 = parse_class_def_v0(
+                table,
+                subtable + read_u16_at_v0(table, subtable + 10).ok_or(invalid)? as usize,
+                tag,
+            )?;
+            let class1_count = read_u16_at_v0(table, subtable + 12).ok_or(invalid)?;
+            let class2_count = read_u16_at_v0(table, subtable + 14).ok_or(invalid)?;
+            let stride = record1_len + record2_len;
+            let cells = class1_count as usize * class2_count as usize;
+            if stride == 0 || table.len() < subtable + 16 + cells * stride {
+                return Err(invalid);
+            }
+            let mut x_advances = Vec::<i16>::with_capacity(cells);
+            for cell in 0..cells {
+                let record = subtable + 16 + cell * stride;
+                x_advances.push(x_advance_in_record_v0(table, record, value_format1, tag)?);
+            }
+            Ok(PairSubtableV0::Classes {
+                coverage,
+                class_def1,
+                class_def2,
+                class1_count,
+                class2_count,
+                x_advances,
+            })
+        }
+        _ => Err(invalid),
+    }
+}
+
+fn validate_glyph_id_v0(glyph_id: u16, num_glyphs: u16, tag: [u8; 4]) -> Result<u16, FontErrorV0> {
+    if glyph_id >= num_glyphs {
+        return Err(FontErrorV0::InvalidTable(tag));
+    }
+    Ok(glyph_id)
+}
+
+fn parse_ligature_subtable_v0(
+    table: &[u8],
+    subtable: usize,
+    num_glyphs: u16,
+) -> Result<LigatureSubtableV0, FontErrorV0> {
+    let tag = TAG_GSUB_V0;
+    let invalid = FontErrorV0::InvalidTable(tag);
+    if read_u16_at_v0(table, subtable) != Some(1) {
+        return Err(invalid);
+    }
+    let coverage = parse_coverage_v0(
+        table,
+        subtable + read_u16_at_v0(table, subtable + 2).ok_or(invalid)? as usize,
+        tag,
+    )?;
+    let set_count = read_u16_at_v0(table, subtable + 4).ok_or(invalid)? as usize;
+    let mut ligature_sets = Vec::<Vec<(Vec<u16>, u16)>>::with_capacity(set_count);
+    for set_index in 0..set_count {
+        let ligature_set =
+            subtable + read_u16_at_v0(table, subtable + 6 + set_index * 2).ok_or(invalid)? as usize;
+        let ligature_count = read_u16_at_v0(table, ligature_set).ok_or(invalid)? as usize;
+        let mut ligatures = Vec::<(Vec<u16>, u16)>::with_capacity(ligature_count);
+        for ligature_index in 0..ligature_count {
+            let ligature = ligature_set
+                + read_u16_at_v0(table, ligature_set + 2 + ligature_index * 2).ok_or(invalid)?
+                    as usize;
+            let ligature_glyph = validate_glyph_id_v0(
+                read_u16_at_v0(table, ligature).ok_or(invalid)?,
+                num_glyphs,
+                tag,
+            )?;
+            let component_count = read_u16_at_v0(table, ligature + 2).ok_or(invalid)? as usize;
+            if component_count < 2 {
+                return Err(invalid);
+            }
+            let mut components = Vec::<u16>::with_capacity(component_count - 1);
+            for component_index in 0..component_count - 1 {
+                components.push(
+                    read_u16_at_v0(table, ligature + 4 + component_index * 2).ok_or(invalid)?,
+                );
+            }
+            ligatures.push((components, ligature_glyph));
+        }
+        ligature_sets.push(ligatures);
+    }
+    Ok(LigatureSubtableV0 {
+        coverage,
+        ligature_sets,
+    })
+}
+
+/// GPOS `kern` feature pair adjustments (lookup type 2, optionally via extension type 9).
+pub(crate) fn parse_gpos_kerning_v0(table: &[u8]) -> Result<KerningV0, FontErrorV0> {
+    let tag = TAG_GPOS_V0;
+    let mut lookups = Vec::<Vec<PairSubtableV0>>::new();
+    let mut subtable_total = 0usize;
+    for lookup_index in feature_lookup_indices_v0(table, FEATURE_KERN_V0, tag)? {
+        let (lookup_type, offsets) =
+            lookup_subtables_v0(table, lookup_index, GPOS_EXTENSION_V0, tag)?;
+        if lookup_type != GPOS_PAIR_ADJUSTMENT_V0 {
+            continue;
+        }
+        subtable_total += offsets.len();
+        if subtable_total > MAX_LAYOUT_SUBTABLES_V0 {
+            return Err(FontErrorV0::InvalidTable(tag));
+        }
+        let mut subtables = Vec::<PairSubtableV0>::with_capacity(offsets.len());
+        for offset in offsets {
+            subtables.push(parse_pair_subtable_v0(table, offset)?);
+        }
+        lookups.push(subtables);
+    }
+    Ok(KerningV0 { lookups })
+}
+
+/// GSUB `liga` feature ligatures (lookup type 4, optionally via extension type 7).
+pub(crate) fn parse_gsub_ligatures_v0(
+    table: &[u8],
+    num_glyphs: u16,
+) -> Result<LigaturesV0, FontErrorV0> {
+    let tag = TAG_GSUB_V0;
+    let mut lookups = Vec::<Vec<LigatureSubtableV0>>::new();
+    let mut subtable_total = 0usize;
+    for lookup_index in feature_lookup_indices_v0(table, FEATURE_LIGA_V0, tag)? {
+        let (lookup_type, offsets) =
+            lookup_subtables_v0(table, lookup_index, GSUB_EXTENSION_V0, tag)?;
+        if lookup_type != GSUB_LIGATURE_V0 {
+            continue;
+        }
+        subtable_total += offsets.len();
+        if subtable_total > MAX_LAYOUT_SUBTABLES_V0 {
+            return Err(FontErrorV0::InvalidTable(tag));
+        }
+        let mut subtables = Vec::<LigatureSubtableV0>::with_capacity(offsets.len());
+        for offset in offsets {
+            subtables.push(parse_ligature_subtable_v0(table, offset, num_glyphs)?);
+        }
+        lookups.push(subtables);
+    }
+    Ok(LigaturesV0 { lookups })
+}
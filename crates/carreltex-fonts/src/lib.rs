@@ -0,0 +1,524 @@
+mod cff_v0;
+mod layout_v0;
+mod outline_v0;
+mod subset_v0;
+mod tables_v0;
+
+use std::collections::BTreeSet;
+
+use cff_v0::cff_outline_v0;
+use layout_v0::{parse_gpos_kerning_v0, parse_gsub_ligatures_v0, KerningV0, LigaturesV0};
+use outline_v0::glyf_outline_v0;
+use subset_v0::{subset_cff_v0, subset_glyf_v0};
+use tables_v0::{
+    parse_cmap_v0, parse_head_v0, parse_hhea_v0, parse_hmtx_v0, parse_maxp_v0, parse_name_v0,
+    CmapV0, FontNamesV0,
+};
+
+pub use outline_v0::{OutlineCommandV0, MAX_OUTLINE_COMMANDS_V0};
+
+pub const MAX_FONT_BYTES_V0: usize = 16 * 1024 * 1024;
+pub const MAX_FONT_TABLES_V0: usize = 64;
+pub const MAX_SHAPED_GLYPHS_V0: usize = 65_536;
+
+const SFNT_VERSION_TRUETYPE_V0: u32 = 0x0001_0000;
+const SFNT_VERSION_OPENTYPE_CFF_V0: u32 = 0x4f54_544f;
+const SFNT_VERSION_APPLE_TRUE_V0: u32 = 0x7472_7565;
+
+pub const TAG_CMAP_V0: [u8; 4] = *b"cmap";
+pub const TAG_HEAD_V0: [u8; 4] = *b"head";
+pub const TAG_HHEA_V0: [u8; 4] = *b"hhea";
+pub const TAG_HMTX_V0: [u8; 4] = *b"hmtx";
+pub const TAG_MAXP_V0: [u8; 4] = *b"maxp";
+pub const TAG_NAME_V0: [u8; 4] = *b"name";
+pub const TAG_GPOS_V0: [u8; 4] = *b"GPOS";
+pub const TAG_GSUB_V0: [u8; 4] = *b"GSUB";
+pub const TAG_GLYF_V0: [u8; 4] = *b"glyf";
+pub const TAG_LOCA_V0: [u8; 4] = *b"loca";
+pub const TAG_CFF_V0: [u8; 4] = *b"CFF ";
+
+/// Tables copied unchanged into subset fonts (hinting programs included, layout
+/// tables dropped).
+const SUBSET_COPIED_TABLES_V0: [[u8; 4]; 11] = [
+    TAG_HEAD_V0,
+    TAG_HHEA_V0,
+    TAG_HMTX_V0,
+    TAG_MAXP_V0,
+    TAG_CMAP_V0,
+    TAG_NAME_V0,
+    *b"OS/2",
+    *b"post",
+    *b"cvt ",
+    *b"fpgm",
+    *b"prep",
+];
+
+/// `(tag, offset, length)` of one table directory entry.
+type TableRecordV0 = ([u8; 4], usize, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontErrorV0 {
+    TooLarge,
+    Truncated,
+    SfntVersionUnsupported,
+    TooManyTables,
+    DuplicateTable([u8; 4]),
+    TableOutOfBounds([u8; 4]),
+    MissingTable([u8; 4]),
+    InvalidTable([u8; 4]),
+    CmapUnsupported,
+    GlyphIdInvalid,
+}
+
+impl FontErrorV0 {
+    /// Stable fail-closed reason token for compile logs.
+    pub fn reason_v0(&self) -> &'static str {
+        match self {
+            FontErrorV0::TooLarge => "font_too_large",
+            FontErrorV0::Truncated => "font_truncated",
+            FontErrorV0::SfntVersionUnsupported => "font_sfnt_version_unsupported",
+            FontErrorV0::TooManyTables => "font_too_many_tables",
+            FontErrorV0::DuplicateTable(_) => "font_table_duplicate",
+            FontErrorV0::TableOutOfBounds(_) => "font_table_out_of_bounds",
+            FontErrorV0::MissingTable(_) => "font_table_missing",
+            FontErrorV0::InvalidTable(_) => "font_table_invalid",
+            FontErrorV0::CmapUnsupported => "font_cmap_unsupported",
+            FontErrorV0::GlyphIdInvalid => "font_glyph_id_invalid",
+        }
+    }
+
+    pub fn table_tag_v0(&self) -> Option<[u8; 4]> {
+        match self {
+            FontErrorV0::DuplicateTable(tag)
+            | FontErrorV0::TableOutOfBounds(tag)
+            | FontErrorV0::MissingTable(tag)
+            | FontErrorV0::InvalidTable(tag) => Some(*tag),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineFormatV0 {
+    TrueType,
+    Cff,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShapedGlyphV0 {
+    pub glyph_id: u16,
+    /// Advance in font design units, including any GPOS pair adjustment.
+    pub x_advance: i32,
+    /// Number of input chars consumed (greater than one for ligatures).
+    pub cluster_len: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct FontV0 {
+    outline_format: OutlineFormatV0,
+    units_per_em: u16,
+    num_glyphs: u16,
+    index_to_loc_format: i16,
+    bounding_box: [i16; 4],
+    ascender: i16,
+    descender: i16,
+    line_gap: i16,
+    advances: Vec<u16>,
+    cmap: CmapV0,
+    names: FontNamesV0,
+    kerning: KerningV0,
+    ligatures: LigaturesV0,
+    tables: Vec<TableRecordV0>,
+}
+
+pub(crate) fn read_u16_at_v0(bytes: &[u8], offset: usize) -> Option<u16> {
+    let end = offset.checked_add(2)?;
+    let slice = bytes.get(offset..end)?;
+    Some(u16::from_be_bytes([slice[0], slice[1]]))
+}
+
+pub(crate) fn read_i16_at_v0(bytes: &[u8], offset: usize) -> Option<i16> {
+    read_u16_at_v0(bytes, offset).map(|value| value as i16)
+}
+
+pub(crate) fn read_u32_at_v0(bytes: &[u8], offset: usize) -> Option<u32> {
+    let end = offset.checked_add(4)?;
+    let slice = bytes.get(offset..end)?;
+    Some(u32::from_be_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+fn read_table_directory_v0(
+    bytes: &[u8],
+) -> Result<(OutlineFormatV0, Vec<TableRecordV0>), FontErrorV0> {
+    let version = read_u32_at_v0(bytes, 0).ok_or(FontErrorV0::Truncated)?;
+    let outline_format = match version {
+        SFNT_VERSION_TRUETYPE_V0 | SFNT_VERSION_APPLE_TRUE_V0 => OutlineFormatV0::TrueType,
+        SFNT_VERSION_OPENTYPE_CFF_V0 => OutlineFormatV0::Cff,
+        _ => return Err(FontErrorV0::SfntVersionUnsupported),
+    };
+    let num_tables = read_u16_at_v0(bytes, 4).ok_or(FontErrorV0::Truncated)? as usize;
+    if num_tables > MAX_FONT_TABLES_V0 {
+        return Err(FontErrorV0::TooManyTables);
+    }
+    let mut tables = Vec::<TableRecordV0>::new();
+    for table_index in 0..num_tables {
+        let record = 12 + table_index * 16;
+        let tag_bytes = bytes
+            .get(record..record + 4)
+            .ok_or(FontErrorV0::Truncated)?;
+        let tag = [tag_bytes[0], tag_bytes[1], tag_bytes[2], tag_bytes[3]];
+        let offset = read_u32_at_v0(bytes, record + 8).ok_or(FontErrorV0::Truncated)? as usize;
+        let length = read_u32_at_v0(bytes, record + 12).ok_or(FontErrorV0::Truncated)? as usize;
+        let end = offset
+            .checked_add(length)
+            .ok_or(FontErrorV0::TableOutOfBounds(tag))?;
+        if end > bytes.len() {
+            return Err(FontErrorV0::TableOutOfBounds(tag));
+        }
+        if tables.iter().any(|(existing, _, _)| *existing == tag) {
+            return Err(FontErrorV0::DuplicateTable(tag));
+        }
+        tables.push((tag, offset, length));
+    }
+    Ok((outline_format, tables))
+}
+
+fn find_table_v0<'a>(bytes: &'a [u8], tables: &[TableRecordV0], tag: [u8; 4]) -> Option<&'a [u8]> {
+    tables
+        .iter()
+        .find(|(candidate, _, _)| *candidate == tag)
+        .and_then(|(_, offset, length)| bytes.get(*offset..*offset + *length))
+}
+
+fn require_table_v0<'a>(
+    bytes: &'a [u8],
+    tables: &[TableRecordV0],
+    tag: [u8; 4],
+) -> Result<&'a [u8], FontErrorV0> {
+    find_table_v0(bytes, tables, tag).ok_or(FontErrorV0::MissingTable(tag))
+}
+
+/// Parse an OpenType/TrueType font (`.otf`/`.ttf`) into glyph mapping and metrics.
+///
+/// Required tables: `head`, `hhea`, `maxp`, `hmtx`, `cmap`, `name`. `GPOS` pair
+/// kerning (`kern` feature) and `GSUB` ligatures (`liga` feature) are optional.
+pub fn parse_font_v0(bytes: &[u8]) -> Result<FontV0, FontErrorV0> {
+    if bytes.len() > MAX_FONT_BYTES_V0 {
+        return Err(FontErrorV0::TooLarge);
+    }
+    let (outline_format, tables) = read_table_directory_v0(bytes)?;
+    let head = parse_head_v0(require_table_v0(bytes, &tables, TAG_HEAD_V0)?)?;
+    let num_glyphs = parse_maxp_v0(require_table_v0(bytes, &tables, TAG_MAXP_V0)?)?;
+    let hhea = parse_hhea_v0(require_table_v0(bytes, &tables, TAG_HHEA_V0)?)?;
+    let advances = parse_hmtx_v0(
+        require_table_v0(bytes, &tables, TAG_HMTX_V0)?,
+        hhea.number_of_h_metrics,
+        num_glyphs,
+    )?;
+    let cmap = parse_cmap_v0(require_table_v0(bytes, &tables, TAG_CMAP_V0)?)?;
+    let names = parse_name_v0(require_table_v0(bytes, &tables, TAG_NAME_V0)?)?;
+    let kerning = match find_table_v0(bytes, &tables, TAG_GPOS_V0) {
+        Some(table) => parse_gpos_kerning_v0(table)?,
+        None => KerningV0::default(),
+    };
+    let ligatures = match find_table_v0(bytes, &tables, TAG_GSUB_V0) {
+        Some(table) => parse_gsub_ligatures_v0(table, num_glyphs)?,
+        None => LigaturesV0::default(),
+    };
+    Ok(FontV0 {
+        outline_format,
+        units_per_em: head.units_per_em,
+        num_glyphs,
+        index_to_loc_format: head.index_to_loc_format,
+        bounding_box: head.bounding_box,
+        ascender: hhea.ascender,
+        descender: hhea.descender,
+        line_gap: hhea.line_gap,
+        advances,
+        cmap,
+        names,
+        kerning,
+        ligatures,
+        tables,
+    })
+}
+
+impl FontV0 {
+    pub fn outline_format_v0(&self) -> OutlineFormatV0 {
+        self.outline_format
+    }
+
+    pub fn units_per_em_v0(&self) -> u16 {
+        self.units_per_em
+    }
+
+    pub fn num_glyphs_v0(&self) -> u16 {
+        self.num_glyphs
+    }
+
+    pub fn index_to_loc_format_v0(&self) -> i16 {
+        self.index_to_loc_format
+    }
+
+    /// `head` bounding box of all glyphs: `[x_min, y_min, x_max, y_max]`.
+    pub fn bounding_box_v0(&self) -> [i16; 4] {
+        self.bounding_box
+    }
+
+    pub fn ascender_v0(&self) -> i16 {
+        self.ascender
+    }
+
+    pub fn descender_v0(&self) -> i16 {
+        self.descender
+    }
+
+    pub fn line_gap_v0(&self) -> i16 {
+        self.line_gap
+    }
+
+    pub fn family_name_v0(&self) -> Option<&str> {
+        self.names.family.as_deref()
+    }
+
+    pub fn subfamily_name_v0(&self) -> Option<&str> {
+        self.names.subfamily.as_deref()
+    }
+
+    pub fn full_name_v0(&self) -> Option<&str> {
+        self.names.full.as_deref()
+    }
+
+    pub fn postscript_name_v0(&self) -> Option<&str> {
+        self.names.postscript.as_deref()
+    }
+
+    /// Byte range (`offset`, `length`) of a table inside the source font bytes.
+    pub fn table_range_v0(&self, tag: [u8; 4]) -> Option<(usize, usize)> {
+        self.tables
+            .iter()
+            .find(|(candidate, _, _)| *candidate == tag)
+            .map(|(_, offset, length)| (*offset, *length))
+    }
+
+    pub fn glyph_id_v0(&self, ch: char) -> Option<u16> {
+        let glyph_id = self.cmap.lookup_v0(ch as u32)?;
+        if glyph_id == 0 || glyph_id >= self.num_glyphs {
+            return None;
+        }
+        Some(glyph_id)
+    }
+
+    pub fn advance_width_v0(&self, glyph_id: u16) -> Option<u16> {
+        if glyph_id >= self.num_glyphs {
+            return None;
+        }
+        self.advances.get(glyph_id as usize).copied()
+    }
+
+    /// GPOS `kern` pair adjustment (x advance of the first glyph) in design units.
+    pub fn kerning_v0(&self, left: u16, right: u16) -> i16 {
+        self.kerning.lookup_v0(left, right)
+    }
+
+    /// First `liga` ligature (in font order) matching the start of `glyph_ids`:
+    /// `(ligature_glyph, consumed)`.
+    pub fn ligature_v0(&self, glyph_ids: &[u16]) -> Option<(u16, usize)> {
+        self.ligatures.lookup_v0(glyph_ids)
+    }
+
+    /// Scale a design-unit length to scaled points at `size_sp` (the font em size).
+    pub fn design_units_to_sp_v0(&self, units: i32, size_sp: i32) -> Option<i32> {
+        if size_sp <= 0 {
+            return None;
+        }
+        let scaled = (units as i64).checked_mul(size_sp as i64)? / self.units_per_em as i64;
+        i32::try_from(scaled).ok()
+    }
+
+    /// Outline of `glyph_id` in design units from the `glyf`/`loca` or `CFF ` table of
+    /// `bytes`, which must be the bytes this font was parsed from. TrueType contours
+    /// come out as quadratic segments, CFF charstrings as cubic ones.
+    pub fn glyph_outline_v0(
+        &self,
+        bytes: &[u8],
+        glyph_id: u16,
+    ) -> Result<Vec<OutlineCommandV0>, FontErrorV0> {
+        if glyph_id >= self.num_glyphs {
+            return Err(FontErrorV0::GlyphIdInvalid);
+        }
+        match self.outline_format {
+            OutlineFormatV0::TrueType => {
+                let glyf = require_table_v0(bytes, &self.tables, TAG_GLYF_V0)?;
+                let loca = require_table_v0(bytes, &self.tables, TAG_LOCA_V0)?;
+                let mut out = Vec::new();
+                glyf_outline_v0(glyf, loca, self.index_to_loc_format, glyph_id, 0, &mut out)?;
+                Ok(out)
+            }
+            OutlineFormatV0::Cff => {
+                cff_outline_v0(require_table_v0(bytes, &self.tables, TAG_CFF_V0)?, glyph_id)
+            }
+        }
+    }
+
+    /// Standalone sfnt keeping the outlines of `glyph_ids` (plus `.notdef`, and
+    /// composite components for TrueType) under their original glyph ids, so the
+    /// result can be embedded and addressed by glyph id. Other glyphs become empty.
+    pub fn subset_font_v0(
+        &self,
+        bytes: &[u8],
+        glyph_ids: &BTreeSet<u16>,
+    ) -> Result<Vec<u8>, FontErrorV0> {
+        let mut tables = Vec::<([u8; 4], Vec<u8>)>::new();
+        for tag in SUBSET_COPIED_TABLES_V0 {
+            if let Some(table) = find_table_v0(bytes, &self.tables, tag) {
+                tables.push((tag, table.to_vec()));
+            }
+        }
+        match self.outline_format {
+            OutlineFormatV0::TrueType => {
+                let (glyf, loca) = subset_glyf_v0(
+                    require_table_v0(bytes, &self.tables, TAG_GLYF_V0)?,
+                    require_table_v0(bytes, &self.tables, TAG_LOCA_V0)?,
+                    self.index_to_loc_format,
+                    self.num_glyphs,
+                    glyph_ids,
+                )?;
+                for (tag, table) in tables.iter_mut() {
+                    if *tag == TAG_HEAD_V0 {
+                        // The rebuilt `loca` always uses the long format.
+                        table[50..52].copy_from_slice(&1i16.to_be_bytes());
+                    }
+                }
+                tables.push((TAG_GLYF_V0, glyf));
+                tables.push((TAG_LOCA_V0, loca));
+            }
+            OutlineFormatV0::Cff => {
+                if glyph_ids.iter().any(|id| *id >= self.num_glyphs) {
+                    return Err(FontErrorV0::GlyphIdInvalid);
+                }
+                let cff = require_table_v0(bytes, &self.tables, TAG_CFF_V0)?;
+                tables.push((TAG_CFF_V0, subset_cff_v0(cff, glyph_ids)?));
+            }
+        }
+        write_sfnt_v0(self.outline_format, &tables)
+    }
+
+    /// Map text to glyphs (missing chars map to `.notdef` = 0), apply `liga`
+    /// ligatures, then attach advances adjusted by `kern` pairs.
+    pub fn shape_text_v0(&self, text: &str) -> Option<Vec<ShapedGlyphV0>> {
+        let chars = text.chars().collect::<Vec<char>>();
+        if chars.len() > MAX_SHAPED_GLYPHS_V0 {
+            return None;
+        }
+        let mapped = chars
+            .iter()
+            .map(|ch| self.glyph_id_v0(*ch).unwrap_or(0))
+            .collect::<Vec<u16>>();
+        let mut shaped = Vec::<ShapedGlyphV0>::new();
+        let mut index = 0usize;
+        while index < mapped.len() {
+            let (glyph_id, consumed) = match self.ligature_v0(&mapped[index..]) {
+                Some(found) => found,
+                None => (mapped[index], 1),
+            };
+            let advance = self.advance_width_v0(glyph_id)? as i32;
+            shaped.push(ShapedGlyphV0 {
+                glyph_id,
+                x_advance: advance,
+                cluster_len: consumed,
+            });
+            index += consumed;
+        }
+        for pair_index in 1..shaped.len() {
+            let adjustment =
+                self.kerning_v0(shaped[pair_index - 1].glyph_id, shaped[pair_index].glyph_id);
+            shaped[pair_index - 1].x_advance += adjustment as i32;
+        }
+        Some(shaped)
+    }
+}
+
+fn sfnt_checksum_v0(bytes: &[u8]) -> u32 {
+    bytes.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// Assemble an sfnt file from `(tag, table_bytes)` pairs.
+///
+/// Tables are written in tag order, 4-byte aligned, with table checksums and the
+/// `head` checkSumAdjustment filled in, so equal inputs give identical bytes.
+pub fn write_sfnt_v0(
+    outline_format: OutlineFormatV0,
+    tables: &[([u8; 4], Vec<u8>)],
+) -> Result<Vec<u8>, FontErrorV0> {
+    if tables.len() > MAX_FONT_TABLES_V0 {
+        return Err(FontErrorV0::TooManyTables);
+    }
+    let mut sorted = tables.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|(tag, _)| *tag);
+    for pair in sorted.windows(2) {
+        if pair[0].0 == pair[1].0 {
+            return Err(FontErrorV0::DuplicateTable(pair[0].0));
+        }
+    }
+    let num_tables = sorted.len() as u16;
+    let entry_selector = if num_tables == 0 {
+        0
+    } else {
+        15 - num_tables.leading_zeros() as u16
+    };
+    let search_range = (1u16 << entry_selector) * 16;
+    let version = match outline_format {
+        OutlineFormatV0::TrueType => SFNT_VERSION_TRUETYPE_V0,
+        OutlineFormatV0::Cff => SFNT_VERSION_OPENTYPE_CFF_V0,
+    };
+    let mut out = Vec::<u8>::new();
+    out.extend_from_slice(&version.to_be_bytes());
+    out.extend_from_slice(&num_tables.to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&entry_selector.to_be_bytes());
+    out.extend_from_slice(&(num_tables * 16 - search_range).to_be_bytes());
+    let mut offset = 12 + sorted.len() * 16;
+    let mut head_offset = None::<usize>;
+    for (tag, table) in &sorted {
+        if *tag == TAG_HEAD_V0 {
+            head_offset = Some(offset);
+        }
+        out.extend_from_slice(tag);
+        out.extend_from_slice(&sfnt_checksum_v0(table).to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(table.len() as u32).to_be_bytes());
+        offset += table.len().next_multiple_of(4);
+    }
+    for (_, table) in &sorted {
+        out.extend_from_slice(table);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+    if out.len() > MAX_FONT_BYTES_V0 {
+        return Err(FontErrorV0::TooLarge);
+    }
+    if let Some(head_offset) = head_offset {
+        if out.len() < head_offset + 12 {
+            return Err(FontErrorV0::InvalidTable(TAG_HEAD_V0));
+        }
+        out[head_offset + 8..head_offset + 12].fill(0);
+        let adjustment = 0xb1b0_afbau32.wrapping_sub(sfnt_checksum_v0(&out));
+        out[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod outline_v0_tests;
+#[cfg(test)]
+mod subset_v0_tests;
+#[cfg(test)]
+mod test_font_v0;
+#[cfg(test)]
+mod test_outline_v0;
+#[cfg(test)]
+mod tests;
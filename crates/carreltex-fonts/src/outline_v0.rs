@@ -0,0 +1,359 @@
+use crate::{
+    read_i16_at_v0, read_u16_at_v0, read_u32_at_v0, FontErrorV0, TAG_GLYF_V0, TAG_LOCA_V0,
+};
+
+pub const MAX_OUTLINE_COMMANDS_V0: usize = 65_536;
+const MAX_COMPOSITE_DEPTH_V0: usize = 8;
+
+const GLYF_ON_CURVE: u8 = 0x01;
+const GLYF_X_SHORT: u8 = 0x02;
+const GLYF_Y_SHORT: u8 = 0x04;
+const GLYF_REPEAT: u8 = 0x08;
+const GLYF_X_SAME_OR_POSITIVE: u8 = 0x10;
+const GLYF_Y_SAME_OR_POSITIVE: u8 = 0x20;
+
+const COMPONENT_ARGS_ARE_WORDS: u16 = 0x0001;
+const COMPONENT_ARGS_ARE_XY: u16 = 0x0002;
+const COMPONENT_HAS_SCALE: u16 = 0x0008;
+const COMPONENT_MORE: u16 = 0x0020;
+const COMPONENT_HAS_XY_SCALE: u16 = 0x0040;
+const COMPONENT_HAS_2X2: u16 = 0x0080;
+
+/// One glyph outline command in font design units (y up).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineCommandV0 {
+    MoveTo(i32, i32),
+    LineTo(i32, i32),
+    QuadTo(i32, i32, i32, i32),
+    CubicTo(i32, i32, i32, i32, i32, i32),
+    Close,
+}
+
+impl OutlineCommandV0 {
+    fn map_points_v0(self, map: impl Fn(i32, i32) -> (i32, i32)) -> OutlineCommandV0 {
+        match self {
+            OutlineCommandV0::MoveTo(x, y) => {
+                let (x, y) = map(x, y);
+                OutlineCommandV0::MoveTo(x, y)
+            }
+            OutlineCommandV0::LineTo(x, y) => {
+                let (x, y) = map(x, y);
+                OutlineCommandV0::LineTo(x, y)
+            }
+            OutlineCommandV0::QuadTo(x1, y1, x, y) => {
+                let (x1, y1) = map(x1, y1);
+                let (x, y) = map(x, y);
+                OutlineCommandV0::QuadTo(x1, y1, x, y)
+            }
+            OutlineCommandV0::CubicTo(x1, y1, x2, y2, x, y) => {
+                let (x1, y1) = map(x1, y1);
+                let (x2, y2) = map(x2, y2);
+                let (x, y) = map(x, y);
+                OutlineCommandV0::CubicTo(x1, y1, x2, y2, x, y)
+            }
+            OutlineCommandV0::Close => OutlineCommandV0::Close,
+        }
+    }
+}
+
+fn invalid_glyf_v0() -> FontErrorV0 {
+    FontErrorV0::InvalidTable(TAG_GLYF_V0)
+}
+
+/// Byte range of `glyph_id` inside `glyf`, from `loca`.
+pub(crate) fn glyf_range_v0(
+    loca: &[u8],
+    index_to_loc_format: i16,
+    glyph_id: u16,
+) -> Result<(usize, usize), FontErrorV0> {
+    let glyph_index = glyph_id as usize;
+    let read = |index: usize| match index_to_loc_format {
+        0 => read_u16_at_v0(loca, index * 2).map(|value| value as usize * 2),
+        _ => read_u32_at_v0(loca, index * 4).map(|value| value as usize),
+    };
+    let start = read(glyph_index).ok_or(FontErrorV0::InvalidTable(TAG_LOCA_V0))?;
+    let end = read(glyph_index + 1).ok_or(FontErrorV0::InvalidTable(TAG_LOCA_V0))?;
+    if end < start {
+        return Err(FontErrorV0::InvalidTable(TAG_LOCA_V0));
+    }
+    Ok((start, end))
+}
+
+fn component_record_len_v0(flags: u16) -> usize {
+    let args = if flags & COMPONENT_ARGS_ARE_WORDS != 0 {
+        4
+    } else {
+        2
+    };
+    let transform = if flags & COMPONENT_HAS_SCALE != 0 {
+        2
+    } else if flags & COMPONENT_HAS_XY_SCALE != 0 {
+        4
+    } else if flags & COMPONENT_HAS_2X2 != 0 {
+        8
+    } else {
+        0
+    };
+    4 + args + transform
+}
+
+/// Component glyph ids referenced by a composite glyph (none for simple glyphs).
+pub(crate) fn glyf_components_v0(glyph: &[u8]) -> Result<Vec<u16>, FontErrorV0> {
+    if glyph.is_empty() || read_i16_at_v0(glyph, 0).ok_or_else(invalid_glyf_v0)? >= 0 {
+        return Ok(Vec::new());
+    }
+    let mut components = Vec::new();
+    let mut offset = 10usize;
+    loop {
+        let flags = read_u16_at_v0(glyph, offset).ok_or_else(invalid_glyf_v0)?;
+        components.push(read_u16_at_v0(glyph, offset + 2).ok_or_else(invalid_glyf_v0)?);
+        offset += component_record_len_v0(flags);
+        if flags & COMPONENT_MORE == 0 {
+            return Ok(components);
+        }
+    }
+}
+
+fn push_command_v0(
+    out: &mut Vec<OutlineCommandV0>,
+    command: OutlineCommandV0,
+) -> Result<(), FontErrorV0> {
+    if out.len() >= MAX_OUTLINE_COMMANDS_V0 {
+        return Err(invalid_glyf_v0());
+    }
+    out.push(command);
+    Ok(())
+}
+
+fn read_coordinates_v0(
+    glyph: &[u8],
+    offset: &mut usize,
+    flags: &[u8],
+    short_flag: u8,
+    same_flag: u8,
+) -> Result<Vec<i32>, FontErrorV0> {
+    let mut value = 0i32;
+    let mut values = Vec::with_capacity(flags.len());
+    for flag in flags {
+        if flag & short_flag != 0 {
+            let delta = *glyph.get(*offset).ok_or_else(invalid_glyf_v0)? as i32;
+            *offset += 1;
+            value += if flag & same_flag != 0 { delta } else { -delta };
+        } else if flag & same_flag == 0 {
+            value += read_i16_at_v0(glyph, *offset).ok_or_else(invalid_glyf_v0)? as i32;
+            *offset += 2;
+        }
+        values.push(value);
+    }
+    Ok(values)
+}
+
+fn simple_glyph_outline_v0(
+    glyph: &[u8],
+    contour_count: usize,
+    out: &mut Vec<OutlineCommandV0>,
+) -> Result<(), FontErrorV0> {
+    let mut end_points = Vec::with_capacity(contour_count);
+    for contour_index in 0..contour_count {
+        let end = read_u16_at_v0(glyph, 10 + contour_index * 2).ok_or_else(invalid_glyf_v0)?;
+        if end_points
+            .last()
+            .is_some_and(|previous| end as usize <= *previous)
+        {
+            return Err(invalid_glyf_v0());
+        }
+        end_points.push(end as usize);
+    }
+    let point_count = end_points.last().map(|end| end + 1).unwrap_or(0);
+    let instructions_at = 10 + contour_count * 2;
+    let instruction_len = read_u16_at_v0(glyph, instructions_at).ok_or_else(invalid_glyf_v0)?;
+    let mut offset = instructions_at + 2 + instruction_len as usize;
+    let mut flags = Vec::<u8>::with_capacity(point_count);
+    while flags.len() < point_count {
+        let flag = *glyph.get(offset).ok_or_else(invalid_glyf_v0)?;
+        offset += 1;
+        flags.push(flag);
+        if flag & GLYF_REPEAT != 0 {
+            let repeat = *glyph.get(offset).ok_or_else(invalid_glyf_v0)?;
+            offset += 1;
+            for _ in 0..repeat {
+                flags.push(flag);
+            }
+        }
+    }
+    flags.truncate(point_count);
+    let xs = read_coordinates_v0(
+        glyph,
+        &mut offset,
+        &flags,
+        GLYF_X_SHORT,
+        GLYF_X_SAME_OR_POSITIVE,
+    )?;
+    let ys = read_coordinates_v0(
+        glyph,
+        &mut offset,
+        &flags,
+        GLYF_Y_SHORT,
+        GLYF_Y_SAME_OR_POSITIVE,
+    )?;
+    let mut start = 0usize;
+    for end in end_points {
+        let points = (start..=end)
+            .map(|index| (xs[index], ys[index], flags[index] & GLYF_ON_CURVE != 0))
+            .collect::<Vec<_>>();
+        start = end + 1;
+        quadratic_contour_v0(&points, out)?;
+    }
+    Ok(())
+}
+
+/// Emit one TrueType contour, inserting the implied on-curve midpoints between
+/// consecutive off-curve points.
+fn quadratic_contour_v0(
+    points: &[(i32, i32, bool)],
+    out: &mut Vec<OutlineCommandV0>,
+) -> Result<(), FontErrorV0> {
+    let midpoint = |a: (i32, i32, bool), b: (i32, i32, bool)| {
+        ((a.0 + b.0).div_euclid(2), (a.1 + b.1).div_euclid(2))
+    };
+    let Some(first) = points.first().copied() else {
+        return Ok(());
+    };
+    let last = points[points.len() - 1];
+    let (start, rest) = if first.2 {
+        ((first.0, first.1), &points[1..])
+    } else if last.2 {
+        ((last.0, last.1), &points[..points.len() - 1])
+    } else {
+        (midpoint(last, first), points)
+    };
+    push_command_v0(out, OutlineCommandV0::MoveTo(start.0, start.1))?;
+    let mut control = None::<(i32, i32, bool)>;
+    for point in rest {
+        match (point.2, control) {
+            (true, None) => push_command_v0(out, OutlineCommandV0::LineTo(point.0, point.1))?,
+            (true, Some(off)) => {
+                push_command_v0(
+                    out,
+                    OutlineCommandV0::QuadTo(off.0, off.1, point.0, point.1),
+                )?;
+                control = None;
+            }
+            (false, None) => control = Some(*point),
+            (false, Some(off)) => {
+                let (x, y) = midpoint(off, *point);
+                push_command_v0(out, OutlineCommandV0::QuadTo(off.0, off.1, x, y))?;
+                control = Some(*point);
+            }
+        }
+    }
+    match control {
+        Some(off) => push_command_v0(
+            out,
+            OutlineCommandV0::QuadTo(off.0, off.1, start.0, start.1),
+        )?,
+        None => push_command_v0(out, OutlineCommandV0::LineTo(start.0, start.1))?,
+    }
+    push_command_v0(out, OutlineCommandV0::Close)
+}
+
+fn f2dot14_v0(glyph: &[u8], offset: usize) -> Result<f64, FontErrorV0> {
+    Ok(read_i16_at_v0(glyph, offset).ok_or_else(invalid_glyf_v0)? as f64 / 16384.0)
+}
+
+fn composite_glyph_outline_v0(
+    glyf: &[u8],
+    loca: &[u8],
+    index_to_loc_format: i16,
+    glyph: &[u8],
+    depth: usize,
+    out: &mut Vec<OutlineCommandV0>,
+) -> Result<(), FontErrorV0> {
+    let mut offset = 10usize;
+    loop {
+        let flags = read_u16_at_v0(glyph, offset).ok_or_else(invalid_glyf_v0)?;
+        let component = read_u16_at_v0(glyph, offset + 2).ok_or_else(invalid_glyf_v0)?;
+        if flags & COMPONENT_ARGS_ARE_XY == 0 {
+            // Point-matching placement needs hinted point numbering; not supported.
+            return Err(invalid_glyf_v0());
+        }
+        let mut cursor = offset + 4;
+        let (dx, dy) = if flags & COMPONENT_ARGS_ARE_WORDS != 0 {
+            let dx = read_i16_at_v0(glyph, cursor).ok_or_else(invalid_glyf_v0)? as i32;
+            let dy = read_i16_at_v0(glyph, cursor + 2).ok_or_else(invalid_glyf_v0)? as i32;
+            cursor += 4;
+            (dx, dy)
+        } else {
+            let bytes = glyph.get(cursor..cursor + 2).ok_or_else(invalid_glyf_v0)?;
+            cursor += 2;
+            (bytes[0] as i8 as i32, bytes[1] as i8 as i32)
+        };
+        let (a, b, c, d) = if flags & COMPONENT_HAS_SCALE != 0 {
+            let scale = f2dot14_v0(glyph, cursor)?;
+            (scale, 0.0, 0.0, scale)
+        } else if flags & COMPONENT_HAS_XY_SCALE != 0 {
+            (
+                f2dot14_v0(glyph, cursor)?,
+                0.0,
+                0.0,
+                f2dot14_v0(glyph, cursor + 2)?,
+            )
+        } else if flags & COMPONENT_HAS_2X2 != 0 {
+            (
+                f2dot14_v0(glyph, cursor)?,
+                f2dot14_v0(glyph, cursor + 2)?,
+                f2dot14_v0(glyph, cursor + 4)?,
+                f2dot14_v0(glyph, cursor + 6)?,
+            )
+        } else {
+            (1.0, 0.0, 0.0, 1.0)
+        };
+        let mut component_out = Vec::new();
+        glyf_outline_v0(
+            glyf,
+            loca,
+            index_to_loc_format,
+            component,
+            depth + 1,
+            &mut component_out,
+        )?;
+        for command in component_out {
+            let mapped = command.map_points_v0(|x, y| {
+                let (x, y) = (x as f64, y as f64);
+                (
+                    (a * x + c * y).round() as i32 + dx,
+                    (b * x + d * y).round() as i32 + dy,
+                )
+            });
+            push_command_v0(out, mapped)?;
+        }
+        offset += component_record_len_v0(flags);
+        if flags & COMPONENT_MORE == 0 {
+            return Ok(());
+        }
+    }
+}
+
+pub(crate) fn glyf_outline_v0(
+    glyf: &[u8],
+    loca: &[u8],
+    index_to_loc_format: i16,
+    glyph_id: u16,
+    depth: usize,
+    out: &mut Vec<OutlineCommandV0>,
+) -> Result<(), FontErrorV0> {
+    if depth > MAX_COMPOSITE_DEPTH_V0 {
+        return Err(invalid_glyf_v0());
+    }
+    let (start, end) = glyf_range_v0(loca, index_to_loc_format, glyph_id)?;
+    if start == end {
+        return Ok(());
+    }
+    let glyph = glyf.get(start..end).ok_or_else(invalid_glyf_v0)?;
+    let contour_count = read_i16_at_v0(glyph, 0).ok_or_else(invalid_glyf_v0)?;
+    if contour_count >= 0 {
+        simple_glyph_outline_v0(glyph, contour_count as usize, out)
+    } else {
+        composite_glyph_outline_v0(glyf, loca, index_to_loc_format, glyph, depth, out)
+    }
+}
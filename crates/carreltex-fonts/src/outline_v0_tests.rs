@@ -0,0 +1,152 @@
+use super::test_font_v0::test_font_bytes_v0;
+use super::test_outline_v0::{test_cff_font_bytes_v0, test_glyf_font_bytes_v0};
+use super::{
+    parse_font_v0, FontErrorV0, OutlineCommandV0, OutlineFormatV0, TAG_CFF_V0, TAG_GLYF_V0,
+};
+use OutlineCommandV0::{Close, CubicTo, LineTo, MoveTo, QuadTo};
+
+#[test]
+fn glyf_simple_glyph_outlines_close_each_contour() {
+    let bytes = test_glyf_font_bytes_v0();
+    let font = parse_font_v0(&bytes).expect("glyf font should parse");
+    assert_eq!(font.glyph_outline_v0(&bytes, 0), Ok(Vec::new()));
+    assert_eq!(
+        font.glyph_outline_v0(&bytes, 1),
+        Ok(vec![
+            MoveTo(0, 0),
+            LineTo(100, 0),
+            LineTo(100, 300),
+            LineTo(0, 300),
+            LineTo(0, 0),
+            Close,
+        ])
+    );
+}
+
+#[test]
+fn glyf_off_curve_runs_use_implied_midpoints() {
+    let bytes = test_glyf_font_bytes_v0();
+    let font = parse_font_v0(&bytes).expect("glyf font should parse");
+    assert_eq!(
+        font.glyph_outline_v0(&bytes, 2),
+        Ok(vec![
+            MoveTo(0, 0),
+            QuadTo(50, 100, 75, 100),
+            QuadTo(100, 100, 150, 0),
+            LineTo(0, 0),
+            Close,
+            MoveTo(10, 10),
+            QuadTo(0, 10, 10, 0),
+            QuadTo(20, 10, 10, 10),
+            Close,
+        ])
+    );
+}
+
+#[test]
+fn glyf_composite_applies_component_offsets_and_scale() {
+    let bytes = test_glyf_font_bytes_v0();
+    let font = parse_font_v0(&bytes).expect("glyf font should parse");
+    let outline = font.glyph_outline_v0(&bytes, 3).expect("composite outline");
+    assert_eq!(
+        outline[..6],
+        [
+            MoveTo(300, -50),
+            LineTo(350, -50),
+            LineTo(350, 100),
+            LineTo(300, 100),
+            LineTo(300, -50),
+            Close,
+        ]
+    );
+    assert_eq!(outline[6], MoveTo(5, -6));
+    assert_eq!(outline[7], QuadTo(55, 94, 80, 94));
+    assert_eq!(outline.len(), 6 + 9);
+}
+
+#[test]
+fn glyph_outline_fails_closed_without_outline_tables_or_glyph() {
+    let bytes = test_font_bytes_v0();
+    let font = parse_font_v0(&bytes).expect("font should parse");
+    assert_eq!(
+        font.glyph_outline_v0(&bytes, 1),
+        Err(FontErrorV0::MissingTable(TAG_GLYF_V0))
+    );
+    let bytes = test_glyf_font_bytes_v0();
+    let font = parse_font_v0(&bytes).expect("glyf font should parse");
+    let error = font.glyph_outline_v0(&bytes, 6).unwrap_err();
+    assert_eq!(error, FontErrorV0::GlyphIdInvalid);
+    assert_eq!(error.reason_v0(), "font_glyph_id_invalid");
+    let mut truncated = bytes.clone();
+    let (glyf_offset, _) = font.table_range_v0(TAG_GLYF_V0).expect("glyf range");
+    truncated[glyf_offset + 11] = 0xff;
+    assert_eq!(
+        font.glyph_outline_v0(&truncated, 1),
+        Err(FontErrorV0::InvalidTable(TAG_GLYF_V0))
+    );
+}
+
+#[test]
+fn cff_charstrings_decode_width_lines_and_subrs() {
+    let bytes = test_cff_font_bytes_v0();
+    let font = parse_font_v0(&bytes).expect("cff font should parse");
+    assert_eq!(font.outline_format_v0(), OutlineFormatV0::Cff);
+    assert_eq!(font.glyph_outline_v0(&bytes, 0), Ok(Vec::new()));
+    assert_eq!(
+        font.glyph_outline_v0(&bytes, 1),
+        Ok(vec![
+            MoveTo(10, 20),
+            LineTo(110, 20),
+            LineTo(110, 220),
+            LineTo(10, 220),
+            LineTo(10, 20),
+            Close,
+        ])
+    );
+    assert_eq!(
+        font.glyph_outline_v0(&bytes, 3),
+        Ok(vec![MoveTo(5, 5), LineTo(15, 5), Close])
+    );
+}
+
+#[test]
+fn cff_curve_operators_accumulate_relative_points() {
+    let bytes = test_cff_font_bytes_v0();
+    let font = parse_font_v0(&bytes).expect("cff font should parse");
+    assert_eq!(
+        font.glyph_outline_v0(&bytes, 2),
+        Ok(vec![
+            MoveTo(0, 0),
+            CubicTo(10, 0, 30, 30, 30, 70),
+            CubicTo(31, 72, 34, 76, 39, 82),
+            Close,
+        ])
+    );
+    assert_eq!(
+        font.glyph_outline_v0(&bytes, 4),
+        Ok(vec![
+            MoveTo(0, 0),
+            CubicTo(10, 0, 30, 30, 70, 30),
+            CubicTo(120, 30, 180, 0, 250, 0),
+            Close,
+        ])
+    );
+}
+
+#[test]
+fn cff_rejects_seac_and_corrupt_charstrings() {
+    let bytes = test_cff_font_bytes_v0();
+    let font = parse_font_v0(&bytes).expect("cff font should parse");
+    assert_eq!(
+        font.glyph_outline_v0(&bytes, 5),
+        Err(FontErrorV0::InvalidTable(TAG_CFF_V0))
+    );
+    let (cff_offset, cff_length) = font.table_range_v0(TAG_CFF_V0).expect("cff range");
+    let mut corrupt = bytes.clone();
+    // The last byte is the local subr's `return`; an undefined operator fails closed.
+    corrupt[cff_offset + cff_length - 1] = 0x02;
+    assert_eq!(
+        font.glyph_outline_v0(&corrupt, 3),
+        Err(FontErrorV0::InvalidTable(TAG_CFF_V0))
+    );
+}
@@ -0,0 +1,330 @@
+use std::collections::BTreeSet;
+
+use crate::cff_v0::{
+    dict_offset_v0, dict_operands_v0, invalid_cff_v0, parse_dict_entries_v0, read_index_v0,
+    DictEntryV0, DICT_CHARSET, DICT_CHARSTRINGS, DICT_ENCODING, DICT_FD_ARRAY, DICT_FD_SELECT,
+    DICT_PRIVATE, DICT_SUBRS,
+};
+use crate::outline_v0::{glyf_components_v0, glyf_range_v0};
+use crate::{read_u16_at_v0, FontErrorV0, TAG_GLYF_V0};
+
+const CHARSTRING_ENDCHAR_V0: u8 = 14;
+/// Charset operands up to 2 name the predefined ISOAdobe/Expert/ExpertSubset sets.
+const MAX_PREDEFINED_CHARSET_V0: i32 = 2;
+
+/// New `glyf` and long-format `loca` keeping `glyph_ids`, `.notdef` and every
+/// component they reference; other glyphs become empty but keep their ids.
+pub(crate) fn subset_glyf_v0(
+    glyf: &[u8],
+    loca: &[u8],
+    index_to_loc_format: i16,
+    num_glyphs: u16,
+    glyph_ids: &BTreeSet<u16>,
+) -> Result<(Vec<u8>, Vec<u8>), FontErrorV0> {
+    let mut kept = BTreeSet::<u16>::new();
+    let mut pending = glyph_ids.iter().copied().chain([0]).collect::<Vec<u16>>();
+    while let Some(glyph_id) = pending.pop() {
+        if glyph_id >= num_glyphs {
+            return Err(FontErrorV0::GlyphIdInvalid);
+        }
+        if !kept.insert(glyph_id) {
+            continue;
+        }
+        let (start, end) = glyf_range_v0(loca, index_to_loc_format, glyph_id)?;
+        let glyph = glyf
+            .get(start..end)
+            .ok_or(FontErrorV0::InvalidTable(TAG_GLYF_V0))?;
+        pending.extend(glyf_components_v0(glyph)?);
+    }
+    let mut new_glyf = Vec::<u8>::new();
+    let mut new_loca = Vec::<u8>::new();
+    for glyph_id in 0..num_glyphs {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        if kept.contains(&glyph_id) {
+            let (start, end) = glyf_range_v0(loca, index_to_loc_format, glyph_id)?;
+            new_glyf.extend_from_slice(&glyf[start..end]);
+            new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+    Ok((new_glyf, new_loca))
+}
+
+fn write_index_v0(entries: &[&[u8]]) -> Vec<u8> {
+    let mut out = (entries.len() as u16).to_be_bytes().to_vec();
+    if entries.is_empty() {
+        return out;
+    }
+    out.push(4);
+    let mut offset = 1u32;
+    out.extend_from_slice(&offset.to_be_bytes());
+    for entry in entries {
+        offset += entry.len() as u32;
+        out.extend_from_slice(&offset.to_be_bytes());
+    }
+    for entry in entries {
+        out.extend_from_slice(entry);
+    }
+    out
+}
+
+/// Copy DICT entries, dropping `drop` operators and re-encoding the operands of
+/// `replace` operators as 5-byte integers (so the DICT size does not depend on them).
+fn write_dict_v0(
+    dict: &[u8],
+    entries: &[DictEntryV0],
+    drop: &[u16],
+    replace: &[(u16, &[i32])],
+) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (operator, _, (start, end)) in entries {
+        if drop.contains(operator) {
+            continue;
+        }
+        match replace.iter().find(|(candidate, _)| candidate == operator) {
+            Some((_, operands)) => {
+                for operand in *operands {
+                    out.push(29);
+                    out.extend_from_slice(&operand.to_be_bytes());
+                }
+                if *operator >= 1200 {
+                    out.extend_from_slice(&[12, (operator - 1200) as u8]);
+                } else {
+                    out.push(*operator as u8);
+                }
+            }
+            None => out.extend_from_slice(&dict[*start..*end]),
+        }
+    }
+    out
+}
+
+fn checked_range_v0(cff: &[u8], start: usize, len: usize) -> Result<&[u8], FontErrorV0> {
+    cff.get(start..start + len).ok_or_else(invalid_cff_v0)
+}
+
+fn charset_len_v0(cff: &[u8], at: usize, glyph_count: usize) -> Result<usize, FontErrorV0> {
+    let needed = glyph_count.saturating_sub(1);
+    let len = match *cff.get(at).ok_or_else(invalid_cff_v0)? {
+        0 => 1 + needed * 2,
+        format @ (1 | 2) => {
+            let (mut covered, mut cursor) = (0usize, at + 1);
+            while covered < needed {
+                let left = if format == 1 {
+                    *cff.get(cursor + 2).ok_or_else(invalid_cff_v0)? as usize
+                } else {
+                    read_u16_at_v0(cff, cursor + 2).ok_or_else(invalid_cff_v0)? as usize
+                };
+                covered += left + 1;
+                cursor += if format == 1 { 3 } else { 4 };
+            }
+            cursor - at
+        }
+        _ => return Err(invalid_cff_v0()),
+    };
+    checked_range_v0(cff, at, len).map(|_| len)
+}
+
+fn fd_select_len_v0(cff: &[u8], at: usize, glyph_count: usize) -> Result<usize, FontErrorV0> {
+    let len = match *cff.get(at).ok_or_else(invalid_cff_v0)? {
+        0 => 1 + glyph_count,
+        3 => 5 + read_u16_at_v0(cff, at + 1).ok_or_else(invalid_cff_v0)? as usize * 3,
+        _ => return Err(invalid_cff_v0()),
+    };
+    checked_range_v0(cff, at, len).map(|_| len)
+}
+
+/// A Private DICT followed by its local Subrs INDEX, with the Subrs offset (relative
+/// to the DICT) rewritten; returns `(blob, dict_len)`.
+fn private_blob_v0(cff: &[u8], operands: &[i32]) -> Result<(Vec<u8>, usize), FontErrorV0> {
+    let [size, offset] = operands else {
+        return Err(invalid_cff_v0());
+    };
+    if *size < 0 || *offset < 0 {
+        return Err(invalid_cff_v0());
+    }
+    let (start, size) = (*offset as usize, *size as usize);
+    let dict = checked_range_v0(cff, start, size)?;
+    let entries = parse_dict_entries_v0(dict)?;
+    let plain = entries
+        .iter()
+        .map(|(operator, operands, _)| (*operator, operands.clone()))
+        .collect::<Vec<_>>();
+    let Some(subrs) = dict_offset_v0(&plain, DICT_SUBRS)? else {
+        return Ok((dict.to_vec(), size));
+    };
+    let (_, subrs_end) = read_index_v0(cff, start + subrs)?;
+    let dict_len = write_dict_v0(dict, &entries, &[], &[(DICT_SUBRS, &[0])]).len();
+    let mut blob = write_dict_v0(dict, &entries, &[], &[(DICT_SUBRS, &[dict_len as i32])]);
+    blob.extend_from_slice(&cff[start + subrs..subrs_end]);
+    Ok((blob, dict_len))
+}
+
+/// Rebuild a single-font `CFF ` table with the charstrings of glyphs outside
+/// `glyph_ids` (except `.notdef`) replaced by `endchar`, keeping glyph ids, the
+/// charset, FDSelect and subroutines. The Encoding is dropped (PDF embedding selects
+/// glyphs by id).
+pub(crate) fn subset_cff_v0(cff: &[u8], glyph_ids: &BTreeSet<u16>) -> Result<Vec<u8>, FontErrorV0> {
+    let header_size = *cff.get(2).ok_or_else(invalid_cff_v0)? as usize;
+    let (_, names_end) = read_index_v0(cff, header_size)?;
+    let (top_dicts, top_end) = read_index_v0(cff, names_end)?;
+    let (_, strings_end) = read_index_v0(cff, top_end)?;
+    let (_, globals_end) = read_index_v0(cff, strings_end)?;
+    let [(top_start, top_stop)] = top_dicts.as_slice() else {
+        return Err(invalid_cff_v0());
+    };
+    let top_bytes = &cff[*top_start..*top_stop];
+    let top_entries = parse_dict_entries_v0(top_bytes)?;
+    let top = top_entries
+        .iter()
+        .map(|(operator, operands, _)| (*operator, operands.clone()))
+        .collect::<Vec<_>>();
+    let charstrings_at = dict_offset_v0(&top, DICT_CHARSTRINGS)?.ok_or_else(invalid_cff_v0)?;
+    let (charstrings, _) = read_index_v0(cff, charstrings_at)?;
+    let glyph_count = charstrings.len();
+    if glyph_ids.iter().any(|id| *id as usize >= glyph_count) {
+        return Err(FontErrorV0::GlyphIdInvalid);
+    }
+    let endchar = [CHARSTRING_ENDCHAR_V0];
+    let kept_charstrings = charstrings
+        .iter()
+        .enumerate()
+        .map(|(glyph_id, (start, end))| {
+            if glyph_id == 0 || glyph_ids.contains(&(glyph_id as u16)) || start == end {
+                &cff[*start..*end]
+            } else {
+                &endchar[..]
+            }
+        })
+        .collect::<Vec<&[u8]>>();
+    let charstrings_index = write_index_v0(&kept_charstrings);
+
+    let charset = match dict_operands_v0(&top, DICT_CHARSET) {
+        Some([value]) if *value > MAX_PREDEFINED_CHARSET_V0 => {
+            let at = *value as usize;
+            Some(checked_range_v0(
+                cff,
+                at,
+                charset_len_v0(cff, at, glyph_count)?,
+            )?)
+        }
+        Some([_]) | None => None,
+        Some(_) => return Err(invalid_cff_v0()),
+    };
+    let fd_select = match dict_offset_v0(&top, DICT_FD_SELECT)? {
+        Some(at) => Some(checked_range_v0(
+            cff,
+            at,
+            fd_select_len_v0(cff, at, glyph_count)?,
+        )?),
+        None => None,
+    };
+    // Font DICTs (CID-keyed) or the Top DICT (name-keyed) own the Private DICTs.
+    let mut font_dicts = Vec::<(&[u8], Vec<DictEntryV0>)>::new();
+    if let Some(fd_array) = dict_offset_v0(&top, DICT_FD_ARRAY)? {
+        for (start, end) in read_index_v0(cff, fd_array)?.0 {
+            let dict = &cff[start..end];
+            font_dicts.push((dict, parse_dict_entries_v0(dict)?));
+        }
+    }
+    let private_owners = if font_dicts.is_empty() {
+        vec![&top_entries]
+    } else {
+        font_dicts.iter().map(|(_, entries)| entries).collect()
+    };
+    let mut privates = Vec::<Option<(Vec<u8>, usize)>>::new();
+    for entries in private_owners {
+        privates.push(
+            match entries
+                .iter()
+                .find(|(operator, _, _)| *operator == DICT_PRIVATE)
+            {
+                Some((_, operands, _)) => Some(private_blob_v0(cff, operands)?),
+                None => None,
+            },
+        );
+    }
+
+    // Every rewritten offset is a 5-byte integer, so sizes are known before offsets.
+    let fd_array_bytes = |private_at: &[i32]| -> Vec<u8> {
+        let dicts = font_dicts
+            .iter()
+            .zip(&privates)
+            .enumerate()
+            .map(|(index, ((dict, entries), private))| match private {
+                Some((_, dict_len)) => write_dict_v0(
+                    dict,
+                    entries,
+                    &[],
+                    &[(DICT_PRIVATE, &[*dict_len as i32, private_at[index]])],
+                ),
+                None => dict.to_vec(),
+            })
+            .collect::<Vec<Vec<u8>>>();
+        write_index_v0(&dicts.iter().map(Vec::as_slice).collect::<Vec<&[u8]>>())
+    };
+    let top_dict = |offsets: &[(u16, Vec<i32>)]| -> Vec<u8> {
+        let replace = offsets
+            .iter()
+            .map(|(operator, operands)| (*operator, operands.as_slice()))
+            .collect::<Vec<_>>();
+        write_dict_v0(top_bytes, &top_entries, &[DICT_ENCODING], &replace)
+    };
+    let layout = |positions: &[i32; 4], private_at: &[i32]| -> Vec<(u16, Vec<i32>)> {
+        let [charset_at, fd_select_at, charstrings_at, fd_array_at] = *positions;
+        let mut offsets = vec![(DICT_CHARSTRINGS, vec![charstrings_at])];
+        if charset.is_some() {
+            offsets.push((DICT_CHARSET, vec![charset_at]));
+        }
+        if fd_select.is_some() {
+            offsets.push((DICT_FD_SELECT, vec![fd_select_at]));
+        }
+        if !font_dicts.is_empty() {
+            offsets.push((DICT_FD_ARRAY, vec![fd_array_at]));
+        } else if let Some((_, dict_len)) = &privates[0] {
+            offsets.push((DICT_PRIVATE, vec![*dict_len as i32, private_at[0]]));
+        }
+        offsets
+    };
+
+    let zeros = vec![0i32; privates.len()];
+    let top_len = top_dict(&layout(&[0; 4], &zeros)).len();
+    let mut position = 4 + (names_end - header_size) + 11 + top_len + (globals_end - top_end);
+    let mut next = |len: usize| -> Result<i32, FontErrorV0> {
+        let at = i32::try_from(position).map_err(|_| FontErrorV0::TooLarge)?;
+        position += len;
+        Ok(at)
+    };
+    let charset_at = next(charset.map_or(0, <[u8]>::len))?;
+    let fd_select_at = next(fd_select.map_or(0, <[u8]>::len))?;
+    let charstrings_at = next(charstrings_index.len())?;
+    let fd_array_at = next(if font_dicts.is_empty() {
+        0
+    } else {
+        fd_array_bytes(&zeros).len()
+    })?;
+    let mut private_at = Vec::with_capacity(privates.len());
+    for private in &privates {
+        private_at.push(next(private.as_ref().map_or(0, |(blob, _)| blob.len()))?);
+    }
+
+    let positions = [charset_at, fd_select_at, charstrings_at, fd_array_at];
+    let top_dict_bytes = top_dict(&layout(&positions, &private_at));
+    let mut out = vec![1, 0, 4, 4];
+    out.extend_from_slice(&cff[header_size..names_end]);
+    out.extend_from_slice(&write_index_v0(&[&top_dict_bytes]));
+    out.extend_from_slice(&cff[top_end..globals_end]);
+    out.extend_from_slice(charset.unwrap_or_default());
+    out.extend_from_slice(fd_select.unwrap_or_default());
+    out.extend_from_slice(&charstrings_index);
+    if !font_dicts.is_empty() {
+        out.extend_from_slice(&fd_array_bytes(&private_at));
+    }
+    for (blob, _) in privates.iter().flatten() {
+        out.extend_from_slice(blob);
+    }
+    if out.len() != position {
+        return Err(invalid_cff_v0());
+    }
+    Ok(out)
+}
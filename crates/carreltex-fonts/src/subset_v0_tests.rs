@@ -0,0 +1,70 @@
+use std::collections::BTreeSet;
+
+use super::test_outline_v0::{test_cff_font_bytes_v0, test_glyf_font_bytes_v0};
+use super::{parse_font_v0, FontErrorV0, OutlineFormatV0, TAG_GPOS_V0, TAG_LOCA_V0};
+
+#[test]
+fn glyf_subset_keeps_requested_glyphs_and_composite_components() {
+    let bytes = test_glyf_font_bytes_v0();
+    let font = parse_font_v0(&bytes).expect("glyf font should parse");
+    let subset = font
+        .subset_font_v0(&bytes, &BTreeSet::from([3]))
+        .expect("subset should build");
+    assert!(subset.len() < bytes.len());
+    let parsed = parse_font_v0(&subset).expect("subset should parse");
+    assert_eq!(parsed.outline_format_v0(), OutlineFormatV0::TrueType);
+    assert_eq!(parsed.num_glyphs_v0(), font.num_glyphs_v0());
+    assert_eq!(parsed.index_to_loc_format_v0(), 1);
+    assert_eq!(parsed.table_range_v0(TAG_GPOS_V0), None);
+    assert_eq!(
+        parsed.table_range_v0(TAG_LOCA_V0).map(|(_, len)| len),
+        Some((font.num_glyphs_v0() as usize + 1) * 4)
+    );
+    for glyph_id in 1..=3 {
+        assert_eq!(
+            parsed.glyph_outline_v0(&subset, glyph_id),
+            font.glyph_outline_v0(&bytes, glyph_id)
+        );
+    }
+
+    let square_only = font
+        .subset_font_v0(&bytes, &BTreeSet::from([1]))
+        .expect("subset should build");
+    let parsed = parse_font_v0(&square_only).expect("subset should parse");
+    assert_eq!(parsed.glyph_outline_v0(&square_only, 2), Ok(Vec::new()));
+    assert_eq!(
+        font.subset_font_v0(&bytes, &BTreeSet::from([1])),
+        Ok(square_only)
+    );
+}
+
+#[test]
+fn cff_subset_blanks_unused_charstrings_and_relocates_subrs() {
+    let bytes = test_cff_font_bytes_v0();
+    let font = parse_font_v0(&bytes).expect("cff font should parse");
+    let subset = font
+        .subset_font_v0(&bytes, &BTreeSet::from([1, 3]))
+        .expect("subset should build");
+    let parsed = parse_font_v0(&subset).expect("subset should parse");
+    assert_eq!(parsed.outline_format_v0(), OutlineFormatV0::Cff);
+    for glyph_id in [1, 3] {
+        assert_eq!(
+            parsed.glyph_outline_v0(&subset, glyph_id),
+            font.glyph_outline_v0(&bytes, glyph_id)
+        );
+    }
+    assert_eq!(parsed.glyph_outline_v0(&subset, 2), Ok(Vec::new()));
+    // The unused `seac` glyph is replaced, so it no longer fails.
+    assert_eq!(parsed.glyph_outline_v0(&subset, 5), Ok(Vec::new()));
+}
+
+#[test]
+fn subset_rejects_glyph_ids_outside_the_font() {
+    for bytes in [test_glyf_font_bytes_v0(), test_cff_font_bytes_v0()] {
+        let font = parse_font_v0(&bytes).expect("font should parse");
+        assert_eq!(
+            font.subset_font_v0(&bytes, &BTreeSet::from([font.num_glyphs_v0()])),
+            Err(FontErrorV0::GlyphIdInvalid)
+        );
+    }
+}
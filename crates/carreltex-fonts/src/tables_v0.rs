@@ -0,0 +1,349 @@
+use crate::{
+    read_i16_at_v0, read_u16_at_v0, read_u32_at_v0, FontErrorV0, TAG_CMAP_V0, TAG_HEAD_V0,
+    TAG_HHEA_V0, TAG_HMTX_V0, TAG_MAXP_V0, TAG_NAME_V0,
+};
+
+const HEAD_MAGIC_V0: u32 = 0x5f0f_3cf5;
+const MAXP_VERSION_0_5_V0: u32 = 0x0000_5000;
+const MAXP_VERSION_1_0_V0: u32 = 0x0001_0000;
+const NAME_ID_FAMILY_V0: u16 = 1;
+const NAME_ID_SUBFAMILY_V0: u16 = 2;
+const NAME_ID_FULL_V0: u16 = 4;
+const NAME_ID_POSTSCRIPT_V0: u16 = 6;
+const MAX_NAME_CHARS_V0: usize = 256;
+
+pub(crate) struct HeadV0 {
+    pub(crate) units_per_em: u16,
+    pub(crate) index_to_loc_format: i16,
+    /// `xMin, yMin, xMax, yMax` in design units.
+    pub(crate) bounding_box: [i16; 4],
+}
+
+pub(crate) struct HheaV0 {
+    pub(crate) ascender: i16,
+    pub(crate) descender: i16,
+    pub(crate) line_gap: i16,
+    pub(crate) number_of_h_metrics: u16,
+}
+
+pub(crate) fn parse_head_v0(table: &[u8]) -> Result<HeadV0, FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(TAG_HEAD_V0);
+    if table.len() < 54 {
+        return Err(invalid);
+    }
+    if read_u32_at_v0(table, 12) != Some(HEAD_MAGIC_V0) {
+        return Err(invalid);
+    }
+    let units_per_em = read_u16_at_v0(table, 18).ok_or(invalid)?;
+    if !(16..=16_384).contains(&units_per_em) {
+        return Err(invalid);
+    }
+    let index_to_loc_format = read_i16_at_v0(table, 50).ok_or(invalid)?;
+    if !(0..=1).contains(&index_to_loc_format) {
+        return Err(invalid);
+    }
+    let mut bounding_box = [0i16; 4];
+    for (index, value) in bounding_box.iter_mut().enumerate() {
+        *value = read_i16_at_v0(table, 36 + index * 2).ok_or(invalid)?;
+    }
+    Ok(HeadV0 {
+        units_per_em,
+        index_to_loc_format,
+        bounding_box,
+    })
+}
+
+pub(crate) fn parse_maxp_v0(table: &[u8]) -> Result<u16, FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(TAG_MAXP_V0);
+    let version = read_u32_at_v0(table, 0).ok_or(invalid)?;
+    if version != MAXP_VERSION_0_5_V0 && version != MAXP_VERSION_1_0_V0 {
+        return Err(invalid);
+    }
+    let num_glyphs = read_u16_at_v0(table, 4).ok_or(invalid)?;
+    if num_glyphs == 0 {
+        return Err(invalid);
+    }
+    Ok(num_glyphs)
+}
+
+pub(crate) fn parse_hhea_v0(table: &[u8]) -> Result<HheaV0, FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(TAG_HHEA_V0);
+    if table.len() < 36 {
+        return Err(invalid);
+    }
+    let number_of_h_metrics = read_u16_at_v0(table, 34).ok_or(invalid)?;
+    if number_of_h_metrics == 0 {
+        return Err(invalid);
+    }
+    Ok(HheaV0 {
+        ascender: read_i16_at_v0(table, 4).ok_or(invalid)?,
+        descender: read_i16_at_v0(table, 6).ok_or(invalid)?,
+        line_gap: read_i16_at_v0(table, 8).ok_or(invalid)?,
+        number_of_h_metrics,
+    })
+}
+
+pub(crate) fn parse_hmtx_v0(
+    table: &[u8],
+    number_of_h_metrics: u16,
+    num_glyphs: u16,
+) -> Result<Vec<u16>, FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(TAG_HMTX_V0);
+    if number_of_h_metrics > num_glyphs {
+        return Err(invalid);
+    }
+    let long_metrics = number_of_h_metrics as usize;
+    let trailing_lsbs = (num_glyphs - number_of_h_metrics) as usize;
+    if table.len() < long_metrics * 4 + trailing_lsbs * 2 {
+        return Err(invalid);
+    }
+    let mut advances = Vec::<u16>::with_capacity(num_glyphs as usize);
+    for metric_index in 0..long_metrics {
+        advances.push(read_u16_at_v0(table, metric_index * 4).ok_or(invalid)?);
+    }
+    let last_advance = *advances.last().ok_or(invalid)?;
+    advances.resize(num_glyphs as usize, last_advance);
+    Ok(advances)
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Format4SegmentV0 {
+    start: u16,
+    end: u16,
+    id_delta: u16,
+    id_range_offset: u16,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum CmapV0 {
+    Format4 {
+        segments: Vec<Format4SegmentV0>,
+        glyph_id_array: Vec<u16>,
+    },
+    Format12 {
+        groups: Vec<(u32, u32, u32)>,
+    },
+}
+
+impl CmapV0 {
+    pub(crate) fn lookup_v0(&self, code_point: u32) -> Option<u16> {
+        match self {
+            CmapV0::Format4 {
+                segments,
+                glyph_id_array,
+            } => {
+                let code = u16::try_from(code_point).ok()?;
+                let segment_index = segments
+                    .iter()
+                    .position(|segment| segment.start <= code && code <= segment.end)?;
+                let segment = &segments[segment_index];
+                if segment.id_range_offset == 0 {
+                    return Some(code.wrapping_add(segment.id_delta));
+                }
+                let array_index = (segment.id_range_offset as usize / 2)
+                    .checked_add((code - segment.start) as usize)?
+                    .checked_sub(segments.len() - segment_index)?;
+                let glyph_id = *glyph_id_array.get(array_index)?;
+                if glyph_id == 0 {
+                    return None;
+                }
+                Some(glyph_id.wrapping_add(segment.id_delta))
+            }
+            CmapV0::Format12 { groups } => {
+                let (start, _, start_glyph) = groups
+                    .iter()
+                    .find(|(start, end, _)| *start <= code_point && code_point <= *end)?;
+                u16::try_from(start_glyph.checked_add(code_point - start)?).ok()
+            }
+        }
+    }
+}
+
+fn cmap_subtable_priority_v0(platform_id: u16, encoding_id: u16, format: u16) -> Option<u8> {
+    match (platform_id, encoding_id, format) {
+        (3, 10, 12) => Some(0),
+        (0, 4, 12) | (0, 6, 12) => Some(1),
+        (3, 1, 4) => Some(2),
+        (0, 3, 4) => Some(3),
+        (0, _, 4) => Some(4),
+        _ => None,
+    }
+}
+
+pub(crate) fn parse_cmap_v0(table: &[u8]) -> Result<CmapV0, FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(TAG_CMAP_V0);
+    let num_subtables = read_u16_at_v0(table, 2).ok_or(invalid)? as usize;
+    let mut best = None::<(u8, usize)>;
+    for record_index in 0..num_subtables {
+        let record = 4 + record_index * 8;
+        let platform_id = read_u16_at_v0(table, record).ok_or(invalid)?;
+        let encoding_id = read_u16_at_v0(table, record + 2).ok_or(invalid)?;
+        let offset = read_u32_at_v0(table, record + 4).ok_or(invalid)? as usize;
+        let format = read_u16_at_v0(table, offset).ok_or(invalid)?;
+        if let Some(priority) = cmap_subtable_priority_v0(platform_id, encoding_id, format) {
+            if best.is_none_or(|(best_priority, _)| priority < best_priority) {
+                best = Some((priority, offset));
+            }
+        }
+    }
+    let (_, offset) = best.ok_or(FontErrorV0::CmapUnsupported)?;
+    let subtable = table.get(offset..).ok_or(invalid)?;
+    match read_u16_at_v0(subtable, 0).ok_or(invalid)? {
+        4 => parse_cmap_format4_v0(subtable),
+        12 => parse_cmap_format12_v0(subtable),
+        _ => Err(FontErrorV0::CmapUnsupported),
+    }
+}
+
+fn parse_cmap_format4_v0(subtable: &[u8]) -> Result<CmapV0, FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(TAG_CMAP_V0);
+    let length = read_u16_at_v0(subtable, 2).ok_or(invalid)? as usize;
+    let subtable = subtable.get(..length).ok_or(invalid)?;
+    let seg_count_x2 = read_u16_at_v0(subtable, 6).ok_or(invalid)? as usize;
+    if seg_count_x2 == 0 || !seg_count_x2.is_multiple_of(2) {
+        return Err(invalid);
+    }
+    let seg_count = seg_count_x2 / 2;
+    let end_codes = 14usize;
+    let start_codes = end_codes + seg_count_x2 + 2;
+    let id_deltas = start_codes + seg_count_x2;
+    let id_range_offsets = id_deltas + seg_count_x2;
+    let glyph_ids = id_range_offsets + seg_count_x2;
+    if subtable.len() < glyph_ids {
+        return Err(invalid);
+    }
+    let mut segments = Vec::<Format4SegmentV0>::with_capacity(seg_count);
+    for segment_index in 0..seg_count {
+        let segment = Format4SegmentV0 {
+            start: read_u16_at_v0(subtable, start_codes + segment_index * 2).ok_or(invalid)?,
+            end: read_u16_at_v0(subtable, end_codes + segment_index * 2).ok_or(invalid)?,
+            id_delta: read_u16_at_v0(subtable, id_deltas + segment_index * 2).ok_or(invalid)?,
+            id_range_offset: read_u16_at_v0(subtable, id_range_offsets + segment_index * 2)
+                .ok_or(invalid)?,
+        };
+        if segment.start > segment.end {
+            return Err(invalid);
+        }
+        segments.push(segment);
+    }
+    let glyph_id_array = subtable[glyph_ids..]
+        .chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect::<Vec<u16>>();
+    Ok(CmapV0::Format4 {
+        segments,
+        glyph_id_array,
+    })
+}
+
+fn parse_cmap_format12_v0(subtable: &[u8]) -> Result<CmapV0, FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(TAG_CMAP_V0);
+    let length = read_u32_at_v0(subtable, 4).ok_or(invalid)? as usize;
+    let subtable = subtable.get(..length).ok_or(invalid)?;
+    let num_groups = read_u32_at_v0(subtable, 12).ok_or(invalid)? as usize;
+    let groups_end = num_groups
+        .checked_mul(12)
+        .and_then(|bytes| bytes.checked_add(16))
+        .ok_or(invalid)?;
+    if subtable.len() < groups_end {
+        return Err(invalid);
+    }
+    let mut groups = Vec::<(u32, u32, u32)>::with_capacity(num_groups);
+    for group_index in 0..num_groups {
+        let record = 16 + group_index * 12;
+        let start = read_u32_at_v0(subtable, record).ok_or(invalid)?;
+        let end = read_u32_at_v0(subtable, record + 4).ok_or(invalid)?;
+        let start_glyph = read_u32_at_v0(subtable, record + 8).ok_or(invalid)?;
+        if start > end || end > 0x10_ffff {
+            return Err(invalid);
+        }
+        groups.push((start, end, start_glyph));
+    }
+    Ok(CmapV0::Format12 { groups })
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FontNamesV0 {
+    pub(crate) family: Option<String>,
+    pub(crate) subfamily: Option<String>,
+    pub(crate) full: Option<String>,
+    pub(crate) postscript: Option<String>,
+}
+
+fn name_record_priority_v0(platform_id: u16, encoding_id: u16, language_id: u16) -> Option<u8> {
+    match (platform_id, encoding_id, language_id) {
+        (3, 1 | 10, 0x0409) => Some(0),
+        (3, 1 | 10, _) => Some(1),
+        (0, _, _) => Some(2),
+        (1, 0, _) => Some(3),
+        _ => None,
+    }
+}
+
+fn decode_name_v0(platform_id: u16, raw: &[u8]) -> Option<String> {
+    let decoded = if platform_id == 1 {
+        raw.iter()
+            .map(|byte| {
+                if byte.is_ascii() {
+                    *byte as char
+                } else {
+                    char::REPLACEMENT_CHARACTER
+                }
+            })
+            .collect::<String>()
+    } else {
+        if !raw.len().is_multiple_of(2) {
+            return None;
+        }
+        let units = raw
+            .chunks_exact(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+        char::decode_utf16(units)
+            .map(|unit| unit.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect::<String>()
+    };
+    Some(decoded.chars().take(MAX_NAME_CHARS_V0).collect())
+}
+
+pub(crate) fn parse_name_v0(table: &[u8]) -> Result<FontNamesV0, FontErrorV0> {
+    let invalid = FontErrorV0::InvalidTable(TAG_NAME_V0);
+    let count = read_u16_at_v0(table, 2).ok_or(invalid)? as usize;
+    let string_offset = read_u16_at_v0(table, 4).ok_or(invalid)? as usize;
+    let mut best = [None::<(u8, String)>, None, None, None];
+    for record_index in 0..count {
+        let record = 6 + record_index * 12;
+        let platform_id = read_u16_at_v0(table, record).ok_or(invalid)?;
+        let encoding_id = read_u16_at_v0(table, record + 2).ok_or(invalid)?;
+        let language_id = read_u16_at_v0(table, record + 4).ok_or(invalid)?;
+        let name_id = read_u16_at_v0(table, record + 6).ok_or(invalid)?;
+        let length = read_u16_at_v0(table, record + 8).ok_or(invalid)? as usize;
+        let offset = read_u16_at_v0(table, record + 10).ok_or(invalid)? as usize;
+        let slot = match name_id {
+            NAME_ID_FAMILY_V0 => 0,
+            NAME_ID_SUBFAMILY_V0 => 1,
+            NAME_ID_FULL_V0 => 2,
+            NAME_ID_POSTSCRIPT_V0 => 3,
+            _ => continue,
+        };
+        let Some(priority) = name_record_priority_v0(platform_id, encoding_id, language_id) else {
+            continue;
+        };
+        if best[slot]
+            .as_ref()
+            .is_some_and(|(best_priority, _)| *best_priority <= priority)
+        {
+            continue;
+        }
+        let start = string_offset.checked_add(offset).ok_or(invalid)?;
+        let raw = table.get(start..start + length).ok_or(invalid)?;
+        let decoded = decode_name_v0(platform_id, raw).ok_or(invalid)?;
+        best[slot] = Some((priority, decoded));
+    }
+    let [family, subfamily, full, postscript] = best.map(|entry| entry.map(|(_, name)| name));
+    Ok(FontNamesV0 {
+        family,
+        subfamily,
+        full,
+        postscript,
+    })
+}
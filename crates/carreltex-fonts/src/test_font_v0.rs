@@ -0,0 +1,282 @@
+use crate::{
+    write_sfnt_v0, OutlineFormatV0, TAG_CMAP_V0, TAG_GPOS_V0, TAG_GSUB_V0, TAG_HEAD_V0,
+    TAG_HHEA_V0, TAG_HMTX_V0, TAG_MAXP_V0, TAG_NAME_V0,
+};
+
+pub(crate) const TEST_GLYPH_F_V0: u16 = 1;
+pub(crate) const TEST_GLYPH_I_V0: u16 = 2;
+pub(crate) const TEST_GLYPH_A_V0: u16 = 3;
+pub(crate) const TEST_GLYPH_V_V0: u16 = 4;
+pub(crate) const TEST_GLYPH_FI_V0: u16 = 5;
+pub(crate) const TEST_ADVANCES_V0: [u16; 6] = [500, 300, 250, 600, 600, 520];
+pub(crate) const TEST_KERN_AV_V0: i16 = -80;
+
+fn push_u16(out: &mut Vec<u8>, value: u16) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn push_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+fn head_table_v0() -> Vec<u8> {
+    let mut out = vec![0u8; 54];
+    out[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    out[12..16].copy_from_slice(&0x5f0f_3cf5u32.to_be_bytes());
+    out[18..20].copy_from_slice(&1000u16.to_be_bytes());
+    out
+}
+
+fn maxp_table_v0() -> Vec<u8> {
+    let mut out = Vec::<u8>::new();
+    push_u32(&mut out, 0x0000_5000);
+    push_u16(&mut out, TEST_ADVANCES_V0.len() as u16);
+    out
+}
+
+fn hhea_table_v0() -> Vec<u8> {
+    let mut out = vec![0u8; 36];
+    out[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    out[4..6].copy_from_slice(&800i16.to_be_bytes());
+    out[6..8].copy_from_slice(&(-200i16).to_be_bytes());
+    out[8..10].copy_from_slice(&90i16.to_be_bytes());
+    // Last two glyphs share the final long metric via the trailing lsb array.
+    out[34..36].copy_from_slice(&5u16.to_be_bytes());
+    out
+}
+
+fn hmtx_table_v0() -> Vec<u8> {
+    let mut out = Vec::<u8>::new();
+    for advance in &TEST_ADVANCES_V0[..5] {
+        push_u16(&mut out, *advance);
+        push_u16(&mut out, 0);
+    }
+    push_u16(&mut out, 0);
+    out
+}
+
+/// Format 4: 'A' (delta), 'V' (delta), 'f'..'i' via idRangeOffset, and the 0xffff sentinel.
+fn cmap_format4_v0() -> Vec<u8> {
+    let segments: [(u16, u16, u16); 4] = [
+        (
+            b'A' as u16,
+            b'A' as u16,
+            TEST_GLYPH_A_V0.wrapping_sub(b'A' as u16),
+        ),
+        (
+            b'V' as u16,
+            b'V' as u16,
+            TEST_GLYPH_V_V0.wrapping_sub(b'V' as u16),
+        ),
+        (b'f' as u16, b'i' as u16, 0),
+        (0xffff, 0xffff, 1),
+    ];
+    let glyph_id_array: [u16; 4] = [TEST_GLYPH_F_V0, 0, 0, TEST_GLYPH_I_V0];
+    let seg_count = segments.len() as u16;
+    let mut out = Vec::<u8>::new();
+    push_u16(&mut out, 4);
+    push_u16(
+        &mut out,
+        16 + seg_count * 8 + glyph_id_array.len() as u16 * 2,
+    );
+    push_u16(&mut out, 0);
+    push_u16(&mut out, seg_count * 2);
+    push_u16(&mut out, 8);
+    push_u16(&mut out, 2);
+    push_u16(&mut out, 0);
+    for (_, end, _) in &segments {
+        push_u16(&mut out, *end);
+    }
+    push_u16(&mut out, 0);
+    for (start, _, _) in &segments {
+        push_u16(&mut out, *start);
+    }
+    for (_, _, delta) in &segments {
+        push_u16(&mut out, *delta);
+    }
+    for segment_index in 0..segments.len() {
+        let id_range_offset = if segment_index == 2 {
+            (segments.len() - segment_index) as u16 * 2
+        } else {
+            0
+        };
+        push_u16(&mut out, id_range_offset);
+    }
+    for glyph_id in glyph_id_array {
+        push_u16(&mut out, glyph_id);
+    }
+    out
+}
+
+fn cmap_format12_v0() -> Vec<u8> {
+    let groups: [(u32, u32, u32); 4] = [
+        (b'A' as u32, b'A' as u32, TEST_GLYPH_A_V0 as u32),
+        (b'V' as u32, b'V' as u32, TEST_GLYPH_V_V0 as u32),
+        (b'f' as u32, b'f' as u32, TEST_GLYPH_F_V0 as u32),
+        (b'i' as u32, b'i' as u32, TEST_GLYPH_I_V0 as u32),
+    ];
+    let mut out = Vec::<u8>::new();
+    push_u16(&mut out, 12);
+    push_u16(&mut out, 0);
+    push_u32(&mut out, 16 + groups.len() as u32 * 12);
+    push_u32(&mut out, 0);
+    push_u32(&mut out, groups.len() as u32);
+    for (start, end, start_glyph) in groups {
+        push_u32(&mut out, start);
+        push_u32(&mut out, end);
+        push_u32(&mut out, start_glyph);
+    }
+    out
+}
+
+fn cmap_table_v0(subtables: &[(u16, u16, Vec<u8>)]) -> Vec<u8> {
+    let mut out = Vec::<u8>::new();
+    push_u16(&mut out, 0);
+    push_u16(&mut out, subtables.len() as u16);
+    let mut offset = 4 + subtables.len() as u32 * 8;
+    for (platform_id, encoding_id, subtable) in subtables {
+        push_u16(&mut out, *platform_id);
+        push_u16(&mut out, *encoding_id);
+        push_u32(&mut out, offset);
+        offset += subtable.len() as u32;
+    }
+    for (_, _, subtable) in subtables {
+        out.extend_from_slice(subtable);
+    }
+    out
+}
+
+fn name_table_v0() -> Vec<u8> {
+    let names: [(u16, &str); 4] = [
+        (1, "Carrel Test"),
+        (2, "Regular"),
+        (4, "Carrel Test Regular"),
+        (6, "CarrelTest-Regular"),
+    ];
+    let mut strings = Vec::<u8>::new();
+    let mut out = Vec::<u8>::new();
+    push_u16(&mut out, 0);
+    // One Mac Roman family record that must lose against the Windows record.
+    push_u16(&mut out, names.len() as u16 + 1);
+    push_u16(&mut out, 6 + (names.len() as u16 + 1) * 12);
+    let mac_family = b"Mac Family";
+    push_u16(&mut out, 1);
+    push_u16(&mut out, 0);
+    push_u16(&mut out, 0);
+    push_u16(&mut out, 1);
+    push_u16(&mut out, mac_family.len() as u16);
+    push_u16(&mut out, 0);
+    strings.extend_from_slice(mac_family);
+    for (name_id, value) in names {
+        let encoded = value
+            .encode_utf16()
+            .flat_map(|unit| unit.to_be_bytes())
+            .collect::<Vec<u8>>();
+        push_u16(&mut out, 3);
+        push_u16(&mut out, 1);
+        push_u16(&mut out, 0x0409);
+        push_u16(&mut out, name_id);
+        push_u16(&mut out, encoded.len() as u16);
+        push_u16(&mut out, strings.len() as u16);
+        strings.extend_from_slice(&encoded);
+    }
+    out.extend_from_slice(&strings);
+    out
+}
+
+/// GPOS/GSUB table with an empty ScriptList, one feature and one lookup holding `subtable`.
+fn layout_table_v0(feature_tag: &[u8; 4], lookup_type: u16, subtable: &[u8]) -> Vec<u8> {
+    let mut out = Vec::<u8>::new();
+    push_u32(&mut out, 0x0001_0000);
+    push_u16(&mut out, 10);
+    push_u16(&mut out, 12);
+    push_u16(&mut out, 26);
+    // ScriptList (empty).
+    push_u16(&mut out, 0);
+    // FeatureList at 12.
+    push_u16(&mut out, 1);
+    out.extend_from_slice(feature_tag);
+    push_u16(&mut out, 8);
+    push_u16(&mut out, 0);
+    push_u16(&mut out, 1);
+    push_u16(&mut out, 0);
+    // LookupList at 26.
+    push_u16(&mut out, 1);
+    push_u16(&mut out, 4);
+    push_u16(&mut out, lookup_type);
+    push_u16(&mut out, 0);
+    push_u16(&mut out, 1);
+    push_u16(&mut out, 8);
+    out.extend_from_slice(subtable);
+    out
+}
+
+fn gpos_table_v0() -> Vec<u8> {
+    let mut subtable = Vec::<u8>::new();
+    push_u16(&mut subtable, 1);
+    push_u16(&mut subtable, 14);
+    push_u16(&mut subtable, 0x0004);
+    push_u16(&mut subtable, 0);
+    push_u16(&mut subtable, 1);
+    push_u16(&mut subtable, 20);
+    push_u16(&mut subtable, 0);
+    // Coverage at 14.
+    push_u16(&mut subtable, 1);
+    push_u16(&mut subtable, 1);
+    push_u16(&mut subtable, TEST_GLYPH_A_V0);
+    // PairSet at 20.
+    push_u16(&mut subtable, 1);
+    push_u16(&mut subtable, TEST_GLYPH_V_V0);
+    subtable.extend_from_slice(&TEST_KERN_AV_V0.to_be_bytes());
+    layout_table_v0(b"kern", 2, &subtable)
+}
+
+fn gsub_table_v0() -> Vec<u8> {
+    let mut subtable = Vec::<u8>::new();
+    push_u16(&mut subtable, 1);
+    push_u16(&mut subtable, 8);
+    push_u16(&mut subtable, 1);
+    push_u16(&mut subtable, 14);
+    // Coverage at 8.
+    push_u16(&mut subtable, 1);
+    push_u16(&mut subtable, 1);
+    push_u16(&mut subtable, TEST_GLYPH_F_V0);
+    // LigatureSet at 14, Ligature at 18.
+    push_u16(&mut subtable, 1);
+    push_u16(&mut subtable, 4);
+    push_u16(&mut subtable, TEST_GLYPH_FI_V0);
+    push_u16(&mut subtable, 2);
+    push_u16(&mut subtable, TEST_GLYPH_I_V0);
+    layout_table_v0(b"liga", 4, &subtable)
+}
+
+pub(crate) fn test_font_tables_v0() -> Vec<([u8; 4], Vec<u8>)> {
+    vec![
+        (TAG_HEAD_V0, head_table_v0()),
+        (TAG_MAXP_V0, maxp_table_v0()),
+        (TAG_HHEA_V0, hhea_table_v0()),
+        (TAG_HMTX_V0, hmtx_table_v0()),
+        (TAG_CMAP_V0, cmap_table_v0(&[(3, 1, cmap_format4_v0())])),
+        (TAG_NAME_V0, name_table_v0()),
+        (TAG_GPOS_V0, gpos_table_v0()),
+        (TAG_GSUB_V0, gsub_table_v0()),
+    ]
+}
+
+pub(crate) fn test_font_format12_tables_v0() -> Vec<([u8; 4], Vec<u8>)> {
+    let mut tables = test_font_tables_v0();
+    for (tag, table) in tables.iter_mut() {
+        if *tag == TAG_CMAP_V0 {
+            *table = cmap_table_v0(&[(3, 1, cmap_format4_v0()), (3, 10, cmap_format12_v0())]);
+        }
+    }
+    tables
+}
+
+pub(crate) fn build_font_v0(tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    write_sfnt_v0(OutlineFormatV0::TrueType, tables).expect("test font must assemble")
+}
+
+pub(crate) fn test_font_bytes_v0() -> Vec<u8> {
+    build_font_v0(&test_font_tables_v0())
+}
@@ -0,0 +1,225 @@
+use crate::test_font_v0::{build_font_v0, test_font_tables_v0};
+use crate::{write_sfnt_v0, OutlineFormatV0, TAG_CFF_V0, TAG_GLYF_V0, TAG_LOCA_V0};
+
+/// Simple glyph from contours of `(x, y, on_curve)` points, using short, same and
+/// word coordinate encodings and repeated flags where they apply.
+fn simple_glyph_v0(contours: &[&[(i32, i32, bool)]]) -> Vec<u8> {
+    let points = contours.concat();
+    let mut out = Vec::<u8>::new();
+    out.extend_from_slice(&(contours.len() as i16).to_be_bytes());
+    out.extend_from_slice(&[0u8; 8]);
+    let mut end = 0usize;
+    for contour in contours {
+        end += contour.len();
+        out.extend_from_slice(&((end - 1) as u16).to_be_bytes());
+    }
+    out.extend_from_slice(&0u16.to_be_bytes());
+    let mut flags = Vec::<u8>::new();
+    let mut x_bytes = Vec::<u8>::new();
+    let mut y_bytes = Vec::<u8>::new();
+    let (mut last_x, mut last_y) = (0i32, 0i32);
+    for (x, y, on_curve) in &points {
+        let mut flag = if *on_curve { 0x01 } else { 0x00 };
+        for (delta, short, same, bytes) in [
+            (x - last_x, 0x02u8, 0x10u8, &mut x_bytes),
+            (y - last_y, 0x04u8, 0x20u8, &mut y_bytes),
+        ] {
+            if delta == 0 {
+                flag |= same;
+            } else if delta.abs() < 256 {
+                flag |= short;
+                if delta > 0 {
+                    flag |= same;
+                }
+                bytes.push(delta.unsigned_abs() as u8);
+            } else {
+                bytes.extend_from_slice(&(delta as i16).to_be_bytes());
+            }
+        }
+        flags.push(flag);
+        (last_x, last_y) = (*x, *y);
+    }
+    let mut index = 0usize;
+    while index < flags.len() {
+        let run = flags[index..]
+            .iter()
+            .take_while(|flag| **flag == flags[index])
+            .count();
+        if run > 1 {
+            out.push(flags[index] | 0x08);
+            out.push((run - 1) as u8);
+        } else {
+            out.push(flags[index]);
+        }
+        index += run;
+    }
+    out.extend_from_slice(&x_bytes);
+    out.extend_from_slice(&y_bytes);
+    out
+}
+
+/// Square, curved glyph (off-curve runs and an off-curve start), and a composite of
+/// both (scaled by 0.5 with word offsets, then unscaled with byte offsets).
+pub(crate) fn test_glyf_tables_v0() -> Vec<([u8; 4], Vec<u8>)> {
+    let square = simple_glyph_v0(&[&[
+        (0, 0, true),
+        (100, 0, true),
+        (100, 300, true),
+        (0, 300, true),
+    ]]);
+    let curved = simple_glyph_v0(&[
+        &[
+            (0, 0, true),
+            (50, 100, false),
+            (100, 100, false),
+            (150, 0, true),
+        ],
+        &[(0, 10, false), (10, 0, true), (20, 10, false)],
+    ]);
+    let mut composite = Vec::<u8>::new();
+    composite.extend_from_slice(&(-1i16).to_be_bytes());
+    composite.extend_from_slice(&[0u8; 8]);
+    composite.extend_from_slice(&(0x0001u16 | 0x0002 | 0x0008 | 0x0020).to_be_bytes());
+    composite.extend_from_slice(&1u16.to_be_bytes());
+    composite.extend_from_slice(&300i16.to_be_bytes());
+    composite.extend_from_slice(&(-50i16).to_be_bytes());
+    composite.extend_from_slice(&0x2000u16.to_be_bytes());
+    composite.extend_from_slice(&0x0002u16.to_be_bytes());
+    composite.extend_from_slice(&2u16.to_be_bytes());
+    composite.extend_from_slice(&[5, (-6i8) as u8]);
+    let glyphs = [
+        Vec::new(),
+        square,
+        curved,
+        composite,
+        Vec::new(),
+        Vec::new(),
+    ];
+    let mut glyf = Vec::<u8>::new();
+    let mut loca = Vec::<u8>::new();
+    for glyph in &glyphs {
+        loca.extend_from_slice(&((glyf.len() / 2) as u16).to_be_bytes());
+        glyf.extend_from_slice(glyph);
+        glyf.resize(glyf.len().next_multiple_of(2), 0);
+    }
+    loca.extend_from_slice(&((glyf.len() / 2) as u16).to_be_bytes());
+    vec![(TAG_GLYF_V0, glyf), (TAG_LOCA_V0, loca)]
+}
+
+pub(crate) fn test_glyf_font_bytes_v0() -> Vec<u8> {
+    let mut tables = test_font_tables_v0();
+    tables.extend(test_glyf_tables_v0());
+    build_font_v0(&tables)
+}
+
+fn cff_index_v0(entries: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = (entries.len() as u16).to_be_bytes().to_vec();
+    if entries.is_empty() {
+        return out;
+    }
+    out.push(4);
+    let mut offset = 1u32;
+    out.extend_from_slice(&offset.to_be_bytes());
+    for entry in entries {
+        offset += entry.len() as u32;
+        out.extend_from_slice(&offset.to_be_bytes());
+    }
+    for entry in entries {
+        out.extend_from_slice(entry);
+    }
+    out
+}
+
+fn dict_int_v0(value: i32) -> Vec<u8> {
+    let mut out = vec![29];
+    out.extend_from_slice(&value.to_be_bytes());
+    out
+}
+
+/// Type 2 charstring operand; `-107..=107` use the one-byte form.
+fn cs_v0(values: &[i32], operator: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for value in values {
+        if (-107..=107).contains(value) {
+            out.push((value + 139) as u8);
+        } else {
+            out.push(28);
+            out.extend_from_slice(&(*value as i16).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(operator);
+    out
+}
+
+/// Name-keyed CFF with one global and one local subr. Glyph 1 has a width and lines
+/// (one from the global subr), 2 curves, 3 stem hints, a hintmask and a local subr
+/// call, 4 an `hflex`, 5 a deprecated `seac` endchar.
+pub(crate) fn test_cff_table_v0() -> Vec<u8> {
+    let endchar = cs_v0(&[], &[14]);
+    let charstrings = vec![
+        endchar.clone(),
+        [
+            cs_v0(&[50, 10, 20], &[21]),
+            cs_v0(&[100, 200, -100], &[6]),
+            cs_v0(&[-107], &[29]),
+            endchar.clone(),
+        ]
+        .concat(),
+        [
+            cs_v0(&[0, 0], &[21]),
+            cs_v0(&[10, 20, 30, 40], &[31]),
+            cs_v0(&[1, 2, 3, 4, 5, 6], &[8]),
+            endchar.clone(),
+        ]
+        .concat(),
+        [
+            cs_v0(&[10, 20, 30, 40], &[18]),
+            cs_v0(&[50, 60], &[19, 0xe0]),
+            cs_v0(&[-107], &[10]),
+            endchar.clone(),
+        ]
+        .concat(),
+        [
+            cs_v0(&[0, 0], &[21]),
+            cs_v0(&[10, 20, 30, 40, 50, 60, 70], &[12, 34]),
+            endchar.clone(),
+        ]
+        .concat(),
+        cs_v0(&[0, 0, 0, 0], &[14]),
+    ];
+    let global_subrs = vec![cs_v0(&[0, -200], &[5, 11])];
+    let local_subrs = vec![[cs_v0(&[5, 5], &[21]), cs_v0(&[10, 0], &[5, 11])].concat()];
+    let header = vec![1u8, 0, 4, 4];
+    let names = cff_index_v0(&[b"CarrelTest".to_vec()]);
+    let strings = cff_index_v0(&[]);
+    let globals = cff_index_v0(&global_subrs);
+    let private = [dict_int_v0(6), vec![19]].concat();
+    // Top DICT has a fixed size, so the offsets after it are known up front.
+    let top_len = 5 + 1 + 5 + 5 + 1;
+    let top_index_len = 2 + 1 + 8 + top_len;
+    let charstrings_at = header.len() + names.len() + top_index_len + strings.len() + globals.len();
+    let charstrings_index = cff_index_v0(&charstrings);
+    let private_at = charstrings_at + charstrings_index.len();
+    let top = [
+        dict_int_v0(charstrings_at as i32),
+        vec![17],
+        dict_int_v0(private.len() as i32),
+        dict_int_v0(private_at as i32),
+        vec![18],
+    ]
+    .concat();
+    assert_eq!(top.len(), top_len);
+    let mut out = [header, names, cff_index_v0(&[top]), strings, globals].concat();
+    assert_eq!(out.len(), charstrings_at);
+    out.extend_from_slice(&charstrings_index);
+    out.extend_from_slice(&private);
+    assert_eq!(out.len(), private_at + 6);
+    out.extend_from_slice(&cff_index_v0(&local_subrs));
+    out
+}
+
+pub(crate) fn test_cff_font_bytes_v0() -> Vec<u8> {
+    let mut tables = test_font_tables_v0();
+    tables.push((TAG_CFF_V0, test_cff_table_v0()));
+    write_sfnt_v0(OutlineFormatV0::Cff, &tables).expect("test font must assemble")
+}
@@ -0,0 +1,305 @@
+use super::test_font_v0::{
+    build_font_v0, test_font_bytes_v0, test_font_format12_tables_v0, test_font_tables_v0,
+    TEST_ADVANCES_V0, TEST_GLYPH_A_V0, TEST_GLYPH_FI_V0, TEST_GLYPH_F_V0, TEST_GLYPH_I_V0,
+    TEST_GLYPH_V_V0, TEST_KERN_AV_V0,
+};
+use super::{
+    parse_font_v0, read_u16_at_v0, read_u32_at_v0, write_sfnt_v0, FontErrorV0, OutlineFormatV0,
+    ShapedGlyphV0, MAX_FONT_BYTES_V0, TAG_CMAP_V0, TAG_GPOS_V0, TAG_GSUB_V0, TAG_HEAD_V0,
+    TAG_HHEA_V0, TAG_HMTX_V0, TAG_MAXP_V0, TAG_NAME_V0,
+};
+
+fn tables_without_v0(tag: [u8; 4]) -> Vec<([u8; 4], Vec<u8>)> {
+    test_font_tables_v0()
+        .into_iter()
+        .filter(|(candidate, _)| *candidate != tag)
+        .collect()
+}
+
+fn tables_with_replaced_v0(tag: [u8; 4], table: Vec<u8>) -> Vec<([u8; 4], Vec<u8>)> {
+    test_font_tables_v0()
+        .into_iter()
+        .map(|(candidate, existing)| {
+            if candidate == tag {
+                (candidate, table.clone())
+            } else {
+                (candidate, existing)
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn parses_metrics_and_names() {
+    let font = parse_font_v0(&test_font_bytes_v0()).expect("test font should parse");
+    assert_eq!(font.outline_format_v0(), OutlineFormatV0::TrueType);
+    assert_eq!(font.units_per_em_v0(), 1000);
+    assert_eq!(font.num_glyphs_v0(), TEST_ADVANCES_V0.len() as u16);
+    assert_eq!(font.index_to_loc_format_v0(), 0);
+    assert_eq!(font.ascender_v0(), 800);
+    assert_eq!(font.descender_v0(), -200);
+    assert_eq!(font.line_gap_v0(), 90);
+    assert_eq!(font.family_name_v0(), Some("Carrel Test"));
+    assert_eq!(font.subfamily_name_v0(), Some("Regular"));
+    assert_eq!(font.full_name_v0(), Some("Carrel Test Regular"));
+    assert_eq!(font.postscript_name_v0(), Some("CarrelTest-Regular"));
+}
+
+#[test]
+fn hmtx_extends_last_advance_to_trailing_glyphs() {
+    let font = parse_font_v0(&test_font_bytes_v0()).expect("test font should parse");
+    for glyph_id in 0..5u16 {
+        assert_eq!(
+            font.advance_width_v0(glyph_id),
+            Some(TEST_ADVANCES_V0[glyph_id as usize])
+        );
+    }
+    // Glyph 5 has no long metric of its own and reuses glyph 4's advance.
+    assert_eq!(font.advance_width_v0(5), Some(TEST_ADVANCES_V0[4]));
+    assert_eq!(font.advance_width_v0(6), None);
+}
+
+#[test]
+fn cmap_format4_maps_delta_and_range_offset_segments() {
+    let font = parse_font_v0(&test_font_bytes_v0()).expect("test font should parse");
+    assert_eq!(font.glyph_id_v0('A'), Some(TEST_GLYPH_A_V0));
+    assert_eq!(font.glyph_id_v0('V'), Some(TEST_GLYPH_V_V0));
+    assert_eq!(font.glyph_id_v0('f'), Some(TEST_GLYPH_F_V0));
+    assert_eq!(font.glyph_id_v0('i'), Some(TEST_GLYPH_I_V0));
+    assert_eq!(font.glyph_id_v0('g'), None);
+    assert_eq!(font.glyph_id_v0('B'), None);
+    assert_eq!(font.glyph_id_v0('\u{1F600}'), None);
+}
+
+#[test]
+fn cmap_prefers_format12_subtable() {
+    let font = parse_font_v0(&build_font_v0(&test_font_format12_tables_v0()))
+        .expect("format 12 font should parse");
+    assert_eq!(font.glyph_id_v0('A'), Some(TEST_GLYPH_A_V0));
+    assert_eq!(font.glyph_id_v0('i'), Some(TEST_GLYPH_I_V0));
+    assert_eq!(font.glyph_id_v0('g'), None);
+}
+
+#[test]
+fn cmap_without_unicode_subtable_fails_closed() {
+    let mut cmap = Vec::<u8>::new();
+    cmap.extend_from_slice(&0u16.to_be_bytes());
+    cmap.extend_from_slice(&1u16.to_be_bytes());
+    cmap.extend_from_slice(&1u16.to_be_bytes());
+    cmap.extend_from_slice(&0u16.to_be_bytes());
+    cmap.extend_from_slice(&12u32.to_be_bytes());
+    cmap.extend_from_slice(&0u16.to_be_bytes());
+    cmap.extend_from_slice(&262u16.to_be_bytes());
+    cmap.extend_from_slice(&0u16.to_be_bytes());
+    cmap.extend_from_slice(&[0u8; 256]);
+    let bytes = build_font_v0(&tables_with_replaced_v0(TAG_CMAP_V0, cmap));
+    let error = parse_font_v0(&bytes).expect_err("format 0 cmap must be rejected");
+    assert_eq!(error, FontErrorV0::CmapUnsupported);
+    assert_eq!(error.reason_v0(), "font_cmap_unsupported");
+}
+
+#[test]
+fn gpos_pair_kerning_applies_to_listed_pair_only() {
+    let font = parse_font_v0(&test_font_bytes_v0()).expect("test font should parse");
+    assert_eq!(
+        font.kerning_v0(TEST_GLYPH_A_V0, TEST_GLYPH_V_V0),
+        TEST_KERN_AV_V0
+    );
+    assert_eq!(font.kerning_v0(TEST_GLYPH_V_V0, TEST_GLYPH_A_V0), 0);
+    assert_eq!(font.kerning_v0(TEST_GLYPH_A_V0, TEST_GLYPH_A_V0), 0);
+}
+
+#[test]
+fn gsub_ligature_matches_component_sequence() {
+    let font = parse_font_v0(&test_font_bytes_v0()).expect("test font should parse");
+    assert_eq!(
+        font.ligature_v0(&[TEST_GLYPH_F_V0, TEST_GLYPH_I_V0, TEST_GLYPH_A_V0]),
+        Some((TEST_GLYPH_FI_V0, 2))
+    );
+    assert_eq!(font.ligature_v0(&[TEST_GLYPH_F_V0]), None);
+    assert_eq!(font.ligature_v0(&[TEST_GLYPH_F_V0, TEST_GLYPH_A_V0]), None);
+    assert_eq!(font.ligature_v0(&[]), None);
+}
+
+#[test]
+fn shape_text_applies_ligatures_then_kerning() {
+    let font = parse_font_v0(&test_font_bytes_v0()).expect("test font should parse");
+    let shaped = font.shape_text_v0("fiAV?").expect("shaping should succeed");
+    assert_eq!(
+        shaped,
+        vec![
+            ShapedGlyphV0 {
+                glyph_id: TEST_GLYPH_FI_V0,
+                x_advance: TEST_ADVANCES_V0[4] as i32,
+                cluster_len: 2,
+            },
+            ShapedGlyphV0 {
+                glyph_id: TEST_GLYPH_A_V0,
+                x_advance: TEST_ADVANCES_V0[3] as i32 + TEST_KERN_AV_V0 as i32,
+                cluster_len: 1,
+            },
+            ShapedGlyphV0 {
+                glyph_id: TEST_GLYPH_V_V0,
+                x_advance: TEST_ADVANCES_V0[4] as i32,
+                cluster_len: 1,
+            },
+            ShapedGlyphV0 {
+                glyph_id: 0,
+                x_advance: TEST_ADVANCES_V0[0] as i32,
+                cluster_len: 1,
+            },
+        ]
+    );
+}
+
+#[test]
+fn layout_tables_are_optional() {
+    let mut tables = tables_without_v0(TAG_GPOS_V0);
+    tables.retain(|(tag, _)| *tag != TAG_GSUB_V0);
+    let font = parse_font_v0(&build_font_v0(&tables)).expect("font without layout should parse");
+    assert_eq!(font.kerning_v0(TEST_GLYPH_A_V0, TEST_GLYPH_V_V0), 0);
+    assert_eq!(font.ligature_v0(&[TEST_GLYPH_F_V0, TEST_GLYPH_I_V0]), None);
+    assert_eq!(font.table_range_v0(TAG_GPOS_V0), None);
+}
+
+#[test]
+fn missing_required_table_fails_closed_with_tag() {
+    for tag in [
+        TAG_HEAD_V0,
+        TAG_MAXP_V0,
+        TAG_HHEA_V0,
+        TAG_HMTX_V0,
+        TAG_CMAP_V0,
+        TAG_NAME_V0,
+    ] {
+        let error = parse_font_v0(&build_font_v0(&tables_without_v0(tag)))
+            .expect_err("missing required table must fail");
+        assert_eq!(error, FontErrorV0::MissingTable(tag));
+        assert_eq!(error.reason_v0(), "font_table_missing");
+        assert_eq!(error.table_tag_v0(), Some(tag));
+    }
+}
+
+#[test]
+fn rejects_bad_sfnt_header_and_size() {
+    assert_eq!(parse_font_v0(&[]).unwrap_err(), FontErrorV0::Truncated);
+    assert_eq!(
+        parse_font_v0(b"wOFF\0\0\0\0\0\0\0\0").unwrap_err(),
+        FontErrorV0::SfntVersionUnsupported
+    );
+    let mut bytes = test_font_bytes_v0();
+    bytes.truncate(20);
+    assert_eq!(parse_font_v0(&bytes).unwrap_err(), FontErrorV0::Truncated);
+    let oversized = vec![0u8; MAX_FONT_BYTES_V0 + 1];
+    assert_eq!(
+        parse_font_v0(&oversized).unwrap_err(),
+        FontErrorV0::TooLarge
+    );
+}
+
+#[test]
+fn rejects_table_record_out_of_bounds() {
+    let mut bytes = test_font_bytes_v0();
+    let num_tables = read_u16_at_v0(&bytes, 4).expect("num tables") as usize;
+    let last_record = 12 + (num_tables - 1) * 16;
+    let tag = [
+        bytes[last_record],
+        bytes[last_record + 1],
+        bytes[last_record + 2],
+        bytes[last_record + 3],
+    ];
+    let offset = read_u32_at_v0(&bytes, last_record + 8).expect("offset");
+    let overflowing_length = bytes.len() as u32 - offset + 1;
+    bytes[last_record + 12..last_record + 16].copy_from_slice(&overflowing_length.to_be_bytes());
+    assert_eq!(
+        parse_font_v0(&bytes).unwrap_err(),
+        FontErrorV0::TableOutOfBounds(tag)
+    );
+}
+
+#[test]
+fn rejects_truncated_required_tables() {
+    for tag in [
+        TAG_HEAD_V0,
+        TAG_HHEA_V0,
+        TAG_HMTX_V0,
+        TAG_CMAP_V0,
+        TAG_NAME_V0,
+    ] {
+        let truncated = test_font_tables_v0()
+            .into_iter()
+            .find(|(candidate, _)| *candidate == tag)
+            .map(|(_, table)| table[..table.len() / 2].to_vec())
+            .expect("table exists");
+        let error = parse_font_v0(&build_font_v0(&tables_with_replaced_v0(tag, truncated)))
+            .expect_err("truncated table must fail");
+        assert_eq!(error, FontErrorV0::InvalidTable(tag));
+        assert_eq!(error.reason_v0(), "font_table_invalid");
+    }
+}
+
+#[test]
+fn rejects_ligature_glyph_outside_font() {
+    let mut tables = test_font_tables_v0();
+    for (tag, table) in tables.iter_mut() {
+        if *tag == TAG_MAXP_V0 {
+            table[4..6].copy_from_slice(&5u16.to_be_bytes());
+        }
+    }
+    assert_eq!(
+        parse_font_v0(&build_font_v0(&tables)).unwrap_err(),
+        FontErrorV0::InvalidTable(TAG_GSUB_V0)
+    );
+}
+
+#[test]
+fn rejects_truncated_layout_tables() {
+    for tag in [TAG_GPOS_V0, TAG_GSUB_V0] {
+        let truncated = test_font_tables_v0()
+            .into_iter()
+            .find(|(candidate, _)| *candidate == tag)
+            .map(|(_, table)| table[..table.len() - 2].to_vec())
+            .expect("table exists");
+        assert_eq!(
+            parse_font_v0(&build_font_v0(&tables_with_replaced_v0(tag, truncated))).unwrap_err(),
+            FontErrorV0::InvalidTable(tag)
+        );
+    }
+}
+
+#[test]
+fn design_units_scale_to_scaled_points() {
+    let font = parse_font_v0(&test_font_bytes_v0()).expect("test font should parse");
+    let ten_pt = 10 * 65_536;
+    assert_eq!(font.design_units_to_sp_v0(500, ten_pt), Some(327_680));
+    assert_eq!(font.design_units_to_sp_v0(-80, ten_pt), Some(-52_428));
+    assert_eq!(font.design_units_to_sp_v0(500, 0), None);
+}
+
+#[test]
+fn sfnt_writer_is_deterministic_and_order_independent() {
+    let tables = test_font_tables_v0();
+    let mut reversed = tables.clone();
+    reversed.reverse();
+    let forward = write_sfnt_v0(OutlineFormatV0::TrueType, &tables).expect("write");
+    let backward = write_sfnt_v0(OutlineFormatV0::TrueType, &reversed).expect("write");
+    assert_eq!(forward, backward);
+    assert_eq!(forward.len() % 4, 0);
+    let font = parse_font_v0(&forward).expect("written font should parse");
+    let (head_offset, _) = font.table_range_v0(TAG_HEAD_V0).expect("head range");
+    let checksum = forward.chunks(4).fold(0u32, |sum, chunk| {
+        sum.wrapping_add(u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+    });
+    assert_eq!(checksum, 0xb1b0_afba);
+    assert_ne!(read_u32_at_v0(&forward, head_offset + 8), Some(0));
+}
+
+#[test]
+fn sfnt_writer_rejects_duplicate_tables() {
+    let mut tables = test_font_tables_v0();
+    tables.push((TAG_NAME_V0, Vec::new()));
+    assert_eq!(
+        write_sfnt_v0(OutlineFormatV0::TrueType, &tables).unwrap_err(),
+        FontErrorV0::DuplicateTable(TAG_NAME_V0)
+    );
+}
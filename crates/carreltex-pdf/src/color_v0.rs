@@ -0,0 +1,79 @@
+use carreltex_xdv::decimal_text_v0;
+
+/// Graphics state of a fresh page content stream.
+const DEFAULT_COLOR_OPERATORS_V0: &str = "0 g 0 G";
+
+/// Fill and stroke operators for a dvips color specification such as `rgb 1 0 0`.
+fn color_operators_v0(words: &[&[u8]]) -> Option<String> {
+    let (model, components) = words.split_first()?;
+    let (count, fill, stroke) = match *model {
+        b"gray" => (1, "g", "G"),
+        b"rgb" => (3, "rg", "RG"),
+        b"cmyk" => (4, "k", "K"),
+        _ => return None,
+    };
+    if components.len() != count {
+        return None;
+    }
+    let values = components
+        .iter()
+        .map(|component| {
+            core::str::from_utf8(component)
+                .ok()?
+                .parse::<f64>()
+                .ok()
+                .filter(|value| (0.0..=1.0).contains(value))
+                .map(decimal_text_v0)
+        })
+        .collect::<Option<Vec<_>>>()?
+        .join(" ");
+    Some(format!("{values} {fill} {values} {stroke}"))
+}
+
+/// `color push`/`color pop` state; it carries across pages like the dvips stack.
+/// Specifications outside gray/rgb/cmyk push the current color so pops still pair up.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PdfColorStackV0 {
+    stack: Vec<String>,
+}
+
+impl PdfColorStackV0 {
+    pub(crate) fn current_v0(&self) -> &str {
+        self.stack
+            .last()
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_COLOR_OPERATORS_V0)
+    }
+
+    pub(crate) fn apply_special_v0(&mut self, payload: &[u8]) {
+        let words = payload
+            .split(|byte| byte.is_ascii_whitespace())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>();
+        match words.as_slice() {
+            [b"color", b"push", spec @ ..] => {
+                let operators =
+                    color_operators_v0(spec).unwrap_or_else(|| self.current_v0().to_string());
+                self.stack.push(operators);
+            }
+            [b"color", b"pop"] => {
+                self.stack.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Writes the operators of `stack`'s current color when they differ from `emitted`.
+pub(crate) fn sync_color_v0(out: &mut String, emitted: &mut String, stack: &PdfColorStackV0) {
+    if emitted != stack.current_v0() {
+        *emitted = stack.current_v0().to_string();
+        out.push_str(emitted);
+        out.push('\n');
+    }
+}
+
+/// A page's initial color as emitted state: content streams start black.
+pub(crate) fn page_start_color_v0() -> String {
+    DEFAULT_COLOR_OPERATORS_V0.to_string()
+}
@@ -0,0 +1,148 @@
+use carreltex_xdv::{DviImageKindV0, DviImageV0};
+
+use crate::inflate_v0::inflate_zlib_v0;
+use crate::{hex_v0, PdfObjectsV0, PdfWriteErrorV0, MAX_PDF_BYTES_V0};
+
+/// Samples of a PNG row of `width` pixels and the bytes of one pixel, at least one.
+fn png_row_bytes_v0(width: u32, channels: u8, bit_depth: u8) -> (usize, usize) {
+    let bits = width as usize * usize::from(channels) * usize::from(bit_depth);
+    (
+        bits.div_ceil(8),
+        (usize::from(channels) * usize::from(bit_depth) / 8).max(1),
+    )
+}
+
+fn paeth_v0(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = i16::from(left) + i16::from(up) - i16::from(up_left);
+    let distances = [left, up, up_left].map(|value| (estimate - i16::from(value)).abs());
+    if distances[0] <= distances[1] && distances[0] <= distances[2] {
+        left
+    } else if distances[1] <= distances[2] {
+        up
+    } else {
+        up_left
+    }
+}
+
+/// Scanlines of inflated PNG `data` with their filters undone; `None` unless the
+/// data holds exactly `height` filtered rows.
+fn unfilter_png_v0(
+    data: &[u8],
+    height: u32,
+    row_bytes: usize,
+    pixel_bytes: usize,
+) -> Option<Vec<u8>> {
+    if data.len() != height as usize * (row_bytes + 1) {
+        return None;
+    }
+    let mut out = Vec::<u8>::with_capacity(height as usize * row_bytes);
+    for row in data.chunks_exact(row_bytes + 1) {
+        let start = out.len();
+        for (index, byte) in row[1..].iter().enumerate() {
+            let left = index
+                .checked_sub(pixel_bytes)
+                .map_or(0, |back| out[start + back]);
+            let up = start
+                .checked_sub(row_bytes)
+                .map_or(0, |back| out[back + index]);
+            let up_left = match (start.checked_sub(row_bytes), index.checked_sub(pixel_bytes)) {
+                (Some(above), Some(back)) => out[above + back],
+                _ => 0,
+            };
+            let predicted = match row[0] {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((u16::from(left) + u16::from(up)) / 2) as u8,
+                4 => paeth_v0(left, up, up_left),
+                _ => return None,
+            };
+            out.push(byte.wrapping_add(predicted));
+        }
+    }
+    Some(out)
+}
+
+/// Image XObject for `image`, read from `bytes`; returns its object number. JPEGs
+/// are embedded as they are; PNGs keep their compressed samples under the PNG
+/// predictor, except with alpha, whose samples are split into a soft mask and
+/// written uncompressed.
+pub(crate) fn add_image_v0(
+    objects: &mut PdfObjectsV0,
+    image: &DviImageV0,
+    bytes: &[u8],
+) -> Result<usize, PdfWriteErrorV0> {
+    let (width, height) = (image.width, image.height);
+    let DviImageKindV0::Png {
+        bit_depth,
+        color_type,
+        palette,
+        data,
+    } = &image.kind
+    else {
+        let DviImageKindV0::Jpeg { components } = image.kind else {
+            unreachable!("image kinds are PNG or JPEG");
+        };
+        let color_space = if components == 1 {
+            "/DeviceGray"
+        } else {
+            "/DeviceRGB"
+        };
+        return Ok(objects.add_stream_v0(
+            &format!(
+                " /Type /XObject /Subtype /Image /Width {width} /Height {height} /ColorSpace {color_space} /BitsPerComponent 8 /Filter /DCTDecode"
+            ),
+            bytes,
+        ));
+    };
+    let (color_space, colors, alpha) = match color_type {
+        0 => ("/DeviceGray".to_string(), 1u8, false),
+        2 => ("/DeviceRGB".to_string(), 3, false),
+        3 => (
+            format!(
+                "[/Indexed /DeviceRGB {} <{}>]",
+                palette.len() / 3 - 1,
+                hex_v0(palette)
+            ),
+            1,
+            false,
+        ),
+        4 => ("/DeviceGray".to_string(), 1, true),
+        _ => ("/DeviceRGB".to_string(), 3, true),
+    };
+    let channels = colors + u8::from(alpha);
+    let (row_bytes, pixel_bytes) = png_row_bytes_v0(width, channels, *bit_depth);
+    let limit = height as usize * (row_bytes + 1);
+    if limit > MAX_PDF_BYTES_V0 {
+        return Err(PdfWriteErrorV0::TooLarge);
+    }
+    let samples = inflate_zlib_v0(data, limit)
+        .and_then(|inflated| unfilter_png_v0(&inflated, height, row_bytes, pixel_bytes))
+        .ok_or(PdfWriteErrorV0::ImageInvalid)?;
+    let dict = format!(
+        " /Type /XObject /Subtype /Image /Width {width} /Height {height} /ColorSpace {color_space} /BitsPerComponent {bit_depth}"
+    );
+    if !alpha {
+        return Ok(objects.add_stream_v0(
+            &format!(
+                "{dict} /Filter /FlateDecode /DecodeParms << /Predictor 15 /Colors {colors} /BitsPerComponent {bit_depth} /Columns {width} >>"
+            ),
+            data,
+        ));
+    }
+    let sample_bytes = usize::from(*bit_depth / 8);
+    let color_bytes = usize::from(colors) * sample_bytes;
+    let mut color = Vec::with_capacity(samples.len() / pixel_bytes * color_bytes);
+    let mut mask = Vec::with_capacity(samples.len() / pixel_bytes * sample_bytes);
+    for pixel in samples.chunks_exact(pixel_bytes) {
+        color.extend_from_slice(&pixel[..color_bytes]);
+        mask.extend_from_slice(&pixel[color_bytes..]);
+    }
+    let mask_id = objects.add_stream_v0(
+        &format!(
+            " /Type /XObject /Subtype /Image /Width {width} /Height {height} /ColorSpace /DeviceGray /BitsPerComponent {bit_depth}"
+        ),
+        &mask,
+    );
+    Ok(objects.add_stream_v0(&format!("{dict} /SMask {mask_id} 0 R"), &color))
+}
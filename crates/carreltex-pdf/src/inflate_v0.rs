@@ -0,0 +1,224 @@
+/// Base lengths and extra bits of length codes 257..=285 (RFC 1951).
+const LENGTH_BASES_V0: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS_V0: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+/// Base distances and extra bits of distance codes 0..=29 (RFC 1951).
+const DISTANCE_BASES_V0: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS_V0: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+/// Order of the code length code lengths in a dynamic block header.
+const CODE_LENGTH_ORDER_V0: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+const MAX_CODE_BITS_V0: usize = 15;
+
+/// Bits of a deflate stream, least significant first.
+struct BitsV0<'a> {
+    data: &'a [u8],
+    at: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitsV0<'_> {
+    fn bits_v0(&mut self, count: u32) -> Option<u32> {
+        while self.count < count {
+            self.buffer |= u32::from(*self.data.get(self.at)?) << self.count;
+            self.at += 1;
+            self.count += 8;
+        }
+        let value = self.buffer & ((1u32 << count) - 1);
+        self.buffer >>= count;
+        self.count -= count;
+        Some(value)
+    }
+
+    fn align_v0(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+}
+
+/// Canonical Huffman code: the number of codes of each length and the symbols in
+/// code order.
+struct HuffmanV0 {
+    counts: [u16; MAX_CODE_BITS_V0 + 1],
+    symbols: Vec<u16>,
+}
+
+impl HuffmanV0 {
+    /// `None` for over-subscribed code lengths; incomplete codes are allowed.
+    fn new_v0(lengths: &[u8]) -> Option<Self> {
+        let mut counts = [0u16; MAX_CODE_BITS_V0 + 1];
+        for length in lengths {
+            counts[usize::from(*length)] += 1;
+        }
+        let mut left = 1i32;
+        for count in &counts[1..] {
+            left = 2 * left - i32::from(*count);
+            if left < 0 {
+                return None;
+            }
+        }
+        let mut offsets = [0u16; MAX_CODE_BITS_V0 + 2];
+        for length in 1..=MAX_CODE_BITS_V0 {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0u16; usize::from(offsets[MAX_CODE_BITS_V0 + 1])];
+        for (symbol, length) in lengths.iter().enumerate() {
+            if *length != 0 {
+                let offset = &mut offsets[usize::from(*length)];
+                symbols[usize::from(*offset)] = symbol as u16;
+                *offset += 1;
+            }
+        }
+        counts[0] = 0;
+        Some(HuffmanV0 { counts, symbols })
+    }
+
+    fn decode_v0(&self, bits: &mut BitsV0) -> Option<u16> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for count in &self.counts[1..] {
+            code |= bits.bits_v0(1)? as i32;
+            let count = i32::from(*count);
+            if code - count < first {
+                return self.symbols.get((index + code - first) as usize).copied();
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        None
+    }
+}
+
+fn fixed_codes_v0() -> (HuffmanV0, HuffmanV0) {
+    let mut lengths = [8u8; 288];
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    let literals = HuffmanV0::new_v0(&lengths).expect("fixed literal code");
+    let distances = HuffmanV0::new_v0(&[5; 30]).expect("fixed distance code");
+    (literals, distances)
+}
+
+fn dynamic_codes_v0(bits: &mut BitsV0) -> Option<(HuffmanV0, HuffmanV0)> {
+    let literal_count = bits.bits_v0(5)? as usize + 257;
+    let distance_count = bits.bits_v0(5)? as usize + 1;
+    let code_length_count = bits.bits_v0(4)? as usize + 4;
+    if literal_count > 286 || distance_count > 30 {
+        return None;
+    }
+    let mut code_lengths = [0u8; 19];
+    for position in &CODE_LENGTH_ORDER_V0[..code_length_count] {
+        code_lengths[*position] = bits.bits_v0(3)? as u8;
+    }
+    let code_length_code = HuffmanV0::new_v0(&code_lengths)?;
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (length, repeat) = match code_length_code.decode_v0(bits)? {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => (*lengths.last()?, 3 + bits.bits_v0(2)?),
+            17 => (0, 3 + bits.bits_v0(3)?),
+            _ => (0, 11 + bits.bits_v0(7)?),
+        };
+        if lengths.len() + repeat as usize > literal_count + distance_count {
+            return None;
+        }
+        lengths.extend(std::iter::repeat_n(length, repeat as usize));
+    }
+    if lengths[256] == 0 {
+        return None;
+    }
+    Some((
+        HuffmanV0::new_v0(&lengths[..literal_count])?,
+        HuffmanV0::new_v0(&lengths[literal_count..])?,
+    ))
+}
+
+fn inflate_block_v0(
+    bits: &mut BitsV0,
+    (literals, distances): &(HuffmanV0, HuffmanV0),
+    out: &mut Vec<u8>,
+    limit: usize,
+) -> Option<()> {
+    loop {
+        let symbol = usize::from(literals.decode_v0(bits)?);
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Some(()),
+            _ => {
+                let code = symbol - 257;
+                let length = usize::from(*LENGTH_BASES_V0.get(code)?)
+                    + bits.bits_v0(u32::from(LENGTH_EXTRA_BITS_V0[code]))? as usize;
+                let code = usize::from(distances.decode_v0(bits)?);
+                let distance = usize::from(*DISTANCE_BASES_V0.get(code)?)
+                    + bits.bits_v0(u32::from(DISTANCE_EXTRA_BITS_V0[code]))? as usize;
+                if distance > out.len() {
+                    return None;
+                }
+                for _ in 0..length {
+                    out.push(out[out.len() - distance]);
+                }
+            }
+        }
+        if out.len() > limit {
+            return None;
+        }
+    }
+}
+
+/// Decompresses a zlib stream of at most `limit` bytes; `None` for damaged or
+/// longer streams. The Adler-32 trailer is not checked.
+pub(crate) fn inflate_zlib_v0(data: &[u8], limit: usize) -> Option<Vec<u8>> {
+    let (&method, &flags) = (data.first()?, data.get(1)?);
+    if method & 0x0f != 8
+        || (u16::from(method) << 8 | u16::from(flags)) % 31 != 0
+        || flags & 0x20 != 0
+    {
+        return None;
+    }
+    let mut bits = BitsV0 {
+        data: &data[2..],
+        at: 0,
+        buffer: 0,
+        count: 0,
+    };
+    let mut out = Vec::new();
+    loop {
+        let last = bits.bits_v0(1)? == 1;
+        match bits.bits_v0(2)? {
+            0 => {
+                bits.align_v0();
+                let header = bits.data.get(bits.at..bits.at + 4)?;
+                let length = usize::from(u16::from_le_bytes([header[0], header[1]]));
+                if u16::from_le_bytes([header[2], header[3]]) != !(length as u16) {
+                    return None;
+                }
+                bits.at += 4;
+                out.extend_from_slice(bits.data.get(bits.at..bits.at + length)?);
+                bits.at += length;
+                if out.len() > limit {
+                    return None;
+                }
+            }
+            1 => inflate_block_v0(&mut bits, &fixed_codes_v0(), &mut out, limit)?,
+            2 => {
+                let codes = dynamic_codes_v0(&mut bits)?;
+                inflate_block_v0(&mut bits, &codes, &mut out, limit)?
+            }
+            _ => return None,
+        }
+        if last {
+            return Some(out);
+        }
+    }
+}
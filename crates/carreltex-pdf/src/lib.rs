@@ -0,0 +1,675 @@
+mod color_v0;
+mod image_v0;
+mod inflate_v0;
+mod md5_v0;
+
+use core::fmt::Write;
+use std::collections::BTreeMap;
+
+use carreltex_fonts::{parse_font_v0, FontErrorV0, FontV0, OutlineFormatV0};
+use carreltex_xdv::{
+    decimal_text_v0, parse_image_special_v0, parse_image_v0, DviDocumentV0, DviFontKindV0,
+    DviGlyphV0, DviPageV0, DEFAULT_FALLBACK_FONT_SIZE_SP_V0, DEFAULT_PAPER_HEIGHT_SP_V0,
+    DEFAULT_PAPER_WIDTH_SP_V0, ONE_INCH_SP_V0,
+};
+use color_v0::{page_start_color_v0, sync_color_v0, PdfColorStackV0};
+use image_v0::add_image_v0;
+use md5_v0::md5_v0;
+
+pub const MAX_PDF_PAGES_V0: usize = 10_000;
+pub const MAX_PDF_BYTES_V0: usize = 32 * 1024 * 1024;
+
+const PDF_HEADER_V0: &[u8] = b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n";
+const PDF_PRODUCER_V0: &str = "carreltex";
+/// Standard 14 font used for TFM fonts without a mounted file.
+const STANDARD_FONT_V0: &str = "Courier";
+/// Standard 14 font, in its own encoding, for the math symbol fonts (`*-sy`, `*-ex`)
+/// without a mounted file.
+const SYMBOL_FONT_V0: &str = "Symbol";
+/// Entries per `beginbfchar` block (the CMap limit).
+const MAX_BFCHAR_ENTRIES_V0: usize = 100;
+const SECONDS_PER_DAY_V0: u64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PdfWriteOptionsV0 {
+    pub paper_width_sp: i32,
+    pub paper_height_sp: i32,
+    /// Position of the DVI origin from the top-left paper corner (TeX uses 1in, 1in).
+    pub origin_x_sp: i32,
+    pub origin_y_sp: i32,
+    pub fallback_font_size_sp: i32,
+    /// Seconds since 1970-01-01 UTC for `CreationDate`/`ModDate`; also mixed into `/ID`.
+    pub source_date_epoch: u64,
+}
+
+impl Default for PdfWriteOptionsV0 {
+    fn default() -> Self {
+        PdfWriteOptionsV0 {
+            paper_width_sp: DEFAULT_PAPER_WIDTH_SP_V0,
+            paper_height_sp: DEFAULT_PAPER_HEIGHT_SP_V0,
+            origin_x_sp: ONE_INCH_SP_V0,
+            origin_y_sp: ONE_INCH_SP_V0,
+            fallback_font_size_sp: DEFAULT_FALLBACK_FONT_SIZE_SP_V0,
+            source_date_epoch: 0,
+        }
+    }
+}
+
+/// A mounted OpenType/TrueType file, matched against DVI font names (TFM fonts) and
+/// XDV native font filenames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PdfFontFileV0<'a> {
+    pub name: &'a [u8],
+    pub bytes: &'a [u8],
+}
+
+/// A mounted PNG or JPEG file, matched against the names of `pdf:image` specials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PdfImageFileV0<'a> {
+    pub name: &'a [u8],
+    pub bytes: &'a [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdfWriteErrorV0 {
+    OptionsInvalid,
+    UnitsInvalid,
+    TooManyPages,
+    TooLarge,
+    FontUndefined(i32),
+    NativeFontUnavailable(i32),
+    Font(i32, FontErrorV0),
+    GlyphUnmapped(i32, u32),
+    ImageUnavailable,
+    ImageInvalid,
+}
+
+impl PdfWriteErrorV0 {
+    pub fn reason_v0(&self) -> &'static str {
+        match self {
+            PdfWriteErrorV0::OptionsInvalid => "pdf_options_invalid",
+            PdfWriteErrorV0::UnitsInvalid => "pdf_units_invalid",
+            PdfWriteErrorV0::TooManyPages => "pdf_too_many_pages",
+            PdfWriteErrorV0::TooLarge => "pdf_too_large",
+            PdfWriteErrorV0::FontUndefined(_) => "pdf_font_undefined",
+            PdfWriteErrorV0::NativeFontUnavailable(_) => "pdf_native_font_unavailable",
+            PdfWriteErrorV0::Font(_, error) => error.reason_v0(),
+            PdfWriteErrorV0::GlyphUnmapped(_, _) => "pdf_glyph_unmapped",
+            PdfWriteErrorV0::ImageUnavailable => "pdf_image_unavailable",
+            PdfWriteErrorV0::ImageInvalid => "pdf_image_invalid",
+        }
+    }
+}
+
+/// How glyphs of one DVI font are written.
+enum PdfFontV0<'a> {
+    /// Subset of a mounted font, shown by glyph id; TFM char codes go through its cmap.
+    Embedded {
+        font: Box<FontV0>,
+        bytes: &'a [u8],
+        size_bp: f64,
+        native: bool,
+    },
+    /// The non-embedded standard font, shown by char code; `symbol` selects the
+    /// Symbol font over Courier.
+    Standard { size_bp: f64, symbol: bool },
+}
+
+/// Glyph ids used per embedded font, with the char each came from (TFM fonts only).
+type UsedGlyphsV0 = BTreeMap<i32, BTreeMap<u16, Option<char>>>;
+
+/// PDF name object; bytes outside the regular printable set use `#xx` escapes.
+fn pdf_name_v0(name: &str) -> String {
+    let mut out = String::from("/");
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'+' | b'.') {
+            out.push(byte as char);
+        } else {
+            let _ = write!(out, "#{byte:02X}");
+        }
+    }
+    out
+}
+
+fn hex_v0(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02X}");
+        out
+    })
+}
+
+/// `D:YYYYMMDDHHmmSSZ` for seconds since the Unix epoch (proleptic Gregorian, UTC).
+fn pdf_date_v0(epoch: u64) -> Option<String> {
+    let days = (epoch / SECONDS_PER_DAY_V0) as i64;
+    let seconds = epoch % SECONDS_PER_DAY_V0;
+    // Civil-from-days over 400-year eras starting 0000-03-01.
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    if year > 9999 {
+        return None;
+    }
+    Some(format!(
+        "D:{year:04}{month:02}{day:02}{:02}{:02}{:02}Z",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    ))
+}
+
+/// Converts DVI units to PDF points (bp): `num/den` gives units of 10^-7 m, scaled by
+/// `mag/1000`. PDF y grows upwards from the bottom paper edge.
+struct UnitsV0 {
+    bp_per_unit: f64,
+    origin_x_bp: f64,
+    origin_y_bp: f64,
+    page_height_bp: f64,
+}
+
+impl UnitsV0 {
+    fn x_v0(&self, h: i32) -> f64 {
+        self.origin_x_bp + h as f64 * self.bp_per_unit
+    }
+
+    fn y_v0(&self, v: i32) -> f64 {
+        self.page_height_bp - (self.origin_y_bp + v as f64 * self.bp_per_unit)
+    }
+
+    fn length_v0(&self, units: i32) -> f64 {
+        units as f64 * self.bp_per_unit
+    }
+}
+
+fn sp_to_bp_v0(sp: i32) -> f64 {
+    sp as f64 / 65_536.0 * 72.0 / 72.27
+}
+
+fn resolve_fonts_v0<'a>(
+    document: &DviDocumentV0,
+    files: &[PdfFontFileV0<'a>],
+    units: &UnitsV0,
+    options: &PdfWriteOptionsV0,
+) -> Result<BTreeMap<i32, PdfFontV0<'a>>, PdfWriteErrorV0> {
+    let mut fonts = BTreeMap::new();
+    for definition in &document.fonts {
+        let (name, size_bp, native) = match &definition.kind {
+            DviFontKindV0::Tfm(tfm) => {
+                let size_bp = match tfm.scale {
+                    0 => sp_to_bp_v0(options.fallback_font_size_sp),
+                    scale => units.length_v0(scale),
+                };
+                (tfm.name.as_slice(), size_bp, false)
+            }
+            DviFontKindV0::Native(native) => (
+                native.filename.as_slice(),
+                units.length_v0(native.size),
+                true,
+            ),
+        };
+        let file = files.iter().find(|file| file.name == name);
+        let font = match file {
+            Some(file) => PdfFontV0::Embedded {
+                font: Box::new(
+                    parse_font_v0(file.bytes)
+                        .map_err(|error| PdfWriteErrorV0::Font(definition.font_num, error))?,
+                ),
+                bytes: file.bytes,
+                size_bp,
+                native,
+            },
+            None if native => {
+                return Err(PdfWriteErrorV0::NativeFontUnavailable(definition.font_num))
+            }
+            None => PdfFontV0::Standard {
+                size_bp,
+                symbol: name.ends_with(b"-sy") || name.ends_with(b"-ex"),
+            },
+        };
+        fonts.insert(definition.font_num, font);
+    }
+    Ok(fonts)
+}
+
+/// Bytes of the string shown for `glyph`: a big-endian glyph id for embedded fonts,
+/// the char code for the standard font.
+fn glyph_code_v0(
+    glyph: &DviGlyphV0,
+    font: &PdfFontV0,
+    used: &mut UsedGlyphsV0,
+) -> Result<Vec<u8>, PdfWriteErrorV0> {
+    let unmapped = PdfWriteErrorV0::GlyphUnmapped(glyph.font_num, glyph.code);
+    match font {
+        PdfFontV0::Standard { .. } => Ok(vec![u8::try_from(glyph.code).map_err(|_| unmapped)?]),
+        PdfFontV0::Embedded { font, native, .. } => {
+            let (glyph_id, source) = if *native {
+                let glyph_id = u16::try_from(glyph.code).map_err(|_| unmapped)?;
+                if glyph_id >= font.num_glyphs_v0() {
+                    return Err(PdfWriteErrorV0::Font(
+                        glyph.font_num,
+                        FontErrorV0::GlyphIdInvalid,
+                    ));
+                }
+                (glyph_id, None)
+            } else {
+                let ch = char::from_u32(glyph.code).ok_or(unmapped)?;
+                (font.glyph_id_v0(ch).ok_or(unmapped)?, Some(ch))
+            };
+            used.entry(glyph.font_num)
+                .or_default()
+                .entry(glyph_id)
+                .or_insert(source);
+            Ok(glyph_id.to_be_bytes().to_vec())
+        }
+    }
+}
+
+/// Content stream of one page. Images are drawn first, then rules, then text; the
+/// rule and text passes replay the page's color specials in DVI order, and `colors`
+/// leaves with the page's final stack.
+fn page_content_v0(
+    page: &DviPageV0,
+    fonts: &BTreeMap<i32, PdfFontV0>,
+    images: &BTreeMap<&[u8], usize>,
+    units: &UnitsV0,
+    used: &mut UsedGlyphsV0,
+    colors: &mut PdfColorStackV0,
+) -> Result<String, PdfWriteErrorV0> {
+    let mut out = String::new();
+    for special in &page.specials {
+        let Some(image) = parse_image_special_v0(&special.bytes) else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "q {} 0 0 {} {} {} cm /Im{} Do Q",
+            decimal_text_v0(units.length_v0(image.width_sp)),
+            decimal_text_v0(units.length_v0(image.height_sp)),
+            decimal_text_v0(units.x_v0(special.h)),
+            decimal_text_v0(units.y_v0(special.v)),
+            images[image.name]
+        );
+    }
+    let mut emitted = page_start_color_v0();
+    let mut rule_colors = colors.clone();
+    let mut specials = page.specials.iter().peekable();
+    for (rule_index, rule) in page.rules.iter().enumerate() {
+        while let Some(special) = specials.next_if(|special| special.rule_index <= rule_index) {
+            rule_colors.apply_special_v0(&special.bytes);
+        }
+        sync_color_v0(&mut out, &mut emitted, &rule_colors);
+        let _ = writeln!(
+            out,
+            "{} {} {} {} re f",
+            decimal_text_v0(units.x_v0(rule.h)),
+            decimal_text_v0(units.y_v0(rule.v)),
+            decimal_text_v0(units.length_v0(rule.width)),
+            decimal_text_v0(units.length_v0(rule.height))
+        );
+    }
+    let mut specials = page.specials.iter().peekable();
+    if !page.glyphs.is_empty() {
+        out.push_str("BT\n");
+    }
+    let mut current_font = None::<i32>;
+    for (glyph_index, glyph) in page.glyphs.iter().enumerate() {
+        while let Some(special) = specials.next_if(|special| special.glyph_index <= glyph_index) {
+            colors.apply_special_v0(&special.bytes);
+        }
+        sync_color_v0(&mut out, &mut emitted, colors);
+        let font = fonts
+            .get(&glyph.font_num)
+            .ok_or(PdfWriteErrorV0::FontUndefined(glyph.font_num))?;
+        let code = glyph_code_v0(glyph, font, used)?;
+        if current_font != Some(glyph.font_num) {
+            let size_bp = match font {
+                PdfFontV0::Embedded { size_bp, .. } | PdfFontV0::Standard { size_bp, .. } => {
+                    *size_bp
+                }
+            };
+            let _ = writeln!(out, "/F{} {} Tf", glyph.font_num, decimal_text_v0(size_bp));
+            current_font = Some(glyph.font_num);
+        }
+        let _ = writeln!(
+            out,
+            "1 0 0 1 {} {} Tm <{}> Tj",
+            decimal_text_v0(units.x_v0(glyph.h)),
+            decimal_text_v0(units.y_v0(glyph.v)),
+            hex_v0(&code)
+        );
+    }
+    for special in specials {
+        colors.apply_special_v0(&special.bytes);
+    }
+    if !page.glyphs.is_empty() {
+        out.push_str("ET\n");
+    }
+    Ok(out)
+}
+
+/// Objects numbered from 1 in reservation order.
+#[derive(Default)]
+struct PdfObjectsV0 {
+    objects: Vec<Vec<u8>>,
+}
+
+impl PdfObjectsV0 {
+    fn reserve_v0(&mut self) -> usize {
+        self.objects.push(Vec::new());
+        self.objects.len()
+    }
+
+    fn set_v0(&mut self, id: usize, body: impl Into<Vec<u8>>) {
+        self.objects[id - 1] = body.into();
+    }
+
+    fn add_v0(&mut self, body: impl Into<Vec<u8>>) -> usize {
+        let id = self.reserve_v0();
+        self.set_v0(id, body);
+        id
+    }
+
+    fn add_stream_v0(&mut self, dict_entries: &str, data: &[u8]) -> usize {
+        let mut body = format!("<< /Length {}{dict_entries} >>\nstream\n", data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.add_v0(body)
+    }
+}
+
+fn to_unicode_cmap_v0(glyphs: &BTreeMap<u16, Option<char>>) -> Option<String> {
+    let mapped = glyphs
+        .iter()
+        .filter_map(|(glyph_id, ch)| ch.map(|ch| (*glyph_id, ch)))
+        .collect::<Vec<(u16, char)>>();
+    if mapped.is_empty() {
+        return None;
+    }
+    let mut out = String::from(concat!(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n",
+        "/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n",
+        "/CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n",
+        "1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    ));
+    for chunk in mapped.chunks(MAX_BFCHAR_ENTRIES_V0) {
+        let _ = writeln!(out, "{} beginbfchar", chunk.len());
+        for (glyph_id, ch) in chunk {
+            let mut units = [0u16; 2];
+            let utf16 = ch
+                .encode_utf16(&mut units)
+                .iter()
+                .fold(String::new(), |mut text, unit| {
+                    let _ = write!(text, "{unit:04X}");
+                    text
+                });
+            let _ = writeln!(out, "<{glyph_id:04X}> <{utf16}>");
+        }
+        out.push_str("endbfchar\n");
+    }
+    out.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    Some(out)
+}
+
+/// Type 0 font (Identity-H, two-byte glyph ids) over a CIDFont with the embedded
+/// subset; returns the Type 0 font object number.
+fn add_embedded_font_v0(
+    objects: &mut PdfObjectsV0,
+    font_num: i32,
+    font: &FontV0,
+    bytes: &[u8],
+    glyphs: &BTreeMap<u16, Option<char>>,
+) -> Result<usize, PdfWriteErrorV0> {
+    let glyph_ids = glyphs.keys().copied().collect();
+    let subset = font
+        .subset_font_v0(bytes, &glyph_ids)
+        .map_err(|error| PdfWriteErrorV0::Font(font_num, error))?;
+    // Subset tag from the glyph set, so equal inputs give equal names.
+    let digest = md5_v0(
+        &glyphs
+            .keys()
+            .flat_map(|id| id.to_be_bytes())
+            .collect::<Vec<u8>>(),
+    );
+    let tag = digest[..6]
+        .iter()
+        .map(|byte| (b'A' + byte % 26) as char)
+        .collect::<String>();
+    let base_name = match font.postscript_name_v0() {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => format!("F{font_num}"),
+    };
+    let base_font = pdf_name_v0(&format!("{tag}+{base_name}"));
+    let scale = 1000.0 / font.units_per_em_v0() as f64;
+    let design = |units: i32| decimal_text_v0(units as f64 * scale);
+    let (file_key, file_id, cid_subtype, cid_to_gid) = match font.outline_format_v0() {
+        OutlineFormatV0::TrueType => (
+            "FontFile2",
+            objects.add_stream_v0(&format!(" /Length1 {}", subset.len()), &subset),
+            "CIDFontType2",
+            " /CIDToGIDMap /Identity",
+        ),
+        OutlineFormatV0::Cff => (
+            "FontFile3",
+            objects.add_stream_v0(" /Subtype /OpenType", &subset),
+            "CIDFontType0",
+            "",
+        ),
+    };
+    let [x_min, y_min, x_max, y_max] = font.bounding_box_v0().map(i32::from);
+    let descriptor = objects.add_v0(format!(
+        "<< /Type /FontDescriptor /FontName {base_font} /Flags 4 /FontBBox [{} {} {} {}] /ItalicAngle 0 /Ascent {} /Descent {} /CapHeight {} /StemV 80 /{file_key} {file_id} 0 R >>",
+        design(x_min),
+        design(y_min),
+        design(x_max),
+        design(y_max),
+        design(font.ascender_v0() as i32),
+        design(font.descender_v0() as i32),
+        design(font.ascender_v0() as i32),
+    ));
+    let mut widths = String::new();
+    for glyph_id in glyphs.keys() {
+        let advance = font.advance_width_v0(*glyph_id).unwrap_or(0);
+        let _ = write!(widths, " {glyph_id} [{}]", design(advance as i32));
+    }
+    let cid_font = objects.add_v0(format!(
+        "<< /Type /Font /Subtype /{cid_subtype} /BaseFont {base_font} /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /FontDescriptor {descriptor} 0 R /DW 0 /W [{}]{cid_to_gid} >>",
+        widths.trim_start()
+    ));
+    let to_unicode = match to_unicode_cmap_v0(glyphs) {
+        Some(cmap) => format!(
+            " /ToUnicode {} 0 R",
+            objects.add_stream_v0("", cmap.as_bytes())
+        ),
+        None => String::new(),
+    };
+    Ok(objects.add_v0(format!(
+        "<< /Type /Font /Subtype /Type0 /BaseFont {base_font} /Encoding /Identity-H /DescendantFonts [{cid_font} 0 R]{to_unicode} >>"
+    )))
+}
+
+fn serialize_v0(objects: &PdfObjectsV0, catalog_id: usize, info_id: usize, epoch: u64) -> Vec<u8> {
+    let mut out = PDF_HEADER_V0.to_vec();
+    let mut offsets = Vec::with_capacity(objects.objects.len());
+    for (index, body) in objects.objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+        out.extend_from_slice(body);
+        out.extend_from_slice(b"\nendobj\n");
+    }
+    let mut seed = out.clone();
+    seed.extend_from_slice(epoch.to_string().as_bytes());
+    let id = hex_v0(&md5_v0(&seed));
+    let xref_at = out.len();
+    let mut xref = format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.objects.len() + 1
+    );
+    for offset in offsets {
+        let _ = writeln!(xref, "{offset:010} 00000 n ");
+    }
+    let _ = write!(
+        xref,
+        "trailer\n<< /Size {} /Root {catalog_id} 0 R /Info {info_id} 0 R /ID [<{id}> <{id}>] >>\nstartxref\n{xref_at}\n%%EOF\n",
+        objects.objects.len() + 1
+    );
+    out.extend_from_slice(xref.as_bytes());
+    out
+}
+
+/// Write a DVI/XDV page model as a PDF 1.7 file.
+///
+/// Positions convert DVI units to PDF points from the preamble `num`/`den`/`mag`.
+/// Rules become filled rectangles. Fonts found in `files` are embedded as glyph-id
+/// preserving subsets (`FontFile2` for TrueType, OpenType `FontFile3` for CFF) under
+/// Identity-H Type 0 fonts; other TFM fonts use the standard Courier font, or Symbol
+/// for math symbol fonts. XDV native fonts must be present in `files`. Streams are
+/// uncompressed, `CreationDate` comes from `source_date_epoch`, and `/ID` hashes the
+/// body with it, so equal inputs give byte-identical output.
+pub fn write_pdf_v0(
+    document: &DviDocumentV0,
+    files: &[PdfFontFileV0],
+    options: &PdfWriteOptionsV0,
+) -> Result<Vec<u8>, PdfWriteErrorV0> {
+    write_pdf_with_images_v0(document, files, &[], options)
+}
+
+/// `write_pdf_v0`, with the images of `pdf:image` specials read from `images` and
+/// embedded once each as image XObjects, drawn with their lower left corner at the
+/// special. A special naming a file missing from `images`, or one that is not a PNG
+/// or JPEG `parse_image_v0` accepts, fails the write.
+pub fn write_pdf_with_images_v0(
+    document: &DviDocumentV0,
+    files: &[PdfFontFileV0],
+    images: &[PdfImageFileV0],
+    options: &PdfWriteOptionsV0,
+) -> Result<Vec<u8>, PdfWriteErrorV0> {
+    if options.paper_width_sp <= 0
+        || options.paper_height_sp <= 0
+        || options.fallback_font_size_sp <= 0
+    {
+        return Err(PdfWriteErrorV0::OptionsInvalid);
+    }
+    let date = pdf_date_v0(options.source_date_epoch).ok_or(PdfWriteErrorV0::OptionsInvalid)?;
+    if document.pages.len() > MAX_PDF_PAGES_V0 {
+        return Err(PdfWriteErrorV0::TooManyPages);
+    }
+    let preamble = &document.preamble;
+    if preamble.num == 0 || preamble.den == 0 || preamble.mag == 0 {
+        return Err(PdfWriteErrorV0::UnitsInvalid);
+    }
+    let meters_e7_per_unit =
+        preamble.num as f64 / preamble.den as f64 * preamble.mag as f64 / 1000.0;
+    let units = UnitsV0 {
+        bp_per_unit: meters_e7_per_unit * 72.0 / 254_000.0,
+        origin_x_bp: sp_to_bp_v0(options.origin_x_sp),
+        origin_y_bp: sp_to_bp_v0(options.origin_y_sp),
+        page_height_bp: sp_to_bp_v0(options.paper_height_sp),
+    };
+    let fonts = resolve_fonts_v0(document, files, &units, options)?;
+    let mut image_numbers = BTreeMap::<&[u8], usize>::new();
+    let mut used_images = Vec::new();
+    for special in document.pages.iter().flat_map(|page| &page.specials) {
+        let Some(image) = parse_image_special_v0(&special.bytes) else {
+            continue;
+        };
+        if image_numbers.contains_key(image.name) {
+            continue;
+        }
+        let file = images
+            .iter()
+            .find(|file| file.name == image.name)
+            .ok_or(PdfWriteErrorV0::ImageUnavailable)?;
+        let parsed = parse_image_v0(file.bytes).ok_or(PdfWriteErrorV0::ImageInvalid)?;
+        image_numbers.insert(image.name, used_images.len() + 1);
+        used_images.push((parsed, file.bytes));
+    }
+    let mut used = UsedGlyphsV0::new();
+    let mut colors = PdfColorStackV0::default();
+    let mut contents = Vec::with_capacity(document.pages.len());
+    for page in &document.pages {
+        contents.push(page_content_v0(
+            page,
+            &fonts,
+            &image_numbers,
+            &units,
+            &mut used,
+            &mut colors,
+        )?);
+    }
+
+    let mut objects = PdfObjectsV0::default();
+    let catalog = objects.reserve_v0();
+    let pages = objects.reserve_v0();
+    objects.set_v0(catalog, format!("<< /Type /Catalog /Pages {pages} 0 R >>"));
+    let info = objects.add_v0(format!(
+        "<< /Producer ({PDF_PRODUCER_V0}) /CreationDate ({date}) /ModDate ({date}) >>"
+    ));
+    let mut font_resources = String::new();
+    let mut standard_id = None::<usize>;
+    let mut symbol_id = None::<usize>;
+    for (font_num, font) in &fonts {
+        let id = match font {
+            PdfFontV0::Embedded { font, bytes, .. } => match used.get(font_num) {
+                Some(glyphs) => add_embedded_font_v0(&mut objects, *font_num, font, bytes, glyphs)?,
+                None => continue,
+            },
+            PdfFontV0::Standard { symbol: false, .. } => *standard_id.get_or_insert_with(|| {
+                objects.add_v0(format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{STANDARD_FONT_V0} /Encoding /WinAnsiEncoding >>"
+                ))
+            }),
+            PdfFontV0::Standard { symbol: true, .. } => *symbol_id.get_or_insert_with(|| {
+                objects.add_v0(format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{SYMBOL_FONT_V0} >>"
+                ))
+            }),
+        };
+        let _ = write!(font_resources, " /F{font_num} {id} 0 R");
+    }
+    let mut image_resources = String::new();
+    for (number, (image, bytes)) in used_images.iter().enumerate() {
+        let id = add_image_v0(&mut objects, image, bytes)?;
+        let _ = write!(image_resources, " /Im{} {id} 0 R", number + 1);
+    }
+    if !image_resources.is_empty() {
+        image_resources = format!(" /XObject <<{image_resources} >>");
+    }
+    let mut kids = Vec::with_capacity(contents.len());
+    for content in &contents {
+        let content_id = objects.add_stream_v0("", content.as_bytes());
+        kids.push(format!(
+            "{} 0 R",
+            objects.add_v0(format!(
+                "<< /Type /Page /Parent {pages} 0 R /Contents {content_id} 0 R >>"
+            ))
+        ));
+    }
+    objects.set_v0(
+        pages,
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} /MediaBox [0 0 {} {}] /Resources << /Font <<{font_resources} >>{image_resources} /ProcSet [/PDF /Text] >> >>",
+            kids.join(" "),
+            kids.len(),
+            decimal_text_v0(sp_to_bp_v0(options.paper_width_sp)),
+            decimal_text_v0(sp_to_bp_v0(options.paper_height_sp))
+        ),
+    );
+    let out = serialize_v0(&objects, catalog, info, options.source_date_epoch);
+    if out.len() > MAX_PDF_BYTES_V0 {
+        return Err(PdfWriteErrorV0::TooLarge);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,597 @@
+use carreltex_fonts::{parse_font_v0, write_sfnt_v0, OutlineFormatV0};
+use carreltex_xdv::{
+    image_special_v0, read_dvi_v0, write_dvi_v2_text_page_with_layout_paging_and_specials_v0,
+    write_dvi_v2_text_page_with_layout_wrap_and_paging_v0, DviDocumentV0, DviFontDefV0,
+    DviFontKindV0, DviGlyphV0, DviPageV0, DviPostambleV0, DviPreambleV0, DviRuleV0, DviSpecialV0,
+    DviTextSpecialV0, DviTfmFontDefV0, XdvNativeFontDefV0, DVI_DEN, DVI_MAG, DVI_NUM,
+};
+
+use super::inflate_v0::inflate_zlib_v0;
+use super::md5_v0::md5_v0;
+use super::{
+    hex_v0, write_pdf_v0, write_pdf_with_images_v0, PdfFontFileV0, PdfImageFileV0, PdfWriteErrorV0,
+    PdfWriteOptionsV0,
+};
+
+const TEN_PT_SP: i32 = 655_360;
+
+/// TrueType font with 1000 units/em, `A` mapped to glyph 1: a 500x700 box.
+fn box_font_bytes_v0() -> Vec<u8> {
+    let mut head = vec![0u8; 54];
+    head[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    head[12..16].copy_from_slice(&0x5f0f_3cf5u32.to_be_bytes());
+    head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+    head[40..44].copy_from_slice(&[0x01, 0xf4, 0x02, 0xbc]);
+    let mut maxp = 0x0000_5000u32.to_be_bytes().to_vec();
+    maxp.extend_from_slice(&2u16.to_be_bytes());
+    let mut hhea = vec![0u8; 36];
+    hhea[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    hhea[34..36].copy_from_slice(&2u16.to_be_bytes());
+    let hmtx = [0u16, 0, 500, 0]
+        .iter()
+        .flat_map(|value| value.to_be_bytes())
+        .collect::<Vec<u8>>();
+    // Format 4 with a delta segment for 'A' and the 0xffff sentinel.
+    let format4 = [
+        4u16, 32, 0, 4, 4, 1, 0, 0x41, 0xffff, 0, 0x41, 0xffff, 0xffc0, 1, 0, 0,
+    ]
+    .iter()
+    .flat_map(|value| value.to_be_bytes())
+    .collect::<Vec<u8>>();
+    let mut cmap = [0u16, 1, 3, 1]
+        .iter()
+        .flat_map(|v| v.to_be_bytes())
+        .collect::<Vec<u8>>();
+    cmap.extend_from_slice(&12u32.to_be_bytes());
+    cmap.extend_from_slice(&format4);
+    let name = vec![0u8; 6];
+    let mut glyph = Vec::<u8>::new();
+    for value in [1i16, 0, 0, 500, 700, 3, 0] {
+        glyph.extend_from_slice(&value.to_be_bytes());
+    }
+    glyph.extend_from_slice(&[0x01, 0x01, 0x01, 0x01]);
+    for value in [0i16, 500, 0, -500, 0, 0, 700, 0] {
+        glyph.extend_from_slice(&value.to_be_bytes());
+    }
+    let loca = [0u16, 0, (glyph.len() / 2) as u16]
+        .iter()
+        .flat_map(|value| value.to_be_bytes())
+        .collect::<Vec<u8>>();
+    write_sfnt_v0(
+        OutlineFormatV0::TrueType,
+        &[
+            (*b"head", head),
+            (*b"maxp", maxp),
+            (*b"hhea", hhea),
+            (*b"hmtx", hmtx),
+            (*b"cmap", cmap),
+            (*b"name", name),
+            (*b"glyf", glyph),
+            (*b"loca", loca),
+        ],
+    )
+    .expect("test font must assemble")
+}
+
+fn native_document_v0(glyphs: Vec<DviGlyphV0>, rules: Vec<DviRuleV0>) -> DviDocumentV0 {
+    DviDocumentV0 {
+        preamble: DviPreambleV0 {
+            id: 7,
+            num: DVI_NUM,
+            den: DVI_DEN,
+            mag: DVI_MAG,
+            comment: Vec::new(),
+        },
+        postamble: DviPostambleV0 {
+            last_bop: 15,
+            num: DVI_NUM,
+            den: DVI_DEN,
+            mag: DVI_MAG,
+            max_height_depth: 0,
+            max_width: 0,
+            max_stack_depth: 0,
+            total_pages: 1,
+        },
+        fonts: vec![DviFontDefV0 {
+            font_num: 3,
+            kind: DviFontKindV0::Native(XdvNativeFontDefV0 {
+                size: TEN_PT_SP,
+                flags: 0,
+                filename: b"box.ttf".to_vec(),
+                index: 0,
+                rgba: None,
+                extend: None,
+                slant: None,
+                embolden: None,
+            }),
+        }],
+        pages: vec![DviPageV0 {
+            offset: 15,
+            counts: [1, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            glyphs,
+            rules,
+            specials: Vec::new(),
+        }],
+    }
+}
+
+fn native_glyph_v0(h: i32, v: i32) -> DviGlyphV0 {
+    DviGlyphV0 {
+        font_num: 3,
+        code: 1,
+        native: true,
+        h,
+        v,
+        width: 327_680,
+    }
+}
+
+fn find_v0(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn number_at_v0(bytes: &[u8], at: usize) -> usize {
+    let digits = bytes[at..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count();
+    String::from_utf8_lossy(&bytes[at..at + digits])
+        .parse()
+        .expect("decimal number")
+}
+
+/// Check that every xref entry points at its `N 0 obj` header and `startxref` at
+/// the xref table; returns the number of objects.
+fn assert_xref_consistent_v0(pdf: &[u8]) -> usize {
+    assert!(pdf.starts_with(b"%PDF-1.7\n"));
+    assert!(pdf.ends_with(b"%%EOF\n"));
+    let startxref = pdf.len() - b"startxref\n".len() - b"%%EOF\n".len();
+    let startxref =
+        find_v0(&pdf[startxref - 12..], b"startxref\n").expect("startxref") + startxref - 12;
+    let xref_at = number_at_v0(pdf, startxref + 10);
+    assert!(pdf[xref_at..].starts_with(b"xref\n0 "));
+    let count = number_at_v0(pdf, xref_at + 7);
+    let entries_at = xref_at + find_v0(&pdf[xref_at..], b"0000000000 65535 f \n").expect("free");
+    for id in 1..count {
+        let offset = number_at_v0(pdf, entries_at + id * 20);
+        assert!(pdf[offset..].starts_with(format!("{id} 0 obj\n").as_bytes()));
+    }
+    count - 1
+}
+
+/// Bytes of the first stream whose dictionary contains `key`.
+fn stream_with_key_v0<'a>(pdf: &'a [u8], key: &str) -> &'a [u8] {
+    let dict_at = find_v0(pdf, key.as_bytes()).expect("stream key");
+    let length_at = pdf[..dict_at]
+        .windows(8)
+        .rposition(|window| window == b"/Length ")
+        .expect("stream length")
+        + 8;
+    let length = number_at_v0(pdf, length_at);
+    let data_at = dict_at + find_v0(&pdf[dict_at..], b">>\nstream\n").expect("stream start") + 10;
+    &pdf[data_at..data_at + length]
+}
+
+#[test]
+fn writes_pages_with_standard_font_text_and_consistent_xref() {
+    let bytes = write_dvi_v2_text_page_with_layout_wrap_and_paging_v0(
+        b"a<\nb\x0cc",
+        65_536,
+        786_432,
+        80,
+        200,
+    )
+    .expect("writer should accept text");
+    let document = read_dvi_v0(&bytes).expect("reader should accept writer output");
+    let pdf =
+        write_pdf_v0(&document, &[], &PdfWriteOptionsV0::default()).expect("pdf should be written");
+    assert_eq!(assert_xref_consistent_v0(&pdf), 8);
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("/Type /Pages /Kids [6 0 R 8 0 R] /Count 2 /MediaBox [0 0 612 792]"));
+    assert!(text.contains("/Type /Font /Subtype /Type1 /BaseFont /Courier"));
+    assert!(text.contains(concat!(
+        "stream\nBT\n/F0 9.9626 Tf\n",
+        "1 0 0 1 72 720 Tm <61> Tj\n",
+        "1 0 0 1 72.9963 720 Tm <3C> Tj\n",
+        "1 0 0 1 72 708.0448 Tm <62> Tj\n",
+        "ET\n\nendstream",
+    )));
+}
+
+#[test]
+fn creation_date_and_id_follow_source_date_epoch() {
+    let document = read_dvi_v0(
+        &write_dvi_v2_text_page_with_layout_wrap_and_paging_v0(b"x", 65_536, 786_432, 80, 200)
+            .expect("writer should accept text"),
+    )
+    .expect("reader should accept writer output");
+    let write = |epoch: u64| {
+        let options = PdfWriteOptionsV0 {
+            source_date_epoch: epoch,
+            ..PdfWriteOptionsV0::default()
+        };
+        String::from_utf8_lossy(&write_pdf_v0(&document, &[], &options).expect("pdf")).into_owned()
+    };
+    let first = write(1_700_000_000);
+    assert_eq!(first, write(1_700_000_000));
+    assert!(first.contains("/CreationDate (D:20231114221320Z) /ModDate (D:20231114221320Z)"));
+    assert!(write(0).contains("(D:19700101000000Z)"));
+    assert!(write(951_782_400).contains("(D:20000229000000Z)"));
+    let id_of = |pdf: &str| pdf[pdf.find("/ID [<").expect("id") + 6..][..32].to_string();
+    assert_ne!(id_of(&first), id_of(&write(1_700_000_001)));
+    assert_eq!(
+        write_pdf_v0(
+            &document,
+            &[],
+            &PdfWriteOptionsV0 {
+                source_date_epoch: 253_402_300_800,
+                ..PdfWriteOptionsV0::default()
+            }
+        ),
+        Err(PdfWriteErrorV0::OptionsInvalid)
+    );
+}
+
+#[test]
+fn embeds_truetype_subset_as_type0_font_with_rules() {
+    let document = native_document_v0(
+        vec![native_glyph_v0(0, 0), native_glyph_v0(327_680, 65_536)],
+        vec![DviRuleV0 {
+            h: 65_536,
+            v: 131_072,
+            width: 655_360,
+            height: 26_214,
+        }],
+    );
+    let font = box_font_bytes_v0();
+    let files = [PdfFontFileV0 {
+        name: b"box.ttf",
+        bytes: &font,
+    }];
+    let options = PdfWriteOptionsV0 {
+        origin_x_sp: 0,
+        origin_y_sp: 0,
+        ..PdfWriteOptionsV0::default()
+    };
+    let pdf = write_pdf_v0(&document, &files, &options).expect("pdf should be written");
+    assert_eq!(pdf, write_pdf_v0(&document, &files, &options).expect("pdf"));
+    assert_xref_consistent_v0(&pdf);
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("/Subtype /Type0 /BaseFont /"));
+    assert!(text.contains("/Encoding /Identity-H"));
+    assert!(text.contains("/Subtype /CIDFontType2"));
+    assert!(text.contains("/FontBBox [0 0 500 700]"));
+    assert!(text.contains("/W [1 [500]] /CIDToGIDMap /Identity"));
+    assert!(!text.contains("/ToUnicode"));
+    assert!(text.contains("0.9963 790.0075 9.9626 0.3985 re f\n"));
+    assert!(text.contains("/F3 9.9626 Tf\n1 0 0 1 0 792 Tm <0001> Tj\n"));
+    assert!(text.contains("1 0 0 1 4.9813 791.0037 Tm <0001> Tj\n"));
+    let embedded = stream_with_key_v0(&pdf, "/Length1");
+    let parsed = parse_font_v0(embedded).expect("embedded subset should parse");
+    assert_eq!(parsed.num_glyphs_v0(), 2);
+    assert_eq!(
+        parsed.glyph_outline_v0(embedded, 1),
+        parse_font_v0(&font)
+            .expect("font")
+            .glyph_outline_v0(&font, 1)
+    );
+}
+
+#[test]
+fn tfm_fonts_with_mounted_files_get_to_unicode_maps() {
+    let mut document = native_document_v0(vec![], vec![]);
+    document.fonts[0].kind = DviFontKindV0::Tfm(DviTfmFontDefV0 {
+        checksum: 0,
+        scale: TEN_PT_SP,
+        design_size: TEN_PT_SP,
+        area: Vec::new(),
+        name: b"box".to_vec(),
+    });
+    let mut glyph = native_glyph_v0(0, 0);
+    glyph.native = false;
+    glyph.code = b'A' as u32;
+    document.pages[0].glyphs = vec![glyph];
+    let font = box_font_bytes_v0();
+    let files = [PdfFontFileV0 {
+        name: b"box",
+        bytes: &font,
+    }];
+    let pdf = write_pdf_v0(&document, &files, &PdfWriteOptionsV0::default())
+        .expect("pdf should be written");
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains("/ToUnicode "));
+    assert!(text.contains("1 beginbfchar\n<0001> <0041>\nendbfchar\n"));
+    document.pages[0].glyphs[0].code = b'B' as u32;
+    let error = write_pdf_v0(&document, &files, &PdfWriteOptionsV0::default())
+        .expect_err("unmapped char should fail");
+    assert_eq!(error, PdfWriteErrorV0::GlyphUnmapped(3, b'B' as u32));
+    assert_eq!(error.reason_v0(), "pdf_glyph_unmapped");
+}
+
+#[test]
+fn color_specials_set_fill_and_stroke_across_pages() {
+    let specials =
+        [(1, "color push rgb 1 0 0"), (4, "color pop")].map(|(offset, bytes)| DviTextSpecialV0 {
+            offset,
+            bytes: bytes.as_bytes().to_vec(),
+        });
+    let bytes = write_dvi_v2_text_page_with_layout_paging_and_specials_v0(
+        b"ab\x0ccd",
+        &specials,
+        65_536,
+        786_432,
+        80,
+        200,
+    )
+    .expect("writer should accept specials");
+    let document = read_dvi_v0(&bytes).expect("reader should accept writer output");
+    let pdf =
+        write_pdf_v0(&document, &[], &PdfWriteOptionsV0::default()).expect("pdf should be written");
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains(concat!(
+        "1 0 0 1 72 720 Tm <61> Tj\n",
+        "1 0 0 rg 1 0 0 RG\n",
+        "1 0 0 1 72.9963 720 Tm <62> Tj\n",
+        "ET\n",
+    )));
+    assert!(text.contains(concat!(
+        "BT\n1 0 0 rg 1 0 0 RG\n/F0 9.9626 Tf\n",
+        "1 0 0 1 72 720 Tm <63> Tj\n",
+        "0 g 0 G\n",
+        "1 0 0 1 72.9963 720 Tm <64> Tj\n",
+    )));
+
+    let mut document = native_document_v0(
+        vec![native_glyph_v0(0, 0)],
+        vec![
+            DviRuleV0 {
+                h: 0,
+                v: 0,
+                width: 65_536,
+                height: 65_536,
+            };
+            2
+        ],
+    );
+    let special = |glyph_index, rule_index, bytes: &[u8]| DviSpecialV0 {
+        h: 0,
+        v: 0,
+        glyph_index,
+        rule_index,
+        bytes: bytes.to_vec(),
+    };
+    document.pages[0].specials = vec![
+        special(0, 1, b"color push cmyk 0 0.5 1 0"),
+        special(0, 1, b"color push Red"),
+        special(0, 2, b"color pop"),
+        special(1, 2, b"color pop"),
+    ];
+    let font = box_font_bytes_v0();
+    let files = [PdfFontFileV0 {
+        name: b"box.ttf",
+        bytes: &font,
+    }];
+    let pdf = write_pdf_v0(&document, &files, &PdfWriteOptionsV0::default()).expect("pdf");
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains(concat!(
+        "stream\n72 720 0.9963 0.9963 re f\n",
+        "0 0.5 1 0 k 0 0.5 1 0 K\n",
+        "72 720 0.9963 0.9963 re f\n",
+        "BT\n/F3",
+    )));
+}
+
+#[test]
+fn fails_closed_on_missing_fonts_and_bad_inputs() {
+    let document = native_document_v0(vec![native_glyph_v0(0, 0)], vec![]);
+    let error = write_pdf_v0(&document, &[], &PdfWriteOptionsV0::default())
+        .expect_err("native font must be mounted");
+    assert_eq!(error, PdfWriteErrorV0::NativeFontUnavailable(3));
+    assert_eq!(error.reason_v0(), "pdf_native_font_unavailable");
+
+    let font = box_font_bytes_v0();
+    let files = [PdfFontFileV0 {
+        name: b"box.ttf",
+        bytes: &font,
+    }];
+    let mut out_of_range = document.clone();
+    out_of_range.pages[0].glyphs[0].code = 9;
+    let error = write_pdf_v0(&out_of_range, &files, &PdfWriteOptionsV0::default())
+        .expect_err("glyph id outside the font must fail");
+    assert_eq!(error.reason_v0(), "font_glyph_id_invalid");
+
+    let mut wide_code = document.clone();
+    wide_code.fonts[0].kind = DviFontKindV0::Tfm(DviTfmFontDefV0 {
+        checksum: 0,
+        scale: 0,
+        design_size: 0,
+        area: Vec::new(),
+        name: b"cmr10".to_vec(),
+    });
+    wide_code.pages[0].glyphs[0].native = false;
+    wide_code.pages[0].glyphs[0].code = 256;
+    assert_eq!(
+        write_pdf_v0(&wide_code, &[], &PdfWriteOptionsV0::default()),
+        Err(PdfWriteErrorV0::GlyphUnmapped(3, 256))
+    );
+
+    let options = PdfWriteOptionsV0 {
+        paper_width_sp: 0,
+        ..PdfWriteOptionsV0::default()
+    };
+    assert_eq!(
+        write_pdf_v0(&document, &files, &options),
+        Err(PdfWriteErrorV0::OptionsInvalid)
+    );
+    let mut zero_mag = document.clone();
+    zero_mag.preamble.mag = 0;
+    assert_eq!(
+        write_pdf_v0(&zero_mag, &files, &PdfWriteOptionsV0::default()),
+        Err(PdfWriteErrorV0::UnitsInvalid)
+    );
+}
+
+#[test]
+fn md5_matches_rfc_1321_vectors() {
+    assert_eq!(hex_v0(&md5_v0(b"")), "D41D8CD98F00B204E9800998ECF8427E");
+    assert_eq!(hex_v0(&md5_v0(b"abc")), "900150983CD24FB0D6963F7D28E17F72");
+    assert_eq!(
+        hex_v0(&md5_v0(
+            b"12345678901234567890123456789012345678901234567890123456789012345678901234567890"
+        )),
+        "57EDF4A22BE3C955AC49DA2E2107B67A"
+    );
+}
+
+/// PNG of `samples`, one filtered row after another, in a stored zlib block; the
+/// CRCs and the Adler-32 sum are left zero.
+fn png_v0(width: u32, height: u32, color_type: u8, samples: &[u8]) -> Vec<u8> {
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    let mut chunk = |chunk_type: &[u8], data: &[u8]| {
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        out.extend_from_slice(chunk_type);
+        out.extend_from_slice(data);
+        out.extend_from_slice(&[0; 4]);
+    };
+    let mut header = width.to_be_bytes().to_vec();
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[8, color_type, 0, 0, 0]);
+    chunk(b"IHDR", &header);
+    let length = samples.len() as u16;
+    let mut data = vec![0x78, 0x01, 0x01];
+    data.extend_from_slice(&length.to_le_bytes());
+    data.extend_from_slice(&(!length).to_le_bytes());
+    data.extend_from_slice(samples);
+    data.extend_from_slice(&[0; 4]);
+    chunk(b"IDAT", &data);
+    chunk(b"IEND", b"");
+    out
+}
+
+fn image_document_v0(names: &[&[u8]]) -> DviDocumentV0 {
+    let mut document = native_document_v0(vec![], vec![]);
+    document.fonts.clear();
+    document.pages[0].specials = names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| DviSpecialV0 {
+            h: index as i32 * TEN_PT_SP,
+            v: TEN_PT_SP,
+            glyph_index: 0,
+            rule_index: 0,
+            bytes: image_special_v0(TEN_PT_SP, TEN_PT_SP / 2, name),
+        })
+        .collect();
+    document
+}
+
+#[test]
+fn inflates_stored_fixed_and_dynamic_zlib_blocks() {
+    let decode = |hex: &str| {
+        (0..hex.len())
+            .step_by(2)
+            .map(|at| u8::from_str_radix(&hex[at..at + 2], 16).expect("hex"))
+            .collect::<Vec<u8>>()
+    };
+    assert_eq!(
+        inflate_zlib_v0(&decode("78dacb48cdc9c957c8402701680308b1"), 64),
+        Some(b"hello hello hello hello".to_vec())
+    );
+    let dynamic = decode(concat!(
+        "78da158cc10dc0400cc25661351202ec3f41af4fcb96776eda465326ec694c128c42a8852cf518ccde",
+        "1881ee8a972a59c3d3671097e56517a2000576ef7db03fb186903a26e07e24bd295c"
+    ));
+    let text = inflate_zlib_v0(&dynamic, 120).expect("dynamic block");
+    assert!(text.starts_with(b"cbebhhhgdbhaggahedbfaaa agdga"));
+    assert_eq!(text.len(), 120);
+    assert_eq!(inflate_zlib_v0(&dynamic, 119), None);
+    assert_eq!(
+        inflate_zlib_v0(&[0x78, 0x01, 0x01, 2, 0, 0xfd, 0xff, b'o', b'k'], 2),
+        Some(b"ok".to_vec())
+    );
+    for damaged in [
+        &[0x78, 0x01, 0x01, 2, 0, 0xfd, 0xfe, b'o', b'k'][..],
+        &[0x78, 0x02, 0x03, 0x00],
+        &[0x78, 0x01, 0x07],
+        &dynamic[..40],
+    ] {
+        assert_eq!(inflate_zlib_v0(damaged, 1024), None);
+    }
+}
+
+#[test]
+fn embeds_png_and_jpeg_images_once_each_with_soft_masks() {
+    // Two RGBA pixels, the second one sub-filtered.
+    let rgba = png_v0(2, 1, 6, &[1, 10, 20, 30, 255, 5, 5, 5, 0]);
+    let jpeg = [
+        &[0xff, 0xd8, 0xff, 0xc0, 0, 11, 8, 0, 1, 0, 1, 1, 1, 0x11, 0][..],
+        &[0xff, 0xda, 0xff, 0xd9],
+    ]
+    .concat();
+    let images = [
+        PdfImageFileV0 {
+            name: b"dot.png",
+            bytes: &rgba,
+        },
+        PdfImageFileV0 {
+            name: b"dot.jpg",
+            bytes: &jpeg,
+        },
+    ];
+    let document = image_document_v0(&[b"dot.png", b"dot.jpg", b"dot.png"]);
+    let pdf = write_pdf_with_images_v0(&document, &[], &images, &PdfWriteOptionsV0::default())
+        .expect("pdf");
+    assert_eq!(assert_xref_consistent_v0(&pdf), 8);
+    let text = String::from_utf8_lossy(&pdf);
+    assert!(text.contains(concat!(
+        "stream\nq 9.9626 0 0 4.9813 72 710.0374 cm /Im1 Do Q\n",
+        "q 9.9626 0 0 4.9813 81.9626 710.0374 cm /Im2 Do Q\n",
+        "q 9.9626 0 0 4.9813 91.9253 710.0374 cm /Im1 Do Q\n",
+    )));
+    assert!(text.contains("/XObject << /Im1 5 0 R /Im2 6 0 R >>"));
+    assert!(text.contains("/ColorSpace /DeviceRGB /BitsPerComponent 8 /SMask 4 0 R"));
+    assert_eq!(
+        stream_with_key_v0(&pdf, "/SMask"),
+        &[10, 20, 30, 15, 25, 35]
+    );
+    assert_eq!(
+        stream_with_key_v0(&pdf, "/ColorSpace /DeviceGray /BitsPerComponent 8\x20"),
+        &[255, 255]
+    );
+    assert_eq!(stream_with_key_v0(&pdf, "/DCTDecode"), jpeg.as_slice());
+
+    let gray = png_v0(1, 1, 0, &[0, 7]);
+    let files = [PdfImageFileV0 {
+        name: b"dot.png",
+        bytes: &gray,
+    }];
+    let document = image_document_v0(&[b"dot.png"]);
+    let pdf = write_pdf_with_images_v0(&document, &[], &files, &PdfWriteOptionsV0::default())
+        .expect("pdf");
+    assert!(String::from_utf8_lossy(&pdf).contains(
+        "/Filter /FlateDecode /DecodeParms << /Predictor 15 /Colors 1 /BitsPerComponent 8 /Columns 1 >>"
+    ));
+}
+
+#[test]
+fn fails_closed_on_missing_and_damaged_images() {
+    let document = image_document_v0(&[b"dot.png"]);
+    let options = PdfWriteOptionsV0::default();
+    let error =
+        write_pdf_with_images_v0(&document, &[], &[], &options).expect_err("image must be mounted");
+    assert_eq!(error.reason_v0(), "pdf_image_unavailable");
+    let short_row = png_v0(2, 1, 6, &[0, 1, 2, 3, 4]);
+    let bad_filter = png_v0(1, 1, 6, &[5, 1, 2, 3, 4]);
+    for bytes in [b"GIF89a".to_vec(), short_row, bad_filter] {
+        let files = [PdfImageFileV0 {
+            name: b"dot.png",
+            bytes: &bytes,
+        }];
+        assert_eq!(
+            write_pdf_with_images_v0(&document, &[], &files, &options),
+            Err(PdfWriteErrorV0::ImageInvalid)
+        );
+    }
+}
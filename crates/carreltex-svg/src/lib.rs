@@ -0,0 +1,422 @@
+use core::fmt::Write;
+use std::collections::{btree_map, BTreeMap};
+
+use carreltex_fonts::{parse_font_v0, FontErrorV0, FontV0, OutlineCommandV0};
+use carreltex_xdv::{
+    decimal_text_v0, parse_image_special_v0, parse_image_v0, DviDocumentV0, DviFontKindV0,
+    DviGlyphV0, DviImageKindV0, DviPageV0, DviRuleV0, DEFAULT_FALLBACK_FONT_SIZE_SP_V0,
+    DEFAULT_PAPER_HEIGHT_SP_V0, DEFAULT_PAPER_WIDTH_SP_V0, ONE_INCH_SP_V0,
+};
+
+pub const MAX_SVG_PAGES_V0: usize = 10_000;
+
+const SVG_NAMESPACE_V0: &str = "http://www.w3.org/2000/svg";
+const XLINK_NAMESPACE_V0: &str = "http://www.w3.org/1999/xlink";
+const BASE64_ALPHABET_V0: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SvgRenderOptionsV0 {
+    pub paper_width_sp: i32,
+    pub paper_height_sp: i32,
+    /// Position of the DVI origin from the top-left paper corner (TeX uses 1in, 1in).
+    pub origin_x_sp: i32,
+    pub origin_y_sp: i32,
+    pub fallback_font_size_sp: i32,
+}
+
+impl Default for SvgRenderOptionsV0 {
+    fn default() -> Self {
+        SvgRenderOptionsV0 {
+            paper_width_sp: DEFAULT_PAPER_WIDTH_SP_V0,
+            paper_height_sp: DEFAULT_PAPER_HEIGHT_SP_V0,
+            origin_x_sp: ONE_INCH_SP_V0,
+            origin_y_sp: ONE_INCH_SP_V0,
+            fallback_font_size_sp: DEFAULT_FALLBACK_FONT_SIZE_SP_V0,
+        }
+    }
+}
+
+/// A mounted OpenType/TrueType file, matched against DVI font names (TFM fonts) and
+/// XDV native font filenames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SvgFontFileV0<'a> {
+    pub name: &'a [u8],
+    pub bytes: &'a [u8],
+}
+
+/// A mounted PNG or JPEG file, matched against the names of `pdf:image` specials.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SvgImageFileV0<'a> {
+    pub name: &'a [u8],
+    pub bytes: &'a [u8],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SvgRenderErrorV0 {
+    OptionsInvalid,
+    UnitsInvalid,
+    TooManyPages,
+    FontUndefined(i32),
+    NativeFontUnavailable(i32),
+    Font(i32, FontErrorV0),
+    GlyphUnmapped(i32, u32),
+    ImageUnavailable,
+    ImageInvalid,
+}
+
+impl SvgRenderErrorV0 {
+    pub fn reason_v0(&self) -> &'static str {
+        match self {
+            SvgRenderErrorV0::OptionsInvalid => "svg_options_invalid",
+            SvgRenderErrorV0::UnitsInvalid => "svg_units_invalid",
+            SvgRenderErrorV0::TooManyPages => "svg_too_many_pages",
+            SvgRenderErrorV0::FontUndefined(_) => "svg_font_undefined",
+            SvgRenderErrorV0::NativeFontUnavailable(_) => "svg_native_font_unavailable",
+            SvgRenderErrorV0::Font(_, error) => error.reason_v0(),
+            SvgRenderErrorV0::GlyphUnmapped(_, _) => "svg_glyph_unmapped",
+            SvgRenderErrorV0::ImageUnavailable => "svg_image_unavailable",
+            SvgRenderErrorV0::ImageInvalid => "svg_image_invalid",
+        }
+    }
+}
+
+/// How glyphs of one DVI font are drawn.
+enum SvgFontV0<'a> {
+    /// Outlines from a mounted font; TFM char codes go through its cmap.
+    Outline {
+        font: Box<FontV0>,
+        bytes: &'a [u8],
+        size_pt: f64,
+        native: bool,
+    },
+    /// `<text>` referencing the font by name.
+    Reference { family: String, size_pt: f64 },
+}
+
+fn escape_xml_v0(text: &str) -> String {
+    let mut out = String::new();
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+fn path_data_v0(outline: &[OutlineCommandV0]) -> String {
+    let mut out = String::new();
+    for command in outline {
+        let _ = match command {
+            OutlineCommandV0::MoveTo(x, y) => write!(out, "M{x} {y}"),
+            OutlineCommandV0::LineTo(x, y) => write!(out, "L{x} {y}"),
+            OutlineCommandV0::QuadTo(x1, y1, x, y) => write!(out, "Q{x1} {y1} {x} {y}"),
+            OutlineCommandV0::CubicTo(x1, y1, x2, y2, x, y) => {
+                write!(out, "C{x1} {y1} {x2} {y2} {x} {y}")
+            }
+            OutlineCommandV0::Close => write!(out, "Z"),
+        };
+    }
+    out
+}
+
+/// Converts DVI units to points: `num/den` gives units of 10^-7 m, scaled by
+/// `mag/1000`; with `DVI_NUM`/`DVI_DEN`/`DVI_MAG` one unit is one sp (1/65536 pt).
+struct UnitsV0 {
+    pt_per_unit: f64,
+    origin_x_pt: f64,
+    origin_y_pt: f64,
+}
+
+impl UnitsV0 {
+    fn x_v0(&self, h: i32) -> f64 {
+        self.origin_x_pt + h as f64 * self.pt_per_unit
+    }
+
+    fn y_v0(&self, v: i32) -> f64 {
+        self.origin_y_pt + v as f64 * self.pt_per_unit
+    }
+
+    fn length_v0(&self, units: i32) -> f64 {
+        units as f64 * self.pt_per_unit
+    }
+}
+
+fn sp_to_pt_v0(sp: i32) -> f64 {
+    sp as f64 / 65_536.0
+}
+
+fn resolve_fonts_v0<'a>(
+    document: &DviDocumentV0,
+    files: &[SvgFontFileV0<'a>],
+    units: &UnitsV0,
+    options: &SvgRenderOptionsV0,
+) -> Result<BTreeMap<i32, SvgFontV0<'a>>, SvgRenderErrorV0> {
+    let mut fonts = BTreeMap::new();
+    for definition in &document.fonts {
+        let (name, size_pt, native) = match &definition.kind {
+            DviFontKindV0::Tfm(tfm) => {
+                let size_pt = match tfm.scale {
+                    0 => sp_to_pt_v0(options.fallback_font_size_sp),
+                    scale => units.length_v0(scale),
+                };
+                (tfm.name.as_slice(), size_pt, false)
+            }
+            DviFontKindV0::Native(native) => (
+                native.filename.as_slice(),
+                units.length_v0(native.size),
+                true,
+            ),
+        };
+        let file = files.iter().find(|file| file.name == name);
+        let font = match file {
+            Some(file) => SvgFontV0::Outline {
+                font: Box::new(
+                    parse_font_v0(file.bytes)
+                        .map_err(|error| SvgRenderErrorV0::Font(definition.font_num, error))?,
+                ),
+                bytes: file.bytes,
+                size_pt,
+                native,
+            },
+            None if native => {
+                return Err(SvgRenderErrorV0::NativeFontUnavailable(definition.font_num))
+            }
+            None => SvgFontV0::Reference {
+                family: String::from_utf8_lossy(name).into_owned(),
+                size_pt,
+            },
+        };
+        fonts.insert(definition.font_num, font);
+    }
+    Ok(fonts)
+}
+
+/// Text for a referenced glyph; codes that are not printable Unicode scalars use
+/// U+FFFD so the SVG stays well-formed.
+fn reference_text_v0(code: u32) -> String {
+    match char::from_u32(code) {
+        Some(ch) if !ch.is_control() => escape_xml_v0(&ch.to_string()),
+        _ => "\u{fffd}".to_string(),
+    }
+}
+
+fn render_glyph_v0(
+    glyph: &DviGlyphV0,
+    fonts: &BTreeMap<i32, SvgFontV0>,
+    units: &UnitsV0,
+    defs: &mut BTreeMap<(i32, u16), String>,
+    body: &mut String,
+) -> Result<(), SvgRenderErrorV0> {
+    let font = fonts
+        .get(&glyph.font_num)
+        .ok_or(SvgRenderErrorV0::FontUndefined(glyph.font_num))?;
+    let (x, y) = (
+        decimal_text_v0(units.x_v0(glyph.h)),
+        decimal_text_v0(units.y_v0(glyph.v)),
+    );
+    match font {
+        SvgFontV0::Reference { family, size_pt } => {
+            let _ = writeln!(
+                body,
+                "<text x=\"{x}\" y=\"{y}\" font-family=\"{}\" font-size=\"{}\">{}</text>",
+                escape_xml_v0(family),
+                decimal_text_v0(*size_pt),
+                reference_text_v0(glyph.code)
+            );
+        }
+        SvgFontV0::Outline {
+            font,
+            bytes,
+            size_pt,
+            native,
+        } => {
+            let glyph_id = if *native {
+                u16::try_from(glyph.code).ok()
+            } else {
+                char::from_u32(glyph.code).and_then(|ch| font.glyph_id_v0(ch))
+            }
+            .ok_or(SvgRenderErrorV0::GlyphUnmapped(glyph.font_num, glyph.code))?;
+            let key = (glyph.font_num, glyph_id);
+            if let btree_map::Entry::Vacant(entry) = defs.entry(key) {
+                let outline = font
+                    .glyph_outline_v0(bytes, glyph_id)
+                    .map_err(|error| SvgRenderErrorV0::Font(glyph.font_num, error))?;
+                entry.insert(path_data_v0(&outline));
+            }
+            let scale = size_pt / font.units_per_em_v0() as f64;
+            let _ = writeln!(
+                body,
+                "<use xlink:href=\"#f{}g{glyph_id}\" transform=\"matrix({} 0 0 {} {x} {y})\"/>",
+                glyph.font_num,
+                decimal_text_v0(scale),
+                decimal_text_v0(-scale)
+            );
+        }
+    }
+    Ok(())
+}
+
+fn base64_v0(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | u32::from(*byte) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                out.push(BASE64_ALPHABET_V0[(group >> (18 - 6 * index) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// `data:` URIs of the images named by the `pdf:image` specials of `document`.
+fn resolve_images_v0<'a>(
+    document: &'a DviDocumentV0,
+    images: &[SvgImageFileV0],
+) -> Result<BTreeMap<&'a [u8], String>, SvgRenderErrorV0> {
+    let mut uris = BTreeMap::new();
+    for special in document.pages.iter().flat_map(|page| &page.specials) {
+        let Some(image) = parse_image_special_v0(&special.bytes) else {
+            continue;
+        };
+        if let btree_map::Entry::Vacant(entry) = uris.entry(image.name) {
+            let file = images
+                .iter()
+                .find(|file| file.name == image.name)
+                .ok_or(SvgRenderErrorV0::ImageUnavailable)?;
+            let mime = match parse_image_v0(file.bytes).map(|image| image.kind) {
+                Some(DviImageKindV0::Png { .. }) => "image/png",
+                Some(DviImageKindV0::Jpeg { .. }) => "image/jpeg",
+                None => return Err(SvgRenderErrorV0::ImageInvalid),
+            };
+            entry.insert(format!("data:{mime};base64,{}", base64_v0(file.bytes)));
+        }
+    }
+    Ok(uris)
+}
+
+fn render_rule_v0(rule: &DviRuleV0, units: &UnitsV0, body: &mut String) {
+    let _ = writeln!(
+        body,
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+        decimal_text_v0(units.x_v0(rule.h)),
+        decimal_text_v0(units.y_v0(rule.v.saturating_sub(rule.height))),
+        decimal_text_v0(units.length_v0(rule.width)),
+        decimal_text_v0(units.length_v0(rule.height))
+    );
+}
+
+fn render_page_v0(
+    page: &DviPageV0,
+    fonts: &BTreeMap<i32, SvgFontV0>,
+    images: &BTreeMap<&[u8], String>,
+    units: &UnitsV0,
+    width_pt: &str,
+    height_pt: &str,
+) -> Result<String, SvgRenderErrorV0> {
+    let mut defs = BTreeMap::<(i32, u16), String>::new();
+    let mut body = String::new();
+    for special in &page.specials {
+        let Some(image) = parse_image_special_v0(&special.bytes) else {
+            continue;
+        };
+        let _ = writeln!(
+            body,
+            "<image x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\" xlink:href=\"{}\"/>",
+            decimal_text_v0(units.x_v0(special.h)),
+            decimal_text_v0(units.y_v0(special.v.saturating_sub(image.height_sp))),
+            decimal_text_v0(units.length_v0(image.width_sp)),
+            decimal_text_v0(units.length_v0(image.height_sp)),
+            images[image.name]
+        );
+    }
+    for rule in &page.rules {
+        render_rule_v0(rule, units, &mut body);
+    }
+    for glyph in &page.glyphs {
+        render_glyph_v0(glyph, fonts, units, &mut defs, &mut body)?;
+    }
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "<svg xmlns=\"{SVG_NAMESPACE_V0}\" xmlns:xlink=\"{XLINK_NAMESPACE_V0}\" width=\"{width_pt}pt\" height=\"{height_pt}pt\" viewBox=\"0 0 {width_pt} {height_pt}\">"
+    );
+    if !defs.is_empty() {
+        out.push_str("<defs>\n");
+        for ((font_num, glyph_id), data) in &defs {
+            let _ = writeln!(out, "<path id=\"f{font_num}g{glyph_id}\" d=\"{data}\"/>");
+        }
+        out.push_str("</defs>\n");
+    }
+    out.push_str(&body);
+    out.push_str("</svg>\n");
+    Ok(out)
+}
+
+/// Render each page of a DVI/XDV page model to a standalone SVG document.
+///
+/// Positions convert DVI units to pt from the preamble `num`/`den`/`mag`; the SVG user
+/// unit is 1pt. Rules become `<rect>`s. Glyphs of fonts found in `files` are embedded
+/// as outline `<path>`s (one `<defs>` entry per used glyph, in font/glyph order) and
+/// placed with `<use>`; other TFM fonts are referenced by name in `<text>`. XDV native
+/// fonts must be present in `files`. Equal inputs give byte-identical output.
+pub fn render_svg_pages_v0(
+    document: &DviDocumentV0,
+    files: &[SvgFontFileV0],
+    options: &SvgRenderOptionsV0,
+) -> Result<Vec<String>, SvgRenderErrorV0> {
+    render_svg_pages_with_images_v0(document, files, &[], options)
+}
+
+/// `render_svg_pages_v0`, with the images of `pdf:image` specials read from
+/// `images` and drawn under the rules and glyphs as `<image>`s holding the file in
+/// a `data:` URI. A special naming a file missing from `images`, or one that is not
+/// a PNG or JPEG `parse_image_v0` accepts, fails the render.
+pub fn render_svg_pages_with_images_v0(
+    document: &DviDocumentV0,
+    files: &[SvgFontFileV0],
+    images: &[SvgImageFileV0],
+    options: &SvgRenderOptionsV0,
+) -> Result<Vec<String>, SvgRenderErrorV0> {
+    if options.paper_width_sp <= 0
+        || options.paper_height_sp <= 0
+        || options.fallback_font_size_sp <= 0
+    {
+        return Err(SvgRenderErrorV0::OptionsInvalid);
+    }
+    if document.pages.len() > MAX_SVG_PAGES_V0 {
+        return Err(SvgRenderErrorV0::TooManyPages);
+    }
+    let preamble = &document.preamble;
+    if preamble.num == 0 || preamble.den == 0 || preamble.mag == 0 {
+        return Err(SvgRenderErrorV0::UnitsInvalid);
+    }
+    let meters_e7_per_unit =
+        preamble.num as f64 / preamble.den as f64 * preamble.mag as f64 / 1000.0;
+    let units = UnitsV0 {
+        pt_per_unit: meters_e7_per_unit * 72.27 / 254_000.0,
+        origin_x_pt: sp_to_pt_v0(options.origin_x_sp),
+        origin_y_pt: sp_to_pt_v0(options.origin_y_sp),
+    };
+    let fonts = resolve_fonts_v0(document, files, &units, options)?;
+    let images = resolve_images_v0(document, images)?;
+    let width_pt = decimal_text_v0(sp_to_pt_v0(options.paper_width_sp));
+    let height_pt = decimal_text_v0(sp_to_pt_v0(options.paper_height_sp));
+    document
+        .pages
+        .iter()
+        .map(|page| render_page_v0(page, &fonts, &images, &units, &width_pt, &height_pt))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,329 @@
+use carreltex_fonts::{write_sfnt_v0, OutlineFormatV0};
+use carreltex_xdv::{
+    image_special_v0, read_dvi_v0, write_dvi_v2_text_page_with_layout_wrap_and_paging_v0,
+    DviDocumentV0, DviFontDefV0, DviFontKindV0, DviGlyphV0, DviPageV0, DviPostambleV0,
+    DviPreambleV0, DviRuleV0, DviSpecialV0, DviTfmFontDefV0, XdvNativeFontDefV0, DVI_DEN, DVI_MAG,
+    DVI_NUM,
+};
+
+use super::{
+    base64_v0, render_svg_pages_v0, render_svg_pages_with_images_v0, SvgFontFileV0, SvgImageFileV0,
+    SvgRenderErrorV0, SvgRenderOptionsV0,
+};
+
+const TEN_PT_SP: i32 = 655_360;
+
+/// TrueType font with 1000 units/em, `A` mapped to glyph 1: a 500x700 box.
+fn box_font_bytes_v0() -> Vec<u8> {
+    let mut head = vec![0u8; 54];
+    head[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    head[12..16].copy_from_slice(&0x5f0f_3cf5u32.to_be_bytes());
+    head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+    let mut maxp = 0x0000_5000u32.to_be_bytes().to_vec();
+    maxp.extend_from_slice(&2u16.to_be_bytes());
+    let mut hhea = vec![0u8; 36];
+    hhea[0..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    hhea[34..36].copy_from_slice(&2u16.to_be_bytes());
+    let hmtx = [0u16, 0, 500, 0]
+        .iter()
+        .flat_map(|value| value.to_be_bytes())
+        .collect::<Vec<u8>>();
+    // Format 4 with a delta segment for 'A' and the 0xffff sentinel.
+    let format4 = [
+        4u16, 32, 0, 4, 4, 1, 0, 0x41, 0xffff, 0, 0x41, 0xffff, 0xffc0, 1, 0, 0,
+    ]
+    .iter()
+    .flat_map(|value| value.to_be_bytes())
+    .collect::<Vec<u8>>();
+    let mut cmap = [0u16, 1, 3, 1]
+        .iter()
+        .flat_map(|v| v.to_be_bytes())
+        .collect::<Vec<u8>>();
+    cmap.extend_from_slice(&12u32.to_be_bytes());
+    cmap.extend_from_slice(&format4);
+    let name = vec![0u8; 6];
+    let mut glyph = Vec::<u8>::new();
+    for value in [1i16, 0, 0, 500, 700, 3, 0] {
+        glyph.extend_from_slice(&value.to_be_bytes());
+    }
+    glyph.extend_from_slice(&[0x01, 0x01, 0x01, 0x01]);
+    for value in [0i16, 500, 0, -500, 0, 0, 700, 0] {
+        glyph.extend_from_slice(&value.to_be_bytes());
+    }
+    let loca = [0u16, 0, (glyph.len() / 2) as u16]
+        .iter()
+        .flat_map(|value| value.to_be_bytes())
+        .collect::<Vec<u8>>();
+    write_sfnt_v0(
+        OutlineFormatV0::TrueType,
+        &[
+            (*b"head", head),
+            (*b"maxp", maxp),
+            (*b"hhea", hhea),
+            (*b"hmtx", hmtx),
+            (*b"cmap", cmap),
+            (*b"name", name),
+            (*b"glyf", glyph),
+            (*b"loca", loca),
+        ],
+    )
+    .expect("test font must assemble")
+}
+
+fn native_document_v0(glyphs: Vec<DviGlyphV0>, rules: Vec<DviRuleV0>) -> DviDocumentV0 {
+    DviDocumentV0 {
+        preamble: DviPreambleV0 {
+            id: 7,
+            num: DVI_NUM,
+            den: DVI_DEN,
+            mag: DVI_MAG,
+            comment: Vec::new(),
+        },
+        postamble: DviPostambleV0 {
+            last_bop: 15,
+            num: DVI_NUM,
+            den: DVI_DEN,
+            mag: DVI_MAG,
+            max_height_depth: 0,
+            max_width: 0,
+            max_stack_depth: 0,
+            total_pages: 1,
+        },
+        fonts: vec![DviFontDefV0 {
+            font_num: 3,
+            kind: DviFontKindV0::Native(XdvNativeFontDefV0 {
+                size: TEN_PT_SP,
+                flags: 0,
+                filename: b"box.ttf".to_vec(),
+                index: 0,
+                rgba: None,
+                extend: None,
+                slant: None,
+                embolden: None,
+            }),
+        }],
+        pages: vec![DviPageV0 {
+            offset: 15,
+            counts: [1, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            glyphs,
+            rules,
+            specials: Vec::new(),
+        }],
+    }
+}
+
+fn native_glyph_v0(h: i32, v: i32) -> DviGlyphV0 {
+    DviGlyphV0 {
+        font_num: 3,
+        code: 1,
+        native: true,
+        h,
+        v,
+        width: 327_680,
+    }
+}
+
+#[test]
+fn renders_one_svg_per_page_with_font_references() {
+    let bytes = write_dvi_v2_text_page_with_layout_wrap_and_paging_v0(
+        b"a<\nb\x0cc",
+        65_536,
+        786_432,
+        80,
+        200,
+    )
+    .expect("writer should accept text");
+    let document = read_dvi_v0(&bytes).expect("reader should accept writer output");
+    let pages = render_svg_pages_v0(&document, &[], &SvgRenderOptionsV0::default())
+        .expect("render should succeed");
+    assert_eq!(pages.len(), 2);
+    assert_eq!(
+        pages[0],
+        concat!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" width=\"614.295pt\" height=\"794.97pt\" viewBox=\"0 0 614.295 794.97\">\n",
+            "<text x=\"72.27\" y=\"72.27\" font-family=\"carreltex-v0\" font-size=\"10\">a</text>\n",
+            "<text x=\"73.27\" y=\"72.27\" font-family=\"carreltex-v0\" font-size=\"10\">&lt;</text>\n",
+            "<text x=\"72.27\" y=\"84.27\" font-family=\"carreltex-v0\" font-size=\"10\">b</text>\n",
+            "</svg>\n",
+        )
+    );
+    assert!(pages[1].contains(">c</text>"));
+}
+
+#[test]
+fn embeds_outlines_once_and_places_them_with_pt_coordinates() {
+    let document = native_document_v0(
+        vec![native_glyph_v0(0, 0), native_glyph_v0(327_680, 65_536)],
+        vec![DviRuleV0 {
+            h: 65_536,
+            v: 131_072,
+            width: 655_360,
+            height: 26_214,
+        }],
+    );
+    let font = box_font_bytes_v0();
+    let files = [SvgFontFileV0 {
+        name: b"box.ttf",
+        bytes: &font,
+    }];
+    let options = SvgRenderOptionsV0 {
+        origin_x_sp: 0,
+        origin_y_sp: 0,
+        ..SvgRenderOptionsV0::default()
+    };
+    let first = render_svg_pages_v0(&document, &files, &options).expect("render should succeed");
+    let second = render_svg_pages_v0(&document, &files, &options).expect("render should succeed");
+    assert_eq!(first, second);
+    let page = &first[0];
+    assert_eq!(page.matches("<path ").count(), 1);
+    assert!(
+        page.contains("<defs>\n<path id=\"f3g1\" d=\"M0 0L500 0L500 700L0 700L0 0Z\"/>\n</defs>\n")
+    );
+    assert!(page.contains("<rect x=\"1\" y=\"1.6\" width=\"10\" height=\"0.4\"/>\n"));
+    assert!(page.contains("<use xlink:href=\"#f3g1\" transform=\"matrix(0.01 0 0 -0.01 0 0)\"/>\n"));
+    assert!(page.contains("<use xlink:href=\"#f3g1\" transform=\"matrix(0.01 0 0 -0.01 5 1)\"/>\n"));
+}
+
+#[test]
+fn tfm_fonts_with_mounted_files_map_codes_through_cmap() {
+    let mut document = native_document_v0(vec![], vec![]);
+    document.fonts[0].kind = DviFontKindV0::Tfm(DviTfmFontDefV0 {
+        checksum: 0,
+        scale: TEN_PT_SP,
+        design_size: TEN_PT_SP,
+        area: Vec::new(),
+        name: b"box".to_vec(),
+    });
+    let mut glyph = native_glyph_v0(0, 0);
+    glyph.native = false;
+    glyph.code = b'A' as u32;
+    document.pages[0].glyphs = vec![glyph];
+    let font = box_font_bytes_v0();
+    let files = [SvgFontFileV0 {
+        name: b"box",
+        bytes: &font,
+    }];
+    let pages = render_svg_pages_v0(&document, &files, &SvgRenderOptionsV0::default())
+        .expect("render should succeed");
+    assert!(pages[0].contains("<path id=\"f3g1\""));
+    document.pages[0].glyphs[0].code = b'B' as u32;
+    let error = render_svg_pages_v0(&document, &files, &SvgRenderOptionsV0::default())
+        .expect_err("unmapped char should fail");
+    assert_eq!(error, SvgRenderErrorV0::GlyphUnmapped(3, b'B' as u32));
+    assert_eq!(error.reason_v0(), "svg_glyph_unmapped");
+}
+
+#[test]
+fn fails_closed_on_missing_native_font_and_bad_inputs() {
+    let document = native_document_v0(vec![native_glyph_v0(0, 0)], vec![]);
+    let error = render_svg_pages_v0(&document, &[], &SvgRenderOptionsV0::default())
+        .expect_err("native font must be mounted");
+    assert_eq!(error, SvgRenderErrorV0::NativeFontUnavailable(3));
+    assert_eq!(error.reason_v0(), "svg_native_font_unavailable");
+
+    let broken = b"not a font".to_vec();
+    let files = [SvgFontFileV0 {
+        name: b"box.ttf",
+        bytes: &broken,
+    }];
+    let error = render_svg_pages_v0(&document, &files, &SvgRenderOptionsV0::default())
+        .expect_err("broken font must fail");
+    assert_eq!(error.reason_v0(), "font_sfnt_version_unsupported");
+
+    let font = box_font_bytes_v0();
+    let files = [SvgFontFileV0 {
+        name: b"box.ttf",
+        bytes: &font,
+    }];
+    let mut out_of_range = document.clone();
+    out_of_range.pages[0].glyphs[0].code = 9;
+    let error = render_svg_pages_v0(&out_of_range, &files, &SvgRenderOptionsV0::default())
+        .expect_err("glyph id outside the font must fail");
+    assert_eq!(error.reason_v0(), "font_glyph_id_invalid");
+
+    let options = SvgRenderOptionsV0 {
+        paper_height_sp: 0,
+        ..SvgRenderOptionsV0::default()
+    };
+    assert_eq!(
+        render_svg_pages_v0(&document, &files, &options),
+        Err(SvgRenderErrorV0::OptionsInvalid)
+    );
+    let mut zero_den = document.clone();
+    zero_den.preamble.den = 0;
+    assert_eq!(
+        render_svg_pages_v0(&zero_den, &files, &SvgRenderOptionsV0::default()),
+        Err(SvgRenderErrorV0::UnitsInvalid)
+    );
+}
+
+#[test]
+fn magnification_scales_positions() {
+    let mut document = native_document_v0(vec![native_glyph_v0(65_536, 0)], vec![]);
+    document.preamble.mag = 2000;
+    let font = box_font_bytes_v0();
+    let files = [SvgFontFileV0 {
+        name: b"box.ttf",
+        bytes: &font,
+    }];
+    let options = SvgRenderOptionsV0 {
+        origin_x_sp: 0,
+        origin_y_sp: 0,
+        ..SvgRenderOptionsV0::default()
+    };
+    let pages = render_svg_pages_v0(&document, &files, &options).expect("render should succeed");
+    assert!(pages[0].contains("matrix(0.02 0 0 -0.02 2 0)"));
+}
+
+#[test]
+fn draws_images_under_rules_as_data_uris() {
+    assert_eq!(base64_v0(b""), "");
+    assert_eq!(base64_v0(b"f"), "Zg==");
+    assert_eq!(base64_v0(b"fo"), "Zm8=");
+    assert_eq!(base64_v0(b"foobar"), "Zm9vYmFy");
+
+    let jpeg = [
+        0xff, 0xd8, 0xff, 0xc0, 0, 11, 8, 0, 1, 0, 1, 1, 1, 0x11, 0, 0xff, 0xda,
+    ];
+    let mut document = native_document_v0(
+        vec![],
+        vec![DviRuleV0 {
+            h: 0,
+            v: 0,
+            width: TEN_PT_SP,
+            height: TEN_PT_SP,
+        }],
+    );
+    document.fonts.clear();
+    document.pages[0].specials = vec![DviSpecialV0 {
+        h: TEN_PT_SP,
+        v: 2 * TEN_PT_SP,
+        glyph_index: 0,
+        rule_index: 0,
+        bytes: image_special_v0(3 * TEN_PT_SP, TEN_PT_SP, b"dot.jpg"),
+    }];
+    let images = [SvgImageFileV0 {
+        name: b"dot.jpg",
+        bytes: &jpeg,
+    }];
+    let pages =
+        render_svg_pages_with_images_v0(&document, &[], &images, &SvgRenderOptionsV0::default())
+            .expect("svg");
+    assert!(pages[0].contains(concat!(
+        "<image x=\"82.27\" y=\"82.27\" width=\"30\" height=\"10\" preserveAspectRatio=\"none\" ",
+        "xlink:href=\"data:image/jpeg;base64,/9j/wAALCAABAAEBAREA/9o=\"/>\n<rect "
+    )));
+
+    assert_eq!(
+        render_svg_pages_v0(&document, &[], &SvgRenderOptionsV0::default()),
+        Err(SvgRenderErrorV0::ImageUnavailable)
+    );
+    let images = [SvgImageFileV0 {
+        name: b"dot.jpg",
+        bytes: b"GIF89a",
+    }];
+    let error =
+        render_svg_pages_with_images_v0(&document, &[], &images, &SvgRenderOptionsV0::default())
+            .expect_err("unrecognized image");
+    assert_eq!(error.reason_v0(), "svg_image_invalid");
+}
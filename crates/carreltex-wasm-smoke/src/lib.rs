@@ -1,12 +1,13 @@
+use std::collections::BTreeMap;
 use std::sync::{Mutex, OnceLock};
 
 use carreltex_core::{
-    append_event_v0, artifact_bytes_within_cap_v0, report_json_has_status_token_v0,
-    report_json_missing_components_is_empty_v0, validate_compile_report_json,
-    validate_input_trace_json_v0, validate_main_tex, validate_tex_stats_json_v0, CompileRequestV0,
-    CompileStatus, Mount, DEFAULT_COMPILE_MAIN_MAX_LOG_BYTES_V0, EVENT_KIND_LOG_BYTES_V0,
-    EVENT_KIND_TEX_STATS_JSON_V0, MAX_LOG_BYTES_V0, MAX_TEX_STATS_JSON_BYTES_V0,
-    MAX_WASM_ALLOC_BYTES_V0,
+    append_event_v0, artifact_bytes_within_cap_v0, ok_log_has_warnings_only_v0,
+    report_json_has_status_token_v0, report_json_missing_components_is_empty_v0,
+    validate_compile_report_json, validate_input_trace_json_v0, validate_main_tex,
+    validate_tex_stats_json_v0, CompileRequestV0, CompileResultV0, CompileStatus, Mount,
+    DEFAULT_COMPILE_MAIN_MAX_LOG_BYTES_V0, EVENT_KIND_LOG_BYTES_V0, EVENT_KIND_TEX_STATS_JSON_V0,
+    MAX_LOG_BYTES_V0, MAX_TEX_STATS_JSON_BYTES_V0, MAX_WASM_ALLOC_BYTES_V0,
 };
 use carreltex_engine::{compile_main_v0, compile_request_v0};
 
@@ -30,9 +31,9 @@ fn last_log_state() -> &'static Mutex<Vec<u8>> {
     STATE.get_or_init(|| Mutex::new(Vec::new()))
 }
 
-fn last_xdv_state() -> &'static Mutex<Vec<u8>> {
-    static STATE: OnceLock<Mutex<Vec<u8>>> = OnceLock::new();
-    STATE.get_or_init(|| Mutex::new(Vec::new()))
+fn last_artifacts_state() -> &'static Mutex<BTreeMap<&'static str, Vec<u8>>> {
+    static STATE: OnceLock<Mutex<BTreeMap<&'static str, Vec<u8>>>> = OnceLock::new();
+    STATE.get_or_init(|| Mutex::new(BTreeMap::new()))
 }
 
 fn last_events_state() -> &'static Mutex<Vec<u8>> {
@@ -94,6 +95,7 @@ pub extern "C" fn carreltex_wasm_alloc(size: usize) -> *mut u8 {
 }
 
 #[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn carreltex_wasm_dealloc(ptr: *mut u8, size: usize) {
     if ptr.is_null() || size == 0 || size > MAX_WASM_ALLOC_BYTES_V0 {
         return;
@@ -105,6 +107,7 @@ pub extern "C" fn carreltex_wasm_dealloc(ptr: *mut u8, size: usize) {
 }
 
 #[cfg(test)]
+#[allow(clippy::items_after_test_module)]
 mod tests {
     use super::{carreltex_wasm_alloc, carreltex_wasm_dealloc};
     use carreltex_core::MAX_WASM_ALLOC_BYTES_V0;
@@ -232,6 +235,7 @@ pub extern "C" fn carreltex_wasm_mount_read_file_len_v0(
 }
 
 #[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn carreltex_wasm_mount_read_file_copy_v0(
     path_ptr: *const u8,
     path_len: usize,
@@ -280,18 +284,23 @@ fn set_last_log_bytes(log_bytes: &[u8]) {
     last.extend_from_slice(log_bytes);
 }
 
-fn set_last_xdv_bytes(xdv_bytes: &[u8]) {
-    let mut last = match last_xdv_state().lock() {
+/// Replaces the artifacts of the last compile, keyed by file name.
+fn set_last_artifacts(artifacts: &[(&'static str, &[u8])]) {
+    let mut last = match last_artifacts_state().lock() {
         Ok(guard) => guard,
         Err(_) => return,
     };
     last.clear();
-    last.extend_from_slice(xdv_bytes);
+    last.extend(
+        artifacts
+            .iter()
+            .map(|(name, bytes)| (*name, bytes.to_vec())),
+    );
 }
 
-fn read_last_xdv_bytes() -> Option<Vec<u8>> {
-    let last = last_xdv_state().lock().ok()?;
-    Some(last.clone())
+fn read_last_artifact_bytes(name: &str) -> Option<Vec<u8>> {
+    let last = last_artifacts_state().lock().ok()?;
+    last.get(name).cloned()
 }
 
 fn set_last_events_bytes(events_bytes: &[u8]) {
@@ -313,7 +322,7 @@ fn write_report_for_status(status: CompileStatus) {
     };
     set_last_report_bytes(fallback);
     set_last_log_bytes(&[]);
-    set_last_xdv_bytes(&[]);
+    set_last_artifacts(&[]);
     set_last_events_bytes(&[]);
 }
 
@@ -326,13 +335,32 @@ fn find_trace_marker_offset_v0(haystack: &[u8]) -> Option<usize> {
 }
 
 fn store_compile_result_or_fail_closed(
-    report_json: &str,
-    log_bytes: &[u8],
-    xdv_bytes: &[u8],
-    tex_stats_json: &str,
-    status: CompileStatus,
+    result: &CompileResultV0,
     expected_log_max_bytes: usize,
 ) -> i32 {
+    let CompileResultV0 {
+        status,
+        report_json,
+        log_bytes,
+        main_xdv_bytes: xdv_bytes,
+        main_pdf_bytes: pdf_bytes,
+        main_aux_bytes: aux_bytes,
+        main_toc_bytes: toc_bytes,
+        main_lof_bytes: lof_bytes,
+        main_lot_bytes: lot_bytes,
+        main_bbl_bytes: bbl_bytes,
+        tex_stats_json,
+    } = result;
+    let artifacts: [(&'static str, &[u8]); 7] = [
+        ("main.xdv", xdv_bytes),
+        ("main.pdf", pdf_bytes),
+        ("main.aux", aux_bytes),
+        ("main.toc", toc_bytes),
+        ("main.lof", lof_bytes),
+        ("main.lot", lot_bytes),
+        ("main.bbl", bbl_bytes),
+    ];
+    let status = *status;
     if validate_compile_report_json(report_json).is_err() {
         write_report_for_status(CompileStatus::InvalidInput);
         return CompileStatus::InvalidInput as i32;
@@ -363,7 +391,7 @@ fn store_compile_result_or_fail_closed(
         write_report_for_status(CompileStatus::InvalidInput);
         return CompileStatus::InvalidInput as i32;
     }
-    if matches!(status, CompileStatus::Ok) && !log_bytes.is_empty() {
+    if matches!(status, CompileStatus::Ok) && !ok_log_has_warnings_only_v0(log_bytes) {
         write_report_for_status(CompileStatus::InvalidInput);
         return CompileStatus::InvalidInput as i32;
     }
@@ -403,16 +431,21 @@ fn store_compile_result_or_fail_closed(
         }
         _ => {}
     }
-    if !artifact_bytes_within_cap_v0(xdv_bytes) {
+    if artifacts
+        .iter()
+        .any(|(_, bytes)| !artifact_bytes_within_cap_v0(bytes))
+    {
         write_report_for_status(CompileStatus::InvalidInput);
         return CompileStatus::InvalidInput as i32;
     }
     match status {
-        CompileStatus::Ok if xdv_bytes.is_empty() => {
+        CompileStatus::Ok if xdv_bytes.is_empty() || pdf_bytes.is_empty() => {
             write_report_for_status(CompileStatus::InvalidInput);
             return CompileStatus::InvalidInput as i32;
         }
-        CompileStatus::InvalidInput | CompileStatus::NotImplemented if !xdv_bytes.is_empty() => {
+        CompileStatus::InvalidInput | CompileStatus::NotImplemented
+            if artifacts.iter().any(|(_, bytes)| !bytes.is_empty()) =>
+        {
             write_report_for_status(CompileStatus::InvalidInput);
             return CompileStatus::InvalidInput as i32;
         }
@@ -421,7 +454,7 @@ fn store_compile_result_or_fail_closed(
 
     set_last_report_bytes(report_json);
     set_last_log_bytes(log_bytes);
-    set_last_xdv_bytes(xdv_bytes);
+    set_last_artifacts(&artifacts);
     set_last_events_bytes(&[]);
     status as i32
 }
@@ -456,11 +489,7 @@ pub extern "C" fn carreltex_wasm_compile_main_v0() -> i32 {
 
     let result = compile_main_v0(&mut mount);
     let status = store_compile_result_or_fail_closed(
-        &result.report_json,
-        &result.log_bytes,
-        &result.main_xdv_bytes,
-        &result.tex_stats_json,
-        result.status,
+        &result,
         DEFAULT_COMPILE_MAIN_MAX_LOG_BYTES_V0 as usize,
     );
     if status == CompileStatus::InvalidInput as i32 {
@@ -622,14 +651,7 @@ pub extern "C" fn carreltex_wasm_compile_run_v0() -> i32 {
     };
 
     let result = compile_request_v0(&mut mount, &request);
-    let status = store_compile_result_or_fail_closed(
-        &result.report_json,
-        &result.log_bytes,
-        &result.main_xdv_bytes,
-        &result.tex_stats_json,
-        result.status,
-        request.max_log_bytes as usize,
-    );
+    let status = store_compile_result_or_fail_closed(&result, request.max_log_bytes as usize);
     if status == CompileStatus::InvalidInput as i32 {
         return status;
     }
@@ -650,6 +672,7 @@ pub extern "C" fn carreltex_wasm_compile_report_len_v0() -> usize {
 }
 
 #[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn carreltex_wasm_compile_report_copy_v0(out_ptr: *mut u8, out_len: usize) -> usize {
     if out_ptr.is_null() || out_len == 0 {
         return 0;
@@ -677,6 +700,7 @@ pub extern "C" fn carreltex_wasm_compile_log_len_v0() -> usize {
 }
 
 #[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn carreltex_wasm_compile_log_copy_v0(out_ptr: *mut u8, out_len: usize) -> usize {
     if out_ptr.is_null() || out_len == 0 {
         return 0;
@@ -704,6 +728,7 @@ pub extern "C" fn carreltex_wasm_events_len_v0() -> usize {
 }
 
 #[no_mangle]
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub extern "C" fn carreltex_wasm_events_copy_v0(out_ptr: *mut u8, out_len: usize) -> usize {
     if out_ptr.is_null() || out_len == 0 {
         return 0;
@@ -742,11 +767,7 @@ pub extern "C" fn carreltex_wasm_artifact_len_v0(name_ptr: *const u8, name_len:
         Some(name) => name,
         None => return 0,
     };
-    if name != "main.xdv" {
-        return 0;
-    }
-
-    let bytes = match read_last_xdv_bytes() {
+    let bytes = match read_last_artifact_bytes(name) {
         Some(bytes) => bytes,
         None => return 0,
     };
@@ -767,11 +788,7 @@ pub extern "C" fn carreltex_wasm_artifact_copy_v0(
         Some(name) => name,
         None => return 0,
     };
-    if name != "main.xdv" {
-        return 0;
-    }
-
-    let bytes = match read_last_xdv_bytes() {
+    let bytes = match read_last_artifact_bytes(name) {
         Some(bytes) => bytes,
         None => return 0,
     };
@@ -0,0 +1,79 @@
+use std::io::Write;
+use std::process::ExitCode;
+
+use carreltex_xdv::{
+    dump_dvi_with_char_widths_v0, parse_tfm_v0, tfm_char_width_v0, DviDumpFormatV0, TfmFontV0,
+    MAX_DVI_READ_BYTES_V0, MAX_TFM_BYTES_V0,
+};
+
+const USAGE_V0: &str =
+    "usage: carreltex-dvitype [--json] [--tfm <font>=<file.tfm>]... <file.dvi|file.xdv>";
+
+/// Bytes of `path`, or the message to print when it cannot be read within `max_bytes`.
+fn read_capped_v0(path: &str, max_bytes: usize, too_large: &str) -> Result<Vec<u8>, String> {
+    match std::fs::read(path) {
+        Ok(bytes) if bytes.len() <= max_bytes => Ok(bytes),
+        Ok(_) => Err(format!("{path}: {too_large}")),
+        Err(error) => Err(format!("{path}: {error}")),
+    }
+}
+
+/// The metrics named by a `--tfm <font>=<file.tfm>` argument.
+fn load_tfm_v0(spec: &str) -> Result<(Vec<u8>, TfmFontV0), String> {
+    let Some((name, path)) = spec.split_once('=').filter(|(name, _)| !name.is_empty()) else {
+        return Err(USAGE_V0.to_owned());
+    };
+    let bytes = read_capped_v0(path, MAX_TFM_BYTES_V0, "tfm_too_large")?;
+    let tfm = parse_tfm_v0(&bytes).ok_or_else(|| format!("{path}: tfm_invalid"))?;
+    Ok((name.as_bytes().to_vec(), tfm))
+}
+
+fn main() -> ExitCode {
+    let mut format = DviDumpFormatV0::Text;
+    let mut tfms = Vec::<(Vec<u8>, TfmFontV0)>::new();
+    let mut path = None::<String>;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => format = DviDumpFormatV0::Json,
+            "--tfm" => match args.next().as_deref().map(load_tfm_v0) {
+                Some(Ok(tfm)) => tfms.push(tfm),
+                Some(Err(message)) => {
+                    eprintln!("{message}");
+                    return ExitCode::FAILURE;
+                }
+                None => {
+                    eprintln!("{USAGE_V0}");
+                    return ExitCode::from(2);
+                }
+            },
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => {
+                eprintln!("{USAGE_V0}");
+                return ExitCode::from(2);
+            }
+        }
+    }
+    let Some(path) = path else {
+        eprintln!("{USAGE_V0}");
+        return ExitCode::from(2);
+    };
+    let bytes = match read_capped_v0(&path, MAX_DVI_READ_BYTES_V0, "dvi_too_large") {
+        Ok(bytes) => bytes,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let dump = dump_dvi_with_char_widths_v0(&bytes, tfm_char_width_v0(&tfms), format);
+    let mut stdout = std::io::stdout().lock();
+    if stdout.write_all(dump.output.as_bytes()).is_err()
+        || (format == DviDumpFormatV0::Json && stdout.write_all(b"\n").is_err())
+    {
+        return ExitCode::FAILURE;
+    }
+    match dump.error {
+        Some(_) => ExitCode::FAILURE,
+        None => ExitCode::SUCCESS,
+    }
+}
@@ -0,0 +1,60 @@
+use crate::reader_v0::DviDocumentV0;
+
+/// Deepest `color push` nesting accepted by `DviColorStackV0`.
+pub const MAX_DVI_COLOR_STACK_DEPTH_V0: usize = 255;
+
+/// dvips/xdvipdfmx color stack driven by `color push <spec>` and `color pop`
+/// specials. The stack carries across pages, so balance is a per-file property.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DviColorStackV0 {
+    depth: usize,
+}
+
+impl DviColorStackV0 {
+    pub fn depth_v0(&self) -> usize {
+        self.depth
+    }
+
+    /// Applies one special payload. Returns `None` on a pop from an empty stack, a
+    /// push without a color or past `MAX_DVI_COLOR_STACK_DEPTH_V0`; specials other
+    /// than `color push`/`color pop` leave the stack unchanged.
+    pub fn apply_special_v0(&mut self, payload: &[u8]) -> Option<()> {
+        let mut words = payload
+            .split(|byte| byte.is_ascii_whitespace())
+            .filter(|word| !word.is_empty());
+        if words.next() != Some(&b"color"[..]) {
+            return Some(());
+        }
+        match words.next() {
+            Some(b"push") => {
+                words.next()?;
+                if self.depth >= MAX_DVI_COLOR_STACK_DEPTH_V0 {
+                    return None;
+                }
+                self.depth += 1;
+            }
+            Some(b"pop") => {
+                if words.next().is_some() {
+                    return None;
+                }
+                self.depth = self.depth.checked_sub(1)?;
+            }
+            _ => {}
+        }
+        Some(())
+    }
+}
+
+/// True when every `color push` in the document is matched by a later `color pop`
+/// and no pop underflows the stack.
+pub fn validate_dvi_color_stack_v0(document: &DviDocumentV0) -> bool {
+    let mut stack = DviColorStackV0::default();
+    for page in &document.pages {
+        for special in &page.specials {
+            if stack.apply_special_v0(&special.bytes).is_none() {
+                return false;
+            }
+        }
+    }
+    stack.depth_v0() == 0
+}
@@ -0,0 +1,367 @@
+use core::fmt::Write;
+
+use crate::interpret_v0::{
+    walk_dvi_v0, DviCommandV0, DviFontDefV0, DviFontKindV0, DviReadErrorV0, DviStateV0,
+};
+use crate::reader_v0::zero_scale_char_width_v0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DviDumpFormatV0 {
+    Text,
+    Json,
+}
+
+/// Opcode listing of a DVI/XDV file. On a read failure `output` still holds every
+/// command decoded before the failure, followed by the error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviDumpV0 {
+    pub output: String,
+    pub error: Option<DviReadErrorV0>,
+}
+
+/// Operand value: numbers and integer lists print as-is, byte strings are quoted
+/// per output format.
+enum DumpValueV0 {
+    Literal(String),
+    Bytes(Vec<u8>),
+}
+
+impl DumpValueV0 {
+    fn text_v0(&self) -> String {
+        match self {
+            DumpValueV0::Literal(value) => value.clone(),
+            DumpValueV0::Bytes(bytes) => format!("\"{}\"", quote_bytes_v0(bytes)),
+        }
+    }
+
+    fn json_v0(&self) -> String {
+        match self {
+            DumpValueV0::Literal(value) => value.clone(),
+            DumpValueV0::Bytes(bytes) => json_string_v0(bytes),
+        }
+    }
+}
+
+fn number_v0(value: impl ToString) -> DumpValueV0 {
+    DumpValueV0::Literal(value.to_string())
+}
+
+fn opcode_name_v0(opcode: u8) -> String {
+    match opcode {
+        0..=127 => format!("setchar{opcode}"),
+        128..=131 => format!("set{}", opcode - 127),
+        132 => "setrule".to_string(),
+        133..=136 => format!("put{}", opcode - 132),
+        137 => "putrule".to_string(),
+        138 => "nop".to_string(),
+        139 => "bop".to_string(),
+        140 => "eop".to_string(),
+        141 => "push".to_string(),
+        142 => "pop".to_string(),
+        143..=146 => format!("right{}", opcode - 142),
+        147..=151 => format!("w{}", opcode - 147),
+        152..=156 => format!("x{}", opcode - 152),
+        157..=160 => format!("down{}", opcode - 156),
+        161..=165 => format!("y{}", opcode - 161),
+        166..=170 => format!("z{}", opcode - 166),
+        171..=234 => format!("fntnum{}", opcode - 171),
+        235..=238 => format!("fnt{}", opcode - 234),
+        239..=242 => format!("xxx{}", opcode - 238),
+        243..=246 => format!("fntdef{}", opcode - 242),
+        247 => "pre".to_string(),
+        248 => "post".to_string(),
+        249 => "post_post".to_string(),
+        252 => "native_font_def".to_string(),
+        253 => "set_glyphs".to_string(),
+        254 => "set_text_and_glyphs".to_string(),
+        _ => format!("undefined{opcode}"),
+    }
+}
+
+/// Bytes as text with printable ASCII kept and everything else as `\xNN`.
+fn quote_bytes_v0(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for byte in bytes {
+        match byte {
+            b'\\' => out.push_str("\\\\"),
+            b'"' => out.push_str("\\\""),
+            0x20..=0x7e => out.push(*byte as char),
+            _ => {
+                let _ = write!(out, "\\x{byte:02x}");
+            }
+        }
+    }
+    out
+}
+
+fn json_string_v0(bytes: &[u8]) -> String {
+    let mut out = String::from("\"");
+    for byte in bytes {
+        match byte {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            0x20..=0x7e => out.push(*byte as char),
+            _ => {
+                let _ = write!(out, "\\u{:04x}", byte);
+            }
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn font_fields_v0(definition: &DviFontDefV0) -> Vec<(&'static str, DumpValueV0)> {
+    let mut fields = vec![("font", number_v0(definition.font_num))];
+    match &definition.kind {
+        DviFontKindV0::Tfm(font) => {
+            fields.push(("checksum", number_v0(font.checksum)));
+            fields.push(("scale", number_v0(font.scale)));
+            fields.push(("design_size", number_v0(font.design_size)));
+            fields.push(("area", DumpValueV0::Bytes(font.area.clone())));
+            fields.push(("name", DumpValueV0::Bytes(font.name.clone())));
+        }
+        DviFontKindV0::Native(font) => {
+            fields.push(("size", number_v0(font.size)));
+            fields.push(("flags", number_v0(font.flags)));
+            fields.push(("filename", DumpValueV0::Bytes(font.filename.clone())));
+            fields.push(("index", number_v0(font.index)));
+            for (key, value) in [
+                ("rgba", font.rgba.map(|value| value as i64)),
+                ("extend", font.extend.map(|value| value as i64)),
+                ("slant", font.slant.map(|value| value as i64)),
+                ("embolden", font.embolden.map(|value| value as i64)),
+            ] {
+                if let Some(value) = value {
+                    fields.push((key, number_v0(value)));
+                }
+            }
+        }
+    }
+    fields
+}
+
+fn command_fields_v0(command: &DviCommandV0) -> Vec<(&'static str, DumpValueV0)> {
+    let optional = |key: &'static str, value: &Option<i32>| match value {
+        Some(value) => vec![(key, number_v0(value))],
+        None => Vec::new(),
+    };
+    match command {
+        DviCommandV0::Pre(preamble) => vec![
+            ("id", number_v0(preamble.id)),
+            ("num", number_v0(preamble.num)),
+            ("den", number_v0(preamble.den)),
+            ("mag", number_v0(preamble.mag)),
+            ("comment", DumpValueV0::Bytes(preamble.comment.clone())),
+        ],
+        DviCommandV0::Bop { counts, prev_bop } => vec![
+            (
+                "counts",
+                DumpValueV0::Literal(format!(
+                    "[{}]",
+                    counts
+                        .iter()
+                        .map(|count| count.to_string())
+                        .collect::<Vec<String>>()
+                        .join(",")
+                )),
+            ),
+            ("prev", number_v0(prev_bop)),
+        ],
+        DviCommandV0::SetChar(code) | DviCommandV0::PutChar(code) => {
+            vec![("char", number_v0(code))]
+        }
+        DviCommandV0::SetRule { height, width } | DviCommandV0::PutRule { height, width } => {
+            vec![("height", number_v0(height)), ("width", number_v0(width))]
+        }
+        DviCommandV0::Right(amount) | DviCommandV0::Down(amount) => {
+            vec![("amount", number_v0(amount))]
+        }
+        DviCommandV0::W(value) | DviCommandV0::X(value) => optional("amount", value),
+        DviCommandV0::Y(value) | DviCommandV0::Z(value) => optional("amount", value),
+        DviCommandV0::FntNum(font_num) => vec![("font", number_v0(font_num))],
+        DviCommandV0::Xxx(special) => vec![("special", DumpValueV0::Bytes(special.clone()))],
+        DviCommandV0::FntDef(definition) => font_fields_v0(definition),
+        DviCommandV0::SetGlyphs {
+            text,
+            width,
+            glyphs,
+        } => {
+            let mut fields = Vec::new();
+            if !text.is_empty() {
+                fields.push((
+                    "text",
+                    DumpValueV0::Literal(format!(
+                        "[{}]",
+                        text.iter()
+                            .map(|unit| unit.to_string())
+                            .collect::<Vec<String>>()
+                            .join(",")
+                    )),
+                ));
+            }
+            fields.push(("width", number_v0(width)));
+            fields.push((
+                "glyphs",
+                DumpValueV0::Literal(format!(
+                    "[{}]",
+                    glyphs
+                        .iter()
+                        .map(|(x, y, glyph)| format!("[{x},{y},{glyph}]"))
+                        .collect::<Vec<String>>()
+                        .join(",")
+                )),
+            ));
+            fields
+        }
+        DviCommandV0::Post(postamble) => vec![
+            ("last_bop", number_v0(postamble.last_bop)),
+            ("num", number_v0(postamble.num)),
+            ("den", number_v0(postamble.den)),
+            ("mag", number_v0(postamble.mag)),
+            ("max_height_depth", number_v0(postamble.max_height_depth)),
+            ("max_width", number_v0(postamble.max_width)),
+            ("max_stack_depth", number_v0(postamble.max_stack_depth)),
+            ("total_pages", number_v0(postamble.total_pages)),
+        ],
+        DviCommandV0::PostPost { post_offset, id } => {
+            vec![("post", number_v0(post_offset)), ("id", number_v0(id))]
+        }
+        DviCommandV0::Eop | DviCommandV0::Nop | DviCommandV0::Push | DviCommandV0::Pop => {
+            Vec::new()
+        }
+    }
+}
+
+fn write_text_line_v0(
+    out: &mut String,
+    offset: usize,
+    opcode: u8,
+    command: &DviCommandV0,
+    state: &DviStateV0,
+) {
+    let name = opcode_name_v0(opcode);
+    match command {
+        DviCommandV0::Bop { .. } => {
+            let page = state.page_index.map(|index| index + 1).unwrap_or(0);
+            let _ = writeln!(out, "\n{offset}: beginning of page {page}");
+        }
+        DviCommandV0::Eop => {
+            let page = state.page_index.map(|index| index + 1).unwrap_or(0);
+            let _ = writeln!(out, "{offset}: eop (end of page {page})");
+            return;
+        }
+        DviCommandV0::Post(_) => {
+            let _ = writeln!(out, "\n{offset}: beginning of the postamble");
+        }
+        _ => {}
+    }
+    let _ = write!(out, "{offset}: {name}");
+    for (key, value) in command_fields_v0(command) {
+        let _ = write!(out, " {key}={}", value.text_v0());
+    }
+    if state.page_index.is_some() {
+        let registers = state.registers;
+        let _ = write!(
+            out,
+            " [h={} v={} w={} x={} y={} z={} level={}",
+            registers.h,
+            registers.v,
+            registers.w,
+            registers.x,
+            registers.y,
+            registers.z,
+            state.stack_depth
+        );
+        if let Some(font) = state.font {
+            let _ = write!(out, " font={font}");
+        }
+        out.push(']');
+    }
+    out.push('\n');
+}
+
+fn write_json_entry_v0(
+    out: &mut String,
+    offset: usize,
+    opcode: u8,
+    command: &DviCommandV0,
+    state: &DviStateV0,
+) {
+    if !out.ends_with('[') {
+        out.push(',');
+    }
+    let _ = write!(
+        out,
+        "{{\"offset\":{offset},\"opcode\":{opcode},\"op\":\"{}\"",
+        opcode_name_v0(opcode)
+    );
+    for (key, value) in command_fields_v0(command) {
+        let _ = write!(out, ",\"{key}\":{}", value.json_v0());
+    }
+    if let Some(page_index) = state.page_index {
+        let registers = state.registers;
+        let _ = write!(
+            out,
+            ",\"page\":{},\"h\":{},\"v\":{},\"w\":{},\"x\":{},\"y\":{},\"z\":{},\"level\":{}",
+            page_index + 1,
+            registers.h,
+            registers.v,
+            registers.w,
+            registers.x,
+            registers.y,
+            registers.z,
+            state.stack_depth
+        );
+        if let Some(font) = state.font {
+            let _ = write!(out, ",\"font_selected\":{font}");
+        }
+    }
+    out.push('}');
+}
+
+/// `dvitype`-style listing: one entry per command with its byte offset, opcode,
+/// operands and the h/v/w/x/y/z registers, stack level and font after it executes.
+///
+/// `Text` separates pages with blank lines; `Json` emits
+/// `{"commands":[...],"error":null|{"offset":N,"reason":"..."}}` for golden files.
+pub fn dump_dvi_with_char_widths_v0<W>(
+    bytes: &[u8],
+    char_width: W,
+    format: DviDumpFormatV0,
+) -> DviDumpV0
+where
+    W: FnMut(&DviFontDefV0, u32) -> Option<i32>,
+{
+    let mut out = String::new();
+    if format == DviDumpFormatV0::Json {
+        out.push_str("{\"commands\":[");
+    }
+    let result = walk_dvi_v0(bytes, char_width, |offset, command, state| {
+        let opcode = bytes[offset];
+        match format {
+            DviDumpFormatV0::Text => write_text_line_v0(&mut out, offset, opcode, command, state),
+            DviDumpFormatV0::Json => write_json_entry_v0(&mut out, offset, opcode, command, state),
+        }
+    });
+    let error = result.err();
+    match (format, error) {
+        (DviDumpFormatV0::Text, Some(error)) => {
+            let _ = writeln!(out, "! {} at byte {}", error.reason_v0(), error.offset);
+        }
+        (DviDumpFormatV0::Text, None) => {}
+        (DviDumpFormatV0::Json, Some(error)) => {
+            let _ = write!(
+                out,
+                "],\"error\":{{\"offset\":{},\"reason\":\"{}\"}}}}",
+                error.offset,
+                error.reason_v0()
+            );
+        }
+        (DviDumpFormatV0::Json, None) => out.push_str("],\"error\":null}"),
+    }
+    DviDumpV0 { output: out, error }
+}
+
+pub fn dump_dvi_v0(bytes: &[u8], format: DviDumpFormatV0) -> DviDumpV0 {
+    dump_dvi_with_char_widths_v0(bytes, zero_scale_char_width_v0, format)
+}
@@ -0,0 +1,132 @@
+use super::test_dvi_v0::{
+    build_tfm_v0, fnt_def1_v0, native_font_def_v0, op_i32_v0, rule_v0, TestDviV0,
+};
+use super::{
+    dump_dvi_v0, dump_dvi_with_char_widths_v0, parse_tfm_v0, tfm_char_width_v0,
+    write_dvi_v2_empty_page_v0, write_dvi_v2_text_page_v0, DviDumpFormatV0, DviReadErrorKindV0,
+    XDV_ID_V0,
+};
+
+#[test]
+fn text_dump_of_empty_page_lists_every_command_with_offsets() {
+    let dump = dump_dvi_v0(&write_dvi_v2_empty_page_v0(), DviDumpFormatV0::Text);
+    assert_eq!(dump.error, None);
+    let lines = dump.output.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[0],
+        "0: pre id=2 num=25400000 den=473628672 mag=1000 comment=\"\""
+    );
+    assert_eq!(lines[1], "");
+    assert!(lines.contains(&"15: beginning of page 1"));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("15: bop counts=[0,0,0,0,0,0,0,0,0,0] prev=-1 [h=0 v=0")));
+    assert!(lines.contains(&"60: eop (end of page 1)"));
+    assert!(lines.contains(&"61: beginning of the postamble"));
+    assert_eq!(lines.last(), Some(&"90: post_post post=61 id=2"));
+}
+
+#[test]
+fn text_dump_tracks_registers_stack_and_fonts() {
+    let bytes = TestDviV0::new(XDV_ID_V0)
+        .set_max_stack_depth(1)
+        .bop(3)
+        .raw(&native_font_def_v0(
+            5,
+            655_360,
+            b"Latin Modern.otf",
+            0xff00_00ff,
+        ))
+        .raw(&[176])
+        .raw(&[141])
+        .raw(&op_i32_v0(146, 1000))
+        .raw(&op_i32_v0(151, -20))
+        .raw(&[147])
+        .raw(&rule_v0(137, 40, 50))
+        .raw(&[142])
+        .raw(&[239, 3, b'a', b'"', 0x01])
+        .eop()
+        .finish(&native_font_def_v0(
+            5,
+            655_360,
+            b"Latin Modern.otf",
+            0xff00_00ff,
+        ));
+    let dump = dump_dvi_v0(&bytes, DviDumpFormatV0::Text);
+    assert_eq!(dump.error, None);
+    let output = dump.output;
+    assert!(output.contains(
+        "native_font_def font=5 size=655360 flags=512 filename=\"Latin Modern.otf\" index=0 rgba=4278190335"
+    ));
+    assert!(output.contains(": fntnum5 font=5 [h=0 v=0 w=0 x=0 y=0 z=0 level=0 font=5]"));
+    assert!(output.contains(": push [h=0 v=0 w=0 x=0 y=0 z=0 level=1 font=5]"));
+    assert!(output.contains(": right4 amount=1000 [h=1000 v=0 w=0 x=0 y=0 z=0 level=1 font=5]"));
+    assert!(output.contains(": w4 amount=-20 [h=980 v=0 w=-20 x=0 y=0 z=0 level=1 font=5]"));
+    assert!(output.contains(": w0 [h=960 v=0 w=-20 x=0 y=0 z=0 level=1 font=5]"));
+    assert!(output.contains(": putrule height=40 width=50 [h=960 "));
+    assert!(output.contains(": pop [h=0 v=0 w=0 x=0 y=0 z=0 level=0 font=5]"));
+    assert!(output.contains(": xxx1 special=\"a\\\"\\x01\" [h=0 "));
+}
+
+#[test]
+fn json_dump_is_deterministic_and_carries_state() {
+    let bytes = write_dvi_v2_text_page_v0(b"a").expect("writer should accept text");
+    let first = dump_dvi_v0(&bytes, DviDumpFormatV0::Json);
+    let second = dump_dvi_v0(&bytes, DviDumpFormatV0::Json);
+    assert_eq!(first, second);
+    let output = first.output;
+    assert!(output.starts_with(
+        "{\"commands\":[{\"offset\":0,\"opcode\":247,\"op\":\"pre\",\"id\":2,\"num\":25400000,\"den\":473628672,\"mag\":1000,\"comment\":\"\"},"
+    ));
+    assert!(output.contains(
+        "{\"offset\":15,\"opcode\":139,\"op\":\"bop\",\"counts\":[0,0,0,0,0,0,0,0,0,0],\"prev\":-1,\"page\":1,\"h\":0,\"v\":0,\"w\":0,\"x\":0,\"y\":0,\"z\":0,\"level\":0}"
+    ));
+    assert!(output.contains("\"op\":\"fntdef1\",\"font\":0,\"checksum\":0,\"scale\":0,\"design_size\":0,\"area\":\"\",\"name\":\"carreltex-v0\""));
+    assert!(output.ends_with("],\"error\":null}"));
+}
+
+#[test]
+fn dump_keeps_partial_listing_on_error() {
+    let bytes = TestDviV0::new(2).bop(1).raw(&[142]).eop().finish(&[]);
+    let text = dump_dvi_v0(&bytes, DviDumpFormatV0::Text);
+    let error = text.error.expect("unbalanced pop should fail");
+    assert_eq!(error.kind, DviReadErrorKindV0::StackUnderflow);
+    assert_eq!(error.offset, 64);
+    assert!(text
+        .output
+        .contains("19: bop counts=[1,0,0,0,0,0,0,0,0,0] prev=-1"));
+    assert!(text.output.ends_with("! dvi_stack_underflow at byte 64\n"));
+    let json = dump_dvi_v0(&bytes, DviDumpFormatV0::Json);
+    assert!(json
+        .output
+        .ends_with("],\"error\":{\"offset\":64,\"reason\":\"dvi_stack_underflow\"}}"));
+}
+
+#[test]
+fn dump_advances_scaled_tfm_chars_by_their_metric_widths() {
+    let font = fnt_def1_v0(1, 0x1234_5678, 655_360, b"cmr10");
+    let bytes = TestDviV0::new(2)
+        .raw(&font)
+        .bop(1)
+        .raw(&[172, 65, 66])
+        .eop()
+        .finish(&font);
+    let zero = dump_dvi_v0(&bytes, DviDumpFormatV0::Text);
+    let error = zero.error.expect("scaled font has no zero-width fallback");
+    assert_eq!(error.kind, DviReadErrorKindV0::CharWidthUnavailable);
+
+    let tfm = parse_tfm_v0(&build_tfm_v0(65, &[1 << 19, 1 << 18], &[])).expect("test tfm");
+    let tfms = vec![(b"cmr10".to_vec(), tfm)];
+    let dump =
+        dump_dvi_with_char_widths_v0(&bytes, tfm_char_width_v0(&tfms), DviDumpFormatV0::Text);
+    assert_eq!(dump.error, None);
+    let lines = dump.output.lines().collect::<Vec<_>>();
+    assert!(lines
+        .iter()
+        .any(|line| line
+            .ends_with(": setchar65 char=65 [h=327680 v=0 w=0 x=0 y=0 z=0 level=0 font=1]")));
+    assert!(lines
+        .iter()
+        .any(|line| line
+            .ends_with(": setchar66 char=66 [h=491520 v=0 w=0 x=0 y=0 z=0 level=0 font=1]")));
+}
@@ -0,0 +1,300 @@
+/// Largest image side, in pixels, accepted by `parse_image_v0`.
+pub const MAX_DVI_IMAGE_SIDE_PX_V0: u32 = 1 << 16;
+
+const PNG_SIGNATURE_V0: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// TeX points per inch, times 100.
+const POINTS_PER_100_INCHES_V0: u64 = 7227;
+const UNITY_SP_V0: u64 = 65_536;
+
+/// How the samples of an image are stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DviImageKindV0<'a> {
+    /// A non-interlaced PNG: its `IHDR` bit depth and color type, its `PLTE` entries
+    /// and its `IDAT` chunks joined, still zlib-compressed.
+    Png {
+        bit_depth: u8,
+        color_type: u8,
+        palette: &'a [u8],
+        data: Vec<u8>,
+    },
+    /// A JPEG of 8-bit gray or YCbCr samples, embedded as it is.
+    Jpeg { components: u8 },
+}
+
+/// A PNG or JPEG image as its headers describe it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviImageV0<'a> {
+    pub width: u32,
+    pub height: u32,
+    pub kind: DviImageKindV0<'a>,
+    /// TeX points per dot across and down, as `(numerator, denominator)` pairs.
+    dot_size: [(u64, u64); 2],
+}
+
+impl DviImageV0<'_> {
+    /// Size at the image's own resolution, or 72 dots per inch without one, as
+    /// graphicx under dvipdfmx sizes it; `None` past `\maxdimen`.
+    pub fn natural_size_sp_v0(&self) -> Option<(i32, i32)> {
+        let side = |pixels: u32, (numerator, denominator): (u64, u64)| {
+            let scaled = u64::from(pixels) * UNITY_SP_V0 * numerator;
+            let sp = (2 * scaled + denominator) / (2 * denominator);
+            i32::try_from(sp).ok().filter(|sp| *sp <= 0x3fff_ffff)
+        };
+        Some((
+            side(self.width, self.dot_size[0])?,
+            side(self.height, self.dot_size[1])?,
+        ))
+    }
+}
+
+fn be_u16_v0(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn be_u32_v0(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn side_ok_v0(pixels: u32) -> bool {
+    (1..=MAX_DVI_IMAGE_SIDE_PX_V0).contains(&pixels)
+}
+
+/// Dot size of `dots_per_inch`, the default resolution without one.
+fn inch_dot_size_v0(dots_per_inch: u64) -> (u64, u64) {
+    (POINTS_PER_100_INCHES_V0, 100 * dots_per_inch)
+}
+
+/// Reads a PNG or JPEG file; `None` for other formats, and for interlaced PNGs,
+/// JPEGs other than 8-bit gray or color, and damaged headers.
+pub fn parse_image_v0(bytes: &[u8]) -> Option<DviImageV0<'_>> {
+    if bytes.starts_with(PNG_SIGNATURE_V0) {
+        parse_png_v0(bytes)
+    } else if bytes.starts_with(&[0xff, 0xd8]) {
+        parse_jpeg_v0(bytes)
+    } else {
+        None
+    }
+}
+
+fn parse_png_v0(bytes: &[u8]) -> Option<DviImageV0<'_>> {
+    let mut at = PNG_SIGNATURE_V0.len();
+    let mut header = None::<(u32, u32, u8, u8)>;
+    let mut palette: &[u8] = &[];
+    let mut data = Vec::<u8>::new();
+    let default_dot_size = inch_dot_size_v0(72);
+    let mut dot_size = [default_dot_size; 2];
+    loop {
+        let length = be_u32_v0(bytes, at)? as usize;
+        let chunk_type = bytes.get(at + 4..at + 8)?;
+        let chunk = bytes.get(at + 8..(at + 8).checked_add(length)?)?;
+        at += 12 + length;
+        if header.is_none() && chunk_type != b"IHDR" {
+            return None;
+        }
+        match chunk_type {
+            b"IHDR" => {
+                if header.is_some() || length != 13 {
+                    return None;
+                }
+                let (width, height) = (be_u32_v0(chunk, 0)?, be_u32_v0(chunk, 4)?);
+                let (bit_depth, color_type) = (chunk[8], chunk[9]);
+                let depth_ok = match color_type {
+                    0 => matches!(bit_depth, 1 | 2 | 4 | 8 | 16),
+                    3 => matches!(bit_depth, 1 | 2 | 4 | 8),
+                    2 | 4 | 6 => matches!(bit_depth, 8 | 16),
+                    _ => false,
+                };
+                if !side_ok_v0(width) || !side_ok_v0(height) || !depth_ok || chunk[10..] != [0; 3] {
+                    return None;
+                }
+                header = Some((width, height, bit_depth, color_type));
+            }
+            b"PLTE" => {
+                if length == 0 || !length.is_multiple_of(3) || length > 3 * 256 {
+                    return None;
+                }
+                palette = chunk;
+            }
+            b"pHYs" => {
+                let (x, y) = (be_u32_v0(chunk, 0)?, be_u32_v0(chunk, 4)?);
+                if chunk.get(8) == Some(&1) && x > 0 && y > 0 {
+                    dot_size = [x, y].map(|per_meter| {
+                        (100 * POINTS_PER_100_INCHES_V0, 254 * u64::from(per_meter))
+                    });
+                }
+            }
+            b"IDAT" => data.extend_from_slice(chunk),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+    let (width, height, bit_depth, color_type) = header?;
+    let palette_fits = palette.len() / 3 <= 1 << bit_depth;
+    if data.is_empty() || (color_type == 3 && (palette.is_empty() || !palette_fits)) {
+        return None;
+    }
+    Some(DviImageV0 {
+        width,
+        height,
+        kind: DviImageKindV0::Png {
+            bit_depth,
+            color_type,
+            palette,
+            data,
+        },
+        dot_size,
+    })
+}
+
+fn parse_jpeg_v0(bytes: &[u8]) -> Option<DviImageV0<'_>> {
+    let mut at = 2usize;
+    let mut dot_size = [inch_dot_size_v0(72); 2];
+    loop {
+        if *bytes.get(at)? != 0xff {
+            return None;
+        }
+        let marker = *bytes.get(at + 1)?;
+        at += 2;
+        match marker {
+            0xff => at -= 1,
+            0x01 | 0xd0..=0xd7 => {}
+            0xd8..=0xda => return None,
+            _ => {
+                let length = usize::from(be_u16_v0(bytes, at)?);
+                let segment = bytes.get(at + 2..(at + length).max(at + 2))?;
+                if length < 2 || segment.len() != length - 2 {
+                    return None;
+                }
+                at += length;
+                match marker {
+                    0xe0 if segment.len() >= 12 && segment.starts_with(b"JFIF\0") => {
+                        let (x, y) = (be_u16_v0(segment, 8)?, be_u16_v0(segment, 10)?);
+                        let unit = match segment[7] {
+                            1 => 100,
+                            2 => 254,
+                            _ => continue,
+                        };
+                        if x > 0 && y > 0 {
+                            dot_size = [x, y].map(|density| {
+                                (POINTS_PER_100_INCHES_V0, unit * u64::from(density))
+                            });
+                        }
+                    }
+                    0xc0..=0xcf if !matches!(marker, 0xc4 | 0xc8 | 0xcc) => {
+                        let (precision, components) = (*segment.first()?, *segment.get(5)?);
+                        let height = u32::from(be_u16_v0(segment, 1)?);
+                        let width = u32::from(be_u16_v0(segment, 3)?);
+                        if precision != 8
+                            || !matches!(components, 1 | 3)
+                            || !side_ok_v0(width)
+                            || !side_ok_v0(height)
+                        {
+                            return None;
+                        }
+                        return Some(DviImageV0 {
+                            width,
+                            height,
+                            kind: DviImageKindV0::Jpeg { components },
+                            dot_size,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// `\special` placing image `name` with its lower left corner at the current
+/// point, `width_sp` across and `height_sp` up, in the form graphicx's dvipdfmx
+/// driver writes: `pdf:image width 72.26999pt height 36.135pt (plot.png)`.
+pub fn image_special_v0(width_sp: i32, height_sp: i32, name: &[u8]) -> Vec<u8> {
+    let mut special = b"pdf:image width ".to_vec();
+    special.extend_from_slice(&print_scaled_v0(width_sp));
+    special.extend_from_slice(b"pt height ");
+    special.extend_from_slice(&print_scaled_v0(height_sp));
+    special.extend_from_slice(b"pt (");
+    special.extend_from_slice(name);
+    special.push(b')');
+    special
+}
+
+/// A `pdf:image` special as `image_special_v0` writes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviImageSpecialV0<'a> {
+    pub width_sp: i32,
+    pub height_sp: i32,
+    pub name: &'a [u8],
+}
+
+/// Reads back what `image_special_v0` writes; `None` for any other payload.
+pub fn parse_image_special_v0(payload: &[u8]) -> Option<DviImageSpecialV0<'_>> {
+    let rest = payload.strip_prefix(b"pdf:image width ")?;
+    let (width, rest) = split_once_v0(rest, b"pt height ")?;
+    let (height, rest) = split_once_v0(rest, b"pt (")?;
+    let name = rest.strip_suffix(b")")?;
+    if name.is_empty() || name.iter().any(|byte| matches!(byte, b'(' | b')' | b'\\')) {
+        return None;
+    }
+    Some(DviImageSpecialV0 {
+        width_sp: scan_scaled_v0(width)?,
+        height_sp: scan_scaled_v0(height)?,
+        name,
+    })
+}
+
+fn split_once_v0<'a>(bytes: &'a [u8], separator: &[u8]) -> Option<(&'a [u8], &'a [u8])> {
+    let at = bytes
+        .windows(separator.len())
+        .position(|window| window == separator)?;
+    Some((&bytes[..at], &bytes[at + separator.len()..]))
+}
+
+/// TeX's `print_scaled`: the shortest decimal that reads back as `sp`.
+fn print_scaled_v0(sp: i32) -> Vec<u8> {
+    let unity = UNITY_SP_V0 as i64;
+    let mut text = Vec::<u8>::new();
+    let mut s = i64::from(sp);
+    if s < 0 {
+        text.push(b'-');
+        s = -s;
+    }
+    text.extend_from_slice((s / unity).to_string().as_bytes());
+    text.push(b'.');
+    s = 10 * (s % unity) + 5;
+    let mut delta = 10i64;
+    loop {
+        if delta > unity {
+            s += 0x8000 - 50_000;
+        }
+        text.push(b'0' + (s / unity) as u8);
+        s = 10 * (s % unity);
+        delta *= 10;
+        if s <= delta {
+            break;
+        }
+    }
+    text
+}
+
+/// A non-negative decimal in points, rounded to scaled points as TeX's
+/// `round_decimals` does.
+fn scan_scaled_v0(text: &[u8]) -> Option<i32> {
+    let (integer, fraction) = split_once_v0(text, b".").unwrap_or((text, b""));
+    if integer.is_empty()
+        || integer.len() > 5
+        || fraction.len() > 17
+        || !integer.iter().chain(fraction).all(u8::is_ascii_digit)
+    {
+        return None;
+    }
+    let integer = integer
+        .iter()
+        .fold(0i64, |value, digit| value * 10 + i64::from(digit - b'0'));
+    let mut fraction_sp = 0i64;
+    for digit in fraction.iter().rev() {
+        fraction_sp = (fraction_sp + i64::from(digit - b'0') * 0x2_0000) / 10;
+    }
+    let sp = integer * UNITY_SP_V0 as i64 + (fraction_sp + 1) / 2;
+    i32::try_from(sp).ok().filter(|sp| *sp <= 0x3fff_ffff)
+}
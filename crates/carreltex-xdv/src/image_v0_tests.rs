@@ -0,0 +1,148 @@
+use super::{
+    image_special_v0, parse_image_special_v0, parse_image_v0, DviImageKindV0, DviImageSpecialV0,
+};
+
+fn chunk_v0(out: &mut Vec<u8>, chunk_type: &[u8], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(chunk_type);
+    out.extend_from_slice(data);
+    out.extend_from_slice(&[0; 4]);
+}
+
+fn png_v0(
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    color_type: u8,
+    extra: &[(&[u8], &[u8])],
+) -> Vec<u8> {
+    let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+    let mut header = width.to_be_bytes().to_vec();
+    header.extend_from_slice(&height.to_be_bytes());
+    header.extend_from_slice(&[bit_depth, color_type, 0, 0, 0]);
+    chunk_v0(&mut out, b"IHDR", &header);
+    for (chunk_type, data) in extra {
+        chunk_v0(&mut out, chunk_type, data);
+    }
+    chunk_v0(&mut out, b"IDAT", b"zl");
+    chunk_v0(&mut out, b"IDAT", b"ib");
+    chunk_v0(&mut out, b"IEND", b"");
+    out
+}
+
+fn jpeg_v0(app0: Option<(u8, u16, u16)>, width: u16, height: u16, components: u8) -> Vec<u8> {
+    let mut out = vec![0xff, 0xd8];
+    if let Some((unit, x, y)) = app0 {
+        out.extend_from_slice(&[0xff, 0xe0, 0, 16]);
+        out.extend_from_slice(b"JFIF\0\x01\x02");
+        out.push(unit);
+        out.extend_from_slice(&x.to_be_bytes());
+        out.extend_from_slice(&y.to_be_bytes());
+        out.extend_from_slice(&[0, 0]);
+    }
+    out.extend_from_slice(&[0xff, 0xdb, 0, 3, 0]);
+    out.extend_from_slice(&[0xff, 0xc2, 0, 8 + 3 * components as u16 as u8, 8]);
+    out.extend_from_slice(&height.to_be_bytes());
+    out.extend_from_slice(&width.to_be_bytes());
+    out.push(components);
+    out.extend(std::iter::repeat_n([1, 0x11, 0], components as usize).flatten());
+    out.extend_from_slice(&[0xff, 0xda]);
+    out
+}
+
+#[test]
+fn png_headers_give_samples_and_natural_size() {
+    let bytes = png_v0(144, 72, 8, 6, &[]);
+    let image = parse_image_v0(&bytes).expect("png should parse");
+    assert_eq!((image.width, image.height), (144, 72));
+    assert_eq!(
+        image.kind,
+        DviImageKindV0::Png {
+            bit_depth: 8,
+            color_type: 6,
+            palette: &[],
+            data: b"zlib".to_vec(),
+        }
+    );
+    // 72 dots per inch: a dot is a big point.
+    assert_eq!(image.natural_size_sp_v0(), Some((9_472_573, 4_736_287)));
+
+    // 11811 dots per meter is 300 per inch, near enough.
+    let mut phys = 11_811u32.to_be_bytes().repeat(2);
+    phys.push(1);
+    let palette = [0u8, 0, 0, 255, 255, 255];
+    let bytes = png_v0(300, 600, 1, 3, &[(b"pHYs", &phys), (b"PLTE", &palette)]);
+    let image = parse_image_v0(&bytes).expect("png should parse");
+    assert_eq!(image.natural_size_sp_v0(), Some((4_736_296, 9_472_592)));
+}
+
+#[test]
+fn jpeg_headers_give_components_and_natural_size() {
+    let bytes = jpeg_v0(None, 72, 36, 3);
+    let image = parse_image_v0(&bytes).expect("jpeg should parse");
+    assert_eq!((image.width, image.height), (72, 36));
+    assert_eq!(image.kind, DviImageKindV0::Jpeg { components: 3 });
+    assert_eq!(image.natural_size_sp_v0(), Some((4_736_287, 2_368_143)));
+    for (app0, side, size_sp) in [
+        ((1, 144, 144), 72, 2_368_143),
+        ((2, 100, 100), 254, 4_736_287),
+    ] {
+        let bytes = jpeg_v0(Some(app0), side, side, 1);
+        let image = parse_image_v0(&bytes).expect("jpeg should parse");
+        assert_eq!(image.natural_size_sp_v0(), Some((size_sp, size_sp)));
+    }
+}
+
+#[test]
+fn unrecognized_images_are_rejected() {
+    let mut interlaced = png_v0(1, 1, 8, 2, &[]);
+    interlaced[28] = 1;
+    let mut truncated = png_v0(1, 1, 8, 2, &[]);
+    truncated.truncate(40);
+    for bytes in [
+        b"GIF89a".to_vec(),
+        Vec::new(),
+        interlaced,
+        truncated,
+        png_v0(0, 1, 8, 2, &[]),
+        png_v0(1, 1, 4, 2, &[]),
+        png_v0(1, 1, 8, 3, &[]),
+        png_v0(1 << 17, 1, 8, 2, &[]),
+        jpeg_v0(None, 1, 1, 4),
+        jpeg_v0(None, 1, 0, 3),
+        vec![0xff, 0xd8, 0xff, 0xda],
+    ] {
+        assert!(parse_image_v0(&bytes).is_none(), "{bytes:?}");
+    }
+}
+
+#[test]
+fn image_specials_read_back_exactly() {
+    let special = image_special_v0(4_736_286, 65_536 * 3 / 2, b"fig/plot.png");
+    assert_eq!(
+        special,
+        b"pdf:image width 72.26999pt height 1.5pt (fig/plot.png)"
+    );
+    assert_eq!(
+        parse_image_special_v0(&special),
+        Some(DviImageSpecialV0 {
+            width_sp: 4_736_286,
+            height_sp: 98_304,
+            name: b"fig/plot.png",
+        })
+    );
+    for sp in [0, 1, 7, 65_535, 123_456_789, 0x3fff_ffff] {
+        let special = image_special_v0(sp, sp, b"a.jpg");
+        let parsed = parse_image_special_v0(&special).expect("special should parse");
+        assert_eq!((parsed.width_sp, parsed.height_sp), (sp, sp));
+    }
+    for payload in [
+        b"color push gray 0".as_slice(),
+        b"pdf:image width 1pt height 1pt ()",
+        b"pdf:image width 1pt height 1pt (a)b)",
+        b"pdf:image width -1pt height 1pt (a)",
+        b"pdf:image width 1pt height 99999pt (a)",
+    ] {
+        assert!(parse_image_special_v0(payload).is_none());
+    }
+}
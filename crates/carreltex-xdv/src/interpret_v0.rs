@@ -0,0 +1,701 @@
+use crate::{
+    read_i24_be, read_i32_be, read_u16_be, read_u32_be, read_u8, DVI_BOP, DVI_EOP, DVI_ID_V2,
+    DVI_POST, DVI_POSTPOST, DVI_PRE, DVI_TRAILER_BYTE,
+};
+
+pub const XDV_ID_V0: u8 = 7;
+pub const MAX_DVI_READ_BYTES_V0: usize = 32 * 1024 * 1024;
+pub const MAX_DVI_STACK_DEPTH_V0: usize = 4096;
+
+const DVI_SET1: u8 = 128;
+const DVI_SET_RULE: u8 = 132;
+const DVI_PUT1: u8 = 133;
+const DVI_PUT_RULE: u8 = 137;
+const DVI_NOP: u8 = 138;
+const DVI_PUSH: u8 = 141;
+const DVI_POP: u8 = 142;
+const DVI_RIGHT1: u8 = 143;
+const DVI_W0: u8 = 147;
+const DVI_X0: u8 = 152;
+const DVI_DOWN1: u8 = 157;
+const DVI_Y0: u8 = 161;
+const DVI_Z0: u8 = 166;
+const DVI_FNT_NUM_63: u8 = 234;
+const DVI_FNT1: u8 = 235;
+const DVI_XXX1: u8 = 239;
+const DVI_FNT_DEF_FIRST: u8 = 243;
+const DVI_FNT_DEF_LAST: u8 = 246;
+const XDV_NATIVE_FONT_DEF: u8 = 252;
+const XDV_SET_GLYPHS: u8 = 253;
+const XDV_SET_TEXT_AND_GLYPHS: u8 = 254;
+const XDV_FLAG_COLORED: u16 = 0x0200;
+const XDV_FLAG_EXTEND: u16 = 0x1000;
+const XDV_FLAG_SLANT: u16 = 0x2000;
+const XDV_FLAG_EMBOLDEN: u16 = 0x4000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DviReadErrorKindV0 {
+    TooLarge,
+    Truncated,
+    PreambleInvalid,
+    IdUnsupported,
+    OpcodeUndefined(u8),
+    OpcodeOutsidePage(u8),
+    BopChainInvalid,
+    StackUnderflow,
+    StackOverflow,
+    StackNotEmptyAtEop,
+    FontUndefined,
+    FontRedefined,
+    FontNotSelected,
+    CharWidthUnavailable,
+    PositionOverflow,
+    SpecialLengthInvalid,
+    PostambleInvalid,
+    TrailerInvalid,
+}
+
+impl DviReadErrorKindV0 {
+    pub fn reason_v0(&self) -> &'static str {
+        match self {
+            DviReadErrorKindV0::TooLarge => "dvi_too_large",
+            DviReadErrorKindV0::Truncated => "dvi_truncated",
+            DviReadErrorKindV0::PreambleInvalid => "dvi_preamble_invalid",
+            DviReadErrorKindV0::IdUnsupported => "dvi_id_unsupported",
+            DviReadErrorKindV0::OpcodeUndefined(_) => "dvi_opcode_undefined",
+            DviReadErrorKindV0::OpcodeOutsidePage(_) => "dvi_opcode_outside_page",
+            DviReadErrorKindV0::BopChainInvalid => "dvi_bop_chain_invalid",
+            DviReadErrorKindV0::StackUnderflow => "dvi_stack_underflow",
+            DviReadErrorKindV0::StackOverflow => "dvi_stack_overflow",
+            DviReadErrorKindV0::StackNotEmptyAtEop => "dvi_stack_not_empty_at_eop",
+            DviReadErrorKindV0::FontUndefined => "dvi_font_undefined",
+            DviReadErrorKindV0::FontRedefined => "dvi_font_redefined",
+            DviReadErrorKindV0::FontNotSelected => "dvi_font_not_selected",
+            DviReadErrorKindV0::CharWidthUnavailable => "dvi_char_width_unavailable",
+            DviReadErrorKindV0::PositionOverflow => "dvi_position_overflow",
+            DviReadErrorKindV0::SpecialLengthInvalid => "dvi_special_length_invalid",
+            DviReadErrorKindV0::PostambleInvalid => "dvi_postamble_invalid",
+            DviReadErrorKindV0::TrailerInvalid => "dvi_trailer_invalid",
+        }
+    }
+}
+
+/// Read failure with the byte offset of the offending command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviReadErrorV0 {
+    pub offset: usize,
+    pub kind: DviReadErrorKindV0,
+}
+
+impl DviReadErrorV0 {
+    pub fn reason_v0(&self) -> &'static str {
+        self.kind.reason_v0()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviTfmFontDefV0 {
+    pub checksum: u32,
+    pub scale: i32,
+    pub design_size: i32,
+    pub area: Vec<u8>,
+    pub name: Vec<u8>,
+}
+
+/// XDV `define_native_font` (id 7 layout); optional fields follow `flags`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XdvNativeFontDefV0 {
+    pub size: i32,
+    pub flags: u16,
+    pub filename: Vec<u8>,
+    pub index: u32,
+    pub rgba: Option<u32>,
+    pub extend: Option<i32>,
+    pub slant: Option<i32>,
+    pub embolden: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DviFontKindV0 {
+    Tfm(DviTfmFontDefV0),
+    Native(XdvNativeFontDefV0),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviFontDefV0 {
+    pub font_num: i32,
+    pub kind: DviFontKindV0,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DviRegistersV0 {
+    pub h: i32,
+    pub v: i32,
+    pub w: i32,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviPreambleV0 {
+    pub id: u8,
+    pub num: u32,
+    pub den: u32,
+    pub mag: u32,
+    pub comment: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviPostambleV0 {
+    pub last_bop: i32,
+    pub num: u32,
+    pub den: u32,
+    pub mag: u32,
+    pub max_height_depth: i32,
+    pub max_width: i32,
+    pub max_stack_depth: u16,
+    pub total_pages: u16,
+}
+
+/// One decoded DVI/XDV command. Positions in `SetGlyphs` are relative to `h`/`v`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum DviCommandV0 {
+    Pre(DviPreambleV0),
+    Bop {
+        counts: [i32; 10],
+        prev_bop: i32,
+    },
+    Eop,
+    SetChar(u32),
+    PutChar(u32),
+    SetRule {
+        height: i32,
+        width: i32,
+    },
+    PutRule {
+        height: i32,
+        width: i32,
+    },
+    Nop,
+    Push,
+    Pop,
+    Right(i32),
+    W(Option<i32>),
+    X(Option<i32>),
+    Down(i32),
+    Y(Option<i32>),
+    Z(Option<i32>),
+    FntNum(i32),
+    Xxx(Vec<u8>),
+    FntDef(DviFontDefV0),
+    SetGlyphs {
+        text: Vec<u16>,
+        width: i32,
+        glyphs: Vec<(i32, i32, u16)>,
+    },
+    Post(DviPostambleV0),
+    PostPost {
+        post_offset: u32,
+        id: u8,
+    },
+}
+
+/// Interpreter state after a command; `font` is the currently selected font number and
+/// `char_width` the resolved width of a `set_char`/`put_char` command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DviStateV0 {
+    pub(crate) registers: DviRegistersV0,
+    pub(crate) stack_depth: usize,
+    pub(crate) font: Option<i32>,
+    pub(crate) page_index: Option<usize>,
+    pub(crate) char_width: Option<i32>,
+}
+
+fn error_at_v0(offset: usize, kind: DviReadErrorKindV0) -> DviReadErrorV0 {
+    DviReadErrorV0 { offset, kind }
+}
+
+fn read_signed_v0(bytes: &[u8], index: &mut usize, len: u8) -> Option<i32> {
+    match len {
+        1 => read_u8(bytes, index).map(|value| value as i8 as i32),
+        2 => read_u16_be(bytes, index).map(|value| value as i16 as i32),
+        3 => read_i24_be(bytes, index),
+        _ => read_i32_be(bytes, index),
+    }
+}
+
+fn read_unsigned_v0(bytes: &[u8], index: &mut usize, len: u8) -> Option<u32> {
+    match len {
+        1 => read_u8(bytes, index).map(|value| value as u32),
+        2 => read_u16_be(bytes, index).map(|value| value as u32),
+        3 => read_i24_be(bytes, index).map(|value| (value as u32) & 0x00ff_ffff),
+        _ => read_u32_be(bytes, index),
+    }
+}
+
+fn read_bytes_v0(bytes: &[u8], index: &mut usize, len: usize) -> Option<Vec<u8>> {
+    let end = index.checked_add(len)?;
+    let slice = bytes.get(*index..end)?;
+    *index = end;
+    Some(slice.to_vec())
+}
+
+fn read_font_def_v0(bytes: &[u8], index: &mut usize, len: u8) -> Option<DviFontDefV0> {
+    let font_num = if len == 4 {
+        read_i32_be(bytes, index)?
+    } else {
+        read_unsigned_v0(bytes, index, len)? as i32
+    };
+    let checksum = read_u32_be(bytes, index)?;
+    let scale = read_i32_be(bytes, index)?;
+    let design_size = read_i32_be(bytes, index)?;
+    let area_len = read_u8(bytes, index)? as usize;
+    let name_len = read_u8(bytes, index)? as usize;
+    let area = read_bytes_v0(bytes, index, area_len)?;
+    let name = read_bytes_v0(bytes, index, name_len)?;
+    Some(DviFontDefV0 {
+        font_num,
+        kind: DviFontKindV0::Tfm(DviTfmFontDefV0 {
+            checksum,
+            scale,
+            design_size,
+            area,
+            name,
+        }),
+    })
+}
+
+fn read_native_font_def_v0(bytes: &[u8], index: &mut usize) -> Option<DviFontDefV0> {
+    let font_num = read_i32_be(bytes, index)?;
+    let size = read_i32_be(bytes, index)?;
+    let flags = read_u16_be(bytes, index)?;
+    let filename_len = read_u8(bytes, index)? as usize;
+    let filename = read_bytes_v0(bytes, index, filename_len)?;
+    let font_index = read_u32_be(bytes, index)?;
+    let mut optional = |flag: u16| -> Option<Option<u32>> {
+        if flags & flag == 0 {
+            return Some(None);
+        }
+        read_u32_be(bytes, index).map(Some)
+    };
+    let rgba = optional(XDV_FLAG_COLORED)?;
+    let extend = optional(XDV_FLAG_EXTEND)?.map(|value| value as i32);
+    let slant = optional(XDV_FLAG_SLANT)?.map(|value| value as i32);
+    let embolden = optional(XDV_FLAG_EMBOLDEN)?.map(|value| value as i32);
+    Some(DviFontDefV0 {
+        font_num,
+        kind: DviFontKindV0::Native(XdvNativeFontDefV0 {
+            size,
+            flags,
+            filename,
+            index: font_index,
+            rgba,
+            extend,
+            slant,
+            embolden,
+        }),
+    })
+}
+
+fn read_glyph_run_v0(bytes: &[u8], index: &mut usize, with_text: bool) -> Option<DviCommandV0> {
+    let mut text = Vec::<u16>::new();
+    if with_text {
+        let text_len = read_u16_be(bytes, index)? as usize;
+        for _ in 0..text_len {
+            text.push(read_u16_be(bytes, index)?);
+        }
+    }
+    let width = read_i32_be(bytes, index)?;
+    let count = read_u16_be(bytes, index)? as usize;
+    let mut positions = Vec::<(i32, i32)>::with_capacity(count);
+    for _ in 0..count {
+        positions.push((read_i32_be(bytes, index)?, read_i32_be(bytes, index)?));
+    }
+    let mut glyphs = Vec::<(i32, i32, u16)>::with_capacity(count);
+    for (x, y) in positions {
+        glyphs.push((x, y, read_u16_be(bytes, index)?));
+    }
+    Some(DviCommandV0::SetGlyphs {
+        text,
+        width,
+        glyphs,
+    })
+}
+
+/// Decode the command at `*index`; `id` gates the XDV-only opcodes.
+fn decode_command_v0(
+    bytes: &[u8],
+    index: &mut usize,
+    id: u8,
+) -> Result<DviCommandV0, DviReadErrorKindV0> {
+    let truncated = DviReadErrorKindV0::Truncated;
+    let opcode = read_u8(bytes, index).ok_or(truncated)?;
+    let command = match opcode {
+        0..=127 => DviCommandV0::SetChar(opcode as u32),
+        128..=131 => DviCommandV0::SetChar(
+            read_unsigned_v0(bytes, index, opcode - DVI_SET1 + 1).ok_or(truncated)?,
+        ),
+        DVI_SET_RULE | DVI_PUT_RULE => {
+            let height = read_i32_be(bytes, index).ok_or(truncated)?;
+            let width = read_i32_be(bytes, index).ok_or(truncated)?;
+            if opcode == DVI_SET_RULE {
+                DviCommandV0::SetRule { height, width }
+            } else {
+                DviCommandV0::PutRule { height, width }
+            }
+        }
+        133..=136 => DviCommandV0::PutChar(
+            read_unsigned_v0(bytes, index, opcode - DVI_PUT1 + 1).ok_or(truncated)?,
+        ),
+        DVI_NOP => DviCommandV0::Nop,
+        DVI_BOP => {
+            let mut counts = [0i32; 10];
+            for count in counts.iter_mut() {
+                *count = read_i32_be(bytes, index).ok_or(truncated)?;
+            }
+            let prev_bop = read_i32_be(bytes, index).ok_or(truncated)?;
+            DviCommandV0::Bop { counts, prev_bop }
+        }
+        DVI_EOP => DviCommandV0::Eop,
+        DVI_PUSH => DviCommandV0::Push,
+        DVI_POP => DviCommandV0::Pop,
+        143..=146 => DviCommandV0::Right(
+            read_signed_v0(bytes, index, opcode - DVI_RIGHT1 + 1).ok_or(truncated)?,
+        ),
+        DVI_W0 => DviCommandV0::W(None),
+        148..=151 => DviCommandV0::W(Some(
+            read_signed_v0(bytes, index, opcode - DVI_W0).ok_or(truncated)?,
+        )),
+        DVI_X0 => DviCommandV0::X(None),
+        153..=156 => DviCommandV0::X(Some(
+            read_signed_v0(bytes, index, opcode - DVI_X0).ok_or(truncated)?,
+        )),
+        157..=160 => DviCommandV0::Down(
+            read_signed_v0(bytes, index, opcode - DVI_DOWN1 + 1).ok_or(truncated)?,
+        ),
+        DVI_Y0 => DviCommandV0::Y(None),
+        162..=165 => DviCommandV0::Y(Some(
+            read_signed_v0(bytes, index, opcode - DVI_Y0).ok_or(truncated)?,
+        )),
+        DVI_Z0 => DviCommandV0::Z(None),
+        167..=170 => DviCommandV0::Z(Some(
+            read_signed_v0(bytes, index, opcode - DVI_Z0).ok_or(truncated)?,
+        )),
+        171..=DVI_FNT_NUM_63 => DviCommandV0::FntNum((opcode - 171) as i32),
+        235..=238 => {
+            let len = opcode - DVI_FNT1 + 1;
+            let font_num = if len == 4 {
+                read_i32_be(bytes, index).ok_or(truncated)?
+            } else {
+                read_unsigned_v0(bytes, index, len).ok_or(truncated)? as i32
+            };
+            DviCommandV0::FntNum(font_num)
+        }
+        239..=242 => {
+            let len = opcode - DVI_XXX1 + 1;
+            let special_len = if len == 4 {
+                usize::try_from(read_i32_be(bytes, index).ok_or(truncated)?)
+                    .map_err(|_| DviReadErrorKindV0::SpecialLengthInvalid)?
+            } else {
+                read_unsigned_v0(bytes, index, len).ok_or(truncated)? as usize
+            };
+            DviCommandV0::Xxx(read_bytes_v0(bytes, index, special_len).ok_or(truncated)?)
+        }
+        DVI_FNT_DEF_FIRST..=DVI_FNT_DEF_LAST => DviCommandV0::FntDef(
+            read_font_def_v0(bytes, index, opcode - DVI_FNT_DEF_FIRST + 1).ok_or(truncated)?,
+        ),
+        DVI_PRE => {
+            let id = read_u8(bytes, index).ok_or(truncated)?;
+            let num = read_u32_be(bytes, index).ok_or(truncated)?;
+            let den = read_u32_be(bytes, index).ok_or(truncated)?;
+            let mag = read_u32_be(bytes, index).ok_or(truncated)?;
+            let comment_len = read_u8(bytes, index).ok_or(truncated)? as usize;
+            let comment = read_bytes_v0(bytes, index, comment_len).ok_or(truncated)?;
+            DviCommandV0::Pre(DviPreambleV0 {
+                id,
+                num,
+                den,
+                mag,
+                comment,
+            })
+        }
+        DVI_POST => DviCommandV0::Post(DviPostambleV0 {
+            last_bop: read_i32_be(bytes, index).ok_or(truncated)?,
+            num: read_u32_be(bytes, index).ok_or(truncated)?,
+            den: read_u32_be(bytes, index).ok_or(truncated)?,
+            mag: read_u32_be(bytes, index).ok_or(truncated)?,
+            max_height_depth: read_i32_be(bytes, index).ok_or(truncated)?,
+            max_width: read_i32_be(bytes, index).ok_or(truncated)?,
+            max_stack_depth: read_u16_be(bytes, index).ok_or(truncated)?,
+            total_pages: read_u16_be(bytes, index).ok_or(truncated)?,
+        }),
+        DVI_POSTPOST => DviCommandV0::PostPost {
+            post_offset: read_u32_be(bytes, index).ok_or(truncated)?,
+            id: read_u8(bytes, index).ok_or(truncated)?,
+        },
+        XDV_NATIVE_FONT_DEF if id == XDV_ID_V0 => {
+            DviCommandV0::FntDef(read_native_font_def_v0(bytes, index).ok_or(truncated)?)
+        }
+        XDV_SET_GLYPHS if id == XDV_ID_V0 => {
+            read_glyph_run_v0(bytes, index, false).ok_or(truncated)?
+        }
+        XDV_SET_TEXT_AND_GLYPHS if id == XDV_ID_V0 => {
+            read_glyph_run_v0(bytes, index, true).ok_or(truncated)?
+        }
+        _ => return Err(DviReadErrorKindV0::OpcodeUndefined(opcode)),
+    };
+    Ok(command)
+}
+
+fn add_position_v0(base: i32, delta: i32) -> Result<i32, DviReadErrorKindV0> {
+    base.checked_add(delta)
+        .ok_or(DviReadErrorKindV0::PositionOverflow)
+}
+
+fn is_page_content_v0(command: &DviCommandV0) -> bool {
+    !matches!(
+        command,
+        DviCommandV0::Pre(_)
+            | DviCommandV0::Bop { .. }
+            | DviCommandV0::Post(_)
+            | DviCommandV0::PostPost { .. }
+            | DviCommandV0::Nop
+            | DviCommandV0::FntDef(_)
+    )
+}
+
+fn register_fetch_or_set_v0(register: &mut i32, value: Option<i32>) -> i32 {
+    if let Some(value) = value {
+        *register = value;
+    }
+    *register
+}
+
+fn check_trailer_v0(bytes: &[u8], index: usize) -> Result<(), DviReadErrorV0> {
+    let trailer = &bytes[index..];
+    if trailer.len() < 4
+        || !bytes.len().is_multiple_of(4)
+        || trailer.iter().any(|byte| *byte != DVI_TRAILER_BYTE)
+    {
+        return Err(error_at_v0(index, DviReadErrorKindV0::TrailerInvalid));
+    }
+    Ok(())
+}
+
+/// Decode and execute every command of a DVI (id 2) or XDV (id 7) file in order,
+/// calling `visit(offset, command, state_after)` for each one.
+///
+/// `char_width` supplies `set_char`/`put_char` widths for TFM fonts; fonts,
+/// registers, the push/pop stack, the bop back-pointer chain and the postamble are
+/// checked as they are read and any inconsistency fails closed.
+pub(crate) fn walk_dvi_v0<W, V>(
+    bytes: &[u8],
+    mut char_width: W,
+    mut visit: V,
+) -> Result<(), DviReadErrorV0>
+where
+    W: FnMut(&DviFontDefV0, u32) -> Option<i32>,
+    V: FnMut(usize, &DviCommandV0, &DviStateV0),
+{
+    if bytes.len() > MAX_DVI_READ_BYTES_V0 {
+        return Err(error_at_v0(0, DviReadErrorKindV0::TooLarge));
+    }
+    let mut index = 0usize;
+    let preamble = match decode_command_v0(bytes, &mut index, DVI_ID_V2) {
+        Ok(DviCommandV0::Pre(preamble)) => preamble,
+        Ok(_) => return Err(error_at_v0(0, DviReadErrorKindV0::PreambleInvalid)),
+        Err(kind) => return Err(error_at_v0(0, kind)),
+    };
+    if preamble.id != DVI_ID_V2 && preamble.id != XDV_ID_V0 {
+        return Err(error_at_v0(0, DviReadErrorKindV0::IdUnsupported));
+    }
+    if preamble.num == 0 || preamble.den == 0 || preamble.mag == 0 {
+        return Err(error_at_v0(0, DviReadErrorKindV0::PreambleInvalid));
+    }
+    let id = preamble.id;
+    let mut state = DviStateV0 {
+        registers: DviRegistersV0::default(),
+        stack_depth: 0,
+        font: None,
+        page_index: None,
+        char_width: None,
+    };
+    visit(0, &DviCommandV0::Pre(preamble.clone()), &state);
+
+    let mut fonts = Vec::<DviFontDefV0>::new();
+    let mut stack = Vec::<DviRegistersV0>::new();
+    let mut max_stack_depth = 0usize;
+    let mut page_count = 0usize;
+    let mut last_bop = -1i32;
+    let mut post_offset = None::<usize>;
+    loop {
+        let offset = index;
+        let command =
+            decode_command_v0(bytes, &mut index, id).map_err(|kind| error_at_v0(offset, kind))?;
+        let fail = |kind: DviReadErrorKindV0| error_at_v0(offset, kind);
+        let in_page = state.page_index.is_some();
+        if is_page_content_v0(&command) && !in_page && post_offset.is_none() {
+            return Err(fail(DviReadErrorKindV0::OpcodeOutsidePage(bytes[offset])));
+        }
+        if post_offset.is_some()
+            && !matches!(
+                command,
+                DviCommandV0::FntDef(_) | DviCommandV0::Nop | DviCommandV0::PostPost { .. }
+            )
+        {
+            return Err(fail(DviReadErrorKindV0::PostambleInvalid));
+        }
+        let mut char_advance = None::<i32>;
+        let registers = &mut state.registers;
+        match &command {
+            DviCommandV0::Pre(_) => return Err(fail(DviReadErrorKindV0::PreambleInvalid)),
+            DviCommandV0::Bop { prev_bop, .. } => {
+                if in_page {
+                    return Err(fail(DviReadErrorKindV0::OpcodeOutsidePage(DVI_BOP)));
+                }
+                if *prev_bop != last_bop {
+                    return Err(fail(DviReadErrorKindV0::BopChainInvalid));
+                }
+                last_bop = i32::try_from(offset).map_err(|_| fail(DviReadErrorKindV0::TooLarge))?;
+                state = DviStateV0 {
+                    registers: DviRegistersV0::default(),
+                    stack_depth: 0,
+                    font: None,
+                    page_index: Some(page_count),
+                    char_width: None,
+                };
+                page_count += 1;
+            }
+            DviCommandV0::Eop => {
+                if !stack.is_empty() {
+                    return Err(fail(DviReadErrorKindV0::StackNotEmptyAtEop));
+                }
+            }
+            DviCommandV0::SetChar(code) | DviCommandV0::PutChar(code) => {
+                let font_num = state
+                    .font
+                    .ok_or(fail(DviReadErrorKindV0::FontNotSelected))?;
+                let font = fonts
+                    .iter()
+                    .find(|font| font.font_num == font_num)
+                    .ok_or(fail(DviReadErrorKindV0::FontUndefined))?;
+                let width = char_width(font, *code)
+                    .ok_or(fail(DviReadErrorKindV0::CharWidthUnavailable))?;
+                if matches!(command, DviCommandV0::SetChar(_)) {
+                    registers.h = add_position_v0(registers.h, width).map_err(fail)?;
+                }
+                char_advance = Some(width);
+            }
+            DviCommandV0::SetRule { width, .. } => {
+                registers.h = add_position_v0(registers.h, *width).map_err(fail)?;
+            }
+            DviCommandV0::PutRule { .. } | DviCommandV0::Nop | DviCommandV0::Xxx(_) => {}
+            DviCommandV0::Push => {
+                if stack.len() >= MAX_DVI_STACK_DEPTH_V0 {
+                    return Err(fail(DviReadErrorKindV0::StackOverflow));
+                }
+                stack.push(*registers);
+                max_stack_depth = max_stack_depth.max(stack.len());
+            }
+            DviCommandV0::Pop => {
+                let saved = stack
+                    .pop()
+                    .ok_or(fail(DviReadErrorKindV0::StackUnderflow))?;
+                *registers = saved;
+            }
+            DviCommandV0::Right(amount) => {
+                registers.h = add_position_v0(registers.h, *amount).map_err(fail)?;
+            }
+            DviCommandV0::W(value) => {
+                let amount = register_fetch_or_set_v0(&mut registers.w, *value);
+                registers.h = add_position_v0(registers.h, amount).map_err(fail)?;
+            }
+            DviCommandV0::X(value) => {
+                let amount = register_fetch_or_set_v0(&mut registers.x, *value);
+                registers.h = add_position_v0(registers.h, amount).map_err(fail)?;
+            }
+            DviCommandV0::Down(amount) => {
+                registers.v = add_position_v0(registers.v, *amount).map_err(fail)?;
+            }
+            DviCommandV0::Y(value) => {
+                let amount = register_fetch_or_set_v0(&mut registers.y, *value);
+                registers.v = add_position_v0(registers.v, amount).map_err(fail)?;
+            }
+            DviCommandV0::Z(value) => {
+                let amount = register_fetch_or_set_v0(&mut registers.z, *value);
+                registers.v = add_position_v0(registers.v, amount).map_err(fail)?;
+            }
+            DviCommandV0::FntNum(font_num) => {
+                if !fonts.iter().any(|font| font.font_num == *font_num) {
+                    return Err(fail(DviReadErrorKindV0::FontUndefined));
+                }
+                state.font = Some(*font_num);
+            }
+            DviCommandV0::FntDef(definition) => {
+                match fonts
+                    .iter()
+                    .find(|font| font.font_num == definition.font_num)
+                {
+                    Some(existing) if existing != definition => {
+                        return Err(fail(DviReadErrorKindV0::FontRedefined));
+                    }
+                    Some(_) => {}
+                    None if post_offset.is_some() => {
+                        return Err(fail(DviReadErrorKindV0::PostambleInvalid));
+                    }
+                    None => fonts.push(definition.clone()),
+                }
+            }
+            DviCommandV0::SetGlyphs { width, glyphs, .. } => {
+                let font_num = state
+                    .font
+                    .ok_or(fail(DviReadErrorKindV0::FontNotSelected))?;
+                let is_native = fonts.iter().any(|font| {
+                    font.font_num == font_num && matches!(font.kind, DviFontKindV0::Native(_))
+                });
+                if !is_native {
+                    return Err(fail(DviReadErrorKindV0::FontUndefined));
+                }
+                for (x, y, _) in glyphs {
+                    add_position_v0(registers.h, *x).map_err(fail)?;
+                    add_position_v0(registers.v, *y).map_err(fail)?;
+                }
+                registers.h = add_position_v0(registers.h, *width).map_err(fail)?;
+            }
+            DviCommandV0::Post(postamble) => {
+                if in_page {
+                    return Err(fail(DviReadErrorKindV0::OpcodeOutsidePage(DVI_POST)));
+                }
+                if (postamble.num, postamble.den, postamble.mag)
+                    != (preamble.num, preamble.den, preamble.mag)
+                    || postamble.last_bop != last_bop
+                    || postamble.total_pages as usize != page_count
+                    || (postamble.max_stack_depth as usize) < max_stack_depth
+                {
+                    return Err(fail(DviReadErrorKindV0::PostambleInvalid));
+                }
+                post_offset = Some(offset);
+            }
+            DviCommandV0::PostPost {
+                post_offset: pointer,
+                id: trailer_id,
+            } => {
+                let Some(post_offset) = post_offset else {
+                    return Err(fail(DviReadErrorKindV0::PostambleInvalid));
+                };
+                if *pointer as usize != post_offset || *trailer_id != id {
+                    return Err(fail(DviReadErrorKindV0::PostambleInvalid));
+                }
+                visit(offset, &command, &state);
+                return check_trailer_v0(bytes, index);
+            }
+        }
+        state.stack_depth = stack.len();
+        state.char_width = char_advance;
+        visit(offset, &command, &state);
+        if matches!(command, DviCommandV0::Eop) {
+            state.page_index = None;
+        }
+    }
+}
@@ -1,3 +1,68 @@
+mod color_v0;
+mod dump_v0;
+mod image_v0;
+mod interpret_v0;
+mod reader_v0;
+mod text_floats_v0;
+mod text_fonts_v0;
+mod text_footnotes_v0;
+mod text_layout_v0;
+mod text_marks_v0;
+mod text_pages_v0;
+mod text_v0;
+mod text_validate_v0;
+mod tfm_v0;
+
+pub use color_v0::{validate_dvi_color_stack_v0, DviColorStackV0, MAX_DVI_COLOR_STACK_DEPTH_V0};
+pub use dump_v0::{dump_dvi_v0, dump_dvi_with_char_widths_v0, DviDumpFormatV0, DviDumpV0};
+pub use image_v0::{
+    image_special_v0, parse_image_special_v0, parse_image_v0, DviImageKindV0, DviImageSpecialV0,
+    DviImageV0, MAX_DVI_IMAGE_SIDE_PX_V0,
+};
+pub use interpret_v0::{
+    DviFontDefV0, DviFontKindV0, DviPostambleV0, DviPreambleV0, DviReadErrorKindV0, DviReadErrorV0,
+    DviRegistersV0, DviTfmFontDefV0, XdvNativeFontDefV0, MAX_DVI_READ_BYTES_V0,
+    MAX_DVI_STACK_DEPTH_V0, XDV_ID_V0,
+};
+pub use reader_v0::{
+    decimal_text_v0, read_dvi_v0, read_dvi_with_char_widths_v0, tfm_char_width_v0,
+    zero_scale_char_width_v0, DviDocumentV0, DviExtentsV0, DviGlyphV0, DviPageV0, DviRuleV0,
+    DviSpecialV0, DEFAULT_FALLBACK_FONT_SIZE_SP_V0, DEFAULT_PAPER_HEIGHT_SP_V0,
+    DEFAULT_PAPER_WIDTH_SP_V0, ONE_INCH_SP_V0,
+};
+pub use text_floats_v0::{DviTextFloatPlacementV0, DviTextFloatV0, MAX_DVI_TEXT_FLOATS_V0};
+pub use text_fonts_v0::{
+    DviTextFontSwitchV0, DviTextFontV0, DVI_TEXT_FONT_DESIGN_SIZE_SP_V0, MAX_DVI_TEXT_FONTS_V0,
+};
+pub use text_footnotes_v0::{DviTextFootnoteV0, DviTextFootnotesV0, MAX_DVI_TEXT_FOOTNOTES_V0};
+pub use text_layout_v0::{
+    text_float_pages_v0, text_page_starts_v0, DviTextIndentV0, MAX_DVI_TEXT_INDENTS_V0,
+};
+pub use text_marks_v0::{
+    DviTextFillV0, DviTextKernV0, DviTextMarksV0, DviTextRaiseV0, DviTextRuleV0,
+    MAX_DVI_RULE_DIMENSION_SP_V0, MAX_DVI_TEXT_FILLS_V0, MAX_DVI_TEXT_KERNS_V0,
+    MAX_DVI_TEXT_RAISES_V0, MAX_DVI_TEXT_RULES_V0,
+};
+pub use text_pages_v0::{
+    DviTextFurnitureV0, DviTextPagesV0, DviTextRunV0, MAX_DVI_TEXT_RUNS_V0,
+    MAX_DVI_TEXT_RUN_BYTES_V0,
+};
+pub use text_v0::{
+    ascii_glyph_char_v0, extract_text_v0, extract_text_with_char_widths_v0, page_text_v0,
+    DviTextOptionsV0, MAX_TEXT_LINE_BREAKS_V0,
+};
+pub use text_validate_v0::count_dvi_v2_text_movements_with_layout_v0;
+pub use tfm_v0::{parse_tfm_v0, TfmFontV0, MAX_TFM_BYTES_V0};
+
+use text_floats_v0::floats_start_v0;
+use text_fonts_v0::{fnt_num_v0, TextFontsV0};
+use text_footnotes_v0::{emit_footnote_rule_v0, TextRowV0};
+#[cfg(test)]
+use text_layout_v0::wrap_logical_line_ranges_v0;
+use text_layout_v0::{layout_text_pages_v0, TextLineV0};
+use text_marks_v0::{emit_text_line_v0, validate_text_marks_v0, LineWidthV0, TextMarkCursorV0};
+use text_pages_v0::{emit_furniture_v0, emit_origin_v0};
+
 const DVI_PRE: u8 = 247;
 const DVI_BOP: u8 = 139;
 const DVI_EOP: u8 = 140;
@@ -6,12 +71,15 @@ const DVI_POSTPOST: u8 = 249;
 const DVI_FNT_DEF1: u8 = 243;
 const DVI_FNT_NUM_0: u8 = 171;
 const DVI_RIGHT3: u8 = 145;
-const DVI_DOWN3: u8 = 160;
+const DVI_RIGHT4: u8 = 146;
+const DVI_DOWN3: u8 = 159;
+const DVI_XXX1: u8 = 239;
+const DVI_XXX4: u8 = 242;
 const DVI_ID_V2: u8 = 2;
 const DVI_TRAILER_BYTE: u8 = 223;
-const DVI_NUM: u32 = 25_400_000;
-const DVI_DEN: u32 = 473_628_672;
-const DVI_MAG: u32 = 1000;
+pub const DVI_NUM: u32 = 25_400_000;
+pub const DVI_DEN: u32 = 473_628_672;
+pub const DVI_MAG: u32 = 1000;
 const FONT_ID_V0: u8 = 0;
 const FONT_NAME_V0: &[u8] = b"carreltex-v0";
 const PAGEBREAK_MARKER_V0: u8 = 0x0c;
@@ -20,6 +88,15 @@ pub const DEFAULT_GLYPH_ADVANCE_SP_V0: i32 = 65_536;
 pub const DEFAULT_LINE_ADVANCE_SP_V0: i32 = 786_432;
 pub const DEFAULT_MAX_LINE_GLYPHS_V0: usize = 80;
 pub const DEFAULT_MAX_LINES_PER_PAGE_V0: usize = 200;
+pub const MAX_DVI_SPECIAL_BYTES_V0: usize = 4096;
+pub const MAX_DVI_SPECIALS_V0: usize = 1024;
+
+/// `\special` payload anchored before the text byte at `offset`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviTextSpecialV0 {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
 
 fn push_u32_be(out: &mut Vec<u8>, value: u32) {
     out.extend_from_slice(&value.to_be_bytes());
@@ -82,7 +159,15 @@ fn is_supported_text_byte_v0(byte: u8) -> bool {
     (0x20..=0x7e).contains(&byte)
 }
 
-fn glyph_width_sp_v0(byte: u8, glyph_advance_sp: i32) -> Option<i32> {
+/// Glyphs of the body text: printable ASCII plus the codes from 128 that math
+/// symbol fonts use, set with `set1`.
+fn is_text_glyph_byte_v0(byte: u8) -> bool {
+    is_supported_text_byte_v0(byte) || byte >= 0x80
+}
+
+/// Advance of `byte` in a font of em `glyph_advance_sp`: half an em for space, `.`
+/// and `i`, one and a half for `m` and `W`, one em otherwise.
+pub fn glyph_width_sp_v0(byte: u8, glyph_advance_sp: i32) -> Option<i32> {
     if glyph_advance_sp <= 0 {
         return None;
     }
@@ -99,91 +184,6 @@ fn glyph_width_sp_v0(byte: u8, glyph_advance_sp: i32) -> Option<i32> {
     Some(width)
 }
 
-fn split_pages_v0(text: &[u8]) -> Option<Vec<&[u8]>> {
-    if text.iter().any(|byte| {
-        !is_supported_text_byte_v0(*byte)
-            && *byte != PAGEBREAK_MARKER_V0
-            && *byte != NEWLINE_MARKER_V0
-    }) {
-        return None;
-    }
-    let mut pages = Vec::<&[u8]>::new();
-    let mut start = 0usize;
-    for (index, byte) in text.iter().enumerate() {
-        if *byte == PAGEBREAK_MARKER_V0 {
-            pages.push(&text[start..index]);
-            start = index + 1;
-        }
-    }
-    pages.push(&text[start..]);
-    Some(pages)
-}
-
-fn split_lines_v0(page: &[u8]) -> Vec<&[u8]> {
-    let mut lines = Vec::<&[u8]>::new();
-    let mut start = 0usize;
-    for (index, byte) in page.iter().enumerate() {
-        if *byte == NEWLINE_MARKER_V0 {
-            lines.push(&page[start..index]);
-            start = index + 1;
-        }
-    }
-    lines.push(&page[start..]);
-    lines
-}
-
-fn wrap_logical_line_v0(line: &[u8], max_line_glyphs: usize) -> Option<Vec<Vec<u8>>> {
-    if max_line_glyphs == 0 {
-        return None;
-    }
-    if line.is_empty() {
-        return Some(vec![Vec::new()]);
-    }
-    let mut wrapped = Vec::<Vec<u8>>::new();
-    let mut start = 0usize;
-    while start < line.len() {
-        if line.len() - start <= max_line_glyphs {
-            wrapped.push(line[start..].to_vec());
-            break;
-        }
-        let limit = start + max_line_glyphs;
-        let mut break_at = None::<usize>;
-        for index in (start..limit).rev() {
-            if line[index] == b' ' {
-                break_at = Some(index);
-                break;
-            }
-        }
-        if let Some(space_index) = break_at {
-            if space_index > start {
-                wrapped.push(line[start..space_index].to_vec());
-            } else {
-                wrapped.push(Vec::new());
-            }
-            start = space_index + 1;
-            while start < line.len() && line[start] == b' ' {
-                start += 1;
-            }
-        } else {
-            wrapped.push(line[start..limit].to_vec());
-            start = limit;
-        }
-    }
-    Some(wrapped)
-}
-
-fn emit_line_glyphs_v0(out: &mut Vec<u8>, line: &[u8], glyph_advance_sp: i32) -> Option<u32> {
-    let mut line_h = 0u32;
-    for byte in line {
-        out.push(*byte);
-        let glyph_width = glyph_width_sp_v0(*byte, glyph_advance_sp)?;
-        out.push(DVI_RIGHT3);
-        push_i24_be(out, glyph_width)?;
-        line_h = line_h.checked_add(u32::try_from(glyph_width).ok()?)?;
-    }
-    Some(line_h)
-}
-
 fn append_font_def_v0(out: &mut Vec<u8>) {
     out.push(DVI_FNT_DEF1);
     out.push(FONT_ID_V0);
@@ -307,6 +307,84 @@ pub fn write_dvi_v2_text_page_with_layout_wrap_and_paging_v0(
     line_advance_sp: i32,
     max_line_glyphs: usize,
     max_lines_per_page: usize,
+) -> Option<Vec<u8>> {
+    write_dvi_v2_text_page_with_layout_paging_and_specials_v0(
+        text,
+        &[],
+        glyph_advance_sp,
+        line_advance_sp,
+        max_line_glyphs,
+        max_lines_per_page,
+    )
+}
+
+/// Text pages with `xxx1`/`xxx4` specials, each emitted just before the glyph at its
+/// `offset`; specials anchored on a line or page break (or on spaces dropped by
+/// wrapping) are emitted where the text around them is placed. Offsets must be
+/// non-decreasing and at most `text.len()`.
+pub fn write_dvi_v2_text_page_with_layout_paging_and_specials_v0(
+    text: &[u8],
+    specials: &[DviTextSpecialV0],
+    glyph_advance_sp: i32,
+    line_advance_sp: i32,
+    max_line_glyphs: usize,
+    max_lines_per_page: usize,
+) -> Option<Vec<u8>> {
+    write_dvi_v2_text_page_with_layout_paging_specials_and_rules_v0(
+        text,
+        specials,
+        &[],
+        glyph_advance_sp,
+        line_advance_sp,
+        max_line_glyphs,
+        max_lines_per_page,
+    )
+}
+
+/// `write_dvi_v2_text_page_with_layout_paging_and_specials_v0` plus rules, anchored
+/// like specials. Rules take no part in wrapping, but advancing rules count towards
+/// the line reset and every rule towards the postamble's maximum width.
+pub fn write_dvi_v2_text_page_with_layout_paging_specials_and_rules_v0(
+    text: &[u8],
+    specials: &[DviTextSpecialV0],
+    rules: &[DviTextRuleV0],
+    glyph_advance_sp: i32,
+    line_advance_sp: i32,
+    max_line_glyphs: usize,
+    max_lines_per_page: usize,
+) -> Option<Vec<u8>> {
+    let marks = DviTextMarksV0 {
+        specials,
+        rules,
+        ..DviTextMarksV0::default()
+    };
+    write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+        text,
+        &marks,
+        glyph_advance_sp,
+        line_advance_sp,
+        max_line_glyphs,
+        max_lines_per_page,
+    )
+}
+
+/// Text pages with every kind of text-anchored mark. Indented lines start with a
+/// `right4` to their margin and get a glyph budget shortened by it; a `right4` also
+/// moves from an item label to the text after it. Every page defines the extra
+/// fonts after the default one and selects the font of its first glyph; glyph
+/// widths scale with their font's size, while line breaks still count glyphs.
+/// Floats take the top or bottom of a page, or sit between main lines, set off by
+/// blank lines, or fill pages of their own; footnote bodies end their page, below
+/// blank lines and the footnote rule. Pages
+/// move from the DVI origin to their first line's origin; those with furniture
+/// carry their number in `\count0` and set their head and foot before any line.
+pub fn write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+    text: &[u8],
+    text_marks: &DviTextMarksV0<'_>,
+    glyph_advance_sp: i32,
+    line_advance_sp: i32,
+    max_line_glyphs: usize,
+    max_lines_per_page: usize,
 ) -> Option<Vec<u8>> {
     if glyph_advance_sp <= 0
         || line_advance_sp <= 0
@@ -315,7 +393,63 @@ pub fn write_dvi_v2_text_page_with_layout_wrap_and_paging_v0(
     {
         return None;
     }
-    let forced_pages = split_pages_v0(text)?;
+    let DviTextMarksV0 {
+        specials,
+        rules,
+        indents,
+        fonts,
+        font_switches,
+        fills,
+        raises,
+        kerns,
+        floats,
+        footnotes,
+        pages: furniture,
+    } = *text_marks;
+    validate_text_marks_v0(text.len(), text_marks)?;
+    let fonts = TextFontsV0::new_v0(text.len(), fonts, font_switches, glyph_advance_sp)?;
+    let pages = layout_text_pages_v0(
+        text,
+        indents,
+        floats,
+        &footnotes,
+        &fonts,
+        glyph_advance_sp,
+        max_line_glyphs,
+        max_lines_per_page,
+    )?;
+    furniture.validate_v0(pages.len(), &fonts)?;
+    let footnote_rules = pages
+        .iter()
+        .filter(|page| !page.footnote_lines.is_empty())
+        .count();
+    if rules.len() + footnote_rules > MAX_DVI_TEXT_RULES_V0 {
+        return None;
+    }
+    let mut marks = TextMarkCursorV0 {
+        specials,
+        rules,
+        fills,
+        raises,
+        kerns,
+        next_special: 0,
+        next_rule: 0,
+        next_fill: 0,
+        next_kern: 0,
+    };
+    // Float and footnote bodies have their own cursors, starting at the marks
+    // anchored in them.
+    let cursor_at = |start: usize| TextMarkCursorV0 {
+        next_special: specials.partition_point(|special| special.offset < start),
+        next_rule: rules.partition_point(|rule| rule.offset < start),
+        next_fill: fills.partition_point(|fill| fill.offset < start),
+        next_kern: kerns.partition_point(|kern| kern.offset < start),
+        ..marks
+    };
+    let floats_start = floats_start_v0(floats);
+    let bodies_start = footnotes.bodies_start_v0();
+    let mut float_marks = cursor_at(floats_start.min(bodies_start));
+    let mut footnote_marks = cursor_at(bodies_start);
 
     let mut out = Vec::<u8>::new();
     out.push(DVI_PRE);
@@ -328,53 +462,80 @@ pub fn write_dvi_v2_text_page_with_layout_wrap_and_paging_v0(
     let mut bop_offsets = Vec::<u32>::new();
     let mut max_h = 0u32;
     let mut max_v = 0u32;
-    for forced_page in forced_pages {
-        let logical_lines = split_lines_v0(forced_page);
-        let mut physical_lines = Vec::<Vec<u8>>::new();
-        for line in logical_lines {
-            let wrapped = wrap_logical_line_v0(line, max_line_glyphs)?;
-            physical_lines.extend(wrapped);
+    for (page_index, page) in pages.iter().enumerate() {
+        let bop_offset = out.len() as u32;
+        let page_furniture = furniture.furniture.get(page_index);
+        out.push(DVI_BOP);
+        push_i32_be(&mut out, page_furniture.map_or(0, |page| page.number));
+        for _ in 1..10 {
+            push_i32_be(&mut out, 0);
         }
-        for chunk in physical_lines.chunks(max_lines_per_page) {
-            let bop_offset = out.len() as u32;
-            out.push(DVI_BOP);
-            for _ in 0..10 {
-                push_i32_be(&mut out, 0);
-            }
-            let prev_bop = if let Some(previous) = bop_offsets.last() {
-                i32::try_from(*previous).ok()?
-            } else {
-                -1
-            };
-            push_i32_be(&mut out, prev_bop);
-            append_font_def_v0(&mut out);
-            out.push(DVI_FNT_NUM_0);
-
-            let mut page_h = 0u32;
-            let mut page_v = 0u32;
-            let mut previous_line_h = emit_line_glyphs_v0(
-                &mut out,
-                chunk.first().map(|line| line.as_slice()).unwrap_or(&[]),
-                glyph_advance_sp,
-            )?;
-            page_h = page_h.max(previous_line_h);
-            for line in chunk.iter().skip(1) {
+        let prev_bop = if let Some(previous) = bop_offsets.last() {
+            i32::try_from(*previous).ok()?
+        } else {
+            -1
+        };
+        push_i32_be(&mut out, prev_bop);
+        append_font_def_v0(&mut out);
+        fonts.append_font_defs_v0(&mut out);
+        emit_origin_v0(&mut out, &furniture);
+        let (mut page_h, page_v) = match page_furniture {
+            Some(page) => emit_furniture_v0(&mut out, page, &furniture, &fonts)?,
+            None => (0, 0),
+        };
+        let rows = page.rows_v0(max_lines_per_page);
+        let first_start = rows.iter().find_map(|row| match row {
+            TextRowV0::Text(line) | TextRowV0::Float(line) => Some(line.start),
+            _ => None,
+        });
+        let mut font = fonts.font_at_v0(first_start.unwrap_or(0));
+        out.push(fnt_num_v0(font));
+
+        let mut line_v = 0u32;
+        let mut previous_line_h = 0u32;
+        for (line_index, row) in rows.iter().enumerate() {
+            if line_index > 0 {
                 if previous_line_h > 0 {
-                    out.push(DVI_RIGHT3);
                     let reset_back = -i32::try_from(previous_line_h).ok()?;
-                    push_i24_be(&mut out, reset_back)?;
+                    // Lines wider than `right3` reaches, as images make them.
+                    if reset_back < -0x80_0000 {
+                        out.push(DVI_RIGHT4);
+                        push_i32_be(&mut out, reset_back);
+                    } else {
+                        out.push(DVI_RIGHT3);
+                        push_i24_be(&mut out, reset_back)?;
+                    }
                 }
                 out.push(DVI_DOWN3);
                 push_i24_be(&mut out, line_advance_sp)?;
-                page_v = page_v.checked_add(u32::try_from(line_advance_sp).ok()?)?;
-                previous_line_h = emit_line_glyphs_v0(&mut out, line.as_slice(), glyph_advance_sp)?;
-                page_h = page_h.max(previous_line_h);
+                line_v = line_v.checked_add(u32::try_from(line_advance_sp).ok()?)?;
             }
-            max_h = max_h.max(page_h);
-            max_v = max_v.max(page_v);
-            out.push(DVI_EOP);
-            bop_offsets.push(bop_offset);
+            let width = match row {
+                TextRowV0::Text(line) => {
+                    emit_text_line_v0(&mut out, text, line, &mut marks, &fonts, &mut font)?
+                }
+                TextRowV0::Blank => LineWidthV0::default(),
+                TextRowV0::Float(line) => {
+                    emit_text_line_v0(&mut out, text, line, &mut float_marks, &fonts, &mut font)?
+                }
+                TextRowV0::FootnoteRule => emit_footnote_rule_v0(&mut out, &footnotes)?,
+                TextRowV0::Footnote(line) => {
+                    emit_text_line_v0(&mut out, text, line, &mut footnote_marks, &fonts, &mut font)?
+                }
+            };
+            previous_line_h = width.advance;
+            page_h = page_h.max(width.extent);
         }
+        max_h = max_h.max(page_h);
+        max_v = max_v.max(page_v).max(line_v);
+        out.push(DVI_EOP);
+        bop_offsets.push(bop_offset);
+    }
+    if !marks.is_done_through_v0(floats_start.min(bodies_start))
+        || !float_marks.is_done_through_v0(bodies_start)
+        || !footnote_marks.is_done_through_v0(usize::MAX)
+    {
+        return None;
     }
     let page_count = u16::try_from(bop_offsets.len()).ok()?;
     if page_count == 0 {
@@ -389,7 +550,11 @@ pub fn write_dvi_v2_text_page_with_layout_wrap_and_paging_v0(
     push_u32_be(&mut out, DVI_MAG);
     push_u32_be(&mut out, max_h);
     push_u32_be(&mut out, max_v);
-    out.extend_from_slice(&0u16.to_be_bytes());
+    let pushes = furniture
+        .furniture
+        .iter()
+        .any(|page| !page.head.is_empty() || !page.foot.is_empty());
+    out.extend_from_slice(&u16::from(pushes).to_be_bytes());
     out.extend_from_slice(&page_count.to_be_bytes());
 
     out.push(DVI_POSTPOST);
@@ -415,7 +580,7 @@ pub fn write_dvi_v2_text_page_v0(text: &[u8]) -> Option<Vec<u8>> {
 }
 
 pub fn validate_dvi_v2_empty_page_v0(bytes: &[u8]) -> bool {
-    if bytes.is_empty() || bytes.len() % 4 != 0 {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(4) {
         return false;
     }
 
@@ -512,205 +677,6 @@ pub fn count_dvi_v2_text_pages_with_layout_v0(
         .map(|(_, _, _, _, page_count)| page_count)
 }
 
-pub fn count_dvi_v2_text_movements_with_layout_v0(
-    bytes: &[u8],
-    glyph_advance_sp: i32,
-    line_advance_sp: i32,
-) -> Option<(u32, u32, u32, u32, u16)> {
-    if glyph_advance_sp <= 0 || line_advance_sp <= 0 {
-        return None;
-    }
-    if bytes.is_empty() || bytes.len() % 4 != 0 {
-        return None;
-    }
-
-    let mut index = 0usize;
-    if read_u8(bytes, &mut index) != Some(DVI_PRE) {
-        return None;
-    }
-    if read_u8(bytes, &mut index) != Some(DVI_ID_V2) {
-        return None;
-    }
-    if read_u32_be(bytes, &mut index) != Some(DVI_NUM) {
-        return None;
-    }
-    if read_u32_be(bytes, &mut index) != Some(DVI_DEN) {
-        return None;
-    }
-    if read_u32_be(bytes, &mut index) != Some(DVI_MAG) {
-        return None;
-    }
-    if read_u8(bytes, &mut index) != Some(0) {
-        return None;
-    }
-
-    let mut right3_count = 0u32;
-    let w3_count = 0u32;
-    let w0_count = 0u32;
-    let mut down3_count = 0u32;
-    let mut page_count = 0u16;
-    let mut previous_bop_offset: Option<usize> = None;
-    let mut last_bop_offset = 0u32;
-    let mut max_h = 0u32;
-    let mut max_v = 0u32;
-    loop {
-        let opcode = *bytes.get(index)?;
-        if opcode == DVI_POST {
-            break;
-        }
-        if opcode != DVI_BOP {
-            return None;
-        }
-        let bop_offset = index;
-        last_bop_offset = bop_offset as u32;
-        index += 1;
-        for _ in 0..10 {
-            if read_i32_be(bytes, &mut index) != Some(0) {
-                return None;
-            }
-        }
-        let expected_prev = if let Some(previous) = previous_bop_offset {
-            i32::try_from(previous).ok()?
-        } else {
-            -1
-        };
-        if read_i32_be(bytes, &mut index) != Some(expected_prev) {
-            return None;
-        }
-        read_and_validate_font_def_v0(bytes, &mut index)?;
-        if read_u8(bytes, &mut index) != Some(DVI_FNT_NUM_0) {
-            return None;
-        }
-        let mut page_h = 0u32;
-        let mut page_h_max = 0u32;
-        let mut page_v = 0u32;
-        let mut expect_width_right_after_char = false;
-        let mut expect_down3_after_reset = false;
-        let mut expected_right_after_char = 0i32;
-        while let Some(op) = bytes.get(index).copied() {
-            if op == DVI_EOP {
-                if expect_down3_after_reset || expect_width_right_after_char {
-                    return None;
-                }
-                index += 1;
-                break;
-            }
-            if expect_width_right_after_char {
-                if op == DVI_RIGHT3 {
-                    right3_count = right3_count.checked_add(1)?;
-                    index += 1;
-                    let amount = read_i24_be(bytes, &mut index)?;
-                    if amount != expected_right_after_char {
-                        return None;
-                    }
-                    page_h = page_h.checked_add(u32::try_from(amount).ok()?)?;
-                    page_h_max = page_h_max.max(page_h);
-                    expect_width_right_after_char = false;
-                    continue;
-                } else {
-                    return None;
-                }
-            } else {
-                if op == DVI_RIGHT3 {
-                    right3_count = right3_count.checked_add(1)?;
-                    index += 1;
-                    let amount = read_i24_be(bytes, &mut index)?;
-                    if amount >= 0 {
-                        return None;
-                    }
-                    let back = u32::try_from(-amount).ok()?;
-                    if back != page_h {
-                        return None;
-                    }
-                    page_h = 0;
-                    expect_down3_after_reset = true;
-                    continue;
-                } else if op == DVI_DOWN3 {
-                    down3_count = down3_count.checked_add(1)?;
-                    index += 1;
-                    if read_i24_be(bytes, &mut index)? != line_advance_sp {
-                        return None;
-                    }
-                    if page_h != 0 {
-                        return None;
-                    }
-                    if expect_down3_after_reset {
-                        expect_down3_after_reset = false;
-                    }
-                    page_v = page_v.checked_add(u32::try_from(line_advance_sp).ok()?)?;
-                    continue;
-                }
-                if op > 127 || !is_supported_text_byte_v0(op) {
-                    return None;
-                }
-                if expect_down3_after_reset {
-                    return None;
-                }
-                expected_right_after_char = glyph_width_sp_v0(op, glyph_advance_sp)?;
-                index += 1;
-                expect_width_right_after_char = true;
-            }
-        }
-        if page_h_max > max_h {
-            max_h = page_h_max;
-        }
-        if page_v > max_v {
-            max_v = page_v;
-        }
-        previous_bop_offset = Some(bop_offset);
-        page_count = page_count.checked_add(1)?;
-    }
-    if page_count == 0 {
-        return None;
-    }
-
-    let post_offset = index;
-    if read_u8(bytes, &mut index) != Some(DVI_POST) {
-        return None;
-    }
-    if read_u32_be(bytes, &mut index) != Some(last_bop_offset) {
-        return None;
-    }
-    if read_u32_be(bytes, &mut index) != Some(DVI_NUM) {
-        return None;
-    }
-    if read_u32_be(bytes, &mut index) != Some(DVI_DEN) {
-        return None;
-    }
-    if read_u32_be(bytes, &mut index) != Some(DVI_MAG) {
-        return None;
-    }
-    if read_u32_be(bytes, &mut index) != Some(max_h) {
-        return None;
-    }
-    if read_u32_be(bytes, &mut index) != Some(max_v) {
-        return None;
-    }
-    if read_u16_be(bytes, &mut index) != Some(0) {
-        return None;
-    }
-    if read_u16_be(bytes, &mut index) != Some(page_count) {
-        return None;
-    }
-    if read_u8(bytes, &mut index) != Some(DVI_POSTPOST) {
-        return None;
-    }
-    if read_u32_be(bytes, &mut index) != Some(post_offset as u32) {
-        return None;
-    }
-    if read_u8(bytes, &mut index) != Some(DVI_ID_V2) {
-        return None;
-    }
-    let trailer_len = bytes.len().saturating_sub(index);
-    if trailer_len < 4 {
-        return None;
-    }
-    if !bytes[index..].iter().all(|byte| *byte == DVI_TRAILER_BYTE) {
-        return None;
-    }
-    Some((right3_count, w3_count, w0_count, down3_count, page_count))
-}
-
 pub fn sum_dvi_v2_positive_right3_amounts_with_layout_v0(
     bytes: &[u8],
     glyph_advance_sp: i32,
@@ -756,5 +722,21 @@ pub fn validate_dvi_v2_text_page_v0(bytes: &[u8]) -> bool {
     count_dvi_v2_text_pages_v0(bytes).is_some()
 }
 
+#[cfg(test)]
+mod dump_v0_tests;
+#[cfg(test)]
+mod image_v0_tests;
+#[cfg(test)]
+mod reader_v0_tests;
+#[cfg(test)]
+mod test_dvi_v0;
 #[cfg(test)]
 mod tests;
+#[cfg(test)]
+mod text_floats_v0_tests;
+#[cfg(test)]
+mod text_footnotes_v0_tests;
+#[cfg(test)]
+mod text_pages_v0_tests;
+#[cfg(test)]
+mod text_v0_tests;
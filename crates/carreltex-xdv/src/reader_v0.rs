@@ -0,0 +1,280 @@
+use crate::interpret_v0::{
+    walk_dvi_v0, DviCommandV0, DviFontDefV0, DviFontKindV0, DviPostambleV0, DviPreambleV0,
+    DviReadErrorKindV0, DviReadErrorV0, DviRegistersV0,
+};
+use crate::tfm_v0::TfmFontV0;
+use crate::FONT_NAME_V0;
+
+/// TeX's `1in` in scaled points.
+pub const ONE_INCH_SP_V0: i32 = 4_736_286;
+/// US letter (614.295pt x 794.97pt), LaTeX's default paper.
+pub const DEFAULT_PAPER_WIDTH_SP_V0: i32 = 40_258_437;
+pub const DEFAULT_PAPER_HEIGHT_SP_V0: i32 = 52_099_154;
+/// Size used for fonts defined at scale 0 (such as `carreltex-v0`).
+pub const DEFAULT_FALLBACK_FONT_SIZE_SP_V0: i32 = 655_360;
+
+/// Shortest decimal with at most four fraction digits, so page output is stable
+/// across backends.
+pub fn decimal_text_v0(value: f64) -> String {
+    let text = format!("{value:.4}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" | "" => "0".to_string(),
+        _ => text.to_string(),
+    }
+}
+
+/// A positioned glyph. `code` is the character code for TFM fonts and the glyph id for
+/// XDV native fonts; `width` is the advance attributed to the glyph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviGlyphV0 {
+    pub font_num: i32,
+    pub code: u32,
+    pub native: bool,
+    pub h: i32,
+    pub v: i32,
+    pub width: i32,
+}
+
+/// A filled rule whose bottom-left corner is at (`h`, `v`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviRuleV0 {
+    pub h: i32,
+    pub v: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// An `xxx` payload; `glyph_index` and `rule_index` count the page's glyphs and rules
+/// preceding it, which places it in DVI order relative to both.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviSpecialV0 {
+    pub h: i32,
+    pub v: i32,
+    pub glyph_index: usize,
+    pub rule_index: usize,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviPageV0 {
+    /// Byte offset of the page's `bop`.
+    pub offset: usize,
+    pub counts: [i32; 10],
+    pub glyphs: Vec<DviGlyphV0>,
+    pub rules: Vec<DviRuleV0>,
+    pub specials: Vec<DviSpecialV0>,
+}
+
+/// Bounding box in DVI units, with `v` growing downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviExtentsV0 {
+    pub min_h: i32,
+    pub min_v: i32,
+    pub max_h: i32,
+    pub max_v: i32,
+}
+
+impl DviPageV0 {
+    /// Box covering every rule and every glyph's advance along its baseline (glyph
+    /// heights are not known here); `None` for a page without either.
+    pub fn extents_v0(&self) -> Option<DviExtentsV0> {
+        let glyphs = self.glyphs.iter().map(|glyph| {
+            (
+                glyph.h,
+                glyph.v,
+                glyph.h.saturating_add(glyph.width),
+                glyph.v,
+            )
+        });
+        let rules = self.rules.iter().map(|rule| {
+            (
+                rule.h,
+                rule.v.saturating_sub(rule.height),
+                rule.h.saturating_add(rule.width),
+                rule.v,
+            )
+        });
+        glyphs
+            .chain(rules)
+            .map(|(h0, v0, h1, v1)| DviExtentsV0 {
+                min_h: h0.min(h1),
+                min_v: v0,
+                max_h: h0.max(h1),
+                max_v: v1,
+            })
+            .reduce(|a, b| DviExtentsV0 {
+                min_h: a.min_h.min(b.min_h),
+                min_v: a.min_v.min(b.min_v),
+                max_h: a.max_h.max(b.max_h),
+                max_v: a.max_v.max(b.max_v),
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviDocumentV0 {
+    pub preamble: DviPreambleV0,
+    pub postamble: DviPostambleV0,
+    /// Font definitions in order of first appearance.
+    pub fonts: Vec<DviFontDefV0>,
+    pub pages: Vec<DviPageV0>,
+}
+
+impl DviDocumentV0 {
+    pub fn font_v0(&self, font_num: i32) -> Option<&DviFontDefV0> {
+        self.fonts.iter().find(|font| font.font_num == font_num)
+    }
+}
+
+/// The text writer's fonts, which move with explicit `right3`s after every glyph.
+fn is_text_writer_font_v0(name: &[u8]) -> bool {
+    name.strip_prefix(FONT_NAME_V0)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(b"-"))
+}
+
+/// Width resolver for fonts without metrics: TFM fonts defined at scale 0 and the
+/// `carreltex-v0` fonts written by this crate at any size have zero-width characters.
+pub fn zero_scale_char_width_v0(font: &DviFontDefV0, _code: u32) -> Option<i32> {
+    match &font.kind {
+        DviFontKindV0::Tfm(definition)
+            if definition.scale == 0 || is_text_writer_font_v0(&definition.name) =>
+        {
+            Some(0)
+        }
+        _ => None,
+    }
+}
+
+/// Width resolver over loaded TFM files, matched by font name (and checksum when both
+/// sides carry one); scale-0 and `carreltex-v0` fonts fall back to
+/// `zero_scale_char_width_v0`.
+pub fn tfm_char_width_v0<'a>(
+    tfms: &'a [(Vec<u8>, TfmFontV0)],
+) -> impl Fn(&DviFontDefV0, u32) -> Option<i32> + 'a {
+    move |font, code| {
+        let DviFontKindV0::Tfm(definition) = &font.kind else {
+            return None;
+        };
+        if definition.scale == 0 || is_text_writer_font_v0(&definition.name) {
+            return Some(0);
+        }
+        let (_, tfm) = tfms.iter().find(|(name, tfm)| {
+            *name == definition.name
+                && (definition.checksum == 0
+                    || tfm.checksum == 0
+                    || definition.checksum == tfm.checksum)
+        })?;
+        tfm.scaled_width_v0(code, definition.scale)
+    }
+}
+
+/// Read any DVI (id 2) or XDV (id 7) file into per-page glyphs, rules and specials.
+///
+/// `char_width` resolves `set_char`/`put_char` advances for TFM fonts (see
+/// `tfm_char_width_v0`); unresolvable characters fail closed with
+/// `dvi_char_width_unavailable`.
+pub fn read_dvi_with_char_widths_v0<W>(
+    bytes: &[u8],
+    char_width: W,
+) -> Result<DviDocumentV0, DviReadErrorV0>
+where
+    W: FnMut(&DviFontDefV0, u32) -> Option<i32>,
+{
+    let mut preamble = None::<DviPreambleV0>;
+    let mut postamble = None::<DviPostambleV0>;
+    let mut fonts = Vec::<DviFontDefV0>::new();
+    let mut pages = Vec::<DviPageV0>::new();
+    let mut before = DviRegistersV0::default();
+    walk_dvi_v0(bytes, char_width, |offset, command, state| {
+        let current_font = state.font.unwrap_or(0);
+        match command {
+            DviCommandV0::Pre(header) => preamble = Some(header.clone()),
+            DviCommandV0::Post(trailer) => postamble = Some(trailer.clone()),
+            DviCommandV0::Bop { counts, .. } => pages.push(DviPageV0 {
+                offset,
+                counts: *counts,
+                glyphs: Vec::new(),
+                rules: Vec::new(),
+                specials: Vec::new(),
+            }),
+            DviCommandV0::FntDef(definition) => {
+                if !fonts
+                    .iter()
+                    .any(|font| font.font_num == definition.font_num)
+                {
+                    fonts.push(definition.clone());
+                }
+            }
+            _ => {}
+        }
+        if let (Some(page), true) = (pages.last_mut(), state.page_index.is_some()) {
+            match command {
+                DviCommandV0::SetChar(code) | DviCommandV0::PutChar(code) => {
+                    page.glyphs.push(DviGlyphV0 {
+                        font_num: current_font,
+                        code: *code,
+                        native: false,
+                        h: before.h,
+                        v: before.v,
+                        width: state.char_width.unwrap_or(0),
+                    })
+                }
+                DviCommandV0::SetRule { height, width }
+                | DviCommandV0::PutRule { height, width } => {
+                    if *height > 0 && *width > 0 {
+                        page.rules.push(DviRuleV0 {
+                            h: before.h,
+                            v: before.v,
+                            width: *width,
+                            height: *height,
+                        });
+                    }
+                }
+                DviCommandV0::Xxx(special) => page.specials.push(DviSpecialV0 {
+                    h: before.h,
+                    v: before.v,
+                    glyph_index: page.glyphs.len(),
+                    rule_index: page.rules.len(),
+                    bytes: special.clone(),
+                }),
+                DviCommandV0::SetGlyphs { width, glyphs, .. } => {
+                    for (glyph_index, (x, y, glyph_id)) in glyphs.iter().enumerate() {
+                        let next_x = glyphs
+                            .get(glyph_index + 1)
+                            .map(|(next_x, _, _)| *next_x)
+                            .unwrap_or(*width);
+                        page.glyphs.push(DviGlyphV0 {
+                            font_num: current_font,
+                            code: *glyph_id as u32,
+                            native: true,
+                            h: before.h.saturating_add(*x),
+                            v: before.v.saturating_add(*y),
+                            width: next_x.saturating_sub(*x),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        before = state.registers;
+    })?;
+    let (Some(preamble), Some(postamble)) = (preamble, postamble) else {
+        return Err(DviReadErrorV0 {
+            offset: bytes.len(),
+            kind: DviReadErrorKindV0::Truncated,
+        });
+    };
+    Ok(DviDocumentV0 {
+        preamble,
+        postamble,
+        fonts,
+        pages,
+    })
+}
+
+/// `read_dvi_with_char_widths_v0` with `zero_scale_char_width_v0`, which covers every
+/// file written by this crate and XDV files that only use native fonts.
+pub fn read_dvi_v0(bytes: &[u8]) -> Result<DviDocumentV0, DviReadErrorV0> {
+    read_dvi_with_char_widths_v0(bytes, zero_scale_char_width_v0)
+}
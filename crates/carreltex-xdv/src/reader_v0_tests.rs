@@ -0,0 +1,337 @@
+use super::test_dvi_v0::{
+    build_tfm_v0, fnt_def1_v0, native_font_def_v0, op_i32_v0, rule_v0, TestDviV0,
+};
+use super::{
+    parse_tfm_v0, read_dvi_v0, read_dvi_with_char_widths_v0, tfm_char_width_v0,
+    write_dvi_v2_text_page_with_layout_wrap_and_paging_v0, DviFontKindV0, DviGlyphV0,
+    DviReadErrorKindV0, DviRuleV0, DviSpecialV0, XDV_ID_V0,
+};
+
+const TEN_PT_SP: i32 = 655_360;
+const HALF_FIX_WORD: u32 = 1 << 19;
+const QUARTER_FIX_WORD: u32 = 1 << 18;
+
+fn cmr_tfms_v0() -> Vec<(Vec<u8>, super::TfmFontV0)> {
+    let tfm = parse_tfm_v0(&build_tfm_v0(65, &[HALF_FIX_WORD, QUARTER_FIX_WORD], &[]))
+        .expect("test tfm should parse");
+    vec![(b"cmr10".to_vec(), tfm)]
+}
+
+fn glyph_v0(code: u32, h: i32, v: i32, width: i32) -> DviGlyphV0 {
+    DviGlyphV0 {
+        font_num: 1,
+        code,
+        native: false,
+        h,
+        v,
+        width,
+    }
+}
+
+#[test]
+fn reads_text_writer_output_as_pages() {
+    let bytes = write_dvi_v2_text_page_with_layout_wrap_and_paging_v0(
+        b"ab\nc\x0cd",
+        65_536,
+        786_432,
+        80,
+        200,
+    )
+    .expect("writer should accept text");
+    let document = read_dvi_v0(&bytes).expect("reader should accept writer output");
+    assert_eq!(document.preamble.id, 2);
+    assert_eq!(document.postamble.total_pages, 2);
+    assert_eq!(document.fonts.len(), 1);
+    let DviFontKindV0::Tfm(font) = &document.fonts[0].kind else {
+        panic!("expected tfm font");
+    };
+    assert_eq!(font.name, b"carreltex-v0");
+    assert_eq!(document.pages.len(), 2);
+    let first = &document.pages[0].glyphs;
+    let positions = first
+        .iter()
+        .map(|glyph| (glyph.code as u8, glyph.h, glyph.v))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        positions,
+        vec![(b'a', 0, 0), (b'b', 65_536, 0), (b'c', 0, 786_432)]
+    );
+    assert_eq!(document.pages[1].glyphs[0].code, b'd' as u32);
+    assert_eq!(
+        document.pages[1].offset,
+        document.postamble.last_bop as usize
+    );
+}
+
+#[test]
+fn interprets_every_dvi_movement_and_char_opcode() {
+    let font = fnt_def1_v0(1, 0x1234_5678, TEN_PT_SP, b"cmr10");
+    let mut dvi = TestDviV0::new(2);
+    dvi.set_max_stack_depth(1)
+        .raw(&font)
+        .bop(7)
+        .raw(&[172, 65, 141])
+        .raw(&[143, 0x10, 148, 0x20, 147])
+        .raw(&[154, 0x01, 0x00, 152])
+        .raw(&[157, 5])
+        .raw(&op_i32_v0(165, 7))
+        .raw(&[161, 167, 0xfd, 166])
+        .raw(&[133, 66, 142, 128, 66])
+        .raw(&rule_v0(132, 10, 20))
+        .raw(&rule_v0(137, 0, 30))
+        .raw(&[239, 3, b'a', b'b', b'c', 235, 1])
+        .raw(&op_i32_v0(242, 2))
+        .raw(b"hi")
+        .eop();
+    let bytes = dvi.finish(&font);
+    let tfms = cmr_tfms_v0();
+    let document = read_dvi_with_char_widths_v0(&bytes, tfm_char_width_v0(&tfms))
+        .expect("full opcode file should read");
+    let page = &document.pages[0];
+    assert_eq!(page.counts[0], 7);
+    let half = TEN_PT_SP / 2;
+    let quarter = TEN_PT_SP / 4;
+    let pushed_h = half + 16 + 32 + 32 + 256 + 256;
+    let pushed_v = 5 + 7 + 7 - 3 - 3;
+    assert_eq!(
+        page.glyphs,
+        vec![
+            glyph_v0(65, 0, 0, half),
+            glyph_v0(66, pushed_h, pushed_v, quarter),
+            glyph_v0(66, half, 0, quarter),
+        ]
+    );
+    assert_eq!(
+        page.rules,
+        vec![DviRuleV0 {
+            h: half + quarter,
+            v: 0,
+            width: 20,
+            height: 10,
+        }]
+    );
+    assert_eq!(
+        page.specials,
+        vec![
+            DviSpecialV0 {
+                h: half + quarter + 20,
+                v: 0,
+                glyph_index: 3,
+                rule_index: 1,
+                bytes: b"abc".to_vec(),
+            },
+            DviSpecialV0 {
+                h: half + quarter + 20,
+                v: 0,
+                glyph_index: 3,
+                rule_index: 1,
+                bytes: b"hi".to_vec(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn reads_xdv_native_glyph_runs() {
+    let mut dvi = TestDviV0::new(XDV_ID_V0);
+    dvi.bop(1)
+        .raw(&native_font_def_v0(
+            3,
+            TEN_PT_SP,
+            b"DejaVuSans.ttf",
+            0xff00_00ff,
+        ))
+        .raw(&[235, 3, 157, 100]);
+    let mut run = vec![253];
+    run.extend_from_slice(&300i32.to_be_bytes());
+    run.extend_from_slice(&2u16.to_be_bytes());
+    for (x, y) in [(0i32, 0i32), (120, -4)] {
+        run.extend_from_slice(&x.to_be_bytes());
+        run.extend_from_slice(&y.to_be_bytes());
+    }
+    run.extend_from_slice(&[0, 36, 0, 57]);
+    let mut text_run = vec![254];
+    text_run.extend_from_slice(&1u16.to_be_bytes());
+    text_run.extend_from_slice(&(b'A' as u16).to_be_bytes());
+    text_run.extend_from_slice(&50i32.to_be_bytes());
+    text_run.extend_from_slice(&1u16.to_be_bytes());
+    text_run.extend_from_slice(&[0u8; 8]);
+    text_run.extend_from_slice(&[0, 36]);
+    dvi.raw(&run).raw(&text_run).eop();
+    let document = read_dvi_v0(&dvi.finish(&[])).expect("xdv should read");
+    let DviFontKindV0::Native(font) = &document.font_v0(3).expect("native font").kind else {
+        panic!("expected native font");
+    };
+    assert_eq!(font.filename, b"DejaVuSans.ttf");
+    assert_eq!(font.rgba, Some(0xff00_00ff));
+    let glyphs = document.pages[0]
+        .glyphs
+        .iter()
+        .map(|glyph| (glyph.code, glyph.native, glyph.h, glyph.v, glyph.width))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        glyphs,
+        vec![
+            (36, true, 0, 100, 120),
+            (57, true, 120, 96, 180),
+            (36, true, 300, 100, 50),
+        ]
+    );
+}
+
+fn read_error_kind_v0(bytes: &[u8]) -> DviReadErrorKindV0 {
+    read_dvi_v0(bytes)
+        .expect_err("malformed dvi must fail")
+        .kind
+}
+
+fn single_page_v0(body: &[u8]) -> Vec<u8> {
+    let mut dvi = TestDviV0::new(2);
+    dvi.raw(&fnt_def1_v0(0, 0, 0, b"carreltex-v0"))
+        .bop(1)
+        .raw(body)
+        .eop();
+    dvi.finish(&[])
+}
+
+#[test]
+fn rejects_stack_and_font_misuse() {
+    assert_eq!(
+        read_error_kind_v0(&single_page_v0(&[142])),
+        DviReadErrorKindV0::StackUnderflow
+    );
+    let mut unbalanced = TestDviV0::new(2);
+    unbalanced.set_max_stack_depth(1).bop(1).raw(&[141]).eop();
+    assert_eq!(
+        read_error_kind_v0(&unbalanced.finish(&[])),
+        DviReadErrorKindV0::StackNotEmptyAtEop
+    );
+    assert_eq!(
+        read_error_kind_v0(&single_page_v0(&[65])),
+        DviReadErrorKindV0::FontNotSelected
+    );
+    assert_eq!(
+        read_error_kind_v0(&single_page_v0(&[172])),
+        DviReadErrorKindV0::FontUndefined
+    );
+    let redefined = single_page_v0(&fnt_def1_v0(0, 1, 0, b"carreltex-v0"));
+    assert_eq!(
+        read_error_kind_v0(&redefined),
+        DviReadErrorKindV0::FontRedefined
+    );
+}
+
+#[test]
+fn rejects_char_without_width_metrics() {
+    let mut dvi = TestDviV0::new(2);
+    dvi.raw(&fnt_def1_v0(1, 0, TEN_PT_SP, b"cmr10"))
+        .bop(1)
+        .raw(&[172, 65])
+        .eop();
+    let bytes = dvi.finish(&[]);
+    let error = read_dvi_v0(&bytes).expect_err("unknown metrics must fail");
+    assert_eq!(error.kind, DviReadErrorKindV0::CharWidthUnavailable);
+    assert_eq!(error.reason_v0(), "dvi_char_width_unavailable");
+    let tfms = cmr_tfms_v0();
+    let missing_char = {
+        let mut dvi = TestDviV0::new(2);
+        dvi.raw(&fnt_def1_v0(1, 0, TEN_PT_SP, b"cmr10"))
+            .bop(1)
+            .raw(&[172, 90])
+            .eop();
+        dvi.finish(&[])
+    };
+    assert_eq!(
+        read_dvi_with_char_widths_v0(&missing_char, tfm_char_width_v0(&tfms))
+            .unwrap_err()
+            .kind,
+        DviReadErrorKindV0::CharWidthUnavailable
+    );
+}
+
+#[test]
+fn rejects_structural_corruption_with_offsets() {
+    let good = single_page_v0(&[]);
+    assert!(read_dvi_v0(&good).is_ok());
+
+    assert_eq!(read_error_kind_v0(&[]), DviReadErrorKindV0::Truncated);
+    let mut bad_trailer = good.clone();
+    *bad_trailer.last_mut().expect("non-empty") = 0;
+    assert_eq!(
+        read_error_kind_v0(&bad_trailer),
+        DviReadErrorKindV0::TrailerInvalid
+    );
+    let mut wrong_id = good.clone();
+    wrong_id[1] = 3;
+    assert_eq!(
+        read_error_kind_v0(&wrong_id),
+        DviReadErrorKindV0::IdUnsupported
+    );
+
+    let xdv_op_in_dvi = single_page_v0(&[253]);
+    let error = read_dvi_v0(&xdv_op_in_dvi).expect_err("xdv opcode must fail in dvi");
+    assert_eq!(error.kind, DviReadErrorKindV0::OpcodeUndefined(253));
+    assert_eq!(xdv_op_in_dvi[error.offset], 253);
+
+    let mut bad_chain = TestDviV0::new(2);
+    bad_chain.bop(1).eop();
+    let mut bytes = bad_chain.finish(&[]);
+    let prev_pointer = 19 + 1 + 40;
+    bytes[prev_pointer..prev_pointer + 4].copy_from_slice(&0i32.to_be_bytes());
+    assert_eq!(
+        read_error_kind_v0(&bytes),
+        DviReadErrorKindV0::BopChainInvalid
+    );
+
+    let mut outside = TestDviV0::new(2);
+    outside.raw(&[65]);
+    assert_eq!(
+        read_error_kind_v0(&outside.finish(&[])),
+        DviReadErrorKindV0::OpcodeOutsidePage(65)
+    );
+
+    let mut bad_count = good.clone();
+    let trailer_len = good.iter().rev().take_while(|byte| **byte == 223).count();
+    let post_post = good.len() - trailer_len - 6;
+    let post = u32::from_be_bytes([
+        good[post_post + 1],
+        good[post_post + 2],
+        good[post_post + 3],
+        good[post_post + 4],
+    ]) as usize;
+    let total_pages = post + 27;
+    bad_count[total_pages..total_pages + 2].copy_from_slice(&2u16.to_be_bytes());
+    assert_eq!(
+        read_error_kind_v0(&bad_count),
+        DviReadErrorKindV0::PostambleInvalid
+    );
+
+    let oversized_special = single_page_v0(&op_i32_v0(242, -1));
+    assert_eq!(
+        read_error_kind_v0(&oversized_special),
+        DviReadErrorKindV0::SpecialLengthInvalid
+    );
+}
+
+#[test]
+fn tfm_widths_and_params_scale_like_tex() {
+    let tfm = parse_tfm_v0(&build_tfm_v0(
+        48,
+        &[HALF_FIX_WORD, 0xfff8_0000],
+        &[0, QUARTER_FIX_WORD],
+    ))
+    .expect("tfm should parse");
+    assert_eq!(tfm.checksum, 0x1234_5678);
+    assert_eq!(tfm.design_size, 10 << 20);
+    assert_eq!(tfm.scaled_width_v0(48, TEN_PT_SP), Some(TEN_PT_SP / 2));
+    assert_eq!(tfm.scaled_width_v0(49, TEN_PT_SP), Some(-TEN_PT_SP / 2));
+    assert_eq!(tfm.scaled_width_v0(47, TEN_PT_SP), None);
+    assert_eq!(tfm.scaled_width_v0(50, TEN_PT_SP), None);
+    assert_eq!(tfm.scaled_param_v0(2, TEN_PT_SP), Some(TEN_PT_SP / 4));
+    assert_eq!(tfm.scaled_param_v0(1, TEN_PT_SP), None);
+    assert_eq!(tfm.param_count_v0(), 2);
+
+    let mut truncated = build_tfm_v0(48, &[HALF_FIX_WORD], &[]);
+    truncated.pop();
+    assert!(parse_tfm_v0(&truncated).is_none());
+}
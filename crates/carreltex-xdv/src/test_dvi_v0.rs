@@ -0,0 +1,137 @@
+/// Hand-assembled DVI/XDV files for reader tests; pointers and page counts are
+/// filled in by `finish`.
+pub(crate) struct TestDviV0 {
+    out: Vec<u8>,
+    id: u8,
+    bops: Vec<usize>,
+    max_stack_depth: u16,
+}
+
+pub(crate) fn fnt_def1_v0(font_num: u8, checksum: u32, scale: i32, name: &[u8]) -> Vec<u8> {
+    let mut out = vec![243, font_num];
+    out.extend_from_slice(&checksum.to_be_bytes());
+    out.extend_from_slice(&scale.to_be_bytes());
+    out.extend_from_slice(&scale.to_be_bytes());
+    out.push(0);
+    out.push(name.len() as u8);
+    out.extend_from_slice(name);
+    out
+}
+
+pub(crate) fn native_font_def_v0(font_num: i32, size: i32, filename: &[u8], rgba: u32) -> Vec<u8> {
+    let mut out = vec![252];
+    out.extend_from_slice(&font_num.to_be_bytes());
+    out.extend_from_slice(&size.to_be_bytes());
+    out.extend_from_slice(&0x0200u16.to_be_bytes());
+    out.push(filename.len() as u8);
+    out.extend_from_slice(filename);
+    out.extend_from_slice(&0u32.to_be_bytes());
+    out.extend_from_slice(&rgba.to_be_bytes());
+    out
+}
+
+pub(crate) fn op_i32_v0(opcode: u8, value: i32) -> Vec<u8> {
+    let mut out = vec![opcode];
+    out.extend_from_slice(&value.to_be_bytes());
+    out
+}
+
+pub(crate) fn rule_v0(opcode: u8, height: i32, width: i32) -> Vec<u8> {
+    let mut out = op_i32_v0(opcode, height);
+    out.extend_from_slice(&width.to_be_bytes());
+    out
+}
+
+impl TestDviV0 {
+    pub(crate) fn new(id: u8) -> Self {
+        let mut out = vec![247, id];
+        out.extend_from_slice(&25_400_000u32.to_be_bytes());
+        out.extend_from_slice(&473_628_672u32.to_be_bytes());
+        out.extend_from_slice(&1000u32.to_be_bytes());
+        out.push(4);
+        out.extend_from_slice(b"test");
+        TestDviV0 {
+            out,
+            id,
+            bops: Vec::new(),
+            max_stack_depth: 0,
+        }
+    }
+
+    pub(crate) fn raw(&mut self, bytes: &[u8]) -> &mut Self {
+        self.out.extend_from_slice(bytes);
+        self
+    }
+
+    pub(crate) fn set_max_stack_depth(&mut self, depth: u16) -> &mut Self {
+        self.max_stack_depth = depth;
+        self
+    }
+
+    pub(crate) fn bop(&mut self, count0: i32) -> &mut Self {
+        let prev = self.bops.last().map(|offset| *offset as i32).unwrap_or(-1);
+        self.bops.push(self.out.len());
+        self.out.push(139);
+        self.out.extend_from_slice(&count0.to_be_bytes());
+        for _ in 1..10 {
+            self.out.extend_from_slice(&0i32.to_be_bytes());
+        }
+        self.out.extend_from_slice(&prev.to_be_bytes());
+        self
+    }
+
+    pub(crate) fn eop(&mut self) -> &mut Self {
+        self.out.push(140);
+        self
+    }
+
+    pub(crate) fn finish(&mut self, postamble_fonts: &[u8]) -> Vec<u8> {
+        let mut out = self.out.clone();
+        let post_offset = out.len() as u32;
+        let last_bop = self.bops.last().map(|offset| *offset as i32).unwrap_or(-1);
+        out.push(248);
+        out.extend_from_slice(&last_bop.to_be_bytes());
+        out.extend_from_slice(&25_400_000u32.to_be_bytes());
+        out.extend_from_slice(&473_628_672u32.to_be_bytes());
+        out.extend_from_slice(&1000u32.to_be_bytes());
+        out.extend_from_slice(&0u32.to_be_bytes());
+        out.extend_from_slice(&0u32.to_be_bytes());
+        out.extend_from_slice(&self.max_stack_depth.to_be_bytes());
+        out.extend_from_slice(&(self.bops.len() as u16).to_be_bytes());
+        out.extend_from_slice(postamble_fonts);
+        out.push(249);
+        out.extend_from_slice(&post_offset.to_be_bytes());
+        out.push(self.id);
+        let padding = 4 + (4 - out.len() % 4) % 4;
+        out.extend(std::iter::repeat_n(223u8, padding));
+        out
+    }
+}
+
+/// Minimal TFM with characters `bc..=bc+widths.len()-1`, each with its own width
+/// (fix_word, design-size units), and `params` as fix_words.
+pub(crate) fn build_tfm_v0(bc: u16, widths: &[u32], params: &[u32]) -> Vec<u8> {
+    let lh = 2u16;
+    let ec = bc + widths.len() as u16 - 1;
+    let nw = widths.len() as u16 + 1;
+    let np = params.len() as u16;
+    let lf = 6 + lh + widths.len() as u16 + nw + 1 + 1 + 1 + np;
+    let mut out = Vec::<u8>::new();
+    for value in [lf, lh, bc, ec, nw, 1, 1, 1, 0, 0, 0, np] {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    out.extend_from_slice(&0x1234_5678u32.to_be_bytes());
+    out.extend_from_slice(&(10u32 << 20).to_be_bytes());
+    for char_index in 0..widths.len() {
+        out.extend_from_slice(&[(char_index + 1) as u8, 0, 0, 0]);
+    }
+    out.extend_from_slice(&0u32.to_be_bytes());
+    for width in widths {
+        out.extend_from_slice(&width.to_be_bytes());
+    }
+    out.extend_from_slice(&[0u8; 12]);
+    for param in params {
+        out.extend_from_slice(&param.to_be_bytes());
+    }
+    out
+}
@@ -1,11 +1,20 @@
 use super::{
     count_dvi_v2_text_movements_v0, count_dvi_v2_text_pages_v0,
-    count_dvi_v2_text_pages_with_advance_v0, validate_dvi_v2_empty_page_v0,
-    sum_dvi_v2_positive_right3_amounts_with_layout_v0,
-    validate_dvi_v2_text_page_v0, write_dvi_v2_empty_page_v0, write_dvi_v2_text_page_v0,
-    write_dvi_v2_text_page_with_advance_v0, write_dvi_v2_text_page_with_layout_and_wrap_v0,
+    count_dvi_v2_text_pages_with_advance_v0, read_dvi_v0,
+    sum_dvi_v2_positive_right3_amounts_with_layout_v0, text_page_starts_v0,
+    validate_dvi_color_stack_v0, validate_dvi_v2_empty_page_v0, validate_dvi_v2_text_page_v0,
+    write_dvi_v2_empty_page_v0, write_dvi_v2_text_page_v0, write_dvi_v2_text_page_with_advance_v0,
+    write_dvi_v2_text_page_with_layout_and_wrap_v0,
+    write_dvi_v2_text_page_with_layout_paging_and_marks_v0,
+    write_dvi_v2_text_page_with_layout_paging_and_specials_v0,
+    write_dvi_v2_text_page_with_layout_paging_specials_and_rules_v0,
     write_dvi_v2_text_page_with_layout_v0, write_dvi_v2_text_page_with_layout_wrap_and_paging_v0,
-    DVI_DOWN3, DVI_EOP, DVI_FNT_DEF1, DVI_PRE, DVI_RIGHT3, DVI_TRAILER_BYTE,
+    DviColorStackV0, DviExtentsV0, DviFontKindV0, DviRuleV0, DviTextFillV0, DviTextFontSwitchV0,
+    DviTextFontV0, DviTextIndentV0, DviTextMarksV0, DviTextRuleV0, DviTextSpecialV0, DVI_DOWN3,
+    DVI_EOP, DVI_FNT_DEF1, DVI_PRE, DVI_RIGHT3, DVI_TRAILER_BYTE, DVI_XXX1, DVI_XXX4,
+    MAX_DVI_COLOR_STACK_DEPTH_V0, MAX_DVI_RULE_DIMENSION_SP_V0, MAX_DVI_SPECIALS_V0,
+    MAX_DVI_SPECIAL_BYTES_V0, MAX_DVI_TEXT_FONTS_V0, MAX_DVI_TEXT_INDENTS_V0,
+    MAX_DVI_TEXT_RULES_V0,
 };
 
 #[test]
@@ -81,8 +90,9 @@ fn text_writer_uses_per_glyph_metrics_for_right3_amounts() {
     assert!(validate_dvi_v2_text_page_v0(&bytes));
     let movement = count_dvi_v2_text_movements_v0(&bytes).expect("movement summary should parse");
     assert_eq!(movement, (3, 0, 0, 0, 1));
-    let total = sum_dvi_v2_positive_right3_amounts_with_layout_v0(&bytes, glyph_advance_sp, 786_432)
-        .expect("sum parser should parse");
+    let total =
+        sum_dvi_v2_positive_right3_amounts_with_layout_v0(&bytes, glyph_advance_sp, 786_432)
+            .expect("sum parser should parse");
     assert_eq!(total, (65_536 * 5 / 2) as u32);
 }
 
@@ -286,3 +296,672 @@ fn write_with_paging_limit_splits_into_multiple_pages() {
     let pages = count_dvi_v2_text_pages_v0(&bytes).expect("page count");
     assert!(pages >= 2);
 }
+
+fn specials_v0(anchors: &[(usize, &[u8])]) -> Vec<DviTextSpecialV0> {
+    anchors
+        .iter()
+        .map(|(offset, bytes)| DviTextSpecialV0 {
+            offset: *offset,
+            bytes: bytes.to_vec(),
+        })
+        .collect()
+}
+
+fn write_with_specials_v0(text: &[u8], specials: &[DviTextSpecialV0]) -> Option<Vec<u8>> {
+    write_dvi_v2_text_page_with_layout_paging_and_specials_v0(
+        text, specials, 65_536, 786_432, 80, 200,
+    )
+}
+
+#[test]
+fn text_writer_places_specials_at_their_anchors() {
+    let long = vec![b'x'; 300];
+    let specials = specials_v0(&[
+        (0, b"first"),
+        (1, b"mid"),
+        (2, b"line end"),
+        (3, &long),
+        (5, b"last"),
+    ]);
+    let bytes =
+        write_with_specials_v0(b"ab\ncd", &specials).expect("writer should accept specials");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+    assert_eq!(
+        count_dvi_v2_text_movements_v0(&bytes),
+        Some((5, 0, 0, 1, 1))
+    );
+    assert!(bytes.windows(2).any(|pair| pair == [DVI_XXX1, 5]));
+    assert!(bytes
+        .windows(5)
+        .any(|window| window == [DVI_XXX4, 0, 0, 1, 44]));
+
+    let document = read_dvi_v0(&bytes).expect("reader should accept specials");
+    let placed = document.pages[0]
+        .specials
+        .iter()
+        .map(|special| (special.h, special.v, special.bytes.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        placed,
+        vec![
+            (0, 0, 5),
+            (65_536, 0, 3),
+            (131_072, 0, 8),
+            (0, 786_432, 300),
+            (131_072, 786_432, 4),
+        ]
+    );
+}
+
+#[test]
+fn text_writer_keeps_specials_across_wraps_and_pages() {
+    let specials = specials_v0(&[(2, b"wrap"), (5, b"page"), (6, b"next")]);
+    let bytes = write_dvi_v2_text_page_with_layout_paging_and_specials_v0(
+        b"ab cd\x0cef",
+        &specials,
+        65_536,
+        786_432,
+        2,
+        200,
+    )
+    .expect("writer should accept specials");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+    let document = read_dvi_v0(&bytes).expect("reader should accept specials");
+    assert_eq!(document.pages.len(), 2);
+    assert_eq!(document.pages[0].specials[0].bytes, b"wrap");
+    assert_eq!(
+        (
+            document.pages[0].specials[0].h,
+            document.pages[0].specials[0].v
+        ),
+        (131_072, 0)
+    );
+    assert_eq!(document.pages[0].specials[1].bytes, b"page");
+    assert_eq!(document.pages[1].specials[0].bytes, b"next");
+    assert_eq!(
+        write_with_specials_v0(b"ab", &[]),
+        write_dvi_v2_text_page_v0(b"ab")
+    );
+}
+
+#[test]
+fn text_writer_rejects_misplaced_or_oversized_specials() {
+    assert_eq!(
+        write_with_specials_v0(b"ab", &specials_v0(&[(3, b"x")])),
+        None
+    );
+    assert_eq!(
+        write_with_specials_v0(b"ab", &specials_v0(&[(1, b"x"), (0, b"y")])),
+        None
+    );
+    let oversized = vec![b'x'; MAX_DVI_SPECIAL_BYTES_V0 + 1];
+    assert_eq!(
+        write_with_specials_v0(b"ab", &specials_v0(&[(0, &oversized)])),
+        None
+    );
+    let too_many = vec![
+        DviTextSpecialV0 {
+            offset: 0,
+            bytes: b"x".to_vec(),
+        };
+        MAX_DVI_SPECIALS_V0 + 1
+    ];
+    assert_eq!(write_with_specials_v0(b"ab", &too_many), None);
+    let at_cap = &too_many[..MAX_DVI_SPECIALS_V0];
+    let bytes = write_with_specials_v0(b"ab", at_cap).expect("cap count should be accepted");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+}
+
+#[test]
+fn validator_rejects_malformed_specials() {
+    let bytes = write_with_specials_v0(b"ab", &specials_v0(&[(1, b"s")]))
+        .expect("writer should accept specials");
+    let special_index = bytes
+        .windows(3)
+        .position(|window| window == [DVI_XXX1, 1, b's'])
+        .expect("xxx1 should exist");
+
+    // A special between a glyph and its width move breaks the glyph/advance pairing.
+    let mut split = bytes.clone();
+    split.drain(special_index..special_index + 3);
+    let glyph_index = split
+        .iter()
+        .position(|byte| *byte == b'a')
+        .expect("glyph should exist");
+    split.splice(glyph_index + 1..glyph_index + 1, [DVI_XXX1, 1, b's']);
+    assert!(!validate_dvi_v2_text_page_v0(&split));
+
+    // xxx4 must not be used for payloads that fit xxx1.
+    let mut short_xxx4 = bytes.clone();
+    short_xxx4.splice(special_index..special_index + 2, [DVI_XXX4, 0, 0, 0, 1]);
+    assert!(!validate_dvi_v2_text_page_v0(&short_xxx4));
+
+    let mut truncated = bytes.clone();
+    truncated[special_index + 1] = 200;
+    assert!(!validate_dvi_v2_text_page_v0(&truncated));
+}
+
+#[test]
+fn color_stack_balances_across_pages() {
+    let specials = specials_v0(&[
+        (0, b"color push rgb 1 0 0"),
+        (1, b"color push gray 0.5"),
+        (2, b"color pop"),
+        (3, b"papersize=1in,1in"),
+        (4, b"color pop"),
+    ]);
+    let bytes =
+        write_with_specials_v0(b"ab\x0ccd", &specials).expect("writer should accept colors");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+    let document = read_dvi_v0(&bytes).expect("reader should accept colors");
+    assert_eq!(document.pages.len(), 2);
+    assert!(validate_dvi_color_stack_v0(&document));
+
+    for unbalanced in [
+        specials_v0(&[(0, b"color push rgb 1 0 0")]),
+        specials_v0(&[(0, b"color pop")]),
+        specials_v0(&[
+            (0, b"color push rgb 1 0 0"),
+            (1, b"color pop"),
+            (2, b"color pop"),
+        ]),
+        specials_v0(&[(0, b"color push"), (1, b"color pop")]),
+    ] {
+        let bytes =
+            write_with_specials_v0(b"ab", &unbalanced).expect("writer does not track colors");
+        assert!(!validate_dvi_v2_text_page_v0(&bytes), "{unbalanced:?}");
+        let document = read_dvi_v0(&bytes).expect("reader does not track colors");
+        assert!(!validate_dvi_color_stack_v0(&document), "{unbalanced:?}");
+    }
+}
+
+#[test]
+fn color_stack_caps_depth_and_ignores_other_specials() {
+    let mut stack = DviColorStackV0::default();
+    assert_eq!(stack.apply_special_v0(b"colorpush rgb 1 0 0"), Some(()));
+    assert_eq!(stack.apply_special_v0(b"color rgb 1 0 0"), Some(()));
+    assert_eq!(stack.depth_v0(), 0);
+    for _ in 0..MAX_DVI_COLOR_STACK_DEPTH_V0 {
+        assert_eq!(
+            stack.apply_special_v0(b" color  push cmyk 0 1 0 0"),
+            Some(())
+        );
+    }
+    assert_eq!(stack.apply_special_v0(b"color push gray 0"), None);
+    assert_eq!(stack.apply_special_v0(b"color pop now"), None);
+    assert_eq!(stack.depth_v0(), MAX_DVI_COLOR_STACK_DEPTH_V0);
+}
+
+fn rule_v0(
+    offset: usize,
+    special_index: usize,
+    width: i32,
+    height: i32,
+    raise: i32,
+    advance: bool,
+) -> DviTextRuleV0 {
+    DviTextRuleV0 {
+        offset,
+        special_index,
+        width,
+        height,
+        raise,
+        advance,
+    }
+}
+
+fn write_with_rules_v0(
+    text: &[u8],
+    specials: &[DviTextSpecialV0],
+    rules: &[DviTextRuleV0],
+) -> Option<Vec<u8>> {
+    write_dvi_v2_text_page_with_layout_paging_specials_and_rules_v0(
+        text, specials, rules, 65_536, 786_432, 80, 200,
+    )
+}
+
+#[test]
+fn text_writer_sets_and_puts_rules_at_their_anchors() {
+    let specials = specials_v0(&[(1, b"color push gray 0.5"), (1, b"color pop")]);
+    let rules = [
+        rule_v0(1, 1, 131_072, 26_214, 0, true),
+        rule_v0(2, 2, 655_360, 26_214, -65_536, false),
+        rule_v0(3, 2, 26_214, 786_432, -196_608, true),
+    ];
+    let bytes =
+        write_with_rules_v0(b"ab\nc", &specials, &rules).expect("writer should accept rules");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+    assert_eq!(
+        count_dvi_v2_text_movements_v0(&bytes),
+        Some((4, 0, 0, 1, 1))
+    );
+    let reset = bytes
+        .windows(4)
+        .position(|window| window[0] == DVI_RIGHT3 && window[1] == 0xfc)
+        .expect("line reset");
+    // a + rule + b = 65_536 + 131_072 + 65_536.
+    assert_eq!(
+        &bytes[reset + 1..reset + 4],
+        &(-262_144i32).to_be_bytes()[1..]
+    );
+    assert!(bytes
+        .windows(9)
+        .any(|window| window[0] == 132 && window[1..5] == 26_214i32.to_be_bytes()));
+
+    let document = read_dvi_v0(&bytes).expect("reader should accept rules");
+    let page = &document.pages[0];
+    assert_eq!(
+        page.rules,
+        vec![
+            DviRuleV0 {
+                h: 65_536,
+                v: 0,
+                width: 131_072,
+                height: 26_214,
+            },
+            DviRuleV0 {
+                h: 262_144,
+                v: 65_536,
+                width: 655_360,
+                height: 26_214,
+            },
+            DviRuleV0 {
+                h: 0,
+                v: 786_432 + 196_608,
+                width: 26_214,
+                height: 786_432,
+            },
+        ]
+    );
+    assert_eq!(
+        page.specials
+            .iter()
+            .map(|special| (special.glyph_index, special.rule_index))
+            .collect::<Vec<_>>(),
+        vec![(1, 0), (1, 1)]
+    );
+    assert_eq!(page.glyphs[2].h, 26_214);
+    assert_eq!(
+        page.extents_v0(),
+        Some(DviExtentsV0 {
+            min_h: 0,
+            min_v: -26_214,
+            max_h: 917_504,
+            max_v: 786_432 + 196_608,
+        })
+    );
+    // The put rule reaches past the widest line of glyphs.
+    assert_eq!(document.postamble.max_height_depth, 917_504);
+}
+
+#[test]
+fn text_writer_rejects_inconsistent_rules() {
+    let special = specials_v0(&[(1, b"x")]);
+    for (specials, rules) in [
+        (vec![], vec![rule_v0(3, 0, 1, 1, 0, true)]),
+        (vec![], vec![rule_v0(0, 0, -1, 1, 0, true)]),
+        (
+            vec![],
+            vec![rule_v0(0, 0, 1, MAX_DVI_RULE_DIMENSION_SP_V0 + 1, 0, true)],
+        ),
+        (
+            vec![],
+            vec![rule_v0(1, 0, 1, 1, 0, true), rule_v0(0, 0, 1, 1, 0, true)],
+        ),
+        (vec![], vec![rule_v0(0, 1, 1, 1, 0, true)]),
+        (special.clone(), vec![rule_v0(0, 1, 1, 1, 0, true)]),
+        (special.clone(), vec![rule_v0(2, 0, 1, 1, 0, true)]),
+    ] {
+        assert_eq!(
+            write_with_rules_v0(b"ab", &specials, &rules),
+            None,
+            "{rules:?}"
+        );
+    }
+    let too_many = vec![rule_v0(0, 0, 1, 1, 0, true); MAX_DVI_TEXT_RULES_V0 + 1];
+    assert_eq!(write_with_rules_v0(b"ab", &[], &too_many), None);
+    let bytes =
+        write_with_rules_v0(b"ab", &[], &too_many[1..]).expect("cap count should be accepted");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+}
+
+#[test]
+fn validator_rejects_malformed_rules() {
+    let bytes = write_with_rules_v0(b"ab", &[], &[rule_v0(1, 0, 65_536, 1, 5, true)])
+        .expect("writer should accept rules");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+    let rule_index = bytes
+        .iter()
+        .position(|byte| *byte == 160)
+        .expect("raised rule starts with down4");
+
+    // The closing down4 must undo the opening one.
+    let mut unbalanced = bytes.clone();
+    unbalanced[rule_index + 17] ^= 1;
+    assert!(!validate_dvi_v2_text_page_v0(&unbalanced));
+
+    let mut negative = bytes.clone();
+    negative[rule_index + 6] = 0xff;
+    assert!(!validate_dvi_v2_text_page_v0(&negative));
+
+    // A set_rule advance must be part of the line reset.
+    let mut put = bytes.clone();
+    put[rule_index + 5] = 137;
+    assert!(!validate_dvi_v2_text_page_v0(&put));
+
+    let mut split = bytes.clone();
+    let rule = split.drain(rule_index..rule_index + 19).collect::<Vec<_>>();
+    let glyph_index = split.iter().position(|byte| *byte == b'a').expect("glyph");
+    split.splice(glyph_index + 1..glyph_index + 1, rule);
+    assert!(!validate_dvi_v2_text_page_v0(&split));
+}
+
+fn indent_v0(
+    offset: usize,
+    left_sp: i32,
+    label_len: usize,
+    label_h_sp: Option<i32>,
+) -> DviTextIndentV0 {
+    DviTextIndentV0 {
+        offset,
+        left_sp,
+        label_len,
+        label_sep_sp: 32_768,
+        label_h_sp,
+    }
+}
+
+fn write_with_indents_v0(
+    text: &[u8],
+    indents: &[DviTextIndentV0],
+    max_line_glyphs: usize,
+) -> Option<Vec<u8>> {
+    let marks = DviTextMarksV0 {
+        indents,
+        ..DviTextMarksV0::default()
+    };
+    write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+        text,
+        &marks,
+        65_536,
+        786_432,
+        max_line_glyphs,
+        200,
+    )
+}
+
+#[test]
+fn text_writer_fills_gaps_with_aligned_leaders() {
+    let fills = [
+        DviTextFillV0 {
+            offset: 2,
+            to_h: 6 * 65_536,
+            leader_pitch_sp: Some(65_536),
+        },
+        DviTextFillV0 {
+            offset: 2,
+            to_h: 9 * 65_536,
+            leader_pitch_sp: None,
+        },
+    ];
+    let marks = DviTextMarksV0 {
+        fills: &fills,
+        ..DviTextMarksV0::default()
+    };
+    let bytes = write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+        b"ab1", &marks, 65_536, 786_432, 3, 200,
+    )
+    .expect("writer should accept fills");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+    let document = read_dvi_v0(&bytes).expect("reader should accept fills");
+    let glyphs = document.pages[0]
+        .glyphs
+        .iter()
+        .map(|glyph| (glyph.code as u8, glyph.h))
+        .collect::<Vec<_>>();
+    // Dots sit centered in the 1em boxes between the text and 6em; fills do not
+    // count towards the three-glyph line.
+    assert_eq!(
+        glyphs,
+        vec![
+            (b'a', 0),
+            (b'b', 65_536),
+            (b'.', 147_456),
+            (b'.', 212_992),
+            (b'.', 278_528),
+            (b'.', 344_064),
+            (b'1', 589_824),
+        ]
+    );
+    assert_eq!(document.postamble.max_height_depth, 655_360);
+
+    let bad_fills = [
+        DviTextFillV0 {
+            offset: 4,
+            to_h: 0,
+            leader_pitch_sp: None,
+        },
+        DviTextFillV0 {
+            offset: 0,
+            to_h: -1,
+            leader_pitch_sp: None,
+        },
+        DviTextFillV0 {
+            offset: 0,
+            to_h: 65_536,
+            leader_pitch_sp: Some(0),
+        },
+        DviTextFillV0 {
+            offset: 0,
+            to_h: 65_536,
+            leader_pitch_sp: Some(16_384),
+        },
+    ];
+    for fill in bad_fills {
+        let marks = DviTextMarksV0 {
+            fills: std::slice::from_ref(&fill),
+            ..DviTextMarksV0::default()
+        };
+        assert!(
+            write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+                b"ab1", &marks, 65_536, 786_432, 80, 200
+            )
+            .is_none(),
+            "{fill:?}"
+        );
+    }
+}
+
+#[test]
+fn text_page_starts_follow_page_breaks_wraps_and_page_length() {
+    let marks = DviTextMarksV0::default();
+    assert_eq!(
+        text_page_starts_v0(b"", &marks, 65_536, 80, 200),
+        Some(vec![0])
+    );
+    assert_eq!(
+        text_page_starts_v0(b"ab\x0ccd", &marks, 65_536, 80, 200),
+        Some(vec![0, 3])
+    );
+    // "one two" wraps after "one", so with two lines per page "x" starts the second.
+    assert_eq!(
+        text_page_starts_v0(b"one two\nx\ny", &marks, 65_536, 4, 2),
+        Some(vec![0, 8])
+    );
+    assert_eq!(text_page_starts_v0(b"ab", &marks, 65_536, 0, 200), None);
+}
+
+#[test]
+fn text_writer_indents_lines_and_sets_item_labels() {
+    let text = b"Intro\n*one two three\ncont\n1.x\nout";
+    let indents = [
+        indent_v0(6, 163_840, 1, None),
+        indent_v0(26, 163_840, 2, Some(0)),
+        indent_v0(30, 0, 0, None),
+    ];
+    let bytes = write_with_indents_v0(text, &indents, 8).expect("writer should accept indents");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+    let document = read_dvi_v0(&bytes).expect("reader should accept indents");
+    let mut lines = vec![Vec::<(u8, i32)>::new(); 7];
+    for glyph in &document.pages[0].glyphs {
+        lines[(glyph.v / 786_432) as usize].push((glyph.code as u8, glyph.h));
+    }
+    let starts = lines.iter().map(|line| line[0]).collect::<Vec<_>>();
+    // The bullet hangs 0.5em before the 2.5em margin; wrapped lines keep the margin.
+    assert_eq!(
+        starts,
+        vec![
+            (b'I', 0),
+            (b'*', 65_536),
+            (b't', 163_840),
+            (b't', 163_840),
+            (b'c', 163_840),
+            (b'1', 0),
+            (b'o', 0),
+        ]
+    );
+    assert_eq!(lines[1][1], (b'o', 163_840));
+    assert_eq!(lines[5], vec![(b'1', 0), (b'.', 65_536), (b'x', 131_072)]);
+    // The writer records the widest line, "three" after the margin, in this slot.
+    assert_eq!(document.postamble.max_height_depth, 491_520);
+}
+
+#[test]
+fn text_writer_rejects_misplaced_indents_and_validator_rejects_bad_moves() {
+    let text = b"ab\ncd";
+    for indents in [
+        vec![indent_v0(1, 65_536, 0, None)],
+        vec![indent_v0(3, 65_536, 3, None)],
+        vec![indent_v0(3, -1, 0, None)],
+        vec![indent_v0(3, 65_536, 0, Some(-1))],
+        vec![indent_v0(0, 0, 0, None), indent_v0(0, 65_536, 0, None)],
+        vec![indent_v0(0, 0, 0, None); MAX_DVI_TEXT_INDENTS_V0 + 1],
+    ] {
+        assert_eq!(
+            write_with_indents_v0(text, &indents, 80),
+            None,
+            "{indents:?}"
+        );
+    }
+
+    let bytes = write_with_indents_v0(text, &[indent_v0(3, 65_536, 0, None)], 80).expect("indent");
+    let right4 = bytes
+        .windows(5)
+        .position(|window| window == [146, 0, 1, 0, 0])
+        .expect("right4 to the margin");
+    for amount in [0i32, -65_536] {
+        let mut bad = bytes.clone();
+        bad[right4 + 1..right4 + 5].copy_from_slice(&amount.to_be_bytes());
+        assert!(!validate_dvi_v2_text_page_v0(&bad));
+    }
+}
+
+fn text_font_v0(name: &[u8], size_sp: i32) -> DviTextFontV0 {
+    DviTextFontV0 {
+        name: name.to_vec(),
+        size_sp,
+    }
+}
+
+fn write_with_fonts_v0(
+    text: &[u8],
+    fonts: &[DviTextFontV0],
+    font_switches: &[DviTextFontSwitchV0],
+) -> Option<Vec<u8>> {
+    let marks = DviTextMarksV0 {
+        fonts,
+        font_switches,
+        ..DviTextMarksV0::default()
+    };
+    write_dvi_v2_text_page_with_layout_paging_and_marks_v0(text, &marks, 65_536, 786_432, 80, 200)
+}
+
+#[test]
+fn text_writer_switches_fonts_and_scales_their_glyph_widths() {
+    let fonts = [
+        text_font_v0(b"carreltex-v0-rm-bx-n", 655_360),
+        text_font_v0(b"carreltex-v0-rm-m-n", 786_432),
+    ];
+    let switches = [
+        DviTextFontSwitchV0 { offset: 1, font: 1 },
+        DviTextFontSwitchV0 { offset: 4, font: 2 },
+    ];
+    let bytes = write_with_fonts_v0(b"ab\x0ccde", &fonts, &switches).expect("fonts");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+    let document = read_dvi_v0(&bytes).expect("reader should accept sized fonts");
+    let defined = document
+        .fonts
+        .iter()
+        .map(|font| match &font.kind {
+            DviFontKindV0::Tfm(definition) => {
+                (font.font_num, definition.name.clone(), definition.scale)
+            }
+            other => panic!("unexpected font {other:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        defined,
+        vec![
+            (0, b"carreltex-v0".to_vec(), 0),
+            (1, fonts[0].name.clone(), 655_360),
+            (2, fonts[1].name.clone(), 786_432),
+        ]
+    );
+    let glyphs = document
+        .pages
+        .iter()
+        .map(|page| {
+            page.glyphs
+                .iter()
+                .map(|glyph| (glyph.code as u8, glyph.font_num, glyph.h))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    // The second page starts in the font still in effect; 12pt glyphs are 1.2em wide.
+    assert_eq!(
+        glyphs,
+        vec![
+            vec![(b'a', 0, 0), (b'b', 1, 65_536)],
+            vec![(b'c', 1, 0), (b'd', 2, 65_536), (b'e', 2, 144_179)],
+        ]
+    );
+}
+
+#[test]
+fn text_writer_rejects_bad_fonts_and_validator_rejects_undefined_switches() {
+    let font = text_font_v0(b"carreltex-v0-tt-m-n", 655_360);
+    let switch = |offset, font| DviTextFontSwitchV0 { offset, font };
+    let cases: [(Vec<DviTextFontV0>, Vec<DviTextFontSwitchV0>); 6] = [
+        (vec![font.clone()], vec![switch(0, 2)]),
+        (vec![font.clone()], vec![switch(1, 1), switch(1, 0)]),
+        (vec![font.clone()], vec![switch(6, 1)]),
+        (vec![text_font_v0(b"", 655_360)], Vec::new()),
+        (vec![text_font_v0(b"x", 0)], Vec::new()),
+        (vec![font.clone(); MAX_DVI_TEXT_FONTS_V0 + 1], Vec::new()),
+    ];
+    for (fonts, switches) in cases {
+        assert_eq!(
+            write_with_fonts_v0(b"ab\x0ccd", &fonts, &switches),
+            None,
+            "{fonts:?} {switches:?}"
+        );
+    }
+
+    let bytes = write_with_fonts_v0(b"ab\x0ccd", std::slice::from_ref(&font), &[switch(1, 1)])
+        .expect("font");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+    let fnt_num = bytes
+        .windows(2)
+        .position(|window| window == [172, b'b'])
+        .expect("fnt_num before the switched glyph");
+    let mut undefined = bytes.clone();
+    undefined[fnt_num] = 173;
+    assert!(!validate_dvi_v2_text_page_v0(&undefined));
+    let second_def = bytes
+        .windows(font.name.len())
+        .rposition(|window| window == font.name.as_slice())
+        .expect("second page font definition");
+    let mut redefined = bytes.clone();
+    redefined[second_def + font.name.len() - 1] = b'x';
+    assert!(!validate_dvi_v2_text_page_v0(&redefined));
+}
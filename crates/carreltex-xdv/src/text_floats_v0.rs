@@ -0,0 +1,272 @@
+use std::collections::VecDeque;
+
+use crate::text_footnotes_v0::{DviTextFootnotesV0, TextPageV0};
+use crate::text_layout_v0::TextLineV0;
+use crate::{NEWLINE_MARKER_V0, PAGEBREAK_MARKER_V0};
+
+pub const MAX_DVI_TEXT_FLOATS_V0: usize = 256;
+
+/// LaTeX's float placement specifier: `here` after the line holding the anchor,
+/// at the `top` or `bottom` of a text page, or on a `page` of floats alone;
+/// `force` (`!`) lifts the limits on how many floats a text page takes and how
+/// much of it they fill.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DviTextFloatPlacementV0 {
+    pub here: bool,
+    pub top: bool,
+    pub bottom: bool,
+    pub page: bool,
+    pub force: bool,
+}
+
+/// A float body, the whole lines `start..end` of the text, set on the page holding
+/// the main-text byte at `anchor` or a later one. Bodies follow the main text, and
+/// precede any footnote bodies, one per line range in anchor order, each after a
+/// newline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviTextFloatV0 {
+    pub anchor: usize,
+    pub start: usize,
+    pub end: usize,
+    pub placement: DviTextFloatPlacementV0,
+}
+
+/// `\topnumber`, `\bottomnumber` and `\totalnumber`.
+const TOP_NUMBER_V0: usize = 2;
+const BOTTOM_NUMBER_V0: usize = 1;
+const TOTAL_NUMBER_V0: usize = 3;
+/// `\topfraction`, `\bottomfraction` and `\floatpagefraction`, in tenths of a page.
+const TOP_TENTHS_V0: usize = 7;
+const BOTTOM_TENTHS_V0: usize = 3;
+const FLOAT_PAGE_TENTHS_V0: usize = 5;
+
+/// Offset of the first float body, past every offset without floats.
+pub(crate) fn floats_start_v0(floats: &[DviTextFloatV0]) -> usize {
+    floats.first().map_or(usize::MAX, |float| float.start)
+}
+
+/// End of the main text: the whole text without float and footnote bodies.
+pub(crate) fn floats_main_end_v0(
+    floats: &[DviTextFloatV0],
+    footnotes: &DviTextFootnotesV0<'_>,
+    text_len: usize,
+) -> usize {
+    floats.first().map_or_else(
+        || footnotes.main_end_v0(text_len),
+        |float| float.start.saturating_sub(1),
+    )
+}
+
+/// Bodies must tile the text between the main text and the footnote bodies
+/// without page breaks, anchors must be ordered inside the main text, as must
+/// footnote anchors, and every float needs somewhere to go.
+pub(crate) fn validate_text_floats_v0(
+    text: &[u8],
+    floats: &[DviTextFloatV0],
+    footnotes: &DviTextFootnotesV0<'_>,
+) -> Option<()> {
+    let Some(first) = floats.first() else {
+        return Some(());
+    };
+    let main_end = floats_main_end_v0(floats, footnotes, text.len());
+    if floats.len() > MAX_DVI_TEXT_FLOATS_V0
+        || first.start == 0
+        || text.get(first.start - 1) != Some(&NEWLINE_MARKER_V0)
+        || floats.last()?.end != footnotes.main_end_v0(text.len())
+        || floats
+            .windows(2)
+            .any(|pair| pair[0].anchor > pair[1].anchor || pair[1].start != pair[0].end + 1)
+        || footnotes.notes.iter().any(|note| note.anchor > main_end)
+    {
+        return None;
+    }
+    for float in floats {
+        let body = text.get(float.start..float.end)?;
+        let placement = float.placement;
+        if float.anchor > main_end
+            || body.contains(&PAGEBREAK_MARKER_V0)
+            || (float.end < text.len() && text[float.end] != NEWLINE_MARKER_V0)
+            || !(placement.here || placement.top || placement.bottom || placement.page)
+        {
+            return None;
+        }
+    }
+    Some(())
+}
+
+/// A float set on a page: its index among the text's floats and its lines.
+pub(crate) struct PlacedFloatV0 {
+    pub(crate) index: usize,
+    pub(crate) lines: Vec<TextLineV0>,
+}
+
+/// Places floats on pages the way LaTeX's output routine does, with its default
+/// parameters. A float goes where its line is set when no earlier float is
+/// waiting and it fits there; otherwise it waits, in anchor order, for the start
+/// of a later page, where waiting floats first make float pages and then go to
+/// the top and bottom of the new page, until one does not fit. Floats are set in
+/// the order of their bodies: top floats before here floats before bottom ones.
+pub(crate) struct FloatPlacerV0<'a> {
+    floats: &'a [DviTextFloatV0],
+    bodies: Vec<Vec<TextLineV0>>,
+    next: usize,
+    deferred: VecDeque<usize>,
+    max_lines_per_page: usize,
+}
+
+impl<'a> FloatPlacerV0<'a> {
+    pub(crate) fn new_v0(
+        floats: &'a [DviTextFloatV0],
+        bodies: Vec<Vec<TextLineV0>>,
+        max_lines_per_page: usize,
+    ) -> Self {
+        FloatPlacerV0 {
+            floats,
+            bodies,
+            next: 0,
+            deferred: VecDeque::new(),
+            max_lines_per_page,
+        }
+    }
+
+    fn take_v0(&mut self, index: usize) -> PlacedFloatV0 {
+        PlacedFloatV0 {
+            index,
+            lines: std::mem::take(&mut self.bodies[index]),
+        }
+    }
+
+    /// Places the floats anchored up to `owned_end`, the end of the line just set
+    /// on `page`.
+    pub(crate) fn anchor_through_v0(&mut self, page: &mut TextPageV0, owned_end: usize) {
+        while self
+            .floats
+            .get(self.next)
+            .is_some_and(|float| float.anchor <= owned_end)
+        {
+            let index = self.next;
+            self.next += 1;
+            if !self.deferred.is_empty() || !self.place_v0(page, index, true) {
+                self.deferred.push_back(index);
+            }
+        }
+    }
+
+    /// Tries `here` (when `here_allowed`), then the top, then the bottom of `page`.
+    /// Each float area is set off from the text by a blank line.
+    fn place_v0(&mut self, page: &mut TextPageV0, index: usize, here_allowed: bool) -> bool {
+        let placement = self.floats[index].placement;
+        let height = self.bodies[index].len();
+        let free = self.max_lines_per_page.saturating_sub(page.len_v0());
+        let force = placement.force;
+        let within = |area: &[PlacedFloatV0], number: usize, tenths: usize| {
+            let lines = area
+                .iter()
+                .map(|float| float.lines.len() + 1)
+                .sum::<usize>();
+            force
+                || (area.len() < number
+                    && page.top.len() + page.bottom.len() < TOTAL_NUMBER_V0
+                    && (lines + height + 1) * 10 <= self.max_lines_per_page * tenths)
+        };
+        if here_allowed && placement.here && page.bottom.is_empty() && height + 2 <= free {
+            let after = page.lines.len();
+            let float = self.take_v0(index);
+            page.here.push((after, float));
+            return true;
+        }
+        if placement.top
+            && page.here.is_empty()
+            && page.bottom.is_empty()
+            && height < free
+            && within(&page.top, TOP_NUMBER_V0, TOP_TENTHS_V0)
+        {
+            let float = self.take_v0(index);
+            page.top.push(float);
+            return true;
+        }
+        if placement.bottom
+            && height < free
+            && within(&page.bottom, BOTTOM_NUMBER_V0, BOTTOM_TENTHS_V0)
+        {
+            let float = self.take_v0(index);
+            page.bottom.push(float);
+            return true;
+        }
+        false
+    }
+
+    /// Ends `page` and starts the next text page, after the float pages the
+    /// waiting floats make.
+    pub(crate) fn next_page_v0(
+        &mut self,
+        pages: &mut Vec<TextPageV0>,
+        page: TextPageV0,
+    ) -> TextPageV0 {
+        pages.push(page);
+        while let Some(float_page) = self.float_page_v0(false) {
+            pages.push(float_page);
+        }
+        let mut page = TextPageV0::default();
+        while let Some(index) = self.deferred.front().copied() {
+            if !self.place_v0(&mut page, index, false) {
+                break;
+            }
+            self.deferred.pop_front();
+        }
+        page
+    }
+
+    /// A page of the leading waiting floats, as many as fit and at least one.
+    /// Unless `flush`, only floats allowing `p` go there, and only when they fill
+    /// half the page.
+    fn float_page_v0(&mut self, flush: bool) -> Option<TextPageV0> {
+        let mut count = 0usize;
+        let mut used = 0usize;
+        for index in &self.deferred {
+            let height = self.bodies[*index].len() + 1;
+            if !(flush || self.floats[*index].placement.page)
+                || (count > 0 && used + height > self.max_lines_per_page)
+            {
+                break;
+            }
+            count += 1;
+            used += height;
+        }
+        if count == 0 || (!flush && used * 10 < self.max_lines_per_page * FLOAT_PAGE_TENTHS_V0) {
+            return None;
+        }
+        let mut page = TextPageV0::default();
+        for index in self.deferred.drain(..count).collect::<Vec<_>>() {
+            let float = self.take_v0(index);
+            page.top.push(float);
+        }
+        Some(page)
+    }
+
+    /// Ends the last text page, where floats anchored past its last line may still
+    /// go, then sets every waiting float on float pages, as `\end{document}` does.
+    pub(crate) fn finish_v0(&mut self, pages: &mut Vec<TextPageV0>, mut page: TextPageV0) {
+        self.anchor_through_v0(&mut page, usize::MAX);
+        pages.push(page);
+        while let Some(float_page) = self.float_page_v0(true) {
+            pages.push(float_page);
+        }
+    }
+}
+
+/// Page of every float, 0-based.
+pub(crate) fn float_pages_v0(pages: &[TextPageV0], float_count: usize) -> Vec<usize> {
+    let mut float_pages = vec![0usize; float_count];
+    for (page_index, page) in pages.iter().enumerate() {
+        let placed = page
+            .top
+            .iter()
+            .chain(page.here.iter().map(|(_, float)| float))
+            .chain(page.bottom.iter());
+        for float in placed {
+            float_pages[float.index] = page_index;
+        }
+    }
+    float_pages
+}
@@ -0,0 +1,216 @@
+use super::{
+    read_dvi_v0, text_float_pages_v0, text_page_starts_v0, validate_dvi_v2_text_page_v0,
+    write_dvi_v2_text_page_with_layout_paging_and_marks_v0, DviDocumentV0, DviTextFloatPlacementV0,
+    DviTextFloatV0, DviTextFootnoteV0, DviTextFootnotesV0, DviTextMarksV0, DviTextSpecialV0,
+};
+
+const LINE_SP: i32 = 786_432;
+
+fn write_v0(text: &[u8], marks: &DviTextMarksV0<'_>, max_lines: usize) -> Option<Vec<u8>> {
+    write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+        text, marks, 65_536, LINE_SP, 80, max_lines,
+    )
+}
+
+/// `(glyph, line)` of every page.
+fn page_lines_v0(bytes: &[u8]) -> Vec<Vec<(u8, i32)>> {
+    assert!(validate_dvi_v2_text_page_v0(bytes));
+    let document: DviDocumentV0 =
+        read_dvi_v0(bytes).expect("reader should accept the writer's output");
+    document
+        .pages
+        .iter()
+        .map(|page| {
+            page.glyphs
+                .iter()
+                .map(|glyph| (glyph.code as u8, glyph.v / LINE_SP))
+                .collect()
+        })
+        .collect()
+}
+
+fn placement_v0(spec: &[u8]) -> DviTextFloatPlacementV0 {
+    DviTextFloatPlacementV0 {
+        here: spec.contains(&b'h'),
+        top: spec.contains(&b't'),
+        bottom: spec.contains(&b'b'),
+        page: spec.contains(&b'p'),
+        force: spec.contains(&b'!'),
+    }
+}
+
+fn float_v0(anchor: usize, start: usize, end: usize, spec: &[u8]) -> DviTextFloatV0 {
+    DviTextFloatV0 {
+        anchor,
+        start,
+        end,
+        placement: placement_v0(spec),
+    }
+}
+
+#[test]
+fn floats_go_here_or_at_the_top_or_bottom_of_their_page() {
+    let text = b"aa\nbb\ncc\nX";
+    let write = |spec: &[u8]| {
+        let floats = [float_v0(1, 9, 10, spec)];
+        let marks = DviTextMarksV0 {
+            floats: &floats,
+            ..DviTextMarksV0::default()
+        };
+        page_lines_v0(&write_v0(text, &marks, 10).expect("writer should accept floats"))
+    };
+    // `(glyph line of aa, bb, cc and X)`.
+    let lines = |a: i32, b: i32, c: i32, x: i32| {
+        let mut glyphs = vec![
+            (b'a', a),
+            (b'a', a),
+            (b'b', b),
+            (b'b', b),
+            (b'c', c),
+            (b'c', c),
+        ];
+        let at = glyphs
+            .iter()
+            .position(|glyph| glyph.1 > x)
+            .unwrap_or(glyphs.len());
+        glyphs.insert(at, (b'X', x));
+        vec![glyphs]
+    };
+    assert_eq!(write(b"h"), lines(0, 4, 5, 2));
+    assert_eq!(write(b"tbp"), lines(2, 3, 4, 0));
+    assert_eq!(write(b"b"), lines(0, 1, 2, 4));
+}
+
+#[test]
+fn floats_that_do_not_fit_wait_for_a_later_page() {
+    let text = b"aa\nbb\ncc\ndd\nee\nX\nY";
+    let pages_of = |spec: &[u8]| {
+        let floats = [float_v0(6, 15, 18, spec)];
+        let marks = DviTextMarksV0 {
+            floats: &floats,
+            ..DviTextMarksV0::default()
+        };
+        let bytes = write_v0(text, &marks, 4).expect("writer should defer floats");
+        let pages = page_lines_v0(&bytes)
+            .into_iter()
+            .map(|page| page.into_iter().map(|glyph| glyph.0).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        (
+            pages,
+            text_page_starts_v0(text, &marks, 65_536, 80, 4),
+            text_float_pages_v0(text, &marks, 65_536, 80, 4),
+        )
+    };
+    // A float page takes it before the next text page.
+    assert_eq!(
+        pages_of(b"tbp"),
+        (
+            vec![b"aabbccdd".to_vec(), b"XY".to_vec(), b"ee".to_vec()],
+            Some(vec![0, 12, 12]),
+            Some(vec![1]),
+        )
+    );
+    // `!` lets it fill most of the next page's top.
+    assert_eq!(
+        pages_of(b"!t"),
+        (
+            vec![b"aabbccdd".to_vec(), b"XYee".to_vec()],
+            Some(vec![0, 12]),
+            Some(vec![1]),
+        )
+    );
+    // Too tall for `\topfraction`, it waits for the end of the document.
+    assert_eq!(
+        pages_of(b"t"),
+        (
+            vec![b"aabbccdd".to_vec(), b"ee".to_vec(), b"XY".to_vec()],
+            Some(vec![0, 12, 15]),
+            Some(vec![2]),
+        )
+    );
+}
+
+#[test]
+fn float_and_footnote_bodies_keep_their_marks() {
+    let text = b"a1\nb\nF\nnote";
+    let floats = [float_v0(1, 5, 6, b"t")];
+    let notes = [DviTextFootnoteV0 {
+        anchor: 1,
+        start: 7,
+        end: 11,
+    }];
+    let specials = [
+        DviTextSpecialV0 {
+            offset: 0,
+            bytes: b"main".to_vec(),
+        },
+        DviTextSpecialV0 {
+            offset: 5,
+            bytes: b"float".to_vec(),
+        },
+        DviTextSpecialV0 {
+            offset: 7,
+            bytes: b"note".to_vec(),
+        },
+    ];
+    let marks = DviTextMarksV0 {
+        specials: &specials,
+        floats: &floats,
+        footnotes: DviTextFootnotesV0 {
+            notes: &notes,
+            rule_width: 1,
+            rule_height: 1,
+        },
+        ..DviTextMarksV0::default()
+    };
+    let bytes = write_v0(text, &marks, 8).expect("writer should accept both bodies");
+    let document = read_dvi_v0(&bytes).expect("reader should accept the writer's output");
+    let specials = document.pages[0]
+        .specials
+        .iter()
+        .map(|special| (special.bytes.as_slice(), special.v / LINE_SP))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        specials,
+        vec![
+            (b"float".as_slice(), 0),
+            (b"main".as_slice(), 2),
+            (b"note".as_slice(), 7)
+        ]
+    );
+}
+
+#[test]
+fn text_writer_rejects_misplaced_floats() {
+    let text = b"ab\ncd\nX\nY";
+    for floats in [
+        [float_v0(1, 6, 7, b""), float_v0(4, 8, 9, b"t")],
+        [float_v0(4, 6, 7, b"t"), float_v0(1, 8, 9, b"t")],
+        [float_v0(7, 6, 7, b"t"), float_v0(7, 8, 9, b"t")],
+        [float_v0(1, 5, 7, b"t"), float_v0(4, 8, 9, b"t")],
+        [float_v0(1, 6, 7, b"t"), float_v0(4, 8, 8, b"t")],
+    ] {
+        let marks = DviTextMarksV0 {
+            floats: &floats,
+            ..DviTextMarksV0::default()
+        };
+        assert!(write_v0(text, &marks, 5).is_none(), "{floats:?}");
+    }
+    // A footnote may not be anchored in a float body.
+    let floats = [float_v0(1, 3, 5, b"t")];
+    let notes = [DviTextFootnoteV0 {
+        anchor: 4,
+        start: 6,
+        end: 9,
+    }];
+    let marks = DviTextMarksV0 {
+        floats: &floats,
+        footnotes: DviTextFootnotesV0 {
+            notes: &notes,
+            rule_width: 1,
+            rule_height: 1,
+        },
+        ..DviTextMarksV0::default()
+    };
+    assert!(write_v0(b"ab\ncd\nX\nY", &marks, 5).is_none());
+}
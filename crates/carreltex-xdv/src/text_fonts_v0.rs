@@ -0,0 +1,163 @@
+use crate::{
+    glyph_width_sp_v0, push_i32_be, push_u32_be, read_i32_be, read_u32_be, read_u8, DVI_FNT_DEF1,
+    DVI_FNT_NUM_0, MAX_DVI_RULE_DIMENSION_SP_V0,
+};
+
+/// Fonts besides the default one, numbered from 1 so each has a one-byte `fnt_num`.
+pub const MAX_DVI_TEXT_FONTS_V0: usize = 63;
+/// Design size of the writer's fonts: a font's glyph advances are the layout
+/// advances scaled by its size over this.
+pub const DVI_TEXT_FONT_DESIGN_SIZE_SP_V0: i32 = 655_360;
+
+/// A font defined after the default `carreltex-v0` one, at `size_sp`. Its number is
+/// its position in the font list plus one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviTextFontV0 {
+    pub name: Vec<u8>,
+    pub size_sp: i32,
+}
+
+/// Glyphs from the text byte at `offset` on are set in font `font` (0 for the default).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviTextFontSwitchV0 {
+    pub offset: usize,
+    pub font: usize,
+}
+
+/// Glyph advance of a font of `size_sp`, if every glyph width stays writable.
+fn font_em_sp_v0(size_sp: i32, glyph_advance_sp: i32) -> Option<i32> {
+    let em = i64::from(glyph_advance_sp) * i64::from(size_sp)
+        / i64::from(DVI_TEXT_FONT_DESIGN_SIZE_SP_V0);
+    let em = i32::try_from(em).ok()?;
+    glyph_width_sp_v0(b' ', em)?;
+    glyph_width_sp_v0(b'm', em)?;
+    Some(em)
+}
+
+/// Fonts of one text with their glyph advances, and the switches between them.
+pub(crate) struct TextFontsV0<'a> {
+    fonts: &'a [DviTextFontV0],
+    ems: Vec<i32>,
+    switches: &'a [DviTextFontSwitchV0],
+}
+
+impl<'a> TextFontsV0<'a> {
+    /// Switches must be ordered, within the text and refer to defined fonts.
+    pub(crate) fn new_v0(
+        text_len: usize,
+        fonts: &'a [DviTextFontV0],
+        switches: &'a [DviTextFontSwitchV0],
+        glyph_advance_sp: i32,
+    ) -> Option<Self> {
+        if fonts.len() > MAX_DVI_TEXT_FONTS_V0
+            || switches
+                .windows(2)
+                .any(|pair| pair[0].offset >= pair[1].offset)
+            || switches
+                .iter()
+                .any(|switch| switch.offset > text_len || switch.font > fonts.len())
+        {
+            return None;
+        }
+        let mut ems = vec![glyph_advance_sp];
+        for font in fonts {
+            if font.name.is_empty()
+                || font.name.len() > usize::from(u8::MAX)
+                || !(1..=MAX_DVI_RULE_DIMENSION_SP_V0).contains(&font.size_sp)
+            {
+                return None;
+            }
+            ems.push(font_em_sp_v0(font.size_sp, glyph_advance_sp)?);
+        }
+        Some(TextFontsV0 {
+            fonts,
+            ems,
+            switches,
+        })
+    }
+
+    pub(crate) fn font_at_v0(&self, offset: usize) -> usize {
+        let switched = self
+            .switches
+            .partition_point(|switch| switch.offset <= offset);
+        switched
+            .checked_sub(1)
+            .map_or(0, |index| self.switches[index].font)
+    }
+
+    pub(crate) fn font_count_v0(&self) -> usize {
+        self.fonts.len()
+    }
+
+    pub(crate) fn em_v0(&self, font: usize) -> i32 {
+        self.ems[font]
+    }
+
+    pub(crate) fn glyph_width_at_v0(&self, offset: usize, byte: u8) -> Option<i32> {
+        glyph_width_sp_v0(byte, self.em_v0(self.font_at_v0(offset)))
+    }
+
+    /// `fnt_def1` of every font after the default one.
+    pub(crate) fn append_font_defs_v0(&self, out: &mut Vec<u8>) {
+        for (index, font) in self.fonts.iter().enumerate() {
+            out.push(DVI_FNT_DEF1);
+            out.push(index as u8 + 1);
+            push_u32_be(out, 0);
+            push_i32_be(out, font.size_sp);
+            push_i32_be(out, DVI_TEXT_FONT_DESIGN_SIZE_SP_V0);
+            out.push(0);
+            out.push(font.name.len() as u8);
+            out.extend_from_slice(&font.name);
+        }
+    }
+}
+
+pub(crate) fn fnt_num_v0(font: usize) -> u8 {
+    DVI_FNT_NUM_0 + font as u8
+}
+
+/// Reads the `fnt_def1`s following the default font's definition, numbered from 1,
+/// as `(name, size_sp)` pairs.
+pub(crate) fn read_text_font_defs_v0(
+    bytes: &[u8],
+    index: &mut usize,
+) -> Option<Vec<(Vec<u8>, i32)>> {
+    let mut fonts = Vec::<(Vec<u8>, i32)>::new();
+    while bytes.get(*index) == Some(&DVI_FNT_DEF1) {
+        *index += 1;
+        if usize::from(read_u8(bytes, index)?) != fonts.len() + 1
+            || fonts.len() >= MAX_DVI_TEXT_FONTS_V0
+            || read_u32_be(bytes, index)? != 0
+        {
+            return None;
+        }
+        let size_sp = read_i32_be(bytes, index)?;
+        if !(1..=MAX_DVI_RULE_DIMENSION_SP_V0).contains(&size_sp)
+            || read_i32_be(bytes, index)? != DVI_TEXT_FONT_DESIGN_SIZE_SP_V0
+            || read_u8(bytes, index)? != 0
+        {
+            return None;
+        }
+        let len = usize::from(read_u8(bytes, index)?);
+        let end = index.checked_add(len)?;
+        let name = bytes.get(*index..end)?;
+        if name.is_empty() {
+            return None;
+        }
+        *index = end;
+        fonts.push((name.to_vec(), size_sp));
+    }
+    Some(fonts)
+}
+
+/// Glyph advances of the default font and the fonts read by `read_text_font_defs_v0`.
+pub(crate) fn text_font_ems_v0(
+    fonts: &[(Vec<u8>, i32)],
+    glyph_advance_sp: i32,
+) -> Option<Vec<i32>> {
+    let mut ems = vec![glyph_advance_sp];
+    for (_, size_sp) in fonts {
+        ems.push(font_em_sp_v0(*size_sp, glyph_advance_sp)?);
+    }
+    Some(ems)
+}
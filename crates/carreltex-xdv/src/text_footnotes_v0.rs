@@ -0,0 +1,165 @@
+use crate::text_floats_v0::PlacedFloatV0;
+use crate::text_layout_v0::TextLineV0;
+use crate::text_marks_v0::{append_text_rule_v0, LineWidthV0};
+use crate::{DviTextRuleV0, MAX_DVI_RULE_DIMENSION_SP_V0, NEWLINE_MARKER_V0, PAGEBREAK_MARKER_V0};
+
+pub const MAX_DVI_TEXT_FOOTNOTES_V0: usize = 1024;
+
+/// A footnote body, the whole lines `start..end` of the text, set at the bottom of
+/// the page holding the main-text byte at `anchor`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviTextFootnoteV0 {
+    pub anchor: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Footnotes of a text and the rule set above them, on a line of its own. Their
+/// bodies follow the main text one per line range, in anchor order, each after a
+/// newline; a page keeps room for the bodies anchored on it, moving a line to the
+/// next page when its footnotes do not fit below it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DviTextFootnotesV0<'a> {
+    pub notes: &'a [DviTextFootnoteV0],
+    pub rule_width: i32,
+    pub rule_height: i32,
+}
+
+impl DviTextFootnotesV0<'_> {
+    /// Offset of the first footnote body, past every offset without footnotes; marks
+    /// from there on belong to the bodies.
+    pub(crate) fn bodies_start_v0(&self) -> usize {
+        self.notes.first().map_or(usize::MAX, |note| note.start)
+    }
+
+    /// End of the main text: the whole text without footnotes.
+    pub(crate) fn main_end_v0(&self, text_len: usize) -> usize {
+        self.notes
+            .first()
+            .map_or(text_len, |note| note.start.saturating_sub(1))
+    }
+}
+
+/// Bodies must tile the end of the text without page breaks, and anchors must be
+/// ordered inside the main text.
+pub(crate) fn validate_text_footnotes_v0(
+    text: &[u8],
+    footnotes: &DviTextFootnotesV0<'_>,
+) -> Option<()> {
+    let notes = footnotes.notes;
+    let Some(first) = notes.first() else {
+        return Some(());
+    };
+    let main_end = footnotes.main_end_v0(text.len());
+    if notes.len() > MAX_DVI_TEXT_FOOTNOTES_V0
+        || (first.start > 0 && text.get(first.start - 1) != Some(&NEWLINE_MARKER_V0))
+        || notes.last()?.end != text.len()
+        || !(0..=MAX_DVI_RULE_DIMENSION_SP_V0).contains(&footnotes.rule_width)
+        || !(0..=MAX_DVI_RULE_DIMENSION_SP_V0).contains(&footnotes.rule_height)
+        || notes
+            .windows(2)
+            .any(|pair| pair[0].anchor > pair[1].anchor || pair[1].start != pair[0].end + 1)
+    {
+        return None;
+    }
+    for note in notes {
+        let body = text.get(note.start..note.end)?;
+        if note.anchor > main_end
+            || body.contains(&PAGEBREAK_MARKER_V0)
+            || (note.end < text.len() && text[note.end] != NEWLINE_MARKER_V0)
+        {
+            return None;
+        }
+    }
+    Some(())
+}
+
+/// Lines of one output page: floats at the top, the main text with the floats set
+/// `here` after the number of its lines they follow, floats at the bottom, then,
+/// when footnotes are anchored on it, the footnote rule and their bodies.
+#[derive(Default)]
+pub(crate) struct TextPageV0 {
+    pub(crate) lines: Vec<TextLineV0>,
+    pub(crate) top: Vec<PlacedFloatV0>,
+    pub(crate) here: Vec<(usize, PlacedFloatV0)>,
+    pub(crate) bottom: Vec<PlacedFloatV0>,
+    pub(crate) footnote_lines: Vec<TextLineV0>,
+}
+
+/// One line of a page as the writer emits it.
+pub(crate) enum TextRowV0<'a> {
+    Text(&'a TextLineV0),
+    Blank,
+    Float(&'a TextLineV0),
+    FootnoteRule,
+    Footnote(&'a TextLineV0),
+}
+
+impl TextPageV0 {
+    /// Lines the page takes, the blank lines around floats and the footnote rule
+    /// included.
+    pub(crate) fn len_v0(&self) -> usize {
+        let rule = usize::from(!self.footnote_lines.is_empty());
+        let floats = self
+            .top
+            .iter()
+            .chain(self.bottom.iter())
+            .map(|float| float.lines.len() + 1)
+            .chain(self.here.iter().map(|(_, float)| float.lines.len() + 2))
+            .sum::<usize>();
+        floats + self.lines.len() + rule + self.footnote_lines.len()
+    }
+
+    /// Top floats each followed by a blank line, main lines with here floats
+    /// between blank lines, bottom floats each after a blank line, then blank lines
+    /// down to the footnote block, which ends on the page's last line unless the
+    /// page is overfull.
+    pub(crate) fn rows_v0(&self, max_lines_per_page: usize) -> Vec<TextRowV0<'_>> {
+        let mut rows = Vec::<TextRowV0<'_>>::new();
+        for float in &self.top {
+            rows.extend(float.lines.iter().map(TextRowV0::Float));
+            rows.push(TextRowV0::Blank);
+        }
+        let mut here = self.here.iter().peekable();
+        for (index, line) in self.lines.iter().enumerate() {
+            rows.push(TextRowV0::Text(line));
+            while let Some((_, float)) = here.next_if(|(after, _)| *after == index + 1) {
+                rows.push(TextRowV0::Blank);
+                rows.extend(float.lines.iter().map(TextRowV0::Float));
+                rows.push(TextRowV0::Blank);
+            }
+        }
+        for float in &self.bottom {
+            rows.push(TextRowV0::Blank);
+            rows.extend(float.lines.iter().map(TextRowV0::Float));
+        }
+        if self.footnote_lines.is_empty() {
+            return rows;
+        }
+        let blank = max_lines_per_page.saturating_sub(self.len_v0());
+        rows.extend((0..blank).map(|_| TextRowV0::Blank));
+        rows.push(TextRowV0::FootnoteRule);
+        rows.extend(self.footnote_lines.iter().map(TextRowV0::Footnote));
+        rows
+    }
+}
+
+/// The footnote rule: a `put_rule` on the baseline of its line.
+pub(crate) fn emit_footnote_rule_v0(
+    out: &mut Vec<u8>,
+    footnotes: &DviTextFootnotesV0<'_>,
+) -> Option<LineWidthV0> {
+    let rule = DviTextRuleV0 {
+        offset: 0,
+        special_index: 0,
+        width: footnotes.rule_width,
+        height: footnotes.rule_height,
+        raise: 0,
+        advance: false,
+    };
+    append_text_rule_v0(out, &rule)?;
+    Some(LineWidthV0 {
+        advance: 0,
+        extent: footnotes.rule_width as u32,
+    })
+}
@@ -0,0 +1,476 @@
+use super::{
+    read_dvi_v0, text_page_starts_v0, validate_dvi_v2_text_page_v0,
+    write_dvi_v2_text_page_with_layout_paging_and_marks_v0, DviDocumentV0, DviRuleV0,
+    DviTextFootnoteV0, DviTextFootnotesV0, DviTextKernV0, DviTextMarksV0, DviTextRaiseV0,
+    DviTextRuleV0, DviTextSpecialV0,
+};
+
+const LINE_SP: i32 = 786_432;
+
+fn write_v0(text: &[u8], marks: &DviTextMarksV0<'_>, max_lines: usize) -> Option<Vec<u8>> {
+    write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+        text, marks, 65_536, LINE_SP, 80, max_lines,
+    )
+}
+
+fn read_valid_v0(bytes: &[u8]) -> DviDocumentV0 {
+    assert!(validate_dvi_v2_text_page_v0(bytes));
+    read_dvi_v0(bytes).expect("reader should accept the writer's output")
+}
+
+/// `(glyph, v)` of every page.
+fn page_glyphs_v0(document: &DviDocumentV0) -> Vec<Vec<(u8, i32)>> {
+    document
+        .pages
+        .iter()
+        .map(|page| {
+            page.glyphs
+                .iter()
+                .map(|glyph| (glyph.code as u8, glyph.v))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn raised_glyphs_leave_the_baseline_on_every_line_they_span() {
+    let write = |marks: &DviTextMarksV0<'_>| {
+        write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+            b"abc de", marks, 65_536, LINE_SP, 3, 200,
+        )
+    };
+    let raises = [DviTextRaiseV0 {
+        start: 1,
+        end: 5,
+        raise: 200_000,
+    }];
+    let specials = [DviTextSpecialV0 {
+        offset: 2,
+        bytes: b"inside".to_vec(),
+    }];
+    let rules = [DviTextRuleV0 {
+        offset: 5,
+        special_index: 1,
+        width: 10,
+        height: 10,
+        raise: 0,
+        advance: true,
+    }];
+    let marks = DviTextMarksV0 {
+        raises: &raises,
+        specials: &specials,
+        rules: &rules,
+        ..DviTextMarksV0::default()
+    };
+    let document = read_valid_v0(&write(&marks).expect("writer should accept raises"));
+    assert_eq!(
+        page_glyphs_v0(&document),
+        vec![vec![
+            (b'a', 0),
+            (b'b', -200_000),
+            (b'c', -200_000),
+            (b' ', LINE_SP - 200_000),
+            (b'd', LINE_SP - 200_000),
+            (b'e', LINE_SP)
+        ]]
+    );
+    assert_eq!(document.pages[0].specials[0].v, -200_000);
+    assert_eq!(document.pages[0].rules[0].v, LINE_SP);
+
+    let rules = [DviTextRuleV0 {
+        special_index: 0,
+        ..rules[0]
+    }];
+    for bad in [
+        DviTextRaiseV0 {
+            start: 1,
+            end: 7,
+            raise: 1,
+        },
+        DviTextRaiseV0 {
+            start: 2,
+            end: 2,
+            raise: 1,
+        },
+        DviTextRaiseV0 {
+            start: 1,
+            end: 2,
+            raise: 0,
+        },
+        DviTextRaiseV0 {
+            start: 4,
+            end: 6,
+            raise: 1,
+        },
+    ] {
+        let raises = [bad];
+        let marks = DviTextMarksV0 {
+            raises: &raises,
+            rules: &rules,
+            ..DviTextMarksV0::default()
+        };
+        assert!(write(&marks).is_none(), "{bad:?}");
+    }
+    let overlapping = [
+        DviTextRaiseV0 {
+            start: 0,
+            end: 2,
+            raise: 1,
+        },
+        DviTextRaiseV0 {
+            start: 1,
+            end: 3,
+            raise: 1,
+        },
+    ];
+    let marks = DviTextMarksV0 {
+        raises: &overlapping,
+        ..DviTextMarksV0::default()
+    };
+    assert!(write(&marks).is_none());
+}
+
+#[test]
+fn kerns_move_between_rules_and_high_codes_are_set_with_set1() {
+    let text = b"a\xd6b";
+    let rules = [DviTextRuleV0 {
+        offset: 1,
+        special_index: 0,
+        width: 500,
+        height: 10,
+        raise: 0,
+        advance: false,
+    }];
+    let kern = |offset: usize, rule_index: usize, width: i32| DviTextKernV0 {
+        offset,
+        special_index: 0,
+        rule_index,
+        width,
+    };
+    let kerns = [kern(1, 0, 1_000), kern(1, 1, -300), kern(2, 1, -2_000)];
+    // Raised alike on both sides of the rule, which still sits on the baseline.
+    let raises = [
+        DviTextRaiseV0 {
+            start: 0,
+            end: 1,
+            raise: 100,
+        },
+        DviTextRaiseV0 {
+            start: 1,
+            end: 2,
+            raise: 100,
+        },
+    ];
+    let marks = DviTextMarksV0 {
+        rules: &rules,
+        kerns: &kerns,
+        raises: &raises,
+        ..DviTextMarksV0::default()
+    };
+    let document = read_valid_v0(&write_v0(text, &marks, 200).expect("writer should accept kerns"));
+    let glyphs = &document.pages[0].glyphs;
+    assert_eq!(
+        glyphs
+            .iter()
+            .map(|glyph| (glyph.code, glyph.h, glyph.v))
+            .collect::<Vec<_>>(),
+        [
+            (u32::from(b'a'), 0, -100),
+            (0xd6, 66_236, -100),
+            (u32::from(b'b'), 129_772, 0)
+        ]
+    );
+    assert_eq!(
+        document.pages[0].rules,
+        [DviRuleV0 {
+            h: 66_536,
+            v: 0,
+            width: 500,
+            height: 10
+        }]
+    );
+
+    for bad in [
+        [kern(1, 0, 1_000), kern(1, 1, 0), kern(2, 1, -2_000)],
+        [kern(1, 0, 1_000), kern(1, 2, -300), kern(2, 1, -2_000)],
+        [kern(1, 1, 1_000), kern(1, 0, -300), kern(2, 1, -2_000)],
+        [kern(2, 0, 1_000), kern(1, 1, -300), kern(2, 1, -2_000)],
+        [kern(0, 0, -1), kern(1, 1, -300), kern(4, 1, -2_000)],
+        [kern(0, 0, -1), kern(1, 1, -300), kern(2, 1, -2_000)],
+    ] {
+        let marks = DviTextMarksV0 {
+            rules: &rules,
+            kerns: &bad,
+            ..DviTextMarksV0::default()
+        };
+        assert!(write_v0(text, &marks, 200).is_none(), "{bad:?}");
+    }
+}
+
+#[test]
+fn kerns_follow_the_specials_before_them_and_wide_lines_reset_with_right4() {
+    // An image special, then the 200 em kern reserving its box; the line reset back
+    // from past it does not fit `right3`.
+    let text = b"a\nb";
+    let specials = [DviTextSpecialV0 {
+        offset: 0,
+        bytes: b"pdf:image".to_vec(),
+    }];
+    let kern = |special_index: usize| DviTextKernV0 {
+        offset: 0,
+        special_index,
+        rule_index: 0,
+        width: 200 * 65_536,
+    };
+    let kerns = [kern(1)];
+    let marks = DviTextMarksV0 {
+        specials: &specials,
+        kerns: &kerns,
+        ..DviTextMarksV0::default()
+    };
+    let document = read_valid_v0(&write_v0(text, &marks, 200).expect("writer should accept kerns"));
+    let page = &document.pages[0];
+    assert_eq!((page.specials[0].h, page.specials[0].v), (0, 0));
+    assert_eq!(
+        page.glyphs
+            .iter()
+            .map(|glyph| (glyph.code, glyph.h, glyph.v))
+            .collect::<Vec<_>>(),
+        [
+            (u32::from(b'a'), 200 * 65_536, 0),
+            (u32::from(b'b'), 0, LINE_SP)
+        ]
+    );
+
+    let kerns = [kern(0)];
+    let marks = DviTextMarksV0 {
+        specials: &specials,
+        kerns: &kerns,
+        ..DviTextMarksV0::default()
+    };
+    let document = read_valid_v0(&write_v0(text, &marks, 200).expect("writer should accept kerns"));
+    assert_eq!(document.pages[0].specials[0].h, 200 * 65_536);
+    let kerns = [kern(2)];
+    let marks = DviTextMarksV0 {
+        specials: &specials,
+        kerns: &kerns,
+        ..DviTextMarksV0::default()
+    };
+    assert!(write_v0(text, &marks, 200).is_none());
+}
+
+#[test]
+fn footnotes_end_the_page_of_their_anchor() {
+    // Main text `a1`, `b`, `c2`; bodies `one` and `two` / `more`. The body anchored on
+    // `c2` does not fit below it on the first page, so `c2` starts the second one.
+    let text = b"a1\nb\nc2\none\ntwo\nmore";
+    let notes = [
+        DviTextFootnoteV0 {
+            anchor: 1,
+            start: 8,
+            end: 11,
+        },
+        DviTextFootnoteV0 {
+            anchor: 6,
+            start: 12,
+            end: 20,
+        },
+    ];
+    let footnotes = DviTextFootnotesV0 {
+        notes: &notes,
+        rule_width: 300,
+        rule_height: 20,
+    };
+    let specials = [
+        DviTextSpecialV0 {
+            offset: 3,
+            bytes: b"main".to_vec(),
+        },
+        DviTextSpecialV0 {
+            offset: 8,
+            bytes: b"note".to_vec(),
+        },
+    ];
+    let marks = DviTextMarksV0 {
+        footnotes,
+        specials: &specials,
+        ..DviTextMarksV0::default()
+    };
+    let bytes = write_v0(text, &marks, 5).expect("writer should accept footnotes");
+    let document = read_valid_v0(&bytes);
+    let line = |index: i32| index * LINE_SP;
+    assert_eq!(
+        page_glyphs_v0(&document),
+        vec![
+            vec![
+                (b'a', 0),
+                (b'1', 0),
+                (b'b', line(1)),
+                (b'o', line(4)),
+                (b'n', line(4)),
+                (b'e', line(4))
+            ],
+            vec![
+                (b'c', 0),
+                (b'2', 0),
+                (b't', line(3)),
+                (b'w', line(3)),
+                (b'o', line(3)),
+                (b'm', line(4)),
+                (b'o', line(4)),
+                (b'r', line(4)),
+                (b'e', line(4)),
+            ],
+        ]
+    );
+    let rule = |v: i32| DviRuleV0 {
+        h: 0,
+        v,
+        width: 300,
+        height: 20,
+    };
+    assert_eq!(document.pages[0].rules, vec![rule(line(3))]);
+    assert_eq!(document.pages[1].rules, vec![rule(line(2))]);
+    let specials = document.pages[0]
+        .specials
+        .iter()
+        .map(|special| (special.bytes.as_slice(), special.v))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        specials,
+        vec![(b"main".as_slice(), line(1)), (b"note".as_slice(), line(4))]
+    );
+    assert_eq!(
+        text_page_starts_v0(text, &marks, 65_536, 80, 5),
+        Some(vec![0, 5])
+    );
+
+    // A body too long for any page still goes with its anchor, overfilling the page.
+    let notes = [DviTextFootnoteV0 {
+        anchor: 0,
+        start: 4,
+        end: 9,
+    }];
+    let marks = DviTextMarksV0 {
+        footnotes: DviTextFootnotesV0 {
+            notes: &notes,
+            ..footnotes
+        },
+        ..DviTextMarksV0::default()
+    };
+    let document =
+        read_valid_v0(&write_v0(b"a\nb\nxy\nzw", &marks, 2).expect("writer should overfill"));
+    assert_eq!(
+        page_glyphs_v0(&document),
+        vec![
+            vec![
+                (b'a', 0),
+                (b'x', line(2)),
+                (b'y', line(2)),
+                (b'z', line(3)),
+                (b'w', line(3))
+            ],
+            vec![(b'b', 0)],
+        ]
+    );
+}
+
+#[test]
+fn text_writer_rejects_misplaced_footnotes() {
+    let text = b"a1\nb\nc2\none\ntwo";
+    for notes in [
+        [
+            DviTextFootnoteV0 {
+                anchor: 1,
+                start: 9,
+                end: 11,
+            },
+            DviTextFootnoteV0 {
+                anchor: 6,
+                start: 12,
+                end: 15,
+            },
+        ],
+        [
+            DviTextFootnoteV0 {
+                anchor: 6,
+                start: 8,
+                end: 11,
+            },
+            DviTextFootnoteV0 {
+                anchor: 1,
+                start: 12,
+                end: 15,
+            },
+        ],
+        [
+            DviTextFootnoteV0 {
+                anchor: 8,
+                start: 8,
+                end: 11,
+            },
+            DviTextFootnoteV0 {
+                anchor: 8,
+                start: 12,
+                end: 15,
+            },
+        ],
+        [
+            DviTextFootnoteV0 {
+                anchor: 1,
+                start: 8,
+                end: 10,
+            },
+            DviTextFootnoteV0 {
+                anchor: 6,
+                start: 12,
+                end: 15,
+            },
+        ],
+        [
+            DviTextFootnoteV0 {
+                anchor: 1,
+                start: 8,
+                end: 11,
+            },
+            DviTextFootnoteV0 {
+                anchor: 6,
+                start: 12,
+                end: 14,
+            },
+        ],
+    ] {
+        let marks = DviTextMarksV0 {
+            footnotes: DviTextFootnotesV0 {
+                notes: &notes,
+                rule_width: 1,
+                rule_height: 1,
+            },
+            ..DviTextMarksV0::default()
+        };
+        assert!(write_v0(text, &marks, 5).is_none(), "{notes:?}");
+    }
+    let notes = [DviTextFootnoteV0 {
+        anchor: 0,
+        start: 2,
+        end: 5,
+    }];
+    let marks = DviTextMarksV0 {
+        footnotes: DviTextFootnotesV0 {
+            notes: &notes,
+            rule_width: 1,
+            rule_height: 1,
+        },
+        ..DviTextMarksV0::default()
+    };
+    assert!(write_v0(b"a\nb\x0cc", &marks, 5).is_none());
+    let marks = DviTextMarksV0 {
+        footnotes: DviTextFootnotesV0 {
+            notes: &notes,
+            rule_width: -1,
+            rule_height: 1,
+        },
+        ..DviTextMarksV0::default()
+    };
+    assert!(write_v0(b"a\nbcd", &marks, 5).is_none());
+}
@@ -0,0 +1,435 @@
+use crate::text_floats_v0::{
+    float_pages_v0, floats_main_end_v0, validate_text_floats_v0, DviTextFloatV0, FloatPlacerV0,
+};
+use crate::text_fonts_v0::TextFontsV0;
+use crate::text_footnotes_v0::{validate_text_footnotes_v0, DviTextFootnotesV0, TextPageV0};
+use crate::text_marks_v0::validate_text_marks_v0;
+use crate::{
+    is_text_glyph_byte_v0, DviTextMarksV0, MAX_DVI_RULE_DIMENSION_SP_V0, NEWLINE_MARKER_V0,
+    PAGEBREAK_MARKER_V0,
+};
+
+pub const MAX_DVI_TEXT_INDENTS_V0: usize = 4096;
+
+/// Left margin of the lines from the line starting at `offset` up to the next
+/// indent. The first `label_len` bytes at `offset` are an item label: with
+/// `label_h_sp` unset it hangs in the margin, ending `label_sep_sp` before
+/// `left_sp` (or as far left as the page allows), and the text resumes at
+/// `left_sp`; otherwise it starts at `label_h_sp` and the text follows it after
+/// `label_sep_sp`. Wrapped lines after the first start at `left_sp`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviTextIndentV0 {
+    pub offset: usize,
+    pub left_sp: i32,
+    pub label_len: usize,
+    pub label_sep_sp: i32,
+    pub label_h_sp: Option<i32>,
+}
+
+fn indent_dimension_ok_v0(value: i32) -> bool {
+    (0..=MAX_DVI_RULE_DIMENSION_SP_V0).contains(&value)
+}
+
+/// Indents must start lines, in increasing order, with labels inside their line.
+pub(crate) fn validate_text_indents_v0(text: &[u8], indents: &[DviTextIndentV0]) -> Option<()> {
+    if indents.len() > MAX_DVI_TEXT_INDENTS_V0
+        || indents
+            .windows(2)
+            .any(|pair| pair[0].offset >= pair[1].offset)
+    {
+        return None;
+    }
+    for indent in indents {
+        let label_end = indent.offset.checked_add(indent.label_len)?;
+        let at_line_start = indent.offset == 0
+            || matches!(
+                text.get(indent.offset - 1),
+                Some(&(NEWLINE_MARKER_V0 | PAGEBREAK_MARKER_V0))
+            );
+        if !at_line_start
+            || label_end > text.len()
+            || text[indent.offset..label_end]
+                .iter()
+                .any(|byte| *byte == NEWLINE_MARKER_V0 || *byte == PAGEBREAK_MARKER_V0)
+            || !indent_dimension_ok_v0(indent.left_sp)
+            || !indent_dimension_ok_v0(indent.label_sep_sp)
+            || indent
+                .label_h_sp
+                .is_some_and(|h| !indent_dimension_ok_v0(h))
+        {
+            return None;
+        }
+    }
+    Some(())
+}
+
+/// A physical output line: glyphs `start..end` of the text. Specials anchored at or
+/// before `owned_end` that were not emitted earlier are emitted on this line. The
+/// line starts at `indent_h`; glyphs from `label_end` on start at `text_h`.
+pub(crate) struct TextLineV0 {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) owned_end: usize,
+    pub(crate) indent_h: u32,
+    pub(crate) label_end: usize,
+    pub(crate) text_h: u32,
+}
+
+/// Greedy wrap limits and positions of one logical line.
+struct LineIndentV0 {
+    indent_h: u32,
+    label_len: usize,
+    text_h: u32,
+    left_h: u32,
+    first_max_glyphs: usize,
+    max_glyphs: usize,
+}
+
+/// The glyph budget shrinks by the whole glyphs that fit in the margin; the label,
+/// starting at `label_start`, is measured in the fonts it is set in.
+fn line_indent_v0(
+    label: &[u8],
+    label_start: usize,
+    fonts: &TextFontsV0<'_>,
+    indent: Option<&DviTextIndentV0>,
+    glyph_advance_sp: i32,
+    max_line_glyphs: usize,
+) -> Option<LineIndentV0> {
+    let budget_after = |h: u32| -> usize {
+        let margin_glyphs = (h / glyph_advance_sp as u32) as usize;
+        max_line_glyphs.saturating_sub(margin_glyphs).max(1)
+    };
+    let Some(indent) = indent else {
+        return Some(LineIndentV0 {
+            indent_h: 0,
+            label_len: 0,
+            text_h: 0,
+            left_h: 0,
+            first_max_glyphs: max_line_glyphs,
+            max_glyphs: max_line_glyphs,
+        });
+    };
+    let left_h = indent.left_sp as u32;
+    let mut label_width = 0u32;
+    for (index, byte) in label.iter().enumerate() {
+        let glyph_width = fonts.glyph_width_at_v0(label_start + index, *byte)?;
+        label_width = label_width.checked_add(glyph_width as u32)?;
+    }
+    let sep = indent.label_sep_sp as u32;
+    let (indent_h, text_h) = match (label.is_empty(), indent.label_h_sp) {
+        (true, _) => (left_h, left_h),
+        (false, None) => {
+            let indent_h = left_h.saturating_sub(sep).saturating_sub(label_width);
+            let label_end = indent_h.checked_add(label_width)?.checked_add(sep)?;
+            (indent_h, left_h.max(label_end))
+        }
+        (false, Some(label_h)) => {
+            let label_h = label_h as u32;
+            (label_h, label_h.checked_add(label_width)?.checked_add(sep)?)
+        }
+    };
+    Some(LineIndentV0 {
+        indent_h,
+        label_len: label.len(),
+        text_h,
+        left_h,
+        first_max_glyphs: label.len() + budget_after(text_h),
+        max_glyphs: budget_after(left_h),
+    })
+}
+
+/// Builds the physical lines of text ranges taken in text order; `next_indent` is
+/// the first indent not reached yet.
+struct LineBuilderV0<'a> {
+    text: &'a [u8],
+    indents: &'a [DviTextIndentV0],
+    fonts: &'a TextFontsV0<'a>,
+    glyph_advance_sp: i32,
+    max_line_glyphs: usize,
+    next_indent: usize,
+}
+
+impl LineBuilderV0<'_> {
+    /// Physical lines of `start..end`, a run of whole logical lines.
+    fn lines_v0(&mut self, start: usize, end: usize) -> Option<Vec<TextLineV0>> {
+        let text = self.text;
+        let mut physical_lines = Vec::<TextLineV0>::new();
+        for (line_start, line_end) in split_ranges_v0(text, start, end, NEWLINE_MARKER_V0) {
+            let mut label = &text[line_start..line_start];
+            if self
+                .indents
+                .get(self.next_indent)
+                .is_some_and(|indent| indent.offset == line_start)
+            {
+                let indent = &self.indents[self.next_indent];
+                label = &text[line_start..line_start + indent.label_len];
+                self.next_indent += 1;
+            }
+            let indent = self
+                .next_indent
+                .checked_sub(1)
+                .map(|index| &self.indents[index]);
+            let line_indent = line_indent_v0(
+                label,
+                line_start,
+                self.fonts,
+                indent,
+                self.glyph_advance_sp,
+                self.max_line_glyphs,
+            )?;
+            let wrapped = wrap_indented_line_ranges_v0(&text[line_start..line_end], &line_indent)?;
+            let wrapped_len = wrapped.len();
+            for (index, (start, end)) in wrapped.into_iter().enumerate() {
+                let first = index == 0;
+                physical_lines.push(TextLineV0 {
+                    start: line_start + start,
+                    end: line_start + end,
+                    owned_end: if index + 1 == wrapped_len {
+                        line_end
+                    } else {
+                        line_start + end
+                    },
+                    indent_h: if first {
+                        line_indent.indent_h
+                    } else {
+                        line_indent.left_h
+                    },
+                    label_end: line_start + start + if first { line_indent.label_len } else { 0 },
+                    text_h: if first {
+                        line_indent.text_h
+                    } else {
+                        line_indent.left_h
+                    },
+                });
+            }
+        }
+        Some(physical_lines)
+    }
+}
+
+/// Pages of the main text, split at page breaks and after `max_lines_per_page`
+/// lines, each with the floats placed on it and the footnotes anchored on its
+/// lines. A line whose footnotes do not fit on the page starts the next one, where
+/// they are set even if they overfill it. Pages of floats alone may come between
+/// text pages and after the last one.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn layout_text_pages_v0(
+    text: &[u8],
+    indents: &[DviTextIndentV0],
+    floats: &[DviTextFloatV0],
+    footnotes: &DviTextFootnotesV0<'_>,
+    fonts: &TextFontsV0<'_>,
+    glyph_advance_sp: i32,
+    max_line_glyphs: usize,
+    max_lines_per_page: usize,
+) -> Option<Vec<TextPageV0>> {
+    if text.iter().any(|byte| {
+        !is_text_glyph_byte_v0(*byte) && *byte != PAGEBREAK_MARKER_V0 && *byte != NEWLINE_MARKER_V0
+    }) {
+        return None;
+    }
+    validate_text_indents_v0(text, indents)?;
+    validate_text_footnotes_v0(text, footnotes)?;
+    validate_text_floats_v0(text, floats, footnotes)?;
+    let mut builder = LineBuilderV0 {
+        text,
+        indents,
+        fonts,
+        glyph_advance_sp,
+        max_line_glyphs,
+        next_indent: 0,
+    };
+    let main_end = floats_main_end_v0(floats, footnotes, text.len());
+    let mut segments = Vec::<Vec<TextLineV0>>::new();
+    for (page_start, page_end) in split_ranges_v0(text, 0, main_end, PAGEBREAK_MARKER_V0) {
+        segments.push(builder.lines_v0(page_start, page_end)?);
+    }
+    let mut bodies = Vec::<Vec<TextLineV0>>::new();
+    for float in floats {
+        bodies.push(builder.lines_v0(float.start, float.end)?);
+    }
+    let mut notes = Vec::<Vec<TextLineV0>>::new();
+    for note in footnotes.notes {
+        notes.push(builder.lines_v0(note.start, note.end)?);
+    }
+    let mut placer = FloatPlacerV0::new_v0(floats, bodies, max_lines_per_page);
+    let mut next_note = 0usize;
+    let mut pages = Vec::<TextPageV0>::new();
+    let mut page = TextPageV0::default();
+    for (segment, lines) in segments.into_iter().enumerate() {
+        if segment > 0 {
+            page = placer.next_page_v0(&mut pages, page);
+        }
+        for line in lines {
+            let anchored = footnotes.notes[next_note..]
+                .iter()
+                .take_while(|note| note.anchor <= line.owned_end)
+                .count();
+            let note_lines = notes[next_note..next_note + anchored]
+                .iter()
+                .map(Vec::len)
+                .sum::<usize>();
+            let rule = usize::from(page.footnote_lines.is_empty() && note_lines > 0);
+            if page.len_v0() > 0 && page.len_v0() + 1 + rule + note_lines > max_lines_per_page {
+                page = placer.next_page_v0(&mut pages, page);
+            }
+            let owned_end = line.owned_end;
+            page.lines.push(line);
+            for note in &mut notes[next_note..next_note + anchored] {
+                page.footnote_lines.append(note);
+            }
+            next_note += anchored;
+            placer.anchor_through_v0(&mut page, owned_end);
+        }
+    }
+    placer.finish_v0(&mut pages, page);
+    Some(pages)
+}
+
+/// Pages of `text` as the marks writer lays them out.
+fn layout_marked_text_pages_v0(
+    text: &[u8],
+    text_marks: &DviTextMarksV0<'_>,
+    glyph_advance_sp: i32,
+    max_line_glyphs: usize,
+    max_lines_per_page: usize,
+) -> Option<Vec<TextPageV0>> {
+    if glyph_advance_sp <= 0 || max_line_glyphs == 0 || max_lines_per_page == 0 {
+        return None;
+    }
+    validate_text_marks_v0(text.len(), text_marks)?;
+    let fonts = TextFontsV0::new_v0(
+        text.len(),
+        text_marks.fonts,
+        text_marks.font_switches,
+        glyph_advance_sp,
+    )?;
+    layout_text_pages_v0(
+        text,
+        text_marks.indents,
+        text_marks.floats,
+        &text_marks.footnotes,
+        &fonts,
+        glyph_advance_sp,
+        max_line_glyphs,
+        max_lines_per_page,
+    )
+}
+
+/// Offset of the first main-text byte of every page the marks writer would emit,
+/// so positions in the text can be mapped to page numbers. A page of floats alone
+/// starts where the next page's main text does, or just past the main text.
+pub fn text_page_starts_v0(
+    text: &[u8],
+    text_marks: &DviTextMarksV0<'_>,
+    glyph_advance_sp: i32,
+    max_line_glyphs: usize,
+    max_lines_per_page: usize,
+) -> Option<Vec<usize>> {
+    let pages = layout_marked_text_pages_v0(
+        text,
+        text_marks,
+        glyph_advance_sp,
+        max_line_glyphs,
+        max_lines_per_page,
+    )?;
+    let main_end = floats_main_end_v0(text_marks.floats, &text_marks.footnotes, text.len());
+    let mut next_start = main_end + 1;
+    let mut starts = vec![0usize; pages.len()];
+    for (index, page) in pages.iter().enumerate().rev() {
+        if let Some(line) = page.lines.first() {
+            next_start = line.start;
+        }
+        starts[index] = next_start;
+    }
+    Some(starts)
+}
+
+/// 0-based page of every float the marks writer would emit.
+pub fn text_float_pages_v0(
+    text: &[u8],
+    text_marks: &DviTextMarksV0<'_>,
+    glyph_advance_sp: i32,
+    max_line_glyphs: usize,
+    max_lines_per_page: usize,
+) -> Option<Vec<usize>> {
+    let pages = layout_marked_text_pages_v0(
+        text,
+        text_marks,
+        glyph_advance_sp,
+        max_line_glyphs,
+        max_lines_per_page,
+    )?;
+    Some(float_pages_v0(&pages, text_marks.floats.len()))
+}
+
+fn split_ranges_v0(text: &[u8], start: usize, end: usize, marker: u8) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::<(usize, usize)>::new();
+    let mut range_start = start;
+    for (index, byte) in text[start..end].iter().enumerate() {
+        if *byte == marker {
+            ranges.push((range_start, start + index));
+            range_start = start + index + 1;
+        }
+    }
+    ranges.push((range_start, end));
+    ranges
+}
+
+#[cfg(test)]
+pub(crate) fn wrap_logical_line_ranges_v0(
+    line: &[u8],
+    max_line_glyphs: usize,
+) -> Option<Vec<(usize, usize)>> {
+    let indent = LineIndentV0 {
+        indent_h: 0,
+        label_len: 0,
+        text_h: 0,
+        left_h: 0,
+        first_max_glyphs: max_line_glyphs,
+        max_glyphs: max_line_glyphs,
+    };
+    wrap_indented_line_ranges_v0(line, &indent)
+}
+
+/// Breaks at the last space that fits; a label is never split.
+fn wrap_indented_line_ranges_v0(line: &[u8], indent: &LineIndentV0) -> Option<Vec<(usize, usize)>> {
+    if indent.max_glyphs == 0 || indent.first_max_glyphs <= indent.label_len {
+        return None;
+    }
+    if line.is_empty() {
+        return Some(vec![(0, 0)]);
+    }
+    let mut wrapped = Vec::<(usize, usize)>::new();
+    let mut start = 0usize;
+    while start < line.len() {
+        let (max_line_glyphs, keep) = if wrapped.is_empty() {
+            (indent.first_max_glyphs, indent.label_len)
+        } else {
+            (indent.max_glyphs, 0)
+        };
+        if line.len() - start <= max_line_glyphs {
+            wrapped.push((start, line.len()));
+            break;
+        }
+        let limit = start + max_line_glyphs;
+        let mut break_at = None::<usize>;
+        for index in (start + keep..limit).rev() {
+            if line[index] == b' ' {
+                break_at = Some(index);
+                break;
+            }
+        }
+        if let Some(space_index) = break_at {
+            wrapped.push((start, space_index));
+            start = space_index + 1;
+            while start < line.len() && line[start] == b' ' {
+                start += 1;
+            }
+        } else {
+            wrapped.push((start, limit));
+            start = limit;
+        }
+    }
+    Some(wrapped)
+}
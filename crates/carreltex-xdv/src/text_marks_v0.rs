@@ -0,0 +1,489 @@
+use crate::text_floats_v0::DviTextFloatV0;
+use crate::text_fonts_v0::{fnt_num_v0, TextFontsV0};
+use crate::text_footnotes_v0::DviTextFootnotesV0;
+use crate::text_pages_v0::DviTextPagesV0;
+use crate::{
+    push_i24_be, push_i32_be, push_u32_be, read_i32_be, read_u8, DviTextFontSwitchV0,
+    DviTextFontV0, DviTextIndentV0, DviTextSpecialV0, TextLineV0, DVI_RIGHT3, DVI_RIGHT4, DVI_XXX1,
+    DVI_XXX4, MAX_DVI_SPECIALS_V0, MAX_DVI_SPECIAL_BYTES_V0,
+};
+
+pub(crate) const DVI_SET1: u8 = 128;
+
+pub(crate) const DVI_SET_RULE: u8 = 132;
+pub(crate) const DVI_PUT_RULE: u8 = 137;
+pub(crate) const DVI_DOWN4: u8 = 160;
+pub const MAX_DVI_TEXT_RULES_V0: usize = 1024;
+pub const MAX_DVI_TEXT_FILLS_V0: usize = 4096;
+pub const MAX_DVI_TEXT_RAISES_V0: usize = 4096;
+pub const MAX_DVI_TEXT_KERNS_V0: usize = 16 * 1024;
+/// Leader dots one fill may set.
+const MAX_DVI_FILL_LEADERS_V0: u32 = 1024;
+/// TeX's `\maxdimen` (16383.99998pt); rule dimensions and raises stay within it.
+pub const MAX_DVI_RULE_DIMENSION_SP_V0: i32 = 0x3fff_ffff;
+
+/// A rule anchored before the text byte at `offset`, emitted after the first
+/// `special_index` specials. Its bottom edge sits `raise` above the baseline. With
+/// `advance` it is a `set_rule` that moves right by `width` (as `\vrule` and `\rule`
+/// do); otherwise a `put_rule` that leaves the position unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviTextRuleV0 {
+    pub offset: usize,
+    pub special_index: usize,
+    pub width: i32,
+    pub height: i32,
+    pub raise: i32,
+    pub advance: bool,
+}
+
+/// A move right to `to_h` on the line of the text byte at `offset`, made before
+/// that byte once the specials and rules anchored up to it are emitted, and skipped
+/// when the line is already past `to_h`. With `leader_pitch_sp` the gap gets `.`
+/// leaders in the font of that byte, as TeX's `\leaders` sets them: a dot centered
+/// in every `leader_pitch_sp` box, counted from the line's left edge, that fits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviTextFillV0 {
+    pub offset: usize,
+    pub to_h: i32,
+    pub leader_pitch_sp: Option<i32>,
+}
+
+/// Glyphs `start..end` set `raise` above the baseline (below it when negative), as
+/// superscripts are: a `down4 -raise` before the first glyph of the range on each
+/// line and a `down4 raise` after its last one. No rule or fill may be anchored
+/// inside the range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviTextRaiseV0 {
+    pub start: usize,
+    pub end: usize,
+    pub raise: i32,
+}
+
+/// A `right4 width` before the text byte at `offset`, made after the first
+/// `special_index` specials and `rule_index` rules and before the fills anchored
+/// up to it; negative kerns move left, as math sets a denominator under its
+/// numerator, but never past the start of the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviTextKernV0 {
+    pub offset: usize,
+    pub special_index: usize,
+    pub rule_index: usize,
+    pub width: i32,
+}
+
+/// Everything the text page writer anchors at text offsets.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DviTextMarksV0<'a> {
+    pub specials: &'a [DviTextSpecialV0],
+    pub rules: &'a [DviTextRuleV0],
+    pub indents: &'a [DviTextIndentV0],
+    pub fonts: &'a [DviTextFontV0],
+    pub font_switches: &'a [DviTextFontSwitchV0],
+    pub fills: &'a [DviTextFillV0],
+    pub raises: &'a [DviTextRaiseV0],
+    pub kerns: &'a [DviTextKernV0],
+    pub floats: &'a [DviTextFloatV0],
+    pub footnotes: DviTextFootnotesV0<'a>,
+    pub pages: DviTextPagesV0<'a>,
+}
+
+fn rule_dimension_ok_v0(value: i32) -> bool {
+    (0..=MAX_DVI_RULE_DIMENSION_SP_V0).contains(&value)
+}
+
+/// Anchors must be ordered and within the text, each rule's `special_index` must
+/// agree with the special offsets around it, as each kern's `special_index` and
+/// `rule_index` with the special and rule offsets, raises must be disjoint, in order and clear of rules and fills, and
+/// kerns must move.
+pub(crate) fn validate_text_marks_v0(text_len: usize, marks: &DviTextMarksV0<'_>) -> Option<()> {
+    let DviTextMarksV0 {
+        specials,
+        rules,
+        fills,
+        raises,
+        kerns,
+        ..
+    } = *marks;
+    let inside_raise = |offset: usize| {
+        raises
+            .iter()
+            .any(|raise| raise.start < offset && offset < raise.end)
+    };
+    if specials.len() > MAX_DVI_SPECIALS_V0
+        || raises.len() > MAX_DVI_TEXT_RAISES_V0
+        || raises.iter().any(|raise| {
+            raise.start >= raise.end
+                || raise.end > text_len
+                || raise.raise == 0
+                || raise.raise.unsigned_abs() > MAX_DVI_RULE_DIMENSION_SP_V0 as u32
+        })
+        || raises.windows(2).any(|pair| pair[0].end > pair[1].start)
+        || rules.iter().any(|rule| inside_raise(rule.offset))
+        || fills.iter().any(|fill| inside_raise(fill.offset))
+        || kerns.len() > MAX_DVI_TEXT_KERNS_V0
+        || kerns
+            .windows(2)
+            .any(|pair| (pair[0].offset, pair[0].rule_index) > (pair[1].offset, pair[1].rule_index))
+        || kerns
+            .windows(2)
+            .any(|pair| pair[0].special_index > pair[1].special_index)
+        || kerns.iter().any(|kern| {
+            let before = kern
+                .rule_index
+                .checked_sub(1)
+                .map(|index| rules.get(index).map(|rule| rule.offset));
+            let after = rules.get(kern.rule_index).map(|rule| rule.offset);
+            let special_before = kern
+                .special_index
+                .checked_sub(1)
+                .map(|index| specials.get(index).map(|special| special.offset));
+            let special_after = specials
+                .get(kern.special_index)
+                .map(|special| special.offset);
+            kern.offset > text_len
+                || before.is_some_and(|offset| offset.is_none_or(|offset| offset > kern.offset))
+                || after.is_some_and(|offset| offset < kern.offset)
+                || special_before
+                    .is_some_and(|offset| offset.is_none_or(|offset| offset > kern.offset))
+                || special_after.is_some_and(|offset| offset < kern.offset)
+                || kern.width == 0
+                || kern.width.unsigned_abs() > MAX_DVI_RULE_DIMENSION_SP_V0 as u32
+        })
+        || specials.len() > MAX_DVI_SPECIALS_V0
+        || rules.len() > MAX_DVI_TEXT_RULES_V0
+        || fills.len() > MAX_DVI_TEXT_FILLS_V0
+        || fills.windows(2).any(|pair| pair[0].offset > pair[1].offset)
+        || fills.iter().any(|fill| {
+            fill.offset > text_len
+                || !rule_dimension_ok_v0(fill.to_h)
+                || fill
+                    .leader_pitch_sp
+                    .is_some_and(|pitch| pitch <= 0 || !rule_dimension_ok_v0(pitch))
+        })
+        || specials
+            .windows(2)
+            .any(|pair| pair[0].offset > pair[1].offset)
+        || specials.last().is_some_and(|last| last.offset > text_len)
+        || rules.windows(2).any(|pair| {
+            (pair[0].offset, pair[0].special_index) > (pair[1].offset, pair[1].special_index)
+        })
+    {
+        return None;
+    }
+    for rule in rules {
+        let before = rule
+            .special_index
+            .checked_sub(1)
+            .map(|index| specials.get(index).map(|special| special.offset));
+        let after = specials
+            .get(rule.special_index)
+            .map(|special| special.offset);
+        if rule.offset > text_len
+            || before.is_some_and(|offset| offset.is_none_or(|offset| offset > rule.offset))
+            || after.is_some_and(|offset| offset < rule.offset)
+            || !rule_dimension_ok_v0(rule.width)
+            || !rule_dimension_ok_v0(rule.height)
+            || rule.raise.unsigned_abs() > MAX_DVI_RULE_DIMENSION_SP_V0 as u32
+        {
+            return None;
+        }
+    }
+    Some(())
+}
+
+/// Specials, rules, fills and kerns of one text in emission order; `next_*` are the
+/// first ones not yet emitted. Raises are looked up by offset.
+pub(crate) struct TextMarkCursorV0<'a> {
+    pub(crate) specials: &'a [DviTextSpecialV0],
+    pub(crate) rules: &'a [DviTextRuleV0],
+    pub(crate) fills: &'a [DviTextFillV0],
+    pub(crate) raises: &'a [DviTextRaiseV0],
+    pub(crate) kerns: &'a [DviTextKernV0],
+    pub(crate) next_special: usize,
+    pub(crate) next_rule: usize,
+    pub(crate) next_fill: usize,
+    pub(crate) next_kern: usize,
+}
+
+/// Horizontal effect of the material emitted on a line so far: `advance` is the
+/// current position and `extent` the rightmost point reached, rules included.
+#[derive(Default)]
+pub(crate) struct LineWidthV0 {
+    pub(crate) advance: u32,
+    pub(crate) extent: u32,
+}
+
+impl TextMarkCursorV0<'_> {
+    /// Whether every special, rule, fill and kern anchored before `offset` was
+    /// emitted and none after it.
+    pub(crate) fn is_done_through_v0(&self, offset: usize) -> bool {
+        self.next_special
+            == self
+                .specials
+                .partition_point(|special| special.offset < offset)
+            && self.next_rule == self.rules.partition_point(|rule| rule.offset < offset)
+            && self.next_fill == self.fills.partition_point(|fill| fill.offset < offset)
+            && self.next_kern == self.kerns.partition_point(|kern| kern.offset < offset)
+    }
+
+    /// Raise of the glyph at `offset`, 0 outside every raise.
+    fn raise_at_v0(&self, offset: usize) -> i32 {
+        self.raises
+            .get(self.raises.partition_point(|raise| raise.end <= offset))
+            .filter(|raise| raise.start <= offset)
+            .map_or(0, |raise| raise.raise)
+    }
+
+    /// Specials, rules and kerns, then fills, anchored at or before `offset`.
+    fn emit_through_with_fills_v0(
+        &mut self,
+        out: &mut Vec<u8>,
+        offset: usize,
+        width: &mut LineWidthV0,
+        fonts: &TextFontsV0<'_>,
+        font: &mut usize,
+    ) -> Option<()> {
+        self.emit_through_v0(out, offset, width)?;
+        while let Some(fill) = self
+            .fills
+            .get(self.next_fill)
+            .filter(|fill| fill.offset <= offset)
+        {
+            emit_fill_v0(out, fill, width, fonts, font)?;
+            self.next_fill += 1;
+        }
+        Some(())
+    }
+
+    fn emit_through_v0(
+        &mut self,
+        out: &mut Vec<u8>,
+        offset: usize,
+        width: &mut LineWidthV0,
+    ) -> Option<()> {
+        loop {
+            let special = self
+                .specials
+                .get(self.next_special)
+                .filter(|special| special.offset <= offset);
+            let rule = self
+                .rules
+                .get(self.next_rule)
+                .filter(|rule| rule.offset <= offset);
+            let kern = self
+                .kerns
+                .get(self.next_kern)
+                .filter(|kern| kern.offset <= offset);
+            match (special, rule, kern) {
+                (_, _, Some(kern))
+                    if kern.rule_index <= self.next_rule
+                        && kern.special_index <= self.next_special =>
+                {
+                    out.push(DVI_RIGHT4);
+                    push_i32_be(out, kern.width);
+                    width.advance = width.advance.checked_add_signed(kern.width)?;
+                    self.next_kern += 1;
+                }
+                (_, Some(rule), _) if rule.special_index <= self.next_special => {
+                    append_text_rule_v0(out, rule)?;
+                    let right = width.advance.checked_add(rule.width as u32)?;
+                    width.extent = width.extent.max(right);
+                    if rule.advance {
+                        width.advance = right;
+                    }
+                    self.next_rule += 1;
+                }
+                (Some(special), _, _) => {
+                    append_special_v0(out, &special.bytes)?;
+                    self.next_special += 1;
+                }
+                _ => return Some(()),
+            }
+        }
+    }
+}
+
+fn append_special_v0(out: &mut Vec<u8>, bytes: &[u8]) -> Option<()> {
+    if bytes.len() > MAX_DVI_SPECIAL_BYTES_V0 {
+        return None;
+    }
+    if let Ok(len) = u8::try_from(bytes.len()) {
+        out.push(DVI_XXX1);
+        out.push(len);
+    } else {
+        out.push(DVI_XXX4);
+        push_u32_be(out, u32::try_from(bytes.len()).ok()?);
+    }
+    out.extend_from_slice(bytes);
+    Some(())
+}
+
+/// `set_rule`/`put_rule`, framed by `down4 -raise` and `down4 raise` when raised.
+pub(crate) fn append_text_rule_v0(out: &mut Vec<u8>, rule: &DviTextRuleV0) -> Option<()> {
+    if rule.raise != 0 {
+        out.push(DVI_DOWN4);
+        push_i32_be(out, rule.raise.checked_neg()?);
+    }
+    out.push(if rule.advance {
+        DVI_SET_RULE
+    } else {
+        DVI_PUT_RULE
+    });
+    push_i32_be(out, rule.height);
+    push_i32_be(out, rule.width);
+    if rule.raise != 0 {
+        out.push(DVI_DOWN4);
+        push_i32_be(out, rule.raise);
+    }
+    Some(())
+}
+
+/// Reads one rule as written by `append_text_rule_v0` starting at `index`; returns
+/// its width and whether it advances.
+pub(crate) fn read_text_rule_v0(bytes: &[u8], index: &mut usize) -> Option<(u32, bool)> {
+    let mut raise = None::<i32>;
+    if bytes.get(*index) == Some(&DVI_DOWN4) {
+        *index += 1;
+        let lowered = read_i32_be(bytes, index)?;
+        if lowered == 0 || lowered.unsigned_abs() > MAX_DVI_RULE_DIMENSION_SP_V0 as u32 {
+            return None;
+        }
+        raise = Some(-lowered);
+    }
+    let advance = match read_u8(bytes, index)? {
+        DVI_SET_RULE => true,
+        DVI_PUT_RULE => false,
+        _ => return None,
+    };
+    let height = read_i32_be(bytes, index)?;
+    let width = read_i32_be(bytes, index)?;
+    if !rule_dimension_ok_v0(height) || !rule_dimension_ok_v0(width) {
+        return None;
+    }
+    if let Some(raise) = raise {
+        if read_u8(bytes, index)? != DVI_DOWN4 || read_i32_be(bytes, index)? != raise {
+            return None;
+        }
+    }
+    Some((width as u32, advance))
+}
+
+/// `right4` over the gap from the current position to `h`, if any.
+fn move_right_to_v0(out: &mut Vec<u8>, width: &mut LineWidthV0, h: u32) -> Option<()> {
+    if h > width.advance {
+        out.push(DVI_RIGHT4);
+        push_i32_be(out, i32::try_from(h - width.advance).ok()?);
+        width.advance = h;
+    }
+    Some(())
+}
+
+fn emit_fill_v0(
+    out: &mut Vec<u8>,
+    fill: &DviTextFillV0,
+    width: &mut LineWidthV0,
+    fonts: &TextFontsV0<'_>,
+    font: &mut usize,
+) -> Option<()> {
+    let to_h = fill.to_h as u32;
+    if let Some(pitch) = fill.leader_pitch_sp {
+        let pitch = pitch as u32;
+        let dot_font = fonts.font_at_v0(fill.offset);
+        let dot_width = fonts.glyph_width_at_v0(fill.offset, b'.')? as u32;
+        if dot_width > pitch {
+            return None;
+        }
+        let mut box_h = width.advance.div_ceil(pitch).checked_mul(pitch)?;
+        let mut dots = 0u32;
+        while box_h.checked_add(pitch)? <= to_h {
+            dots += 1;
+            if dots > MAX_DVI_FILL_LEADERS_V0 {
+                return None;
+            }
+            move_right_to_v0(out, width, box_h + (pitch - dot_width) / 2)?;
+            if dot_font != *font {
+                out.push(fnt_num_v0(dot_font));
+                *font = dot_font;
+            }
+            out.push(b'.');
+            out.push(DVI_RIGHT3);
+            push_i24_be(out, dot_width as i32)?;
+            width.advance = width.advance.checked_add(dot_width)?;
+            box_h += pitch;
+        }
+    }
+    move_right_to_v0(out, width, to_h)?;
+    width.extent = width.extent.max(width.advance);
+    Some(())
+}
+
+/// Moves from the baseline raised by `raised` to the one raised by `raise`.
+fn move_raise_to_v0(out: &mut Vec<u8>, raised: &mut i32, raise: i32) -> Option<()> {
+    if *raised != 0 {
+        out.push(DVI_DOWN4);
+        push_i32_be(out, *raised);
+    }
+    if raise != 0 {
+        out.push(DVI_DOWN4);
+        push_i32_be(out, raise.checked_neg()?);
+    }
+    *raised = raise;
+    Some(())
+}
+
+/// Emits one line; `font` is the selected font, switched with `fnt_num` before the
+/// first glyph set in another one. Codes from 128 are set with `set1`. Raised glyphs
+/// leave the baseline only after the marks anchored before them and return to it
+/// before the marks after them, and before any rule, even between two runs raised
+/// alike.
+pub(crate) fn emit_text_line_v0(
+    out: &mut Vec<u8>,
+    text: &[u8],
+    line: &TextLineV0,
+    marks: &mut TextMarkCursorV0<'_>,
+    fonts: &TextFontsV0<'_>,
+    font: &mut usize,
+) -> Option<LineWidthV0> {
+    let mut width = LineWidthV0::default();
+    move_right_to_v0(out, &mut width, line.indent_h)?;
+    let mut raised = 0i32;
+    for (offset, byte) in text.iter().enumerate().take(line.end).skip(line.start) {
+        let raise = marks.raise_at_v0(offset);
+        let rule_pending = marks
+            .rules
+            .get(marks.next_rule)
+            .is_some_and(|rule| rule.offset <= offset);
+        if raise != raised || rule_pending {
+            move_raise_to_v0(out, &mut raised, 0)?;
+        }
+        if offset == line.label_end {
+            move_right_to_v0(out, &mut width, line.text_h)?;
+        }
+        marks.emit_through_with_fills_v0(out, offset, &mut width, fonts, font)?;
+        if raise != raised {
+            move_raise_to_v0(out, &mut raised, raise)?;
+        }
+        let glyph_font = fonts.font_at_v0(offset);
+        if glyph_font != *font {
+            out.push(fnt_num_v0(glyph_font));
+            *font = glyph_font;
+        }
+        if *byte >= DVI_SET1 {
+            out.push(DVI_SET1);
+        }
+        out.push(*byte);
+        let glyph_width = fonts.glyph_width_at_v0(offset, *byte)?;
+        out.push(DVI_RIGHT3);
+        push_i24_be(out, glyph_width)?;
+        width.advance = width
+            .advance
+            .checked_add(u32::try_from(glyph_width).ok()?)?;
+        width.extent = width.extent.max(width.advance);
+    }
+    move_raise_to_v0(out, &mut raised, 0)?;
+    if line.label_end == line.end {
+        move_right_to_v0(out, &mut width, line.text_h)?;
+    }
+    marks.emit_through_with_fills_v0(out, line.owned_end, &mut width, fonts, font)?;
+    Some(width)
+}
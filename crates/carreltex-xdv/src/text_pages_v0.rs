@@ -0,0 +1,208 @@
+use crate::text_fonts_v0::{fnt_num_v0, TextFontsV0};
+use crate::text_marks_v0::DVI_DOWN4;
+use crate::{
+    glyph_width_sp_v0, is_supported_text_byte_v0, push_i24_be, push_i32_be, read_i24_be,
+    read_i32_be, read_u8, DVI_FNT_NUM_0, DVI_RIGHT3, DVI_RIGHT4, MAX_DVI_RULE_DIMENSION_SP_V0,
+};
+
+pub(crate) const DVI_PUSH: u8 = 141;
+pub(crate) const DVI_POP: u8 = 142;
+/// Runs a running head or foot may have.
+pub const MAX_DVI_TEXT_RUNS_V0: usize = 8;
+pub const MAX_DVI_TEXT_RUN_BYTES_V0: usize = 256;
+
+/// Glyphs of a running head or foot set from `h` on in `font` (0 for the default
+/// font, `n` for the `n`th of `DviTextMarksV0::fonts`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DviTextRunV0 {
+    pub h: i32,
+    pub font: usize,
+    pub bytes: Vec<u8>,
+}
+
+/// The `\count0` of one output page and its running head and foot, runs ordered
+/// left to right without overlapping.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DviTextFurnitureV0 {
+    pub number: i32,
+    pub head: Vec<DviTextRunV0>,
+    pub foot: Vec<DviTextRunV0>,
+}
+
+/// Furniture of every page, or of none; heads sit on baseline `head_v` and feet on
+/// `foot_v`, measured from the page's first line. Every page's first line starts
+/// at `(origin_h, origin_v)` from the DVI origin.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DviTextPagesV0<'a> {
+    pub furniture: &'a [DviTextFurnitureV0],
+    pub head_v: i32,
+    pub foot_v: i32,
+    pub origin_h: i32,
+    pub origin_v: i32,
+}
+
+impl DviTextPagesV0<'_> {
+    pub(crate) fn validate_v0(&self, page_count: usize, fonts: &TextFontsV0<'_>) -> Option<()> {
+        let dimension = -MAX_DVI_RULE_DIMENSION_SP_V0..=MAX_DVI_RULE_DIMENSION_SP_V0;
+        if !self.furniture.is_empty() && self.furniture.len() != page_count
+            || !dimension.contains(&self.head_v)
+            || !dimension.contains(&self.foot_v)
+            || !dimension.contains(&self.origin_h)
+            || !dimension.contains(&self.origin_v)
+        {
+            return None;
+        }
+        for furniture in self.furniture {
+            for runs in [&furniture.head, &furniture.foot] {
+                let mut h = 0i64;
+                if runs.len() > MAX_DVI_TEXT_RUNS_V0 {
+                    return None;
+                }
+                for run in runs {
+                    if i64::from(run.h) < h
+                        || run.bytes.len() > MAX_DVI_TEXT_RUN_BYTES_V0
+                        || !run
+                            .bytes
+                            .iter()
+                            .all(|byte| is_supported_text_byte_v0(*byte))
+                        || run.font > fonts.font_count_v0()
+                    {
+                        return None;
+                    }
+                    h = i64::from(run.h) + i64::from(run_width_v0(run, fonts)?);
+                    if h > i64::from(MAX_DVI_RULE_DIMENSION_SP_V0) {
+                        return None;
+                    }
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+fn run_width_v0(run: &DviTextRunV0, fonts: &TextFontsV0<'_>) -> Option<i32> {
+    let em = fonts.em_v0(run.font);
+    run.bytes.iter().try_fold(0i32, |width, byte| {
+        width.checked_add(glyph_width_sp_v0(*byte, em)?)
+    })
+}
+
+/// A head or foot between `push` and `pop`: `down4` to its baseline, then each run
+/// as a `right4` to its start, its font and its glyphs. Returns its right edge.
+fn emit_running_line_v0(
+    out: &mut Vec<u8>,
+    runs: &[DviTextRunV0],
+    v: i32,
+    fonts: &TextFontsV0<'_>,
+) -> Option<u32> {
+    out.push(DVI_PUSH);
+    out.push(DVI_DOWN4);
+    push_i32_be(out, v);
+    let mut h = 0i32;
+    for run in runs {
+        if run.h > h {
+            out.push(DVI_RIGHT4);
+            push_i32_be(out, run.h - h);
+            h = run.h;
+        }
+        out.push(fnt_num_v0(run.font));
+        for byte in &run.bytes {
+            let width = glyph_width_sp_v0(*byte, fonts.em_v0(run.font))?;
+            out.push(*byte);
+            out.push(DVI_RIGHT3);
+            push_i24_be(out, width)?;
+            h += width;
+        }
+    }
+    out.push(DVI_POP);
+    u32::try_from(h).ok()
+}
+
+/// `right4`/`down4` from the DVI origin to the first line, where either is non-zero.
+pub(crate) fn emit_origin_v0(out: &mut Vec<u8>, pages: &DviTextPagesV0<'_>) {
+    for (op, amount) in [(DVI_RIGHT4, pages.origin_h), (DVI_DOWN4, pages.origin_v)] {
+        if amount != 0 {
+            out.push(op);
+            push_i32_be(out, amount);
+        }
+    }
+}
+
+/// Reads the moves `emit_origin_v0` writes.
+pub(crate) fn read_origin_v0(bytes: &[u8], index: &mut usize) -> Option<()> {
+    for op in [DVI_RIGHT4, DVI_DOWN4] {
+        if bytes.get(*index) == Some(&op) {
+            *index += 1;
+            let amount = read_i32_be(bytes, index)?;
+            if amount == 0 || amount.unsigned_abs() > MAX_DVI_RULE_DIMENSION_SP_V0 as u32 {
+                return None;
+            }
+        }
+    }
+    Some(())
+}
+
+/// Head and foot of a page, before its first line; returns their extent and their
+/// lowest baseline below the page's top.
+pub(crate) fn emit_furniture_v0(
+    out: &mut Vec<u8>,
+    furniture: &DviTextFurnitureV0,
+    pages: &DviTextPagesV0<'_>,
+    fonts: &TextFontsV0<'_>,
+) -> Option<(u32, u32)> {
+    let mut extent = (0u32, 0u32);
+    for (runs, v) in [
+        (&furniture.head, pages.head_v),
+        (&furniture.foot, pages.foot_v),
+    ] {
+        if !runs.is_empty() {
+            let h = emit_running_line_v0(out, runs, v, fonts)?;
+            extent = (extent.0.max(h), extent.1.max(v.max(0) as u32));
+        }
+    }
+    Some(extent)
+}
+
+/// Reads a head or foot as `emit_running_line_v0` writes it; returns its extent and
+/// baseline.
+pub(crate) fn read_running_line_v0(
+    bytes: &[u8],
+    index: &mut usize,
+    font_ems: &[i32],
+) -> Option<(u32, i32)> {
+    if read_u8(bytes, index)? != DVI_PUSH || read_u8(bytes, index)? != DVI_DOWN4 {
+        return None;
+    }
+    let v = read_i32_be(bytes, index)?;
+    if v.unsigned_abs() > MAX_DVI_RULE_DIMENSION_SP_V0 as u32 {
+        return None;
+    }
+    let mut h = 0u32;
+    let mut font_em = None::<i32>;
+    loop {
+        match read_u8(bytes, index)? {
+            DVI_POP => return Some((h, v)),
+            DVI_RIGHT4 => {
+                let amount = read_i32_be(bytes, index)?;
+                if !(1..=MAX_DVI_RULE_DIMENSION_SP_V0).contains(&amount) {
+                    return None;
+                }
+                h = h.checked_add(amount as u32)?;
+            }
+            op if op >= DVI_FNT_NUM_0 => {
+                font_em = Some(*font_ems.get(usize::from(op - DVI_FNT_NUM_0))?);
+            }
+            op if is_supported_text_byte_v0(op) => {
+                let width = glyph_width_sp_v0(op, font_em?)?;
+                if read_u8(bytes, index)? != DVI_RIGHT3 || read_i24_be(bytes, index)? != width {
+                    return None;
+                }
+                h = h.checked_add(width as u32)?;
+            }
+            _ => return None,
+        }
+        if h > MAX_DVI_RULE_DIMENSION_SP_V0 as u32 {
+            return None;
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use super::{
+    read_dvi_v0, validate_dvi_v2_text_page_v0,
+    write_dvi_v2_text_page_with_layout_paging_and_marks_v0, DviTextFontV0, DviTextFurnitureV0,
+    DviTextMarksV0, DviTextPagesV0, DviTextRunV0,
+};
+
+const LINE_SP: i32 = 786_432;
+
+fn run_v0(h: i32, font: usize, bytes: &[u8]) -> DviTextRunV0 {
+    DviTextRunV0 {
+        h,
+        font,
+        bytes: bytes.to_vec(),
+    }
+}
+
+#[test]
+fn furniture_numbers_pages_and_sets_heads_and_feet_around_the_text() {
+    let fonts = [DviTextFontV0 {
+        name: b"carreltex-v0-rm-m-sl".to_vec(),
+        size_sp: 327_680,
+    }];
+    let furniture = [
+        DviTextFurnitureV0 {
+            number: 3,
+            head: vec![run_v0(0, 1, b"AB"), run_v0(200_000, 0, b"iii")],
+            foot: vec![run_v0(100_000, 0, b"iii")],
+        },
+        DviTextFurnitureV0 {
+            number: 4,
+            head: Vec::new(),
+            foot: vec![run_v0(100_000, 0, b"iv")],
+        },
+    ];
+    let pages = DviTextPagesV0 {
+        furniture: &furniture,
+        head_v: -2_293_760,
+        foot_v: 3_000_000,
+        ..DviTextPagesV0::default()
+    };
+    let marks = DviTextMarksV0 {
+        fonts: &fonts,
+        pages,
+        ..DviTextMarksV0::default()
+    };
+    let write = |marks: &DviTextMarksV0<'_>| {
+        write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+            b"ab\ncd", marks, 65_536, LINE_SP, 80, 1,
+        )
+    };
+    let bytes = write(&marks).expect("writer should accept furniture");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+    let document = read_dvi_v0(&bytes).expect("reader should accept the writer's output");
+    let counts = document
+        .pages
+        .iter()
+        .map(|page| page.counts)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        counts,
+        [
+            [3, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            [4, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        ]
+    );
+    let glyphs = document.pages[0]
+        .glyphs
+        .iter()
+        .map(|glyph| (glyph.code as u8, glyph.h, glyph.v, glyph.font_num))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        glyphs,
+        [
+            (b'A', 0, -2_293_760, 1),
+            (b'B', 32_768, -2_293_760, 1),
+            (b'i', 200_000, -2_293_760, 0),
+            (b'i', 232_768, -2_293_760, 0),
+            (b'i', 265_536, -2_293_760, 0),
+            (b'i', 100_000, 3_000_000, 0),
+            (b'i', 132_768, 3_000_000, 0),
+            (b'i', 165_536, 3_000_000, 0),
+            (b'a', 0, 0, 0),
+            (b'b', 65_536, 0, 0),
+        ]
+    );
+    assert_eq!(document.postamble.max_width, 3_000_000);
+    assert_eq!(document.postamble.max_stack_depth, 1);
+
+    // Furniture must cover every page with ordered runs in defined fonts.
+    let mut bad_pages = vec![furniture[..1].to_vec()];
+    for bad_run in [
+        run_v0(250_000, 0, b"x"),
+        run_v0(300_000, 2, b"x"),
+        run_v0(300_000, 0, b"\\\x01"),
+    ] {
+        let mut bad = furniture.to_vec();
+        bad[0].head.push(bad_run);
+        bad_pages.push(bad);
+    }
+    for bad in &bad_pages {
+        let marks = DviTextMarksV0 {
+            pages: DviTextPagesV0 {
+                furniture: bad,
+                ..pages
+            },
+            ..marks
+        };
+        assert!(write(&marks).is_none(), "{bad:?}");
+    }
+}
+
+#[test]
+fn every_page_starts_at_the_origin_of_its_first_line() {
+    let furniture = [
+        DviTextFurnitureV0 {
+            number: 1,
+            head: Vec::new(),
+            foot: vec![run_v0(0, 0, b"1")],
+        },
+        DviTextFurnitureV0::default(),
+    ];
+    let pages = DviTextPagesV0 {
+        furniture: &furniture,
+        foot_v: 2 * LINE_SP,
+        origin_h: 4_736_286,
+        origin_v: 655_360,
+        ..DviTextPagesV0::default()
+    };
+    let marks = DviTextMarksV0 {
+        pages,
+        ..DviTextMarksV0::default()
+    };
+    let bytes = write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+        b"a\nb\x0cc",
+        &marks,
+        65_536,
+        LINE_SP,
+        80,
+        2,
+    )
+    .expect("writer should accept an origin");
+    assert!(validate_dvi_v2_text_page_v0(&bytes));
+    let document = read_dvi_v0(&bytes).expect("reader should accept the writer's output");
+    let glyphs = document
+        .pages
+        .iter()
+        .map(|page| {
+            page.glyphs
+                .iter()
+                .map(|glyph| (glyph.code as u8, glyph.h, glyph.v))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        glyphs,
+        [
+            vec![
+                (b'1', 4_736_286, 655_360 + 2 * LINE_SP),
+                (b'a', 4_736_286, 655_360),
+                (b'b', 4_736_286, 655_360 + LINE_SP),
+            ],
+            vec![(b'c', 4_736_286, 655_360)],
+        ]
+    );
+}
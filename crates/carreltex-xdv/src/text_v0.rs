@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use crate::interpret_v0::{DviFontDefV0, DviReadErrorV0};
+use crate::reader_v0::{
+    read_dvi_with_char_widths_v0, zero_scale_char_width_v0, DviGlyphV0, DviPageV0,
+};
+
+/// Upper bound on line breaks produced by a single vertical move.
+pub const MAX_TEXT_LINE_BREAKS_V0: usize = 256;
+
+/// Thresholds for `page_text_v0`, in DVI units (scaled points for files using the
+/// standard `DVI_NUM`/`DVI_DEN`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DviTextOptionsV0 {
+    /// A horizontal gap wider than this between consecutive glyphs becomes a space.
+    pub space_gap: i32,
+    /// A baseline change larger than this starts a new line.
+    pub line_gap: i32,
+    /// Baseline distance of one line; when non-zero, downward moves spanning several
+    /// pitches add blank lines. Zero emits one newline per line change.
+    pub line_pitch: i32,
+}
+
+impl Default for DviTextOptionsV0 {
+    fn default() -> Self {
+        DviTextOptionsV0 {
+            space_gap: 98_304,
+            line_gap: 262_144,
+            line_pitch: 0,
+        }
+    }
+}
+
+/// Character resolver covering printable ASCII codes of TFM fonts; native glyph ids
+/// need the font's `cmap` and resolve to `None`.
+pub fn ascii_glyph_char_v0(glyph: &DviGlyphV0) -> Option<char> {
+    if glyph.native || !(0x20..=0x7e).contains(&glyph.code) {
+        return None;
+    }
+    char::from_u32(glyph.code)
+}
+
+fn line_breaks_v0(dv: i64, options: &DviTextOptionsV0) -> usize {
+    if dv <= 0 || options.line_pitch <= 0 {
+        return 1;
+    }
+    let pitch = options.line_pitch as i64;
+    let lines = (dv + pitch / 2) / pitch;
+    lines.clamp(1, MAX_TEXT_LINE_BREAKS_V0 as i64) as usize
+}
+
+/// Reading-order text of one page. Glyphs whose baseline lies within `line_gap` of
+/// a line's first glyph join that line; lines run top to bottom and keep DVI order
+/// (which is TeX's output order) within, so furniture such as a footer folio comes
+/// after the body above it. Glyphs with a zero width, such as those of scale-0
+/// fonts, are assumed to end where the next glyph starts. Unresolved glyphs become
+/// U+FFFD.
+pub fn page_text_v0<M>(page: &DviPageV0, options: &DviTextOptionsV0, mut glyph_char: M) -> String
+where
+    M: FnMut(&DviGlyphV0) -> Option<char>,
+{
+    let mut text = String::new();
+    let mut previous_line = None::<i32>;
+    for line in page_lines_v0(page, options) {
+        if let Some(previous_line) = previous_line {
+            let dv = line[0].v as i64 - previous_line as i64;
+            for _ in 0..line_breaks_v0(dv, options) {
+                text.push('\n');
+            }
+        }
+        let mut previous = None::<&DviGlyphV0>;
+        for glyph in &line {
+            if let Some(previous) = previous.filter(|previous| previous.width != 0) {
+                let end = previous.h as i64 + previous.width as i64;
+                if glyph.h as i64 - end > options.space_gap as i64 {
+                    text.push(' ');
+                }
+            }
+            text.push(glyph_char(glyph).unwrap_or(char::REPLACEMENT_CHARACTER));
+            previous = Some(glyph);
+        }
+        previous_line = Some(line[0].v);
+    }
+    text
+}
+
+fn page_lines_v0<'a>(page: &'a DviPageV0, options: &DviTextOptionsV0) -> Vec<Vec<&'a DviGlyphV0>> {
+    let gap = options.line_gap.max(0) as i64;
+    let mut baselines = BTreeMap::<i32, usize>::new();
+    let mut lines = Vec::<Vec<&DviGlyphV0>>::new();
+    for glyph in &page.glyphs {
+        let low = (glyph.v as i64 - gap).max(i32::MIN as i64) as i32;
+        let high = (glyph.v as i64 + gap).min(i32::MAX as i64) as i32;
+        let nearest = baselines
+            .range(low..=high)
+            .min_by_key(|(baseline, _)| (**baseline as i64 - glyph.v as i64).abs())
+            .map(|(_, index)| *index);
+        match nearest {
+            Some(index) => lines[index].push(glyph),
+            None => {
+                baselines.insert(glyph.v, lines.len());
+                lines.push(vec![glyph]);
+            }
+        }
+    }
+    lines.sort_by_key(|line| line[0].v);
+    lines
+}
+
+/// Per-page text of any DVI/XDV file via `page_text_v0` and `ascii_glyph_char_v0`;
+/// `char_width` is passed to `read_dvi_with_char_widths_v0`.
+pub fn extract_text_with_char_widths_v0<W>(
+    bytes: &[u8],
+    char_width: W,
+    options: &DviTextOptionsV0,
+) -> Result<Vec<String>, DviReadErrorV0>
+where
+    W: FnMut(&DviFontDefV0, u32) -> Option<i32>,
+{
+    let document = read_dvi_with_char_widths_v0(bytes, char_width)?;
+    Ok(document
+        .pages
+        .iter()
+        .map(|page| page_text_v0(page, options, ascii_glyph_char_v0))
+        .collect())
+}
+
+pub fn extract_text_v0(
+    bytes: &[u8],
+    options: &DviTextOptionsV0,
+) -> Result<Vec<String>, DviReadErrorV0> {
+    extract_text_with_char_widths_v0(bytes, zero_scale_char_width_v0, options)
+}
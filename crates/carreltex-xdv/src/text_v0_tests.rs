@@ -0,0 +1,230 @@
+use super::test_dvi_v0::{build_tfm_v0, fnt_def1_v0, op_i32_v0, TestDviV0};
+use super::{
+    ascii_glyph_char_v0, extract_text_v0, extract_text_with_char_widths_v0, page_text_v0,
+    parse_tfm_v0, tfm_char_width_v0, wrap_logical_line_ranges_v0,
+    write_dvi_v2_text_page_with_layout_paging_and_marks_v0,
+    write_dvi_v2_text_page_with_layout_wrap_and_paging_v0, DviGlyphV0, DviPageV0,
+    DviReadErrorKindV0, DviTextFurnitureV0, DviTextMarksV0, DviTextOptionsV0, DviTextPagesV0,
+    DviTextRunV0,
+};
+
+const TEN_PT_SP: i32 = 655_360;
+const TWELVE_PT_SP: i32 = 786_432;
+
+/// xorshift64; the property test only needs a fixed, reproducible stream.
+struct RngV0(u64);
+
+impl RngV0 {
+    fn next_v0(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound
+    }
+}
+
+fn random_text_v0(rng: &mut RngV0) -> Vec<u8> {
+    let len = rng.next_v0(120) as usize;
+    (0..len)
+        .map(|_| match rng.next_v0(20) {
+            0 => b'\n',
+            1 => 0x0c,
+            2..=5 => b' ',
+            _ => 0x21 + rng.next_v0(0x5e) as u8,
+        })
+        .collect()
+}
+
+/// Page texts the writer's layout should read back as: wrapped physical lines per
+/// page, with leading and trailing blank lines dropped since they carry no glyphs.
+fn expected_pages_v0(text: &[u8], max_line_glyphs: usize, max_lines: usize) -> Vec<String> {
+    let mut pages = Vec::new();
+    for forced_page in text.split(|byte| *byte == 0x0c) {
+        let mut lines = Vec::<Vec<u8>>::new();
+        for line in forced_page.split(|byte| *byte == b'\n') {
+            lines.extend(
+                wrap_logical_line_ranges_v0(line, max_line_glyphs)
+                    .expect("wrap")
+                    .into_iter()
+                    .map(|(start, end)| line[start..end].to_vec()),
+            );
+        }
+        for chunk in lines.chunks(max_lines) {
+            let first = chunk.iter().position(|line| !line.is_empty());
+            let last = chunk.iter().rposition(|line| !line.is_empty());
+            let page = match (first, last) {
+                (Some(first), Some(last)) => chunk[first..=last]
+                    .iter()
+                    .map(|line| String::from_utf8(line.clone()).expect("ascii"))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                _ => String::new(),
+            };
+            pages.push(page);
+        }
+    }
+    pages
+}
+
+#[test]
+fn text_round_trips_through_the_text_page_writer() {
+    let mut rng = RngV0(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..300 {
+        let text = random_text_v0(&mut rng);
+        let glyph_advance = 1 + rng.next_v0(65_536) as i32;
+        let line_advance = 1 + rng.next_v0(TWELVE_PT_SP as u64) as i32;
+        let max_line_glyphs = 1 + rng.next_v0(40) as usize;
+        let max_lines = 1 + rng.next_v0(8) as usize;
+        let bytes = write_dvi_v2_text_page_with_layout_wrap_and_paging_v0(
+            &text,
+            glyph_advance,
+            line_advance,
+            max_line_glyphs,
+            max_lines,
+        )
+        .expect("writer should accept generated text");
+        let options = DviTextOptionsV0 {
+            space_gap: 0,
+            line_gap: 0,
+            line_pitch: line_advance,
+        };
+        assert_eq!(
+            extract_text_v0(&bytes, &options).expect("writer output should read"),
+            expected_pages_v0(&text, max_line_glyphs, max_lines),
+            "text {text:?} advance {glyph_advance} line {line_advance} wrap {max_line_glyphs} lines {max_lines}"
+        );
+    }
+}
+
+#[test]
+fn spaces_and_newlines_follow_glyph_gaps_and_baselines() {
+    let font = fnt_def1_v0(1, 0, TEN_PT_SP, b"cmr10");
+    let tfm =
+        parse_tfm_v0(&build_tfm_v0(65, &[1 << 19, 1 << 18], &[])).expect("test tfm should parse");
+    let tfms = vec![(b"cmr10".to_vec(), tfm)];
+    let mut dvi = TestDviV0::new(2);
+    dvi.raw(&font)
+        .bop(1)
+        .raw(&[172, 65, 66])
+        .raw(&op_i32_v0(146, 196_608))
+        .raw(&[65, 66])
+        .raw(&op_i32_v0(146, 32_768))
+        .raw(&[65])
+        .raw(&op_i32_v0(160, TWELVE_PT_SP))
+        .raw(&op_i32_v0(146, -2_000_000))
+        .raw(&[66])
+        .raw(&op_i32_v0(160, -131_072))
+        .raw(&[65])
+        .raw(&op_i32_v0(160, 131_072 + 2 * TWELVE_PT_SP))
+        .raw(&[65])
+        .eop();
+    let bytes = dvi.finish(&font);
+
+    let pages = extract_text_with_char_widths_v0(
+        &bytes,
+        tfm_char_width_v0(&tfms),
+        &DviTextOptionsV0::default(),
+    )
+    .expect("tfm file should read");
+    assert_eq!(pages, vec!["AB ABA\nBA\nA".to_string()]);
+
+    let options = DviTextOptionsV0 {
+        line_pitch: TWELVE_PT_SP,
+        ..DviTextOptionsV0::default()
+    };
+    let pages = extract_text_with_char_widths_v0(&bytes, tfm_char_width_v0(&tfms), &options)
+        .expect("tfm file should read");
+    assert_eq!(pages, vec!["AB ABA\nBA\n\nA".to_string()]);
+
+    let error =
+        extract_text_v0(&bytes, &DviTextOptionsV0::default()).expect_err("tfm widths are required");
+    assert_eq!(error.kind, DviReadErrorKindV0::CharWidthUnavailable);
+}
+
+#[test]
+fn footer_runs_follow_the_body_they_sit_under() {
+    let furniture = [1, 2].map(|number| DviTextFurnitureV0 {
+        number,
+        head: vec![DviTextRunV0 {
+            h: 0,
+            font: 0,
+            bytes: b"Head".to_vec(),
+        }],
+        foot: vec![DviTextRunV0 {
+            h: 0,
+            font: 0,
+            bytes: number.to_string().into_bytes(),
+        }],
+    });
+    let marks = DviTextMarksV0 {
+        pages: DviTextPagesV0 {
+            furniture: &furniture,
+            head_v: -2 * TWELVE_PT_SP,
+            foot_v: 3 * TWELVE_PT_SP,
+            ..DviTextPagesV0::default()
+        },
+        ..DviTextMarksV0::default()
+    };
+    let bytes = write_dvi_v2_text_page_with_layout_paging_and_marks_v0(
+        b"ab\ncd\nef",
+        &marks,
+        TEN_PT_SP,
+        TWELVE_PT_SP,
+        80,
+        2,
+    )
+    .expect("writer should accept furniture");
+    let options = DviTextOptionsV0 {
+        line_pitch: TWELVE_PT_SP,
+        ..DviTextOptionsV0::default()
+    };
+    assert_eq!(
+        extract_text_v0(&bytes, &options).expect("writer output should read"),
+        ["Head\n\nab\ncd\n\n1", "Head\n\nef\n\n\n2"]
+    );
+}
+
+#[test]
+fn ascii_resolver_skips_native_and_control_codes() {
+    let glyph = DviGlyphV0 {
+        font_num: 0,
+        code: b'x' as u32,
+        native: false,
+        h: 0,
+        v: 0,
+        width: 0,
+    };
+    assert_eq!(ascii_glyph_char_v0(&glyph), Some('x'));
+    assert_eq!(
+        ascii_glyph_char_v0(&DviGlyphV0 {
+            code: 0x0b,
+            ..glyph
+        }),
+        None
+    );
+    assert_eq!(
+        ascii_glyph_char_v0(&DviGlyphV0 {
+            native: true,
+            ..glyph
+        }),
+        None
+    );
+
+    let page = DviPageV0 {
+        offset: 0,
+        counts: [0; 10],
+        glyphs: vec![
+            glyph,
+            DviGlyphV0 {
+                native: true,
+                ..glyph
+            },
+        ],
+        rules: Vec::new(),
+        specials: Vec::new(),
+    };
+    assert_eq!(
+        page_text_v0(&page, &DviTextOptionsV0::default(), ascii_glyph_char_v0),
+        "x\u{fffd}"
+    );
+}
@@ -0,0 +1,314 @@
+use crate::text_fonts_v0::{read_text_font_defs_v0, text_font_ems_v0};
+use crate::text_marks_v0::{read_text_rule_v0, DVI_DOWN4, DVI_PUT_RULE, DVI_SET1, DVI_SET_RULE};
+use crate::text_pages_v0::{read_origin_v0, read_running_line_v0, DVI_PUSH};
+use crate::{
+    glyph_width_sp_v0, is_supported_text_byte_v0, read_and_validate_font_def_v0, read_i24_be,
+    read_i32_be, read_u16_be, read_u32_be, read_u8, DviColorStackV0, DVI_BOP, DVI_DEN, DVI_DOWN3,
+    DVI_EOP, DVI_FNT_NUM_0, DVI_ID_V2, DVI_MAG, DVI_NUM, DVI_POST, DVI_POSTPOST, DVI_PRE,
+    DVI_RIGHT3, DVI_RIGHT4, DVI_TRAILER_BYTE, DVI_XXX1, DVI_XXX4, MAX_DVI_RULE_DIMENSION_SP_V0,
+    MAX_DVI_SPECIALS_V0, MAX_DVI_SPECIAL_BYTES_V0, MAX_DVI_TEXT_RULES_V0,
+};
+
+pub fn count_dvi_v2_text_movements_with_layout_v0(
+    bytes: &[u8],
+    glyph_advance_sp: i32,
+    line_advance_sp: i32,
+) -> Option<(u32, u32, u32, u32, u16)> {
+    if glyph_advance_sp <= 0 || line_advance_sp <= 0 {
+        return None;
+    }
+    if bytes.is_empty() || !bytes.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let mut index = 0usize;
+    if read_u8(bytes, &mut index) != Some(DVI_PRE) {
+        return None;
+    }
+    if read_u8(bytes, &mut index) != Some(DVI_ID_V2) {
+        return None;
+    }
+    if read_u32_be(bytes, &mut index) != Some(DVI_NUM) {
+        return None;
+    }
+    if read_u32_be(bytes, &mut index) != Some(DVI_DEN) {
+        return None;
+    }
+    if read_u32_be(bytes, &mut index) != Some(DVI_MAG) {
+        return None;
+    }
+    if read_u8(bytes, &mut index) != Some(0) {
+        return None;
+    }
+
+    let mut right3_count = 0u32;
+    let w3_count = 0u32;
+    let w0_count = 0u32;
+    let mut down3_count = 0u32;
+    let mut page_count = 0u16;
+    let mut special_count = 0usize;
+    let mut rule_count = 0usize;
+    let mut color_stack = DviColorStackV0::default();
+    let mut previous_bop_offset: Option<usize> = None;
+    let mut text_fonts = None::<Vec<(Vec<u8>, i32)>>;
+    let mut last_bop_offset = 0u32;
+    let mut max_h = 0u32;
+    let mut max_v = 0u32;
+    let mut pushes = false;
+    loop {
+        let opcode = *bytes.get(index)?;
+        if opcode == DVI_POST {
+            break;
+        }
+        if opcode != DVI_BOP {
+            return None;
+        }
+        let bop_offset = index;
+        last_bop_offset = bop_offset as u32;
+        index += 1;
+        read_i32_be(bytes, &mut index)?;
+        for _ in 1..10 {
+            if read_i32_be(bytes, &mut index) != Some(0) {
+                return None;
+            }
+        }
+        let expected_prev = if let Some(previous) = previous_bop_offset {
+            i32::try_from(previous).ok()?
+        } else {
+            -1
+        };
+        if read_i32_be(bytes, &mut index) != Some(expected_prev) {
+            return None;
+        }
+        read_and_validate_font_def_v0(bytes, &mut index)?;
+        let page_fonts = read_text_font_defs_v0(bytes, &mut index)?;
+        if text_fonts
+            .as_ref()
+            .is_some_and(|text_fonts| *text_fonts != page_fonts)
+        {
+            return None;
+        }
+        let font_ems = text_font_ems_v0(&page_fonts, glyph_advance_sp)?;
+        text_fonts = Some(page_fonts);
+        let mut page_h_max = 0u32;
+        let mut page_v = 0u32;
+        read_origin_v0(bytes, &mut index)?;
+        while bytes.get(index) == Some(&DVI_PUSH) {
+            pushes = true;
+            let (extent, v) = read_running_line_v0(bytes, &mut index, &font_ems)?;
+            page_h_max = page_h_max.max(extent);
+            page_v = page_v.max(v.max(0) as u32);
+        }
+        let mut font_em = *read_u8(bytes, &mut index)
+            .and_then(|op| op.checked_sub(DVI_FNT_NUM_0))
+            .and_then(|font| font_ems.get(usize::from(font)))?;
+        let mut page_h = 0u32;
+        let mut line_v = 0u32;
+        let mut expect_width_right_after_char = false;
+        let mut expect_down3_after_reset = false;
+        // `down4` amount that lowered the glyphs of an open raise.
+        let mut raised = None::<i32>;
+        let mut expected_right_after_char = 0i32;
+        while let Some(op) = bytes.get(index).copied() {
+            if op == DVI_EOP {
+                if expect_down3_after_reset || expect_width_right_after_char || raised.is_some() {
+                    return None;
+                }
+                index += 1;
+                break;
+            }
+            if expect_width_right_after_char {
+                if op == DVI_RIGHT3 {
+                    right3_count = right3_count.checked_add(1)?;
+                    index += 1;
+                    let amount = read_i24_be(bytes, &mut index)?;
+                    if amount != expected_right_after_char {
+                        return None;
+                    }
+                    page_h = page_h.checked_add(u32::try_from(amount).ok()?)?;
+                    page_h_max = page_h_max.max(page_h);
+                    expect_width_right_after_char = false;
+                    continue;
+                } else {
+                    return None;
+                }
+            } else {
+                if op == DVI_RIGHT3 {
+                    right3_count = right3_count.checked_add(1)?;
+                    index += 1;
+                    let amount = read_i24_be(bytes, &mut index)?;
+                    if amount >= 0 || raised.is_some() {
+                        return None;
+                    }
+                    let back = u32::try_from(-amount).ok()?;
+                    if back != page_h {
+                        return None;
+                    }
+                    page_h = 0;
+                    expect_down3_after_reset = true;
+                    continue;
+                } else if op == DVI_DOWN3 {
+                    down3_count = down3_count.checked_add(1)?;
+                    index += 1;
+                    if read_i24_be(bytes, &mut index)? != line_advance_sp {
+                        return None;
+                    }
+                    if page_h != 0 || raised.is_some() {
+                        return None;
+                    }
+                    if expect_down3_after_reset {
+                        expect_down3_after_reset = false;
+                    }
+                    line_v = line_v.checked_add(u32::try_from(line_advance_sp).ok()?)?;
+                    continue;
+                }
+                if op == DVI_RIGHT4 {
+                    if expect_down3_after_reset {
+                        return None;
+                    }
+                    index += 1;
+                    let amount = read_i32_be(bytes, &mut index)?;
+                    if amount == 0 || amount.unsigned_abs() > MAX_DVI_RULE_DIMENSION_SP_V0 as u32 {
+                        return None;
+                    }
+                    page_h = page_h.checked_add_signed(amount)?;
+                    continue;
+                }
+                let raises_glyphs = op == DVI_DOWN4
+                    && (raised.is_some()
+                        || !matches!(bytes.get(index + 5), Some(&(DVI_SET_RULE | DVI_PUT_RULE))));
+                if raises_glyphs {
+                    if expect_down3_after_reset {
+                        return None;
+                    }
+                    index += 1;
+                    let amount = read_i32_be(bytes, &mut index)?;
+                    match raised {
+                        Some(lowered) if amount == -lowered => raised = None,
+                        None if amount != 0
+                            && amount.unsigned_abs() <= MAX_DVI_RULE_DIMENSION_SP_V0 as u32 =>
+                        {
+                            raised = Some(amount)
+                        }
+                        _ => return None,
+                    }
+                    continue;
+                }
+                if op == DVI_SET_RULE || op == DVI_PUT_RULE || op == DVI_DOWN4 {
+                    if expect_down3_after_reset || raised.is_some() {
+                        return None;
+                    }
+                    let (width, advance) = read_text_rule_v0(bytes, &mut index)?;
+                    rule_count += 1;
+                    if rule_count > MAX_DVI_TEXT_RULES_V0 {
+                        return None;
+                    }
+                    page_h_max = page_h_max.max(page_h.checked_add(width)?);
+                    if advance {
+                        page_h += width;
+                    }
+                    continue;
+                }
+                if op == DVI_XXX1 || op == DVI_XXX4 {
+                    if expect_down3_after_reset {
+                        return None;
+                    }
+                    index += 1;
+                    let len = if op == DVI_XXX1 {
+                        read_u8(bytes, &mut index)? as usize
+                    } else {
+                        read_u32_be(bytes, &mut index)? as usize
+                    };
+                    if len > MAX_DVI_SPECIAL_BYTES_V0 || (op == DVI_XXX4 && len <= 255) {
+                        return None;
+                    }
+                    let start = index;
+                    index = index.checked_add(len).filter(|end| *end <= bytes.len())?;
+                    color_stack.apply_special_v0(&bytes[start..index])?;
+                    special_count += 1;
+                    if special_count > MAX_DVI_SPECIALS_V0 {
+                        return None;
+                    }
+                    continue;
+                }
+                if let Some(font) = op.checked_sub(DVI_FNT_NUM_0) {
+                    if expect_down3_after_reset {
+                        return None;
+                    }
+                    font_em = *font_ems.get(usize::from(font))?;
+                    index += 1;
+                    continue;
+                }
+                let code = if op == DVI_SET1 {
+                    index += 1;
+                    bytes.get(index).copied().filter(|code| *code >= DVI_SET1)?
+                } else if op <= 127 && is_supported_text_byte_v0(op) {
+                    op
+                } else {
+                    return None;
+                };
+                if expect_down3_after_reset {
+                    return None;
+                }
+                expected_right_after_char = glyph_width_sp_v0(code, font_em)?;
+                index += 1;
+                expect_width_right_after_char = true;
+            }
+        }
+        if page_h_max > max_h {
+            max_h = page_h_max;
+        }
+        max_v = max_v.max(page_v).max(line_v);
+        previous_bop_offset = Some(bop_offset);
+        page_count = page_count.checked_add(1)?;
+    }
+    if page_count == 0 || color_stack.depth_v0() != 0 {
+        return None;
+    }
+
+    let post_offset = index;
+    if read_u8(bytes, &mut index) != Some(DVI_POST) {
+        return None;
+    }
+    if read_u32_be(bytes, &mut index) != Some(last_bop_offset) {
+        return None;
+    }
+    if read_u32_be(bytes, &mut index) != Some(DVI_NUM) {
+        return None;
+    }
+    if read_u32_be(bytes, &mut index) != Some(DVI_DEN) {
+        return None;
+    }
+    if read_u32_be(bytes, &mut index) != Some(DVI_MAG) {
+        return None;
+    }
+    if read_u32_be(bytes, &mut index) != Some(max_h) {
+        return None;
+    }
+    if read_u32_be(bytes, &mut index) != Some(max_v) {
+        return None;
+    }
+    if read_u16_be(bytes, &mut index) != Some(u16::from(pushes)) {
+        return None;
+    }
+    if read_u16_be(bytes, &mut index) != Some(page_count) {
+        return None;
+    }
+    if read_u8(bytes, &mut index) != Some(DVI_POSTPOST) {
+        return None;
+    }
+    if read_u32_be(bytes, &mut index) != Some(post_offset as u32) {
+        return None;
+    }
+    if read_u8(bytes, &mut index) != Some(DVI_ID_V2) {
+        return None;
+    }
+    let trailer_len = bytes.len().saturating_sub(index);
+    if trailer_len < 4 {
+        return None;
+    }
+    if !bytes[index..].iter().all(|byte| *byte == DVI_TRAILER_BYTE) {
+        return None;
+    }
+    Some((right3_count, w3_count, w0_count, down3_count, page_count))
+}
@@ -0,0 +1,121 @@
+use crate::{read_u16_be, read_u32_be};
+
+pub const MAX_TFM_BYTES_V0: usize = 1024 * 1024;
+
+/// Width table and parameters of a TeX font metric (`.tfm`) file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TfmFontV0 {
+    pub checksum: u32,
+    /// Design size as a TFM fix_word (2^-20 pt units).
+    pub design_size: i32,
+    pub first_char: u16,
+    pub last_char: u16,
+    width_indices: Vec<u8>,
+    widths: Vec<[u8; 4]>,
+    params: Vec<[u8; 4]>,
+}
+
+fn read_word_v0(bytes: &[u8], word_index: usize) -> Option<[u8; 4]> {
+    let start = word_index.checked_mul(4)?;
+    let slice = bytes.get(start..start.checked_add(4)?)?;
+    Some([slice[0], slice[1], slice[2], slice[3]])
+}
+
+/// Scale a fix_word by `scale_sp` exactly as TeX's `store_scaled` does.
+fn scale_fix_word_v0(word: [u8; 4], scale_sp: i32) -> Option<i32> {
+    if scale_sp <= 0 || scale_sp >= 0x0800_0000 {
+        return None;
+    }
+    let mut z = scale_sp as i64;
+    let mut alpha = 16i64;
+    while z >= 0x80_0000 {
+        z /= 2;
+        alpha *= 2;
+    }
+    let beta = 256 / alpha;
+    let alpha = alpha * z;
+    let [a, b, c, d] = word.map(|byte| byte as i64);
+    let scaled = (((d * z) / 256 + c * z) / 256 + b * z) / beta;
+    match a {
+        0 => i32::try_from(scaled).ok(),
+        255 => i32::try_from(scaled - alpha).ok(),
+        _ => None,
+    }
+}
+
+pub fn parse_tfm_v0(bytes: &[u8]) -> Option<TfmFontV0> {
+    if bytes.len() > MAX_TFM_BYTES_V0 {
+        return None;
+    }
+    let mut index = 0usize;
+    let mut header = [0usize; 12];
+    for field in header.iter_mut() {
+        *field = read_u16_be(bytes, &mut index)? as usize;
+    }
+    let [lf, lh, bc, ec, nw, nh, nd, ni, nl, nk, ne, np] = header;
+    if lf * 4 != bytes.len() || lh < 2 || bc > ec + 1 || ec > 255 || nw == 0 {
+        return None;
+    }
+    let char_count = ec + 1 - bc;
+    let total = 6 + lh + char_count + nw + nh + nd + ni + nl + nk + ne + np;
+    if total != lf {
+        return None;
+    }
+    let mut header_index = 24usize;
+    let checksum = read_u32_be(bytes, &mut header_index)?;
+    let design_size = read_u32_be(bytes, &mut header_index)? as i32;
+    let char_info = 6 + lh;
+    let mut width_indices = Vec::<u8>::with_capacity(char_count);
+    for char_index in 0..char_count {
+        let width_index = read_word_v0(bytes, char_info + char_index)?[0];
+        if width_index as usize >= nw {
+            return None;
+        }
+        width_indices.push(width_index);
+    }
+    let width_table = char_info + char_count;
+    let widths = (0..nw)
+        .map(|width_index| read_word_v0(bytes, width_table + width_index))
+        .collect::<Option<Vec<[u8; 4]>>>()?;
+    if widths[0] != [0; 4] {
+        return None;
+    }
+    let param_table = width_table + nw + nh + nd + ni + nl + nk + ne;
+    let params = (0..np)
+        .map(|param_index| read_word_v0(bytes, param_table + param_index))
+        .collect::<Option<Vec<[u8; 4]>>>()?;
+    Some(TfmFontV0 {
+        checksum,
+        design_size,
+        first_char: bc as u16,
+        last_char: ec as u16,
+        width_indices,
+        widths,
+        params,
+    })
+}
+
+impl TfmFontV0 {
+    /// Width of `code` at `scale_sp`; `None` for characters absent from the font.
+    pub fn scaled_width_v0(&self, code: u32, scale_sp: i32) -> Option<i32> {
+        let offset = code.checked_sub(self.first_char as u32)? as usize;
+        let width_index = *self.width_indices.get(offset)? as usize;
+        if width_index == 0 {
+            return None;
+        }
+        scale_fix_word_v0(self.widths[width_index], scale_sp)
+    }
+
+    /// Font parameter `number` (1-based, as in `\fontdimen`) scaled to `scale_sp`.
+    /// Parameter 1 (slant) is unscaled and is not available through this call.
+    pub fn scaled_param_v0(&self, number: usize, scale_sp: i32) -> Option<i32> {
+        if number < 2 {
+            return None;
+        }
+        scale_fix_word_v0(*self.params.get(number - 1)?, scale_sp)
+    }
+
+    pub fn param_count_v0(&self) -> usize {
+        self.params.len()
+    }
+}
@@ -313,6 +313,54 @@ export function createAssertHelpers(ctx, mem) {
       ctx.dealloc(genericOutPtr, 1);
     }
 
+    const pdfName = new TextEncoder().encode('main.pdf');
+    const pdfLen = mem.callWithBytes(pdfName, `${label}_generic_pdf_len`, (namePtr, nameLen) =>
+      ctx.artifactLenByName(namePtr, nameLen),
+    );
+    if (pdfLen !== 0) {
+      throw new Error(`${label}: expected generic artifact_len(main.pdf)=0, got ${pdfLen}`);
+    }
+
+    const auxName = new TextEncoder().encode('main.aux');
+    const auxLen = mem.callWithBytes(auxName, `${label}_generic_aux_len`, (namePtr, nameLen) =>
+      ctx.artifactLenByName(namePtr, nameLen),
+    );
+    if (auxLen !== 0) {
+      throw new Error(`${label}: expected generic artifact_len(main.aux)=0, got ${auxLen}`);
+    }
+
+    const tocName = new TextEncoder().encode('main.toc');
+    const tocLen = mem.callWithBytes(tocName, `${label}_generic_toc_len`, (namePtr, nameLen) =>
+      ctx.artifactLenByName(namePtr, nameLen),
+    );
+    if (tocLen !== 0) {
+      throw new Error(`${label}: expected generic artifact_len(main.toc)=0, got ${tocLen}`);
+    }
+
+    const lofName = new TextEncoder().encode('main.lof');
+    const lofLen = mem.callWithBytes(lofName, `${label}_generic_lof_len`, (namePtr, nameLen) =>
+      ctx.artifactLenByName(namePtr, nameLen),
+    );
+    if (lofLen !== 0) {
+      throw new Error(`${label}: expected generic artifact_len(main.lof)=0, got ${lofLen}`);
+    }
+
+    const lotName = new TextEncoder().encode('main.lot');
+    const lotLen = mem.callWithBytes(lotName, `${label}_generic_lot_len`, (namePtr, nameLen) =>
+      ctx.artifactLenByName(namePtr, nameLen),
+    );
+    if (lotLen !== 0) {
+      throw new Error(`${label}: expected generic artifact_len(main.lot)=0, got ${lotLen}`);
+    }
+
+    const bblName = new TextEncoder().encode('main.bbl');
+    const bblLen = mem.callWithBytes(bblName, `${label}_generic_bbl_len`, (namePtr, nameLen) =>
+      ctx.artifactLenByName(namePtr, nameLen),
+    );
+    if (bblLen !== 0) {
+      throw new Error(`${label}: expected generic artifact_len(main.bbl)=0, got ${bblLen}`);
+    }
+
     const unknownName = new TextEncoder().encode('unknown.bin');
     const unknownLen = mem.callWithBytes(unknownName, `${label}_generic_unknown_len`, (namePtr, nameLen) =>
       ctx.artifactLenByName(namePtr, nameLen),
@@ -377,6 +425,31 @@ export function createAssertHelpers(ctx, mem) {
     return dedicatedBytes;
   }
 
+  function readArtifactBytesByName(label, name) {
+    const nameBytes = new TextEncoder().encode(name);
+    const bytesLen = mem.callWithBytes(nameBytes, `${label}_artifact_len`, (namePtr, nameLen) =>
+      ctx.artifactLenByName(namePtr, nameLen),
+    );
+    if (!Number.isInteger(bytesLen) || bytesLen <= 0 || bytesLen > 32 * 1024 * 1024) {
+      throw new Error(`${label}: expected ${name} len>0, got ${bytesLen}`);
+    }
+    const outPtr = ctx.alloc(bytesLen);
+    if (!Number.isInteger(outPtr) || outPtr <= 0) {
+      throw new Error(`${label}: alloc failed for ${name} copy`);
+    }
+    try {
+      const written = mem.callWithBytes(nameBytes, `${label}_artifact_copy`, (namePtr, nameLen) =>
+        ctx.artifactCopyByName(namePtr, nameLen, outPtr, bytesLen),
+      );
+      if (written !== bytesLen) {
+        throw new Error(`${label}: artifact_copy_v0(${name}) expected ${bytesLen}, got ${written}`);
+      }
+      return new Uint8Array(ctx.memory.buffer, outPtr, bytesLen).slice();
+    } finally {
+      ctx.dealloc(outPtr, bytesLen);
+    }
+  }
+
   function assertNoEvents(label) {
     const bytes = readEventsBytes();
     if (bytes.length !== 0) {
@@ -399,6 +472,7 @@ export function createAssertHelpers(ctx, mem) {
     assertReadbackZero,
     assertMainXdvArtifactEmpty,
     readMainXdvArtifactBytes,
+    readArtifactBytesByName,
     assertNoEvents,
   };
 }
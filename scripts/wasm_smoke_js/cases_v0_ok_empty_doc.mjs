@@ -6,6 +6,7 @@ export function runOkEmptyDocCases(ctx, helpers) {
     readCompileLogBytes,
     assertEventsMatchLogAndStats,
     readMainXdvArtifactBytes,
+    readArtifactBytesByName,
     callWithBytes,
   } = helpers;
 
@@ -30,7 +31,32 @@ export function runOkEmptyDocCases(ctx, helpers) {
     const DVI_FNT_DEF1 = 243;
     const DVI_FNT_NUM_0 = 171;
     const DVI_RIGHT3 = 145;
-    const DVI_DOWN3 = 160;
+    const DVI_DOWN3 = 159;
+    const DVI_RIGHT4 = 146;
+    const DVI_DOWN4 = 160;
+    const DVI_PUSH = 141;
+    const DVI_POP = 142;
+    // Running heads and feet: push, down4, then right4s, font selects and
+    // glyph/right3 pairs up to the pop.
+    const skipRunningLine = (start) => {
+      let at = start + 1;
+      if (bytes[at] !== DVI_DOWN4) {
+        throw new Error(`${label} expected down4 in running line`);
+      }
+      at += 5;
+      while (at < bytes.length && bytes[at] !== DVI_POP) {
+        if (bytes[at] === DVI_RIGHT4) {
+          at += 5;
+        } else if (bytes[at] >= DVI_FNT_NUM_0) {
+          at += 1;
+        } else if (bytes[at + 1] === DVI_RIGHT3) {
+          at += 5;
+        } else {
+          throw new Error(`${label} unexpected opcode in running line`);
+        }
+      }
+      return at + 1;
+    };
     let index = 0;
     if (bytes[index++] !== DVI_PRE) {
       throw new Error(`${label} expected DVI preamble`);
@@ -55,6 +81,16 @@ export function runOkEmptyDocCases(ctx, helpers) {
       const areaLen = bytes[index + 14];
       const nameLen = bytes[index + 15];
       index += 16 + areaLen + nameLen;
+      // The page's move to its first line's origin.
+      if (bytes[index] === DVI_RIGHT4) {
+        index += 5;
+      }
+      if (bytes[index] === DVI_DOWN4) {
+        index += 5;
+      }
+      while (bytes[index] === DVI_PUSH) {
+        index = skipRunningLine(index);
+      }
       if (bytes[index] !== DVI_FNT_NUM_0) {
         throw new Error(`${label} expected font select`);
       }
@@ -168,6 +204,21 @@ export function runOkEmptyDocCases(ctx, helpers) {
   if (trailerCount < 4) {
     throw new Error(`compile_main(ok empty doc) trailer byte count expected >=4, got ${trailerCount}`);
   }
+  const pdfBytes = readArtifactBytesByName('compile_main(ok empty doc)', 'main.pdf');
+  const pdfText = new TextDecoder('latin1').decode(pdfBytes);
+  if (!pdfText.startsWith('%PDF-1.7\n') || !pdfText.endsWith('%%EOF\n')) {
+    throw new Error('compile_main(ok empty doc) main.pdf expected %PDF-1.7 header and %%EOF trailer');
+  }
+  const auxText = new TextDecoder('latin1').decode(readArtifactBytesByName('compile_main(ok empty doc)', 'main.aux'));
+  if (auxText !== '\\relax\n') {
+    throw new Error(`compile_main(ok empty doc) main.aux expected \\relax line, got ${JSON.stringify(auxText)}`);
+  }
+  const tocLen = callWithBytes(new TextEncoder().encode('main.toc'), 'ok_empty_doc_toc_len', (namePtr, nameLen) =>
+    ctx.artifactLenByName(namePtr, nameLen),
+  );
+  if (tocLen !== 0) {
+    throw new Error(`compile_main(ok empty doc) main.toc expected empty, got ${tocLen} bytes`);
+  }
 
   if (ctx.mountReset() !== 0) {
     throw new Error('mount_reset before OK text doc case failed');